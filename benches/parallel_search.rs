@@ -0,0 +1,74 @@
+//! Throughput of one shared `MultiProviderSearch`, searched sequentially and concurrently
+//!
+//! Providers are mocks with a fixed delay standing in for network latency, so the numbers
+//! measure how well concurrent searches overlap rather than any real provider.
+//!
+//! ```sh
+//! cargo bench --bench parallel_search
+//! ```
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use websearch::{
+    multi_provider::{
+        MultiProviderConfig, MultiProviderSearch, MultiProviderStrategy, SearchOptionsMulti,
+    },
+    testing::MockProvider,
+};
+
+const QUERIES: usize = 64;
+const LATENCY: Duration = Duration::from_millis(20);
+
+fn shared_search() -> Arc<MultiProviderSearch> {
+    let config = ["alpha", "beta", "gamma"].into_iter().fold(
+        MultiProviderConfig::new(MultiProviderStrategy::Aggregate),
+        |config, name| config.add_provider(Box::new(MockProvider::new(name).with_delay(LATENCY))),
+    );
+    Arc::new(MultiProviderSearch::new(config))
+}
+
+fn report(label: &str, elapsed: Duration) {
+    println!(
+        "{label:<12} {QUERIES} searches in {elapsed:>10.2?} ({:.1} searches/s)",
+        QUERIES as f64 / elapsed.as_secs_f64()
+    );
+}
+
+#[tokio::main]
+async fn main() {
+    let search = shared_search();
+    let start = Instant::now();
+    for i in 0..QUERIES {
+        search
+            .search(&SearchOptionsMulti::new(format!("query {i}")))
+            .await
+            .expect("mock search failed");
+    }
+    report("sequential", start.elapsed());
+
+    let search = shared_search();
+    let start = Instant::now();
+    let tasks: Vec<_> = (0..QUERIES)
+        .map(|i| {
+            let search = search.clone();
+            tokio::spawn(async move {
+                search
+                    .search(&SearchOptionsMulti::new(format!("query {i}")))
+                    .await
+            })
+        })
+        .collect();
+    for task in tasks {
+        task.await
+            .expect("search task panicked")
+            .expect("mock search failed");
+    }
+    report("concurrent", start.elapsed());
+
+    let requests: u64 = search
+        .get_stats()
+        .values()
+        .map(|stats| stats.total_requests)
+        .sum();
+    assert_eq!(requests, (QUERIES * 3) as u64);
+}
@@ -0,0 +1,211 @@
+//! Benchmarks for the search pipeline: response parsing, result processing and
+//! multi-provider aggregation
+//!
+//! Parsing benchmarks replay the recorded responses under `tests/fixtures` through a
+//! cassette, so they measure the provider's mapping of a response and not the network.
+//! Processing and aggregation run on generated results and mock providers.
+//!
+//! ```sh
+//! cargo bench --bench pipeline
+//! cargo bench --bench pipeline -- parse/   # one group
+//! ```
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::runtime::Runtime;
+use websearch::{
+    cassette::Cassette,
+    multi_provider::{
+        MultiProviderConfig, MultiProviderSearch, MultiProviderStrategy, SearchOptionsMulti,
+    },
+    processors::{Consensus, Dedup, ResultProcessor},
+    providers::{
+        duckduckgo::{DuckDuckGoConfig, DuckDuckGoProvider},
+        google::{GoogleConfig, GoogleProvider},
+        ArxivProvider,
+    },
+    testing::{sample_results, MockProvider},
+    ProviderHit, SearchOptions, SearchProvider, SearchResult,
+};
+use wiremock::{matchers::any, Mock, MockServer, ResponseTemplate};
+
+fn fixture(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(name)
+}
+
+fn options(query: &str) -> SearchOptions {
+    SearchOptions {
+        query: query.to_string(),
+        ..Default::default()
+    }
+}
+
+/// Record one search against a mock server serving `name`, and return the cassette to
+/// replay it from
+fn record_fixture<P, F>(
+    runtime: &Runtime,
+    dir: &Path,
+    name: &str,
+    content_type: &str,
+    provider: F,
+) -> (P, Cassette)
+where
+    P: SearchProvider,
+    F: FnOnce(&str) -> P,
+{
+    let body = std::fs::read(fixture(name)).expect("fixture exists");
+    let path = dir.join(name.replace('/', "-") + ".cassette.json");
+    runtime.block_on(async {
+        let server = MockServer::start().await;
+        Mock::given(any())
+            .respond_with(ResponseTemplate::new(200).set_body_raw(body, content_type))
+            .mount(&server)
+            .await;
+        let provider = provider(&server.uri());
+        Cassette::record(&path)
+            .scope(provider.search_response(&options("rust")))
+            .await
+            .expect("fixture search succeeds");
+        (
+            provider,
+            Cassette::replay(&path).expect("cassette was written"),
+        )
+    })
+}
+
+fn bench_parse(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let query = options("rust");
+    let mut group = c.benchmark_group("parse");
+
+    let (google, cassette) = record_fixture(
+        &runtime,
+        dir.path(),
+        "google/rust.json",
+        "application/json",
+        |uri| {
+            GoogleProvider::with_config(GoogleConfig {
+                api_key: "key".to_string(),
+                cx: "cx".to_string(),
+                base_url: uri.to_string(),
+                ..Default::default()
+            })
+            .unwrap()
+        },
+    );
+    group.bench_function("google_json", |b| {
+        b.to_async(&runtime)
+            .iter(|| cassette.scope(google.search_response(&query)))
+    });
+
+    let (arxiv, cassette) = record_fixture(
+        &runtime,
+        dir.path(),
+        "arxiv/transformers.xml",
+        "application/atom+xml",
+        |uri| {
+            ArxivProvider::new()
+                .with_base_url(uri)
+                .with_request_interval(Duration::ZERO)
+        },
+    );
+    group.bench_function("arxiv_xml", |b| {
+        b.to_async(&runtime)
+            .iter(|| cassette.scope(arxiv.search_response(&query)))
+    });
+
+    let (duckduckgo, cassette) = record_fixture(
+        &runtime,
+        dir.path(),
+        "duckduckgo/rust.html",
+        "text/html; charset=utf-8",
+        |uri| {
+            DuckDuckGoProvider::with_config(DuckDuckGoConfig {
+                base_url: uri.to_string(),
+                respect_robots_txt: false,
+                ..Default::default()
+            })
+        },
+    );
+    group.bench_function("duckduckgo_html", |b| {
+        b.to_async(&runtime)
+            .iter(|| cassette.scope(duckduckgo.search_response(&query)))
+    });
+
+    group.finish();
+}
+
+/// 10,000 results from four providers, with every URL returned by two of them
+fn overlapping_results() -> Vec<SearchResult> {
+    let providers = ["google", "brave", "exa", "duckduckgo"];
+    (0..10_000)
+        .map(|i| {
+            let provider = providers[i % providers.len()];
+            let rank = i / providers.len() + 1;
+            SearchResult {
+                url: format!("https://example.com/page/{}", i / 2),
+                title: format!("Result {i}"),
+                provider: Some(provider.into()),
+                sources: vec![ProviderHit {
+                    provider: provider.into(),
+                    rank,
+                    score: None,
+                }],
+                ..Default::default()
+            }
+        })
+        .collect()
+}
+
+fn bench_processing(c: &mut Criterion) {
+    let results = overlapping_results();
+    let consensus = Consensus::default().with_weight("google", 2.0);
+    let mut group = c.benchmark_group("process_10k");
+
+    group.bench_function("dedup", |b| {
+        b.iter_batched(
+            || results.clone(),
+            |results| Dedup.process(results),
+            BatchSize::LargeInput,
+        )
+    });
+    group.bench_function("consensus", |b| {
+        b.iter_batched(
+            || results.clone(),
+            |results| consensus.process(results),
+            BatchSize::LargeInput,
+        )
+    });
+
+    group.finish();
+}
+
+fn bench_aggregate(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+    let config = ["google", "brave", "exa", "duckduckgo"].into_iter().fold(
+        MultiProviderConfig::new(MultiProviderStrategy::Aggregate)
+            .with_max_concurrent(4)
+            .with_dedup(true),
+        |config, name| {
+            config.add_provider(Box::new(
+                MockProvider::new(name).with_results(sample_results(name, 100)),
+            ))
+        },
+    );
+    let search = MultiProviderSearch::new(config);
+    let options = SearchOptionsMulti {
+        max_results: Some(100),
+        ..SearchOptionsMulti::new("rust")
+    };
+
+    c.bench_function("aggregate/4_providers_100_results", |b| {
+        b.to_async(&runtime).iter(|| search.search(&options))
+    });
+}
+
+criterion_group!(benches, bench_parse, bench_processing, bench_aggregate);
+criterion_main!(benches);
@@ -1,11 +1,7 @@
 //! Exa search example demonstrating AI-powered semantic search capabilities
 
 use std::env;
-use websearch::{
-    providers::ExaProvider,
-    types::{DebugOptions, SearchOptions, SearchProvider},
-    web_search,
-};
+use websearch::prelude::*;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
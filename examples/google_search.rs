@@ -1,11 +1,7 @@
 //! Google Custom Search API example demonstrating various search capabilities
 
 use std::env;
-use websearch::{
-    providers::GoogleProvider,
-    types::{DebugOptions, SafeSearch, SearchOptions, SearchProvider},
-    web_search,
-};
+use websearch::prelude::*;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -2,13 +2,7 @@
 
 use std::env;
 use tokio::time::{Duration, Instant};
-use websearch::{
-    multi_provider::{
-        MultiProviderConfig, MultiProviderSearch, MultiProviderStrategy, SearchOptionsMulti,
-    },
-    providers::{ExaProvider, GoogleProvider},
-    types::DebugOptions,
-};
+use websearch::prelude::*;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -38,7 +32,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         config = config.add_provider(Box::new(GoogleProvider::new(google_key, cx)?));
         config = config.add_provider(Box::new(ExaProvider::new(exa_key)?));
 
-        let mut multi_search = MultiProviderSearch::new(config);
+        let multi_search = MultiProviderSearch::new(config);
 
         let start = Instant::now();
         let results = multi_search
@@ -60,8 +54,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             "🏆 Winner: {} (returned {} results)",
             results
                 .first()
-                .and_then(|r| r.provider.as_ref())
-                .unwrap_or(&"unknown".to_string()),
+                .and_then(|r| r.provider.as_deref())
+                .unwrap_or("unknown"),
             results.len()
         );
 
@@ -70,7 +64,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 "  {}. {} ({})",
                 i + 1,
                 result.title,
-                result.provider.as_ref().unwrap_or(&"unknown".to_string())
+                result.provider.as_deref().unwrap_or("unknown")
             );
         }
     }
@@ -93,7 +87,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         config = config.add_provider(Box::new(GoogleProvider::new(google_key, cx)?));
         config = config.add_provider(Box::new(ExaProvider::new(exa_key)?));
 
-        let mut multi_search = MultiProviderSearch::new(config);
+        let multi_search = MultiProviderSearch::new(config);
 
         let results = multi_search
             .search(&SearchOptionsMulti {
@@ -135,7 +129,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 "  {}. {} ({})",
                 i + 1,
                 result.title,
-                result.provider.as_ref().unwrap_or(&"unknown".to_string())
+                result.provider.as_deref().unwrap_or("unknown")
             );
             if let Some(domain) = &result.domain {
                 println!("     🔗 {domain}");
@@ -161,7 +155,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         config = config.add_provider(Box::new(GoogleProvider::new(google_key, cx)?));
         config = config.add_provider(Box::new(ExaProvider::new(exa_key)?));
 
-        let mut multi_search = MultiProviderSearch::new(config);
+        let multi_search = MultiProviderSearch::new(config);
 
         // Test failover with a few searches
         for i in 1..=3 {
@@ -184,8 +178,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                     "✅ Success with: {}",
                     results
                         .first()
-                        .and_then(|r| r.provider.as_ref())
-                        .unwrap_or(&"unknown".to_string())
+                        .and_then(|r| r.provider.as_deref())
+                        .unwrap_or("unknown")
                 );
             }
         }
@@ -221,7 +215,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         config = config.add_provider(Box::new(GoogleProvider::new(google_key, cx)?));
         config = config.add_provider(Box::new(ExaProvider::new(exa_key)?));
 
-        let mut multi_search = MultiProviderSearch::new(config);
+        let multi_search = MultiProviderSearch::new(config);
 
         println!("🔄 Making multiple requests to demonstrate load balancing:");
 
@@ -243,7 +237,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 println!(
                     "  Request {}: {} handled search",
                     i,
-                    result.provider.as_ref().unwrap_or(&"unknown".to_string())
+                    result.provider.as_deref().unwrap_or("unknown")
                 );
             }
         }
@@ -1,13 +1,7 @@
 //! Multi-provider search example demonstrating load balancing and failover
 
 use tokio::time::Duration;
-use websearch::{
-    multi_provider::{
-        MultiProviderConfig, MultiProviderSearch, MultiProviderStrategy, SearchOptionsMulti,
-    },
-    providers::{DuckDuckGoProvider, GoogleProvider},
-    types::DebugOptions,
-};
+use websearch::prelude::*;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -17,7 +11,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Example 1: Failover Strategy
     println!("=== Failover Strategy ===");
-    let mut failover_search = create_multi_provider_search(MultiProviderStrategy::Failover).await?;
+    let failover_search = create_multi_provider_search(MultiProviderStrategy::Failover).await?;
 
     let results = failover_search
         .search(&SearchOptionsMulti {
@@ -38,14 +32,14 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             "{}. {} ({})",
             i + 1,
             result.title,
-            result.provider.as_ref().unwrap_or(&"unknown".to_string())
+            result.provider.as_deref().unwrap_or("unknown")
         );
     }
     println!();
 
     // Example 2: Load Balance Strategy
     println!("=== Load Balance Strategy ===");
-    let mut lb_search = create_multi_provider_search(MultiProviderStrategy::LoadBalance).await?;
+    let lb_search = create_multi_provider_search(MultiProviderStrategy::LoadBalance).await?;
 
     // Make multiple requests to see load balancing
     for i in 1..=3 {
@@ -68,15 +62,15 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             results.len(),
             results
                 .first()
-                .and_then(|r| r.provider.as_ref())
-                .unwrap_or(&"unknown".to_string())
+                .and_then(|r| r.provider.as_deref())
+                .unwrap_or("unknown")
         );
     }
     println!();
 
     // Example 3: Aggregate Strategy
     println!("=== Aggregate Strategy ===");
-    let mut agg_search = create_multi_provider_search(MultiProviderStrategy::Aggregate).await?;
+    let agg_search = create_multi_provider_search(MultiProviderStrategy::Aggregate).await?;
 
     let results = agg_search
         .search(&SearchOptionsMulti {
@@ -105,7 +99,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Example 4: Race Strategy
     println!("=== Race First Strategy ===");
-    let mut race_search = create_multi_provider_search(MultiProviderStrategy::RaceFirst).await?;
+    let race_search = create_multi_provider_search(MultiProviderStrategy::RaceFirst).await?;
 
     let results = race_search
         .search(&SearchOptionsMulti {
@@ -125,8 +119,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         results.len(),
         results
             .first()
-            .and_then(|r| r.provider.as_ref())
-            .unwrap_or(&"unknown".to_string())
+            .and_then(|r| r.provider.as_deref())
+            .unwrap_or("unknown")
     );
     println!();
 
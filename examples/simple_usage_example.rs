@@ -1,13 +1,8 @@
 //! Simple example showing how another project would use this search SDK
 
 use std::env;
-use websearch::{
-    multi_provider::{
-        MultiProviderConfig, MultiProviderSearch, MultiProviderStrategy, SearchOptionsMulti,
-    },
-    providers::{ExaProvider, GoogleProvider},
-    types::{DebugOptions, SearchProvider},
-};
+use std::sync::Arc;
+use websearch::prelude::*;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -20,9 +15,13 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("📋 Option 1: Single Provider Usage");
     println!("----------------------------------");
 
-    if let Ok(exa_key) = env::var("EXA_API_KEY") {
-        let exa_provider = ExaProvider::new(&exa_key)?;
+    // Providers are configured once and shared through an Arc
+    let exa_provider: Option<Arc<dyn SearchProvider>> = match env::var("EXA_API_KEY") {
+        Ok(exa_key) => Some(Arc::new(ExaProvider::new(&exa_key)?)),
+        Err(_) => None,
+    };
 
+    if let Some(exa_provider) = &exa_provider {
         // This is the main interface other projects would use
         let results = exa_provider
             .search(&websearch::types::SearchOptions {
@@ -33,7 +32,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                     log_requests: false,
                     log_responses: false,
                 }),
-                provider: Box::new(ExaProvider::new(&exa_key)?), // Required by the trait
+                provider: Box::new(exa_provider.clone()), // Required by the trait
                 ..Default::default()
             })
             .await?;
@@ -59,12 +58,12 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         println!("✅ Added Google provider");
     }
 
-    if let Ok(exa_key) = env::var("EXA_API_KEY") {
-        config = config.add_provider(Box::new(ExaProvider::new(&exa_key)?));
+    if let Some(exa_provider) = exa_provider {
+        config = config.add_shared_provider(exa_provider);
         println!("✅ Added Exa provider");
     }
 
-    let mut multi_search = MultiProviderSearch::new(config);
+    let multi_search = MultiProviderSearch::new(config);
 
     let results = multi_search
         .search(&SearchOptionsMulti {
@@ -105,7 +104,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             "  {}. {} ({})",
             i + 1,
             result.title,
-            result.provider.as_ref().unwrap_or(&"unknown".to_string())
+            result.provider.as_deref().unwrap_or("unknown")
         );
         if let Some(domain) = &result.domain {
             println!("     🌐 {domain}");
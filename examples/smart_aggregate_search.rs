@@ -1,13 +1,7 @@
 //! Smart aggregation focused on finding unique links and avoiding quota waste
 
 use std::{collections::HashSet, env};
-use websearch::{
-    multi_provider::{
-        MultiProviderConfig, MultiProviderSearch, MultiProviderStrategy, SearchOptionsMulti,
-    },
-    providers::{ExaProvider, GoogleProvider},
-    types::DebugOptions,
-};
+use websearch::prelude::*;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -30,7 +24,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     config = config.add_provider(Box::new(GoogleProvider::new(&google_api_key, &google_cx)?));
     config = config.add_provider(Box::new(ExaProvider::new(&exa_api_key)?));
 
-    let mut multi_search = MultiProviderSearch::new(config);
+    let multi_search = MultiProviderSearch::new(config);
 
     // Test with a topic that should give different results from each engine
     let query = "machine learning frameworks comparison 2024";
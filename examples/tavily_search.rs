@@ -1,11 +1,7 @@
 //! Tavily search example demonstrating AI-powered search capabilities
 
 use std::env;
-use websearch::{
-    providers::TavilyProvider,
-    types::{DebugOptions, SearchOptions, SearchProvider},
-    web_search,
-};
+use websearch::prelude::*;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -0,0 +1,49 @@
+// Build the library with `cargo build -p websearch-ffi --release`, then:
+//   cc ffi/examples/search.c -Iffi/include -Ltarget/release -lwebsearch_ffi -o search
+//   LD_LIBRARY_PATH=target/release ./search "rust programming"
+#include <stdio.h>
+#include <string.h>
+
+#include "websearch.h"
+
+// Write `text` to `out` as the inside of a JSON string, escaping quotes, backslashes and
+// control characters. Returns 0 if it doesn't fit in `size` bytes.
+static int json_escape(const char *text, char *out, size_t size) {
+    size_t len = 0;
+    for (const unsigned char *c = (const unsigned char *)text; *c; c++) {
+        char escaped[7];
+        if (*c == '"' || *c == '\\') {
+            snprintf(escaped, sizeof escaped, "\\%c", *c);
+        } else if (*c < 0x20) {
+            snprintf(escaped, sizeof escaped, "\\u%04x", *c);
+        } else {
+            escaped[0] = (char)*c;
+            escaped[1] = '\0';
+        }
+
+        size_t n = strlen(escaped);
+        if (len + n >= size) {
+            return 0;
+        }
+        memcpy(out + len, escaped, n);
+        len += n;
+    }
+    out[len] = '\0';
+    return 1;
+}
+
+int main(int argc, char **argv) {
+    const char *query = argc > 1 ? argv[1] : "rust programming";
+    char escaped[512];
+    if (!json_escape(query, escaped, sizeof escaped)) {
+        fprintf(stderr, "query is too long\n");
+        return 1;
+    }
+    char request[1024];
+    snprintf(request, sizeof request, "{\"query\": \"%s\", \"max_results\": 5}", escaped);
+
+    char *response = websearch_search("duckduckgo", request);
+    printf("websearch %s\n%s\n", websearch_version(), response);
+    websearch_string_free(response);
+    return 0;
+}
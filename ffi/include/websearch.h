@@ -0,0 +1,44 @@
+#ifndef WEBSEARCH_H
+#define WEBSEARCH_H
+
+/* Generated by cbindgen from ffi/src/lib.rs. Do not edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Search `provider` with a JSON-encoded query and return the results as JSON
+//
+// `provider` is a provider name such as `"duckduckgo"`, `"google"` or `"arxiv"`. API keys
+// are read from the same environment variables as the CLI (`GOOGLE_API_KEY`,
+// `TAVILY_API_KEY`, ...). `query_json` is a search query object, e.g.
+// `{"query": "rust", "max_results": 5}`.
+//
+// Returns `{"results": [...]}` on success or `{"error": "..."}` on failure. The returned
+// string must be freed with [`websearch_string_free`].
+//
+// # Safety
+//
+// `provider` and `query_json` must be valid NUL-terminated strings (or NULL).
+char *websearch_search(const char *provider, const char *query_json);
+
+// Release a string returned by this library. Passing NULL is a no-op.
+//
+// # Safety
+//
+// `s` must be NULL or a pointer returned by this library that has not been freed yet.
+void websearch_string_free(char *s);
+
+// Version of the library, as a static NUL-terminated string (do not free)
+const char *websearch_version(void);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* WEBSEARCH_H */
@@ -0,0 +1,161 @@
+//! C interface to the websearch SDK
+//!
+//! Every call is synchronous and exchanges JSON strings, so the SDK can be embedded in
+//! C, C++, Go (cgo) or any other language with a C FFI. See `include/websearch.h`.
+//!
+//! Strings returned by this library are owned by the caller and must be released with
+//! [`websearch_string_free`].
+
+use std::ffi::{c_char, CStr, CString};
+use std::panic::{self, AssertUnwindSafe};
+use websearch::{
+    blocking,
+    error::SearchError,
+    providers::{
+        ArxivProvider, BraveProvider, DuckDuckGoProvider, ExaProvider, GoogleProvider,
+        SearxNGProvider, SerpApiProvider, TavilyProvider, YouTubeProvider,
+    },
+    SearchProvider, SearchQuery,
+};
+
+/// Search `provider` with a JSON-encoded query and return the results as JSON
+///
+/// `provider` is a provider name such as `"duckduckgo"`, `"google"` or `"arxiv"`. API keys
+/// are read from the same environment variables as the CLI (`GOOGLE_API_KEY`,
+/// `TAVILY_API_KEY`, ...). `query_json` is a search query object, e.g.
+/// `{"query": "rust", "max_results": 5}`.
+///
+/// Returns `{"results": [...]}` on success or `{"error": "..."}` on failure. The returned
+/// string must be freed with [`websearch_string_free`].
+///
+/// # Safety
+///
+/// `provider` and `query_json` must be valid NUL-terminated strings (or NULL).
+#[no_mangle]
+pub unsafe extern "C" fn websearch_search(
+    provider: *const c_char,
+    query_json: *const c_char,
+) -> *mut c_char {
+    let response = panic::catch_unwind(AssertUnwindSafe(|| {
+        let provider = read_str(provider, "provider")?;
+        let query_json = read_str(query_json, "query_json")?;
+        search(provider, query_json)
+    }))
+    .unwrap_or_else(|_| Err(SearchError::Other("websearch panicked".to_string())));
+
+    let body = match response {
+        Ok(body) => body,
+        Err(error) => serde_json::json!({ "error": error.report() }),
+    };
+    into_c_string(body.to_string())
+}
+
+/// Release a string returned by this library. Passing NULL is a no-op.
+///
+/// # Safety
+///
+/// `s` must be NULL or a pointer returned by this library that has not been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn websearch_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+/// Version of the library, as a static NUL-terminated string (do not free)
+#[no_mangle]
+pub extern "C" fn websearch_version() -> *const c_char {
+    concat!(env!("CARGO_PKG_VERSION"), "\0").as_ptr().cast()
+}
+
+fn search(provider: &str, query_json: &str) -> Result<serde_json::Value, SearchError> {
+    let query: SearchQuery = serde_json::from_str(query_json)?;
+    let results = blocking::web_search(query.with_provider(create_provider(provider)?))?;
+    Ok(serde_json::json!({ "results": results }))
+}
+
+fn create_provider(name: &str) -> Result<Box<dyn SearchProvider>, SearchError> {
+    let env = |var: &str| {
+        std::env::var(var).map_err(|_| SearchError::ConfigError(format!("{var} is not set")))
+    };
+
+    Ok(match name.to_lowercase().as_str() {
+        "google" => Box::new(GoogleProvider::new(
+            &env("GOOGLE_API_KEY")?,
+            &env("GOOGLE_CX")?,
+        )?),
+        "tavily" => Box::new(TavilyProvider::new(&env("TAVILY_API_KEY")?)?),
+        "exa" => Box::new(ExaProvider::new(&env("EXA_API_KEY")?)?),
+        "serpapi" => Box::new(SerpApiProvider::new(&env("SERPAPI_API_KEY")?)?),
+        "brave" => Box::new(BraveProvider::new(&env("BRAVE_API_KEY")?)?),
+        "youtube" => Box::new(YouTubeProvider::new(&env("YOUTUBE_API_KEY")?)?),
+        "searxng" => Box::new(SearxNGProvider::with_instances(
+            env("SEARXNG_URL")?.split(',').map(str::trim),
+        )?),
+        "duckduckgo" => Box::new(DuckDuckGoProvider::new()),
+        "arxiv" => Box::new(ArxivProvider::new()),
+        _ => {
+            return Err(SearchError::InvalidInput(format!(
+                "Unknown provider: {name}"
+            )))
+        }
+    })
+}
+
+unsafe fn read_str<'a>(ptr: *const c_char, name: &str) -> Result<&'a str, SearchError> {
+    if ptr.is_null() {
+        return Err(SearchError::InvalidInput(format!("{name} is NULL")));
+    }
+    CStr::from_ptr(ptr)
+        .to_str()
+        .map_err(|_| SearchError::InvalidInput(format!("{name} is not valid UTF-8")))
+}
+
+fn into_c_string(s: String) -> *mut c_char {
+    // serde_json escapes control characters, so the JSON never contains a NUL byte
+    CString::new(s)
+        .expect("JSON output contains no NUL bytes")
+        .into_raw()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(provider: Option<&str>, query_json: &str) -> serde_json::Value {
+        let provider = provider.map(|p| CString::new(p).unwrap());
+        let query_json = CString::new(query_json).unwrap();
+        unsafe {
+            let ptr = websearch_search(
+                provider.as_ref().map_or(std::ptr::null(), |p| p.as_ptr()),
+                query_json.as_ptr(),
+            );
+            let body = CStr::from_ptr(ptr).to_str().unwrap().to_string();
+            websearch_string_free(ptr);
+            serde_json::from_str(&body).unwrap()
+        }
+    }
+
+    #[test]
+    fn test_errors_are_returned_as_json() {
+        let body = call(Some("duckduckgo"), "not json");
+        assert!(body["error"].as_str().unwrap().contains("JSON"));
+
+        let body = call(Some("altavista"), r#"{"query": "rust"}"#);
+        assert_eq!(body["error"], "Invalid input: Unknown provider: altavista");
+
+        let body = call(None, r#"{"query": "rust"}"#);
+        assert_eq!(body["error"], "Invalid input: provider is NULL");
+    }
+
+    #[test]
+    fn test_version() {
+        let version = unsafe { CStr::from_ptr(websearch_version()) };
+        assert_eq!(version.to_str().unwrap(), env!("CARGO_PKG_VERSION"));
+    }
+
+    #[test]
+    fn test_free_null_is_noop() {
+        unsafe { websearch_string_free(std::ptr::null_mut()) };
+    }
+}
@@ -0,0 +1,187 @@
+//! Batch search execution with bounded concurrency
+
+use crate::{
+    correlation, domains,
+    error::{SearchError, SearchResult as Result},
+    freshness, language, limits, queue, safe_search, seen,
+    types::{SearchProvider, SearchQuery, SearchResult},
+};
+use tokio::sync::Semaphore;
+
+/// Outcome of a single query within a batch
+#[derive(Debug, Clone)]
+pub struct BatchSearchResult {
+    /// The query that was executed
+    pub query: SearchQuery,
+    /// Results or the error returned for this query
+    pub result: Result<Vec<SearchResult>>,
+}
+
+impl BatchSearchResult {
+    /// Whether this query completed successfully
+    pub fn is_ok(&self) -> bool {
+        self.result.is_ok()
+    }
+}
+
+/// Run many queries against a single provider with at most `concurrency` in flight
+///
+/// The provider (and therefore its HTTP client) is shared by every query. Results are
+/// returned in the same order as `queries`; a failing query does not affect the others.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use websearch::{web_search_batch, providers::DuckDuckGoProvider, types::SearchQuery};
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let provider = DuckDuckGoProvider::new();
+/// let queries = vec![SearchQuery::new("rust async"), SearchQuery::new("tokio semaphore")];
+///
+/// for item in web_search_batch(&provider, queries, 4).await {
+///     match item.result {
+///         Ok(results) => println!("{}: {} results", item.query.query, results.len()),
+///         Err(err) => eprintln!("{}: {err}", item.query.query),
+///     }
+/// }
+/// # }
+/// ```
+pub async fn web_search_batch(
+    provider: &dyn SearchProvider,
+    queries: Vec<SearchQuery>,
+    concurrency: usize,
+) -> Vec<BatchSearchResult> {
+    let semaphore = Semaphore::new(concurrency.max(1));
+
+    let searches = queries.into_iter().map(|query| {
+        let semaphore = &semaphore;
+        async move {
+            let _permit = semaphore
+                .acquire()
+                .await
+                .expect("batch semaphore is never closed");
+            let result = search_one(provider, &query).await;
+            BatchSearchResult { query, result }
+        }
+    });
+
+    futures::future::join_all(searches).await
+}
+
+async fn search_one(
+    provider: &dyn SearchProvider,
+    query: &SearchQuery,
+) -> Result<Vec<SearchResult>> {
+    correlation::scope(query.correlation_id.clone(), search_query(provider, query)).await
+}
+
+async fn search_query(
+    provider: &dyn SearchProvider,
+    query: &SearchQuery,
+) -> Result<Vec<SearchResult>> {
+    if query.query.is_empty() && query.id_list.is_none() {
+        return Err(SearchError::InvalidInput(
+            "A search query or ID list (for Arxiv) is required".to_string(),
+        ));
+    }
+
+    queue::wait_turn(provider.name(), query.priority).await?;
+    let _permit = limits::acquire().await;
+    let options = query.to_search_options();
+    let mut results = limits::with_provider(
+        provider.name(),
+        limits::with_max_response_bytes(query.max_response_bytes, provider.search(&options)),
+    )
+    .await?;
+    limits::truncate_snippets(&mut results, query.max_snippet_chars);
+    limits::drop_kinds(&mut results, &query.exclude_kinds);
+    safe_search::enforce(provider, query.safe_search.as_ref(), &mut results);
+    domains::enforce(provider, &options, &mut results);
+    freshness::enforce(provider, query.freshness, &mut results);
+    language::enforce(
+        query.language.as_deref(),
+        query.require_language,
+        &mut results,
+    );
+    seen::enforce(query.exclude_urls.as_ref(), &mut results);
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::SearchOptions;
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tokio::time::Duration;
+
+    #[derive(Debug, Default)]
+    struct CountingProvider {
+        in_flight: AtomicUsize,
+        peak: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl SearchProvider for CountingProvider {
+        fn name(&self) -> &str {
+            "counting"
+        }
+
+        async fn search(&self, options: &SearchOptions) -> Result<Vec<SearchResult>> {
+            let current = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.peak.fetch_max(current, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+
+            if options.query == "fail" {
+                return Err(SearchError::Other("scripted failure".to_string()));
+            }
+
+            Ok(vec![SearchResult {
+                url: format!("https://example.com/{}", options.query),
+                title: options.query.clone(),
+                snippet: None,
+                domain: None,
+                published_date: None,
+                provider: Some("counting".into()),
+                raw: None,
+                ..Default::default()
+            }])
+        }
+    }
+
+    #[tokio::test]
+    async fn test_batch_respects_concurrency_and_order() {
+        let provider = CountingProvider::default();
+        let queries = (0..8).map(|i| SearchQuery::new(format!("q{i}"))).collect();
+
+        let results = web_search_batch(&provider, queries, 3).await;
+
+        assert_eq!(results.len(), 8);
+        assert!(provider.peak.load(Ordering::SeqCst) <= 3);
+        for (i, item) in results.iter().enumerate() {
+            assert_eq!(item.query.query, format!("q{i}"));
+            assert_eq!(item.result.as_ref().unwrap()[0].title, format!("q{i}"));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_batch_isolates_errors() {
+        let provider = CountingProvider::default();
+        let queries = vec![
+            SearchQuery::new("ok"),
+            SearchQuery::new("fail"),
+            SearchQuery::new(""),
+        ];
+
+        let results = web_search_batch(&provider, queries, 0).await;
+
+        assert!(results[0].is_ok());
+        assert!(matches!(results[1].result, Err(SearchError::Other(_))));
+        assert!(matches!(
+            results[2].result,
+            Err(SearchError::InvalidInput(_))
+        ));
+    }
+}
@@ -1,16 +1,37 @@
 //! WebSearch CLI - Command-line interface for the websearch SDK
 //!
 //! A powerful CLI tool for searching across multiple search providers including
-//! Google, Tavily, Exa, SerpAPI, DuckDuckGo, Brave, SearXNG, and ArXiv.
+//! Google, Tavily, Exa, SerpAPI, DuckDuckGo, Brave, SearXNG, ArXiv, Google Scholar and
+//! YouTube.
 
-use clap::{Parser, Subcommand, ValueEnum};
+use chrono::NaiveDate;
+use clap::{Args, Parser, Subcommand, ValueEnum};
 use colored::*;
-use std::env;
+use std::fs::OpenOptions;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+use std::time::Duration;
 use websearch::{
+    cache::{CachedProvider, DiskCache},
+    credentials::Credential,
+    dry_run::RequestPlan,
+    export::{self, ExportFormat},
+    format, highlight,
+    key_rotation::KeyRotatingProvider,
     multi_provider::{MultiProviderConfig, MultiProviderSearch, MultiProviderStrategy, SearchOptionsMulti},
+    processors::Consensus,
+    products::{search_products, ProductResult, SerpApiShoppingProvider},
     providers::*,
-    types::{DebugOptions, SafeSearch, SearchOptions, SortBy, SortOrder},
-    web_search,
+    suggest::{
+        suggest, BraveSuggestProvider, DuckDuckGoSuggestProvider, GoogleSuggestProvider,
+        SuggestProvider,
+    },
+    trends::{trending, SerpApiTrendsProvider},
+    types::{
+        DebugOptions, FileType, Freshness, GeoLocation, HealthStatus, ResultKind, SafeSearch,
+        SearchMode, SearchOptions, SearchProvider, SortBy, SortOrder,
+    },
+    web_search, web_search_response,
 };
 
 #[derive(Parser)]
@@ -37,13 +58,17 @@ struct Cli {
     #[arg(short, long)]
     language: Option<String>,
 
+    /// Drop results the provider reports in a language other than --language
+    #[arg(long, requires = "language")]
+    require_language: bool,
+
     /// Region code (e.g., US, UK, DE)
     #[arg(short, long)]
     region: Option<String>,
 
     /// Safe search setting
     #[arg(short, long, value_enum)]
-    safe_search: Option<SafeSearchCli>,
+    safe_search: Option<SafeSearch>,
 
     /// ArXiv paper IDs (comma-separated, for ArXiv provider)
     #[arg(long)]
@@ -51,11 +76,14 @@ struct Cli {
 
     /// Sort by field (for ArXiv)
     #[arg(long, value_enum)]
-    sort_by: Option<SortByCli>,
+    sort_by: Option<SortBy>,
 
     /// Sort order (for ArXiv)
     #[arg(long, value_enum)]
-    sort_order: Option<SortOrderCli>,
+    sort_order: Option<SortOrder>,
+
+    #[command(flatten)]
+    filters: FilterArgs,
 
     /// Enable debug output
     #[arg(short, long)]
@@ -65,12 +93,67 @@ struct Cli {
     #[arg(long)]
     raw: bool,
 
+    /// Print the requests the provider would send (secrets redacted) without sending them
+    #[arg(long)]
+    dry_run: bool,
+
     /// Output format
     #[arg(short, long, value_enum, default_value = "table")]
     format: OutputFormat,
+
+    /// Write results to this file instead of stdout (without colors)
+    #[arg(short, long, value_name = "FILE")]
+    output: Option<PathBuf>,
+
+    /// Append to the --output file instead of overwriting it
+    #[arg(long, requires = "output")]
+    append: bool,
+
+    /// Suppress headers, totals and other decorative output
+    #[arg(short, long)]
+    quiet: bool,
+
+    /// Write vector-store documents in this format instead of --format output
+    #[arg(long, value_enum, value_name = "FORMAT")]
+    export: Option<ExportCli>,
+
+    /// Scrape providers even when their robots.txt disallows it
+    #[arg(long, global = true)]
+    ignore_robots: bool,
+
+    /// Show the terms providers highlighted in snippets in bold (table and simple formats)
+    #[arg(long, global = true)]
+    highlight: bool,
+
+    #[command(flatten)]
+    cache: CacheArgs,
+}
+
+/// Result cache settings shared by the search commands
+#[derive(Args, Clone, Debug)]
+struct CacheArgs {
+    /// Seconds a cached search result stays valid
+    #[arg(long, global = true, value_name = "SECONDS", default_value = "3600")]
+    cache_ttl: u64,
+
+    /// Always query providers, bypassing the result cache
+    #[arg(long, global = true)]
+    no_cache: bool,
+}
+
+impl CacheArgs {
+    /// The cache to use, unless disabled or no cache directory is known
+    fn disk_cache(&self) -> Option<DiskCache> {
+        if self.no_cache {
+            return None;
+        }
+        DiskCache::with_default_dir(Duration::from_secs(self.cache_ttl))
+    }
 }
 
+// Parsed once per run, so the size of the `Multi` variant doesn't matter
 #[derive(Subcommand)]
+#[allow(clippy::large_enum_variant)]
 enum Commands {
     /// Search using multiple providers with advanced strategies
     Multi {
@@ -79,16 +162,48 @@ enum Commands {
 
         /// Multi-provider strategy
         #[arg(short, long, value_enum, default_value = "aggregate")]
-        strategy: StrategyCli,
+        strategy: MultiProviderStrategy,
 
         /// Providers to use (if not specified, uses available providers)
         #[arg(short, long, value_enum)]
         providers: Vec<Provider>,
 
-        /// Maximum number of results per provider
+        /// Maximum number of results
         #[arg(short, long, default_value = "5")]
         max_results: u32,
 
+        /// Results requested from each provider when aggregating (defaults to --max-results)
+        #[arg(long)]
+        per_provider_results: Option<u32>,
+
+        /// Maximum number of providers queried at once when aggregating
+        #[arg(long, default_value = "3")]
+        max_concurrent: usize,
+
+        /// Drop aggregated results with the same URL
+        #[arg(long)]
+        dedup: bool,
+
+        /// Rank aggregated results by how many providers returned them (Borda count)
+        #[arg(long)]
+        consensus: bool,
+
+        /// Most this search may spend on paid providers, in US dollars (estimated)
+        #[arg(long, value_name = "USD")]
+        budget: Option<f64>,
+
+        /// Overall time limit in milliseconds; aggregate searches return what arrived in time
+        #[arg(long, value_name = "MS")]
+        deadline: Option<u64>,
+
+        /// Failover tier as comma-separated provider names, tried in the order given (repeatable)
+        #[arg(long = "tier", value_name = "PROVIDERS")]
+        tiers: Vec<String>,
+
+        /// Query the providers within a failover tier at the same time
+        #[arg(long, requires = "tiers")]
+        parallel_tiers: bool,
+
         /// Enable debug output
         #[arg(short, long)]
         debug: bool,
@@ -100,12 +215,242 @@ enum Commands {
         /// Show provider statistics
         #[arg(long)]
         stats: bool,
+
+        #[command(flatten)]
+        filters: FilterArgs,
+
+        /// Write results to this file instead of stdout (without colors)
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+
+        /// Append to the --output file instead of overwriting it
+        #[arg(long, requires = "output")]
+        append: bool,
+
+        /// Suppress headers, totals and other decorative output
+        #[arg(short, long)]
+        quiet: bool,
+
+        /// Write vector-store documents in this format instead of --format output
+        #[arg(long, value_enum, value_name = "FORMAT")]
+        export: Option<ExportCli>,
+    },
+    /// Search academic papers on Google Scholar (requires SERPAPI_API_KEY)
+    Scholar {
+        /// Search query
+        query: String,
+
+        /// Maximum number of results (Google Scholar returns at most 20)
+        #[arg(short, long, default_value = "10")]
+        max_results: u32,
+
+        /// Only include papers published in or after this year
+        #[arg(long)]
+        year_from: Option<u16>,
+
+        /// Only include papers published in or before this year
+        #[arg(long)]
+        year_to: Option<u16>,
+
+        /// Show raw provider data (authors, year, cited-by counts)
+        #[arg(long)]
+        raw: bool,
+
+        /// Enable debug output
+        #[arg(short, long)]
+        debug: bool,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "table")]
+        format: OutputFormat,
+
+        /// Write results to this file instead of stdout (without colors)
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+
+        /// Append to the --output file instead of overwriting it
+        #[arg(long, requires = "output")]
+        append: bool,
+
+        /// Suppress headers, totals and other decorative output
+        #[arg(short, long)]
+        quiet: bool,
+
+        /// Write vector-store documents in this format instead of --format output
+        #[arg(long, value_enum, value_name = "FORMAT")]
+        export: Option<ExportCli>,
+    },
+    /// Get autocomplete suggestions for a partial query
+    Suggest {
+        /// Partial search query
+        query: String,
+
+        /// Suggestion provider
+        #[arg(short, long, value_enum, default_value = "duckduckgo")]
+        provider: SuggestProviderCli,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "simple")]
+        format: OutputFormat,
+    },
+    /// Show currently trending searches (requires SERPAPI_API_KEY)
+    Trends {
+        /// Country code, e.g. US or GB
+        #[arg(short, long, default_value = "US")]
+        region: String,
+
+        /// Maximum number of trending queries to show
+        #[arg(short, long, default_value = "20")]
+        max_results: usize,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "table")]
+        format: OutputFormat,
+    },
+    /// Search product listings with prices (requires SERPAPI_API_KEY)
+    Products {
+        /// Product search query
+        query: String,
+
+        /// Country to shop in, e.g. us or gb
+        #[arg(short, long)]
+        country: Option<String>,
+
+        /// Maximum number of products to show
+        #[arg(short, long, default_value = "20")]
+        max_results: u32,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "table")]
+        format: OutputFormat,
+    },
+    /// Run a REST API server backed by multi-provider search
+    #[cfg(feature = "server")]
+    Serve {
+        /// Port to listen on
+        #[arg(long, default_value = "8080")]
+        port: u16,
+
+        /// Address to bind to
+        #[arg(long, default_value = "127.0.0.1")]
+        host: String,
+
+        /// Multi-provider strategy used when a request doesn't name a provider
+        #[arg(short, long, value_enum, default_value = "failover")]
+        strategy: MultiProviderStrategy,
+
+        /// Providers to serve (if not specified, uses available providers)
+        #[arg(short, long, value_enum)]
+        providers: Vec<Provider>,
+    },
+    /// Manage the search result cache
+    Cache {
+        #[command(subcommand)]
+        action: CacheAction,
     },
     /// List available providers and their status
-    Providers,
+    Providers {
+        /// Contact each configured provider to check credentials, latency and quota
+        #[arg(long)]
+        verify: bool,
+    },
 }
 
-#[derive(ValueEnum, Clone, Debug)]
+#[derive(Subcommand)]
+enum CacheAction {
+    /// Delete all cached results
+    Clear,
+    /// Show the cache location, entry count and size
+    Stats,
+}
+
+/// Result limits and concurrency for the `multi` command
+struct MultiLimits {
+    max_results: u32,
+    per_provider_results: Option<u32>,
+    max_concurrent: usize,
+    dedup: bool,
+    consensus: bool,
+    budget: Option<f64>,
+    deadline: Option<u64>,
+    tiers: Vec<String>,
+    parallel_tiers: bool,
+}
+
+/// Paging, timeout and result filters shared by the search commands
+#[derive(Args, Clone, Debug)]
+struct FilterArgs {
+    /// Result page number (starting at 1)
+    #[arg(long)]
+    page: Option<u32>,
+
+    /// Request timeout in milliseconds
+    #[arg(long, value_name = "MS")]
+    timeout_ms: Option<u64>,
+
+    /// Only return results from this domain or its subdomains (repeatable; works with
+    /// every provider)
+    #[arg(long, value_name = "DOMAIN")]
+    site: Vec<String>,
+
+    /// Exclude results from this domain (repeatable)
+    #[arg(long, value_name = "DOMAIN")]
+    exclude_site: Vec<String>,
+
+    /// Only return results published on or after this date (YYYY-MM-DD)
+    #[arg(long, value_name = "DATE")]
+    after: Option<NaiveDate>,
+
+    /// Only return results published on or before this date (YYYY-MM-DD)
+    #[arg(long, value_name = "DATE")]
+    before: Option<NaiveDate>,
+
+    /// Only return documents of this type
+    #[arg(long, value_enum)]
+    file_type: Option<FileType>,
+
+    /// Only return results from the past day, week, month or year (dated results from
+    /// providers without a recency filter are checked client-side)
+    #[arg(long, value_enum)]
+    freshness: Option<Freshness>,
+
+    /// Search for web pages, videos or places (video: brave, serpapi and youtube; places:
+    /// brave and serpapi)
+    #[arg(long, value_enum, default_value = "web")]
+    mode: SearchMode,
+
+    /// Look for results near this location, e.g. "Berlin" (added to the query)
+    #[arg(long, value_name = "LOCATION")]
+    near: Option<String>,
+
+    /// Prefer results relevant to "LAT,LNG" or a location name such as
+    /// "Austin,Texas,United States" (brave and serpapi)
+    #[arg(long, value_name = "LOCATION")]
+    location: Option<GeoLocation>,
+
+    /// Drop results of these kinds (comma-separated; ads by default, pass the flag with no
+    /// value to keep everything)
+    #[arg(long, value_enum, value_name = "KIND", num_args = 0.., value_delimiter = ',',
+        default_values_t = [ResultKind::Ad])]
+    exclude_kind: Vec<ResultKind>,
+}
+
+impl FilterArgs {
+    /// Domain lists are `None` when empty so providers skip the filter entirely
+    fn domains(domains: Vec<String>) -> Option<Vec<String>> {
+        (!domains.is_empty()).then_some(domains)
+    }
+
+    /// The query with the `--near` location appended
+    fn query(&self, query: &str) -> String {
+        match &self.near {
+            Some(near) => format!("{query} near {near}"),
+            None => query.to_string(),
+        }
+    }
+}
+
+#[derive(ValueEnum, Clone, Debug, PartialEq)]
 enum Provider {
     Google,
     Tavily,
@@ -115,34 +460,14 @@ enum Provider {
     Brave,
     Searxng,
     Arxiv,
+    Youtube,
 }
 
 #[derive(ValueEnum, Clone, Debug)]
-enum StrategyCli {
-    Failover,
-    LoadBalance,
-    Aggregate,
-    Race,
-}
-
-#[derive(ValueEnum, Clone, Debug)]
-enum SafeSearchCli {
-    Off,
-    Moderate,
-    Strict,
-}
-
-#[derive(ValueEnum, Clone, Debug)]
-enum SortByCli {
-    Relevance,
-    SubmittedDate,
-    LastUpdatedDate,
-}
-
-#[derive(ValueEnum, Clone, Debug)]
-enum SortOrderCli {
-    Ascending,
-    Descending,
+enum SuggestProviderCli {
+    Google,
+    Duckduckgo,
+    Brave,
 }
 
 #[derive(ValueEnum, Clone, Debug)]
@@ -150,6 +475,33 @@ enum OutputFormat {
     Table,
     Json,
     Simple,
+    Csv,
+    Markdown,
+    Jsonl,
+    Rss,
+    Atom,
+    Bibtex,
+}
+
+/// Vector-store export formats for `--export`
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum ExportCli {
+    /// One JSON document per line with id, text, metadata and embedding
+    Jsonl,
+    /// JSON array of LangChain documents
+    Langchain,
+    /// CSV with id, text, metadata columns and embedding
+    Csv,
+}
+
+impl From<ExportCli> for ExportFormat {
+    fn from(format: ExportCli) -> Self {
+        match format {
+            ExportCli::Jsonl => ExportFormat::Jsonl,
+            ExportCli::Langchain => ExportFormat::LangChain,
+            ExportCli::Csv => ExportFormat::Csv,
+        }
+    }
 }
 
 #[tokio::main]
@@ -162,14 +514,113 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             strategy,
             providers,
             max_results,
+            per_provider_results,
+            max_concurrent,
+            dedup,
+            consensus,
+            budget,
+            deadline,
+            tiers,
+            parallel_tiers,
             debug,
             format,
             stats,
+            filters,
+            output,
+            append,
+            quiet,
+            export,
+        }) => {
+            handle_multi_search(
+                query,
+                strategy,
+                providers,
+                MultiLimits {
+                    max_results,
+                    per_provider_results,
+                    max_concurrent,
+                    dedup,
+                    consensus,
+                    budget,
+                    deadline,
+                    tiers,
+                    parallel_tiers,
+                },
+                debug,
+                format,
+                stats,
+                filters,
+                cli.ignore_robots,
+                cli.cache.disk_cache(),
+                OutputTarget::new(output, append, quiet, export).with_highlight(cli.highlight),
+            )
+            .await?;
+        }
+        Some(Commands::Scholar {
+            query,
+            max_results,
+            year_from,
+            year_to,
+            raw,
+            debug,
+            format,
+            output,
+            append,
+            quiet,
+            export,
+        }) => {
+            handle_scholar_search(
+                query,
+                max_results,
+                year_from,
+                year_to,
+                raw,
+                debug,
+                format,
+                cli.cache.disk_cache(),
+                OutputTarget::new(output, append, quiet, export).with_highlight(cli.highlight),
+            )
+            .await?;
+        }
+        Some(Commands::Suggest {
+            query,
+            provider,
+            format,
         }) => {
-            handle_multi_search(query, strategy, providers, max_results, debug, format, stats).await?;
+            handle_suggest(query, provider, format).await?;
         }
-        Some(Commands::Providers) => {
+        Some(Commands::Trends {
+            region,
+            max_results,
+            format,
+        }) => {
+            handle_trends(region, max_results, format).await?;
+        }
+        Some(Commands::Products {
+            query,
+            country,
+            max_results,
+            format,
+        }) => {
+            handle_products(query, country, max_results, format).await?;
+        }
+        #[cfg(feature = "server")]
+        Some(Commands::Serve {
+            port,
+            host,
+            strategy,
+            providers,
+        }) => {
+            handle_serve(host, port, strategy, providers, cli.ignore_robots).await?;
+        }
+        Some(Commands::Cache { action }) => {
+            handle_cache(action, &cli.cache)?;
+        }
+        Some(Commands::Providers { verify }) => {
             handle_list_providers().await?;
+            if verify {
+                handle_verify_providers(cli.ignore_robots).await;
+            }
         }
         None => {
             // Default search behavior
@@ -182,14 +633,21 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                     provider,
                     max_results,
                     cli.language,
+                    cli.require_language,
                     cli.region,
                     cli.safe_search,
                     cli.arxiv_ids,
                     cli.sort_by,
                     cli.sort_order,
+                    cli.filters,
                     cli.debug,
                     cli.raw,
+                    cli.dry_run,
                     cli.format,
+                    cli.ignore_robots,
+                    cli.cache.disk_cache(),
+                    OutputTarget::new(cli.output, cli.append, cli.quiet, cli.export)
+                        .with_highlight(cli.highlight),
                 )
                 .await?;
             } else {
@@ -204,32 +662,39 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn handle_search(
     query: String,
     provider: Provider,
     max_results: u32,
     language: Option<String>,
+    require_language: bool,
     region: Option<String>,
-    safe_search: Option<SafeSearchCli>,
+    safe_search: Option<SafeSearch>,
     arxiv_ids: Option<String>,
-    sort_by: Option<SortByCli>,
-    sort_order: Option<SortOrderCli>,
+    sort_by: Option<SortBy>,
+    sort_order: Option<SortOrder>,
+    filters: FilterArgs,
     debug: bool,
     raw: bool,
+    dry_run: bool,
     format: OutputFormat,
+    ignore_robots: bool,
+    cache: Option<DiskCache>,
+    output: OutputTarget,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let provider_name = format!("{:?}", provider).to_lowercase();
-    let provider_box = create_provider(provider).await?;
+    let provider_box = with_cache(create_provider(provider, ignore_robots).await?, &cache);
 
     // For ArXiv, use either query or IDs
     let (search_query, id_list) = if provider_name == "arxiv" {
         if let Some(ids) = arxiv_ids {
             ("".to_string(), Some(ids))
         } else {
-            (query.clone(), None)
+            (filters.query(&query), None)
         }
     } else {
-        (query.clone(), None)
+        (filters.query(&query), None)
     };
 
     let options = SearchOptions {
@@ -237,21 +702,22 @@ async fn handle_search(
         id_list,
         max_results: Some(max_results),
         language,
+        require_language,
         region,
-        safe_search: safe_search.map(|s| match s {
-            SafeSearchCli::Off => SafeSearch::Off,
-            SafeSearchCli::Moderate => SafeSearch::Moderate,
-            SafeSearchCli::Strict => SafeSearch::Strict,
-        }),
-        sort_by: sort_by.map(|s| match s {
-            SortByCli::Relevance => SortBy::Relevance,
-            SortByCli::SubmittedDate => SortBy::SubmittedDate,
-            SortByCli::LastUpdatedDate => SortBy::LastUpdatedDate,
-        }),
-        sort_order: sort_order.map(|s| match s {
-            SortOrderCli::Ascending => SortOrder::Ascending,
-            SortOrderCli::Descending => SortOrder::Descending,
-        }),
+        safe_search,
+        sort_by,
+        sort_order,
+        page: filters.page,
+        request_timeout: filters.timeout_ms.map(Duration::from_millis),
+        include_domains: FilterArgs::domains(filters.site),
+        exclude_domains: FilterArgs::domains(filters.exclude_site),
+        published_after: filters.after,
+        published_before: filters.before,
+        file_type: filters.file_type,
+        freshness: filters.freshness,
+        exclude_kinds: filters.exclude_kind,
+        mode: filters.mode,
+        location: filters.location,
         debug: if debug {
             Some(DebugOptions {
                 enabled: true,
@@ -261,50 +727,182 @@ async fn handle_search(
         } else {
             None
         },
+        dry_run,
         provider: provider_box,
         ..Default::default()
     };
 
+    if dry_run {
+        let response = web_search_response(options).await?;
+        return display_request_plan(&response.planned_requests, &format, &output);
+    }
+
     let results = web_search(options).await?;
 
-    display_results(&results, &format, raw, Some(&provider_name));
-    Ok(())
+    display_results(
+        &results,
+        &format,
+        raw,
+        Some(&provider_name),
+        &query,
+        &output,
+    )
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn handle_multi_search(
     query: String,
-    strategy: StrategyCli,
+    strategy: MultiProviderStrategy,
     providers: Vec<Provider>,
-    max_results: u32,
+    limits: MultiLimits,
     debug: bool,
     format: OutputFormat,
     stats: bool,
+    filters: FilterArgs,
+    ignore_robots: bool,
+    cache: Option<DiskCache>,
+    output: OutputTarget,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let strategy = match strategy {
-        StrategyCli::Failover => MultiProviderStrategy::Failover,
-        StrategyCli::LoadBalance => MultiProviderStrategy::LoadBalance,
-        StrategyCli::Aggregate => MultiProviderStrategy::Aggregate,
-        StrategyCli::Race => MultiProviderStrategy::Aggregate, // Use Aggregate as Race strategy
-    };
-
-    let mut config = MultiProviderConfig::new(strategy);
+    let mut config = MultiProviderConfig::new(strategy)
+        .with_max_concurrent(limits.max_concurrent)
+        .with_dedup(limits.dedup);
+    if limits.consensus {
+        config = config.with_consensus_ranking(Consensus::default());
+    }
+    if let Some(per_provider_results) = limits.per_provider_results {
+        config = config.with_per_provider_results(per_provider_results);
+    }
+    if let Some(budget) = limits.budget {
+        config = config.with_search_budget(budget);
+    }
+    if let Some(deadline_ms) = limits.deadline {
+        config = config.with_deadline(std::time::Duration::from_millis(deadline_ms));
+    }
+    for tier in &limits.tiers {
+        config = config.with_tier(tier.split(',').map(|name| name.trim().to_lowercase()));
+    }
+    config = config.with_parallel_tiers(limits.parallel_tiers);
+    if let Some(timeout_ms) = filters.timeout_ms {
+        config = config.with_timeout(std::time::Duration::from_millis(timeout_ms));
+    }
 
     // If no providers specified, try to add all available ones
     let providers_to_use = if providers.is_empty() {
         get_available_providers().await
     } else {
-        providers
+        unique(providers)
     };
 
     for provider in providers_to_use {
-        if let Ok(provider_box) = create_provider(provider).await {
-            config = config.add_provider(provider_box);
+        if let Ok(provider_box) = create_provider(provider, ignore_robots).await {
+            config = config.add_provider(with_cache(provider_box, &cache));
         }
     }
 
-    let mut multi_search = MultiProviderSearch::new(config);
+    let multi_search = MultiProviderSearch::new(config);
 
     let options = SearchOptionsMulti {
+        query: filters.query(&query),
+        max_results: Some(limits.max_results),
+        page: filters.page,
+        request_timeout: filters.timeout_ms.map(Duration::from_millis),
+        include_domains: FilterArgs::domains(filters.site),
+        exclude_domains: FilterArgs::domains(filters.exclude_site),
+        published_after: filters.after,
+        published_before: filters.before,
+        file_type: filters.file_type,
+        freshness: filters.freshness,
+        exclude_kinds: filters.exclude_kind,
+        mode: filters.mode,
+        location: filters.location,
+        debug: if debug {
+            Some(DebugOptions {
+                enabled: true,
+                log_requests: true,
+                log_responses: false,
+            })
+        } else {
+            None
+        },
+        ..Default::default()
+    };
+
+    let response = multi_search.search_response(&options).await?;
+    if response.partial && !output.quiet {
+        eprintln!(
+            "{} deadline reached, showing results from the providers that answered in time",
+            "Warning:".yellow()
+        );
+    }
+
+    display_results(&response.results, &format, false, None, &query, &output)?;
+
+    if stats && !output.quiet {
+        display_provider_stats(&multi_search);
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "server")]
+async fn handle_serve(
+    host: String,
+    port: u16,
+    strategy: MultiProviderStrategy,
+    providers: Vec<Provider>,
+    ignore_robots: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let providers_to_use = if providers.is_empty() {
+        get_available_providers().await
+    } else {
+        unique(providers)
+    };
+
+    let mut config = MultiProviderConfig::new(strategy);
+    for provider in providers_to_use {
+        match create_provider(provider.clone(), ignore_robots).await {
+            Ok(provider_box) => config = config.add_provider(provider_box),
+            Err(e) => eprintln!("{} {:?}: {}", "Skipping".yellow(), provider, e),
+        }
+    }
+
+    let search = MultiProviderSearch::new(config);
+    let addr: std::net::SocketAddr = format!("{host}:{port}").parse()?;
+
+    println!(
+        "{} {} on http://{}",
+        "Serving".bold().green(),
+        search.provider_names().join(", ").bold(),
+        addr
+    );
+    println!("   GET /search?q=...&provider=...  GET /providers  GET /healthz");
+
+    websearch::server::serve(addr, search).await?;
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn handle_scholar_search(
+    query: String,
+    max_results: u32,
+    year_from: Option<u16>,
+    year_to: Option<u16>,
+    raw: bool,
+    debug: bool,
+    format: OutputFormat,
+    cache: Option<DiskCache>,
+    output: OutputTarget,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let api_key = Credential::new("SERPAPI_API_KEY").require()?;
+    let mut provider = GoogleScholarProvider::new(&api_key)?;
+    if let Some(year) = year_from {
+        provider = provider.with_year_from(year);
+    }
+    if let Some(year) = year_to {
+        provider = provider.with_year_to(year);
+    }
+
+    let options = SearchOptions {
         query: query.clone(),
         max_results: Some(max_results),
         debug: if debug {
@@ -316,15 +914,228 @@ async fn handle_multi_search(
         } else {
             None
         },
+        provider: with_cache(Box::new(provider), &cache),
         ..Default::default()
     };
 
-    let results = multi_search.search(&options).await?;
+    let results = web_search(options).await?;
 
-    display_results(&results, &format, false, None);
+    display_results(
+        &results,
+        &format,
+        raw,
+        Some("google_scholar"),
+        &query,
+        &output,
+    )
+}
 
-    if stats {
-        display_provider_stats(&multi_search);
+async fn handle_suggest(
+    query: String,
+    provider: SuggestProviderCli,
+    format: OutputFormat,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let provider: Box<dyn SuggestProvider> = match provider {
+        SuggestProviderCli::Google => Box::new(GoogleSuggestProvider::new()),
+        SuggestProviderCli::Duckduckgo => Box::new(DuckDuckGoSuggestProvider::new()),
+        SuggestProviderCli::Brave => {
+            let api_key = Credential::new("BRAVE_API_KEY").require()?;
+            Box::new(BraveSuggestProvider::new(&api_key)?)
+        }
+    };
+
+    let suggestions = suggest(&query, provider.as_ref()).await?;
+
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&suggestions)?),
+        OutputFormat::Jsonl => print!("{}", format::to_jsonl(&suggestions)?),
+        OutputFormat::Csv => {
+            print!("{}", format::csv_row(&["suggestion"]));
+            for suggestion in &suggestions {
+                print!("{}", format::csv_row(&[suggestion]));
+            }
+        }
+        OutputFormat::Rss | OutputFormat::Atom | OutputFormat::Bibtex => {
+            return Err("RSS, Atom and BibTeX output is only available for search results".into())
+        }
+        OutputFormat::Markdown => {
+            for suggestion in &suggestions {
+                println!("- {}", format::escape_markdown(suggestion));
+            }
+        }
+        OutputFormat::Simple => {
+            for suggestion in &suggestions {
+                println!("{suggestion}");
+            }
+        }
+        OutputFormat::Table => {
+            println!(
+                "{}",
+                format!("Suggestions from {}", provider.name())
+                    .bold()
+                    .blue()
+            );
+            for (index, suggestion) in suggestions.iter().enumerate() {
+                println!("{:>3}. {}", index + 1, suggestion);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn handle_trends(
+    region: String,
+    max_results: usize,
+    format: OutputFormat,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let api_key = Credential::new("SERPAPI_API_KEY").require()?;
+    let provider = SerpApiTrendsProvider::new(&api_key)?;
+
+    let mut trends = trending(&region, &provider).await?;
+    trends.truncate(max_results);
+
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&trends)?),
+        OutputFormat::Jsonl => print!("{}", format::to_jsonl(&trends)?),
+        OutputFormat::Csv => {
+            print!(
+                "{}",
+                format::csv_row(&["query", "search_volume", "categories"])
+            );
+            for trend in &trends {
+                print!(
+                    "{}",
+                    format::csv_row(&[
+                        trend.query.clone(),
+                        trend
+                            .search_volume
+                            .map(|v| v.to_string())
+                            .unwrap_or_default(),
+                        trend.categories.join("; "),
+                    ])
+                );
+            }
+        }
+        OutputFormat::Rss | OutputFormat::Atom | OutputFormat::Bibtex => {
+            return Err("RSS, Atom and BibTeX output is only available for search results".into())
+        }
+        OutputFormat::Markdown => {
+            for trend in &trends {
+                println!("- {}", format::escape_markdown(&trend.query));
+            }
+        }
+        OutputFormat::Simple => {
+            for trend in &trends {
+                println!("{}", trend.query);
+            }
+        }
+        OutputFormat::Table => {
+            println!(
+                "{}",
+                format!("Trending searches in {}", region.to_uppercase())
+                    .bold()
+                    .blue()
+            );
+            for (index, trend) in trends.iter().enumerate() {
+                let volume = trend
+                    .search_volume
+                    .map(|volume| format!(" ({volume}+ searches)"))
+                    .unwrap_or_default();
+                println!(
+                    "{:>3}. {}{}",
+                    index + 1,
+                    trend.query.bold(),
+                    volume.dimmed()
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// "89.99 EUR", or just the amount when the currency is unknown
+fn product_price(product: &ProductResult) -> String {
+    match (product.price, &product.currency) {
+        (Some(price), Some(currency)) => format!("{price:.2} {currency}"),
+        (Some(price), None) => format!("{price:.2}"),
+        _ => String::new(),
+    }
+}
+
+async fn handle_products(
+    query: String,
+    country: Option<String>,
+    max_results: u32,
+    format: OutputFormat,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let api_key = Credential::new("SERPAPI_API_KEY").require()?;
+    let mut provider = SerpApiShoppingProvider::new(&api_key)?;
+    if let Some(country) = &country {
+        provider = provider.with_country(country);
+    }
+
+    let products = search_products(&query, max_results, &provider).await?;
+
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&products)?),
+        OutputFormat::Jsonl => print!("{}", format::to_jsonl(&products)?),
+        OutputFormat::Csv => {
+            print!(
+                "{}",
+                format::csv_row(&["title", "url", "price", "currency", "merchant", "rating"])
+            );
+            for product in &products {
+                print!(
+                    "{}",
+                    format::csv_row(&[
+                        product.title.clone(),
+                        product.url.clone(),
+                        product.price.map(|p| p.to_string()).unwrap_or_default(),
+                        product.currency.clone().unwrap_or_default(),
+                        product.merchant.clone().unwrap_or_default(),
+                        product.rating.map(|r| r.to_string()).unwrap_or_default(),
+                    ])
+                );
+            }
+        }
+        OutputFormat::Rss | OutputFormat::Atom | OutputFormat::Bibtex => {
+            return Err("RSS, Atom and BibTeX output is only available for search results".into())
+        }
+        OutputFormat::Markdown => {
+            for product in &products {
+                println!(
+                    "- [{}]({}) {}",
+                    format::escape_markdown(&product.title),
+                    product.url,
+                    product_price(product)
+                );
+            }
+        }
+        OutputFormat::Simple => {
+            for product in &products {
+                println!("{}\t{}", product_price(product), product.title);
+            }
+        }
+        OutputFormat::Table => {
+            println!("{}", format!("Products for \"{query}\"").bold().blue());
+            for (index, product) in products.iter().enumerate() {
+                println!("{:>3}. {}", index + 1, product.title.bold());
+                println!("     💰 {}", product_price(product).green());
+                if let Some(merchant) = &product.merchant {
+                    println!("     🏪 {merchant}");
+                }
+                if let Some(rating) = product.rating {
+                    let reviews = product
+                        .reviews
+                        .map(|reviews| format!(" ({reviews} reviews)"))
+                        .unwrap_or_default();
+                    println!("     ⭐ {rating}{}", reviews.dimmed());
+                }
+                println!("     🔗 {}", product.url.dimmed());
+            }
+        }
     }
 
     Ok(())
@@ -343,6 +1154,11 @@ async fn handle_list_providers() -> Result<(), Box<dyn std::error::Error>> {
         ("Brave", "Requires BRAVE_API_KEY"),
         ("SearXNG", "Requires SEARXNG_URL"),
         ("ArXiv", "No API key required"),
+        (
+            "Google Scholar",
+            "Requires SERPAPI_API_KEY (websearch scholar)",
+        ),
+        ("YouTube", "Requires YOUTUBE_API_KEY (videos)"),
     ];
 
     for (name, requirement) in providers {
@@ -360,148 +1176,502 @@ async fn handle_list_providers() -> Result<(), Box<dyn std::error::Error>> {
     println!("export SERPAPI_API_KEY=your_key");
     println!("export BRAVE_API_KEY=your_key");
     println!("export SEARXNG_URL=https://your-searxng-instance.com");
+    println!("export YOUTUBE_API_KEY=your_key");
 
     Ok(())
 }
 
-async fn create_provider(provider: Provider) -> Result<Box<dyn websearch::types::SearchProvider>, Box<dyn std::error::Error>> {
-    match provider {
-        Provider::Google => {
-            let api_key = env::var("GOOGLE_API_KEY")?;
-            let cx = env::var("GOOGLE_CX")?;
-            Ok(Box::new(GoogleProvider::new(&api_key, &cx)?))
+async fn handle_verify_providers(ignore_robots: bool) {
+    println!();
+    println!("{}", "Verifying configured providers...".bold().blue());
+
+    let mut providers = Vec::new();
+    for provider in get_available_providers().await {
+        match create_provider(provider.clone(), ignore_robots).await {
+            Ok(provider_box) => providers.push(provider_box),
+            Err(e) => println!("{} {:?} - {}", "❌".red(), provider, e),
         }
-        Provider::Tavily => {
-            let api_key = env::var("TAVILY_API_KEY")?;
-            Ok(Box::new(TavilyProvider::new(&api_key)?))
+    }
+
+    let checks = providers.iter().map(|provider| provider.health_check());
+    for health in futures::future::join_all(checks).await {
+        let (icon, status) = match health.status {
+            HealthStatus::Healthy => ("✅".green(), "healthy".green()),
+            HealthStatus::AuthFailed => ("❌".red(), "authentication failed".red()),
+            HealthStatus::RateLimited => ("⚠️".yellow(), "rate limited".yellow()),
+            HealthStatus::Unavailable => ("❌".red(), "unavailable".red()),
+        };
+        let quota = health
+            .remaining_quota
+            .map(|quota| format!(", {quota} searches left"))
+            .unwrap_or_default();
+
+        println!(
+            "{} {} - {} ({} ms{})",
+            icon,
+            health.provider.bold(),
+            status,
+            health.latency_ms,
+            quota
+        );
+        if let Some(message) = health.message {
+            println!("   {}", message.dimmed());
         }
-        Provider::Exa => {
-            let api_key = env::var("EXA_API_KEY")?;
-            Ok(Box::new(ExaProvider::new(&api_key)?))
+    }
+}
+
+/// Wrap `provider` so its results are served from `cache` when possible
+fn with_cache(
+    provider: Box<dyn SearchProvider>,
+    cache: &Option<DiskCache>,
+) -> Box<dyn SearchProvider> {
+    match cache {
+        Some(cache) => Box::new(CachedProvider::new(provider, cache.clone())),
+        None => provider,
+    }
+}
+
+fn handle_cache(action: CacheAction, args: &CacheArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let cache = DiskCache::with_default_dir(Duration::from_secs(args.cache_ttl))
+        .ok_or("Could not determine a cache directory; set WEBSEARCH_CACHE_DIR")?;
+
+    match action {
+        CacheAction::Clear => {
+            let removed = cache.clear()?;
+            println!(
+                "Removed {} cached searches from {}",
+                removed,
+                cache.dir().display()
+            );
         }
-        Provider::Serpapi => {
-            let api_key = env::var("SERPAPI_API_KEY")?;
-            Ok(Box::new(SerpApiProvider::new(&api_key)?))
+        CacheAction::Stats => {
+            let stats = cache.stats()?;
+            println!("{}", "Search Result Cache:".bold().blue());
+            println!("  Location: {}", cache.dir().display());
+            println!("  Entries: {} ({} expired)", stats.entries, stats.expired);
+            println!("  Size: {:.1} KiB", stats.size_bytes as f64 / 1024.0);
+            println!("  TTL: {}s", args.cache_ttl);
         }
-        Provider::Duckduckgo => Ok(Box::new(DuckDuckGoProvider::new())),
-        Provider::Brave => {
-            let api_key = env::var("BRAVE_API_KEY")?;
-            Ok(Box::new(BraveProvider::new(&api_key)?))
+    }
+
+    Ok(())
+}
+
+/// Provider for the API key in `var`; a comma-separated list rotates among several keys
+fn keyed_provider<P: SearchProvider + 'static>(
+    var: &str,
+    mut build: impl FnMut(&str) -> websearch::Result<P>,
+) -> Result<Box<dyn SearchProvider>, Box<dyn std::error::Error>> {
+    let keys = Credential::new(var).require()?;
+    let mut keys: Vec<&str> = keys
+        .split(',')
+        .map(str::trim)
+        .filter(|key| !key.is_empty())
+        .collect();
+    if keys.len() == 1 {
+        return Ok(Box::new(build(keys.remove(0))?));
+    }
+    Ok(Box::new(KeyRotatingProvider::new(keys, build)?))
+}
+
+async fn create_provider(
+    provider: Provider,
+    ignore_robots: bool,
+) -> Result<Box<dyn websearch::types::SearchProvider>, Box<dyn std::error::Error>> {
+    match provider {
+        Provider::Google => {
+            let cx = Credential::new("GOOGLE_CX").require()?;
+            keyed_provider("GOOGLE_API_KEY", |api_key| {
+                GoogleProvider::new(api_key, &cx)
+            })
         }
+        Provider::Tavily => keyed_provider("TAVILY_API_KEY", TavilyProvider::new),
+        Provider::Exa => keyed_provider("EXA_API_KEY", ExaProvider::new),
+        Provider::Serpapi => keyed_provider("SERPAPI_API_KEY", SerpApiProvider::new),
+        Provider::Duckduckgo => Ok(Box::new(
+            DuckDuckGoProvider::new().respect_robots_txt(!ignore_robots),
+        )),
+        Provider::Brave => keyed_provider("BRAVE_API_KEY", BraveProvider::new),
         Provider::Searxng => {
-            let url = env::var("SEARXNG_URL")?;
-            Ok(Box::new(SearxNGProvider::new(&url)?))
+            // A comma-separated list rotates among several instances
+            let urls = Credential::new("SEARXNG_URL").require()?;
+            Ok(Box::new(SearxNGProvider::with_instances(
+                urls.split(',').map(str::trim),
+            )?))
         }
         Provider::Arxiv => Ok(Box::new(ArxivProvider::new())),
+        Provider::Youtube => keyed_provider("YOUTUBE_API_KEY", YouTubeProvider::new),
     }
 }
 
+/// Drop repeated providers, keeping the first of each
+fn unique(providers: Vec<Provider>) -> Vec<Provider> {
+    let mut unique = Vec::new();
+    for provider in providers {
+        if !unique.contains(&provider) {
+            unique.push(provider);
+        }
+    }
+    unique
+}
+
 async fn get_available_providers() -> Vec<Provider> {
     let mut available = Vec::new();
 
-    if env::var("GOOGLE_API_KEY").is_ok() && env::var("GOOGLE_CX").is_ok() {
+    if Credential::new("GOOGLE_API_KEY").is_available()
+        && Credential::new("GOOGLE_CX").is_available()
+    {
         available.push(Provider::Google);
     }
-    if env::var("TAVILY_API_KEY").is_ok() {
+    if Credential::new("TAVILY_API_KEY").is_available() {
         available.push(Provider::Tavily);
     }
-    if env::var("EXA_API_KEY").is_ok() {
+    if Credential::new("EXA_API_KEY").is_available() {
         available.push(Provider::Exa);
     }
-    if env::var("SERPAPI_API_KEY").is_ok() {
+    if Credential::new("SERPAPI_API_KEY").is_available() {
         available.push(Provider::Serpapi);
     }
     available.push(Provider::Duckduckgo); // Always available
-    if env::var("BRAVE_API_KEY").is_ok() {
+    if Credential::new("BRAVE_API_KEY").is_available() {
         available.push(Provider::Brave);
     }
-    if env::var("SEARXNG_URL").is_ok() {
+    if Credential::new("SEARXNG_URL").is_available() {
         available.push(Provider::Searxng);
     }
     available.push(Provider::Arxiv); // Always available
+    if Credential::new("YOUTUBE_API_KEY").is_available() {
+        available.push(Provider::Youtube);
+    }
 
     available
 }
 
 async fn check_provider_availability(provider_name: &str) -> bool {
     match provider_name {
-        "Google" => env::var("GOOGLE_API_KEY").is_ok() && env::var("GOOGLE_CX").is_ok(),
-        "Tavily" => env::var("TAVILY_API_KEY").is_ok(),
-        "Exa" => env::var("EXA_API_KEY").is_ok(),
-        "SerpAPI" => env::var("SERPAPI_API_KEY").is_ok(),
+        "Google" => {
+            Credential::new("GOOGLE_API_KEY").is_available()
+                && Credential::new("GOOGLE_CX").is_available()
+        }
+        "Tavily" => Credential::new("TAVILY_API_KEY").is_available(),
+        "Exa" => Credential::new("EXA_API_KEY").is_available(),
+        "SerpAPI" => Credential::new("SERPAPI_API_KEY").is_available(),
         "DuckDuckGo" => true,
-        "Brave" => env::var("BRAVE_API_KEY").is_ok(),
-        "SearXNG" => env::var("SEARXNG_URL").is_ok(),
+        "Brave" => Credential::new("BRAVE_API_KEY").is_available(),
+        "SearXNG" => Credential::new("SEARXNG_URL").is_available(),
         "ArXiv" => true,
+        "Google Scholar" => Credential::new("SERPAPI_API_KEY").is_available(),
+        "YouTube" => Credential::new("YOUTUBE_API_KEY").is_available(),
         _ => false,
     }
 }
 
+/// Where result listings are written, from `--output`, `--append`, `--quiet` and `--export`
+struct OutputTarget {
+    path: Option<PathBuf>,
+    append: bool,
+    quiet: bool,
+    export: Option<ExportFormat>,
+    highlight: bool,
+}
+
+impl OutputTarget {
+    fn new(path: Option<PathBuf>, append: bool, quiet: bool, export: Option<ExportCli>) -> Self {
+        Self {
+            path,
+            append,
+            quiet,
+            export: export.map(ExportFormat::from),
+            highlight: false,
+        }
+    }
+
+    /// Render highlighted snippet terms in bold
+    fn with_highlight(mut self, highlight: bool) -> Self {
+        self.highlight = highlight;
+        self
+    }
+
+    /// The result's snippet cut to about `max_len` bytes, with its highlights in bold when
+    /// enabled
+    fn snippet(
+        &self,
+        result: &websearch::types::SearchResult,
+        max_len: Option<usize>,
+        italic: bool,
+    ) -> Option<String> {
+        let mut snippet = result.snippet.as_deref()?;
+        let mut highlights = if self.highlight {
+            result.highlights.clone()
+        } else {
+            Vec::new()
+        };
+        let cut = max_len
+            .filter(|max_len| snippet.len() > *max_len)
+            .map(|max_len| {
+                let end = (0..=max_len)
+                    .rev()
+                    .find(|end| snippet.is_char_boundary(*end))
+                    .unwrap_or(0);
+                snippet = &snippet[..end];
+                highlight::clip(&mut highlights, end);
+            });
+
+        let style = |part: &str, highlighted: bool| {
+            let part = if highlighted {
+                part.bold()
+            } else {
+                part.normal()
+            };
+            if italic { part.italic() } else { part }.to_string()
+        };
+        let mut rendered: String = highlight::segments(snippet, &highlights)
+            .into_iter()
+            .map(|(part, highlighted)| style(part, highlighted))
+            .collect();
+        if cut.is_some() {
+            rendered.push_str(&style("...", false));
+        }
+        Some(rendered)
+    }
+
+    /// Open the target; files get plain text (no ANSI colors)
+    fn open(&self) -> std::io::Result<Box<dyn Write>> {
+        match &self.path {
+            Some(path) => {
+                colored::control::set_override(false);
+                let file = OpenOptions::new()
+                    .create(true)
+                    .write(true)
+                    .append(self.append)
+                    .truncate(!self.append)
+                    .open(path)?;
+                Ok(Box::new(BufWriter::new(file)))
+            }
+            None => Ok(Box::new(std::io::stdout().lock())),
+        }
+    }
+
+    /// Whether new output continues an existing file (so e.g. a CSV header is not repeated)
+    fn continues_file(&self) -> bool {
+        self.append
+            && self
+                .path
+                .as_ref()
+                .and_then(|path| std::fs::metadata(path).ok())
+                .is_some_and(|metadata| metadata.len() > 0)
+    }
+}
+
+/// Feed title for `--format rss` and `--format atom`
+fn feed_title(query: &str) -> String {
+    format!("websearch: {query}")
+}
+
+/// Print the requests captured by `--dry-run`
+fn display_request_plan(
+    requests: &[RequestPlan],
+    format: &OutputFormat,
+    output: &OutputTarget,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut out = output.open()?;
+    if matches!(format, OutputFormat::Json | OutputFormat::Jsonl) {
+        writeln!(out, "{}", serde_json::to_string_pretty(requests)?)?;
+        return Ok(());
+    }
+
+    for request in requests {
+        writeln!(out, "{} {}", request.method.bold(), request.url)?;
+        for (name, value) in &request.headers {
+            writeln!(out, "  {}: {}", name.dimmed(), value)?;
+        }
+        if let Some(body) = &request.body {
+            writeln!(out, "\n  {body}")?;
+        }
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
 fn display_results(
     results: &[websearch::types::SearchResult],
     format: &OutputFormat,
     show_raw: bool,
     provider: Option<&str>,
-) {
+    query: &str,
+    output: &OutputTarget,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let continues_file = output.continues_file();
+    let mut out = output.open()?;
+
+    if let Some(export) = output.export {
+        let exported = export::export_results(results, export)?;
+        match export {
+            // Appended rows continue the existing file's header
+            ExportFormat::Csv if continues_file => write!(
+                out,
+                "{}",
+                exported.split_once("\r\n").map_or("", |(_, rows)| rows)
+            )?,
+            ExportFormat::LangChain => writeln!(out, "{exported}")?,
+            _ => write!(out, "{exported}")?,
+        }
+        return Ok(());
+    }
+
     match format {
         OutputFormat::Json => {
-            println!("{}", serde_json::to_string_pretty(results).unwrap());
+            writeln!(out, "{}", serde_json::to_string_pretty(results)?)?;
+        }
+        OutputFormat::Csv => {
+            let csv = format::to_csv(results);
+            let csv = if continues_file {
+                csv.split_once("\r\n").map_or("", |(_, rows)| rows)
+            } else {
+                &csv
+            };
+            write!(out, "{}", csv)?;
         }
+        OutputFormat::Markdown => write!(out, "{}", format::to_markdown(results))?,
+        OutputFormat::Jsonl => write!(out, "{}", format::to_jsonl(results)?)?,
+        OutputFormat::Rss => write!(out, "{}", format::to_rss(results, &feed_title(query)))?,
+        OutputFormat::Atom => write!(out, "{}", format::to_atom(results, &feed_title(query)))?,
+        OutputFormat::Bibtex => write!(out, "{}", format::to_bibtex(results))?,
         OutputFormat::Simple => {
             for (i, result) in results.iter().enumerate() {
-                println!("{}. {}", i + 1, result.title);
-                println!("   {}", result.url);
-                if let Some(snippet) = &result.snippet {
-                    println!("   {}", snippet);
+                writeln!(out, "{}. {}", i + 1, result.title)?;
+                writeln!(out, "   {}", result.url)?;
+                if let Some(snippet) = output.snippet(result, None, false) {
+                    writeln!(out, "   {}", snippet)?;
                 }
-                println!();
+                writeln!(out)?;
             }
         }
         OutputFormat::Table => {
-            if let Some(provider) = provider {
-                println!("{} {}", "Search Results from".bold(), provider.bold().blue());
-            } else {
-                println!("{}", "Search Results".bold().blue());
+            if !output.quiet {
+                if let Some(provider) = provider {
+                    writeln!(
+                        out,
+                        "{} {}",
+                        "Search Results from".bold(),
+                        provider.bold().blue()
+                    )?;
+                } else {
+                    writeln!(out, "{}", "Search Results".bold().blue())?;
+                }
+                writeln!(out, "{}", "─".repeat(80).dimmed())?;
             }
-            println!("{}", "─".repeat(80).dimmed());
 
             for (i, result) in results.iter().enumerate() {
-                println!("{}. {}", (i + 1).to_string().bold(), result.title.bold());
-                println!("   🔗 {}", result.url.blue().underline());
+                writeln!(
+                    out,
+                    "{}. {}",
+                    (i + 1).to_string().bold(),
+                    result.title.bold()
+                )?;
+                writeln!(out, "   🔗 {}", result.url.blue().underline())?;
 
                 if let Some(domain) = &result.domain {
-                    println!("   🌐 {}", domain.green());
+                    writeln!(out, "   🌐 {}", domain.green())?;
                 }
 
-                if let Some(snippet) = &result.snippet {
-                    let truncated = if snippet.len() > 200 {
-                        format!("{}...", &snippet[..200])
-                    } else {
-                        snippet.clone()
-                    };
-                    println!("   📄 {}", truncated.italic());
+                if let Some(snippet) = output.snippet(result, Some(200), true) {
+                    writeln!(out, "   📄 {}", snippet)?;
                 }
 
                 if let Some(published_date) = &result.published_date {
-                    println!("   📅 {}", published_date.yellow());
+                    writeln!(out, "   📅 {}", published_date.yellow())?;
+                }
+
+                if let Some(video) = &result.video {
+                    if let Some(details) = video_details(video) {
+                        writeln!(out, "   🎬 {}", details.magenta())?;
+                    }
+                }
+
+                if let Some(details) = result.place.as_ref().and_then(place_details) {
+                    writeln!(out, "   📍 {}", details.magenta())?;
                 }
 
                 if let Some(provider) = &result.provider {
-                    println!("   🔍 Provider: {}", provider.cyan());
+                    writeln!(out, "   🔍 Provider: {}", provider.cyan())?;
                 }
 
                 if show_raw {
                     if let Some(raw) = &result.raw {
-                        println!("   📊 Raw: {}", serde_json::to_string_pretty(raw).unwrap());
+                        writeln!(out, "   📊 Raw: {}", serde_json::to_string_pretty(raw)?)?;
                     }
                 }
 
-                println!();
+                writeln!(out)?;
+            }
+
+            if !output.quiet {
+                writeln!(
+                    out,
+                    "{} {}",
+                    "Total results:".bold(),
+                    results.len().to_string().bold()
+                )?;
             }
+        }
+    }
+
+    out.flush()?;
 
-            println!("{} {}", "Total results:".bold(), results.len().to_string().bold());
+    if let Some(path) = &output.path {
+        if !output.quiet {
+            eprintln!("Wrote {} results to {}", results.len(), path.display());
         }
     }
+
+    Ok(())
+}
+
+/// Channel, length and views of a video, e.g. `Fireship · 2:29 · 2,100,000 views`
+fn video_details(video: &websearch::types::VideoResult) -> Option<String> {
+    let duration = video.duration.map(|duration| {
+        let seconds = duration.as_secs();
+        match seconds / 3600 {
+            0 => format!("{}:{:02}", seconds / 60, seconds % 60),
+            hours => format!("{}:{:02}:{:02}", hours, seconds / 60 % 60, seconds % 60),
+        }
+    });
+    let views = video.views.map(|views| {
+        let digits = views.to_string();
+        let mut grouped = String::new();
+        for (i, digit) in digits.chars().enumerate() {
+            if i > 0 && (digits.len() - i) % 3 == 0 {
+                grouped.push(',');
+            }
+            grouped.push(digit);
+        }
+        format!("{grouped} views")
+    });
+    let parts: Vec<String> = video
+        .channel
+        .clone()
+        .into_iter()
+        .chain(duration)
+        .chain(views)
+        .collect();
+    (!parts.is_empty()).then(|| parts.join(" · "))
+}
+
+/// Address, rating and hours of a place, e.g. `Auguststraße 58 · 4.6★ (1520) · Open`
+fn place_details(place: &websearch::types::PlaceResult) -> Option<String> {
+    let rating = place.rating.map(|rating| match place.reviews {
+        Some(reviews) => format!("{rating}★ ({reviews})"),
+        None => format!("{rating}★"),
+    });
+    let parts: Vec<String> = place
+        .address
+        .clone()
+        .into_iter()
+        .chain(rating)
+        .chain(place.hours.clone())
+        .collect();
+    (!parts.is_empty()).then(|| parts.join(" · "))
 }
 
 fn display_provider_stats(multi_search: &MultiProviderSearch) {
@@ -516,11 +1686,63 @@ fn display_provider_stats(multi_search: &MultiProviderSearch) {
         println!("  Total requests: {}", stat.total_requests);
         println!("  Successful: {}", stat.successful_requests.to_string().green());
         println!("  Failed: {}", stat.failed_requests.to_string().red());
+        if stat.cancelled_requests > 0 {
+            println!("  Cancelled: {}", stat.cancelled_requests);
+        }
         println!("  Avg response time: {:.2}ms", stat.avg_response_time_ms);
-        if stat.total_requests > 0 {
-            let success_rate = (stat.successful_requests as f64 / stat.total_requests as f64) * 100.0;
+        if let (Some(p50), Some(p90), Some(p99)) = (
+            stat.p50_latency_ms,
+            stat.p90_latency_ms,
+            stat.p99_latency_ms,
+        ) {
+            println!(
+                "  Latency p50/p90/p99: {:.0}ms / {:.0}ms / {:.0}ms",
+                p50, p90, p99
+            );
+        }
+        // Cancelled requests lost a race without finishing, so they don't count either way
+        let completed = stat.total_requests - stat.cancelled_requests;
+        if completed > 0 {
+            let success_rate = (stat.successful_requests as f64 / completed as f64) * 100.0;
             println!("  Success rate: {:.1}%", success_rate);
         }
+        if stat.skipped_over_budget > 0 {
+            println!(
+                "  Skipped (over budget): {}",
+                stat.skipped_over_budget.to_string().yellow()
+            );
+        }
+        println!("  Estimated cost: ${:.4}", stat.estimated_cost_usd);
+        if stat.queue_depth > 0 {
+            println!("  Queued: {}", stat.queue_depth);
+        }
+        if stat.throttle_delay_ms > 0 {
+            println!(
+                "  Throttled: {}ms between requests",
+                stat.throttle_delay_ms.to_string().yellow()
+            );
+        }
+        if stat.failed_requests > 0 {
+            let errors = &stat.errors;
+            println!(
+                "  Errors: timeout {}, rate limited {}, 5xx {}, auth {}, other {}",
+                errors.timeout, errors.rate_limited, errors.server_error, errors.auth, errors.other
+            );
+        }
+        if let Some(at) = stat.last_success {
+            println!("  Last success: {}", at.format("%Y-%m-%d %H:%M:%S UTC"));
+        }
+        if let Some(at) = stat.last_failure {
+            println!(
+                "  Last failure: {}",
+                at.format("%Y-%m-%d %H:%M:%S UTC").to_string().red()
+            );
+        }
         println!();
     }
+
+    println!(
+        "Total estimated cost: ${:.4}",
+        multi_search.estimated_cost()
+    );
 }
\ No newline at end of file
@@ -0,0 +1,200 @@
+//! Blocking (synchronous) API
+//!
+//! Mirrors the async API for callers that don't run an async runtime themselves, such as
+//! scripts, plugins or simple CLIs. Each call drives the async implementation on an
+//! internal Tokio runtime, so these functions must not be called from within an async
+//! context (they panic there, like `tokio::runtime::Runtime::block_on`).
+//!
+//! Requires the `blocking` feature.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use websearch::{blocking, providers::DuckDuckGoProvider, SearchOptions};
+//!
+//! fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let results = blocking::web_search(SearchOptions {
+//!         query: "rust programming".to_string(),
+//!         provider: Box::new(DuckDuckGoProvider::new()),
+//!         ..Default::default()
+//!     })?;
+//!
+//!     for result in results {
+//!         println!("{}: {}", result.title, result.url);
+//!     }
+//!     Ok(())
+//! }
+//! ```
+
+use crate::{
+    batch::BatchSearchResult,
+    error::{SearchError, SearchResult as Result},
+    multi_provider::{
+        self, CredentialReport, MultiProviderConfig, MultiSearchResponse, ProviderStats,
+        SearchOptionsMulti,
+    },
+    types::{SearchOptions, SearchQuery, SearchResponse, SearchResult},
+};
+use std::collections::HashMap;
+use tokio::runtime::{Builder, Runtime};
+
+fn runtime() -> Result<Runtime> {
+    Ok(Builder::new_current_thread().enable_all().build()?)
+}
+
+/// Blocking version of [`crate::web_search`]
+pub fn web_search(options: SearchOptions) -> Result<Vec<SearchResult>> {
+    runtime()?.block_on(crate::web_search(options))
+}
+
+/// Blocking version of [`crate::web_search_response`]
+pub fn web_search_response(options: SearchOptions) -> Result<SearchResponse> {
+    runtime()?.block_on(crate::web_search_response(options))
+}
+
+/// Blocking version of [`multi_provider::MultiProviderSearch`]
+///
+/// Owns the runtime its searches run on, so keep one around instead of creating one per
+/// query.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use websearch::{
+///     blocking::MultiProviderSearch,
+///     multi_provider::{MultiProviderConfig, MultiProviderStrategy, SearchOptionsMulti},
+///     providers::DuckDuckGoProvider,
+/// };
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let config = MultiProviderConfig::new(MultiProviderStrategy::Failover)
+///     .add_provider(Box::new(DuckDuckGoProvider::new()));
+/// let search = MultiProviderSearch::new(config)?;
+///
+/// let results = search.search(&SearchOptionsMulti {
+///     query: "rust async".to_string(),
+///     ..Default::default()
+/// })?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct MultiProviderSearch {
+    inner: multi_provider::MultiProviderSearch,
+    runtime: Runtime,
+}
+
+impl MultiProviderSearch {
+    pub fn new(config: MultiProviderConfig) -> Result<Self> {
+        Ok(Self {
+            inner: multi_provider::MultiProviderSearch::new(config),
+            runtime: Builder::new_multi_thread().enable_all().build()?,
+        })
+    }
+
+    /// Like [`MultiProviderConfig::build`], checking credentials first if enabled
+    pub fn build(config: MultiProviderConfig) -> Result<(Self, CredentialReport)> {
+        let runtime = Builder::new_multi_thread().enable_all().build()?;
+        let (inner, report) = runtime.block_on(config.build());
+        Ok((Self { inner, runtime }, report))
+    }
+
+    /// Perform search using the configured strategy
+    pub fn search(&self, options: &SearchOptionsMulti) -> Result<Vec<SearchResult>> {
+        self.runtime.block_on(self.inner.search(options))
+    }
+
+    /// Like [`search`](Self::search), but also reports whether the deadline cut it short
+    pub fn search_response(&self, options: &SearchOptionsMulti) -> Result<MultiSearchResponse> {
+        self.runtime.block_on(self.inner.search_response(options))
+    }
+
+    /// Run many queries through the configured strategy with bounded concurrency
+    pub fn search_batch(
+        &self,
+        queries: Vec<SearchQuery>,
+        concurrency: usize,
+    ) -> Vec<BatchSearchResult> {
+        self.runtime
+            .block_on(self.inner.search_batch(queries, concurrency))
+    }
+
+    /// Search with a single named provider, bypassing the strategy
+    pub fn search_provider(
+        &self,
+        name: &str,
+        options: &SearchOptionsMulti,
+    ) -> Result<Vec<SearchResult>> {
+        self.runtime
+            .block_on(self.inner.search_provider(name, options))
+    }
+
+    /// Names of the configured providers, in configuration order
+    pub fn provider_names(&self) -> Vec<&str> {
+        self.inner.provider_names()
+    }
+
+    pub fn get_stats(&self) -> HashMap<String, ProviderStats> {
+        self.inner.get_stats()
+    }
+
+    /// Providers disabled after a misconfigured failure, with that failure
+    pub fn disabled_providers(&self) -> Vec<(String, SearchError)> {
+        self.inner.disabled_providers()
+    }
+
+    /// Query a disabled provider again
+    pub fn enable_provider(&self, name: &str) {
+        self.inner.enable_provider(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{multi_provider::MultiProviderStrategy, types::SearchProvider};
+
+    #[derive(Debug)]
+    struct EchoProvider;
+
+    #[async_trait::async_trait]
+    impl SearchProvider for EchoProvider {
+        fn name(&self) -> &str {
+            "echo"
+        }
+
+        async fn search(&self, options: &SearchOptions) -> Result<Vec<SearchResult>> {
+            Ok(vec![SearchResult {
+                title: options.query.clone(),
+                url: "https://example.com/".to_string(),
+                ..Default::default()
+            }])
+        }
+    }
+
+    #[test]
+    fn test_blocking_web_search() {
+        let results = web_search(SearchOptions {
+            query: "rust".to_string(),
+            provider: Box::new(EchoProvider),
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(results[0].title, "rust");
+    }
+
+    #[test]
+    fn test_blocking_multi_provider_search() {
+        let config = MultiProviderConfig::new(MultiProviderStrategy::Failover)
+            .add_provider(Box::new(EchoProvider));
+        let search = MultiProviderSearch::new(config).unwrap();
+
+        let results = search
+            .search(&SearchOptionsMulti {
+                query: "tokio".to_string(),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(results[0].title, "tokio");
+        assert_eq!(search.get_stats()["echo"].total_requests, 1);
+    }
+}
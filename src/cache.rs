@@ -0,0 +1,452 @@
+//! On-disk caching of search results
+//!
+//! [`DiskCache`] stores each response as a JSON file keyed by the provider and the
+//! search options, and [`CachedProvider`] wraps any [`SearchProvider`] so repeated
+//! identical searches are answered from the cache until the entry expires.
+//! `CachedProvider` also keys entries by the provider's [`config`](SearchProvider::config),
+//! so differently configured providers of the same name (e.g. Google Scholar with another
+//! year range) don't share cached results.
+
+use crate::{
+    error::SearchResult,
+    types::{
+        ProviderHealth, SearchMode, SearchOptions, SearchProvider, SearchResponse,
+        SearchResult as SearchResultType,
+    },
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Default time-to-live for cached responses
+pub const DEFAULT_CACHE_TTL: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheEntry {
+    stored_at: DateTime<Utc>,
+    provider: String,
+    query: String,
+    response: SearchResponse,
+}
+
+/// Summary of the cache directory contents
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct CacheStats {
+    /// Number of cached responses, including expired ones
+    pub entries: usize,
+    /// Entries older than the cache's TTL
+    pub expired: usize,
+    /// Total size of the cache files
+    pub size_bytes: u64,
+}
+
+/// A directory of cached search responses
+#[derive(Debug, Clone)]
+pub struct DiskCache {
+    dir: PathBuf,
+    ttl: Duration,
+}
+
+impl DiskCache {
+    /// Cache in `dir` (created on first write) with entries valid for `ttl`
+    pub fn new(dir: impl Into<PathBuf>, ttl: Duration) -> Self {
+        Self {
+            dir: dir.into(),
+            ttl,
+        }
+    }
+
+    /// Cache in [`default_dir`](Self::default_dir) with the given TTL
+    pub fn with_default_dir(ttl: Duration) -> Option<Self> {
+        Self::default_dir().map(|dir| Self::new(dir, ttl))
+    }
+
+    /// `$WEBSEARCH_CACHE_DIR`, else `$XDG_CACHE_HOME/websearch`, else `~/.cache/websearch`
+    pub fn default_dir() -> Option<PathBuf> {
+        let non_empty = |name: &str| std::env::var_os(name).filter(|value| !value.is_empty());
+
+        non_empty("WEBSEARCH_CACHE_DIR")
+            .map(PathBuf::from)
+            .or_else(|| non_empty("XDG_CACHE_HOME").map(|dir| PathBuf::from(dir).join("websearch")))
+            .or_else(|| non_empty("HOME").map(|home| PathBuf::from(home).join(".cache/websearch")))
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// Cached response for `provider` and `options`, if present and not expired
+    pub fn get(&self, provider: &str, options: &SearchOptions) -> Option<SearchResponse> {
+        self.read(&self.entry_path(provider, "", options))
+    }
+
+    /// Store `response` for `provider` and `options`
+    pub fn put(
+        &self,
+        provider: &str,
+        options: &SearchOptions,
+        response: &SearchResponse,
+    ) -> io::Result<()> {
+        self.write(
+            &self.entry_path(provider, "", options),
+            provider,
+            options,
+            response,
+        )
+    }
+
+    fn read(&self, path: &Path) -> Option<SearchResponse> {
+        let body = fs::read_to_string(path).ok()?;
+        let entry: CacheEntry = serde_json::from_str(&body).ok()?;
+        (!self.is_expired(&entry)).then_some(entry.response)
+    }
+
+    fn write(
+        &self,
+        path: &Path,
+        provider: &str,
+        options: &SearchOptions,
+        response: &SearchResponse,
+    ) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        let entry = CacheEntry {
+            stored_at: Utc::now(),
+            provider: provider.to_string(),
+            query: options.query.clone(),
+            response: response.clone(),
+        };
+        fs::write(path, serde_json::to_vec(&entry)?)
+    }
+
+    /// Remove every cached response, returning how many were removed
+    pub fn clear(&self) -> io::Result<usize> {
+        let mut removed = 0;
+        for path in self.entry_files()? {
+            fs::remove_file(path)?;
+            removed += 1;
+        }
+        Ok(removed)
+    }
+
+    /// Count the cached responses and their size
+    pub fn stats(&self) -> io::Result<CacheStats> {
+        let mut stats = CacheStats::default();
+        for path in self.entry_files()? {
+            stats.entries += 1;
+            stats.size_bytes += fs::metadata(&path)?.len();
+
+            let expired = fs::read_to_string(&path)
+                .ok()
+                .and_then(|body| serde_json::from_str::<CacheEntry>(&body).ok())
+                .is_none_or(|entry| self.is_expired(&entry));
+            if expired {
+                stats.expired += 1;
+            }
+        }
+        Ok(stats)
+    }
+
+    fn is_expired(&self, entry: &CacheEntry) -> bool {
+        let age = Utc::now().signed_duration_since(entry.stored_at);
+        age.to_std().is_ok_and(|age| age > self.ttl)
+    }
+
+    fn entry_files(&self) -> io::Result<Vec<PathBuf>> {
+        match fs::read_dir(&self.dir) {
+            Ok(entries) => Ok(entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+                .collect()),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+            Err(error) => Err(error),
+        }
+    }
+
+    /// File for `provider` configured as `config` (see [`config_key`]) and `options`
+    fn entry_path(&self, provider: &str, config: &str, options: &SearchOptions) -> PathBuf {
+        self.dir.join(format!(
+            "{:016x}.json",
+            cache_key(provider, config, options)
+        ))
+    }
+}
+
+/// A provider's configuration as a stable string, sorted by setting name
+fn config_key(config: HashMap<String, String>) -> String {
+    let mut settings: Vec<(String, String)> = config.into_iter().collect();
+    settings.sort();
+    settings
+        .iter()
+        .map(|(name, value)| format!("{name}={value}\n"))
+        .collect()
+}
+
+/// Stable hash of everything that affects a provider's results
+fn cache_key(provider: &str, config: &str, options: &SearchOptions) -> u64 {
+    let key = format!(
+        "{provider}\n{config}{:?}\n{:?}",
+        (
+            &options.query,
+            &options.id_list,
+            options.max_results,
+            &options.language,
+            options.require_language,
+            &options.region,
+            &options.safe_search,
+            options.page,
+        ),
+        (
+            options.start,
+            &options.sort_by,
+            &options.sort_order,
+            &options.include_domains,
+            &options.exclude_domains,
+            options.published_after,
+            options.published_before,
+            options.file_type,
+            options.freshness,
+            options.mode,
+            &options.location,
+        )
+    );
+
+    stable_hash(&key)
+}
+
+/// FNV-1a hash of `key`, which stays the same across builds and Rust versions
+pub(crate) fn stable_hash(key: &str) -> u64 {
+    key.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// A provider whose responses are cached in a [`DiskCache`]
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use std::time::Duration;
+/// use websearch::{cache::{CachedProvider, DiskCache}, providers::DuckDuckGoProvider};
+///
+/// let cache = DiskCache::new("/tmp/websearch-cache", Duration::from_secs(600));
+/// let provider = CachedProvider::new(Box::new(DuckDuckGoProvider::new()), cache);
+/// ```
+#[derive(Debug)]
+pub struct CachedProvider {
+    inner: Box<dyn SearchProvider>,
+    cache: DiskCache,
+    /// The inner provider's configuration, taken once as providers are configured up front
+    config: String,
+}
+
+impl CachedProvider {
+    pub fn new(inner: Box<dyn SearchProvider>, cache: DiskCache) -> Self {
+        let config = config_key(inner.config());
+        Self {
+            inner,
+            cache,
+            config,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl SearchProvider for CachedProvider {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    async fn search(&self, options: &SearchOptions) -> SearchResult<Vec<SearchResultType>> {
+        Ok(self.search_response(options).await?.results)
+    }
+
+    async fn search_response(&self, options: &SearchOptions) -> SearchResult<SearchResponse> {
+        if options.dry_run {
+            return self.inner.search_response(options).await;
+        }
+        let path = self.cache.entry_path(self.name(), &self.config, options);
+        if let Some(response) = self.cache.read(&path) {
+            log::debug!("Cache hit for {} query {:?}", self.name(), options.query);
+            return Ok(response);
+        }
+
+        let response = self.inner.search_response(options).await?;
+        if let Err(error) = self.cache.write(&path, self.name(), options, &response) {
+            log::warn!("Failed to cache {} results: {}", self.name(), error);
+        }
+        Ok(response)
+    }
+
+    async fn health_check(&self) -> ProviderHealth {
+        self.inner.health_check().await
+    }
+
+    async fn validate_credentials(&self) -> SearchResult<()> {
+        self.inner.validate_credentials().await
+    }
+
+    fn supports_safe_search(&self) -> bool {
+        self.inner.supports_safe_search()
+    }
+
+    fn supports_domain_filter(&self) -> bool {
+        self.inner.supports_domain_filter()
+    }
+
+    fn supports_freshness(&self) -> bool {
+        self.inner.supports_freshness()
+    }
+
+    fn supports_pagination(&self) -> bool {
+        self.inner.supports_pagination()
+    }
+
+    fn supports_mode(&self, mode: SearchMode) -> bool {
+        self.inner.supports_mode(mode)
+    }
+
+    fn config(&self) -> HashMap<String, String> {
+        let mut config = self.inner.config();
+        config.insert(
+            "cache_dir".to_string(),
+            self.cache.dir.display().to_string(),
+        );
+        config
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    #[derive(Debug, Default)]
+    struct CountingProvider {
+        calls: Arc<AtomicUsize>,
+        year_from: Option<u16>,
+    }
+
+    #[async_trait::async_trait]
+    impl SearchProvider for CountingProvider {
+        fn name(&self) -> &str {
+            "counting"
+        }
+
+        async fn search(&self, options: &SearchOptions) -> SearchResult<Vec<SearchResultType>> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(vec![SearchResultType {
+                title: options.query.clone(),
+                url: "https://example.com/".to_string(),
+                ..Default::default()
+            }])
+        }
+
+        fn config(&self) -> HashMap<String, String> {
+            self.year_from
+                .map(|year| HashMap::from([("year_from".to_string(), year.to_string())]))
+                .unwrap_or_default()
+        }
+    }
+
+    fn options(query: &str) -> SearchOptions {
+        SearchOptions {
+            query: query.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_repeated_search_is_served_from_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let provider = CachedProvider::new(
+            Box::new(CountingProvider {
+                calls: calls.clone(),
+                ..Default::default()
+            }),
+            DiskCache::new(dir.path(), DEFAULT_CACHE_TTL),
+        );
+
+        let first = provider.search(&options("rust")).await.unwrap();
+        let second = provider.search(&options("rust")).await.unwrap();
+        provider.search(&options("tokio")).await.unwrap();
+
+        assert_eq!(first[0].title, second[0].title);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_differently_configured_providers_dont_share_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let cached = |year_from| {
+            CachedProvider::new(
+                Box::new(CountingProvider {
+                    calls: calls.clone(),
+                    year_from,
+                }),
+                DiskCache::new(dir.path(), DEFAULT_CACHE_TTL),
+            )
+        };
+
+        cached(None).search(&options("rust")).await.unwrap();
+        cached(Some(2020)).search(&options("rust")).await.unwrap();
+        cached(Some(2020)).search(&options("rust")).await.unwrap();
+
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert_eq!(cached(None).cache.stats().unwrap().entries, 2);
+    }
+
+    #[test]
+    fn test_expired_entries_are_ignored() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = DiskCache::new(dir.path(), Duration::ZERO);
+        cache
+            .put("counting", &options("rust"), &SearchResponse::default())
+            .unwrap();
+
+        std::thread::sleep(Duration::from_millis(5));
+        assert!(cache.get("counting", &options("rust")).is_none());
+        assert_eq!(
+            cache.stats().unwrap(),
+            CacheStats {
+                entries: 1,
+                expired: 1,
+                size_bytes: fs::metadata(cache.entry_path("counting", "", &options("rust")))
+                    .unwrap()
+                    .len(),
+            }
+        );
+
+        assert_eq!(cache.clear().unwrap(), 1);
+        assert_eq!(cache.stats().unwrap().entries, 0);
+    }
+
+    #[test]
+    fn test_cache_key_depends_on_provider_and_options() {
+        let mut paged = options("rust");
+        paged.page = Some(2);
+
+        assert_eq!(
+            cache_key("a", "", &options("rust")),
+            cache_key("a", "", &options("rust"))
+        );
+        assert_ne!(
+            cache_key("a", "", &options("rust")),
+            cache_key("b", "", &options("rust"))
+        );
+        assert_ne!(
+            cache_key("a", "", &options("rust")),
+            cache_key("a", "", &paged)
+        );
+    }
+}
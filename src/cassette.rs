@@ -0,0 +1,449 @@
+//! Record and replay of provider HTTP traffic
+//!
+//! A [`Cassette`] is a JSON file of HTTP interactions. In [`CassetteMode::Record`] every
+//! request made by a provider goes to the network as usual and the request/response pair
+//! is appended to the file; in [`CassetteMode::Replay`] responses are served from the file
+//! and nothing touches the network, which makes integration tests deterministic and lets
+//! you develop offline.
+//!
+//! Activate a cassette for the searches inside a future with [`Cassette::scope`], or for a
+//! whole process by setting `WEBSEARCH_CASSETTE=<path>` and optionally
+//! `WEBSEARCH_CASSETTE_MODE=record|replay` (replay by default).
+//!
+//! API keys and tokens in query strings, form bodies and JSON bodies are replaced with
+//! `REDACTED` before anything is written, and request headers are never recorded, so
+//! cassettes can be committed.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use websearch::{cassette::Cassette, providers::DuckDuckGoProvider, web_search, SearchOptions};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let cassette = Cassette::replay("tests/cassettes/duckduckgo-rust.json")?;
+//! let results = cassette
+//!     .scope(web_search(SearchOptions {
+//!         query: "rust".to_string(),
+//!         provider: Box::new(DuckDuckGoProvider::new()),
+//!         ..Default::default()
+//!     }))
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{
+    correlation, dry_run,
+    error::{SearchError, SearchResult},
+    limits,
+};
+use reqwest::{RequestBuilder, Response};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// Query parameters and body fields whose values are never written to a cassette
+pub const REDACTED_FIELDS: [&str; 6] = [
+    "key",
+    "api_key",
+    "apikey",
+    "token",
+    "access_token",
+    "subscription_token",
+];
+
+const REDACTED: &str = "REDACTED";
+
+tokio::task_local! {
+    static ACTIVE: Cassette;
+}
+
+/// Whether a cassette records new traffic or replays recorded traffic
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CassetteMode {
+    /// Send requests to the network and append each interaction to the file
+    Record,
+    /// Serve responses from the file; unrecorded requests fail
+    Replay,
+}
+
+/// A request as stored in a cassette
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordedRequest {
+    pub method: String,
+    /// URL with secrets redacted
+    pub url: String,
+    /// Body with secrets redacted, if the request had one
+    pub body: Option<String>,
+}
+
+/// A response as stored in a cassette
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordedResponse {
+    pub status: u16,
+    pub content_type: Option<String>,
+    pub body: String,
+}
+
+/// One request and the response it received
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Interaction {
+    pub request: RecordedRequest,
+    pub response: RecordedResponse,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CassetteFile {
+    interactions: Vec<Interaction>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    interactions: Vec<Interaction>,
+    replayed: Vec<bool>,
+}
+
+/// A file of recorded HTTP interactions; clones share the same recording
+#[derive(Debug, Clone)]
+pub struct Cassette {
+    path: PathBuf,
+    mode: CassetteMode,
+    state: Arc<Mutex<State>>,
+}
+
+impl Cassette {
+    /// Start a new recording at `path`, replacing any existing file on the first request
+    pub fn record(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            mode: CassetteMode::Record,
+            state: Arc::default(),
+        }
+    }
+
+    /// Load the recording at `path` for replay
+    pub fn replay(path: impl Into<PathBuf>) -> SearchResult<Self> {
+        let path = path.into();
+        let file: CassetteFile = serde_json::from_str(&std::fs::read_to_string(&path)?)?;
+        let replayed = vec![false; file.interactions.len()];
+
+        Ok(Self {
+            path,
+            mode: CassetteMode::Replay,
+            state: Arc::new(Mutex::new(State {
+                interactions: file.interactions,
+                replayed,
+            })),
+        })
+    }
+
+    /// The cassette named by `WEBSEARCH_CASSETTE` and `WEBSEARCH_CASSETTE_MODE`, if set
+    pub fn from_env() -> SearchResult<Option<Self>> {
+        let Some(path) = std::env::var_os("WEBSEARCH_CASSETTE").filter(|p| !p.is_empty()) else {
+            return Ok(None);
+        };
+
+        let mode = std::env::var("WEBSEARCH_CASSETTE_MODE").unwrap_or_default();
+        match mode.to_lowercase().as_str() {
+            "" | "replay" => Self::replay(path).map(Some),
+            "record" => Ok(Some(Self::record(path))),
+            other => Err(SearchError::ConfigError(format!(
+                "Unknown WEBSEARCH_CASSETTE_MODE '{other}', expected 'record' or 'replay'"
+            ))),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn mode(&self) -> CassetteMode {
+        self.mode
+    }
+
+    /// The interactions recorded so far (or loaded for replay)
+    pub fn interactions(&self) -> Vec<Interaction> {
+        self.state.lock().unwrap().interactions.clone()
+    }
+
+    /// Run `future` with this cassette handling every provider HTTP request it makes
+    pub async fn scope<F: Future>(&self, future: F) -> F::Output {
+        ACTIVE.scope(self.clone(), future).await
+    }
+
+    async fn send(&self, request: RequestBuilder) -> SearchResult<Response> {
+        let (client, request) = request.build_split();
+        let request = request?;
+        let recorded_request = RecordedRequest {
+            method: request.method().to_string(),
+            url: canonical_url(request.url()),
+            body: request
+                .body()
+                .and_then(|body| body.as_bytes())
+                .map(|bytes| canonical_body(&String::from_utf8_lossy(bytes))),
+        };
+
+        let recorded_response = match self.mode {
+            CassetteMode::Replay => self.find(&recorded_request)?,
+            CassetteMode::Record => {
+                let response = client.execute(request).await?;
+                let recorded_response = RecordedResponse {
+                    status: response.status().as_u16(),
+                    content_type: response
+                        .headers()
+                        .get(reqwest::header::CONTENT_TYPE)
+                        .and_then(|value| value.to_str().ok())
+                        .map(str::to_string),
+                    body: response.text().await?,
+                };
+                self.append(Interaction {
+                    request: recorded_request,
+                    response: recorded_response.clone(),
+                })?;
+                recorded_response
+            }
+        };
+
+        to_response(recorded_response)
+    }
+
+    /// The first not-yet-replayed interaction matching `request`, else the last match
+    fn find(&self, request: &RecordedRequest) -> SearchResult<RecordedResponse> {
+        let mut state = self.state.lock().unwrap();
+        let matches: Vec<usize> = (0..state.interactions.len())
+            .filter(|&i| state.interactions[i].request == *request)
+            .collect();
+        let index = matches
+            .iter()
+            .copied()
+            .find(|&i| !state.replayed[i])
+            .or(matches.last().copied())
+            .ok_or_else(|| {
+                SearchError::Other(format!(
+                    "No recorded response for {} {} in cassette {}",
+                    request.method,
+                    request.url,
+                    self.path.display()
+                ))
+            })?;
+
+        state.replayed[index] = true;
+        Ok(state.interactions[index].response.clone())
+    }
+
+    fn append(&self, interaction: Interaction) -> SearchResult<()> {
+        let mut state = self.state.lock().unwrap();
+        state.interactions.push(interaction);
+        state.replayed.push(false);
+
+        if let Some(dir) = self.path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)?;
+        }
+        let file = CassetteFile {
+            interactions: state.interactions.clone(),
+        };
+        std::fs::write(&self.path, serde_json::to_string_pretty(&file)?)?;
+        Ok(())
+    }
+}
+
+/// Send `request`, through the active cassette if there is one, enforcing the current
+/// search's `max_response_bytes` (or capture it, during a dry run)
+pub(crate) async fn send(request: RequestBuilder) -> SearchResult<Response> {
+    let request = correlation::with_request_id(request);
+    if dry_run::is_active() {
+        return dry_run::capture(request);
+    }
+    let response = if let Ok(cassette) = ACTIVE.try_with(Cassette::clone) {
+        cassette.send(request).await?
+    } else {
+        match env_cassette() {
+            Some(cassette) => cassette.send(request).await?,
+            None => send_throttled(request).await?,
+        }
+    };
+    limits::limit_response(response).await
+}
+
+/// Send a live request, waiting out and updating the adaptive throttle
+async fn send_throttled(request: RequestBuilder) -> SearchResult<Response> {
+    let Some(key) = limits::throttle_key() else {
+        return Ok(request.send().await?);
+    };
+    limits::throttle(&key).await;
+    let response = request.send().await?;
+    limits::record_response(&key, &response);
+    Ok(response)
+}
+
+fn env_cassette() -> Option<&'static Cassette> {
+    static ENV: OnceLock<Option<Cassette>> = OnceLock::new();
+    ENV.get_or_init(|| {
+        Cassette::from_env().unwrap_or_else(|error| {
+            log::warn!("Ignoring WEBSEARCH_CASSETTE: {error}");
+            None
+        })
+    })
+    .as_ref()
+}
+
+fn to_response(recorded: RecordedResponse) -> SearchResult<Response> {
+    let mut builder = http::Response::builder().status(recorded.status);
+    if let Some(content_type) = &recorded.content_type {
+        builder = builder.header(reqwest::header::CONTENT_TYPE, content_type);
+    }
+    builder
+        .body(recorded.body)
+        .map(Response::from)
+        .map_err(|error| SearchError::Other(format!("Invalid recorded response: {error}")))
+}
+
+fn is_secret(name: &str) -> bool {
+    REDACTED_FIELDS.contains(&name.to_lowercase().as_str())
+}
+
+/// Secrets redacted and pairs sorted, so a request matches however its parameters were ordered
+fn canonical_pairs<'a>(
+    pairs: impl Iterator<Item = (Cow<'a, str>, Cow<'a, str>)>,
+) -> Vec<(String, String)> {
+    let mut pairs: Vec<(String, String)> = pairs
+        .map(|(name, value)| {
+            let value = if is_secret(&name) {
+                REDACTED.to_string()
+            } else {
+                value.into_owned()
+            };
+            (name.into_owned(), value)
+        })
+        .collect();
+    pairs.sort();
+    pairs
+}
+
+pub(crate) fn canonical_url(url: &url::Url) -> String {
+    let mut url = url.clone();
+    if url.query().is_some_and(|query| !query.is_empty()) {
+        let pairs = canonical_pairs(url.query_pairs());
+        url.query_pairs_mut().clear().extend_pairs(pairs);
+    }
+    url.to_string()
+}
+
+pub(crate) fn canonical_body(body: &str) -> String {
+    if let Ok(mut json) = serde_json::from_str::<serde_json::Value>(body) {
+        if let Some(object) = json.as_object_mut() {
+            for (name, value) in object.iter_mut() {
+                if is_secret(name) {
+                    *value = serde_json::Value::from(REDACTED);
+                }
+            }
+        }
+        // Re-serializing also sorts object keys
+        return json.to_string();
+    }
+
+    if !body.is_empty() && body.split('&').all(|pair| pair.contains('=')) {
+        let pairs = canonical_pairs(url::form_urlencoded::parse(body.as_bytes()));
+        return url::form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(pairs)
+            .finish();
+    }
+
+    body.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::http::HttpClient;
+    use wiremock::{
+        matchers::{method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    #[tokio::test]
+    async fn test_record_then_replay() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("cassettes/search.json");
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/search"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("recorded body"))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let url = format!("{}/search?q=rust&api_key=secret", server.uri());
+
+        let recorder = Cassette::record(&file);
+        let body = recorder
+            .scope(HttpClient::new().get_text(&url))
+            .await
+            .unwrap();
+        assert_eq!(body, "recorded body");
+        assert!(!std::fs::read_to_string(&file).unwrap().contains("secret"));
+        drop(server);
+
+        let player = Cassette::replay(&file).unwrap();
+        let replayed = player
+            .scope(HttpClient::new().get_text(&url))
+            .await
+            .unwrap();
+        assert_eq!(replayed, "recorded body");
+
+        let missing = player
+            .scope(HttpClient::new().get_text("http://127.0.0.1:9/other"))
+            .await;
+        assert!(missing
+            .unwrap_err()
+            .to_string()
+            .contains("No recorded response"));
+    }
+
+    #[tokio::test]
+    async fn test_replayed_errors_keep_their_status() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("errors.json");
+        std::fs::write(
+            &file,
+            serde_json::to_string(&serde_json::json!({
+                "interactions": [{
+                    "request": { "method": "GET", "url": "https://api.example.com/", "body": null },
+                    "response": { "status": 429, "content_type": null, "body": "slow down" }
+                }]
+            }))
+            .unwrap(),
+        )
+        .unwrap();
+
+        let error = Cassette::replay(&file)
+            .unwrap()
+            .scope(HttpClient::new().get_text("https://api.example.com/"))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            SearchError::HttpError {
+                status_code: Some(429),
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_requests_are_redacted_and_canonical() {
+        let url = url::Url::parse("https://example.com/s?q=rust&key=abc&cx=1").unwrap();
+        assert_eq!(
+            canonical_url(&url),
+            "https://example.com/s?cx=1&key=REDACTED&q=rust"
+        );
+        assert_eq!(
+            canonical_body(r#"{"query":"rust","api_key":"abc"}"#),
+            r#"{"api_key":"REDACTED","query":"rust"}"#
+        );
+        assert_eq!(canonical_body("token=abc&q=rust"), "q=rust&token=REDACTED");
+        assert_eq!(canonical_body("plain text"), "plain text");
+    }
+}
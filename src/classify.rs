@@ -0,0 +1,267 @@
+//! Classifying queries by what kind of source answers them best
+//!
+//! [`RuleClassifier`] recognizes paper-like, time-sensitive and code queries from keywords
+//! and a few patterns (arXiv IDs, DOIs, stack trace lines). Anything smarter, such as an ML
+//! model behind an HTTP endpoint, plugs in by implementing [`QueryClassifier`].
+//! [`SmartRouting`](crate::routing::SmartRouting) uses the classification to pick providers.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use websearch::classify::{QueryKind, RuleClassifier};
+//!
+//! let classifier = RuleClassifier::default();
+//! assert_eq!(classifier.classify_text("attention is all you need paper"), QueryKind::Academic);
+//! assert_eq!(classifier.classify_text("thread 'main' panicked at src/main.rs:4:5"), QueryKind::Code);
+//! assert_eq!(classifier.classify_text("best pizza dough"), QueryKind::General);
+//! ```
+
+use crate::types::SearchQuery;
+use serde::{Deserialize, Serialize};
+
+/// What kind of source a query is looking for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryKind {
+    /// Papers, preprints and other scholarly work
+    Academic,
+    /// Recent events, where freshness matters
+    News,
+    /// Error messages, stack traces and programming questions
+    Code,
+    /// Everything else
+    General,
+}
+
+/// Decides the [`QueryKind`] of a query
+#[async_trait::async_trait]
+pub trait QueryClassifier: Send + Sync + std::fmt::Debug {
+    async fn classify(&self, query: &SearchQuery) -> QueryKind;
+}
+
+/// Words that mark a paper-like query
+const ACADEMIC_KEYWORDS: &[&str] = &[
+    "arxiv",
+    "paper",
+    "papers",
+    "preprint",
+    "doi",
+    "journal",
+    "proceedings",
+    "thesis",
+    "dissertation",
+    "survey",
+    "citation",
+    "citations",
+    "et al",
+    "peer reviewed",
+    "literature review",
+];
+
+/// Words that mark a time-sensitive query
+const NEWS_KEYWORDS: &[&str] = &[
+    "news",
+    "latest",
+    "breaking",
+    "today",
+    "yesterday",
+    "tonight",
+    "this week",
+    "announced",
+    "announcement",
+    "headlines",
+    "election",
+    "stock price",
+];
+
+/// Substrings that mark error output or a stack trace
+const CODE_MARKERS: &[&str] = &[
+    "traceback (most recent call last)",
+    "panicked at",
+    "exception in thread",
+    "stack trace",
+    "stacktrace",
+    "segmentation fault",
+    "undefined reference to",
+    "nullpointerexception",
+    "typeerror:",
+    "syntaxerror:",
+    "valueerror:",
+    "referenceerror:",
+    "error[e",
+    "cannot find symbol",
+    "command not found",
+    "compile error",
+    "npm err!",
+    "    at ",
+];
+
+/// Keyword and pattern rules for the built-in query kinds
+///
+/// Code markers are checked first, since stack traces often contain other keywords, then
+/// academic and news keywords. Keywords match whole words, ignoring case.
+#[derive(Debug, Clone)]
+pub struct RuleClassifier {
+    academic: Vec<String>,
+    news: Vec<String>,
+    code: Vec<String>,
+}
+
+impl Default for RuleClassifier {
+    fn default() -> Self {
+        let owned = |words: &[&str]| words.iter().map(|w| w.to_string()).collect();
+        Self {
+            academic: owned(ACADEMIC_KEYWORDS),
+            news: owned(NEWS_KEYWORDS),
+            code: owned(CODE_MARKERS),
+        }
+    }
+}
+
+impl RuleClassifier {
+    /// Also treat queries containing any of `keywords` as `kind`
+    ///
+    /// Keywords added for [`QueryKind::General`] are ignored.
+    pub fn with_keywords<I, S>(mut self, kind: QueryKind, keywords: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let list = match kind {
+            QueryKind::Academic => &mut self.academic,
+            QueryKind::News => &mut self.news,
+            QueryKind::Code => &mut self.code,
+            QueryKind::General => return self,
+        };
+        list.extend(keywords.into_iter().map(|k| k.as_ref().to_lowercase()));
+        self
+    }
+
+    /// Classify query text with the rules alone
+    pub fn classify_text(&self, text: &str) -> QueryKind {
+        let lower = text.to_lowercase();
+        // Code markers are substrings: they include punctuation and leading spaces
+        if self
+            .code
+            .iter()
+            .any(|marker| lower.contains(marker.as_str()))
+            || looks_like_source_location(&lower)
+        {
+            return QueryKind::Code;
+        }
+
+        let words = format!(
+            " {} ",
+            lower
+                .split(|c: char| !c.is_alphanumeric() && c != '.' && c != '/')
+                .map(|w| w.trim_matches('.'))
+                .filter(|w| !w.is_empty())
+                .collect::<Vec<_>>()
+                .join(" ")
+        );
+        let has_keyword = |keywords: &[String]| {
+            keywords
+                .iter()
+                .any(|keyword| words.contains(&format!(" {keyword} ")))
+        };
+
+        if has_keyword(&self.academic) || words.split(' ').any(is_paper_id) {
+            QueryKind::Academic
+        } else if has_keyword(&self.news) {
+            QueryKind::News
+        } else {
+            QueryKind::General
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl QueryClassifier for RuleClassifier {
+    /// ArXiv ID lookups are academic and date-restricted searches are news; otherwise the
+    /// query text decides
+    async fn classify(&self, query: &SearchQuery) -> QueryKind {
+        if query.id_list.is_some() {
+            return QueryKind::Academic;
+        }
+        match self.classify_text(&query.query) {
+            QueryKind::General if query.published_after.is_some() => QueryKind::News,
+            kind => kind,
+        }
+    }
+}
+
+/// A new-style arXiv ID (`2301.12345`) or a DOI (`10.1000/xyz`)
+fn is_paper_id(word: &str) -> bool {
+    let digits = |s: &str, min: usize, max: usize| {
+        (min..=max).contains(&s.len()) && s.chars().all(|c| c.is_ascii_digit())
+    };
+    let arxiv = word.split_once('.').is_some_and(|(yymm, number)| {
+        // Drop a version suffix such as `v2`
+        let number = number.split_once('v').map_or(number, |(number, _)| number);
+        digits(yymm, 4, 4) && digits(number, 4, 5)
+    });
+    let doi = word
+        .strip_prefix("10.")
+        .and_then(|rest| rest.split_once('/'))
+        .is_some_and(|(registrant, suffix)| digits(registrant, 4, 9) && !suffix.is_empty());
+    arxiv || doi
+}
+
+/// `file.ext:line` references such as `src/main.rs:4:5` or `app.py:12`
+fn looks_like_source_location(text: &str) -> bool {
+    const EXTENSIONS: &[&str] = &[
+        ".rs:", ".py:", ".js:", ".ts:", ".go:", ".java:", ".c:", ".cpp:", ".rb:", ".php:",
+    ];
+    EXTENSIONS.iter().any(|ext| {
+        text.match_indices(ext).any(|(at, _)| {
+            text[at + ext.len()..]
+                .chars()
+                .next()
+                .is_some_and(|c| c.is_ascii_digit())
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rule_classifier() {
+        let classifier = RuleClassifier::default();
+        for (text, kind) in [
+            (
+                "Traceback (most recent call last): KeyError 'id'",
+                QueryKind::Code,
+            ),
+            ("error[E0382]: borrow of moved value", QueryKind::Code),
+            ("index.js:17 Uncaught", QueryKind::Code),
+            ("survey of graph neural networks", QueryKind::Academic),
+            ("2301.07041", QueryKind::Academic),
+            ("10.1038/nature14539", QueryKind::Academic),
+            ("latest rust release news", QueryKind::News),
+            ("paperwork for a visa", QueryKind::General),
+            ("how to bake bread", QueryKind::General),
+        ] {
+            assert_eq!(classifier.classify_text(text), kind, "{text}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_query_fields_and_custom_keywords() {
+        let classifier = RuleClassifier::default().with_keywords(QueryKind::News, ["Score"]);
+        assert_eq!(classifier.classify_text("world cup score"), QueryKind::News);
+
+        let query = SearchQuery {
+            published_after: chrono::NaiveDate::from_ymd_opt(2024, 1, 1),
+            ..SearchQuery::new("rust conference")
+        };
+        assert_eq!(classifier.classify(&query).await, QueryKind::News);
+
+        let query = SearchQuery {
+            id_list: Some("2301.07041".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(classifier.classify(&query).await, QueryKind::Academic);
+    }
+}
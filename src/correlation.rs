@@ -0,0 +1,140 @@
+//! Correlation IDs for tracing a search across logs
+//!
+//! Every search made through [`crate::web_search`], [`crate::web_search_batch`] or
+//! [`crate::multi_provider::MultiProviderSearch`] runs under a correlation ID: the caller's
+//! [`SearchOptions::correlation_id`](crate::SearchOptions::correlation_id), or a generated
+//! one. The ID prefixes [debug output](crate::DebugOptions), is recorded on a
+//! `websearch.search` tracing span covering the whole search (with a `websearch.provider`
+//! span per provider call), and can be sent to providers as an `X-Request-Id` header with
+//! [`set_send_request_id`]. A multi-provider search keeps one ID for every provider it
+//! tries, so its failovers and fan-outs can be followed end to end.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use websearch::{correlation, providers::DuckDuckGoProvider, web_search, SearchOptions};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! correlation::set_send_request_id(true);
+//!
+//! let results = web_search(SearchOptions {
+//!     query: "rust".to_string(),
+//!     correlation_id: Some("checkout-1234".to_string()),
+//!     provider: Box::new(DuckDuckGoProvider::new()),
+//!     ..Default::default()
+//! })
+//! .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use reqwest::RequestBuilder;
+use std::collections::hash_map::RandomState;
+use std::future::Future;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::atomic::{AtomicBool, Ordering};
+use tracing::Instrument;
+
+/// Header carrying the correlation ID when [`set_send_request_id`] is on
+pub const REQUEST_ID_HEADER: &str = "X-Request-Id";
+
+static SEND_REQUEST_ID: AtomicBool = AtomicBool::new(false);
+
+tokio::task_local! {
+    static CORRELATION_ID: String;
+}
+
+/// Send the correlation ID to providers as an `X-Request-Id` header (off by default)
+pub fn set_send_request_id(send: bool) {
+    SEND_REQUEST_ID.store(send, Ordering::Relaxed);
+}
+
+/// A new random correlation ID: 16 lowercase hex digits
+pub fn new_correlation_id() -> String {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u128(
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos(),
+    );
+    format!("{:016x}", hasher.finish())
+}
+
+/// The correlation ID of the search running on this task, if any
+pub fn current_correlation_id() -> Option<String> {
+    CORRELATION_ID.try_with(String::clone).ok()
+}
+
+/// Run `search` under the correlation ID `id`
+///
+/// Without an explicit ID, a search nested in another (a multi-provider search's provider
+/// calls) keeps the outer search's ID, and any other search gets a new one.
+pub(crate) async fn scope<F: Future>(id: Option<String>, search: F) -> F::Output {
+    let id = id
+        .or_else(current_correlation_id)
+        .unwrap_or_else(new_correlation_id);
+    let span = tracing::info_span!("websearch.search", correlation_id = %id);
+    CORRELATION_ID.scope(id, search.instrument(span)).await
+}
+
+/// Add the `X-Request-Id` header to `request` if enabled and a search is running
+pub(crate) fn with_request_id(request: RequestBuilder) -> RequestBuilder {
+    match current_correlation_id() {
+        Some(id) if SEND_REQUEST_ID.load(Ordering::Relaxed) => {
+            request.header(REQUEST_ID_HEADER, id)
+        }
+        _ => request,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::http::HttpClient;
+    use wiremock::{
+        matchers::{header, method},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    #[tokio::test]
+    async fn test_nested_searches_keep_the_outer_id() {
+        assert_eq!(current_correlation_id(), None);
+
+        let (outer, inner) = scope(Some("outer".to_string()), async {
+            let inner = scope(None, async { current_correlation_id() }).await;
+            (current_correlation_id(), inner)
+        })
+        .await;
+        assert_eq!(outer.as_deref(), Some("outer"));
+        assert_eq!(inner.as_deref(), Some("outer"));
+
+        let generated = scope(None, async { current_correlation_id() })
+            .await
+            .unwrap();
+        assert_eq!(generated.len(), 16);
+        assert_ne!(generated, new_correlation_id());
+    }
+
+    #[tokio::test]
+    async fn test_request_id_header() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(header(REQUEST_ID_HEADER, "search-42"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("ok"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        set_send_request_id(true);
+        let url = format!("{}/request-id", server.uri());
+        let body = scope(
+            Some("search-42".to_string()),
+            HttpClient::new().get_text(&url),
+        )
+        .await;
+        set_send_request_id(false);
+        assert_eq!(body.unwrap(), "ok");
+    }
+}
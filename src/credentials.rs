@@ -0,0 +1,241 @@
+//! Provider secrets from the environment, files and the OS keychain
+//!
+//! [`Credential`] looks a secret up in order:
+//!
+//! 1. an explicit value passed in code or on the command line
+//! 2. the environment variable, e.g. `BRAVE_API_KEY`
+//! 3. the file named by the same variable with a `_FILE` suffix, e.g.
+//!    `BRAVE_API_KEY_FILE=/run/secrets/brave` for Docker and Kubernetes secrets
+//! 4. with the `keychain` feature, the OS keychain entry for the variable name under the
+//!    [`KEYCHAIN_SERVICE`] service
+//!
+//! Empty values count as unset, and a trailing newline is stripped from secret files.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use websearch::{credentials::Credential, providers::BraveProvider};
+//!
+//! let api_key = Credential::new("BRAVE_API_KEY").require()?;
+//! let brave = BraveProvider::new(&api_key)?;
+//! # Ok::<(), websearch::SearchError>(())
+//! ```
+
+use crate::error::{SearchError, SearchResult};
+use std::path::PathBuf;
+
+/// Keychain service the secrets are stored under by default
+pub const KEYCHAIN_SERVICE: &str = "websearch";
+
+/// Where a resolved secret came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CredentialSource {
+    /// Passed with [`Credential::with_value`]
+    Explicit,
+    /// The environment variable of that name
+    Env(String),
+    /// The file named by the `*_FILE` variable
+    File(PathBuf),
+    /// The OS keychain
+    Keychain,
+}
+
+/// A secret resolved by [`Credential::resolve`]
+#[derive(Clone, PartialEq, Eq)]
+pub struct Secret {
+    pub value: String,
+    pub source: CredentialSource,
+}
+
+impl std::fmt::Debug for Secret {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Secret")
+            .field("value", &"<redacted>")
+            .field("source", &self.source)
+            .finish()
+    }
+}
+
+/// Lookup of one named secret, such as `TAVILY_API_KEY`
+#[derive(Debug, Clone)]
+pub struct Credential {
+    name: String,
+    explicit: Option<String>,
+    keychain_service: String,
+}
+
+impl Credential {
+    /// Look up the secret named `name`, which is also its environment variable
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            explicit: None,
+            keychain_service: KEYCHAIN_SERVICE.to_string(),
+        }
+    }
+
+    /// Use `value` ahead of every other source, if it is set
+    pub fn with_value(mut self, value: Option<impl Into<String>>) -> Self {
+        self.explicit = value.map(Into::into);
+        self
+    }
+
+    /// Look in the keychain under `service` (default [`KEYCHAIN_SERVICE`])
+    pub fn with_keychain_service(mut self, service: impl Into<String>) -> Self {
+        self.keychain_service = service.into();
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The secret from the first source that has it, or `None` if none does
+    ///
+    /// Fails if the `*_FILE` variable names a file that can't be read.
+    pub fn resolve(&self) -> SearchResult<Option<Secret>> {
+        if let Some(value) = self.explicit.clone().filter(|value| !value.is_empty()) {
+            return Ok(Some(Secret {
+                value,
+                source: CredentialSource::Explicit,
+            }));
+        }
+
+        if let Some(value) = non_empty_var(&self.name) {
+            return Ok(Some(Secret {
+                value,
+                source: CredentialSource::Env(self.name.clone()),
+            }));
+        }
+
+        let file_var = format!("{}_FILE", self.name);
+        if let Some(path) = non_empty_var(&file_var).map(PathBuf::from) {
+            let contents = std::fs::read_to_string(&path).map_err(|error| {
+                SearchError::ConfigError(format!(
+                    "Failed to read {file_var} ({}): {error}",
+                    path.display()
+                ))
+            })?;
+            let value = contents.trim_end_matches(['\n', '\r']).to_string();
+            if !value.is_empty() {
+                return Ok(Some(Secret {
+                    value,
+                    source: CredentialSource::File(path),
+                }));
+            }
+        }
+
+        Ok(self.keychain_value().map(|value| Secret {
+            value,
+            source: CredentialSource::Keychain,
+        }))
+    }
+
+    /// The secret's value, failing with a [`SearchError::ConfigError`] if no source has it
+    pub fn require(&self) -> SearchResult<String> {
+        self.resolve()?.map(|secret| secret.value).ok_or_else(|| {
+            SearchError::ConfigError(format!(
+                "{name} is not set; set the {name} environment variable or {name}_FILE",
+                name = self.name
+            ))
+        })
+    }
+
+    /// Whether any source has the secret, treating unreadable files as missing
+    pub fn is_available(&self) -> bool {
+        matches!(self.resolve(), Ok(Some(_)))
+    }
+
+    #[cfg(feature = "keychain")]
+    fn keychain_value(&self) -> Option<String> {
+        let entry = keyring::Entry::new(&self.keychain_service, &self.name);
+        match entry.and_then(|entry| entry.get_password()) {
+            Ok(value) => Some(value).filter(|value| !value.is_empty()),
+            Err(keyring::Error::NoEntry) => None,
+            Err(error) => {
+                log::debug!(
+                    "Keychain lookup of {} in {} failed: {error}",
+                    self.name,
+                    self.keychain_service
+                );
+                None
+            }
+        }
+    }
+
+    #[cfg(not(feature = "keychain"))]
+    fn keychain_value(&self) -> Option<String> {
+        None
+    }
+}
+
+/// Shorthand for `Credential::new(name).resolve()`, returning only the value
+pub fn resolve(name: &str) -> SearchResult<Option<String>> {
+    Ok(Credential::new(name).resolve()?.map(|secret| secret.value))
+}
+
+fn non_empty_var(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|value| !value.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+
+    const NAME: &str = "WEBSEARCH_TEST_CREDENTIAL";
+
+    fn clear() {
+        std::env::remove_var(NAME);
+        std::env::remove_var(format!("{NAME}_FILE"));
+    }
+
+    #[test]
+    #[serial]
+    fn test_explicit_value_wins() {
+        clear();
+        std::env::set_var(NAME, "from-env");
+
+        let secret = Credential::new(NAME)
+            .with_value(Some("explicit"))
+            .resolve()
+            .unwrap()
+            .unwrap();
+        assert_eq!(secret.value, "explicit");
+        assert_eq!(secret.source, CredentialSource::Explicit);
+        clear();
+    }
+
+    #[test]
+    #[serial]
+    fn test_env_before_file() {
+        clear();
+        let file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), "from-file\n").unwrap();
+        std::env::set_var(format!("{NAME}_FILE"), file.path());
+
+        let secret = Credential::new(NAME).resolve().unwrap().unwrap();
+        assert_eq!(secret.value, "from-file");
+        assert_eq!(secret.source, CredentialSource::File(file.path().to_path_buf()));
+
+        std::env::set_var(NAME, "from-env");
+        assert_eq!(resolve(NAME).unwrap().as_deref(), Some("from-env"));
+        clear();
+    }
+
+    #[test]
+    #[serial]
+    fn test_missing_secret() {
+        clear();
+        std::env::set_var(NAME, "");
+        assert!(!Credential::new(NAME).is_available());
+        assert!(matches!(
+            Credential::new(NAME).require(),
+            Err(SearchError::ConfigError(_))
+        ));
+
+        std::env::set_var(format!("{NAME}_FILE"), "/nonexistent/websearch-secret");
+        assert!(Credential::new(NAME).resolve().is_err());
+        clear();
+    }
+}
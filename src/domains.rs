@@ -0,0 +1,171 @@
+//! Site-restricted search
+//!
+//! `SearchOptions::include_domains` and `exclude_domains` mean the same thing with every
+//! provider, but providers apply them differently: Google and Exa take them as request
+//! parameters, DuckDuckGo and Brave understand `site:` query operators, and the rest can't
+//! filter at all. Providers declare native support with
+//! [`SearchProvider::supports_domain_filter`]; for the others, results are filtered
+//! client-side after the search.
+//!
+//! [`web_search_site`] is the shortcut for the common "search only this site" case.
+
+use crate::{
+    error::SearchResult as Result,
+    types::{SearchOptions, SearchProvider, SearchResult},
+    web_search,
+};
+use url::Url;
+
+/// Search only pages on `domain` (including its subdomains)
+///
+/// Any domains already in `options.include_domains` are replaced.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use websearch::{domains::web_search_site, providers::DuckDuckGoProvider, SearchOptions};
+///
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let results = web_search_site(
+///     "docs.rs",
+///     SearchOptions {
+///         query: "serde derive".to_string(),
+///         provider: Box::new(DuckDuckGoProvider::new()),
+///         ..Default::default()
+///     },
+/// )
+/// .await?;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn web_search_site(
+    domain: impl Into<String>,
+    mut options: SearchOptions,
+) -> Result<Vec<SearchResult>> {
+    options.include_domains = Some(vec![domain.into()]);
+    web_search(options).await
+}
+
+/// Append `site:` operators for the domain filters to `query`
+///
+/// Several included domains are OR-ed together: `rust (site:a.com OR site:b.com) -site:c.com`.
+pub fn with_site_operators(query: &str, options: &SearchOptions) -> String {
+    let mut query = query.to_string();
+    match options.include_domains.as_deref().unwrap_or_default() {
+        [] => {}
+        [domain] => query = format!("{query} site:{domain}"),
+        domains => {
+            let sites: Vec<String> = domains.iter().map(|d| format!("site:{d}")).collect();
+            query = format!("{query} ({})", sites.join(" OR "));
+        }
+    }
+    for domain in options.exclude_domains.iter().flatten() {
+        query = format!("{query} -site:{domain}");
+    }
+    query
+}
+
+/// Whether `host` is `domain` or one of its subdomains, ignoring case
+pub fn matches_domain(host: &str, domain: &str) -> bool {
+    let host = host.trim_end_matches('.').to_lowercase();
+    let domain = domain
+        .trim()
+        .trim_start_matches("*.")
+        .trim_end_matches('.')
+        .to_lowercase();
+    host == domain
+        || host
+            .strip_suffix(&domain)
+            .is_some_and(|prefix| prefix.ends_with('.'))
+}
+
+/// Keep results on one of `include` (when non-empty) and on none of `exclude`
+pub fn retain_domains(results: &mut Vec<SearchResult>, include: &[String], exclude: &[String]) {
+    results.retain(|result| {
+        let Some(host) = Url::parse(&result.url)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_string))
+        else {
+            return include.is_empty();
+        };
+        (include.is_empty() || include.iter().any(|d| matches_domain(&host, d)))
+            && !exclude.iter().any(|d| matches_domain(&host, d))
+    });
+}
+
+/// Apply the domain filters client-side when the provider doesn't apply them itself
+pub(crate) fn enforce(
+    provider: &dyn SearchProvider,
+    options: &SearchOptions,
+    results: &mut Vec<SearchResult>,
+) {
+    if provider.supports_domain_filter() {
+        return;
+    }
+    let include = options.include_domains.as_deref().unwrap_or_default();
+    let exclude = options.exclude_domains.as_deref().unwrap_or_default();
+    if !include.is_empty() || !exclude.is_empty() {
+        retain_domains(results, include, exclude);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{sample_results, MockProvider};
+
+    fn domains(domains: &[&str]) -> Option<Vec<String>> {
+        Some(domains.iter().map(|d| d.to_string()).collect())
+    }
+
+    #[test]
+    fn test_with_site_operators() {
+        let options = SearchOptions {
+            include_domains: domains(&["docs.rs", "tokio.rs"]),
+            exclude_domains: domains(&["reddit.com"]),
+            ..Default::default()
+        };
+        assert_eq!(
+            with_site_operators("async", &options),
+            "async (site:docs.rs OR site:tokio.rs) -site:reddit.com"
+        );
+
+        let options = SearchOptions {
+            include_domains: domains(&["docs.rs"]),
+            ..Default::default()
+        };
+        assert_eq!(with_site_operators("serde", &options), "serde site:docs.rs");
+    }
+
+    #[test]
+    fn test_matches_domain() {
+        assert!(matches_domain("docs.rs", "docs.rs"));
+        assert!(matches_domain("www.Docs.rs", "docs.rs"));
+        assert!(matches_domain("blog.rust-lang.org", "*.rust-lang.org"));
+        assert!(!matches_domain("notdocs.rs", "docs.rs"));
+    }
+
+    #[tokio::test]
+    async fn test_web_search_site_filters_client_side() {
+        let mut results = sample_results("alpha", 2);
+        results.extend(sample_results("beta", 2));
+        let provider = MockProvider::new("mock").with_results(results);
+
+        let results = web_search_site(
+            "beta.com",
+            SearchOptions {
+                query: "rust".to_string(),
+                provider: Box::new(provider),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(results.len(), 2);
+        assert!(results
+            .iter()
+            .all(|r| r.url.starts_with("https://beta.com/")));
+    }
+}
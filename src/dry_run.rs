@@ -0,0 +1,139 @@
+//! Dry runs: see the requests a search would send without sending them
+//!
+//! With [`SearchOptions::dry_run`](crate::SearchOptions::dry_run) set, the provider builds
+//! its request as usual but it is captured instead of sent, and
+//! [`web_search_response`](crate::web_search_response) returns it in
+//! [`SearchResponse::planned_requests`](crate::SearchResponse::planned_requests) with no
+//! results. This shows exactly how options map to each provider's parameters. Every
+//! request is captured, including supporting ones such as a scraper's `robots.txt` check.
+//!
+//! API keys and tokens are redacted from the URL and body as in [cassettes](crate::cassette),
+//! and from headers such as `Authorization` and `X-Subscription-Token`.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use websearch::{providers::GoogleProvider, web_search_response, SearchOptions};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let response = web_search_response(SearchOptions {
+//!     query: "rust".to_string(),
+//!     include_domains: Some(vec!["docs.rs".to_string()]),
+//!     dry_run: true,
+//!     provider: Box::new(GoogleProvider::new("api-key", "cx")?),
+//!     ..Default::default()
+//! })
+//! .await?;
+//!
+//! let request = &response.planned_requests[0];
+//! assert!(request.url.contains("siteSearch=docs.rs"));
+//! assert!(request.url.contains("key=REDACTED"));
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{
+    cassette,
+    error::{SearchError, SearchResult as Result},
+};
+use reqwest::{RequestBuilder, Response};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+/// Headers whose values are replaced in a planned request, besides any whose name
+/// contains `token`, `key` or `secret`
+const REDACTED_HEADERS: [&str; 3] = ["authorization", "cookie", "proxy-authorization"];
+
+tokio::task_local! {
+    static PLAN: Arc<Mutex<Vec<RequestPlan>>>;
+}
+
+/// A request a provider would have sent, with secrets redacted
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RequestPlan {
+    pub method: String,
+    pub url: String,
+    pub headers: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+}
+
+/// Run `search` capturing its requests instead of sending them
+pub(crate) async fn plan<F: Future>(search: F) -> (F::Output, Vec<RequestPlan>) {
+    let plan = Arc::new(Mutex::new(Vec::new()));
+    let output = PLAN.scope(plan.clone(), search).await;
+    let requests = std::mem::take(&mut *plan.lock().unwrap());
+    (output, requests)
+}
+
+/// Whether requests are being captured rather than sent
+pub(crate) fn is_active() -> bool {
+    PLAN.try_with(|_| ()).is_ok()
+}
+
+/// Add `request` to the dry run's plan; the provider sees an error instead of a response
+pub(crate) fn capture(request: RequestBuilder) -> Result<Response> {
+    let request = request.build()?;
+    let headers = request
+        .headers()
+        .iter()
+        .map(|(name, value)| {
+            let value = if is_secret_header(name.as_str()) {
+                "REDACTED".to_string()
+            } else {
+                String::from_utf8_lossy(value.as_bytes()).into_owned()
+            };
+            (name.to_string(), value)
+        })
+        .collect();
+    let plan = RequestPlan {
+        method: request.method().to_string(),
+        url: cassette::canonical_url(request.url()),
+        headers,
+        body: request
+            .body()
+            .and_then(|body| body.as_bytes())
+            .map(|bytes| cassette::canonical_body(&String::from_utf8_lossy(bytes))),
+    };
+    PLAN.with(|requests| requests.lock().unwrap().push(plan));
+    Err(SearchError::Other("Dry run: request not sent".to_string()))
+}
+
+fn is_secret_header(name: &str) -> bool {
+    let name = name.to_lowercase();
+    REDACTED_HEADERS.contains(&name.as_str())
+        || ["token", "key", "secret"]
+            .iter()
+            .any(|secret| name.contains(secret))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{providers::BraveProvider, web_search_response, SearchOptions};
+
+    #[tokio::test]
+    async fn test_dry_run_redacts_secrets() {
+        let response = web_search_response(SearchOptions {
+            query: "rust".to_string(),
+            max_results: Some(5),
+            dry_run: true,
+            provider: Box::new(BraveProvider::new("brave-secret").unwrap()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+        assert!(response.results.is_empty());
+        let [request] = response.planned_requests.as_slice() else {
+            panic!("expected one request, got {:?}", response.planned_requests);
+        };
+        assert_eq!(request.method, "GET");
+        assert!(request.url.contains("count=5"));
+        assert!(request.url.contains("q=rust"));
+        assert_eq!(request.headers["x-subscription-token"], "REDACTED");
+        assert_eq!(request.headers["accept"], "application/json");
+    }
+}
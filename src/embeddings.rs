@@ -0,0 +1,316 @@
+//! Embeddings for search results
+//!
+//! [`embed_results`] computes a vector for each result's title and snippet through an
+//! [`EmbeddingBackend`] and stores it in [`SearchResult::embedding`], ready for
+//! vector-store ingestion. [`SimilarityDedup`] then drops near-duplicate results that
+//! URL-based deduplication misses, such as the same article syndicated on several sites.
+//!
+//! Two backends are included: [`OpenAiEmbeddings`] for any OpenAI-compatible
+//! `/embeddings` endpoint, and `LocalEmbeddings` (feature `local-embeddings`), which runs
+//! an ONNX model on the local machine.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use websearch::embeddings::{embed_results, OpenAiEmbeddings, SimilarityDedup};
+//! use websearch::processors::ResultProcessor;
+//! # async fn run(mut results: Vec<websearch::SearchResult>) -> websearch::Result<()> {
+//!
+//! let backend = OpenAiEmbeddings::new("sk-...", "text-embedding-3-small");
+//! embed_results(&backend, &mut results).await?;
+//! let results = SimilarityDedup::new(0.95).process(results);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{
+    cassette,
+    error::{SearchError, SearchResult as Result},
+    processors::ResultProcessor,
+    types::SearchResult,
+};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Computes embedding vectors for text
+#[async_trait::async_trait]
+pub trait EmbeddingBackend: Send + Sync + std::fmt::Debug {
+    /// One vector per input text, in the same order
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>>;
+}
+
+/// Embed every result that doesn't have an embedding yet
+///
+/// Each result is embedded from its title and snippet, in a single backend call.
+pub async fn embed_results(
+    backend: &dyn EmbeddingBackend,
+    results: &mut [SearchResult],
+) -> Result<()> {
+    let pending: Vec<usize> = (0..results.len())
+        .filter(|&i| results[i].embedding.is_none())
+        .collect();
+    if pending.is_empty() {
+        return Ok(());
+    }
+
+    let texts: Vec<String> = pending.iter().map(|&i| text_of(&results[i])).collect();
+    let embeddings = backend.embed(&texts).await?;
+    if embeddings.len() != texts.len() {
+        return Err(SearchError::ParseError(format!(
+            "Expected {} embeddings, got {}",
+            texts.len(),
+            embeddings.len()
+        )));
+    }
+
+    for (i, embedding) in pending.into_iter().zip(embeddings) {
+        results[i].embedding = Some(embedding);
+    }
+    Ok(())
+}
+
+/// Text embedded for a result
+fn text_of(result: &SearchResult) -> String {
+    match &result.snippet {
+        Some(snippet) => format!("{}\n{}", result.title, snippet),
+        None => result.title.clone(),
+    }
+}
+
+/// Cosine similarity of two vectors, in `[-1, 1]` (0 for empty or mismatched vectors)
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() || a.is_empty() {
+        return 0.0;
+    }
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        0.0
+    } else {
+        dot / (norm_a * norm_b)
+    }
+}
+
+/// Drop results whose embedding is at least `threshold` similar to an earlier result's
+///
+/// Results without an embedding are always kept; run [`embed_results`] first.
+#[derive(Debug, Clone, Copy)]
+pub struct SimilarityDedup {
+    threshold: f32,
+}
+
+impl SimilarityDedup {
+    /// `threshold` is a cosine similarity; 0.95 catches reworded copies of the same page
+    pub fn new(threshold: f32) -> Self {
+        Self { threshold }
+    }
+}
+
+impl ResultProcessor for SimilarityDedup {
+    fn process(&self, results: Vec<SearchResult>) -> Vec<SearchResult> {
+        let mut kept: Vec<SearchResult> = Vec::with_capacity(results.len());
+        for result in results {
+            let duplicate = result.embedding.as_deref().is_some_and(|embedding| {
+                kept.iter()
+                    .filter_map(|earlier| earlier.embedding.as_deref())
+                    .any(|earlier| cosine_similarity(embedding, earlier) >= self.threshold)
+            });
+            if !duplicate {
+                kept.push(result);
+            }
+        }
+        kept
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct EmbeddingRequest<'a> {
+    model: &'a str,
+    input: &'a [String],
+}
+
+#[derive(Debug, Deserialize)]
+struct EmbeddingResponse {
+    data: Vec<EmbeddingData>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EmbeddingData {
+    index: usize,
+    embedding: Vec<f32>,
+}
+
+/// Backend for OpenAI's embeddings API and compatible servers (Azure OpenAI, Ollama,
+/// vLLM, LiteLLM, ...)
+#[derive(Debug, Clone)]
+pub struct OpenAiEmbeddings {
+    api_key: String,
+    model: String,
+    base_url: String,
+    timeout: Duration,
+    client: reqwest::Client,
+}
+
+impl OpenAiEmbeddings {
+    pub fn new(api_key: impl Into<String>, model: impl Into<String>) -> Self {
+        Self {
+            api_key: api_key.into(),
+            model: model.into(),
+            base_url: "https://api.openai.com/v1".to_string(),
+            timeout: Duration::from_secs(30),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Use a compatible server; `/embeddings` is appended to `base_url`
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+}
+
+#[async_trait::async_trait]
+impl EmbeddingBackend for OpenAiEmbeddings {
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let request = self
+            .client
+            .post(format!("{}/embeddings", self.base_url))
+            .timeout(self.timeout)
+            .bearer_auth(&self.api_key)
+            .json(&EmbeddingRequest {
+                model: &self.model,
+                input: texts,
+            });
+        let response = cassette::send(request).await?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(SearchError::HttpError {
+                message: format!("Embedding request failed with status: {status}"),
+                status_code: Some(status.as_u16()),
+                response_body: response.text().await.ok(),
+            });
+        }
+
+        let mut response: EmbeddingResponse = response
+            .json()
+            .await
+            .map_err(|e| SearchError::ParseError(format!("Invalid embedding response: {e}")))?;
+        response.data.sort_by_key(|data| data.index);
+        Ok(response
+            .data
+            .into_iter()
+            .map(|data| data.embedding)
+            .collect())
+    }
+}
+
+/// Backend running a local ONNX embedding model through `fastembed`
+///
+/// ONNX Runtime is loaded at run time from the system library path or `ORT_DYLIB_PATH`.
+/// The model files are downloaded from Hugging Face on first use and cached.
+#[cfg(feature = "local-embeddings")]
+pub struct LocalEmbeddings {
+    model: std::sync::Arc<std::sync::Mutex<fastembed::TextEmbedding>>,
+}
+
+#[cfg(feature = "local-embeddings")]
+impl LocalEmbeddings {
+    /// Load `model`, e.g. `fastembed::EmbeddingModel::AllMiniLML6V2`
+    pub fn new(model: fastembed::EmbeddingModel) -> Result<Self> {
+        let options = fastembed::TextInitOptions::new(model).with_show_download_progress(false);
+        let model = fastembed::TextEmbedding::try_new(options).map_err(|e| {
+            SearchError::ConfigError(format!("Failed to load embedding model: {e}"))
+        })?;
+        Ok(Self {
+            model: std::sync::Arc::new(std::sync::Mutex::new(model)),
+        })
+    }
+}
+
+#[cfg(feature = "local-embeddings")]
+impl std::fmt::Debug for LocalEmbeddings {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("LocalEmbeddings")
+    }
+}
+
+#[cfg(feature = "local-embeddings")]
+#[async_trait::async_trait]
+impl EmbeddingBackend for LocalEmbeddings {
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        let model = self.model.clone();
+        let texts = texts.to_vec();
+        // Inference is CPU-bound, so keep it off the async workers
+        tokio::task::spawn_blocking(move || model.lock().unwrap().embed(texts, None))
+            .await
+            .map_err(|e| SearchError::Other(format!("Embedding task failed: {e}")))?
+            .map_err(|e| SearchError::Other(format!("Embedding failed: {e}")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::sample_results;
+    use wiremock::{
+        matchers::{body_json, header, method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    #[tokio::test]
+    async fn test_embed_results_with_openai_backend() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/embeddings"))
+            .and(header("Authorization", "Bearer test-key"))
+            .and(body_json(serde_json::json!({
+                "model": "test-model",
+                "input": ["mock Result 2\nmock content 2"]
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": [{ "index": 0, "embedding": [0.6, 0.8] }]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let backend = OpenAiEmbeddings::new("test-key", "test-model")
+            .with_base_url(&format!("{}/v1/", server.uri()));
+        let mut results = sample_results("mock", 2);
+        results[0].embedding = Some(vec![1.0, 0.0]);
+        embed_results(&backend, &mut results).await.unwrap();
+
+        assert_eq!(results[0].embedding, Some(vec![1.0, 0.0]));
+        assert_eq!(results[1].embedding, Some(vec![0.6, 0.8]));
+    }
+
+    #[test]
+    fn test_similarity_dedup() {
+        let mut results = sample_results("mock", 4);
+        results[0].embedding = Some(vec![1.0, 0.0]);
+        results[1].embedding = Some(vec![0.99, 0.05]);
+        results[2].embedding = Some(vec![0.0, 1.0]);
+
+        let results = SimilarityDedup::new(0.95).process(results);
+        let urls: Vec<_> = results.iter().map(|r| r.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            [
+                "https://mock.com/1",
+                "https://mock.com/3",
+                "https://mock.com/4"
+            ]
+        );
+        assert_eq!(cosine_similarity(&[1.0, 0.0], &[0.0, 1.0]), 0.0);
+    }
+}
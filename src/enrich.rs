@@ -0,0 +1,273 @@
+//! Content enrichment: download result pages and attach their readable text
+//!
+//! Search providers only return titles and snippets. [`fetch_content`] visits each
+//! result URL and fills [`SearchResult::content`] with the page's main text (see [`crate::utils::extract`]), so
+//! retrieval-augmented pipelines don't need a separate crawler.
+
+use crate::{
+    error::SearchError,
+    types::{DebugOptions, SearchResult},
+    utils::{
+        debug,
+        extract::extract_text,
+        http::{normalize_text, HttpClient},
+        robots::RobotsChecker,
+    },
+};
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::sync::Semaphore;
+
+/// Options controlling how result pages are fetched
+#[derive(Debug, Clone)]
+pub struct EnrichOptions {
+    /// Maximum number of pages fetched at the same time
+    pub concurrency: usize,
+    /// Timeout for each page request
+    pub timeout: Duration,
+    /// Maximum number of body bytes read per page
+    pub max_body_bytes: usize,
+    /// Truncate extracted text to this many characters
+    pub max_content_chars: Option<usize>,
+    /// Leave results that already carry content untouched
+    pub skip_existing: bool,
+    /// Skip pages whose robots.txt disallows fetching them
+    pub respect_robots_txt: bool,
+    /// Debug options
+    pub debug: Option<DebugOptions>,
+}
+
+impl Default for EnrichOptions {
+    fn default() -> Self {
+        Self {
+            concurrency: 4,
+            timeout: Duration::from_secs(10),
+            max_body_bytes: 2 * 1024 * 1024,
+            max_content_chars: None,
+            skip_existing: true,
+            respect_robots_txt: true,
+            debug: None,
+        }
+    }
+}
+
+/// A result page that could not be fetched or extracted
+#[derive(Debug, Clone)]
+pub struct EnrichFailure {
+    /// URL of the result that failed
+    pub url: String,
+    /// Why the page could not be enriched
+    pub error: SearchError,
+}
+
+/// Download each result URL and populate [`SearchResult::content`] with its readable text
+///
+/// Pages are fetched with bounded concurrency. Failures never abort the whole run: the
+/// affected results keep `content: None` and are reported in the returned list. Pages
+/// disallowed by their site's robots.txt are reported as
+/// [`SearchError::RobotsDisallowed`] unless [`EnrichOptions::respect_robots_txt`] is off.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use websearch::enrich::{fetch_content, EnrichOptions};
+/// # async fn run(mut results: Vec<websearch::SearchResult>) {
+/// let failures = fetch_content(&mut results, &EnrichOptions::default()).await;
+/// for failure in failures {
+///     eprintln!("could not fetch {}: {}", failure.url, failure.error);
+/// }
+/// # }
+/// ```
+pub async fn fetch_content(
+    results: &mut [SearchResult],
+    options: &EnrichOptions,
+) -> Vec<EnrichFailure> {
+    let client = HttpClient::with_request_timeout(options.timeout);
+    let semaphore = Semaphore::new(options.concurrency.max(1));
+
+    let pending: Vec<(usize, String)> = results
+        .iter()
+        .enumerate()
+        .filter(|(_, result)| !(options.skip_existing && result.content.is_some()))
+        .map(|(index, result)| (index, result.url.clone()))
+        .collect();
+
+    debug::log(
+        &options.debug,
+        "Fetching result content",
+        &format!("{} pages", pending.len()),
+    );
+
+    let fetches = pending.into_iter().map(|(index, url)| {
+        let client = &client;
+        let semaphore = &semaphore;
+        async move {
+            let _permit = semaphore
+                .acquire()
+                .await
+                .expect("enrichment semaphore is never closed");
+            let content = fetch_page_text(client, &url, options).await;
+            (index, url, content)
+        }
+    });
+
+    let mut failures = Vec::new();
+    for (index, url, content) in futures::future::join_all(fetches).await {
+        match content {
+            Ok(text) => results[index].content = Some(text),
+            Err(error) => {
+                debug::log(
+                    &options.debug,
+                    &format!("Failed to fetch content for {url}"),
+                    &error.to_string(),
+                );
+                failures.push(EnrichFailure { url, error });
+            }
+        }
+    }
+
+    failures
+}
+
+async fn fetch_page_text(
+    client: &HttpClient,
+    url: &str,
+    options: &EnrichOptions,
+) -> Result<String, SearchError> {
+    if options.respect_robots_txt {
+        RobotsChecker::shared().check(url).await?;
+    }
+
+    let mut headers = HashMap::new();
+    headers.insert(
+        "Accept".to_string(),
+        "text/html,application/xhtml+xml,text/plain;q=0.9".to_string(),
+    );
+
+    let response = client
+        .get_text_limited(url, headers, options.max_body_bytes)
+        .await?;
+
+    let content_type = response.content_type.as_deref().unwrap_or("text/html");
+    let text = if content_type.contains("html") {
+        extract_text(&response.body)
+    } else if content_type.starts_with("text/") {
+        normalize_text(&response.body)
+    } else {
+        return Err(SearchError::ParseError(format!(
+            "Unsupported content type for extraction: {content_type}"
+        )));
+    };
+
+    Ok(match options.max_content_chars {
+        Some(limit) => text.chars().take(limit).collect(),
+        None => text,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::{
+        matchers::{method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    fn result_for(url: String) -> SearchResult {
+        SearchResult {
+            title: "Result".to_string(),
+            url,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fetch_content_populates_and_reports_failures() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/article"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(
+                "<html><body><p>Readable body text</p></body></html>",
+                "text/html; charset=utf-8",
+            ))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/missing"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+
+        let mut results = vec![
+            result_for(format!("{}/article", server.uri())),
+            result_for(format!("{}/missing", server.uri())),
+        ];
+
+        let failures = fetch_content(&mut results, &EnrichOptions::default()).await;
+
+        assert_eq!(results[0].content.as_deref(), Some("Readable body text"));
+        assert!(results[1].content.is_none());
+        assert_eq!(failures.len(), 1);
+        assert!(matches!(
+            failures[0].error,
+            SearchError::HttpError {
+                status_code: Some(404),
+                ..
+            }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_content_respects_robots_txt() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/robots.txt"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_string("User-agent: *\nDisallow: /private"),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/private"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw("secret", "text/plain"))
+            .mount(&server)
+            .await;
+
+        // Address the server as `localhost` so the shared robots.txt cache can't hold an
+        // entry for this origin from another test's mock server on a reused port
+        let url = format!("http://localhost:{}/private", server.address().port());
+        let mut results = vec![result_for(url.clone())];
+
+        let failures = fetch_content(&mut results, &EnrichOptions::default()).await;
+        assert!(results[0].content.is_none());
+        assert!(matches!(
+            failures[0].error,
+            SearchError::RobotsDisallowed { .. }
+        ));
+
+        let options = EnrichOptions {
+            respect_robots_txt: false,
+            ..Default::default()
+        };
+        fetch_content(&mut results, &options).await;
+        assert_eq!(results[0].content.as_deref(), Some("secret"));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_content_truncates_text() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw("abcdefghij", "text/plain"))
+            .mount(&server)
+            .await;
+
+        let mut results = vec![result_for(format!("{}/plain", server.uri()))];
+        let options = EnrichOptions {
+            max_content_chars: Some(4),
+            ..Default::default()
+        };
+
+        fetch_content(&mut results, &options).await;
+        assert_eq!(results[0].content.as_deref(), Some("abcd"));
+    }
+}
@@ -5,6 +5,21 @@ use thiserror::Error;
 /// Result type alias for search operations
 pub type SearchResult<T> = std::result::Result<T, SearchError>;
 
+/// What a failed search says about trying again, from [`SearchError::class`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorClass {
+    /// The search itself is invalid and would fail with any provider
+    InvalidRequest,
+    /// The provider's credentials or configuration were rejected; it will keep failing
+    /// until that is fixed
+    Misconfigured,
+    /// The provider can't serve this search (e.g. an unsupported language or region), but
+    /// another provider may
+    Unsupported,
+    /// A failure another provider, or the same one later, may not hit
+    Transient,
+}
+
 /// Comprehensive error types for search operations
 #[derive(Error, Debug, Clone)]
 pub enum SearchError {
@@ -20,6 +35,11 @@ pub enum SearchError {
     #[error("Invalid input: {0}")]
     InvalidInput(String),
 
+    /// A valid search option this provider doesn't support, such as a language it can't
+    /// search in
+    #[error("Not supported by this provider: {0}")]
+    Unsupported(String),
+
     /// Provider-specific error
     #[error("Provider error: {0}")]
     ProviderError(String),
@@ -44,9 +64,112 @@ pub enum SearchError {
     #[error("Authentication failed: {0}")]
     AuthenticationError(String),
 
+    /// The provider served an anti-bot challenge or CAPTCHA instead of results
+    #[error("{provider} blocked the request as automated traffic: {hint}")]
+    BotDetected { provider: String, hint: String },
+
+    /// The target site's robots.txt forbids fetching this URL
+    #[error("Fetching {url} is disallowed by robots.txt")]
+    RobotsDisallowed { url: String },
+
+    /// Querying the provider would exceed the configured search or daily budget
+    #[error("Skipped {provider}: a ${cost_usd} request would exceed the budget")]
+    BudgetExceeded { provider: String, cost_usd: f64 },
+
+    /// The provider's request queue already holds its maximum number of waiting searches
+    #[error("{provider} request queue is full ({max_depth} searches waiting)")]
+    QueueFull { provider: String, max_depth: usize },
+
+    /// The response body was larger than the search's `max_response_bytes`
+    #[error("Response exceeded the {limit_bytes}-byte size limit")]
+    ResponseTooLarge { limit_bytes: usize },
+
     /// Generic error for unhandled cases
     #[error("Search error: {0}")]
     Other(String),
+
+    /// A search through [`crate::web_search`] failed; `source` is the provider's own error
+    ///
+    /// The message leaves the source out, as error reporters print it after the message;
+    /// [`report`](SearchError::report) includes it.
+    #[error("Search with provider '{provider}' failed\n\nTroubleshooting: {troubleshooting}")]
+    Provider {
+        provider: String,
+        source: Box<SearchError>,
+        troubleshooting: String,
+    },
+}
+
+impl SearchError {
+    /// Classify the error for failover: whether another provider could succeed
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use websearch::{ErrorClass, SearchError};
+    ///
+    /// let error = SearchError::AuthenticationError("invalid API key".to_string());
+    /// assert_eq!(error.class(), ErrorClass::Misconfigured);
+    /// ```
+    pub fn class(&self) -> ErrorClass {
+        match self.root_cause() {
+            SearchError::InvalidInput(_) => ErrorClass::InvalidRequest,
+            SearchError::Unsupported(_) => ErrorClass::Unsupported,
+            SearchError::AuthenticationError(_)
+            | SearchError::ConfigError(_)
+            | SearchError::HttpError {
+                status_code: Some(401 | 403),
+                ..
+            } => ErrorClass::Misconfigured,
+            _ => ErrorClass::Transient,
+        }
+    }
+
+    /// The underlying error, looking through any [`SearchError::Provider`] wrappers
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use websearch::SearchError;
+    ///
+    /// let error = SearchError::Provider {
+    ///     provider: "brave".to_string(),
+    ///     source: Box::new(SearchError::RateLimit("slow down".to_string())),
+    ///     troubleshooting: String::new(),
+    /// };
+    /// assert!(matches!(error.root_cause(), SearchError::RateLimit(_)));
+    /// ```
+    pub fn root_cause(&self) -> &SearchError {
+        match self {
+            SearchError::Provider { source, .. } => source.root_cause(),
+            error => error,
+        }
+    }
+
+    /// The message followed by the error's sources, one `Caused by:` line each, for
+    /// showing to users where only one string fits
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use websearch::SearchError;
+    ///
+    /// let error = SearchError::Provider {
+    ///     provider: "brave".to_string(),
+    ///     source: Box::new(SearchError::RateLimit("slow down".to_string())),
+    ///     troubleshooting: "Wait a minute".to_string(),
+    /// };
+    /// assert!(error.report().ends_with("\nCaused by: Rate limit exceeded: slow down"));
+    /// ```
+    pub fn report(&self) -> String {
+        let mut report = self.to_string();
+        let mut source = std::error::Error::source(self);
+        while let Some(error) = source {
+            report.push_str(&format!("\nCaused by: {error}"));
+            source = error.source();
+        }
+        report
+    }
 }
 
 impl From<reqwest::Error> for SearchError {
@@ -0,0 +1,240 @@
+//! Export results for vector-store ingestion
+//!
+//! Unlike [`crate::format`], which renders results for people to read, these exporters
+//! produce one document per result, shaped the way ingestion pipelines expect: the text to
+//! embed (the page content when [`crate::enrich`] fetched it, otherwise title and snippet),
+//! a flat metadata object with `source` set to the URL, and the result's embedding when
+//! one was computed.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use websearch::{export::{export_results, ExportFormat}, SearchResult};
+//!
+//! let results = vec![SearchResult {
+//!     title: "Rust".to_string(),
+//!     url: "https://www.rust-lang.org/".to_string(),
+//!     snippet: Some("A language empowering everyone".to_string()),
+//!     ..Default::default()
+//! }];
+//! let documents = export_results(&results, ExportFormat::LangChain).unwrap();
+//! assert!(documents.contains("\"page_content\""));
+//! ```
+
+use crate::{
+    error::SearchResult,
+    format::{csv_row, to_jsonl},
+    types::SearchResult as SearchResultType,
+};
+use serde::Serialize;
+
+/// Ingestion format for [`export_results`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// One `{"id", "text", "metadata", "embedding"}` object per line
+    Jsonl,
+    /// JSON array of LangChain `Document`s (`page_content`, `metadata`)
+    LangChain,
+    /// CSV with the [`EXPORT_CSV_COLUMNS`] header; embeddings as JSON arrays
+    Csv,
+}
+
+/// Columns written by [`to_export_csv`]
+pub const EXPORT_CSV_COLUMNS: [&str; 8] = [
+    "id",
+    "text",
+    "source",
+    "title",
+    "domain",
+    "provider",
+    "published_date",
+    "embedding",
+];
+
+/// Metadata attached to every exported document
+#[derive(Debug, Serialize)]
+pub struct DocumentMetadata<'a> {
+    /// Result URL
+    pub source: &'a str,
+    pub title: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub domain: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub published_date: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<&'a str>,
+}
+
+impl<'a> From<&'a SearchResultType> for DocumentMetadata<'a> {
+    fn from(result: &'a SearchResultType) -> Self {
+        Self {
+            source: &result.url,
+            title: &result.title,
+            domain: result.domain.as_deref(),
+            provider: result.provider.as_deref(),
+            published_date: result.published_date.as_deref(),
+            language: result.language.as_deref(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct JsonlDocument<'a> {
+    id: &'a str,
+    text: String,
+    metadata: DocumentMetadata<'a>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    embedding: Option<&'a [f32]>,
+}
+
+#[derive(Serialize)]
+struct LangChainDocument<'a> {
+    page_content: String,
+    metadata: DocumentMetadata<'a>,
+    #[serde(rename = "type")]
+    kind: &'static str,
+}
+
+/// Export `results` in `format`
+pub fn export_results(results: &[SearchResultType], format: ExportFormat) -> SearchResult<String> {
+    match format {
+        ExportFormat::Jsonl => to_export_jsonl(results),
+        ExportFormat::LangChain => to_langchain_documents(results),
+        ExportFormat::Csv => Ok(to_export_csv(results)),
+    }
+}
+
+/// One JSON document per line, keyed by the result URL
+pub fn to_export_jsonl(results: &[SearchResultType]) -> SearchResult<String> {
+    let documents: Vec<JsonlDocument> = results
+        .iter()
+        .map(|result| JsonlDocument {
+            id: &result.url,
+            text: document_text(result),
+            metadata: result.into(),
+            embedding: result.embedding.as_deref(),
+        })
+        .collect();
+    to_jsonl(&documents)
+}
+
+/// A JSON array of LangChain `Document`s, loadable with
+/// `[Document(**d) for d in json.load(f)]`
+pub fn to_langchain_documents(results: &[SearchResultType]) -> SearchResult<String> {
+    let documents: Vec<LangChainDocument> = results
+        .iter()
+        .map(|result| LangChainDocument {
+            page_content: document_text(result),
+            metadata: result.into(),
+            kind: "Document",
+        })
+        .collect();
+    Ok(serde_json::to_string_pretty(&documents)?)
+}
+
+/// CSV with the [`EXPORT_CSV_COLUMNS`] header
+pub fn to_export_csv(results: &[SearchResultType]) -> String {
+    let mut output = csv_row(&EXPORT_CSV_COLUMNS);
+    for result in results {
+        let embedding = result
+            .embedding
+            .as_ref()
+            .map(|embedding| serde_json::to_string(embedding).unwrap_or_default())
+            .unwrap_or_default();
+        output.push_str(&csv_row(&[
+            result.url.as_str(),
+            document_text(result).as_str(),
+            result.url.as_str(),
+            result.title.as_str(),
+            result.domain.as_deref().unwrap_or_default(),
+            result.provider.as_deref().unwrap_or_default(),
+            result.published_date.as_deref().unwrap_or_default(),
+            embedding.as_str(),
+        ]));
+    }
+    output
+}
+
+/// Text to index for a result: the page content if fetched, else title and snippet
+pub fn document_text(result: &SearchResultType) -> String {
+    if let Some(content) = result.content.as_deref().filter(|c| !c.is_empty()) {
+        return content.to_string();
+    }
+    match result.snippet.as_deref().filter(|s| !s.is_empty()) {
+        Some(snippet) => format!("{}\n\n{}", result.title, snippet),
+        None => result.title.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Vec<SearchResultType> {
+        vec![
+            SearchResultType {
+                title: "Rust".to_string(),
+                url: "https://www.rust-lang.org/".to_string(),
+                snippet: Some("Fast, reliable".to_string()),
+                provider: Some("duckduckgo".into()),
+                embedding: Some(vec![0.5, 0.25]),
+                ..Default::default()
+            },
+            SearchResultType {
+                title: "The Book".to_string(),
+                url: "https://doc.rust-lang.org/book/".to_string(),
+                content: Some("Chapter 1".to_string()),
+                ..Default::default()
+            },
+        ]
+    }
+
+    #[test]
+    fn test_jsonl_documents() {
+        let jsonl = to_export_jsonl(&sample()).unwrap();
+        let lines: Vec<serde_json::Value> = jsonl
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(
+            lines[0],
+            serde_json::json!({
+                "id": "https://www.rust-lang.org/",
+                "text": "Rust\n\nFast, reliable",
+                "metadata": {
+                    "source": "https://www.rust-lang.org/",
+                    "title": "Rust",
+                    "provider": "duckduckgo"
+                },
+                "embedding": [0.5, 0.25]
+            })
+        );
+        assert_eq!(lines[1]["text"], "Chapter 1");
+        assert!(lines[1].get("embedding").is_none());
+    }
+
+    #[test]
+    fn test_langchain_documents() {
+        let json = to_langchain_documents(&sample()).unwrap();
+        let documents: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(documents[1]["page_content"], "Chapter 1");
+        assert_eq!(documents[1]["type"], "Document");
+        assert_eq!(
+            documents[1]["metadata"]["source"],
+            "https://doc.rust-lang.org/book/"
+        );
+    }
+
+    #[test]
+    fn test_export_csv() {
+        let csv = to_export_csv(&sample());
+        let lines: Vec<_> = csv.split("\r\n").collect();
+        assert_eq!(
+            lines[0],
+            "id,text,source,title,domain,provider,published_date,embedding"
+        );
+        assert!(lines[1].ends_with(",duckduckgo,,\"[0.5,0.25]\""));
+    }
+}
@@ -0,0 +1,415 @@
+//! Text output formats for search results
+//!
+//! Shared by the CLI's `--format` flag and usable directly from library code, e.g. to
+//! export results into a spreadsheet, feed them line by line into another tool, or
+//! publish them as an RSS/Atom feed for feed readers, or cite papers with BibTeX.
+
+use crate::{
+    error::SearchResult, scholarly::ScholarlyResult, types::SearchResult as SearchResultType,
+};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Serialize;
+use std::collections::HashSet;
+
+/// Output format for a list of search results
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResultFormat {
+    /// Pretty-printed JSON array
+    Json,
+    /// One compact JSON object per line
+    Jsonl,
+    /// RFC 4180 CSV with a header row
+    Csv,
+    /// Numbered Markdown list of links with snippets
+    Markdown,
+    /// RSS 2.0 feed, one item per result
+    Rss,
+    /// Atom feed, one entry per result
+    Atom,
+    /// BibTeX bibliography, one entry per result
+    Bibtex,
+}
+
+/// Feed title used by [`format_results`]
+pub const DEFAULT_FEED_TITLE: &str = "Search results";
+
+/// Channel link of the feeds written by [`to_rss`]
+pub const FEED_LINK: &str = env!("CARGO_PKG_HOMEPAGE");
+
+/// Columns written by [`to_csv`]
+pub const CSV_COLUMNS: [&str; 6] = [
+    "title",
+    "url",
+    "domain",
+    "snippet",
+    "published_date",
+    "provider",
+];
+
+/// Render `results` in `format`
+///
+/// # Examples
+///
+/// ```rust
+/// use websearch::{format::{format_results, ResultFormat}, SearchResult};
+///
+/// let results = vec![SearchResult {
+///     title: "Rust".to_string(),
+///     url: "https://www.rust-lang.org/".to_string(),
+///     ..Default::default()
+/// }];
+/// let csv = format_results(&results, ResultFormat::Csv).unwrap();
+/// assert!(csv.starts_with("title,url,"));
+/// ```
+pub fn format_results(results: &[SearchResultType], format: ResultFormat) -> SearchResult<String> {
+    match format {
+        ResultFormat::Json => Ok(serde_json::to_string_pretty(results)?),
+        ResultFormat::Jsonl => to_jsonl(results),
+        ResultFormat::Csv => Ok(to_csv(results)),
+        ResultFormat::Markdown => Ok(to_markdown(results)),
+        ResultFormat::Rss => Ok(to_rss(results, DEFAULT_FEED_TITLE)),
+        ResultFormat::Atom => Ok(to_atom(results, DEFAULT_FEED_TITLE)),
+        ResultFormat::Bibtex => Ok(to_bibtex(results)),
+    }
+}
+
+/// Serialize each item as compact JSON on its own line
+pub fn to_jsonl<T: Serialize>(items: &[T]) -> SearchResult<String> {
+    let mut output = String::new();
+    for item in items {
+        output.push_str(&serde_json::to_string(item)?);
+        output.push('\n');
+    }
+    Ok(output)
+}
+
+/// Render results as CSV with the [`CSV_COLUMNS`] header
+pub fn to_csv(results: &[SearchResultType]) -> String {
+    let mut output = csv_row(&CSV_COLUMNS);
+    for result in results {
+        output.push_str(&csv_row(&[
+            result.title.as_str(),
+            result.url.as_str(),
+            result.domain.as_deref().unwrap_or_default(),
+            result.snippet.as_deref().unwrap_or_default(),
+            result.published_date.as_deref().unwrap_or_default(),
+            result.provider.as_deref().unwrap_or_default(),
+        ]));
+    }
+    output
+}
+
+/// Render results as a numbered Markdown list of links
+pub fn to_markdown(results: &[SearchResultType]) -> String {
+    let mut output = String::new();
+    for (index, result) in results.iter().enumerate() {
+        output.push_str(&format!(
+            "{}. [{}]({})\n",
+            index + 1,
+            escape_markdown(&result.title),
+            result.url.replace('(', "%28").replace(')', "%29")
+        ));
+        if let Some(snippet) = result.snippet.as_deref().filter(|s| !s.is_empty()) {
+            output.push_str(&format!("   {}\n", escape_markdown(snippet)));
+        }
+    }
+    output
+}
+
+/// Render results as an RSS 2.0 feed titled `title`
+///
+/// Each item carries the result's title, link and snippet, and its publication date when
+/// the provider returned one in a recognizable format. A results feed has no web page of
+/// its own, so the channel links to [`FEED_LINK`].
+pub fn to_rss(results: &[SearchResultType], title: &str) -> String {
+    let mut output = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    output.push_str("<rss version=\"2.0\">\n<channel>\n");
+    output.push_str(&format!("<title>{}</title>\n", escape_xml(title)));
+    output.push_str(&format!("<link>{}</link>\n", escape_xml(FEED_LINK)));
+    output.push_str(&format!(
+        "<description>{}</description>\n",
+        escape_xml(title)
+    ));
+    output.push_str(&format!(
+        "<lastBuildDate>{}</lastBuildDate>\n",
+        Utc::now().to_rfc2822()
+    ));
+    for result in results {
+        output.push_str("<item>\n");
+        output.push_str(&format!("  <title>{}</title>\n", escape_xml(&result.title)));
+        output.push_str(&format!("  <link>{}</link>\n", escape_xml(&result.url)));
+        output.push_str(&format!(
+            "  <guid isPermaLink=\"true\">{}</guid>\n",
+            escape_xml(&result.url)
+        ));
+        if let Some(snippet) = &result.snippet {
+            output.push_str(&format!(
+                "  <description>{}</description>\n",
+                escape_xml(snippet)
+            ));
+        }
+        if let Some(date) = result.published_date.as_deref().and_then(parse_date) {
+            output.push_str(&format!("  <pubDate>{}</pubDate>\n", date.to_rfc2822()));
+        }
+        output.push_str("</item>\n");
+    }
+    output.push_str("</channel>\n</rss>\n");
+    output
+}
+
+/// Render results as an Atom feed titled `title`
+///
+/// Entries without a recognizable publication date use the feed's generation time as
+/// their `updated` date, which Atom requires.
+pub fn to_atom(results: &[SearchResultType], title: &str) -> String {
+    let now = Utc::now().to_rfc3339();
+    let mut output = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    output.push_str("<feed xmlns=\"http://www.w3.org/2005/Atom\">\n");
+    output.push_str(&format!("<title>{}</title>\n", escape_xml(title)));
+    output.push_str(&format!(
+        "<id>urn:websearch:{}</id>\n",
+        escape_xml(&title.replace(char::is_whitespace, "-"))
+    ));
+    output.push_str(&format!("<updated>{now}</updated>\n"));
+    output.push_str("<author><name>websearch</name></author>\n");
+    for result in results {
+        let updated = result
+            .published_date
+            .as_deref()
+            .and_then(parse_date)
+            .map_or_else(|| now.clone(), |date| date.to_rfc3339());
+        output.push_str("<entry>\n");
+        output.push_str(&format!("  <title>{}</title>\n", escape_xml(&result.title)));
+        output.push_str(&format!("  <link href=\"{}\"/>\n", escape_xml(&result.url)));
+        output.push_str(&format!("  <id>{}</id>\n", escape_xml(&result.url)));
+        output.push_str(&format!("  <updated>{updated}</updated>\n"));
+        if let Some(snippet) = &result.snippet {
+            output.push_str(&format!("  <summary>{}</summary>\n", escape_xml(snippet)));
+        }
+        output.push_str("</entry>\n");
+    }
+    output.push_str("</feed>\n");
+    output
+}
+
+/// Parse the date formats providers return: RFC 3339, RFC 2822 or `YYYY-MM-DD`
+pub(crate) fn parse_date(date: &str) -> Option<DateTime<Utc>> {
+    let date = date.trim();
+    DateTime::parse_from_rfc3339(date)
+        .or_else(|_| DateTime::parse_from_rfc2822(date))
+        .map(|date| date.with_timezone(&Utc))
+        .ok()
+        .or_else(|| {
+            NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                .ok()
+                .and_then(|day| day.and_hms_opt(0, 0, 0))
+                .map(|time| time.and_utc())
+        })
+}
+
+/// Render results as a BibTeX bibliography
+///
+/// Results with scholarly metadata (see [`ScholarlyResult::from_result`]) become full
+/// entries with authors, venue, DOI and arXiv ID; other results are cited as `@misc` web
+/// pages. Citation keys that would repeat get a numeric suffix, e.g. "smith2020rust-2".
+pub fn to_bibtex(results: &[SearchResultType]) -> String {
+    let mut keys = HashSet::new();
+    let mut output = String::new();
+    for result in results {
+        let paper = ScholarlyResult::from_result(result).unwrap_or_else(|| ScholarlyResult {
+            title: result.title.clone(),
+            url: result.url.clone(),
+            year: result
+                .published_date
+                .as_deref()
+                .and_then(|date| date.get(..4)?.parse().ok()),
+            ..Default::default()
+        });
+
+        let base = paper.citation_key();
+        let mut key = base.clone();
+        let mut n = 1;
+        while !keys.insert(key.clone()) {
+            n += 1;
+            key = format!("{base}-{n}");
+        }
+
+        if !output.is_empty() {
+            output.push('\n');
+        }
+        output.push_str(&paper.to_bibtex(&key));
+    }
+    output
+}
+
+/// Escape the characters XML reserves in text and attribute values
+///
+/// Control characters other than tab and line breaks can't appear in XML at all, so they
+/// are dropped.
+pub fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\t' | '\n' | '\r' => escaped.push(c),
+            '\u{0}'..='\u{1f}' => {}
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Build one CSV line (terminated by CRLF), quoting fields that need it
+pub fn csv_row<S: AsRef<str>>(fields: &[S]) -> String {
+    let mut row = fields
+        .iter()
+        .map(|field| {
+            let field = field.as_ref();
+            if field.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",");
+    row.push_str("\r\n");
+    row
+}
+
+/// Escape the characters LaTeX reserves, so text can go inside a BibTeX field
+pub fn escape_bibtex(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' | '%' | '$' | '#' | '_' | '{' | '}' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '~' => escaped.push_str("\\textasciitilde{}"),
+            '^' => escaped.push_str("\\textasciicircum{}"),
+            '\\' => escaped.push_str("\\textbackslash{}"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Escape characters that Markdown would otherwise interpret, and collapse newlines
+pub fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '#' | '|' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\n' | '\r' => escaped.push(' '),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Vec<SearchResultType> {
+        vec![
+            SearchResultType {
+                title: "Rust, \"the\" language".to_string(),
+                url: "https://www.rust-lang.org/".to_string(),
+                domain: Some("rust-lang.org".into()),
+                snippet: Some("Fast *and* reliable".to_string()),
+                provider: Some("duckduckgo".into()),
+                ..Default::default()
+            },
+            SearchResultType {
+                title: "Rust (video game)".to_string(),
+                url: "https://en.wikipedia.org/wiki/Rust_(video_game)".to_string(),
+                ..Default::default()
+            },
+        ]
+    }
+
+    #[test]
+    fn test_csv_quotes_fields() {
+        let csv = to_csv(&sample());
+        let lines: Vec<_> = csv.split("\r\n").collect();
+        assert_eq!(lines[0], "title,url,domain,snippet,published_date,provider");
+        assert_eq!(
+            lines[1],
+            "\"Rust, \"\"the\"\" language\",https://www.rust-lang.org/,rust-lang.org,Fast *and* reliable,,duckduckgo"
+        );
+        assert_eq!(lines.len(), 4);
+    }
+
+    #[test]
+    fn test_markdown_escapes_titles_and_urls() {
+        let markdown = to_markdown(&sample());
+        assert_eq!(
+            markdown,
+            "1. [Rust, \"the\" language](https://www.rust-lang.org/)\n   Fast \\*and\\* reliable\n\
+             2. [Rust (video game)](https://en.wikipedia.org/wiki/Rust_%28video_game%29)\n"
+        );
+    }
+
+    #[test]
+    fn test_rss_and_atom_feeds() {
+        let mut results = sample();
+        results[0].published_date = Some("2024-03-01".to_string());
+
+        let rss = to_rss(&results, "rust & friends");
+        assert!(rss.contains("<title>rust &amp; friends</title>"));
+        assert!(rss.contains("<link>https://github.com/xynehq/websearch</link>"));
+        assert!(rss.contains("<title>Rust, &quot;the&quot; language</title>"));
+        assert!(rss.contains("<pubDate>Fri, 1 Mar 2024 00:00:00 +0000</pubDate>"));
+        assert_eq!(rss.matches("<item>").count(), 2);
+
+        let atom = to_atom(&results, "rust");
+        assert!(atom.contains("<link href=\"https://www.rust-lang.org/\"/>"));
+        assert!(atom.contains("<updated>2024-03-01T00:00:00+00:00</updated>"));
+        assert_eq!(atom.matches("<entry>").count(), 2);
+    }
+
+    #[test]
+    fn test_escape_xml_drops_control_characters() {
+        assert_eq!(
+            escape_xml("a\u{0}b\u{8}c\u{1b}[0m\td\r\n\u{7f}"),
+            "abc[0m\td\r\n\u{7f}"
+        );
+    }
+
+    #[test]
+    fn test_bibtex_keys_are_unique() {
+        let mut results = sample();
+        results.push(results[0].clone());
+        results[0].published_date = Some("2024-03-01".to_string());
+
+        let bibtex = format_results(&results, ResultFormat::Bibtex).unwrap();
+        assert!(bibtex.starts_with(
+            "@misc{2024rust,\n  title = {Rust, \"the\" language},\n  year = {2024},\n"
+        ));
+        assert!(bibtex.contains("@misc{rust,\n"));
+        assert!(bibtex.contains("@misc{rust-2,\n"));
+        assert!(bibtex.contains("  url = {https://en.wikipedia.org/wiki/Rust_(video_game)},\n"));
+        assert_eq!(
+            escape_bibtex("50% of C# ~x^2 \\o"),
+            "50\\% of C\\# \\textasciitilde{}x\\textasciicircum{}2 \\textbackslash{}o"
+        );
+    }
+
+    #[test]
+    fn test_jsonl_one_object_per_line() {
+        let jsonl = format_results(&sample(), ResultFormat::Jsonl).unwrap();
+        let lines: Vec<_> = jsonl.lines().collect();
+        assert_eq!(lines.len(), 2);
+        let first: SearchResultType = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(first.url, "https://www.rust-lang.org/");
+    }
+}
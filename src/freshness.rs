@@ -0,0 +1,103 @@
+//! Result recency
+//!
+//! `SearchOptions::freshness` asks for results published within the past day, week, month
+//! or year. Brave, Google, Tavily and SerpAPI's Google engine take it as a request
+//! parameter; providers declare this with [`SearchProvider::supports_freshness`]. For the
+//! others, results dated before the window are dropped client-side after the search.
+//!
+//! Most providers don't date their results, and some only give relative dates such as
+//! Brave's "2 days ago", so results without a parseable `published_date` are kept.
+
+use crate::{
+    format::parse_date,
+    types::{Freshness, SearchProvider, SearchResult},
+};
+use chrono::{Duration, Utc};
+
+/// Drop results published before the `freshness` window; undated results are kept
+pub fn retain_fresh(results: &mut Vec<SearchResult>, freshness: Freshness) {
+    let cutoff = Utc::now() - Duration::days(freshness.days().into());
+    results.retain(|result| {
+        result
+            .published_date
+            .as_deref()
+            .and_then(parse_date)
+            .is_none_or(|published| published >= cutoff)
+    });
+}
+
+/// Apply the window client-side when the provider doesn't on its own
+pub(crate) fn enforce(
+    provider: &dyn SearchProvider,
+    freshness: Option<Freshness>,
+    results: &mut Vec<SearchResult>,
+) {
+    if let Some(freshness) = freshness {
+        if !provider.supports_freshness() {
+            retain_fresh(results, freshness);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        testing::MockProvider,
+        types::{SearchOptions, SearchQuery},
+        web_search, web_search_batch,
+    };
+
+    fn dated(url: &str, published_date: Option<String>) -> SearchResult {
+        SearchResult {
+            url: url.to_string(),
+            published_date,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_old_dated_results_are_dropped_client_side() {
+        let today = Utc::now().date_naive();
+        let results = vec![
+            dated("https://a.com/", Some(today.to_string())),
+            dated(
+                "https://b.com/",
+                Some((today - Duration::days(30)).to_string()),
+            ),
+            dated("https://c.com/", Some("2 days ago".to_string())),
+            dated("https://d.com/", None),
+        ];
+
+        let results = web_search(SearchOptions {
+            query: "rust".to_string(),
+            freshness: Some(Freshness::Week),
+            provider: Box::new(MockProvider::new("mock").with_results(results)),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+        let urls: Vec<&str> = results.iter().map(|r| r.url.as_str()).collect();
+        assert_eq!(urls, ["https://a.com/", "https://c.com/", "https://d.com/"]);
+    }
+
+    #[tokio::test]
+    async fn test_batch_searches_are_filtered_client_side() {
+        let today = Utc::now().date_naive();
+        let provider = MockProvider::new("mock").with_results(vec![
+            dated("https://a.com/", Some(today.to_string())),
+            dated("https://b.com/", Some("2015-05-15".to_string())),
+        ]);
+        let query = SearchQuery {
+            freshness: Some(Freshness::Month),
+            ..SearchQuery::new("rust")
+        };
+
+        let batch = web_search_batch(&provider, vec![query], 1).await;
+
+        let results = batch[0].result.as_ref().unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].url, "https://a.com/");
+    }
+}
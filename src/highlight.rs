@@ -0,0 +1,160 @@
+//! Query terms providers highlighted in result snippets
+//!
+//! Google and DuckDuckGo mark matching terms with `<b>`, Brave with `<strong>`, and SerpAPI
+//! lists them in `snippet_highlighted_words`. Providers turn these into
+//! [`SearchResult::highlights`](crate::types::SearchResult::highlights), byte ranges into
+//! the plain-text snippet, and [`apply`] renders them back with any markers.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use websearch::highlight::{apply, find_terms, html_terms};
+//! use websearch::utils::extract::HighlightMarkers;
+//!
+//! let html = "<b>Rust</b> is a systems language. Learn <b>Rust</b> today. Trust Rust.";
+//! let snippet = "Rust is a systems language. Learn Rust today. Trust Rust.";
+//! let ranges = find_terms(snippet, &html_terms(html));
+//! assert_eq!(ranges, vec![0..4, 34..38]);
+//! assert_eq!(
+//!     apply(snippet, &ranges, &HighlightMarkers::markdown()),
+//!     "**Rust** is a systems language. Learn **Rust** today. Trust Rust."
+//! );
+//! ```
+
+use crate::utils::extract::{element_text, HighlightMarkers};
+use scraper::{Html, Selector};
+use std::collections::HashMap;
+use std::ops::Range;
+
+/// Terms wrapped in `<b>` or `<strong>` in an HTML fragment, once per time they are marked
+pub fn html_terms(html: &str) -> Vec<String> {
+    let fragment = Html::parse_fragment(html);
+    let selector = Selector::parse("b, strong").unwrap();
+    fragment
+        .select(&selector)
+        .map(|element| element_text(element, None))
+        .filter(|term| !term.is_empty())
+        .collect()
+}
+
+/// Byte ranges of `terms` in `text`, sorted and merged where they overlap
+///
+/// Each entry marks the next occurrence of that term, so a term listed once is only found
+/// once; list it as often as the provider highlighted it. Matching is exact, so terms
+/// should come from the same text, e.g. a provider's HTML snippet and its plain-text
+/// version. Only whole words match: "Rust" isn't found in "Trust".
+pub fn find_terms(text: &str, terms: &[impl AsRef<str>]) -> Vec<Range<usize>> {
+    // Where to look for each term's next occurrence
+    let mut next: HashMap<&str, usize> = HashMap::new();
+    let mut ranges: Vec<Range<usize>> = Vec::new();
+    for term in terms
+        .iter()
+        .map(AsRef::as_ref)
+        .filter(|term| !term.is_empty())
+    {
+        let from = next.entry(term).or_default();
+        let found = text[*from..]
+            .match_indices(term)
+            .map(|(start, _)| *from + start..*from + start + term.len())
+            .find(|range| is_whole_word(text, range));
+        match found {
+            Some(range) => {
+                *from = range.end;
+                ranges.push(range);
+            }
+            None => *from = text.len(),
+        }
+    }
+    ranges.sort_by_key(|range| range.start);
+
+    let mut merged: Vec<Range<usize>> = Vec::with_capacity(ranges.len());
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
+
+/// Whether `range` neither starts nor ends inside a word of `text`
+fn is_whole_word(text: &str, range: &Range<usize>) -> bool {
+    let is_word = |c: Option<char>| c.is_some_and(char::is_alphanumeric);
+    let matched = &text[range.clone()];
+    let starts_inside =
+        is_word(text[..range.start].chars().next_back()) && is_word(matched.chars().next());
+    let ends_inside =
+        is_word(text[range.end..].chars().next()) && is_word(matched.chars().next_back());
+    !starts_inside && !ends_inside
+}
+
+/// `text` with each range wrapped in `markers`
+pub fn apply(text: &str, ranges: &[Range<usize>], markers: &HighlightMarkers) -> String {
+    let mut output = String::with_capacity(text.len());
+    for (part, highlighted) in segments(text, ranges) {
+        if highlighted {
+            output.push_str(&markers.open);
+            output.push_str(part);
+            output.push_str(&markers.close);
+        } else {
+            output.push_str(part);
+        }
+    }
+    output
+}
+
+/// `text` split into consecutive parts, each flagged whether it is highlighted
+///
+/// For rendering highlights with something other than text markers, such as terminal
+/// styles. Ranges that are out of order, fall outside `text` or split a character are
+/// skipped.
+pub fn segments<'a>(text: &'a str, ranges: &[Range<usize>]) -> Vec<(&'a str, bool)> {
+    let mut parts = Vec::with_capacity(ranges.len() * 2 + 1);
+    let mut position = 0;
+    for range in ranges {
+        if range.start < position || text.get(range.clone()).is_none() {
+            continue;
+        }
+        if range.start > position {
+            parts.push((&text[position..range.start], false));
+        }
+        parts.push((&text[range.clone()], true));
+        position = range.end;
+    }
+    if position < text.len() {
+        parts.push((&text[position..], false));
+    }
+    parts
+}
+
+/// Drop the parts of `ranges` past the first `len` bytes, e.g. after truncating a snippet
+pub fn clip(ranges: &mut Vec<Range<usize>>, len: usize) {
+    ranges.retain(|range| range.start < len);
+    if let Some(last) = ranges.last_mut() {
+        last.end = last.end.min(len);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overlapping_terms_merge_and_bad_ranges_are_skipped() {
+        let text = "async rust runtime";
+        let ranges = find_terms(text, &["async rust", "rust runtime", "missing"]);
+        assert_eq!(ranges, vec![0..18]);
+
+        let markers = HighlightMarkers::new("[", "]");
+        assert_eq!(apply("héllo", &[1..2, 9..12], &markers), "héllo");
+        assert_eq!(apply("héllo", &[0..1, 1..3], &markers), "[h][é]llo");
+
+        let ranges = find_terms("Trust Rust, Rusty Rust's", &["Rust", "Rust", "Rust"]);
+        assert_eq!(ranges, vec![6..10, 18..22]);
+        assert_eq!(find_terms("Rust and Rust", &["Rust"]), vec![0..4]);
+
+        let mut ranges = vec![0..4, 6..10, 12..14];
+        clip(&mut ranges, 8);
+        assert_eq!(ranges, vec![0..4, 6..8]);
+    }
+}
@@ -0,0 +1,352 @@
+//! Several API keys for one provider
+//!
+//! Teams often pool the free-tier quotas of several keys for the same API.
+//! [`KeyRotatingProvider`] builds one provider per key and searches with the active key
+//! until the provider rate-limits it or reports its quota used up. That key then cools down
+//! and the search is retried with the next one. Other errors are returned as they are, since
+//! another key wouldn't help.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use websearch::{key_rotation::KeyRotatingProvider, providers::SerpApiProvider};
+//!
+//! let provider = KeyRotatingProvider::new(["key-1", "key-2", "key-3"], SerpApiProvider::new)?;
+//! assert_eq!(provider.key_stats().len(), 3);
+//! # Ok::<(), websearch::SearchError>(())
+//! ```
+
+use crate::{
+    error::{SearchError, SearchResult},
+    types::{
+        HealthStatus, ProviderHealth, SearchMode, SearchOptions, SearchProvider, SearchResponse,
+        SearchResult as SearchResultType,
+    },
+};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// How long a rate-limited key is skipped by default
+pub const DEFAULT_KEY_COOLDOWN: Duration = Duration::from_secs(60);
+
+/// Usage of one pooled key
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct KeyStats {
+    /// The key's last four characters
+    pub key: String,
+    /// Searches sent with the key
+    pub requests: u64,
+    /// Searches rejected because of a rate limit or an exhausted quota
+    pub rate_limited: u64,
+    /// Whether the key is skipped until its cooldown ends
+    pub cooling_down: bool,
+    /// Whether searches currently use the key
+    pub active: bool,
+}
+
+/// Per-key bookkeeping
+#[derive(Debug)]
+struct KeyState {
+    hint: String,
+    requests: u64,
+    rate_limited: u64,
+    cooling_until: Option<Instant>,
+}
+
+impl KeyState {
+    fn is_cooling_down(&self, now: Instant) -> bool {
+        self.cooling_until.is_some_and(|until| until > now)
+    }
+}
+
+#[derive(Debug)]
+struct KeyPool {
+    keys: Vec<KeyState>,
+    active: usize,
+}
+
+/// A provider that rotates among several API keys
+#[derive(Debug)]
+pub struct KeyRotatingProvider {
+    providers: Vec<Box<dyn SearchProvider>>,
+    pool: Mutex<KeyPool>,
+    cooldown: Duration,
+}
+
+impl KeyRotatingProvider {
+    /// Build a provider for each of `keys` with `build`, e.g. `SerpApiProvider::new`
+    pub fn new<I, K, P, F>(keys: I, mut build: F) -> SearchResult<Self>
+    where
+        I: IntoIterator<Item = K>,
+        K: AsRef<str>,
+        P: SearchProvider + 'static,
+        F: FnMut(&str) -> SearchResult<P>,
+    {
+        let mut providers: Vec<Box<dyn SearchProvider>> = Vec::new();
+        let mut states = Vec::new();
+        for key in keys {
+            let key = key.as_ref();
+            providers.push(Box::new(build(key)?));
+            states.push(KeyState {
+                hint: key_hint(key),
+                requests: 0,
+                rate_limited: 0,
+                cooling_until: None,
+            });
+        }
+        if providers.is_empty() {
+            return Err(SearchError::ConfigError(
+                "At least one API key is required".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            providers,
+            pool: Mutex::new(KeyPool {
+                keys: states,
+                active: 0,
+            }),
+            cooldown: DEFAULT_KEY_COOLDOWN,
+        })
+    }
+
+    /// Skip a rate-limited key for `cooldown` (default [`DEFAULT_KEY_COOLDOWN`])
+    pub fn with_cooldown(mut self, cooldown: Duration) -> Self {
+        self.cooldown = cooldown;
+        self
+    }
+
+    /// Usage of every key, in configuration order
+    pub fn key_stats(&self) -> Vec<KeyStats> {
+        let pool = self.pool.lock().unwrap();
+        let now = Instant::now();
+        pool.keys
+            .iter()
+            .enumerate()
+            .map(|(index, state)| KeyStats {
+                key: state.hint.clone(),
+                requests: state.requests,
+                rate_limited: state.rate_limited,
+                cooling_down: state.is_cooling_down(now),
+                active: index == pool.active,
+            })
+            .collect()
+    }
+
+    /// Key indices in the order they should be tried: the active key and the other
+    /// available ones after it, then cooling ones by how soon their cooldown ends
+    fn key_order(&self) -> Vec<usize> {
+        let pool = self.pool.lock().unwrap();
+        let count = pool.keys.len();
+        let now = Instant::now();
+
+        let (mut order, mut cooling): (Vec<usize>, Vec<usize>) = (0..count)
+            .map(|offset| (pool.active + offset) % count)
+            .partition(|&index| !pool.keys[index].is_cooling_down(now));
+        cooling.sort_by_key(|&index| pool.keys[index].cooling_until);
+        order.extend(cooling);
+        order
+    }
+}
+
+/// Last four characters of `key`, for stats and logs
+fn key_hint(key: &str) -> String {
+    let tail: Vec<char> = key.chars().rev().take(4).collect();
+    format!("...{}", tail.into_iter().rev().collect::<String>())
+}
+
+#[async_trait::async_trait]
+impl SearchProvider for KeyRotatingProvider {
+    fn name(&self) -> &str {
+        self.providers[0].name()
+    }
+
+    async fn search(&self, options: &SearchOptions) -> SearchResult<Vec<SearchResultType>> {
+        Ok(self.search_response(options).await?.results)
+    }
+
+    async fn search_response(&self, options: &SearchOptions) -> SearchResult<SearchResponse> {
+        let mut last_error = None;
+
+        for index in self.key_order() {
+            self.pool.lock().unwrap().keys[index].requests += 1;
+            match self.providers[index].search_response(options).await {
+                Err(error) if HealthStatus::from_error(&error) == HealthStatus::RateLimited => {
+                    let mut pool = self.pool.lock().unwrap();
+                    let state = &mut pool.keys[index];
+                    log::warn!(
+                        "{} key {} is rate limited, trying the next key: {}",
+                        self.name(),
+                        state.hint,
+                        error
+                    );
+                    state.rate_limited += 1;
+                    state.cooling_until = Some(Instant::now() + self.cooldown);
+                    last_error = Some(error);
+                }
+                result => {
+                    self.pool.lock().unwrap().active = index;
+                    return result;
+                }
+            }
+        }
+
+        Err(last_error.expect("KeyRotatingProvider has at least one key"))
+    }
+
+    fn config(&self) -> HashMap<String, String> {
+        let active = self.pool.lock().unwrap().active;
+        let mut config = self.providers[active].config();
+        config.insert("api_keys".to_string(), self.providers.len().to_string());
+        config
+    }
+
+    fn supports_safe_search(&self) -> bool {
+        self.providers[0].supports_safe_search()
+    }
+
+    fn supports_domain_filter(&self) -> bool {
+        self.providers[0].supports_domain_filter()
+    }
+
+    fn supports_freshness(&self) -> bool {
+        self.providers[0].supports_freshness()
+    }
+
+    fn supports_pagination(&self) -> bool {
+        self.providers[0].supports_pagination()
+    }
+
+    fn supports_mode(&self, mode: SearchMode) -> bool {
+        self.providers[0].supports_mode(mode)
+    }
+
+    /// Health of the active key
+    async fn health_check(&self) -> ProviderHealth {
+        let active = self.pool.lock().unwrap().active;
+        self.providers[active].health_check().await
+    }
+
+    /// Checks every key, failing with the first rejected one
+    async fn validate_credentials(&self) -> SearchResult<()> {
+        let checks = self
+            .providers
+            .iter()
+            .map(|provider| provider.validate_credentials());
+        futures::future::join_all(checks)
+            .await
+            .into_iter()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::MockProvider;
+
+    fn provider() -> KeyRotatingProvider {
+        KeyRotatingProvider::new(["key-one", "key-two"], |key| {
+            let provider = MockProvider::new("mock");
+            Ok(match key {
+                "key-one" => provider.with_error(SearchError::HttpError {
+                    message: "quota exceeded".to_string(),
+                    status_code: Some(429),
+                    response_body: None,
+                }),
+                _ => provider,
+            })
+        })
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_rotates_past_rate_limited_key() {
+        let provider = provider();
+        let options = SearchOptions {
+            query: "rust".to_string(),
+            ..Default::default()
+        };
+
+        for _ in 0..2 {
+            let results = provider.search(&options).await.unwrap();
+            assert_eq!(results.len(), 2);
+        }
+
+        let stats = provider.key_stats();
+        assert_eq!(stats[0].key, "...-one");
+        // The second search went straight to the second key
+        assert_eq!((stats[0].requests, stats[0].rate_limited), (1, 1));
+        assert!(stats[0].cooling_down && !stats[0].active);
+        assert_eq!((stats[1].requests, stats[1].rate_limited), (2, 0));
+        assert!(stats[1].active);
+    }
+
+    #[tokio::test]
+    async fn test_rotates_serpapi_key_after_429() {
+        use crate::providers::SerpApiProvider;
+        use wiremock::{
+            matchers::{path, query_param},
+            Mock, MockServer, ResponseTemplate,
+        };
+
+        let server = MockServer::start().await;
+        Mock::given(path("/search.json"))
+            .and(query_param("api_key", "spent-key"))
+            .respond_with(ResponseTemplate::new(429).set_body_json(serde_json::json!({
+                "error": "Your account has run out of searches."
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(path("/search.json"))
+            .and(query_param("api_key", "fresh-key"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "organic_results": [
+                    {"position": 1, "title": "Rust", "link": "https://www.rust-lang.org/"}
+                ]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let base_url = format!("{}/search.json", server.uri());
+        let provider = KeyRotatingProvider::new(["spent-key", "fresh-key"], |key| {
+            Ok(SerpApiProvider::new(key)?.with_base_url(&base_url))
+        })
+        .unwrap();
+        let options = SearchOptions {
+            query: "rust".to_string(),
+            ..Default::default()
+        };
+
+        let results = provider.search(&options).await.unwrap();
+        assert_eq!(results[0].url, "https://www.rust-lang.org/");
+        let stats = provider.key_stats();
+        assert_eq!(stats[0].rate_limited, 1);
+        assert!(stats[1].active);
+    }
+
+    #[tokio::test]
+    async fn test_other_errors_do_not_rotate() {
+        let provider = KeyRotatingProvider::new(["a", "b"], |_| {
+            Ok(MockProvider::new("mock").with_error(SearchError::ParseError("bad".to_string())))
+        })
+        .unwrap();
+
+        let error = provider
+            .search(&SearchOptions::default())
+            .await
+            .unwrap_err();
+        assert!(matches!(error, SearchError::ParseError(_)));
+        assert_eq!(provider.key_stats()[1].requests, 0);
+    }
+
+    #[test]
+    fn test_requires_a_key() {
+        let keys: [&str; 0] = [];
+        assert!(KeyRotatingProvider::new(keys, |_| Ok(MockProvider::new("mock"))).is_err());
+    }
+}
@@ -0,0 +1,170 @@
+//! Per-result language detection
+//!
+//! Many providers ignore the `language` search option. [`tag_languages`] detects the
+//! language of each result from its title and snippet and fills
+//! [`SearchResult::language`], so results can be filtered client-side with
+//! [`retain_language`].
+//!
+//! Requires the `langdetect` feature.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use websearch::{langdetect, SearchResult};
+//!
+//! let mut results = vec![
+//!     SearchResult {
+//!         title: "The quick brown fox jumps over the lazy dog".to_string(),
+//!         snippet: Some("A sentence that contains every letter of the English alphabet.".to_string()),
+//!         ..Default::default()
+//!     },
+//!     SearchResult {
+//!         title: "Der schnelle braune Fuchs springt über den faulen Hund".to_string(),
+//!         snippet: Some("Ein Satz, der alle Buchstaben des deutschen Alphabets enthält.".to_string()),
+//!         ..Default::default()
+//!     },
+//! ];
+//!
+//! langdetect::tag_languages(&mut results);
+//! langdetect::retain_language(&mut results, "en-US");
+//! assert_eq!(results.len(), 1);
+//! assert_eq!(results[0].language.as_deref(), Some("en"));
+//! ```
+
+pub use crate::language::retain_language;
+use crate::types::SearchResult;
+
+/// ISO 639-3 codes used by the detector and their ISO 639-1 equivalents
+const ISO_639_1: &[(&str, &str)] = &[
+    ("afr", "af"),
+    ("aka", "ak"),
+    ("amh", "am"),
+    ("ara", "ar"),
+    ("aze", "az"),
+    ("bel", "be"),
+    ("ben", "bn"),
+    ("bul", "bg"),
+    ("cat", "ca"),
+    ("ces", "cs"),
+    ("cmn", "zh"),
+    ("dan", "da"),
+    ("deu", "de"),
+    ("ell", "el"),
+    ("eng", "en"),
+    ("epo", "eo"),
+    ("est", "et"),
+    ("fin", "fi"),
+    ("fra", "fr"),
+    ("guj", "gu"),
+    ("heb", "he"),
+    ("hin", "hi"),
+    ("hrv", "hr"),
+    ("hun", "hu"),
+    ("hye", "hy"),
+    ("ind", "id"),
+    ("ita", "it"),
+    ("jav", "jv"),
+    ("jpn", "ja"),
+    ("kan", "kn"),
+    ("kat", "ka"),
+    ("khm", "km"),
+    ("kor", "ko"),
+    ("lat", "la"),
+    ("lav", "lv"),
+    ("lit", "lt"),
+    ("mal", "ml"),
+    ("mar", "mr"),
+    ("mkd", "mk"),
+    ("mya", "my"),
+    ("nep", "ne"),
+    ("nld", "nl"),
+    ("nob", "nb"),
+    ("ori", "or"),
+    ("pan", "pa"),
+    ("pes", "fa"),
+    ("pol", "pl"),
+    ("por", "pt"),
+    ("ron", "ro"),
+    ("rus", "ru"),
+    ("sin", "si"),
+    ("slk", "sk"),
+    ("slv", "sl"),
+    ("sna", "sn"),
+    ("spa", "es"),
+    ("srp", "sr"),
+    ("swe", "sv"),
+    ("tam", "ta"),
+    ("tel", "te"),
+    ("tgl", "tl"),
+    ("tha", "th"),
+    ("tuk", "tk"),
+    ("tur", "tr"),
+    ("ukr", "uk"),
+    ("urd", "ur"),
+    ("uzb", "uz"),
+    ("vie", "vi"),
+    ("yid", "yi"),
+    ("zul", "zu"),
+];
+
+/// Detect the language of `text`, as an ISO 639-1 code such as `"en"`
+///
+/// Returns `None` when the text is too short or ambiguous for a reliable guess.
+pub fn detect_language(text: &str) -> Option<String> {
+    let info = whatlang::detect(text).filter(|info| info.is_reliable())?;
+    let code = info.lang().code();
+    let code = ISO_639_1
+        .iter()
+        .find(|(iso3, _)| *iso3 == code)
+        .map_or(code, |(_, iso1)| iso1);
+    Some(code.to_string())
+}
+
+/// Fill [`SearchResult::language`] from each result's title and snippet
+///
+/// Results that already carry a language (e.g. from the provider) are left as they are.
+pub fn tag_languages(results: &mut [SearchResult]) {
+    for result in results.iter_mut().filter(|r| r.language.is_none()) {
+        let text = match &result.snippet {
+            Some(snippet) => format!("{} {}", result.title, snippet),
+            None => result.title.clone(),
+        };
+        result.language = detect_language(&text);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(title: &str, snippet: &str) -> SearchResult {
+        SearchResult {
+            title: title.to_string(),
+            snippet: Some(snippet.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_tag_languages() {
+        let mut results = vec![
+            result(
+                "Rust is a systems programming language",
+                "It is fast, memory safe and has a friendly community of developers.",
+            ),
+            result(
+                "Rust es un lenguaje de programación de sistemas",
+                "Es rápido, seguro en el uso de la memoria y tiene una comunidad muy amable.",
+            ),
+            SearchResult {
+                language: Some("fr".to_string()),
+                ..result("Already tagged", "")
+            },
+            result("ok", ""),
+        ];
+
+        tag_languages(&mut results);
+        let languages: Vec<_> = results.iter().map(|r| r.language.as_deref()).collect();
+        assert_eq!(languages, [Some("en"), Some("es"), Some("fr"), None]);
+    }
+}
@@ -0,0 +1,132 @@
+//! Result language
+//!
+//! Google and Brave report the language of each result, which fills
+//! [`SearchResult::language`]. Providers often ignore the `language` search option, so
+//! setting `SearchOptions::require_language` drops results reported in another language
+//! after the search. With the `langdetect` feature, untagged results are detected first
+//! (see `websearch::langdetect`); results whose language is still unknown are kept.
+
+use crate::types::SearchResult;
+
+/// Drop results tagged with a language other than `language`
+///
+/// `language` may be a code such as `"en"` or a locale such as `"en-US"`; only the language
+/// part is compared. Results without a language are kept.
+pub fn retain_language(results: &mut Vec<SearchResult>, language: &str) {
+    let wanted = primary_subtag(language);
+    results.retain(|result| {
+        result
+            .language
+            .as_deref()
+            .is_none_or(|tagged| primary_subtag(tagged) == wanted)
+    });
+}
+
+/// Lowercase language part of a code or locale, e.g. `"pt"` for `"pt_BR"`
+pub(crate) fn primary_subtag(language: &str) -> String {
+    language
+        .split(['-', '_'])
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_lowercase()
+}
+
+/// The ISO 639 code at the start of a BCP 47 tag or locale, e.g. `"pt"` for `"pt_BR"`, or
+/// `None` for values such as `"English"`
+pub(crate) fn language_code(tag: &str) -> Option<String> {
+    let code = primary_subtag(tag.split(',').next()?);
+    let is_code = matches!(code.len(), 2 | 3) && code.chars().all(|c| c.is_ascii_alphabetic());
+    is_code.then_some(code)
+}
+
+/// Apply `require_language` to a provider's results
+pub(crate) fn enforce(
+    language: Option<&str>,
+    require_language: bool,
+    results: &mut Vec<SearchResult>,
+) {
+    let Some(language) = language.filter(|_| require_language) else {
+        return;
+    };
+    #[cfg(feature = "langdetect")]
+    crate::langdetect::tag_languages(results);
+    retain_language(results, language);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        testing::MockProvider,
+        types::{SearchOptions, SearchQuery},
+        web_search, web_search_batch,
+    };
+
+    fn tagged(language: Option<&str>) -> SearchResult {
+        SearchResult {
+            language: language.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_retain_language_keeps_untagged() {
+        let mut results = vec![tagged(Some("en")), tagged(Some("de")), tagged(None)];
+
+        retain_language(&mut results, "EN_gb");
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].language.as_deref(), Some("en"));
+        assert_eq!(results[1].language, None);
+    }
+
+    #[test]
+    fn test_language_code() {
+        assert_eq!(language_code("en_US").as_deref(), Some("en"));
+        assert_eq!(language_code("de-DE, en").as_deref(), Some("de"));
+        assert_eq!(language_code("haw").as_deref(), Some("haw"));
+        assert_eq!(language_code("English"), None);
+        assert_eq!(language_code(""), None);
+    }
+
+    #[tokio::test]
+    async fn test_require_language_drops_other_languages() {
+        let results = vec![
+            tagged(Some("en")),
+            tagged(Some("de-DE")),
+            tagged(Some("fr")),
+        ];
+        let search = |require_language| {
+            let provider = MockProvider::new("mock").with_results(results.clone());
+            web_search(SearchOptions {
+                query: "rust".to_string(),
+                language: Some("de".to_string()),
+                require_language,
+                provider: Box::new(provider),
+                ..Default::default()
+            })
+        };
+
+        let required = search(true).await.unwrap();
+        assert_eq!(required.len(), 1);
+        assert_eq!(required[0].language.as_deref(), Some("de-DE"));
+        assert_eq!(search(false).await.unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_batch_searches_require_the_language_too() {
+        let provider =
+            MockProvider::new("mock").with_results(vec![tagged(Some("en")), tagged(Some("de"))]);
+        let query = SearchQuery {
+            language: Some("de".to_string()),
+            require_language: true,
+            ..SearchQuery::new("rust")
+        };
+
+        let batch = web_search_batch(&provider, vec![query], 1).await;
+
+        let results = batch[0].result.as_ref().unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].language.as_deref(), Some("de"));
+    }
+}
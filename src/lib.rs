@@ -8,7 +8,7 @@
 //!
 //! ## Quick Start
 //!
-//! ```rust
+//! ```rust,no_run
 //! use websearch::{web_search, providers::google::GoogleProvider, SearchOptions};
 //!
 //! #[tokio::main]
@@ -32,15 +32,65 @@
 //! }
 //! ```
 
+pub mod batch;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+pub mod cache;
+pub mod cassette;
+pub mod classify;
+pub mod correlation;
+pub mod credentials;
+pub mod domains;
+pub mod dry_run;
+#[cfg(feature = "embeddings")]
+pub mod embeddings;
+pub mod enrich;
 pub mod error;
+pub mod export;
+pub mod format;
+pub mod freshness;
+pub mod highlight;
+pub mod key_rotation;
+#[cfg(feature = "langdetect")]
+pub mod langdetect;
+pub mod language;
+pub mod limits;
+pub mod locale;
 pub mod multi_provider;
+pub mod prelude;
+pub mod processors;
+pub mod products;
 pub mod providers;
+pub mod query;
+pub mod queue;
+pub mod rerank;
+pub mod routing;
+pub mod safe_search;
+pub mod scholarly;
+pub mod seen;
+#[cfg(feature = "server")]
+pub mod server;
+pub mod sink;
+#[cfg(feature = "store")]
+pub mod store;
+pub mod suggest;
+pub mod testing;
+pub mod trends;
 pub mod types;
 pub mod utils;
 
 // Re-export common types
-pub use error::{SearchError, SearchResult as Result};
-pub use types::{DebugOptions, SearchOptions, SearchProvider, SearchResult};
+pub use batch::{web_search_batch, BatchSearchResult};
+pub use error::{ErrorClass, SearchError, SearchResult as Result};
+pub use query::{web_search_all, web_search_any, QueryMatch};
+pub use types::{
+    DebugOptions, GeoLocation, HealthStatus, ParseWarning, PlaceResult, ProviderHealth,
+    ProviderHit, ResultKind, RichResult, SearchMode, SearchOptions, SearchProvider, SearchQuery,
+    SearchResponse, SearchResult, VideoResult,
+};
+pub use utils::http::HttpSettings;
+
+use std::collections::HashSet;
 
 /// Main search function that queries a web search provider and returns standardized results
 ///
@@ -54,7 +104,7 @@ pub use types::{DebugOptions, SearchOptions, SearchProvider, SearchResult};
 ///
 /// # Examples
 ///
-/// ```rust
+/// ```rust,no_run
 /// use websearch::{web_search, providers::google::GoogleProvider, SearchOptions};
 ///
 /// # #[tokio::main]
@@ -69,6 +119,79 @@ pub use types::{DebugOptions, SearchOptions, SearchProvider, SearchResult};
 /// # }
 /// ```
 pub async fn web_search(options: SearchOptions) -> Result<Vec<SearchResult>> {
+    web_search_response(options)
+        .await
+        .map(|response| response.results)
+}
+
+/// Like [`web_search`], but also returns rich results (answer boxes, knowledge panels, ...)
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use websearch::{web_search_response, providers::SerpApiProvider, RichResult, SearchOptions};
+///
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let response = web_search_response(SearchOptions {
+///     query: "height of mount everest".to_string(),
+///     provider: Box::new(SerpApiProvider::new("api_key")?),
+///     ..Default::default()
+/// }).await?;
+///
+/// for rich in &response.rich_results {
+///     if let RichResult::AnswerBox { answer: Some(answer), .. } = rich {
+///         println!("Answer: {answer}");
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub async fn web_search_response(options: SearchOptions) -> Result<SearchResponse> {
+    correlation::scope(options.correlation_id.clone(), search_response(options)).await
+}
+
+/// Search several providers at once with `strategy`, in one call
+///
+/// A shortcut for building a [`MultiProviderConfig`](multi_provider::MultiProviderConfig)
+/// with default settings and searching it once. Keep a
+/// [`MultiProviderSearch`](multi_provider::MultiProviderSearch) instead to reuse it across
+/// searches, track statistics or change the settings.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use websearch::{multi_search, multi_provider::MultiProviderStrategy, providers::*};
+///
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let results = multi_search(
+///     "rust programming",
+///     vec![
+///         Box::new(DuckDuckGoProvider::new()),
+///         Box::new(BraveProvider::new("api_key")?),
+///     ],
+///     MultiProviderStrategy::Aggregate,
+/// )
+/// .await?;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn multi_search(
+    query: impl Into<SearchQuery>,
+    providers: Vec<Box<dyn SearchProvider>>,
+    strategy: multi_provider::MultiProviderStrategy,
+) -> Result<Vec<SearchResult>> {
+    let config = providers.into_iter().fold(
+        multi_provider::MultiProviderConfig::new(strategy),
+        |config, provider| config.add_provider(provider),
+    );
+    multi_provider::MultiProviderSearch::new(config)
+        .search(&query.into())
+        .await
+}
+
+async fn search_response(options: SearchOptions) -> Result<SearchResponse> {
     use error::SearchError;
     use utils::debug;
 
@@ -78,6 +201,16 @@ pub async fn web_search(options: SearchOptions) -> Result<Vec<SearchResult>> {
             "A search query or ID list (for Arxiv) is required".to_string(),
         ));
     }
+    if !options.provider.supports_mode(options.mode) {
+        return Err(SearchError::InvalidInput(format!(
+            "{} doesn't support {} search",
+            options.provider.name(),
+            options.mode
+        )));
+    }
+    if let Some(location) = &options.location {
+        location.validate()?;
+    }
 
     // Log search parameters if debugging is enabled
     debug::log(
@@ -90,28 +223,153 @@ pub async fn web_search(options: SearchOptions) -> Result<Vec<SearchResult>> {
         ),
     );
 
-    // Perform the search
-    match options.provider.search(&options).await {
-        Ok(results) => {
+    if options.dry_run {
+        let (result, planned_requests) = dry_run::plan(limits::with_provider(
+            options.provider.name(),
+            options.provider.search_response(&options),
+        ))
+        .await;
+        // A provider that fails before building a request reports why
+        if planned_requests.is_empty() {
+            result?;
+        }
+        return Ok(SearchResponse {
+            planned_requests,
+            ..Default::default()
+        });
+    }
+
+    // The caller's timeout covers the search and any deeper pages fetched for it
+    let deadline = options
+        .effective_timeout()
+        .map(|timeout| tokio::time::Instant::now() + timeout);
+    match fetch(&options, &options, deadline).await {
+        Ok(mut response) => {
+            let returned = response.results.len();
+            let fetched = fetched_urls(&response.results);
+            filter_results(&options, &mut response.results);
+            if options.exclude_urls.is_some() && options.provider.supports_pagination() {
+                // Up to as many results as the provider returned before exclusion
+                let wanted = options
+                    .max_results
+                    .map_or(returned, |max| returned.min(max as usize));
+                backfill_unseen(&options, wanted, fetched, deadline, &mut response.results).await;
+            }
+            response.results = processors::apply(&options.processors, response.results);
             debug::log_response(
                 &options.debug,
-                &format!("Received {} results", results.len()),
+                &format!("Received {} results", response.results.len()),
             );
-            Ok(results)
+            Ok(response)
+        }
+        // Returned as-is so callers can match on it and back off or switch provider
+        Err(error @ SearchError::BotDetected { .. }) => {
+            debug::log(&options.debug, "Search error", &error.to_string());
+            Err(error)
         }
+        // Wrapped with troubleshooting hints; the provider's error stays available as the
+        // source
         Err(error) => {
             let troubleshooting = get_troubleshooting_info(options.provider.name(), &error);
-            let detailed_error = format!(
-                "Search with provider '{}' failed: {}\n\nTroubleshooting: {}",
-                options.provider.name(),
-                error,
-                troubleshooting
-            );
+            let error = SearchError::Provider {
+                provider: options.provider.name().to_string(),
+                source: Box::new(error),
+                troubleshooting,
+            };
+
+            debug::log(&options.debug, "Search error", &error.report());
+            Err(error)
+        }
+    }
+}
+
+/// One request to `options.provider` with `request`'s parameters, failing with a timeout
+/// once `deadline` passes; waiting in the provider's queue doesn't count towards it
+async fn fetch(
+    options: &SearchOptions,
+    request: &SearchOptions,
+    deadline: Option<tokio::time::Instant>,
+) -> Result<SearchResponse> {
+    queue::wait_turn(options.provider.name(), options.priority).await?;
+    let _permit = limits::acquire().await;
+    let search = limits::with_provider(
+        options.provider.name(),
+        limits::with_max_response_bytes(
+            options.max_response_bytes,
+            options.provider.search_response(request),
+        ),
+    );
+    match deadline {
+        Some(deadline) => tokio::time::timeout_at(deadline, search)
+            .await
+            .unwrap_or(Err(SearchError::Timeout {
+                timeout_ms: options.effective_timeout().unwrap_or_default().as_millis() as u64,
+            })),
+        None => search.await,
+    }
+}
 
-            debug::log(&options.debug, "Search error", &detailed_error);
-            Err(SearchError::ProviderError(detailed_error))
+/// Client-side limits and filters for the options the provider may not have applied
+fn filter_results(options: &SearchOptions, results: &mut Vec<SearchResult>) {
+    limits::truncate_snippets(results, options.max_snippet_chars);
+    limits::strip_raw(results, options.include_raw);
+    limits::drop_kinds(results, &options.exclude_kinds);
+    safe_search::enforce(
+        options.provider.as_ref(),
+        options.safe_search.as_ref(),
+        results,
+    );
+    domains::enforce(options.provider.as_ref(), options, results);
+    freshness::enforce(options.provider.as_ref(), options.freshness, results);
+    language::enforce(
+        options.language.as_deref(),
+        options.require_language,
+        results,
+    );
+    seen::enforce(options.exclude_urls.as_ref(), results);
+}
+
+/// Canonical URLs of the results a provider returned, before any filtering
+fn fetched_urls(results: &[SearchResult]) -> HashSet<String> {
+    results
+        .iter()
+        .map(|result| processors::dedup_key(&result.url))
+        .collect()
+}
+
+/// Fetch the pages after `options.page` until `results` holds `wanted` results
+///
+/// `fetched` holds the URLs the provider returned so far. Stops early when a page fails,
+/// times out at `deadline`, or adds no new URLs (it came back empty or repeated earlier
+/// pages), keeping what was found so far.
+async fn backfill_unseen(
+    options: &SearchOptions,
+    wanted: usize,
+    mut fetched: HashSet<String>,
+    deadline: Option<tokio::time::Instant>,
+    results: &mut Vec<SearchResult>,
+) {
+    let mut query = SearchQuery::from(options);
+    let page = options.page.unwrap_or(1);
+    for next in page + 1..=page + seen::MAX_BACKFILL_PAGES {
+        if results.len() >= wanted {
+            break;
+        }
+        query.page = Some(next);
+        let mut more = match fetch(options, &query.to_search_options(), deadline).await {
+            Ok(response) => response.results,
+            Err(_) => break,
+        };
+        // Judged before filtering: a page of only excluded URLs is still progress
+        let before = fetched.len();
+        fetched.extend(fetched_urls(&more));
+        if fetched.len() == before {
+            break;
         }
+        filter_results(options, &mut more);
+        seen::extend_unseen(results, more);
     }
+    results.truncate(wanted);
 }
 
 /// Get provider-specific troubleshooting information based on error
@@ -145,6 +403,12 @@ fn get_troubleshooting_info(provider_name: &str, error: &SearchError) -> String
             suggestions =
                 "The search provider is experiencing server issues. Try again later.".to_string();
         }
+        SearchError::BotDetected { .. } => {
+            suggestions = "The provider is challenging requests as automated traffic. Back off before retrying, change the user agent, or switch to another provider.".to_string();
+        }
+        SearchError::RobotsDisallowed { .. } => {
+            suggestions = "The site's robots.txt does not allow this request. Disable robots.txt checks on the provider only if you are permitted to crawl it.".to_string();
+        }
         _ => {}
     }
 
@@ -190,74 +454,98 @@ fn get_troubleshooting_info(provider_name: &str, error: &SearchError) -> String
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::testing::MockProvider;
     use crate::types::*;
     use async_trait::async_trait;
 
-    // Mock provider for testing
     #[derive(Debug)]
-    struct MockProvider {
-        name: String,
-        should_error: bool,
-        error_type: Option<SearchError>,
-        results: Vec<SearchResult>,
-    }
+    struct SlowProvider;
 
-    impl MockProvider {
-        fn new(name: &str) -> Self {
-            Self {
-                name: name.to_string(),
-                should_error: false,
-                error_type: None,
-                results: vec![
-                    SearchResult {
-                        title: "Test Result 1".to_string(),
-                        url: "https://example.com/1".to_string(),
-                        snippet: Some("Test content 1".to_string()),
-                        domain: None,
-                        published_date: None,
-                        provider: Some(name.to_string()),
-                        raw: None,
-                    },
-                    SearchResult {
-                        title: "Test Result 2".to_string(),
-                        url: "https://example.com/2".to_string(),
-                        snippet: Some("Test content 2".to_string()),
-                        domain: None,
-                        published_date: None,
-                        provider: Some(name.to_string()),
-                        raw: None,
-                    },
-                ],
-            }
+    #[async_trait]
+    impl SearchProvider for SlowProvider {
+        fn name(&self) -> &str {
+            "slow"
         }
 
-        fn with_error(mut self, error: SearchError) -> Self {
-            self.should_error = true;
-            self.error_type = Some(error);
-            self
+        async fn search(&self, _options: &SearchOptions) -> Result<Vec<SearchResult>> {
+            tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+            Ok(Vec::new())
         }
+    }
 
-        fn with_results(mut self, results: Vec<SearchResult>) -> Self {
-            self.results = results;
-            self
-        }
+    #[tokio::test]
+    async fn test_web_search_respects_timeout() {
+        let options = SearchOptions {
+            query: "test query".to_string(),
+            request_timeout: Some(std::time::Duration::from_millis(20)),
+            provider: Box::new(SlowProvider),
+            ..Default::default()
+        };
+
+        let error = web_search(options).await.unwrap_err();
+        assert!(error
+            .root_cause()
+            .to_string()
+            .contains("timed out after 20ms"));
     }
 
-    #[async_trait]
-    impl SearchProvider for MockProvider {
-        fn name(&self) -> &str {
-            &self.name
-        }
+    #[tokio::test]
+    #[allow(deprecated)]
+    async fn test_web_search_respects_deprecated_timeout_ms() {
+        let options = SearchOptions {
+            query: "test query".to_string(),
+            timeout: Some(30),
+            provider: Box::new(SlowProvider),
+            ..Default::default()
+        };
 
-        async fn search(&self, _options: &SearchOptions) -> Result<Vec<SearchResult>> {
-            if self.should_error {
-                Err(self
-                    .error_type
-                    .clone()
-                    .unwrap_or(SearchError::Other("Mock error".to_string())))
-            } else {
-                Ok(self.results.clone())
-            }
+        let error = web_search(options).await.unwrap_err();
+        assert!(error
+            .root_cause()
+            .to_string()
+            .contains("timed out after 30ms"));
+    }
+
+    #[tokio::test]
+    async fn test_default_health_check() {
+        let health = MockProvider::new("ok").health_check().await;
+        assert_eq!(health.provider, "ok");
+        assert_eq!(health.status, HealthStatus::Healthy);
+
+        let health = MockProvider::new("bad-key")
+            .with_error(SearchError::HttpError {
+                message: "Unauthorized".to_string(),
+                status_code: Some(401),
+                response_body: None,
+            })
+            .health_check()
+            .await;
+        assert_eq!(health.status, HealthStatus::AuthFailed);
+        assert!(health.message.unwrap().contains("Unauthorized"));
+    }
+
+    #[test]
+    fn test_http_status_classes() {
+        let status = |code: u16| SearchError::HttpError {
+            message: format!("HTTP {code}"),
+            status_code: Some(code),
+            response_body: None,
+        };
+
+        // 402 is an exhausted plan, not a bad key: another provider may still answer
+        for (code, class, health) in [
+            (401, ErrorClass::Misconfigured, HealthStatus::AuthFailed),
+            (402, ErrorClass::Transient, HealthStatus::RateLimited),
+            (403, ErrorClass::Misconfigured, HealthStatus::AuthFailed),
+            (429, ErrorClass::Transient, HealthStatus::RateLimited),
+            (500, ErrorClass::Transient, HealthStatus::Unavailable),
+        ] {
+            assert_eq!(status(code).class(), class, "class of {code}");
+            assert_eq!(
+                HealthStatus::from_error(&status(code)),
+                health,
+                "health of {code}"
+            );
         }
     }
 
@@ -272,9 +560,84 @@ mod tests {
 
         let results = web_search(options).await.unwrap();
         assert_eq!(results.len(), 2);
-        assert_eq!(results[0].title, "Test Result 1");
-        assert_eq!(results[0].url, "https://example.com/1");
-        assert_eq!(results[0].provider, Some("test".to_string()));
+        assert_eq!(results[0].title, "test Result 1");
+        assert_eq!(results[0].url, "https://test.com/1");
+        assert_eq!(results[0].provider.as_deref(), Some("test"));
+    }
+
+    #[tokio::test]
+    async fn test_ads_are_excluded_unless_asked_for() {
+        let provider = MockProvider::new("test").with_results(vec![
+            SearchResult {
+                url: "https://example.com/".to_string(),
+                ..Default::default()
+            },
+            SearchResult {
+                url: "https://ads.example.com/".to_string(),
+                kind: ResultKind::Ad,
+                ..Default::default()
+            },
+        ]);
+
+        let results = web_search(SearchOptions {
+            query: "test".to_string(),
+            provider: Box::new(provider.clone()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].kind, ResultKind::Organic);
+
+        let results = web_search(SearchOptions {
+            query: "test".to_string(),
+            exclude_kinds: Vec::new(),
+            provider: Box::new(provider),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+        assert_eq!(results.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_multi_search() {
+        let results = multi_search(
+            "test query",
+            vec![
+                Box::new(MockProvider::new("first")),
+                Box::new(MockProvider::new("second")),
+            ],
+            multi_provider::MultiProviderStrategy::Aggregate,
+        )
+        .await
+        .unwrap();
+        assert_eq!(results.len(), 4);
+
+        let results = multi_search(
+            SearchQuery {
+                max_results: Some(1),
+                ..SearchQuery::new("test query")
+            },
+            vec![Box::new(MockProvider::new("first"))],
+            multi_provider::MultiProviderStrategy::Failover,
+        )
+        .await
+        .unwrap();
+        assert_eq!(results[0].provider.as_deref(), Some("first"));
+    }
+
+    #[tokio::test]
+    async fn test_web_search_response_defaults_to_plain_results() {
+        let options = SearchOptions {
+            query: "test query".to_string(),
+            provider: Box::new(MockProvider::new("test")),
+            ..Default::default()
+        };
+
+        let response = web_search_response(options).await.unwrap();
+        assert_eq!(response.results.len(), 2);
+        assert!(response.rich_results.is_empty());
     }
 
     #[tokio::test]
@@ -311,13 +674,49 @@ mod tests {
 
         let result = web_search(options).await;
         assert!(result.is_err());
-        match result.unwrap_err() {
-            SearchError::ProviderError(msg) => {
-                assert!(msg.contains("failed"));
-                assert!(msg.contains("authentication issue"));
+        let error = result.unwrap_err();
+        assert!(error.to_string().contains("failed"));
+        match &error {
+            SearchError::Provider {
+                provider,
+                source,
+                troubleshooting,
+            } => {
+                assert_eq!(provider, "test");
+                assert!(matches!(
+                    **source,
+                    SearchError::HttpError {
+                        status_code: Some(401),
+                        ..
+                    }
+                ));
+                assert!(troubleshooting.contains("authentication issue"));
             }
-            _ => panic!("Expected ProviderError"),
+            _ => panic!("Expected Provider error"),
         }
+        let source = std::error::Error::source(&error).unwrap();
+        assert_eq!(source.to_string(), "HTTP request failed: Unauthorized");
+        assert!(!error.to_string().contains("Unauthorized"));
+        assert!(error
+            .report()
+            .contains("\nCaused by: HTTP request failed: Unauthorized"));
+        assert!(matches!(error.root_cause(), SearchError::HttpError { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_web_search_bot_detected_is_not_wrapped() {
+        let provider = MockProvider::new("test").with_error(SearchError::BotDetected {
+            provider: "test".to_string(),
+            hint: "challenge page".to_string(),
+        });
+        let options = SearchOptions {
+            query: "test query".to_string(),
+            provider: Box::new(provider),
+            ..Default::default()
+        };
+
+        let result = web_search(options).await;
+        assert!(matches!(result, Err(SearchError::BotDetected { .. })));
     }
 
     #[tokio::test]
@@ -418,8 +817,9 @@ mod tests {
                 snippet: Some("Content 1".to_string()),
                 domain: None,
                 published_date: None,
-                provider: Some("test".to_string()),
+                provider: Some("test".into()),
                 raw: None,
+                ..Default::default()
             },
             SearchResult {
                 title: "Result 2".to_string(),
@@ -427,8 +827,9 @@ mod tests {
                 snippet: Some("Content 2".to_string()),
                 domain: None,
                 published_date: None,
-                provider: Some("test".to_string()),
+                provider: Some("test".into()),
                 raw: None,
+                ..Default::default()
             },
             SearchResult {
                 title: "Result 3".to_string(),
@@ -436,8 +837,9 @@ mod tests {
                 snippet: Some("Content 3".to_string()),
                 domain: None,
                 published_date: None,
-                provider: Some("test".to_string()),
+                provider: Some("test".into()),
                 raw: None,
+                ..Default::default()
             },
         ];
 
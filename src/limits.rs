@@ -0,0 +1,360 @@
+//! Process-wide limit on in-flight provider requests, adaptive throttling, and per-search
+//! response limits
+//!
+//! Every provider search made through [`crate::web_search`], [`crate::web_search_batch`]
+//! or [`crate::multi_provider::MultiProviderSearch`] first takes a slot from a shared
+//! limit, so bursts of searches from different parts of an application can't open an
+//! unbounded number of connections. Per-call limits (the batch `concurrency` argument,
+//! [`MultiProviderConfig::with_max_concurrent`](crate::multi_provider::MultiProviderConfig::with_max_concurrent))
+//! still apply on top of it.
+//!
+//! There is no limit by default. Set one with [`set_max_concurrent_requests`] or the
+//! `WEBSEARCH_MAX_CONCURRENT_REQUESTS` environment variable.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use websearch::limits;
+//!
+//! limits::set_max_concurrent_requests(Some(16));
+//! assert_eq!(limits::max_concurrent_requests(), Some(16));
+//! ```
+//!
+//! Providers that answer these searches with HTTP 429 twice in a row are throttled
+//! automatically: from then on each 429 doubles the delay between requests to that provider
+//! (starting at one second, up to a minute, or longer if the response asks for it with
+//! `Retry-After`), and each successful response shortens it again by a quarter second. [`throttle_delay`] and
+//! [`ProviderStats::throttle_delay_ms`](crate::multi_provider::ProviderStats::throttle_delay_ms)
+//! report the current delay.
+//!
+//! The response limits (`max_response_bytes` and `max_snippet_chars` in
+//! [`SearchOptions`](crate::SearchOptions)) apply to a single search instead.
+
+use crate::{
+    error::{SearchError, SearchResult as Result},
+    highlight,
+    types::{ResultKind, SearchResult},
+};
+use reqwest::{Response, StatusCode};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+use std::time::Duration;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tokio::time::Instant;
+use tracing::Instrument;
+
+/// Environment variable read for the initial limit
+pub const MAX_CONCURRENT_REQUESTS_ENV: &str = "WEBSEARCH_MAX_CONCURRENT_REQUESTS";
+
+struct Limit {
+    max: usize,
+    semaphore: Arc<Semaphore>,
+}
+
+fn limit() -> &'static RwLock<Option<Limit>> {
+    static LIMIT: OnceLock<RwLock<Option<Limit>>> = OnceLock::new();
+    LIMIT.get_or_init(|| {
+        let max = std::env::var(MAX_CONCURRENT_REQUESTS_ENV)
+            .ok()
+            .and_then(|value| match value.trim().parse() {
+                Ok(max) => Some(max),
+                Err(_) => {
+                    log::warn!("Ignoring {MAX_CONCURRENT_REQUESTS_ENV}={value}: not a number");
+                    None
+                }
+            });
+        RwLock::new(max.map(new_limit))
+    })
+}
+
+fn new_limit(max: usize) -> Limit {
+    let max = max.max(1);
+    Limit {
+        max,
+        semaphore: Arc::new(Semaphore::new(max)),
+    }
+}
+
+/// Allow at most `max` provider requests in flight across the process (`None` for no limit)
+///
+/// Requests already in flight keep their slot; the new limit applies to requests started
+/// afterwards. A limit of zero is treated as one.
+pub fn set_max_concurrent_requests(max: Option<usize>) {
+    *limit().write().unwrap() = max.map(new_limit);
+}
+
+/// The current process-wide limit, if any
+pub fn max_concurrent_requests() -> Option<usize> {
+    limit().read().unwrap().as_ref().map(|limit| limit.max)
+}
+
+/// Wait for a request slot; hold the returned permit until the request completes
+pub(crate) async fn acquire() -> Option<OwnedSemaphorePermit> {
+    let semaphore = limit().read().unwrap().as_ref()?.semaphore.clone();
+    Some(
+        semaphore
+            .acquire_owned()
+            .await
+            .expect("request limit semaphore is never closed"),
+    )
+}
+
+/// Consecutive 429s from a provider before it is throttled
+const THROTTLE_AFTER_429S: u32 = 2;
+/// Delay once throttling starts
+const THROTTLE_INITIAL_DELAY: Duration = Duration::from_secs(1);
+/// Longest delay reached by doubling
+const THROTTLE_MAX_DELAY: Duration = Duration::from_secs(60);
+/// How much each successful response shortens the delay
+const THROTTLE_RECOVERY_STEP: Duration = Duration::from_millis(250);
+
+#[derive(Default)]
+struct Throttle {
+    consecutive_429s: u32,
+    delay: Duration,
+    /// Earliest time the next request may be sent
+    next_slot: Option<Instant>,
+}
+
+fn throttles() -> &'static Mutex<HashMap<String, Throttle>> {
+    static THROTTLES: OnceLock<Mutex<HashMap<String, Throttle>>> = OnceLock::new();
+    THROTTLES.get_or_init(Default::default)
+}
+
+tokio::task_local! {
+    static THROTTLE_KEY: String;
+    static MAX_RESPONSE_BYTES: usize;
+}
+
+/// Run `search` with its requests throttled as `provider`'s, in a `websearch.provider`
+/// tracing span
+pub(crate) async fn with_provider<F: Future>(provider: &str, search: F) -> F::Output {
+    let span = tracing::debug_span!("websearch.provider", provider);
+    THROTTLE_KEY
+        .scope(provider.to_string(), search.instrument(span))
+        .await
+}
+
+/// The provider whose search is running, if the request is made within [`with_provider`]
+pub(crate) fn throttle_key() -> Option<String> {
+    THROTTLE_KEY.try_with(String::clone).ok()
+}
+
+/// Wait until the next request for `key` may be sent
+pub(crate) async fn throttle(key: &str) {
+    let wait_until = {
+        let mut throttles = throttles().lock().unwrap();
+        let Some(throttle) = throttles.get_mut(key).filter(|t| !t.delay.is_zero()) else {
+            return;
+        };
+        // Reserve a slot, so concurrent requests are spaced out rather than sent together
+        let slot = throttle
+            .next_slot
+            .map_or(Instant::now(), |next| next.max(Instant::now()));
+        throttle.next_slot = Some(slot + throttle.delay);
+        slot
+    };
+    tokio::time::sleep_until(wait_until).await;
+}
+
+/// Adjust the delay for `key` after a response: longer on 429, shorter on success
+pub(crate) fn record_response(key: &str, response: &Response) {
+    let status = response.status();
+    let mut throttles = throttles().lock().unwrap();
+    if status == StatusCode::TOO_MANY_REQUESTS {
+        let throttle = throttles.entry(key.to_string()).or_default();
+        throttle.consecutive_429s += 1;
+        if throttle.consecutive_429s < THROTTLE_AFTER_429S {
+            return;
+        }
+        let doubled = (throttle.delay * 2).clamp(THROTTLE_INITIAL_DELAY, THROTTLE_MAX_DELAY);
+        throttle.delay = retry_after(response).map_or(doubled, |wait| wait.max(doubled));
+        log::warn!(
+            "{key} is rate limiting requests; waiting {:?} between requests",
+            throttle.delay
+        );
+    } else if status.is_success() {
+        if let Some(throttle) = throttles.get_mut(key) {
+            throttle.consecutive_429s = 0;
+            throttle.delay = throttle.delay.saturating_sub(THROTTLE_RECOVERY_STEP);
+            if throttle.delay.is_zero() {
+                throttles.remove(key);
+            }
+        }
+    }
+}
+
+/// `Retry-After` given in seconds (HTTP dates aren't supported)
+fn retry_after(response: &Response) -> Option<Duration> {
+    let value = response.headers().get(reqwest::header::RETRY_AFTER)?;
+    value
+        .to_str()
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+        .map(Duration::from_secs)
+}
+
+/// Current delay between requests to a provider, zero when not throttled
+pub fn throttle_delay(provider: &str) -> Duration {
+    throttles()
+        .lock()
+        .unwrap()
+        .get(provider)
+        .map_or(Duration::ZERO, |throttle| throttle.delay)
+}
+
+/// Run `search` with provider responses capped at `max_bytes`
+pub(crate) async fn with_max_response_bytes<F: Future>(
+    max_bytes: Option<usize>,
+    search: F,
+) -> F::Output {
+    match max_bytes {
+        Some(max_bytes) => MAX_RESPONSE_BYTES.scope(max_bytes, search).await,
+        None => search.await,
+    }
+}
+
+/// Read the body of `response` up front, failing once it exceeds the current search's
+/// `max_response_bytes`
+///
+/// Passes the response through untouched when no limit is set.
+pub(crate) async fn limit_response(mut response: Response) -> Result<Response> {
+    let Ok(limit_bytes) = MAX_RESPONSE_BYTES.try_with(|max| *max) else {
+        return Ok(response);
+    };
+    let too_large = SearchError::ResponseTooLarge { limit_bytes };
+    if response
+        .content_length()
+        .is_some_and(|length| length > limit_bytes as u64)
+    {
+        return Err(too_large);
+    }
+
+    let mut builder = http::Response::builder().status(response.status());
+    if let Some(headers) = builder.headers_mut() {
+        *headers = response.headers().clone();
+    }
+
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        if body.len() + chunk.len() > limit_bytes {
+            return Err(too_large);
+        }
+        body.extend_from_slice(&chunk);
+    }
+
+    builder
+        .body(body)
+        .map(Response::from)
+        .map_err(|error| SearchError::Other(format!("Invalid response: {error}")))
+}
+
+/// Cut each snippet down to at most `max_chars` characters
+pub(crate) fn truncate_snippets(results: &mut [SearchResult], max_chars: Option<usize>) {
+    let Some(max_chars) = max_chars else {
+        return;
+    };
+    for result in results.iter_mut() {
+        let Some(snippet) = result.snippet.as_mut() else {
+            continue;
+        };
+        if let Some((end, _)) = snippet.char_indices().nth(max_chars) {
+            snippet.truncate(end);
+            highlight::clip(&mut result.highlights, end);
+        }
+    }
+}
+
+/// Drop each result's provider payload unless `include_raw` is set
+pub(crate) fn strip_raw(results: &mut [SearchResult], include_raw: bool) {
+    if include_raw {
+        return;
+    }
+    for result in results {
+        result.raw = None;
+    }
+}
+
+/// Drop results whose kind is in `exclude_kinds`
+pub(crate) fn drop_kinds(results: &mut Vec<SearchResult>, exclude_kinds: &[ResultKind]) {
+    if !exclude_kinds.is_empty() {
+        results.retain(|result| !exclude_kinds.contains(&result.kind));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_truncate_snippets_on_char_boundaries() {
+        let mut results = vec![
+            SearchResult {
+                snippet: Some("héllo wörld".to_string()),
+                ..Default::default()
+            },
+            SearchResult {
+                snippet: Some("short".to_string()),
+                ..Default::default()
+            },
+            SearchResult::default(),
+        ];
+
+        truncate_snippets(&mut results, Some(7));
+        assert_eq!(results[0].snippet.as_deref(), Some("héllo w"));
+        assert_eq!(results[1].snippet.as_deref(), Some("short"));
+        assert_eq!(results[2].snippet, None);
+    }
+
+    #[test]
+    fn test_throttle_backs_off_on_429_and_recovers() {
+        let response = |status: u16, retry_after: Option<&str>| {
+            let mut response = http::Response::builder().status(status);
+            if let Some(seconds) = retry_after {
+                response = response.header("Retry-After", seconds);
+            }
+            Response::from(response.body("").unwrap())
+        };
+        let key = "throttle-test";
+
+        record_response(key, &response(429, None));
+        assert_eq!(throttle_delay(key), Duration::ZERO);
+        record_response(key, &response(429, None));
+        assert_eq!(throttle_delay(key), Duration::from_secs(1));
+        record_response(key, &response(429, None));
+        assert_eq!(throttle_delay(key), Duration::from_secs(2));
+        record_response(key, &response(429, Some("30")));
+        assert_eq!(throttle_delay(key), Duration::from_secs(30));
+
+        record_response(key, &response(500, None));
+        assert_eq!(throttle_delay(key), Duration::from_secs(30));
+        record_response(key, &response(200, None));
+        assert_eq!(throttle_delay(key), Duration::from_millis(29_750));
+
+        for _ in 0..200 {
+            record_response(key, &response(200, None));
+        }
+        assert_eq!(throttle_delay(key), Duration::ZERO);
+    }
+
+    #[tokio::test]
+    async fn test_limit_response_rejects_large_bodies() {
+        let response = |body: &str| Response::from(http::Response::new(body.to_string()));
+
+        let passed = limit_response(response("0123456789")).await.unwrap();
+        assert_eq!(passed.text().await.unwrap(), "0123456789");
+
+        with_max_response_bytes(Some(8), async {
+            let small = limit_response(response("01234567")).await.unwrap();
+            assert_eq!(small.text().await.unwrap(), "01234567");
+            assert!(matches!(
+                limit_response(response("0123456789")).await,
+                Err(SearchError::ResponseTooLarge { limit_bytes: 8 })
+            ));
+        })
+        .await;
+    }
+}
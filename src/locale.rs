@@ -0,0 +1,495 @@
+//! Locale normalization
+//!
+//! `language` and `region` accept BCP-47 style input (`"en"`, `"en-US"`, `"pt_BR"`,
+//! `"zh-Hant-TW"`) and ISO 3166 country codes (`"US"`, `"gb"`). Every provider API expects
+//! its own spelling: Google wants `lr=lang_en&gl=us`, Brave `search_lang=en&country=US`,
+//! DuckDuckGo `kl=us-en`. [`Locale`] validates the input once and converts it for each
+//! provider, so an invalid locale fails with [`SearchError::InvalidInput`] instead of
+//! being silently ignored by the API. A valid locale one provider doesn't offer fails with
+//! [`SearchError::Unsupported`], which multi-provider searches treat as a reason to try
+//! the next provider rather than to stop.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use websearch::locale::Locale;
+//!
+//! let locale = Locale::from_options(Some("en-GB"), None).unwrap().unwrap();
+//! assert_eq!(locale.google_lr().as_deref(), Some("lang_en"));
+//! assert_eq!(locale.google_gl().as_deref(), Some("uk"));
+//! assert_eq!(locale.duckduckgo_kl().unwrap(), "uk-en");
+//! ```
+
+use crate::error::{SearchError, SearchResult};
+
+/// ISO 639-1 language codes
+const LANGUAGES: &str = "aa ab ae af ak am an ar as av ay az ba be bg bh bi bm bn bo br bs ca \
+    ce ch co cr cs cu cv cy da de dv dz ee el en eo es et eu fa ff fi fj fo fr fy ga gd gl gn \
+    gu gv ha he hi ho hr ht hu hy hz ia id ie ig ii ik io is it iu ja jv ka kg ki kj kk kl km \
+    kn ko kr ks ku kv kw ky la lb lg li ln lo lt lu lv mg mh mi mk ml mn mr ms mt my na nb nd \
+    ne ng nl nn no nr nv ny oc oj om or os pa pi pl ps pt qu rm rn ro ru rw sa sc sd se sg si \
+    sk sl sm sn so sq sr ss st su sv sw ta te tg th ti tk tl tn to tr ts tt tw ty ug uk ur uz \
+    ve vi vo wa wo xh yi yo za zh zu";
+
+/// ISO 3166-1 alpha-2 country codes
+const REGIONS: &str = "AD AE AF AG AI AL AM AO AQ AR AS AT AU AW AX AZ BA BB BD BE BF BG BH BI \
+    BJ BL BM BN BO BQ BR BS BT BV BW BY BZ CA CC CD CF CG CH CI CK CL CM CN CO CR CU CV CW CX \
+    CY CZ DE DJ DK DM DO DZ EC EE EG EH ER ES ET FI FJ FK FM FO FR GA GB GD GE GF GG GH GI GL \
+    GM GN GP GQ GR GS GT GU GW GY HK HM HN HR HT HU ID IE IL IM IN IO IQ IR IS IT JE JM JO JP \
+    KE KG KH KI KM KN KP KR KW KY KZ LA LB LC LI LK LR LS LT LU LV LY MA MC MD ME MF MG MH MK \
+    ML MM MN MO MP MQ MR MS MT MU MV MW MX MY MZ NA NC NE NF NG NI NL NO NP NR NU NZ OM PA PE \
+    PF PG PH PK PL PM PN PR PS PT PW PY QA RE RO RS RU RW SA SB SC SD SE SG SH SI SJ SK SL SM \
+    SN SO SR SS ST SV SX SY SZ TC TD TF TG TH TJ TK TL TM TN TO TR TT TV TW TZ UA UG UM US UY \
+    UZ VA VC VE VG VI VN VU WF WS YE YT ZA ZM ZW";
+
+/// Values of Brave's `search_lang` parameter
+const BRAVE_LANGUAGES: &[&str] = &[
+    "ar", "eu", "bn", "bg", "ca", "zh-hans", "zh-hant", "hr", "cs", "da", "nl", "en", "en-gb",
+    "et", "fi", "fr", "gl", "de", "gu", "he", "hi", "hu", "is", "it", "jp", "kn", "ko", "lv", "lt",
+    "ms", "ml", "mr", "nb", "pl", "pt-br", "pt-pt", "pa", "ro", "ru", "sr", "sk", "sl", "es", "sv",
+    "ta", "te", "th", "tr", "uk", "vi",
+];
+
+/// DuckDuckGo `kl` region codes (`{region}-{language}`)
+const DUCKDUCKGO_REGIONS: &[&str] = &[
+    "ar-es", "au-en", "at-de", "be-fr", "be-nl", "br-pt", "bg-bg", "ca-en", "ca-fr", "ct-ca",
+    "cl-es", "cn-zh", "co-es", "hr-hr", "cz-cs", "dk-da", "ee-et", "fi-fi", "fr-fr", "de-de",
+    "gr-el", "hk-tzh", "hu-hu", "in-en", "id-id", "id-en", "ie-en", "il-he", "it-it", "jp-jp",
+    "kr-kr", "lv-lv", "lt-lt", "xl-es", "my-ms", "my-en", "mx-es", "nl-nl", "nz-en", "no-no",
+    "pe-es", "ph-en", "ph-tl", "pl-pl", "pt-pt", "ro-ro", "ru-ru", "sg-en", "sk-sk", "sl-sl",
+    "za-en", "es-es", "se-sv", "ch-de", "ch-fr", "ch-it", "tw-tzh", "th-th", "tr-tr", "ua-uk",
+    "uk-en", "us-en", "ue-es", "ve-es", "vn-vi", "xa-ar", "xa-en", "wt-wt",
+];
+
+/// Countries DuckDuckGo spells differently from ISO 3166, as (ISO, DuckDuckGo)
+const DUCKDUCKGO_COUNTRIES: &[(&str, &str)] = &[("GB", "uk"), ("SI", "sl")];
+
+/// A validated language and/or region
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Locale {
+    /// Lowercase ISO 639-1 language code, e.g. `"en"`
+    pub language: Option<String>,
+    /// Titlecase ISO 15924 script code, e.g. `"Hant"`
+    pub script: Option<String>,
+    /// Uppercase ISO 3166-1 country code, e.g. `"US"`
+    pub region: Option<String>,
+}
+
+impl Locale {
+    /// Parse a BCP-47 style tag such as `"en"`, `"en-US"`, `"pt_BR"` or `"zh-Hant-TW"`
+    ///
+    /// Variants and extensions after the region are ignored.
+    pub fn parse(tag: &str) -> SearchResult<Self> {
+        let invalid = || SearchError::InvalidInput(format!("Unsupported language tag: {tag}"));
+        let mut subtags = tag.trim().split(['-', '_']);
+
+        let language = subtags.next().unwrap_or_default().to_lowercase();
+        if !LANGUAGES.split_whitespace().any(|code| code == language) {
+            return Err(invalid());
+        }
+
+        let mut next = subtags.next();
+        let script = next
+            .filter(|s| s.len() == 4 && s.chars().all(|c| c.is_ascii_alphabetic()))
+            .map(|s| {
+                let (first, rest) = s.split_at(1);
+                format!("{}{}", first.to_uppercase(), rest.to_lowercase())
+            });
+        if script.is_some() {
+            next = subtags.next();
+        }
+        let region = next
+            .filter(|s| s.len() == 2)
+            .map(parse_region)
+            .transpose()?;
+
+        Ok(Self {
+            language: Some(language),
+            script,
+            region,
+        })
+    }
+
+    /// Combine the `language` and `region` search options
+    ///
+    /// `region` overrides a region carried by `language` (as in `"en-US"`). It may also be a
+    /// DuckDuckGo `kl` code such as `"us-en"`, whose country is used (none for `"wt-wt"`).
+    /// Returns `None` when neither is set.
+    pub fn from_options(
+        language: Option<&str>,
+        region: Option<&str>,
+    ) -> SearchResult<Option<Self>> {
+        let mut locale = match language {
+            Some(language) => Self::parse(language)?,
+            None => Self {
+                language: None,
+                script: None,
+                region: None,
+            },
+        };
+        if let Some(region) = region {
+            locale.region = match duckduckgo_country(region) {
+                Some(country) => country,
+                None => Some(parse_region(region)?),
+            };
+        }
+        Ok((locale.language.is_some() || locale.region.is_some()).then_some(locale))
+    }
+
+    /// Google Custom Search `lr` value, e.g. `"lang_en"` or `"lang_zh-TW"`
+    pub fn google_lr(&self) -> Option<String> {
+        let language = self.language.as_deref()?;
+        Some(match language {
+            "zh" => format!(
+                "lang_zh-{}",
+                if self.is_traditional_chinese() {
+                    "TW"
+                } else {
+                    "CN"
+                }
+            ),
+            _ => format!("lang_{language}"),
+        })
+    }
+
+    /// Google interface language (`hl`), as used by SerpAPI's Google engines
+    pub fn google_hl(&self) -> Option<String> {
+        let language = self.language.as_deref()?;
+        Some(match language {
+            "zh" => format!(
+                "zh-{}",
+                if self.is_traditional_chinese() {
+                    "tw"
+                } else {
+                    "cn"
+                }
+            ),
+            _ => language.to_string(),
+        })
+    }
+
+    /// Google country (`gl`), lowercase with `uk` for Great Britain
+    pub fn google_gl(&self) -> Option<String> {
+        let region = self.region.as_deref()?;
+        Some(match region {
+            "GB" => "uk".to_string(),
+            _ => region.to_lowercase(),
+        })
+    }
+
+    /// Brave `search_lang` value, failing for languages Brave doesn't index
+    pub fn brave_search_lang(&self) -> SearchResult<Option<String>> {
+        let Some(language) = self.language.as_deref() else {
+            return Ok(None);
+        };
+        let region = self.region.as_deref();
+        let value = match language {
+            "ja" => "jp".to_string(),
+            "zh" if self.is_traditional_chinese() => "zh-hant".to_string(),
+            "zh" => "zh-hans".to_string(),
+            "pt" if region == Some("PT") => "pt-pt".to_string(),
+            "pt" => "pt-br".to_string(),
+            "en" if region == Some("GB") => "en-gb".to_string(),
+            "no" | "nn" => "nb".to_string(),
+            _ => language.to_string(),
+        };
+        if !BRAVE_LANGUAGES.contains(&value.as_str()) {
+            return Err(SearchError::Unsupported(format!(
+                "Brave does not support search language: {language}"
+            )));
+        }
+        Ok(Some(value))
+    }
+
+    /// Brave `country` value (uppercase country code)
+    pub fn brave_country(&self) -> Option<String> {
+        self.region.clone()
+    }
+
+    /// DuckDuckGo `kl` value such as `"us-en"`
+    ///
+    /// Without a region this is `wt-wt` (no region) unless the language has a region of the
+    /// same name (`de` → `de-de`). Fails for regions DuckDuckGo doesn't offer.
+    pub fn duckduckgo_kl(&self) -> SearchResult<String> {
+        let language = self.language.as_deref();
+        let Some(region) = self.region.as_deref() else {
+            let home = language.and_then(|language| {
+                DUCKDUCKGO_REGIONS
+                    .iter()
+                    .find(|kl| **kl == format!("{language}-{language}"))
+            });
+            return Ok(home.copied().unwrap_or("wt-wt").to_string());
+        };
+
+        let unsupported =
+            || SearchError::Unsupported(format!("DuckDuckGo does not support region: {region}"));
+        let region = duckduckgo_region(region).ok_or_else(unsupported)?;
+        let candidates: Vec<&str> = DUCKDUCKGO_REGIONS
+            .iter()
+            .copied()
+            .filter(|kl| kl.split('-').next() == Some(region.as_str()))
+            .collect();
+        let preferred = language.and_then(|language| {
+            candidates
+                .iter()
+                .find(|kl| kl.ends_with(&format!("-{language}")))
+        });
+        preferred
+            .or(candidates.first())
+            .map(|kl| kl.to_string())
+            .ok_or_else(unsupported)
+    }
+
+    /// SearXNG `language` value, e.g. `"en"` or `"en-US"`
+    pub fn searxng_language(&self) -> Option<String> {
+        let language = self.language.as_deref()?;
+        Some(match &self.region {
+            Some(region) => format!("{language}-{region}"),
+            None => language.to_string(),
+        })
+    }
+
+    fn is_traditional_chinese(&self) -> bool {
+        self.script.as_deref() == Some("Hant")
+            || matches!(self.region.as_deref(), Some("TW" | "HK" | "MO"))
+    }
+}
+
+/// DuckDuckGo `kl` value for the `language` and `region` search options
+///
+/// A `region` that already is a `kl` code (e.g. `"us-en"`) is passed through. Returns
+/// `None` when neither option is set.
+pub fn duckduckgo_kl(language: Option<&str>, region: Option<&str>) -> SearchResult<Option<String>> {
+    match region {
+        Some(region) if DUCKDUCKGO_REGIONS.contains(&region.to_lowercase().as_str()) => {
+            Ok(Some(region.to_lowercase()))
+        }
+        region => Locale::from_options(language, region)?
+            .map(|locale| locale.duckduckgo_kl())
+            .transpose(),
+    }
+}
+
+/// SearXNG `language` value for the `language` search option
+///
+/// SearXNG's own `all` and `auto` are passed through; anything else is validated as a
+/// [`Locale`]. Returns `None` when `language` isn't set.
+pub fn searxng_language(language: Option<&str>) -> SearchResult<Option<String>> {
+    match language.map(|language| language.trim().to_lowercase()) {
+        Some(language) if language == "all" || language == "auto" => Ok(Some(language)),
+        _ => Ok(Locale::from_options(language, None)?
+            .as_ref()
+            .and_then(Locale::searxng_language)),
+    }
+}
+
+/// The DuckDuckGo region prefix for an ISO country, or `None` if the prefix DuckDuckGo
+/// would read is another country's
+fn duckduckgo_region(region: &str) -> Option<String> {
+    if let Some((_, code)) = DUCKDUCKGO_COUNTRIES.iter().find(|(iso, _)| *iso == region) {
+        return Some(code.to_string());
+    }
+    // DuckDuckGo's `sl` is Slovenia, not Sierra Leone
+    let code = region.to_lowercase();
+    (!DUCKDUCKGO_COUNTRIES.iter().any(|(_, other)| *other == code)).then_some(code)
+}
+
+/// The ISO country of a DuckDuckGo `kl` code, `Some(None)` for its regions that aren't one
+/// country (`wt-wt`, Arabia, Latin America), or `None` if `kl` isn't a `kl` code
+fn duckduckgo_country(kl: &str) -> Option<Option<String>> {
+    let kl = kl.trim().to_lowercase();
+    if !DUCKDUCKGO_REGIONS.contains(&kl.as_str()) {
+        return None;
+    }
+    let (region, _) = kl.split_once('-')?;
+    Some(match region {
+        "wt" | "xa" | "xl" => None,
+        // Catalan Spain and Spanish US
+        "ct" => Some("ES".to_string()),
+        "ue" => Some("US".to_string()),
+        _ => Some(
+            DUCKDUCKGO_COUNTRIES
+                .iter()
+                .find(|(_, code)| *code == region)
+                .map_or_else(|| region.to_uppercase(), |(iso, _)| iso.to_string()),
+        ),
+    })
+}
+
+/// Google `uule` value locating a search at a point
+///
+/// `a ` followed by the base64 of a text-format location record, the form Google's own
+/// geolocated searches send.
+pub(crate) fn google_uule(latitude: f64, longitude: f64) -> String {
+    use base64::Engine;
+
+    let record = format!(
+        "role:1\nproducer:12\nprovenance:6\nlatlng{{\nlatitude_e7:{}\nlongitude_e7:{}\n}}\n\
+         radius:-1",
+        (latitude * 1e7).round() as i64,
+        (longitude * 1e7).round() as i64
+    );
+    format!(
+        "a {}",
+        base64::engine::general_purpose::STANDARD.encode(record)
+    )
+}
+
+fn parse_region(region: &str) -> SearchResult<String> {
+    let region = match region.trim().to_uppercase().as_str() {
+        "UK" => "GB".to_string(),
+        other => other.to_string(),
+    };
+    if REGIONS.split_whitespace().any(|code| code == region) {
+        Ok(region)
+    } else {
+        Err(SearchError::InvalidInput(format!(
+            "Unsupported region: {region}"
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn locale(language: Option<&str>, region: Option<&str>) -> Locale {
+        Locale::from_options(language, region).unwrap().unwrap()
+    }
+
+    #[test]
+    fn test_parse_bcp47() {
+        let parsed = Locale::parse("zh_hant-tw").unwrap();
+        assert_eq!(parsed.language.as_deref(), Some("zh"));
+        assert_eq!(parsed.script.as_deref(), Some("Hant"));
+        assert_eq!(parsed.region.as_deref(), Some("TW"));
+
+        assert_eq!(
+            locale(Some("en-US"), Some("uk")).region.as_deref(),
+            Some("GB")
+        );
+        assert_eq!(Locale::from_options(None, None).unwrap(), None);
+    }
+
+    #[test]
+    fn test_invalid_locales_are_rejected() {
+        for (language, region) in [
+            (Some("xx"), None),
+            (Some("english"), None),
+            (None, Some("ZZ")),
+        ] {
+            assert!(matches!(
+                Locale::from_options(language, region),
+                Err(SearchError::InvalidInput(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_provider_values() {
+        let us = locale(Some("en-US"), None);
+        assert_eq!(us.google_lr().as_deref(), Some("lang_en"));
+        assert_eq!(us.google_gl().as_deref(), Some("us"));
+        assert_eq!(us.brave_search_lang().unwrap().as_deref(), Some("en"));
+        assert_eq!(us.brave_country().as_deref(), Some("US"));
+        assert_eq!(us.duckduckgo_kl().unwrap(), "us-en");
+        assert_eq!(us.searxng_language().as_deref(), Some("en-US"));
+
+        let taiwan = locale(Some("zh"), Some("TW"));
+        assert_eq!(taiwan.google_lr().as_deref(), Some("lang_zh-TW"));
+        assert_eq!(taiwan.google_hl().as_deref(), Some("zh-tw"));
+        assert_eq!(
+            taiwan.brave_search_lang().unwrap().as_deref(),
+            Some("zh-hant")
+        );
+        assert_eq!(taiwan.duckduckgo_kl().unwrap(), "tw-tzh");
+
+        assert_eq!(
+            locale(Some("ja"), None)
+                .brave_search_lang()
+                .unwrap()
+                .as_deref(),
+            Some("jp")
+        );
+        assert_eq!(locale(Some("de"), None).duckduckgo_kl().unwrap(), "de-de");
+        assert_eq!(locale(Some("en"), None).duckduckgo_kl().unwrap(), "wt-wt");
+        assert_eq!(
+            locale(Some("fr"), Some("CH")).duckduckgo_kl().unwrap(),
+            "ch-fr"
+        );
+    }
+
+    #[test]
+    fn test_google_uule() {
+        use base64::Engine;
+
+        let uule = google_uule(52.52, 13.405);
+        let record = base64::engine::general_purpose::STANDARD
+            .decode(uule.strip_prefix("a ").unwrap())
+            .unwrap();
+        let record = String::from_utf8(record).unwrap();
+        assert!(record.contains("latitude_e7:525200000\nlongitude_e7:134050000"));
+    }
+
+    #[test]
+    fn test_provider_specific_validation() {
+        assert!(matches!(
+            locale(Some("sw"), None).brave_search_lang(),
+            Err(SearchError::Unsupported(_))
+        ));
+        assert!(matches!(
+            locale(None, Some("KE")).duckduckgo_kl(),
+            Err(SearchError::Unsupported(_))
+        ));
+        assert_eq!(
+            duckduckgo_kl(None, Some("US-EN")).unwrap().as_deref(),
+            Some("us-en")
+        );
+        assert_eq!(
+            duckduckgo_kl(Some("fr"), Some("CA")).unwrap().as_deref(),
+            Some("ca-fr")
+        );
+        assert_eq!(duckduckgo_kl(None, None).unwrap(), None);
+    }
+
+    #[test]
+    fn test_duckduckgo_country_spellings() {
+        assert_eq!(locale(None, Some("SI")).duckduckgo_kl().unwrap(), "sl-sl");
+        assert_eq!(locale(None, Some("GB")).duckduckgo_kl().unwrap(), "uk-en");
+        assert!(matches!(
+            locale(None, Some("SL")).duckduckgo_kl(),
+            Err(SearchError::Unsupported(_))
+        ));
+    }
+
+    #[test]
+    fn test_duckduckgo_regions_work_everywhere() {
+        let us = locale(Some("en"), Some("us-en"));
+        assert_eq!(us.region.as_deref(), Some("US"));
+        assert_eq!(us.google_gl().as_deref(), Some("us"));
+        assert_eq!(us.brave_country().as_deref(), Some("US"));
+
+        assert_eq!(locale(None, Some("uk-en")).region.as_deref(), Some("GB"));
+        assert_eq!(locale(None, Some("sl-sl")).region.as_deref(), Some("SI"));
+        assert_eq!(locale(None, Some("ct-ca")).region.as_deref(), Some("ES"));
+        assert_eq!(locale(Some("en-US"), Some("wt-wt")).region, None);
+    }
+
+    #[test]
+    fn test_searxng_language() {
+        assert_eq!(
+            searxng_language(Some("all")).unwrap().as_deref(),
+            Some("all")
+        );
+        assert_eq!(
+            searxng_language(Some("Auto")).unwrap().as_deref(),
+            Some("auto")
+        );
+        assert_eq!(
+            searxng_language(Some("pt_BR")).unwrap().as_deref(),
+            Some("pt-BR")
+        );
+        assert_eq!(searxng_language(None).unwrap(), None);
+        assert!(searxng_language(Some("xx")).is_err());
+    }
+}
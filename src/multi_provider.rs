@@ -1,24 +1,140 @@
 //! Multi-provider search functionality with load balancing and failover
 
 use crate::{
-    error::{SearchError, SearchResult as Result},
-    types::{DebugOptions, SafeSearch, SearchProvider, SearchResult, SortBy, SortOrder},
+    batch::BatchSearchResult,
+    correlation, domains,
+    error::{ErrorClass, SearchError, SearchResult as Result},
+    freshness, language, limits,
+    processors::{self, Consensus, Dedup, ResultProcessor},
+    queue,
+    rerank::Reranker,
+    routing::{
+        AggregateRouting, FailoverRouting, LoadBalanceRouting, RaceRouting, RoutingPlan,
+        RoutingStrategy, SmartRouting,
+    },
+    safe_search, seen,
+    types::{parse_variant, ProviderHit, SearchMode, SearchProvider, SearchQuery, SearchResult},
     utils::debug,
 };
-use std::collections::HashMap;
-use tokio::time::{timeout, Duration};
+use chrono::{DateTime, NaiveDate, Utc};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::future::Future;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use tokio::sync::Semaphore;
+use tokio::time::{timeout, timeout_at, Duration, Instant};
 
 /// Strategy for using multiple providers
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
 pub enum MultiProviderStrategy {
     /// Use providers in sequence until one succeeds
+    ///
+    /// Only [transient](ErrorClass::Transient) failures move on to the next provider: a
+    /// search with invalid input fails at once, since every provider would reject it.
     Failover,
     /// Load balance requests across providers (round-robin)
     LoadBalance,
     /// Query all providers and merge results
     Aggregate,
     /// Use fastest responding provider
+    #[cfg_attr(feature = "clap", value(name = "race", alias = "race-first"))]
     RaceFirst,
+    /// Try the providers suited to the kind of query first (see [`SmartRouting`])
+    #[cfg_attr(feature = "clap", value(name = "smart", alias = "smart-route"))]
+    SmartRoute,
+    /// Route each search with your own [`RoutingStrategy`]
+    #[cfg_attr(feature = "clap", value(skip))]
+    Custom(Arc<dyn RoutingStrategy>),
+}
+
+impl MultiProviderStrategy {
+    /// The routing behind this strategy; failover uses the config's tiers, load balancing
+    /// its stickiness
+    fn routing(&self, config: &MultiProviderConfig) -> Arc<dyn RoutingStrategy> {
+        match self {
+            MultiProviderStrategy::Failover => Arc::new(FailoverRouting {
+                tiers: config.tiers.clone(),
+                parallel: config.parallel_tiers,
+            }),
+            MultiProviderStrategy::LoadBalance if config.sticky_load_balance => {
+                Arc::new(LoadBalanceRouting::sticky())
+            }
+            MultiProviderStrategy::LoadBalance => Arc::new(LoadBalanceRouting::default()),
+            MultiProviderStrategy::Aggregate => Arc::new(AggregateRouting),
+            MultiProviderStrategy::RaceFirst => Arc::new(RaceRouting),
+            MultiProviderStrategy::SmartRoute => Arc::new(SmartRouting::new()),
+            MultiProviderStrategy::Custom(routing) => routing.clone(),
+        }
+    }
+}
+
+impl fmt::Display for MultiProviderStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MultiProviderStrategy::Failover => write!(f, "failover"),
+            MultiProviderStrategy::LoadBalance => write!(f, "load_balance"),
+            MultiProviderStrategy::Aggregate => write!(f, "aggregate"),
+            MultiProviderStrategy::RaceFirst => write!(f, "race_first"),
+            MultiProviderStrategy::SmartRoute => write!(f, "smart_route"),
+            MultiProviderStrategy::Custom(_) => write!(f, "custom"),
+        }
+    }
+}
+
+impl FromStr for MultiProviderStrategy {
+    type Err = SearchError;
+
+    /// Accepts the [`Display`](fmt::Display) names in any case, with `-` or `_`, and the
+    /// short forms `race` and `smart`
+    fn from_str(value: &str) -> Result<Self> {
+        parse_variant(
+            "strategy",
+            value,
+            &[
+                ("failover", MultiProviderStrategy::Failover),
+                ("loadbalance", MultiProviderStrategy::LoadBalance),
+                ("aggregate", MultiProviderStrategy::Aggregate),
+                ("racefirst", MultiProviderStrategy::RaceFirst),
+                ("race", MultiProviderStrategy::RaceFirst),
+                ("smartroute", MultiProviderStrategy::SmartRoute),
+                ("smart", MultiProviderStrategy::SmartRoute),
+            ],
+        )
+    }
+}
+
+/// Estimated price of one request to a provider
+#[derive(Debug, Clone, Copy, PartialEq, Default, serde::Serialize)]
+pub struct CostPerRequest {
+    /// Price in US dollars
+    pub usd: f64,
+}
+
+impl CostPerRequest {
+    pub const FREE: Self = Self { usd: 0.0 };
+
+    pub fn usd(usd: f64) -> Self {
+        Self { usd }
+    }
+
+    /// Cost from a price per 1,000 requests, the unit most APIs publish
+    pub fn per_thousand(usd: f64) -> Self {
+        Self { usd: usd / 1000.0 }
+    }
+
+    /// List price of the entry-level paid tier for a built-in provider (free ones cost nothing)
+    ///
+    /// These are estimates; use [`MultiProviderConfig::with_cost`] to match your own plan.
+    pub fn default_for(provider: &str) -> Self {
+        match provider {
+            "google" | "exa" | "brave" => Self::per_thousand(5.0),
+            "tavily" => Self::per_thousand(8.0),
+            "serpapi" | "google_scholar" => Self::per_thousand(15.0),
+            _ => Self::FREE,
+        }
+    }
 }
 
 /// Configuration for multi-provider searches
@@ -28,8 +144,46 @@ pub struct MultiProviderConfig {
     pub strategy: MultiProviderStrategy,
     pub timeout_per_provider: Duration,
     pub max_concurrent: usize,
+    /// Drop aggregated results whose URL was already returned by another provider
+    pub dedup: bool,
+    /// Results requested from each provider when aggregating (defaults to the query's
+    /// `max_results`); any more a provider returns are dropped
+    pub per_provider_results: Option<u32>,
+    /// Per-provider costs, overriding [`CostPerRequest::default_for`]
+    pub costs: HashMap<String, CostPerRequest>,
+    /// Most a single search (all providers it queries) may spend, in US dollars
+    pub search_budget_usd: Option<f64>,
+    /// Most all searches may spend per UTC day, in US dollars
+    pub daily_budget_usd: Option<f64>,
+    /// Failover tiers: provider names tried tier by tier. Providers not listed form a final
+    /// tier; with no tiers, each provider is its own tier in insertion order (see
+    /// [`FailoverRouting`]).
+    pub tiers: Vec<Vec<String>>,
+    /// Query all providers in a failover tier at once and take the first success
+    pub parallel_tiers: bool,
+    /// Load balance by query instead of round-robin, so a repeated query reaches the same
+    /// provider (see [`LoadBalanceRouting::sticky`])
+    pub sticky_load_balance: bool,
+    /// Overall time limit for a strategy search, on top of the per-provider timeout
+    pub deadline: Option<Duration>,
+    /// Applied in order to the results of every strategy search
+    pub processors: Vec<Arc<dyn ResultProcessor>>,
+    /// Orders aggregated results by how many providers returned them, before any reranker
+    pub consensus: Option<Consensus>,
+    /// Orders aggregated results by relevance to the query
+    pub reranker: Option<Arc<dyn Reranker>>,
+    /// Check every provider's credentials in [`build`](Self::build)
+    pub validate_credentials: bool,
+    /// How long a [disabled](MultiProviderSearch::disabled_providers) provider is skipped
+    /// before it is tried again; `None` keeps it disabled until
+    /// [`enable_provider`](MultiProviderSearch::enable_provider)
+    pub disable_cooldown: Option<Duration>,
 }
 
+/// Default for [`MultiProviderConfig::disable_cooldown`]: long enough not to hammer a
+/// provider with a rejected key, short enough to pick up an exhausted quota being reset
+pub const DEFAULT_DISABLE_COOLDOWN: Duration = Duration::from_secs(15 * 60);
+
 impl MultiProviderConfig {
     pub fn new(strategy: MultiProviderStrategy) -> Self {
         Self {
@@ -37,14 +191,34 @@ impl MultiProviderConfig {
             strategy,
             timeout_per_provider: Duration::from_secs(10),
             max_concurrent: 3,
+            dedup: false,
+            per_provider_results: None,
+            costs: HashMap::new(),
+            search_budget_usd: None,
+            daily_budget_usd: None,
+            tiers: Vec::new(),
+            parallel_tiers: false,
+            sticky_load_balance: false,
+            deadline: None,
+            processors: Vec::new(),
+            consensus: None,
+            reranker: None,
+            validate_credentials: false,
+            disable_cooldown: Some(DEFAULT_DISABLE_COOLDOWN),
         }
     }
 
+    /// Add a provider; each provider's [name](SearchProvider::name) must be unique
     pub fn add_provider(mut self, provider: Box<dyn SearchProvider>) -> Self {
         self.providers.push(provider);
         self
     }
 
+    /// Add a provider that is also used elsewhere (other configs, direct searches)
+    pub fn add_shared_provider(self, provider: Arc<dyn SearchProvider>) -> Self {
+        self.add_provider(Box::new(provider))
+    }
+
     pub fn with_timeout(mut self, timeout: Duration) -> Self {
         self.timeout_per_provider = timeout;
         self
@@ -54,124 +228,834 @@ impl MultiProviderConfig {
         self.max_concurrent = max;
         self
     }
+
+    pub fn with_dedup(mut self, dedup: bool) -> Self {
+        self.dedup = dedup;
+        self
+    }
+
+    pub fn with_per_provider_results(mut self, max_results: u32) -> Self {
+        self.per_provider_results = Some(max_results);
+        self
+    }
+
+    /// Set what one request to `provider` costs
+    pub fn with_cost(mut self, provider: &str, cost: CostPerRequest) -> Self {
+        self.costs.insert(provider.to_string(), cost);
+        self
+    }
+
+    /// Skip paid providers once a single search would spend more than `usd`
+    pub fn with_search_budget(mut self, usd: f64) -> Self {
+        self.search_budget_usd = Some(usd);
+        self
+    }
+
+    /// Skip paid providers once today's searches would spend more than `usd`
+    pub fn with_daily_budget(mut self, usd: f64) -> Self {
+        self.daily_budget_usd = Some(usd);
+        self
+    }
+
+    /// Add the next failover tier, tried only after every provider in earlier tiers failed
+    pub fn with_tier<I, S>(mut self, providers: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.tiers
+            .push(providers.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Finish every strategy search within `deadline`
+    ///
+    /// Aggregate searches return the results that arrived in time (see
+    /// [`MultiProviderSearch::search_response`]); other strategies fail with a timeout.
+    pub fn with_deadline(mut self, deadline: Duration) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Run `processor` on the results of every search, after any earlier processors
+    ///
+    /// Aggregate searches apply processors to the merged results before truncating
+    /// them to `max_results`.
+    pub fn with_processor(mut self, processor: impl ResultProcessor + 'static) -> Self {
+        self.processors.push(Arc::new(processor));
+        self
+    }
+
+    /// Order merged aggregate results by consensus among the providers, e.g.
+    /// `Consensus::default().with_weight("google", 2.0)`
+    ///
+    /// Duplicates are merged whether or not [`dedup`](Self::dedup) is on.
+    pub fn with_consensus_ranking(mut self, consensus: Consensus) -> Self {
+        self.consensus = Some(consensus);
+        self
+    }
+
+    /// Rerank merged aggregate results with `reranker` before processors and truncation
+    ///
+    /// If reranking fails the results keep their merged order.
+    pub fn with_reranker(mut self, reranker: impl Reranker + 'static) -> Self {
+        self.reranker = Some(Arc::new(reranker));
+        self
+    }
+
+    pub fn with_parallel_tiers(mut self, parallel: bool) -> Self {
+        self.parallel_tiers = parallel;
+        self
+    }
+
+    /// Keep each query on one provider when load balancing, for better provider-side caching
+    pub fn with_sticky_load_balance(mut self, sticky: bool) -> Self {
+        self.sticky_load_balance = sticky;
+        self
+    }
+
+    /// Check every provider's credentials when [building](Self::build) the search
+    pub fn with_credential_validation(mut self, validate: bool) -> Self {
+        self.validate_credentials = validate;
+        self
+    }
+
+    /// Try disabled providers again after `cooldown`, or never (`None`) until
+    /// [`enable_provider`](MultiProviderSearch::enable_provider) is called
+    pub fn with_disable_cooldown(mut self, cooldown: Option<Duration>) -> Self {
+        self.disable_cooldown = cooldown;
+        self
+    }
+
+    /// Create the search, first checking all providers' credentials at once if
+    /// [enabled](Self::with_credential_validation)
+    ///
+    /// Providers whose credentials or configuration were rejected start out
+    /// [disabled](MultiProviderSearch::disabled_providers). A check that failed for another
+    /// reason, such as a network error, doesn't disable its provider. Without validation
+    /// the report is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use websearch::{
+    ///     multi_provider::{MultiProviderConfig, MultiProviderStrategy},
+    ///     providers::{BraveProvider, DuckDuckGoProvider},
+    /// };
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let (search, report) = MultiProviderConfig::new(MultiProviderStrategy::Failover)
+    ///     .add_provider(Box::new(BraveProvider::new("brave-key")?))
+    ///     .add_provider(Box::new(DuckDuckGoProvider::new()))
+    ///     .with_credential_validation(true)
+    ///     .build()
+    ///     .await;
+    ///
+    /// for check in report.checks.iter().filter(|check| !check.is_usable()) {
+    ///     eprintln!("{} disabled: {:?}", check.provider, check.error);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn build(self) -> (MultiProviderSearch, CredentialReport) {
+        let report = if self.validate_credentials {
+            let checks = self.providers.iter().map(|provider| async {
+                let error = timeout(self.timeout_per_provider, provider.validate_credentials())
+                    .await
+                    .unwrap_or(Err(SearchError::Timeout {
+                        timeout_ms: self.timeout_per_provider.as_millis() as u64,
+                    }))
+                    .err();
+                CredentialCheck {
+                    provider: provider.name().to_string(),
+                    error,
+                }
+            });
+            CredentialReport {
+                checks: futures::future::join_all(checks).await,
+            }
+        } else {
+            CredentialReport::default()
+        };
+
+        let search = MultiProviderSearch::new(self);
+        {
+            let mut disabled = search.disabled.lock().unwrap();
+            for check in &report.checks {
+                if let Some(error) = check.error.as_ref().filter(|_| !check.is_usable()) {
+                    disabled.insert(check.provider.clone(), (error.clone(), Instant::now()));
+                }
+            }
+        }
+        (search, report)
+    }
+
+    /// What one request to `provider` costs
+    pub fn cost_of(&self, provider: &str) -> CostPerRequest {
+        self.costs
+            .get(provider)
+            .copied()
+            .unwrap_or_else(|| CostPerRequest::default_for(provider))
+    }
 }
 
 /// Multi-provider search manager
 pub struct MultiProviderSearch {
     config: MultiProviderConfig,
-    provider_stats: HashMap<String, ProviderStats>,
+    routing: Arc<dyn RoutingStrategy>,
+    /// One lock per provider, so concurrent searches on different providers don't contend
+    provider_stats: HashMap<String, Mutex<ProviderStats>>,
+    /// Spend for the current UTC day
+    daily_spend: Mutex<(NaiveDate, f64)>,
+    /// Providers skipped by every strategy, with the error that disabled them and when
+    disabled: Mutex<HashMap<String, (SearchError, Instant)>>,
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone, serde::Serialize)]
 pub struct ProviderStats {
     pub total_requests: u64,
     pub successful_requests: u64,
     pub failed_requests: u64,
+    /// Requests abandoned before they finished because another provider in a
+    /// [parallel tier](MultiProviderConfig::with_parallel_tiers) answered first; they are
+    /// in `total_requests` but neither succeeded nor failed
+    pub cancelled_requests: u64,
     pub avg_response_time_ms: f64,
+    /// Requests not sent because they would have exceeded a budget
+    pub skipped_over_budget: u64,
+    /// Estimated spend on this provider, in US dollars
+    pub estimated_cost_usd: f64,
+    /// Median latency of recent successful requests
+    pub p50_latency_ms: Option<f64>,
+    pub p90_latency_ms: Option<f64>,
+    pub p99_latency_ms: Option<f64>,
+    /// Failed requests by kind of error
+    pub errors: ErrorCounts,
+    pub last_success: Option<DateTime<Utc>>,
+    pub last_failure: Option<DateTime<Utc>>,
+    /// Current delay between requests after HTTP 429 responses, 0 when not throttled
+    /// (see [`crate::limits`])
+    pub throttle_delay_ms: u64,
+    /// Searches waiting in the provider's request queue (see [`crate::queue`])
+    pub queue_depth: usize,
+    /// Latencies of the most recent successful requests, for the percentiles
+    #[serde(skip)]
+    recent_latencies_ms: VecDeque<f64>,
+}
+
+/// Number of recent latencies the percentiles in [`ProviderStats`] are computed from
+const LATENCY_WINDOW: usize = 1000;
+
+impl ProviderStats {
+    fn record(&mut self, latency: Duration, result: &Result<Vec<SearchResult>>) {
+        let now = Utc::now();
+        match result {
+            Ok(_) => {
+                self.successful_requests += 1;
+                self.last_success = Some(now);
+
+                // Update rolling average
+                let new_time = latency.as_millis() as f64;
+                self.avg_response_time_ms =
+                    (self.avg_response_time_ms * (self.successful_requests - 1) as f64 + new_time)
+                        / self.successful_requests as f64;
+
+                if self.recent_latencies_ms.len() == LATENCY_WINDOW {
+                    self.recent_latencies_ms.pop_front();
+                }
+                self.recent_latencies_ms.push_back(new_time);
+
+                let mut sorted: Vec<f64> = self.recent_latencies_ms.iter().copied().collect();
+                sorted.sort_by(f64::total_cmp);
+                self.p50_latency_ms = percentile(&sorted, 50.0);
+                self.p90_latency_ms = percentile(&sorted, 90.0);
+                self.p99_latency_ms = percentile(&sorted, 99.0);
+            }
+            Err(error) => {
+                self.failed_requests += 1;
+                self.last_failure = Some(now);
+                self.errors.record(error);
+            }
+        }
+    }
+}
+
+/// A sent request, counted as cancelled if its future is dropped before [`finish`]
+///
+/// [`finish`]: InFlight::finish
+struct InFlight<'a> {
+    search: &'a MultiProviderSearch,
+    provider: &'a str,
+    finished: bool,
+}
+
+impl InFlight<'_> {
+    fn finish(mut self) {
+        self.finished = true;
+    }
+}
+
+impl Drop for InFlight<'_> {
+    fn drop(&mut self) {
+        if !self.finished {
+            self.search
+                .update_stats(self.provider, |stats| stats.cancelled_requests += 1);
+        }
+    }
+}
+
+/// Nearest-rank percentile of an ascending slice
+fn percentile(sorted: &[f64], p: f64) -> Option<f64> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let rank = (p / 100.0 * sorted.len() as f64).ceil() as usize;
+    Some(sorted[rank.clamp(1, sorted.len()) - 1])
+}
+
+/// Failed requests counted by kind of error
+#[derive(Debug, Default, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct ErrorCounts {
+    /// Requests that timed out
+    pub timeout: u64,
+    /// HTTP 429, rate limit and anti-bot responses
+    pub rate_limited: u64,
+    /// HTTP 5xx responses
+    pub server_error: u64,
+    /// HTTP 401/403 and other authentication failures
+    pub auth: u64,
+    /// Everything else (network, parsing, ...)
+    pub other: u64,
+}
+
+impl ErrorCounts {
+    fn record(&mut self, error: &SearchError) {
+        let counter = match error {
+            SearchError::Timeout { .. } => &mut self.timeout,
+            SearchError::RateLimit(_)
+            | SearchError::BotDetected { .. }
+            | SearchError::HttpError {
+                status_code: Some(429),
+                ..
+            } => &mut self.rate_limited,
+            SearchError::HttpError {
+                status_code: Some(500..=599),
+                ..
+            } => &mut self.server_error,
+            SearchError::AuthenticationError(_)
+            | SearchError::HttpError {
+                status_code: Some(401 | 403),
+                ..
+            } => &mut self.auth,
+            _ => &mut self.other,
+        };
+        *counter += 1;
+    }
+}
+
+/// Outcome of checking one provider's credentials in [`MultiProviderConfig::build`]
+#[derive(Debug, Clone)]
+pub struct CredentialCheck {
+    pub provider: String,
+    /// Why the check failed, if it did
+    pub error: Option<SearchError>,
+}
+
+impl CredentialCheck {
+    /// Whether the provider can be used: its check passed, or failed for a reason other
+    /// than [misconfiguration](ErrorClass::Misconfigured)
+    pub fn is_usable(&self) -> bool {
+        self.error
+            .as_ref()
+            .is_none_or(|error| error.class() != ErrorClass::Misconfigured)
+    }
+}
+
+/// Credential checks for every provider, in configuration order
+#[derive(Debug, Clone, Default)]
+pub struct CredentialReport {
+    pub checks: Vec<CredentialCheck>,
+}
+
+impl CredentialReport {
+    /// Names of the providers that can be used
+    pub fn usable(&self) -> Vec<&str> {
+        self.checks
+            .iter()
+            .filter(|check| check.is_usable())
+            .map(|check| check.provider.as_str())
+            .collect()
+    }
+}
+
+/// Results of a strategy search
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct MultiSearchResponse {
+    pub results: Vec<SearchResult>,
+    /// The deadline passed before every provider answered
+    pub partial: bool,
+}
+
+impl From<Vec<SearchResult>> for MultiSearchResponse {
+    fn from(results: Vec<SearchResult>) -> Self {
+        Self {
+            results,
+            partial: false,
+        }
+    }
+}
+
+/// State of one strategy search: its spend, checked against the per-search budget, and
+/// its deadline
+#[derive(Debug)]
+struct SearchState {
+    spend: Mutex<f64>,
+    deadline: Option<(Instant, Duration)>,
+}
+
+impl SearchState {
+    fn new(deadline: Option<Duration>) -> Self {
+        Self {
+            spend: Mutex::new(0.0),
+            deadline: deadline.map(|deadline| (Instant::now() + deadline, deadline)),
+        }
+    }
+
+    /// The per-provider timeout, cut short if the deadline is nearer, and the error to
+    /// report when it expires
+    fn timeout(&self, per_provider: Duration) -> (Duration, SearchError) {
+        let (limit, timeout) = match self.deadline {
+            Some((at, deadline)) if at.saturating_duration_since(Instant::now()) < per_provider => {
+                (at.saturating_duration_since(Instant::now()), deadline)
+            }
+            _ => (per_provider, per_provider),
+        };
+        let error = SearchError::Timeout {
+            timeout_ms: timeout.as_millis() as u64,
+        };
+        (limit, error)
+    }
+
+    /// Run `future`, failing with a timeout if the deadline passes first
+    async fn until_deadline<T>(&self, future: impl Future<Output = Result<T>>) -> Result<T> {
+        let Some((at, deadline)) = self.deadline else {
+            return future.await;
+        };
+        timeout_at(at, future)
+            .await
+            .unwrap_or(Err(SearchError::Timeout {
+                timeout_ms: deadline.as_millis() as u64,
+            }))
+    }
+
+    fn deadline_passed(&self) -> bool {
+        self.deadline.is_some_and(|(at, _)| Instant::now() >= at)
+    }
 }
 
 impl MultiProviderSearch {
+    /// Create a search over the config's providers
+    ///
+    /// # Panics
+    ///
+    /// If two providers share a [name](SearchProvider::name): routing plans, statistics and
+    /// [`SearchQuery::providers`] refer to providers by name.
     pub fn new(config: MultiProviderConfig) -> Self {
+        let mut names = HashSet::new();
+        if let Some(duplicate) = config.providers.iter().find(|p| !names.insert(p.name())) {
+            panic!("Duplicate provider name '{}'", duplicate.name());
+        }
         let provider_stats = config
             .providers
             .iter()
-            .map(|p| (p.name().to_string(), ProviderStats::default()))
+            .map(|p| (p.name().to_string(), Mutex::default()))
             .collect();
 
         Self {
+            routing: config.strategy.routing(&config),
             config,
             provider_stats,
+            daily_spend: Mutex::new((Utc::now().date_naive(), 0.0)),
+            disabled: Mutex::default(),
         }
     }
 
     /// Perform search using the configured strategy
-    pub async fn search(&mut self, options: &SearchOptionsMulti) -> Result<Vec<SearchResult>> {
-        match self.config.strategy {
-            MultiProviderStrategy::Failover => self.search_failover(options).await,
-            MultiProviderStrategy::LoadBalance => self.search_load_balance(options).await,
-            MultiProviderStrategy::Aggregate => self.search_aggregate(options).await,
-            MultiProviderStrategy::RaceFirst => self.search_race_first(options).await,
+    ///
+    /// Takes `&self`, so one instance can be shared behind an [`Arc`] and searched from many
+    /// tasks at once; statistics and budgets are kept consistent across them.
+    pub async fn search(&self, options: &SearchOptionsMulti) -> Result<Vec<SearchResult>> {
+        self.search_with_strategy(options)
+            .await
+            .map(|response| response.results)
+    }
+
+    /// Like [`search`](Self::search), but also reports whether the configured
+    /// [deadline](MultiProviderConfig::with_deadline) cut the search short
+    ///
+    /// When the deadline passes, the aggregate strategy returns the results that arrived in
+    /// time with `partial` set instead of failing. The other strategies fail with a timeout.
+    pub async fn search_response(
+        &self,
+        options: &SearchOptionsMulti,
+    ) -> Result<MultiSearchResponse> {
+        self.search_with_strategy(options).await
+    }
+
+    /// Run many queries through the configured strategy with bounded concurrency
+    ///
+    /// Results are returned in the same order as `queries`; a failing query does not
+    /// affect the others.
+    pub async fn search_batch(
+        &self,
+        queries: Vec<SearchQuery>,
+        concurrency: usize,
+    ) -> Vec<BatchSearchResult> {
+        let semaphore = Semaphore::new(concurrency.max(1));
+
+        let searches = queries.into_iter().map(|query| {
+            let semaphore = &semaphore;
+            async move {
+                let _permit = semaphore
+                    .acquire()
+                    .await
+                    .expect("batch semaphore is never closed");
+                let result = self
+                    .search_with_strategy(&query)
+                    .await
+                    .map(|response| response.results);
+                BatchSearchResult { query, result }
+            }
+        });
+
+        futures::future::join_all(searches).await
+    }
+
+    /// Search with a single named provider, bypassing the strategy
+    ///
+    /// Statistics and the per-provider timeout apply as for strategy searches.
+    pub async fn search_provider(
+        &self,
+        name: &str,
+        options: &SearchOptionsMulti,
+    ) -> Result<Vec<SearchResult>> {
+        let index = self
+            .config
+            .providers
+            .iter()
+            .position(|p| p.name() == name)
+            .ok_or_else(|| SearchError::InvalidInput(format!("Unknown provider: {name}")))?;
+
+        correlation::scope(
+            options.correlation_id.clone(),
+            self.search_single_provider_by_index(index, options, &SearchState::new(None)),
+        )
+        .await
+    }
+
+    /// Names of the configured providers, in configuration order
+    pub fn provider_names(&self) -> Vec<&str> {
+        self.config.providers.iter().map(|p| p.name()).collect()
+    }
+
+    /// Providers disabled after a [misconfigured](ErrorClass::Misconfigured) failure, such
+    /// as rejected credentials, with that failure
+    ///
+    /// A disabled provider isn't queried again until the config's
+    /// [`disable_cooldown`](MultiProviderConfig::disable_cooldown) has passed; until then,
+    /// searches that would use it fail with the same error instead, so failover goes
+    /// straight to the next provider.
+    pub fn disabled_providers(&self) -> Vec<(String, SearchError)> {
+        let mut disabled: Vec<_> = self
+            .disabled
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(_, (_, since))| !self.cooled_down(*since))
+            .map(|(name, (error, _))| (name.clone(), error.clone()))
+            .collect();
+        disabled.sort_by(|a, b| a.0.cmp(&b.0));
+        disabled
+    }
+
+    /// Whether a provider disabled at `since` may be tried again
+    fn cooled_down(&self, since: Instant) -> bool {
+        self.config
+            .disable_cooldown
+            .is_some_and(|cooldown| since.elapsed() >= cooldown)
+    }
+
+    /// The error a provider was disabled with, unless it has cooled down since
+    fn disabled_error(&self, name: &str) -> Option<SearchError> {
+        let mut disabled = self.disabled.lock().unwrap();
+        let (error, since) = disabled.get(name)?;
+        if self.cooled_down(*since) {
+            disabled.remove(name);
+            return None;
+        }
+        Some(error.clone())
+    }
+
+    /// Query a disabled provider again, e.g. after rotating its API key
+    pub fn enable_provider(&self, name: &str) {
+        self.disabled.lock().unwrap().remove(name);
+    }
+
+    pub(crate) async fn search_with_strategy(
+        &self,
+        options: &SearchOptionsMulti,
+    ) -> Result<MultiSearchResponse> {
+        correlation::scope(
+            options.correlation_id.clone(),
+            self.search_strategy(options),
+        )
+        .await
+    }
+
+    async fn search_strategy(&self, options: &SearchOptionsMulti) -> Result<MultiSearchResponse> {
+        let state = SearchState::new(self.config.deadline);
+        let selected = self.selected_providers(options)?;
+        let plan = self
+            .routing
+            .route(&selected, options, &self.get_stats())
+            .await;
+        let results = match plan {
+            RoutingPlan::Failover { tiers, parallel } => {
+                let tiers = tiers
+                    .iter()
+                    .map(|tier| self.provider_indices(tier, &selected))
+                    .collect();
+                self.search_failover(tiers, parallel, options, &state).await
+            }
+            RoutingPlan::Aggregate(providers) => {
+                let providers = self.provider_indices(&providers, &selected);
+                return self.search_aggregate(providers, options, &state).await;
+            }
+            RoutingPlan::Race(providers) => {
+                let providers = self.provider_indices(&providers, &selected);
+                self.search_race_first(providers, options, &state).await
+            }
+        };
+        results
+            .map(|results| processors::apply(&self.config.processors, results))
+            .map(MultiSearchResponse::from)
+    }
+
+    /// Names of the providers the search may use: the query's
+    /// [`providers`](SearchQuery::providers) if set, in configuration order, else all of them,
+    /// less those that can't search in the query's [`mode`](SearchQuery::mode)
+    fn selected_providers(&self, options: &SearchOptionsMulti) -> Result<Vec<&str>> {
+        let mut selected = self.named_providers(options)?;
+        if options.mode != SearchMode::Web {
+            selected.retain(|&name| {
+                self.config
+                    .providers
+                    .iter()
+                    .any(|p| p.name() == name && p.supports_mode(options.mode))
+            });
+            if selected.is_empty() {
+                return Err(SearchError::InvalidInput(format!(
+                    "No selected provider supports {} search",
+                    options.mode
+                )));
+            }
         }
+        Ok(selected)
     }
 
-    /// Try providers in sequence until one succeeds
-    async fn search_failover(&mut self, options: &SearchOptionsMulti) -> Result<Vec<SearchResult>> {
+    /// The query's [`providers`](SearchQuery::providers) if set, in configuration order,
+    /// else all of them
+    fn named_providers(&self, options: &SearchOptionsMulti) -> Result<Vec<&str>> {
+        let names = self.provider_names();
+        let Some(subset) = &options.providers else {
+            return Ok(names);
+        };
+
+        if let Some(unknown) = subset.iter().find(|name| !names.contains(&name.as_str())) {
+            return Err(SearchError::InvalidInput(format!(
+                "Unknown provider: {unknown}"
+            )));
+        }
+        let selected: Vec<&str> = names
+            .into_iter()
+            .filter(|name| subset.iter().any(|s| s == name))
+            .collect();
+        if selected.is_empty() {
+            return Err(SearchError::InvalidInput(
+                "The provider list is empty".to_string(),
+            ));
+        }
+        Ok(selected)
+    }
+
+    /// Indices of the named providers, skipping names that aren't configured or
+    /// `selected` for this search
+    fn provider_indices(&self, names: &[String], selected: &[&str]) -> Vec<usize> {
+        names
+            .iter()
+            .filter_map(|name| {
+                let index = self.config.providers.iter().position(|p| p.name() == name);
+                if index.is_none() {
+                    log::warn!("Routing plan names unknown provider '{name}'");
+                }
+                index
+            })
+            .filter(|&i| selected.contains(&self.config.providers[i].name()))
+            .collect()
+    }
+
+    /// Try providers tier by tier until one succeeds
+    async fn search_failover(
+        &self,
+        tiers: Vec<Vec<usize>>,
+        parallel: bool,
+        options: &SearchOptionsMulti,
+        state: &SearchState,
+    ) -> Result<Vec<SearchResult>> {
         let mut last_error = SearchError::Other("No providers configured".to_string());
 
-        for i in 0..self.config.providers.len() {
-            let provider_name = self.config.providers[i].name().to_string();
-            debug::log(&options.debug, "Trying failover provider", &provider_name);
+        for tier in tiers.into_iter().filter(|tier| !tier.is_empty()) {
+            let names: Vec<&str> = tier
+                .iter()
+                .map(|&i| self.config.providers[i].name())
+                .collect();
+            debug::log(&options.debug, "Trying failover tier", &names.join(", "));
+
+            let result = if parallel && tier.len() > 1 {
+                let searches = tier.iter().map(|&i| {
+                    Box::pin(async move {
+                        let results = self
+                            .search_single_provider_by_index(i, options, state)
+                            .await?;
+                        Ok((i, results))
+                    })
+                });
+                futures::future::select_ok(searches)
+                    .await
+                    .map(|(won, _still_running)| won)
+            } else {
+                let mut tier_result = Err(last_error.clone());
+                for &i in &tier {
+                    match self
+                        .search_single_provider_by_index(i, options, state)
+                        .await
+                    {
+                        Ok(results) => {
+                            tier_result = Ok((i, results));
+                            break;
+                        }
+                        Err(err) if err.class() == ErrorClass::InvalidRequest => return Err(err),
+                        Err(err) => {
+                            debug::log(
+                                &options.debug,
+                                &format!(
+                                    "Failover provider {} failed",
+                                    self.config.providers[i].name()
+                                ),
+                                &err.to_string(),
+                            );
+                            tier_result = Err(err);
+                        }
+                    }
+                }
+                tier_result
+            };
 
-            match self.search_single_provider_by_index(i, options).await {
-                Ok(results) => {
+            match result {
+                Ok((i, results)) => {
                     debug::log(
                         &options.debug,
                         "Failover provider succeeded",
-                        &provider_name,
+                        self.config.providers[i].name(),
                     );
                     return Ok(results);
                 }
-                Err(err) => {
-                    debug::log(
-                        &options.debug,
-                        &format!("Failover provider {provider_name} failed"),
-                        &err.to_string(),
-                    );
-                    last_error = err;
-                }
+                Err(err) if err.class() == ErrorClass::InvalidRequest => return Err(err),
+                Err(err) => last_error = err,
             }
         }
 
         Err(last_error)
     }
 
-    /// Use round-robin load balancing
-    async fn search_load_balance(
-        &mut self,
-        options: &SearchOptionsMulti,
-    ) -> Result<Vec<SearchResult>> {
-        if self.config.providers.is_empty() {
-            return Err(SearchError::Other("No providers configured".to_string()));
-        }
-
-        // Simple round-robin: pick based on total requests
-        let total_requests: u64 = self.provider_stats.values().map(|s| s.total_requests).sum();
-        let provider_index = (total_requests as usize) % self.config.providers.len();
-        let provider_name = self.config.providers[provider_index].name().to_string();
-
-        debug::log(&options.debug, "Load balancing to provider", &provider_name);
-
-        self.search_single_provider_by_index(provider_index, options)
-            .await
-    }
-
-    /// Query all providers and merge results
+    /// Query the providers (at most `max_concurrent` at a time) and merge results
     async fn search_aggregate(
-        &mut self,
+        &self,
+        providers: Vec<usize>,
         options: &SearchOptionsMulti,
-    ) -> Result<Vec<SearchResult>> {
+        state: &SearchState,
+    ) -> Result<MultiSearchResponse> {
         debug::log(&options.debug, "Aggregating results from all providers", "");
 
+        // Raw payloads are kept until the provider scores in `sources` are read from them
+        let provider_options = SearchOptionsMulti {
+            max_results: self.config.per_provider_results.or(options.max_results),
+            include_raw: true,
+            ..options.clone()
+        };
+        let semaphore = Semaphore::new(self.config.max_concurrent.max(1));
+
+        let searches = providers.into_iter().map(|i| {
+            let semaphore = &semaphore;
+            let provider_options = &provider_options;
+            async move {
+                let _permit = semaphore
+                    .acquire()
+                    .await
+                    .expect("aggregate semaphore is never closed");
+                (
+                    i,
+                    self.search_single_provider_by_index(i, provider_options, state)
+                        .await,
+                )
+            }
+        });
+
+        // Providers that return more than the configured per-provider count are cut down
+        let per_provider_limit = self
+            .config
+            .per_provider_results
+            .map_or(usize::MAX, |max| max as usize);
         let mut merged_results = Vec::new();
         let mut successful_providers = Vec::new();
-
-        // Search each provider sequentially to avoid borrowing issues
-        for i in 0..self.config.providers.len() {
-            let provider_name = self.config.providers[i].name().to_string();
-            match self.search_single_provider_by_index(i, options).await {
-                Ok(mut provider_results) => {
-                    successful_providers.push(provider_name);
-                    merged_results.append(&mut provider_results);
-                }
-                Err(_) => {
-                    // Continue with other providers
+        let mut timed_out = false;
+
+        for (i, result) in futures::future::join_all(searches).await {
+            // Failed providers are skipped; the others still contribute
+            match result {
+                Ok(provider_results) => {
+                    let name = self.config.providers[i].name();
+                    successful_providers.push(name.to_string());
+                    let provider_results = provider_results.into_iter().take(per_provider_limit);
+                    merged_results.extend(provider_results.enumerate().map(
+                        |(rank, mut result)| {
+                            result.sources = vec![ProviderHit::new(name, rank + 1, &result)];
+                            if !options.include_raw {
+                                result.raw = None;
+                            }
+                            result
+                        },
+                    ));
                 }
+                Err(SearchError::Timeout { .. }) => timed_out = true,
+                Err(_) => {}
             }
         }
 
-        if merged_results.is_empty() {
+        // Providers still running at the deadline were cut off, so whatever arrived is
+        // returned instead of an error
+        let partial = timed_out && state.deadline_passed();
+        if merged_results.is_empty() && !partial {
             return Err(SearchError::Other("All providers failed".to_string()));
         }
+        if partial {
+            debug::log(
+                &options.debug,
+                "Deadline reached",
+                "returning partial results",
+            );
+        }
 
         debug::log(
             &options.debug,
@@ -189,38 +1073,64 @@ impl MultiProviderSearch {
             a.provider.cmp(&b.provider)
         });
 
+        if self.config.dedup {
+            merged_results = Dedup.process(merged_results);
+        }
+        if let Some(consensus) = &self.config.consensus {
+            merged_results = consensus.process(merged_results);
+        }
+        if let Some(reranker) = &self.config.reranker {
+            match reranker
+                .rerank(&options.query, merged_results.clone())
+                .await
+            {
+                Ok(reranked) => merged_results = reranked,
+                Err(error) => debug::log(
+                    &options.debug,
+                    "Reranking failed, keeping merged order",
+                    &error.to_string(),
+                ),
+            }
+        }
+        merged_results = processors::apply(&self.config.processors, merged_results);
+
         // Limit total results
         if let Some(max_results) = options.max_results {
             merged_results.truncate(max_results as usize);
         }
 
-        Ok(merged_results)
+        Ok(MultiSearchResponse {
+            results: merged_results,
+            partial,
+        })
     }
 
-    /// Race all providers, return first successful result
+    /// Race the providers, return first successful result
     async fn search_race_first(
-        &mut self,
+        &self,
+        providers: Vec<usize>,
         options: &SearchOptionsMulti,
+        state: &SearchState,
     ) -> Result<Vec<SearchResult>> {
         debug::log(&options.debug, "Racing all providers", "");
 
-        if self.config.providers.is_empty() {
+        if providers.is_empty() {
             return Err(SearchError::Other("No providers configured".to_string()));
         }
 
         // For now, simplified race - try providers in sequence until first succeeds
         // A true race implementation would require more complex async handling
-        for i in 0..self.config.providers.len() {
+        for i in providers {
             let provider_name = self.config.providers[i].name().to_string();
-            match self.search_single_provider_by_index(i, options).await {
+            match self
+                .search_single_provider_by_index(i, options, state)
+                .await
+            {
                 Ok(results) => {
-                    debug::log(
-                        &options.debug,
-                        &format!("Race won by {provider_name}"),
-                        "",
-                    );
+                    debug::log(&options.debug, &format!("Race won by {provider_name}"), "");
                     return Ok(results);
                 }
+                Err(err) if err.class() == ErrorClass::InvalidRequest => return Err(err),
                 Err(_) => {
                     // Continue to next provider
                 }
@@ -234,224 +1144,179 @@ impl MultiProviderSearch {
 
     /// Search with a single provider by index and update stats
     async fn search_single_provider_by_index(
-        &mut self,
+        &self,
         provider_index: usize,
         options: &SearchOptionsMulti,
+        state: &SearchState,
     ) -> Result<Vec<SearchResult>> {
-        let start_time = std::time::Instant::now();
         let provider = &self.config.providers[provider_index];
         let provider_name = provider.name().to_string();
+        let cost = self.config.cost_of(&provider_name);
+
+        if let Some(error) = self.disabled_error(&provider_name) {
+            debug::log(
+                &options.debug,
+                "Skipping disabled provider",
+                &error.to_string(),
+            );
+            return Err(error);
+        }
+        state
+            .until_deadline(queue::wait_turn(&provider_name, options.priority))
+            .await?;
+        if state.deadline_passed() {
+            return Err(state.timeout(self.config.timeout_per_provider).1);
+        }
+        if let Err(error) = self.charge(&provider_name, cost, state) {
+            debug::log(&options.debug, "Skipping provider", &error.to_string());
+            self.update_stats(&provider_name, |stats| stats.skipped_over_budget += 1);
+            return Err(error);
+        }
 
         // Update request count
-        if let Some(stats) = self.provider_stats.get_mut(&provider_name) {
+        self.update_stats(&provider_name, |stats| {
             stats.total_requests += 1;
-        }
+            stats.estimated_cost_usd += cost.usd;
+        });
+        let in_flight = InFlight {
+            search: self,
+            provider: &provider_name,
+            finished: false,
+        };
 
         // Perform search with timeout - we'll use our internal search interface
+        // Queueing for a request slot doesn't count towards latency or the timeout
+        let _permit = limits::acquire().await;
+        let start_time = Instant::now();
+        let (limit, timeout_error) = state.timeout(self.config.timeout_per_provider);
         let search_future = self.search_provider_internal(provider.as_ref(), options);
-        let result = timeout(self.config.timeout_per_provider, search_future).await;
+        let result = timeout(limit, search_future).await;
 
         let duration = start_time.elapsed();
-
-        // Update stats
-        if let Some(stats) = self.provider_stats.get_mut(&provider_name) {
-            match &result {
-                Ok(Ok(_)) => {
-                    stats.successful_requests += 1;
-                    // Update rolling average
-                    let new_time = duration.as_millis() as f64;
-                    stats.avg_response_time_ms = (stats.avg_response_time_ms
-                        * (stats.successful_requests - 1) as f64
-                        + new_time)
-                        / stats.successful_requests as f64;
-                }
-                _ => {
-                    stats.failed_requests += 1;
-                }
+        let result = result.unwrap_or(Err(timeout_error));
+        in_flight.finish();
+
+        self.update_stats(&provider_name, |stats| stats.record(duration, &result));
+        if let Err(error) = &result {
+            if error.class() == ErrorClass::Misconfigured {
+                debug::log(&options.debug, "Disabling provider", &error.to_string());
+                self.disabled
+                    .lock()
+                    .unwrap()
+                    .insert(provider_name, (error.clone(), Instant::now()));
             }
         }
 
-        match result {
-            Ok(search_result) => search_result,
-            Err(_) => Err(SearchError::Timeout {
-                timeout_ms: self.config.timeout_per_provider.as_millis() as u64,
-            }),
-        }
-    }
-
-    /// Get provider statistics
-    pub fn get_stats(&self) -> &HashMap<String, ProviderStats> {
-        &self.provider_stats
+        result
     }
 
-    /// Internal method to search with a provider without the circular dependency issue
-    async fn search_provider_internal(
-        &self,
-        provider: &dyn SearchProvider,
-        options: &SearchOptionsMulti,
-    ) -> Result<Vec<SearchResult>> {
-        // Create a temporary SearchOptions with a placeholder provider for the interface
-        // The actual provider parameter won't be used since we're calling the provider directly
-        use crate::types::SearchOptions;
-
-        let search_options = SearchOptions {
-            query: options.query.clone(),
-            id_list: options.id_list.clone(),
-            max_results: options.max_results,
-            language: options.language.clone(),
-            region: options.region.clone(),
-            safe_search: options.safe_search.clone(),
-            page: options.page,
-            start: options.start,
-            sort_by: options.sort_by.clone(),
-            sort_order: options.sort_order.clone(),
-            timeout: options.timeout,
-            debug: options.debug.clone(),
-            provider: Box::new(PlaceholderProvider), // This won't be used
-        };
-
-        provider.search(&search_options).await
+    /// Get a snapshot of provider statistics
+    pub fn get_stats(&self) -> HashMap<String, ProviderStats> {
+        self.provider_stats
+            .iter()
+            .map(|(provider, stats)| {
+                let mut stats = stats.lock().unwrap().clone();
+                stats.throttle_delay_ms = limits::throttle_delay(provider).as_millis() as u64;
+                stats.queue_depth = queue::queue_depth(provider);
+                (provider.clone(), stats)
+            })
+            .collect()
     }
-}
-
-/// Multi-provider search options (similar to SearchOptions but without provider field)
-#[derive(Debug)]
-pub struct SearchOptionsMulti {
-    pub query: String,
-    pub id_list: Option<String>,
-    pub max_results: Option<u32>,
-    pub language: Option<String>,
-    pub region: Option<String>,
-    pub safe_search: Option<SafeSearch>,
-    pub page: Option<u32>,
-    pub start: Option<u32>,
-    pub sort_by: Option<SortBy>,
-    pub sort_order: Option<SortOrder>,
-    pub timeout: Option<u64>,
-    pub debug: Option<DebugOptions>,
-}
 
-impl Default for SearchOptionsMulti {
-    fn default() -> Self {
-        Self {
-            query: String::new(),
-            id_list: None,
-            max_results: Some(10),
-            language: None,
-            region: None,
-            safe_search: None,
-            page: Some(1),
-            start: None,
-            sort_by: None,
-            sort_order: None,
-            timeout: Some(15000),
-            debug: None,
+    fn update_stats(&self, provider: &str, update: impl FnOnce(&mut ProviderStats)) {
+        if let Some(stats) = self.provider_stats.get(provider) {
+            update(&mut stats.lock().unwrap());
         }
     }
-}
-
-// Placeholder provider for interface compatibility - never actually used
-#[derive(Debug)]
-struct PlaceholderProvider;
 
-#[async_trait::async_trait]
-impl SearchProvider for PlaceholderProvider {
-    fn name(&self) -> &str {
-        "placeholder"
+    /// Estimated total spend across all providers since this search was created, in US dollars
+    pub fn estimated_cost(&self) -> f64 {
+        self.provider_stats
+            .values()
+            .map(|stats| stats.lock().unwrap().estimated_cost_usd)
+            .sum()
     }
 
-    async fn search(&self, _: &crate::types::SearchOptions) -> Result<Vec<SearchResult>> {
-        Err(SearchError::Other(
-            "PlaceholderProvider should never be called".to_string(),
-        ))
+    /// Estimated spend so far today (UTC), in US dollars
+    pub fn spent_today(&self) -> f64 {
+        let daily = self.daily_spend.lock().unwrap();
+        if daily.0 == Utc::now().date_naive() {
+            daily.1
+        } else {
+            0.0
+        }
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::types::*;
-    use async_trait::async_trait;
-    use tokio::time::Duration;
 
-    // Mock provider for testing
-    #[derive(Debug, Clone)]
-    struct MockProvider {
-        name: String,
-        should_error: bool,
-        error_type: Option<SearchError>,
-        results: Vec<SearchResult>,
-        delay_ms: u64,
-    }
-
-    impl MockProvider {
-        fn new(name: &str) -> Self {
-            Self {
-                name: name.to_string(),
-                should_error: false,
-                error_type: None,
-                results: vec![
-                    SearchResult {
-                        title: format!("{name} Result 1"),
-                        url: format!("https://{name}.com/1"),
-                        snippet: Some(format!("{name} content 1")),
-                        domain: None,
-                        published_date: None,
-                        provider: Some(name.to_string()),
-                        raw: None,
-                    },
-                    SearchResult {
-                        title: format!("{name} Result 2"),
-                        url: format!("https://{name}.com/2"),
-                        snippet: Some(format!("{name} content 2")),
-                        domain: None,
-                        published_date: None,
-                        provider: Some(name.to_string()),
-                        raw: None,
-                    },
-                ],
-                delay_ms: 0,
-            }
+    /// Record `cost` against the budgets, or fail without recording if it would exceed one
+    fn charge(&self, provider: &str, cost: CostPerRequest, state: &SearchState) -> Result<()> {
+        if cost.usd <= 0.0 {
+            return Ok(());
         }
 
-        fn with_error(mut self, error: SearchError) -> Self {
-            self.should_error = true;
-            self.error_type = Some(error);
-            self
+        let mut search_spend = state.spend.lock().unwrap();
+        let mut daily = self.daily_spend.lock().unwrap();
+        let today = Utc::now().date_naive();
+        if daily.0 != today {
+            *daily = (today, 0.0);
         }
 
-        fn with_results(mut self, results: Vec<SearchResult>) -> Self {
-            self.results = results;
-            self
+        let over = |spent: f64, budget: Option<f64>| budget.is_some_and(|b| spent + cost.usd > b);
+        if over(*search_spend, self.config.search_budget_usd)
+            || over(daily.1, self.config.daily_budget_usd)
+        {
+            return Err(SearchError::BudgetExceeded {
+                provider: provider.into(),
+                cost_usd: cost.usd,
+            });
         }
 
-        fn with_delay(mut self, delay_ms: u64) -> Self {
-            self.delay_ms = delay_ms;
-            self
-        }
+        *search_spend += cost.usd;
+        daily.1 += cost.usd;
+        Ok(())
     }
 
-    #[async_trait]
-    impl SearchProvider for MockProvider {
-        fn name(&self) -> &str {
-            &self.name
-        }
+    /// Internal method to search with a provider without the circular dependency issue
+    async fn search_provider_internal(
+        &self,
+        provider: &dyn SearchProvider,
+        options: &SearchOptionsMulti,
+    ) -> Result<Vec<SearchResult>> {
+        // The provider is called directly, so the options don't need one attached
+        let search_options = options.to_search_options();
+        let mut results = limits::with_provider(
+            provider.name(),
+            limits::with_max_response_bytes(
+                options.max_response_bytes,
+                provider.search(&search_options),
+            ),
+        )
+        .await?;
+        limits::truncate_snippets(&mut results, options.max_snippet_chars);
+        limits::strip_raw(&mut results, options.include_raw);
+        limits::drop_kinds(&mut results, &options.exclude_kinds);
+        safe_search::enforce(provider, options.safe_search.as_ref(), &mut results);
+        domains::enforce(provider, &search_options, &mut results);
+        freshness::enforce(provider, options.freshness, &mut results);
+        language::enforce(
+            options.language.as_deref(),
+            options.require_language,
+            &mut results,
+        );
+        seen::enforce(options.exclude_urls.as_ref(), &mut results);
+        Ok(results)
+    }
+}
 
-        async fn search(&self, _options: &SearchOptions) -> Result<Vec<SearchResult>> {
-            if self.delay_ms > 0 {
-                tokio::time::sleep(Duration::from_millis(self.delay_ms)).await;
-            }
+/// Multi-provider search options (similar to SearchOptions but without provider field)
+pub type SearchOptionsMulti = SearchQuery;
 
-            if self.should_error {
-                Err(self
-                    .error_type
-                    .clone()
-                    .unwrap_or(SearchError::Other("Mock error".to_string())))
-            } else {
-                Ok(self.results.clone())
-            }
-        }
-    }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{sample_results, MockProvider};
+    use crate::types::*;
+    use std::collections::HashSet;
+    use tokio::time::Duration;
 
     fn create_test_options(query: &str) -> SearchOptionsMulti {
         SearchOptionsMulti {
@@ -483,6 +1348,15 @@ mod tests {
         assert!(matches!(config.strategy, MultiProviderStrategy::Failover));
     }
 
+    #[test]
+    #[should_panic(expected = "Duplicate provider name 'google'")]
+    fn test_duplicate_provider_names_are_rejected() {
+        let config = MultiProviderConfig::new(MultiProviderStrategy::Aggregate)
+            .add_provider(Box::new(MockProvider::new("google")))
+            .add_provider(Box::new(MockProvider::new("google")));
+        MultiProviderSearch::new(config);
+    }
+
     #[tokio::test]
     async fn test_failover_strategy_success() {
         let provider1 = MockProvider::new("provider1");
@@ -492,12 +1366,12 @@ mod tests {
             .add_provider(Box::new(provider1))
             .add_provider(Box::new(provider2));
 
-        let mut multi_search = MultiProviderSearch::new(config);
+        let multi_search = MultiProviderSearch::new(config);
         let options = create_test_options("test query");
 
         let results = multi_search.search(&options).await.unwrap();
         assert_eq!(results.len(), 2);
-        assert_eq!(results[0].provider, Some("provider1".to_string()));
+        assert_eq!(results[0].provider.as_deref(), Some("provider1"));
         assert!(results[0].title.contains("provider1"));
     }
 
@@ -514,12 +1388,12 @@ mod tests {
             .add_provider(Box::new(provider1))
             .add_provider(Box::new(provider2));
 
-        let mut multi_search = MultiProviderSearch::new(config);
+        let multi_search = MultiProviderSearch::new(config);
         let options = create_test_options("test query");
 
         let results = multi_search.search(&options).await.unwrap();
         assert_eq!(results.len(), 2);
-        assert_eq!(results[0].provider, Some("provider2".to_string()));
+        assert_eq!(results[0].provider.as_deref(), Some("provider2"));
         assert!(results[0].title.contains("provider2"));
     }
 
@@ -540,7 +1414,7 @@ mod tests {
             .add_provider(Box::new(provider1))
             .add_provider(Box::new(provider2));
 
-        let mut multi_search = MultiProviderSearch::new(config);
+        let multi_search = MultiProviderSearch::new(config);
         let options = create_test_options("test query");
 
         let result = multi_search.search(&options).await;
@@ -556,6 +1430,160 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_failover_disables_misconfigured_provider() {
+        let provider1 = MockProvider::new("provider1")
+            .with_error(SearchError::AuthenticationError("invalid key".to_string()));
+        let provider2 = MockProvider::new("provider2");
+        let config = MultiProviderConfig::new(MultiProviderStrategy::Failover)
+            .add_provider(Box::new(provider1.clone()))
+            .add_provider(Box::new(provider2));
+        let multi_search = MultiProviderSearch::new(config);
+        let options = create_test_options("test query");
+
+        for _ in 0..3 {
+            let results = multi_search.search(&options).await.unwrap();
+            assert_eq!(results[0].provider.as_deref(), Some("provider2"));
+        }
+        // Only the first search asked provider1
+        assert_eq!(provider1.call_count(), 1);
+        let disabled = multi_search.disabled_providers();
+        assert_eq!(disabled.len(), 1);
+        assert_eq!(disabled[0].0, "provider1");
+
+        multi_search.enable_provider("provider1");
+        multi_search.search(&options).await.unwrap();
+        assert_eq!(provider1.call_count(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_failover_disables_serpapi_with_rejected_key() {
+        use crate::providers::SerpApiProvider;
+        use wiremock::{matchers::path, Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(path("/search.json"))
+            .respond_with(ResponseTemplate::new(401).set_body_json(serde_json::json!({
+                "error": "Invalid API key."
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let serpapi = SerpApiProvider::new("bad-key")
+            .unwrap()
+            .with_base_url(&format!("{}/search.json", server.uri()));
+        let config = MultiProviderConfig::new(MultiProviderStrategy::Failover)
+            .add_provider(Box::new(serpapi))
+            .add_provider(Box::new(MockProvider::new("fallback")));
+        let multi_search = MultiProviderSearch::new(config);
+        let options = create_test_options("test query");
+
+        for _ in 0..2 {
+            let results = multi_search.search(&options).await.unwrap();
+            assert_eq!(results[0].provider.as_deref(), Some("fallback"));
+        }
+        let disabled = multi_search.disabled_providers();
+        assert_eq!(disabled.len(), 1);
+        assert_eq!(disabled[0].0, "serpapi");
+    }
+
+    #[tokio::test]
+    async fn test_disabled_provider_is_retried_after_cooldown() {
+        let provider1 = MockProvider::new("provider1").fail_times(
+            1,
+            SearchError::HttpError {
+                message: "quota exceeded".to_string(),
+                status_code: Some(403),
+                response_body: None,
+            },
+        );
+        let config = MultiProviderConfig::new(MultiProviderStrategy::Failover)
+            .add_provider(Box::new(provider1.clone()))
+            .add_provider(Box::new(MockProvider::new("provider2")))
+            .with_disable_cooldown(Some(Duration::from_millis(50)));
+        let multi_search = MultiProviderSearch::new(config);
+        let options = create_test_options("test query");
+
+        multi_search.search(&options).await.unwrap();
+        multi_search.search(&options).await.unwrap();
+        assert_eq!(provider1.call_count(), 1);
+        assert_eq!(multi_search.disabled_providers().len(), 1);
+
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        assert!(multi_search.disabled_providers().is_empty());
+        let results = multi_search.search(&options).await.unwrap();
+        assert_eq!(results[0].provider.as_deref(), Some("provider1"));
+        assert_eq!(provider1.call_count(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_failover_skips_provider_without_the_locale() {
+        let provider1 = MockProvider::new("provider1").with_error(SearchError::Unsupported(
+            "Brave does not support search language: sw".to_string(),
+        ));
+        let provider2 = MockProvider::new("provider2");
+        let config = MultiProviderConfig::new(MultiProviderStrategy::Failover)
+            .add_provider(Box::new(provider1))
+            .add_provider(Box::new(provider2));
+        let multi_search = MultiProviderSearch::new(config);
+
+        let results = multi_search
+            .search(&create_test_options("habari"))
+            .await
+            .unwrap();
+        assert_eq!(results[0].provider.as_deref(), Some("provider2"));
+        assert!(multi_search.disabled_providers().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_build_validates_credentials() {
+        let provider1 = MockProvider::new("provider1")
+            .with_error(SearchError::AuthenticationError("invalid key".to_string()));
+        let provider2 = MockProvider::new("provider2").fail_times(
+            1,
+            SearchError::HttpError {
+                message: "connection reset".to_string(),
+                status_code: None,
+                response_body: None,
+            },
+        );
+        let (multi_search, report) = MultiProviderConfig::new(MultiProviderStrategy::Failover)
+            .add_provider(Box::new(provider1.clone()))
+            .add_provider(Box::new(provider2))
+            .with_credential_validation(true)
+            .build()
+            .await;
+
+        assert_eq!(report.checks.len(), 2);
+        assert_eq!(report.usable(), ["provider2"]);
+        assert!(report.checks[1].error.is_some());
+        assert_eq!(multi_search.disabled_providers()[0].0, "provider1");
+
+        let results = multi_search
+            .search(&create_test_options("test query"))
+            .await
+            .unwrap();
+        assert_eq!(results[0].provider.as_deref(), Some("provider2"));
+        assert_eq!(provider1.call_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_failover_fails_fast_on_invalid_input() {
+        let provider1 = MockProvider::new("provider1")
+            .with_error(SearchError::InvalidInput("query is empty".to_string()));
+        let provider2 = MockProvider::new("provider2");
+        let config = MultiProviderConfig::new(MultiProviderStrategy::Failover)
+            .add_provider(Box::new(provider1))
+            .add_provider(Box::new(provider2.clone()));
+        let multi_search = MultiProviderSearch::new(config);
+
+        let result = multi_search.search(&create_test_options("")).await;
+        assert!(matches!(result, Err(SearchError::InvalidInput(_))));
+        assert_eq!(provider2.call_count(), 0);
+        assert!(multi_search.disabled_providers().is_empty());
+    }
+
     #[tokio::test]
     async fn test_load_balance_strategy() {
         let provider1 = MockProvider::new("provider1");
@@ -565,20 +1593,20 @@ mod tests {
             .add_provider(Box::new(provider1))
             .add_provider(Box::new(provider2));
 
-        let mut multi_search = MultiProviderSearch::new(config);
+        let multi_search = MultiProviderSearch::new(config);
         let options = create_test_options("test query");
 
         // First request should go to provider1 (index 0)
         let results1 = multi_search.search(&options).await.unwrap();
-        assert_eq!(results1[0].provider, Some("provider1".to_string()));
+        assert_eq!(results1[0].provider.as_deref(), Some("provider1"));
 
         // Second request should go to provider2 (index 1)
         let results2 = multi_search.search(&options).await.unwrap();
-        assert_eq!(results2[0].provider, Some("provider2".to_string()));
+        assert_eq!(results2[0].provider.as_deref(), Some("provider2"));
 
         // Third request should go back to provider1 (round-robin)
         let results3 = multi_search.search(&options).await.unwrap();
-        assert_eq!(results3[0].provider, Some("provider1".to_string()));
+        assert_eq!(results3[0].provider.as_deref(), Some("provider1"));
     }
 
     #[tokio::test]
@@ -589,8 +1617,9 @@ mod tests {
             snippet: Some("Provider1 content".to_string()),
             domain: None,
             published_date: None,
-            provider: Some("provider1".to_string()),
+            provider: Some("provider1".into()),
             raw: None,
+            ..Default::default()
         }]);
         let provider2 = MockProvider::new("provider2").with_results(vec![SearchResult {
             title: "Provider2 Result".to_string(),
@@ -598,15 +1627,16 @@ mod tests {
             snippet: Some("Provider2 content".to_string()),
             domain: None,
             published_date: None,
-            provider: Some("provider2".to_string()),
+            provider: Some("provider2".into()),
             raw: None,
+            ..Default::default()
         }]);
 
         let config = MultiProviderConfig::new(MultiProviderStrategy::Aggregate)
             .add_provider(Box::new(provider1))
             .add_provider(Box::new(provider2));
 
-        let mut multi_search = MultiProviderSearch::new(config);
+        let multi_search = MultiProviderSearch::new(config);
         let options = create_test_options("test query");
 
         let results = multi_search.search(&options).await.unwrap();
@@ -634,12 +1664,12 @@ mod tests {
             .add_provider(Box::new(provider1))
             .add_provider(Box::new(provider2));
 
-        let mut multi_search = MultiProviderSearch::new(config);
+        let multi_search = MultiProviderSearch::new(config);
         let options = create_test_options("test query");
 
         let results = multi_search.search(&options).await.unwrap();
         assert_eq!(results.len(), 2);
-        assert_eq!(results[0].provider, Some("provider2".to_string()));
+        assert_eq!(results[0].provider.as_deref(), Some("provider2"));
     }
 
     #[tokio::test]
@@ -659,7 +1689,7 @@ mod tests {
             .add_provider(Box::new(provider1))
             .add_provider(Box::new(provider2));
 
-        let mut multi_search = MultiProviderSearch::new(config);
+        let multi_search = MultiProviderSearch::new(config);
         let options = create_test_options("test query");
 
         let result = multi_search.search(&options).await;
@@ -681,13 +1711,13 @@ mod tests {
             .add_provider(Box::new(provider1))
             .add_provider(Box::new(provider2));
 
-        let mut multi_search = MultiProviderSearch::new(config);
+        let multi_search = MultiProviderSearch::new(config);
         let options = create_test_options("test query");
 
         let results = multi_search.search(&options).await.unwrap();
         assert_eq!(results.len(), 2);
         // First provider should win since they all succeed immediately
-        assert_eq!(results[0].provider, Some("provider1".to_string()));
+        assert_eq!(results[0].provider.as_deref(), Some("provider1"));
     }
 
     #[tokio::test]
@@ -703,12 +1733,12 @@ mod tests {
             .add_provider(Box::new(provider1))
             .add_provider(Box::new(provider2));
 
-        let mut multi_search = MultiProviderSearch::new(config);
+        let multi_search = MultiProviderSearch::new(config);
         let options = create_test_options("test query");
 
         let results = multi_search.search(&options).await.unwrap();
         assert_eq!(results.len(), 2);
-        assert_eq!(results[0].provider, Some("provider2".to_string()));
+        assert_eq!(results[0].provider.as_deref(), Some("provider2"));
     }
 
     #[tokio::test]
@@ -724,7 +1754,7 @@ mod tests {
             .add_provider(Box::new(provider1))
             .add_provider(Box::new(provider2));
 
-        let mut multi_search = MultiProviderSearch::new(config);
+        let multi_search = MultiProviderSearch::new(config);
         let options = create_test_options("test query");
 
         // Perform multiple searches to build up stats
@@ -746,15 +1776,345 @@ mod tests {
         assert_eq!(provider2_stats.total_requests, 0);
     }
 
+    #[tokio::test]
+    async fn test_shared_search_across_tasks() {
+        let config = MultiProviderConfig::new(MultiProviderStrategy::Aggregate)
+            .add_provider(Box::new(
+                MockProvider::new("provider1").with_delay(Duration::from_millis(10)),
+            ))
+            .add_provider(Box::new(MockProvider::new("provider2")));
+        let multi_search = Arc::new(MultiProviderSearch::new(config));
+
+        let tasks: Vec<_> = (0..16)
+            .map(|i| {
+                let multi_search = multi_search.clone();
+                tokio::spawn(async move {
+                    multi_search
+                        .search(&create_test_options(&format!("query {i}")))
+                        .await
+                })
+            })
+            .collect();
+        for task in tasks {
+            assert_eq!(task.await.unwrap().unwrap().len(), 4);
+        }
+
+        let stats = multi_search.get_stats();
+        assert_eq!(stats["provider1"].total_requests, 16);
+        assert_eq!(stats["provider2"].successful_requests, 16);
+    }
+
+    #[tokio::test]
+    async fn test_search_named_provider() {
+        let provider1 = MockProvider::new("provider1");
+        let provider2 = MockProvider::new("provider2");
+
+        let config = MultiProviderConfig::new(MultiProviderStrategy::Failover)
+            .add_provider(Box::new(provider1))
+            .add_provider(Box::new(provider2));
+
+        let multi_search = MultiProviderSearch::new(config);
+        let options = create_test_options("test query");
+
+        let results = multi_search
+            .search_provider("provider2", &options)
+            .await
+            .unwrap();
+        assert_eq!(results[0].provider.as_deref(), Some("provider2"));
+        assert_eq!(multi_search.get_stats()["provider2"].total_requests, 1);
+        assert_eq!(multi_search.get_stats()["provider1"].total_requests, 0);
+
+        assert!(matches!(
+            multi_search.search_provider("missing", &options).await,
+            Err(SearchError::InvalidInput(_))
+        ));
+        assert_eq!(multi_search.provider_names(), ["provider1", "provider2"]);
+    }
+
+    #[tokio::test]
+    async fn test_tiered_failover() {
+        let unavailable = || SearchError::Other("down".to_string());
+        let google = MockProvider::new("google").with_error(unavailable());
+        let brave = MockProvider::new("brave").with_error(unavailable());
+        let duckduckgo = MockProvider::new("duckduckgo");
+        let config = MultiProviderConfig::new(MultiProviderStrategy::Failover)
+            .add_provider(Box::new(duckduckgo.clone()))
+            .add_provider(Box::new(google.clone()))
+            .add_provider(Box::new(brave.clone()))
+            .with_tier(["google", "brave"])
+            .with_tier(["duckduckgo"]);
+        let multi_search = MultiProviderSearch::new(config);
+
+        let results = multi_search
+            .search(&create_test_options("test query"))
+            .await
+            .unwrap();
+        assert_eq!(results[0].provider.as_deref(), Some("duckduckgo"));
+        assert_eq!(
+            (
+                google.call_count(),
+                brave.call_count(),
+                duckduckgo.call_count()
+            ),
+            (1, 1, 1)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_custom_routing_strategy() {
+        /// Queries mentioning papers go to arxiv, the rest fail over from web to backup
+        #[derive(Debug)]
+        struct PaperRouting;
+
+        #[async_trait::async_trait]
+        impl RoutingStrategy for PaperRouting {
+            async fn route(
+                &self,
+                providers: &[&str],
+                query: &SearchQuery,
+                _stats: &HashMap<String, ProviderStats>,
+            ) -> RoutingPlan {
+                assert_eq!(providers, ["arxiv", "web", "backup"]);
+                if query.query.contains("paper") {
+                    RoutingPlan::single("arxiv")
+                } else {
+                    RoutingPlan::failover(["missing", "web", "backup"])
+                }
+            }
+        }
+
+        let arxiv = MockProvider::new("arxiv");
+        let web = MockProvider::new("web").with_error(SearchError::Other("down".to_string()));
+        let backup = MockProvider::new("backup");
+        let config =
+            MultiProviderConfig::new(MultiProviderStrategy::Custom(Arc::new(PaperRouting)))
+                .add_provider(Box::new(arxiv.clone()))
+                .add_provider(Box::new(web.clone()))
+                .add_provider(Box::new(backup.clone()));
+        let multi_search = MultiProviderSearch::new(config);
+
+        let results = multi_search
+            .search(&create_test_options("transformer paper"))
+            .await
+            .unwrap();
+        assert_eq!(results[0].provider.as_deref(), Some("arxiv"));
+
+        let results = multi_search
+            .search(&create_test_options("rust"))
+            .await
+            .unwrap();
+        assert_eq!(results[0].provider.as_deref(), Some("backup"));
+        assert_eq!(
+            (arxiv.call_count(), web.call_count(), backup.call_count()),
+            (1, 1, 1)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_per_search_provider_subset() {
+        let first = MockProvider::new("first");
+        let second = MockProvider::new("second");
+        let third = MockProvider::new("third");
+        let config = MultiProviderConfig::new(MultiProviderStrategy::Aggregate)
+            .add_provider(Box::new(first.clone()))
+            .add_provider(Box::new(second.clone()))
+            .add_provider(Box::new(third.clone()));
+        let multi_search = MultiProviderSearch::new(config);
+
+        let options = SearchOptionsMulti {
+            providers: Some(vec!["third".to_string(), "first".to_string()]),
+            ..create_test_options("test query")
+        };
+        let response = multi_search.search_with_strategy(&options).await.unwrap();
+        let providers: HashSet<_> = response
+            .results
+            .iter()
+            .filter_map(|r| r.provider.as_deref())
+            .collect();
+        assert_eq!(providers, HashSet::from(["first", "third"]));
+        assert_eq!(
+            (first.call_count(), second.call_count(), third.call_count()),
+            (1, 0, 1)
+        );
+
+        let options = SearchOptionsMulti {
+            providers: Some(vec!["missing".to_string()]),
+            ..create_test_options("test query")
+        };
+        assert!(matches!(
+            multi_search.search_with_strategy(&options).await,
+            Err(SearchError::InvalidInput(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_video_search_skips_providers_without_video() {
+        let web = MockProvider::new("web");
+        let video = MockProvider::new("video").with_modes([SearchMode::Web, SearchMode::Video]);
+        let config = MultiProviderConfig::new(MultiProviderStrategy::Aggregate)
+            .add_provider(Box::new(web.clone()))
+            .add_provider(Box::new(video.clone()));
+        let multi_search = MultiProviderSearch::new(config);
+
+        let options = SearchOptionsMulti {
+            mode: SearchMode::Video,
+            ..create_test_options("test query")
+        };
+        multi_search.search_with_strategy(&options).await.unwrap();
+        assert_eq!((web.call_count(), video.call_count()), (0, 1));
+
+        let options = SearchOptionsMulti {
+            providers: Some(vec!["web".to_string()]),
+            ..options
+        };
+        assert!(matches!(
+            multi_search.search_with_strategy(&options).await,
+            Err(SearchError::InvalidInput(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_parallel_tier_takes_first_success() {
+        let slow = MockProvider::new("slow").with_delay(Duration::from_millis(500));
+        let fast = MockProvider::new("fast").with_delay(Duration::from_millis(10));
+        let fallback = MockProvider::new("fallback");
+        let config = MultiProviderConfig::new(MultiProviderStrategy::Failover)
+            .add_provider(Box::new(slow))
+            .add_provider(Box::new(fast))
+            .add_provider(Box::new(fallback.clone()))
+            .with_tier(["slow", "fast"])
+            .with_parallel_tiers(true);
+        let multi_search = MultiProviderSearch::new(config);
+
+        let started = std::time::Instant::now();
+        let results = multi_search
+            .search(&create_test_options("test query"))
+            .await
+            .unwrap();
+        assert_eq!(results[0].provider.as_deref(), Some("fast"));
+        assert!(started.elapsed() < Duration::from_millis(400));
+        assert_eq!(fallback.call_count(), 0);
+
+        // The slow search was dropped when the fast one won
+        let stats = multi_search.get_stats();
+        assert_eq!(stats["slow"].total_requests, 1);
+        assert_eq!(stats["slow"].cancelled_requests, 1);
+        assert_eq!(stats["slow"].failed_requests, 0);
+        assert_eq!(stats["fast"].successful_requests, 1);
+        assert_eq!(stats["fast"].cancelled_requests, 0);
+    }
+
+    #[tokio::test]
+    async fn test_estimated_cost() {
+        let config = MultiProviderConfig::new(MultiProviderStrategy::Aggregate)
+            .add_provider(Box::new(MockProvider::new("paid")))
+            .add_provider(Box::new(MockProvider::new("duckduckgo")))
+            .with_cost("paid", CostPerRequest::per_thousand(10.0));
+        let multi_search = MultiProviderSearch::new(config);
+
+        for _ in 0..3 {
+            multi_search
+                .search(&create_test_options("test query"))
+                .await
+                .unwrap();
+        }
+
+        let stats = multi_search.get_stats();
+        assert!((stats["paid"].estimated_cost_usd - 0.03).abs() < 1e-9);
+        assert_eq!(stats["duckduckgo"].estimated_cost_usd, 0.0);
+        assert!((multi_search.estimated_cost() - 0.03).abs() < 1e-9);
+        assert!((multi_search.spent_today() - 0.03).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_default_costs_match_provider_names() {
+        use crate::providers::{GoogleScholarProvider, SerpApiProvider};
+
+        let scholar = GoogleScholarProvider::new("key").unwrap();
+        let serpapi = SerpApiProvider::new("key").unwrap();
+        assert!(CostPerRequest::default_for(scholar.name()).usd > 0.0);
+        assert!(CostPerRequest::default_for(serpapi.name()).usd > 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_search_budget_skips_expensive_providers() {
+        let config = MultiProviderConfig::new(MultiProviderStrategy::Aggregate)
+            .add_provider(Box::new(MockProvider::new("cheap")))
+            .add_provider(Box::new(MockProvider::new("pricey")))
+            .add_provider(Box::new(MockProvider::new("free")))
+            .with_cost("cheap", CostPerRequest::usd(0.01))
+            .with_cost("pricey", CostPerRequest::usd(0.05))
+            .with_max_concurrent(1)
+            .with_search_budget(0.02);
+        let multi_search = MultiProviderSearch::new(config);
+
+        let results = multi_search
+            .search(&create_test_options("test query"))
+            .await
+            .unwrap();
+        let providers: HashSet<_> = results.iter().filter_map(|r| r.provider.clone()).collect();
+        assert_eq!(providers, HashSet::from(["cheap".into(), "free".into()]));
+
+        // The budget applies to each search separately
+        multi_search
+            .search(&create_test_options("test query"))
+            .await
+            .unwrap();
+        let stats = multi_search.get_stats();
+        assert_eq!(stats["cheap"].total_requests, 2);
+        assert_eq!(stats["pricey"].total_requests, 0);
+        assert_eq!(stats["pricey"].skipped_over_budget, 2);
+    }
+
+    #[tokio::test]
+    async fn test_daily_budget_stops_paid_failover() {
+        let config = MultiProviderConfig::new(MultiProviderStrategy::Failover)
+            .add_provider(Box::new(MockProvider::new("paid")))
+            .with_cost("paid", CostPerRequest::usd(0.01))
+            .with_daily_budget(0.025);
+        let multi_search = MultiProviderSearch::new(config);
+        let options = create_test_options("test query");
+
+        multi_search.search(&options).await.unwrap();
+        multi_search.search(&options).await.unwrap();
+        assert!(matches!(
+            multi_search.search(&options).await,
+            Err(SearchError::BudgetExceeded { .. })
+        ));
+        assert!((multi_search.spent_today() - 0.02).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_shared_provider_across_configs() {
+        let shared: Arc<dyn SearchProvider> = Arc::new(MockProvider::new("shared"));
+
+        let failover = MultiProviderSearch::new(
+            MultiProviderConfig::new(MultiProviderStrategy::Failover)
+                .add_shared_provider(shared.clone()),
+        );
+        let aggregate = MultiProviderSearch::new(
+            MultiProviderConfig::new(MultiProviderStrategy::Aggregate)
+                .add_shared_provider(shared.clone())
+                .add_provider(Box::new(MockProvider::new("other"))),
+        );
+
+        let options = create_test_options("test query");
+        let (first, second) = tokio::join!(failover.search(&options), aggregate.search(&options));
+
+        assert_eq!(first.unwrap()[0].provider.as_deref(), Some("shared"));
+        assert_eq!(second.unwrap().len(), 4);
+        assert_eq!(Arc::strong_count(&shared), 3);
+    }
+
     #[tokio::test]
     async fn test_timeout_functionality() {
-        let slow_provider = MockProvider::new("slow").with_delay(100); // 100ms delay
+        let slow_provider = MockProvider::new("slow").with_delay(Duration::from_millis(100)); // 100ms delay
 
         let config = MultiProviderConfig::new(MultiProviderStrategy::Failover)
             .add_provider(Box::new(slow_provider))
             .with_timeout(Duration::from_millis(50)); // 50ms timeout
 
-        let mut multi_search = MultiProviderSearch::new(config);
+        let multi_search = MultiProviderSearch::new(config);
         let options = create_test_options("test query");
 
         let result = multi_search.search(&options).await;
@@ -767,6 +2127,84 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_aggregate_deadline_returns_partial_results() {
+        let config = MultiProviderConfig::new(MultiProviderStrategy::Aggregate)
+            .add_provider(Box::new(MockProvider::new("fast")))
+            .add_provider(Box::new(
+                MockProvider::new("slow").with_delay(Duration::from_millis(500)),
+            ))
+            .with_deadline(Duration::from_millis(50));
+        let multi_search = MultiProviderSearch::new(config);
+        let options = create_test_options("test query");
+
+        let response = multi_search.search_response(&options).await.unwrap();
+        assert!(response.partial);
+        assert_eq!(response.results.len(), 2);
+        assert!(response
+            .results
+            .iter()
+            .all(|r| r.provider.as_deref() == Some("fast")));
+        assert_eq!(multi_search.get_stats()["slow"].errors.timeout, 1);
+    }
+
+    #[tokio::test]
+    async fn test_deadline_not_reached_is_complete() {
+        let config = MultiProviderConfig::new(MultiProviderStrategy::Aggregate)
+            .add_provider(Box::new(MockProvider::new("fast")))
+            .with_deadline(Duration::from_secs(5));
+        let multi_search = MultiProviderSearch::new(config);
+
+        let response = multi_search
+            .search_response(&create_test_options("test query"))
+            .await
+            .unwrap();
+        assert!(!response.partial);
+        assert_eq!(response.results.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_failover_stops_at_deadline() {
+        let next = MockProvider::new("next");
+        let config = MultiProviderConfig::new(MultiProviderStrategy::Failover)
+            .add_provider(Box::new(
+                MockProvider::new("slow").with_delay(Duration::from_millis(500)),
+            ))
+            .add_provider(Box::new(next.clone()))
+            .with_deadline(Duration::from_millis(50));
+        let multi_search = MultiProviderSearch::new(config);
+
+        let error = multi_search
+            .search(&create_test_options("test query"))
+            .await
+            .unwrap_err();
+        assert!(matches!(error, SearchError::Timeout { timeout_ms: 50 }));
+        assert_eq!(next.call_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_queue_wait_stops_at_deadline() {
+        let provider = MockProvider::new("queued-deadline-test");
+        queue::set_provider_queue(provider.name(), Some(queue::ProviderQueue::new(0.5)));
+        // Take the slot, so the search has to wait two seconds for the next one
+        queue::wait_turn(provider.name(), None).await.unwrap();
+
+        let config = MultiProviderConfig::new(MultiProviderStrategy::Failover)
+            .add_provider(Box::new(provider.clone()))
+            .with_deadline(Duration::from_millis(50));
+        let started = Instant::now();
+        let error = MultiProviderSearch::new(config)
+            .search(&create_test_options("test query"))
+            .await
+            .unwrap_err();
+
+        assert!(matches!(error, SearchError::Timeout { timeout_ms: 50 }));
+        assert!(started.elapsed() < Duration::from_secs(1));
+        assert_eq!(provider.call_count(), 0);
+        assert_eq!(queue::queue_depth(provider.name()), 0);
+        queue::set_provider_queue(provider.name(), None);
+    }
+
     #[tokio::test]
     async fn test_max_results_in_aggregate() {
         let provider1 = MockProvider::new("provider1").with_results(vec![
@@ -776,8 +2214,9 @@ mod tests {
                 snippet: Some("Content 1".to_string()),
                 domain: None,
                 published_date: None,
-                provider: Some("provider1".to_string()),
+                provider: Some("provider1".into()),
                 raw: None,
+                ..Default::default()
             },
             SearchResult {
                 title: "Result 2".to_string(),
@@ -785,8 +2224,9 @@ mod tests {
                 snippet: Some("Content 2".to_string()),
                 domain: None,
                 published_date: None,
-                provider: Some("provider1".to_string()),
+                provider: Some("provider1".into()),
                 raw: None,
+                ..Default::default()
             },
         ]);
         let provider2 = MockProvider::new("provider2").with_results(vec![
@@ -796,8 +2236,9 @@ mod tests {
                 snippet: Some("Content 3".to_string()),
                 domain: None,
                 published_date: None,
-                provider: Some("provider2".to_string()),
+                provider: Some("provider2".into()),
                 raw: None,
+                ..Default::default()
             },
             SearchResult {
                 title: "Result 4".to_string(),
@@ -805,8 +2246,9 @@ mod tests {
                 snippet: Some("Content 4".to_string()),
                 domain: None,
                 published_date: None,
-                provider: Some("provider2".to_string()),
+                provider: Some("provider2".into()),
                 raw: None,
+                ..Default::default()
             },
         ]);
 
@@ -814,7 +2256,7 @@ mod tests {
             .add_provider(Box::new(provider1))
             .add_provider(Box::new(provider2));
 
-        let mut multi_search = MultiProviderSearch::new(config);
+        let multi_search = MultiProviderSearch::new(config);
         let mut options = create_test_options("test query");
         options.max_results = Some(3); // Limit to 3 results
 
@@ -822,10 +2264,168 @@ mod tests {
         assert_eq!(results.len(), 3); // Should be limited to 3 results
     }
 
+    #[tokio::test]
+    async fn test_aggregate_dedup_and_per_provider_results() {
+        let result = |url: &str, provider: &str| SearchResult {
+            title: url.to_string(),
+            url: url.to_string(),
+            provider: Some(provider.into()),
+            ..Default::default()
+        };
+        let provider1 = MockProvider::new("provider1").with_results(vec![
+            result("https://www.example.com/page/", "provider1"),
+            result("https://example.com/other", "provider1"),
+        ]);
+        let provider2 = MockProvider::new("provider2").with_results(vec![
+            result("http://example.com/page#intro", "provider2"),
+            result("https://example.org/", "provider2"),
+        ]);
+
+        let config = MultiProviderConfig::new(MultiProviderStrategy::Aggregate)
+            .add_provider(Box::new(provider1))
+            .add_provider(Box::new(provider2))
+            .with_dedup(true)
+            .with_max_concurrent(1);
+
+        let multi_search = MultiProviderSearch::new(config);
+        let results = multi_search
+            .search(&create_test_options("test query"))
+            .await
+            .unwrap();
+
+        let urls: Vec<_> = results.iter().map(|r| r.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            [
+                "https://www.example.com/page/",
+                "https://example.com/other",
+                "https://example.org/"
+            ]
+        );
+
+        // The first result was returned by both providers
+        let sources: Vec<_> = results[0]
+            .sources
+            .iter()
+            .map(|hit| (&*hit.provider, hit.rank))
+            .collect();
+        assert_eq!(sources, [("provider1", 1), ("provider2", 1)]);
+        assert_eq!(results[2].sources[0].rank, 2);
+    }
+
+    #[tokio::test]
+    async fn test_aggregate_processors_run_before_truncation() {
+        let config = MultiProviderConfig::new(MultiProviderStrategy::Aggregate)
+            .add_provider(Box::new(MockProvider::new("provider1")))
+            .add_provider(Box::new(MockProvider::new("provider2")))
+            .with_processor(crate::processors::Filter::new(|result: &SearchResult| {
+                result.provider.as_deref() == Some("provider2")
+            }));
+
+        let multi_search = MultiProviderSearch::new(config);
+        let options = SearchOptionsMulti {
+            max_results: Some(2),
+            ..create_test_options("test query")
+        };
+        let results = multi_search.search(&options).await.unwrap();
+
+        assert_eq!(results.len(), 2);
+        assert!(results
+            .iter()
+            .all(|r| r.provider.as_deref() == Some("provider2")));
+    }
+
+    #[derive(Debug)]
+    struct ReverseReranker;
+
+    #[async_trait::async_trait]
+    impl Reranker for ReverseReranker {
+        async fn rerank(
+            &self,
+            _query: &str,
+            mut results: Vec<SearchResult>,
+        ) -> Result<Vec<SearchResult>> {
+            results.reverse();
+            Ok(results)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_aggregate_reranks_before_truncation() {
+        let config = MultiProviderConfig::new(MultiProviderStrategy::Aggregate)
+            .add_provider(Box::new(MockProvider::new("provider1")))
+            .add_provider(Box::new(MockProvider::new("provider2")))
+            .with_reranker(ReverseReranker);
+
+        let multi_search = MultiProviderSearch::new(config);
+        let options = SearchOptionsMulti {
+            max_results: Some(1),
+            ..create_test_options("test query")
+        };
+        let results = multi_search.search(&options).await.unwrap();
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].url, "https://provider2.com/2");
+    }
+
+    #[tokio::test]
+    async fn test_per_provider_results_caps_each_provider() {
+        let config = MultiProviderConfig::new(MultiProviderStrategy::Aggregate)
+            .add_provider(Box::new(
+                MockProvider::new("provider1").with_results(sample_results("provider1", 5)),
+            ))
+            .add_provider(Box::new(
+                MockProvider::new("provider2").with_results(sample_results("provider2", 5)),
+            ))
+            .with_per_provider_results(2);
+        let multi_search = MultiProviderSearch::new(config);
+
+        let mut options = create_test_options("test query");
+        options.max_results = Some(10);
+        let results = multi_search.search(&options).await.unwrap();
+
+        for provider in ["provider1", "provider2"] {
+            let count = results
+                .iter()
+                .filter(|result| result.provider.as_deref() == Some(provider))
+                .count();
+            assert_eq!(count, 2, "results from {provider}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_search_batch_runs_every_query() {
+        let provider1 = MockProvider::new("provider1").with_delay(Duration::from_millis(10));
+        let provider2 = MockProvider::new("provider2").with_delay(Duration::from_millis(10));
+
+        let config = MultiProviderConfig::new(MultiProviderStrategy::Aggregate)
+            .add_provider(Box::new(provider1))
+            .add_provider(Box::new(provider2));
+
+        let multi_search = MultiProviderSearch::new(config);
+        let queries = vec![
+            create_test_options("first"),
+            create_test_options("second"),
+            create_test_options("third"),
+        ];
+
+        let results = multi_search.search_batch(queries, 2).await;
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[1].query.query, "second");
+        assert!(results
+            .iter()
+            .all(|item| item.result.as_ref().unwrap().len() == 4));
+
+        let stats = multi_search.get_stats();
+        assert_eq!(stats["provider1"].total_requests, 3);
+        assert_eq!(stats["provider2"].successful_requests, 3);
+    }
+
     #[tokio::test]
     async fn test_empty_providers_config() {
         let config = MultiProviderConfig::new(MultiProviderStrategy::Failover);
-        let mut multi_search = MultiProviderSearch::new(config);
+        let multi_search = MultiProviderSearch::new(config);
         let options = create_test_options("test query");
 
         let result = multi_search.search(&options).await;
@@ -837,4 +2437,71 @@ mod tests {
             _ => panic!("Expected 'No providers configured' error"),
         }
     }
+    #[test]
+    fn test_percentile() {
+        let sorted: Vec<f64> = (1..=100).map(f64::from).collect();
+        assert_eq!(percentile(&sorted, 50.0), Some(50.0));
+        assert_eq!(percentile(&sorted, 99.0), Some(99.0));
+        assert_eq!(percentile(&[7.0], 90.0), Some(7.0));
+        assert_eq!(percentile(&[], 50.0), None);
+    }
+
+    #[test]
+    fn test_error_counts_by_kind() {
+        let http = |status| SearchError::HttpError {
+            message: String::new(),
+            status_code: Some(status),
+            response_body: None,
+        };
+
+        let mut errors = ErrorCounts::default();
+        for error in [
+            SearchError::Timeout { timeout_ms: 100 },
+            http(429),
+            SearchError::RateLimit("slow down".to_string()),
+            http(503),
+            http(401),
+            SearchError::AuthenticationError("bad key".to_string()),
+            http(404),
+            SearchError::ParseError("bad json".to_string()),
+        ] {
+            errors.record(&error);
+        }
+
+        assert_eq!(
+            errors,
+            ErrorCounts {
+                timeout: 1,
+                rate_limited: 2,
+                server_error: 1,
+                auth: 2,
+                other: 2,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_stats_track_latency_and_failures() {
+        let config = MultiProviderConfig::new(MultiProviderStrategy::Failover)
+            .add_provider(Box::new(
+                MockProvider::new("flaky")
+                    .with_delay(Duration::from_millis(5))
+                    .fail_times(1, SearchError::RateLimit("slow down".to_string())),
+            ))
+            .with_timeout(Duration::from_secs(5));
+        let multi_search = MultiProviderSearch::new(config);
+        let options = create_test_options("test query");
+
+        assert!(multi_search.search(&options).await.is_err());
+        let stats = &multi_search.get_stats()["flaky"];
+        assert_eq!(stats.errors.rate_limited, 1);
+        assert!(stats.last_failure.is_some() && stats.last_success.is_none());
+        assert_eq!(stats.p50_latency_ms, None);
+
+        multi_search.search(&options).await.unwrap();
+        let stats = &multi_search.get_stats()["flaky"];
+        assert!(stats.last_success.is_some());
+        assert!(stats.p50_latency_ms.unwrap() >= 5.0);
+        assert_eq!(stats.p50_latency_ms, stats.p99_latency_ms);
+    }
 }
@@ -0,0 +1,44 @@
+//! The traits and types most searches need, for a single glob import
+//!
+//! ```rust,no_run
+//! use websearch::prelude::*;
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let results = web_search(SearchOptions {
+//!     query: "rust async".to_string(),
+//!     provider: Box::new(DuckDuckGoProvider::new()),
+//!     ..Default::default()
+//! })
+//! .await?;
+//!
+//! let config = MultiProviderConfig::new(MultiProviderStrategy::Aggregate)
+//!     .add_provider(Box::new(DuckDuckGoProvider::new()))
+//!     .add_provider(Box::new(BraveProvider::new("api_key")?));
+//! let results = MultiProviderSearch::new(config)
+//!     .search(&SearchOptionsMulti::new("rust async"))
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The crate's `Result` alias is left out so it doesn't shadow `std::result::Result`.
+
+pub use crate::batch::{web_search_batch, BatchSearchResult};
+pub use crate::error::{ErrorClass, SearchError};
+pub use crate::multi_provider::{
+    MultiProviderConfig, MultiProviderSearch, MultiProviderStrategy, SearchOptionsMulti,
+};
+pub use crate::processors::ResultProcessor;
+pub use crate::providers::{
+    ArxivProvider, BraveProvider, DuckDuckGoProvider, ExaProvider, GoogleProvider,
+    GoogleScholarProvider, SearxNGProvider, SerpApiProvider, TavilyProvider, YouTubeProvider,
+};
+pub use crate::query::{web_search_all, web_search_any, QueryMatch};
+pub use crate::scholarly::{ScholarlyProvider, ScholarlyResult};
+pub use crate::types::{
+    DebugOptions, FileType, Freshness, GeoLocation, PlaceResult, ProviderHit, ResultKind,
+    RichResult, SafeSearch, SearchMode, SearchOptions, SearchProvider, SearchQuery, SearchResponse,
+    SearchResult, SortBy, SortOrder, VideoResult,
+};
+pub use crate::{multi_search, web_search, web_search_response};
@@ -0,0 +1,518 @@
+//! Result post-processing
+//!
+//! A [`ResultProcessor`] takes a search's results and returns them transformed: filtered,
+//! reordered, deduplicated or annotated. Processors are chained on
+//! [`SearchOptions::processors`](crate::SearchOptions::processors) or with
+//! [`MultiProviderConfig::with_processor`](crate::multi_provider::MultiProviderConfig::with_processor)
+//! and run in order after the provider returns, so custom steps such as PII scrubbing or
+//! allowlist enforcement apply to every search without wrapping each call site.
+//!
+//! Closures `Fn(Vec<SearchResult>) -> Vec<SearchResult>` are processors too.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use std::sync::Arc;
+//! use websearch::processors::{Dedup, Enrich, Filter, ResultProcessor};
+//! use websearch::{testing::sample_results, SearchResult};
+//!
+//! let processors: Vec<Arc<dyn ResultProcessor>> = vec![
+//!     Arc::new(Dedup),
+//!     Arc::new(Filter::new(|result: &SearchResult| result.url.starts_with("https://"))),
+//!     Arc::new(Enrich::new(|result: &mut SearchResult| result.snippet = None)),
+//! ];
+//!
+//! let results = websearch::processors::apply(&processors, sample_results("mock", 3));
+//! assert!(results.iter().all(|result| result.snippet.is_none()));
+//! ```
+
+use crate::types::SearchResult;
+use std::cmp::Ordering;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+use url::Url;
+
+/// A step applied to a search's results after the provider returns them
+pub trait ResultProcessor: Send + Sync {
+    /// Transform `results`, returning what the search should yield instead
+    fn process(&self, results: Vec<SearchResult>) -> Vec<SearchResult>;
+}
+
+impl<F> ResultProcessor for F
+where
+    F: Fn(Vec<SearchResult>) -> Vec<SearchResult> + Send + Sync,
+{
+    fn process(&self, results: Vec<SearchResult>) -> Vec<SearchResult> {
+        self(results)
+    }
+}
+
+impl fmt::Debug for dyn ResultProcessor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ResultProcessor")
+    }
+}
+
+/// Run `results` through each processor in turn
+pub fn apply(
+    processors: &[Arc<dyn ResultProcessor>],
+    results: Vec<SearchResult>,
+) -> Vec<SearchResult> {
+    processors
+        .iter()
+        .fold(results, |results, processor| processor.process(results))
+}
+
+/// Drop results whose URL already appeared earlier in the list
+///
+/// URLs are compared by host without `www.`, path without a trailing slash, and query
+/// string, so `http://www.example.com/a/` and `https://example.com/a` are duplicates. The
+/// [`sources`](SearchResult::sources) of a dropped duplicate are added to the result kept.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Dedup;
+
+impl ResultProcessor for Dedup {
+    fn process(&self, results: Vec<SearchResult>) -> Vec<SearchResult> {
+        let mut seen: HashMap<String, usize> = HashMap::new();
+        let mut kept: Vec<SearchResult> = Vec::with_capacity(results.len());
+        for result in results {
+            match seen.entry(dedup_key(&result.url)) {
+                Entry::Occupied(entry) => kept[*entry.get()].sources.extend(result.sources),
+                Entry::Vacant(entry) => {
+                    entry.insert(kept.len());
+                    kept.push(result);
+                }
+            }
+        }
+        kept
+    }
+}
+
+/// URL identity used for deduplication: host without `www.`, path without a trailing
+/// slash, and the query string; the scheme and fragment are ignored
+pub(crate) fn dedup_key(url: &str) -> String {
+    match Url::parse(url) {
+        Ok(parsed) => {
+            let host = parsed.host_str().unwrap_or_default().to_lowercase();
+            let host = host.strip_prefix("www.").unwrap_or(&host);
+            let path = parsed.path().trim_end_matches('/');
+            match parsed.query() {
+                Some(query) => format!("{host}{path}?{query}"),
+                None => format!("{host}{path}"),
+            }
+        }
+        Err(_) => url.to_string(),
+    }
+}
+
+type Predicate = dyn Fn(&SearchResult) -> bool + Send + Sync;
+
+/// Keep only the results matching a predicate
+#[derive(Clone)]
+pub struct Filter(Arc<Predicate>);
+
+impl Filter {
+    pub fn new(predicate: impl Fn(&SearchResult) -> bool + Send + Sync + 'static) -> Self {
+        Self(Arc::new(predicate))
+    }
+}
+
+impl ResultProcessor for Filter {
+    fn process(&self, mut results: Vec<SearchResult>) -> Vec<SearchResult> {
+        results.retain(|result| (self.0)(result));
+        results
+    }
+}
+
+impl fmt::Debug for Filter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Filter")
+    }
+}
+
+type Scorer = dyn Fn(&SearchResult) -> f64 + Send + Sync;
+
+/// Reorder results by a score, highest first
+///
+/// The sort is stable, so results with equal scores keep the provider's order.
+#[derive(Clone)]
+pub struct Rerank(Arc<Scorer>);
+
+impl Rerank {
+    pub fn new(score: impl Fn(&SearchResult) -> f64 + Send + Sync + 'static) -> Self {
+        Self(Arc::new(score))
+    }
+}
+
+impl ResultProcessor for Rerank {
+    fn process(&self, results: Vec<SearchResult>) -> Vec<SearchResult> {
+        let mut scored: Vec<(f64, SearchResult)> = results
+            .into_iter()
+            .map(|result| ((self.0)(&result), result))
+            .collect();
+        scored.sort_by(|(a, _), (b, _)| b.partial_cmp(a).unwrap_or(Ordering::Equal));
+        scored.into_iter().map(|(_, result)| result).collect()
+    }
+}
+
+impl fmt::Debug for Rerank {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Rerank")
+    }
+}
+
+/// How [`Consensus`] scores one provider's vote for a result
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConsensusMethod {
+    /// Borda count: a provider's first result scores 1, the rest less by position, down to
+    /// `1/n` for the last of its `n` results
+    #[default]
+    Borda,
+    /// One point per provider, whatever the rank
+    Vote,
+}
+
+/// Order results by how many providers returned them, weighted by trust in each provider
+///
+/// Duplicates are merged as by [`Dedup`], then each result scores the weighted sum of its
+/// [`sources`](SearchResult::sources)' votes. A provider that returned a result more than once
+/// votes once, for its best rank. Providers weigh 1 unless set otherwise. The sort is stable,
+/// so ties keep their order; results without sources score nothing.
+#[derive(Debug, Clone, Default)]
+pub struct Consensus {
+    method: ConsensusMethod,
+    weights: HashMap<String, f64>,
+}
+
+impl Consensus {
+    /// Score each provider's vote with `method`, every provider weighing 1
+    pub fn new(method: ConsensusMethod) -> Self {
+        Self {
+            method,
+            weights: HashMap::new(),
+        }
+    }
+
+    /// Count `provider`'s votes `weight` times
+    pub fn with_weight(mut self, provider: &str, weight: f64) -> Self {
+        self.weights.insert(provider.to_string(), weight);
+        self
+    }
+}
+
+impl ResultProcessor for Consensus {
+    fn process(&self, results: Vec<SearchResult>) -> Vec<SearchResult> {
+        let results = Dedup.process(results);
+
+        // Borda points depend on how many results each provider returned
+        let mut lengths: HashMap<&str, usize> = HashMap::new();
+        for hit in results.iter().flat_map(|result| &result.sources) {
+            let length = lengths.entry(&*hit.provider).or_default();
+            *length = (*length).max(hit.rank);
+        }
+        let scores: Vec<f64> = results
+            .iter()
+            .map(|result| {
+                let mut best_ranks: HashMap<&str, usize> = HashMap::new();
+                for hit in &result.sources {
+                    let rank = best_ranks.entry(&*hit.provider).or_insert(hit.rank);
+                    *rank = (*rank).min(hit.rank);
+                }
+                best_ranks
+                    .into_iter()
+                    .map(|(provider, rank)| {
+                        let weight = self.weights.get(provider).copied().unwrap_or(1.0);
+                        let points = match self.method {
+                            ConsensusMethod::Borda => {
+                                let length = lengths[provider] as f64;
+                                (length - rank as f64 + 1.0) / length
+                            }
+                            ConsensusMethod::Vote => 1.0,
+                        };
+                        weight * points
+                    })
+                    .sum()
+            })
+            .collect();
+
+        let mut scored: Vec<(f64, SearchResult)> = scores.into_iter().zip(results).collect();
+        scored.sort_by(|(a, _), (b, _)| b.partial_cmp(a).unwrap_or(Ordering::Equal));
+        scored.into_iter().map(|(_, result)| result).collect()
+    }
+}
+
+/// How [`NormalizeScores`] puts one provider's results on a 0 to 1 scale
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ScoreNormalization {
+    /// Rescale the provider's reported [`score`](SearchResult::score)s so its best result
+    /// scores 1 and its worst 0; results without a score fall back to their rank
+    #[default]
+    MinMax,
+    /// Ignore reported scores: a provider's first result scores 1, the rest less by
+    /// position, down to 0 for its last result, the same range as `MinMax`
+    Rank,
+}
+
+/// Fill in [`normalized_score`](SearchResult::normalized_score) and sort by it
+///
+/// Providers score on different scales, and most don't score at all, so each provider's
+/// results are normalized separately, in the order they come in. The sort is stable, so
+/// ties keep their order.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NormalizeScores {
+    method: ScoreNormalization,
+}
+
+impl NormalizeScores {
+    /// Normalize each provider's results with `method` ([`ScoreNormalization::MinMax`] by
+    /// default)
+    pub fn new(method: ScoreNormalization) -> Self {
+        Self { method }
+    }
+}
+
+impl ResultProcessor for NormalizeScores {
+    fn process(&self, mut results: Vec<SearchResult>) -> Vec<SearchResult> {
+        let mut by_provider: HashMap<Option<Arc<str>>, Vec<usize>> = HashMap::new();
+        for (index, result) in results.iter().enumerate() {
+            by_provider
+                .entry(result.provider.clone())
+                .or_default()
+                .push(index);
+        }
+
+        for indices in by_provider.values() {
+            let scores = indices.iter().filter_map(|&index| results[index].score);
+            let range = scores.clone().reduce(f64::min).zip(scores.reduce(f64::max));
+            let last = indices.len().saturating_sub(1) as f64;
+            for (rank, &index) in indices.iter().enumerate() {
+                // A single result is that provider's best
+                let by_rank = if last > 0.0 {
+                    (last - rank as f64) / last
+                } else {
+                    1.0
+                };
+                let result = &mut results[index];
+                result.normalized_score = Some(match (self.method, result.score, range) {
+                    (ScoreNormalization::MinMax, Some(score), Some((min, max))) if max > min => {
+                        (score - min) / (max - min)
+                    }
+                    (ScoreNormalization::MinMax, Some(_), Some(_)) => 1.0,
+                    _ => by_rank,
+                });
+            }
+        }
+
+        results.sort_by(|a, b| {
+            b.normalized_score
+                .partial_cmp(&a.normalized_score)
+                .unwrap_or(Ordering::Equal)
+        });
+        results
+    }
+}
+
+type Enricher = dyn Fn(&mut SearchResult) + Send + Sync;
+
+/// Modify each result in place, e.g. to fill in or scrub fields
+#[derive(Clone)]
+pub struct Enrich(Arc<Enricher>);
+
+impl Enrich {
+    pub fn new(enrich: impl Fn(&mut SearchResult) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(enrich))
+    }
+}
+
+impl ResultProcessor for Enrich {
+    fn process(&self, mut results: Vec<SearchResult>) -> Vec<SearchResult> {
+        results.iter_mut().for_each(|result| (self.0)(result));
+        results
+    }
+}
+
+impl fmt::Debug for Enrich {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Enrich")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::sample_results;
+    use crate::types::ProviderHit;
+
+    #[test]
+    fn test_dedup_ignores_scheme_www_and_trailing_slash() {
+        let result = |url: &str| SearchResult {
+            url: url.to_string(),
+            ..Default::default()
+        };
+        let results = Dedup.process(vec![
+            result("https://example.com/a"),
+            result("http://www.example.com/a/"),
+            result("https://example.com/a?page=2"),
+        ]);
+        assert_eq!(results.len(), 2);
+    }
+
+    #[test]
+    fn test_dedup_merges_sources() {
+        let result = |provider: &str, rank: usize, score: Option<f64>| SearchResult {
+            url: "https://example.com/a".to_string(),
+            sources: vec![ProviderHit {
+                provider: provider.into(),
+                rank,
+                score,
+            }],
+            ..Default::default()
+        };
+        let results = Dedup.process(vec![result("exa", 2, Some(0.8)), result("brave", 1, None)]);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].sources.len(), 2);
+        assert_eq!(&*results[0].sources[1].provider, "brave");
+    }
+
+    #[test]
+    fn test_consensus_boosts_results_several_providers_return() {
+        let hit = |provider: &str, rank: usize| ProviderHit {
+            provider: provider.into(),
+            rank,
+            score: None,
+        };
+        let result = |url: &str, sources: Vec<ProviderHit>| SearchResult {
+            url: url.to_string(),
+            sources,
+            ..Default::default()
+        };
+        let results = || {
+            vec![
+                result("https://a.com", vec![hit("google", 1)]),
+                result("https://b.com", vec![hit("google", 2)]),
+                result("https://c.com", vec![hit("google", 3)]),
+                result("https://d.com", vec![hit("brave", 1)]),
+                result("https://c.com/", vec![hit("brave", 2)]),
+            ]
+        };
+        let urls = |results: Vec<SearchResult>| -> Vec<String> {
+            results.into_iter().map(|result| result.url).collect()
+        };
+
+        // c.com: 1/3 + 1/2 from two providers beats b.com's 2/3 from one
+        assert_eq!(
+            urls(Consensus::default().process(results())),
+            [
+                "https://a.com",
+                "https://d.com",
+                "https://c.com",
+                "https://b.com"
+            ]
+        );
+        assert_eq!(
+            urls(Consensus::new(ConsensusMethod::Vote).process(results()))[0],
+            "https://c.com"
+        );
+        assert_eq!(
+            urls(
+                Consensus::default()
+                    .with_weight("brave", 0.1)
+                    .process(results())
+            )[1],
+            "https://b.com"
+        );
+    }
+
+    #[test]
+    fn test_consensus_counts_one_vote_per_provider() {
+        let hit = |provider: &str, rank: usize| ProviderHit {
+            provider: provider.into(),
+            rank,
+            score: None,
+        };
+        let result = |url: &str, sources: Vec<ProviderHit>| SearchResult {
+            url: url.to_string(),
+            sources,
+            ..Default::default()
+        };
+        let results = vec![
+            result("https://a.com", vec![hit("google", 1)]),
+            result("https://a.com/", vec![hit("google", 2)]),
+            result("https://b.com", vec![hit("google", 3), hit("brave", 1)]),
+        ];
+
+        // b.com's two providers outvote a.com's one provider listing it twice
+        let ranked = Consensus::new(ConsensusMethod::Vote).process(results);
+        assert_eq!(ranked[0].url, "https://b.com");
+    }
+
+    #[test]
+    fn test_normalize_scores_per_provider() {
+        let result = |provider: &str, url: &str, score: Option<f64>| SearchResult {
+            url: url.to_string(),
+            provider: Some(provider.into()),
+            score,
+            ..Default::default()
+        };
+        let results = || {
+            vec![
+                result("exa", "https://exa.com/1", Some(0.9)),
+                result("google", "https://google.com/1", None),
+                result("tavily", "https://tavily.com/1", Some(0.3)),
+                result("exa", "https://exa.com/2", Some(0.5)),
+                result("google", "https://google.com/2", None),
+                result("tavily", "https://tavily.com/2", Some(0.2)),
+                result("exa", "https://exa.com/3", Some(0.1)),
+            ]
+        };
+
+        let results = NormalizeScores::default().process(results());
+        let scored: Vec<(&str, f64)> = results
+            .iter()
+            .map(|r| (r.url.as_str(), r.normalized_score.unwrap()))
+            .collect();
+        assert_eq!(
+            scored,
+            [
+                ("https://exa.com/1", 1.0),
+                ("https://google.com/1", 1.0),
+                ("https://tavily.com/1", 1.0),
+                ("https://exa.com/2", 0.5),
+                ("https://google.com/2", 0.0),
+                ("https://tavily.com/2", 0.0),
+                ("https://exa.com/3", 0.0),
+            ]
+        );
+
+        let ranked = NormalizeScores::new(ScoreNormalization::Rank).process(vec![
+            result("exa", "https://exa.com/1", Some(0.1)),
+            result("exa", "https://exa.com/2", Some(0.9)),
+        ]);
+        assert_eq!(ranked[0].url, "https://exa.com/1");
+        assert_eq!(ranked[1].normalized_score, Some(0.0));
+    }
+
+    #[test]
+    fn test_processors_run_in_order() {
+        let processors: Vec<Arc<dyn ResultProcessor>> = vec![
+            Arc::new(Rerank::new(|result: &SearchResult| {
+                result.title.len() as f64
+            })),
+            Arc::new(|mut results: Vec<SearchResult>| {
+                results.truncate(2);
+                results
+            }),
+            Arc::new(Enrich::new(|result: &mut SearchResult| {
+                result.title = result.title.to_uppercase()
+            })),
+        ];
+        let mut results = sample_results("mock", 3);
+        results[2].title = "a much longer title than the others".to_string();
+
+        let results = apply(&processors, results);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].title, "A MUCH LONGER TITLE THAN THE OTHERS");
+    }
+}
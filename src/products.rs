@@ -0,0 +1,310 @@
+//! Product search
+//!
+//! [`ProductSearchProvider`] implementations look up products for sale with their price,
+//! merchant and rating, e.g. for price monitoring. Use [`search_products`] to validate the
+//! query and call one.
+
+use crate::{
+    error::{SearchError, SearchResult},
+    providers::parse_count,
+    utils::http::{build_url, HttpClient},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// A product offered by a merchant
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProductResult {
+    /// Product title as listed by the merchant
+    pub title: String,
+    /// Link to the offer
+    pub url: String,
+    /// Current price, in `currency`
+    pub price: Option<f64>,
+    /// ISO 4217 currency code such as "USD", when it can be told from the listing
+    pub currency: Option<String>,
+    /// Store or marketplace selling the product
+    pub merchant: Option<String>,
+    /// Average rating, usually out of 5
+    pub rating: Option<f64>,
+    /// Number of reviews behind `rating`
+    pub reviews: Option<u64>,
+    /// Product image
+    pub thumbnail_url: Option<String>,
+}
+
+/// Trait for providers that search product listings
+#[async_trait::async_trait]
+pub trait ProductSearchProvider: Send + Sync + std::fmt::Debug {
+    /// Name of the product search provider
+    fn name(&self) -> &str;
+
+    /// Return up to `max_results` products matching `query`, most relevant first
+    async fn search_products(
+        &self,
+        query: &str,
+        max_results: u32,
+    ) -> SearchResult<Vec<ProductResult>>;
+}
+
+/// Search `provider` for products matching `query`
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use websearch::products::{search_products, SerpApiShoppingProvider};
+///
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let provider = SerpApiShoppingProvider::new("YOUR_SERPAPI_KEY")?;
+/// for product in search_products("mechanical keyboard", 10, &provider).await? {
+///     println!("{} {:?} {:?}", product.title, product.price, product.currency);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub async fn search_products(
+    query: &str,
+    max_results: u32,
+    provider: &dyn ProductSearchProvider,
+) -> SearchResult<Vec<ProductResult>> {
+    if query.trim().is_empty() {
+        return Err(SearchError::InvalidInput(
+            "A query is required for product search".to_string(),
+        ));
+    }
+    if max_results == 0 {
+        return Err(SearchError::InvalidInput(
+            "max_results must be at least 1".to_string(),
+        ));
+    }
+
+    let mut products = provider.search_products(query, max_results).await?;
+    products.truncate(max_results as usize);
+    Ok(products)
+}
+
+/// Currency code for a formatted price such as "$12.99", "12,99 €" or "CAD 15.00"
+///
+/// A bare "$" is taken to be US dollars.
+fn currency_from_price(price: &str) -> Option<String> {
+    const SYMBOLS: &[(&str, &str)] = &[
+        ("US$", "USD"),
+        ("CA$", "CAD"),
+        ("A$", "AUD"),
+        ("€", "EUR"),
+        ("£", "GBP"),
+        ("¥", "JPY"),
+        ("₹", "INR"),
+        ("₩", "KRW"),
+        ("R$", "BRL"),
+        ("$", "USD"),
+    ];
+
+    let price = price.trim();
+    if let Some(code) = price
+        .split(|c: char| !c.is_ascii_alphabetic())
+        .find(|word| word.len() == 3 && word.chars().all(|c| c.is_ascii_uppercase()))
+    {
+        return Some(code.to_string());
+    }
+    SYMBOLS
+        .iter()
+        .find(|(symbol, _)| price.contains(symbol))
+        .map(|(_, code)| code.to_string())
+}
+
+#[derive(Debug, Deserialize)]
+struct SerpApiShoppingResponse {
+    #[serde(default)]
+    shopping_results: Vec<SerpApiShoppingResult>,
+    error: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SerpApiShoppingResult {
+    title: String,
+    link: Option<String>,
+    product_link: Option<String>,
+    source: Option<String>,
+    price: Option<String>,
+    extracted_price: Option<f64>,
+    rating: Option<f64>,
+    #[serde(default)]
+    reviews: Value,
+    thumbnail: Option<String>,
+}
+
+/// Google Shopping through SerpAPI
+#[derive(Debug)]
+pub struct SerpApiShoppingProvider {
+    api_key: String,
+    base_url: String,
+    country: Option<String>,
+    language: Option<String>,
+    http_client: HttpClient,
+}
+
+impl SerpApiShoppingProvider {
+    pub fn new(api_key: &str) -> SearchResult<Self> {
+        if api_key.is_empty() {
+            return Err(SearchError::ConfigError(
+                "SerpAPI key is required".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            api_key: api_key.to_string(),
+            base_url: "https://serpapi.com/search.json".to_string(),
+            country: None,
+            language: None,
+            http_client: HttpClient::new(),
+        })
+    }
+
+    /// Shop in this country, e.g. "us" or "gb"
+    pub fn with_country(mut self, country: &str) -> Self {
+        self.country = Some(country.to_lowercase());
+        self
+    }
+
+    /// Interface language, e.g. "en"
+    pub fn with_language(mut self, language: &str) -> Self {
+        self.language = Some(language.to_string());
+        self
+    }
+
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.to_string();
+        self
+    }
+}
+
+#[async_trait::async_trait]
+impl ProductSearchProvider for SerpApiShoppingProvider {
+    fn name(&self) -> &str {
+        "serpapi"
+    }
+
+    async fn search_products(
+        &self,
+        query: &str,
+        max_results: u32,
+    ) -> SearchResult<Vec<ProductResult>> {
+        let mut params = HashMap::new();
+        params.insert("engine".to_string(), "google_shopping".to_string());
+        params.insert("api_key".to_string(), self.api_key.clone());
+        params.insert("q".to_string(), query.to_string());
+        params.insert("num".to_string(), max_results.min(100).to_string());
+        if let Some(country) = &self.country {
+            params.insert("gl".to_string(), country.clone());
+        }
+        if let Some(language) = &self.language {
+            params.insert("hl".to_string(), language.clone());
+        }
+
+        let url = build_url(&self.base_url, params)?;
+        let body = self.http_client.get_text(&url).await?;
+        let response: SerpApiShoppingResponse = serde_json::from_str(&body)?;
+
+        if let Some(error) = response.error {
+            return Err(SearchError::ProviderError(format!(
+                "SerpAPI error: {error}"
+            )));
+        }
+
+        Ok(response
+            .shopping_results
+            .into_iter()
+            .filter_map(|item| {
+                let url = item.product_link.or(item.link)?;
+                Some(ProductResult {
+                    title: item.title,
+                    url,
+                    price: item.extracted_price,
+                    currency: item.price.as_deref().and_then(currency_from_price),
+                    merchant: item.source,
+                    rating: item.rating,
+                    reviews: parse_count(&item.reviews),
+                    thumbnail_url: item.thumbnail,
+                })
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::{
+        matchers::{method, query_param},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    #[tokio::test]
+    async fn test_serpapi_shopping() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(query_param("engine", "google_shopping"))
+            .and(query_param("q", "mechanical keyboard"))
+            .and(query_param("gl", "de"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "shopping_results": [{
+                    "position": 1,
+                    "title": "Keychron K2",
+                    "product_link": "https://www.google.com/shopping/product/123",
+                    "source": "Amazon.de",
+                    "price": "89,99 €",
+                    "extracted_price": 89.99,
+                    "rating": 4.6,
+                    "reviews": "1,204",
+                    "thumbnail": "https://example.com/k2.jpg"
+                }, {
+                    "title": "Generic keyboard",
+                    "link": "https://shop.example/keyboard",
+                    "price": "CAD 15.00",
+                    "extracted_price": 15.0
+                }, {
+                    "title": "No link"
+                }]
+            })))
+            .mount(&server)
+            .await;
+
+        let provider = SerpApiShoppingProvider::new("test-key")
+            .unwrap()
+            .with_country("DE")
+            .with_base_url(&server.uri());
+        let products = search_products("mechanical keyboard", 10, &provider)
+            .await
+            .unwrap();
+
+        assert_eq!(products.len(), 2);
+        assert_eq!(products[0].price, Some(89.99));
+        assert_eq!(products[0].currency.as_deref(), Some("EUR"));
+        assert_eq!(products[0].merchant.as_deref(), Some("Amazon.de"));
+        assert_eq!(products[0].rating, Some(4.6));
+        assert_eq!(products[0].reviews, Some(1204));
+        assert_eq!(products[1].url, "https://shop.example/keyboard");
+        assert_eq!(products[1].currency.as_deref(), Some("CAD"));
+        assert_eq!(products[1].reviews, None);
+    }
+
+    #[tokio::test]
+    async fn test_empty_query_is_rejected() {
+        let provider = SerpApiShoppingProvider::new("test-key").unwrap();
+        assert!(matches!(
+            search_products(" ", 10, &provider).await,
+            Err(SearchError::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn test_currency_from_price() {
+        assert_eq!(currency_from_price("$12.99").as_deref(), Some("USD"));
+        assert_eq!(currency_from_price("R$ 49,90").as_deref(), Some("BRL"));
+        assert_eq!(currency_from_price("£5").as_deref(), Some("GBP"));
+        assert_eq!(currency_from_price("12.99").as_deref(), None);
+    }
+}
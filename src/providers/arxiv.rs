@@ -2,22 +2,39 @@
 
 use crate::{
     error::{SearchError, SearchResult},
-    types::{SearchOptions, SearchProvider, SearchResult as SearchResultType},
+    providers::{collect_results, forward_http_headers},
+    scholarly::{ScholarlyProvider, ScholarlyResult},
+    types::{SearchOptions, SearchProvider, SearchResponse, SearchResult as SearchResultType},
+    utils::{http::HttpClient, intern::interned},
 };
+use bytes::Bytes;
 use serde::Deserialize;
 use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
 use url::Url;
 
+/// arXiv asks API clients to wait about three seconds between requests
+const DEFAULT_REQUEST_INTERVAL: Duration = Duration::from_secs(3);
+const DEFAULT_MAX_RETRIES: u32 = 2;
+
+/// A feed entry; entries without an id or title are skipped with a warning
 #[derive(Debug, Deserialize)]
 struct ArxivEntry {
-    id: String,
-    title: String,
-    summary: String,
-    published: String,
-    #[serde(default)]
+    id: Option<String>,
+    title: Option<String>,
+    summary: Option<String>,
+    published: Option<String>,
+    #[serde(rename = "author", default)]
     authors: Vec<ArxivAuthor>,
-    #[serde(default)]
+    #[serde(rename = "link", default)]
     links: Vec<ArxivLink>,
+    /// DOI of the published version, when the authors added one (`arxiv:doi`)
+    doi: Option<String>,
+    /// Where the paper was published (`arxiv:journal_ref`)
+    journal_ref: Option<String>,
+    #[serde(rename = "category", default)]
+    categories: Vec<ArxivCategory>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -27,10 +44,17 @@ struct ArxivAuthor {
 
 #[derive(Debug, Deserialize)]
 struct ArxivLink {
-    #[serde(rename = "href")]
+    #[serde(rename = "@href")]
     href: String,
-    #[serde(rename = "type")]
-    link_type: Option<String>,
+    /// "pdf" on the link to the full text
+    #[serde(rename = "@title")]
+    title: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ArxivCategory {
+    #[serde(rename = "@term")]
+    term: String,
 }
 
 #[derive(Debug, Deserialize)]
@@ -39,17 +63,348 @@ struct ArxivFeed {
     entries: Vec<ArxivEntry>,
 }
 
+/// Field prefixes understood by the arXiv `search_query` syntax
+const FIELD_PREFIXES: &[&str] = &[
+    "ti:", "au:", "abs:", "co:", "jr:", "cat:", "rn:", "id:", "all:",
+];
+
+/// Searchable arXiv metadata field
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArxivField {
+    /// Every field (`all:`)
+    All,
+    /// Title (`ti:`)
+    Title,
+    /// Author (`au:`)
+    Author,
+    /// Abstract (`abs:`)
+    Abstract,
+    /// Comment (`co:`)
+    Comment,
+    /// Journal reference (`jr:`)
+    JournalRef,
+    /// Subject category such as `cs.LG` (`cat:`)
+    Category,
+    /// Report number (`rn:`)
+    ReportNumber,
+}
+
+impl ArxivField {
+    pub fn prefix(&self) -> &'static str {
+        match self {
+            Self::All => "all",
+            Self::Title => "ti",
+            Self::Author => "au",
+            Self::Abstract => "abs",
+            Self::Comment => "co",
+            Self::JournalRef => "jr",
+            Self::Category => "cat",
+            Self::ReportNumber => "rn",
+        }
+    }
+}
+
+/// Builder for fielded arXiv queries
+///
+/// Produces the arXiv `search_query` syntax, e.g.
+/// `ArxivQuery::new().title("attention").and_not(ArxivField::Abstract, "vision").category("cs.CL")`
+/// becomes `(ti:attention ANDNOT abs:vision) AND cat:cs.CL`. Pass the result as
+/// `SearchOptions::query`; fielded queries are sent to arXiv unchanged.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ArxivQuery {
+    clauses: Vec<(&'static str, String)>,
+    categories: Vec<String>,
+}
+
+impl ArxivQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Require `value` in `field`
+    pub fn and(self, field: ArxivField, value: &str) -> Self {
+        self.push("AND", field, value)
+    }
+
+    /// Match `value` in `field` as an alternative to the preceding clauses
+    pub fn or(self, field: ArxivField, value: &str) -> Self {
+        self.push("OR", field, value)
+    }
+
+    /// Exclude papers with `value` in `field`
+    pub fn and_not(self, field: ArxivField, value: &str) -> Self {
+        self.push("ANDNOT", field, value)
+    }
+
+    /// Require `value` anywhere in the paper metadata
+    pub fn all(self, value: &str) -> Self {
+        self.and(ArxivField::All, value)
+    }
+
+    /// Require `value` in the title
+    pub fn title(self, value: &str) -> Self {
+        self.and(ArxivField::Title, value)
+    }
+
+    /// Require `value` in the author list
+    pub fn author(self, value: &str) -> Self {
+        self.and(ArxivField::Author, value)
+    }
+
+    /// Require `value` in the abstract
+    pub fn abstract_text(self, value: &str) -> Self {
+        self.and(ArxivField::Abstract, value)
+    }
+
+    /// Restrict results to a subject category such as `cs.LG`
+    ///
+    /// Several categories are combined with `OR`.
+    pub fn category(mut self, category: &str) -> Self {
+        self.categories.push(category.trim().to_string());
+        self
+    }
+
+    /// Render the query in arXiv `search_query` syntax
+    ///
+    /// Fails with [`SearchError::InvalidInput`] when the first clause is an `or` or
+    /// `and_not`, which has no preceding clause to combine with.
+    pub fn build(&self) -> SearchResult<String> {
+        if let Some((operator @ ("OR" | "ANDNOT"), term)) = self.clauses.first() {
+            return Err(SearchError::InvalidInput(format!(
+                "arXiv query cannot start with {operator} ({term}); add a required clause first"
+            )));
+        }
+
+        let terms = self
+            .clauses
+            .iter()
+            .enumerate()
+            .map(|(index, (operator, term))| {
+                if index == 0 {
+                    term.clone()
+                } else {
+                    format!("{operator} {term}")
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        let categories = self
+            .categories
+            .iter()
+            .map(|category| format!("cat:{category}"))
+            .collect::<Vec<_>>()
+            .join(" OR ");
+
+        if categories.is_empty() {
+            return Ok(terms);
+        }
+        let categories = if self.categories.len() > 1 {
+            format!("({categories})")
+        } else {
+            categories
+        };
+
+        Ok(match self.clauses.len() {
+            0 => categories,
+            1 => format!("{terms} AND {categories}"),
+            _ => format!("({terms}) AND {categories}"),
+        })
+    }
+
+    fn push(mut self, operator: &'static str, field: ArxivField, value: &str) -> Self {
+        let value = value.trim();
+        // Multi-word values are searched as a phrase
+        let value = if value.contains(char::is_whitespace) {
+            format!("\"{value}\"")
+        } else {
+            value.to_string()
+        };
+        self.clauses
+            .push((operator, format!("{}:{value}", field.prefix())));
+        self
+    }
+}
+
+impl TryFrom<ArxivQuery> for String {
+    type Error = SearchError;
+
+    fn try_from(query: ArxivQuery) -> SearchResult<Self> {
+        query.build()
+    }
+}
+
+/// Turn a user query into an arXiv `search_query`
+///
+/// Queries that already use field prefixes (`ti:`, `cat:`, ...) are sent unchanged;
+/// plain text searches every field.
+fn to_search_query(query: &str) -> String {
+    let query = query.trim();
+    let fielded = query.split_whitespace().any(|token| {
+        let token = token.trim_start_matches(['(', '"']);
+        FIELD_PREFIXES
+            .iter()
+            .any(|prefix| token.len() > prefix.len() && token.starts_with(prefix))
+    });
+
+    if fielded {
+        query.to_string()
+    } else {
+        format!("all:{query}")
+    }
+}
+
 #[derive(Debug)]
 pub struct ArxivProvider {
     base_url: String,
+    request_interval: Duration,
+    max_retries: u32,
+    http_client: HttpClient,
+    /// When the last request was sent, shared by all searches on this provider
+    last_request: Mutex<Option<Instant>>,
 }
 
 impl ArxivProvider {
     pub fn new() -> Self {
         Self {
             base_url: "http://export.arxiv.org/api/query".to_string(),
+            request_interval: DEFAULT_REQUEST_INTERVAL,
+            max_retries: DEFAULT_MAX_RETRIES,
+            http_client: HttpClient::new(),
+            last_request: Mutex::new(None),
+        }
+    }
+
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.to_string();
+        self
+    }
+
+    /// Minimum delay between requests sent by this provider (3 seconds by default,
+    /// as the arXiv API terms ask)
+    pub fn with_request_interval(mut self, interval: Duration) -> Self {
+        self.request_interval = interval;
+        self
+    }
+
+    /// How many times a throttled (429/503) or failed request is retried (2 by default)
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    forward_http_headers!();
+
+    /// Wait until `request_interval` has passed since the previous request
+    ///
+    /// The lock is held while sleeping so concurrent searches queue up behind each other.
+    async fn pace(&self) {
+        let mut last_request = self.last_request.lock().await;
+        if let Some(last) = *last_request {
+            let elapsed = last.elapsed();
+            if elapsed < self.request_interval {
+                tokio::time::sleep(self.request_interval - elapsed).await;
+            }
+        }
+        *last_request = Some(Instant::now());
+    }
+
+    /// Fetch the Atom feed for `url`, pacing requests and retrying throttled ones
+    async fn fetch(&self, url: &Url, options: &SearchOptions) -> SearchResult<Bytes> {
+        let mut attempt = 0;
+        loop {
+            self.pace().await;
+
+            match self.fetch_once(url).await {
+                Err(error) if attempt < self.max_retries && is_retryable(&error) => {
+                    attempt += 1;
+                    if let Some(debug) = &options.debug {
+                        if debug.enabled {
+                            log::info!(
+                                "ArXiv request failed ({error}), retry {attempt} of {}",
+                                self.max_retries
+                            );
+                        }
+                    }
+                    // Back off further on each retry, on top of the regular pacing
+                    tokio::time::sleep(self.request_interval * attempt).await;
+                }
+                result => return result,
+            }
         }
     }
+
+    async fn fetch_once(&self, url: &Url) -> SearchResult<Bytes> {
+        let (status, body) = self
+            .http_client
+            .get_bytes_with_status(url.as_str(), HashMap::new())
+            .await
+            .map_err(|e| SearchError::HttpError {
+                message: format!("ArXiv API request failed: {e}"),
+                status_code: None,
+                response_body: None,
+            })?;
+
+        if !status.is_success() {
+            return Err(SearchError::HttpError {
+                message: format!("ArXiv API returned error: {}", status),
+                status_code: Some(status.as_u16()),
+                response_body: Some(String::from_utf8_lossy(&body).into_owned()),
+            });
+        }
+
+        Ok(body)
+    }
+}
+
+/// arXiv signals throttling with 429 or 503; other server errors and connection failures
+/// are retried too
+fn is_retryable(error: &SearchError) -> bool {
+    match error {
+        SearchError::HttpError { status_code, .. } => {
+            matches!(status_code, None | Some(429) | Some(500..=599))
+        }
+        SearchError::Timeout { .. } => true,
+        _ => false,
+    }
+}
+
+/// The paper behind a feed entry
+fn convert_entry(entry: ArxivEntry) -> Result<ScholarlyResult, String> {
+    let id = entry.id.ok_or_else(|| "entry has no id".to_string())?;
+    let title = entry
+        .title
+        .ok_or_else(|| format!("entry {id} has no title"))?;
+
+    // The ID is given as the paper's URL, e.g. http://arxiv.org/abs/1706.03762v7
+    let arxiv_id = id.split('/').next_back().unwrap_or(&id).to_string();
+
+    Ok(ScholarlyResult {
+        url: format!("https://arxiv.org/abs/{arxiv_id}"),
+        title: title.trim().to_string(),
+        authors: entry.authors.into_iter().map(|author| author.name).collect(),
+        doi: entry.doi.map(|doi| doi.trim().to_string()),
+        abstract_text: entry.summary.map(|summary| summary.trim().to_string()),
+        venue: entry.journal_ref.map(|venue| venue.trim().to_string()),
+        year: entry
+            .published
+            .as_deref()
+            .and_then(|published| published.get(..4)?.parse().ok()),
+        published_date: entry.published,
+        pdf_url: entry
+            .links
+            .into_iter()
+            .find(|link| link.title.as_deref() == Some("pdf"))
+            .map(|link| link.href.replacen("http://", "https://", 1)),
+        arxiv_id: Some(arxiv_id),
+        categories: entry
+            .categories
+            .into_iter()
+            .map(|category| category.term)
+            .collect(),
+        provider: Some(interned!("arxiv")),
+        ..Default::default()
+    })
 }
 
 impl Default for ArxivProvider {
@@ -65,13 +420,15 @@ impl SearchProvider for ArxivProvider {
     }
 
     async fn search(&self, options: &SearchOptions) -> SearchResult<Vec<SearchResultType>> {
-        let client = reqwest::Client::new();
+        Ok(self.search_response(options).await?.results)
+    }
+
+    async fn search_response(&self, options: &SearchOptions) -> SearchResult<SearchResponse> {
         let mut url = Url::parse(&self.base_url)?;
 
         // Build query parameters with proper lifetime management
         let search_query;
         let start_str;
-        let max_results_str;
 
         let mut query_params = Vec::new();
 
@@ -80,7 +437,7 @@ impl SearchProvider for ArxivProvider {
             query_params.push(("id_list", id_list.as_str()));
         } else if !options.query.trim().is_empty() {
             // Search by query string
-            search_query = format!("all:{}", options.query.trim());
+            search_query = to_search_query(&options.query);
             query_params.push(("search_query", search_query.as_str()));
         } else {
             return Err(SearchError::InvalidInput(
@@ -95,7 +452,7 @@ impl SearchProvider for ArxivProvider {
         }
 
         let max_results = options.max_results.unwrap_or(10).min(50); // ArXiv max is 50
-        max_results_str = max_results.to_string();
+        let max_results_str = max_results.to_string();
         query_params.push(("max_results", max_results_str.as_str()));
 
         // Add sort parameters
@@ -124,92 +481,36 @@ impl SearchProvider for ArxivProvider {
             }
         }
 
-        let response = client
-            .get(url.as_str())
-            .send()
-            .await
-            .map_err(|e| {
-                SearchError::HttpError {
-                    message: format!("ArXiv API request failed: {e}"),
-                    status_code: None,
-                    response_body: None,
-                }
-            })?;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
-
-            return Err(SearchError::HttpError {
-                message: format!("ArXiv API returned error: {}", status),
-                status_code: Some(status.as_u16()),
-                response_body: Some(error_text),
-            });
-        }
-
-        let xml_text = response.text().await.map_err(|e| {
-            SearchError::ParseError(format!("Failed to read ArXiv response: {e}"))
-        })?;
+        let xml = self.fetch(&url, options).await?;
 
         if let Some(debug) = &options.debug {
             if debug.enabled && debug.log_responses {
-                log::info!("ArXiv API response received ({} bytes)", xml_text.len());
+                log::info!("ArXiv API response received ({} bytes)", xml.len());
             }
         }
 
-        // Parse XML response
-        let feed: ArxivFeed = quick_xml::de::from_str(&xml_text).map_err(|e| {
+        // Parse the XML straight from the response bytes, without decoding it to a String
+        let feed: ArxivFeed = quick_xml::de::from_reader(xml.as_ref()).map_err(|e| {
             SearchError::ParseError(format!("Failed to parse ArXiv XML: {e}"))
         })?;
 
-        // Convert to standard format
-        let results: Vec<SearchResultType> = feed
+        // Convert to standard format, skipping malformed entries
+        let entries = feed
             .entries
             .into_iter()
-            .map(|entry| {
-                // Extract ArXiv ID from the full ID URL
-                let arxiv_id = entry.id
-                    .split('/')
-                    .last()
-                    .unwrap_or(&entry.id)
-                    .to_string();
-
-                // Find the paper URL
-                let paper_url = entry.links
-                    .iter()
-                    .find(|link| link.link_type.as_deref() == Some("text/html"))
-                    .map(|link| link.href.clone())
-                    .unwrap_or_else(|| format!("https://arxiv.org/abs/{}", arxiv_id));
-
-                // Create author list
-                let authors: Vec<String> = entry.authors.iter().map(|a| a.name.clone()).collect();
-                let authors_string = if authors.is_empty() {
-                    None
-                } else {
-                    Some(authors.join(", "))
-                };
-
-                // Store raw data
-                let mut raw_data = HashMap::new();
-                raw_data.insert("arxiv_id".to_string(), serde_json::Value::String(arxiv_id.clone()));
-                raw_data.insert("published".to_string(), serde_json::Value::String(entry.published.clone()));
-                if let Some(authors_str) = &authors_string {
-                    raw_data.insert("authors".to_string(), serde_json::Value::String(authors_str.clone()));
-                }
-
-                SearchResultType {
-                    url: paper_url,
-                    title: entry.title.trim().to_string(),
-                    snippet: Some(entry.summary.trim().to_string()),
-                    domain: Some("arxiv.org".to_string()),
-                    published_date: Some(entry.published),
-                    provider: Some("arxiv".to_string()),
-                    raw: Some(serde_json::to_value(raw_data).unwrap_or_default()),
-                }
-            })
-            .collect();
+            .map(|entry| convert_entry(entry).map(SearchResultType::from));
+
+        let (results, warnings) = collect_results("arxiv", entries);
+        Ok(SearchResponse {
+            results,
+            warnings,
+            ..Default::default()
+        })
+    }
 
-        Ok(results)
+    /// arXiv needs no credentials
+    async fn validate_credentials(&self) -> SearchResult<()> {
+        Ok(())
     }
 
     fn config(&self) -> HashMap<String, String> {
@@ -217,6 +518,232 @@ impl SearchProvider for ArxivProvider {
         config.insert("provider".to_string(), "arxiv".to_string());
         config.insert("base_url".to_string(), self.base_url.clone());
         config.insert("max_results".to_string(), "50".to_string());
+        config.insert(
+            "request_interval_ms".to_string(),
+            self.request_interval.as_millis().to_string(),
+        );
+        config.insert("max_retries".to_string(), self.max_retries.to_string());
         config
     }
 }
+
+#[async_trait::async_trait]
+impl ScholarlyProvider for ArxivProvider {
+    async fn search_scholarly(
+        &self,
+        options: &SearchOptions,
+    ) -> SearchResult<Vec<ScholarlyResult>> {
+        let response = self.search_response(options).await?;
+        Ok(response
+            .results
+            .iter()
+            .filter_map(ScholarlyResult::from_result)
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::{matchers::method, Mock, MockServer, ResponseTemplate};
+
+    const EMPTY_FEED: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom"><title>ArXiv Query</title></feed>"#;
+
+    fn options(query: &str) -> SearchOptions {
+        SearchOptions {
+            query: query.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_search_scholarly_parses_paper_metadata() {
+        let feed = r#"<?xml version="1.0" encoding="UTF-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom" xmlns:arxiv="http://arxiv.org/schemas/atom">
+  <entry>
+    <id>http://arxiv.org/abs/1706.03762v7</id>
+    <published>2017-06-12T17:57:34Z</published>
+    <title>Attention Is All You Need</title>
+    <summary>  The dominant sequence transduction models...
+    </summary>
+    <author><name>Ashish Vaswani</name></author>
+    <author><name>Noam Shazeer</name></author>
+    <arxiv:doi>10.48550/arXiv.1706.03762</arxiv:doi>
+    <arxiv:journal_ref>Advances in Neural Information Processing Systems 30</arxiv:journal_ref>
+    <link href="http://arxiv.org/abs/1706.03762v7" rel="alternate" type="text/html"/>
+    <link title="pdf" href="http://arxiv.org/pdf/1706.03762v7" rel="related"/>
+    <category term="cs.CL" scheme="http://arxiv.org/schemas/atom"/>
+    <category term="cs.LG" scheme="http://arxiv.org/schemas/atom"/>
+  </entry>
+</feed>"#;
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(feed, "application/atom+xml"))
+            .mount(&server)
+            .await;
+
+        let provider = ArxivProvider::new().with_base_url(&server.uri());
+        let papers = provider.search_scholarly(&options("attention")).await.unwrap();
+
+        assert_eq!(papers.len(), 1);
+        let paper = &papers[0];
+        assert_eq!(paper.url, "https://arxiv.org/abs/1706.03762v7");
+        assert_eq!(paper.authors, ["Ashish Vaswani", "Noam Shazeer"]);
+        assert_eq!(paper.doi.as_deref(), Some("10.48550/arXiv.1706.03762"));
+        assert_eq!(
+            paper.venue.as_deref(),
+            Some("Advances in Neural Information Processing Systems 30")
+        );
+        assert_eq!(paper.year, Some(2017));
+        assert_eq!(
+            paper.pdf_url.as_deref(),
+            Some("https://arxiv.org/pdf/1706.03762v7")
+        );
+        assert_eq!(paper.arxiv_id.as_deref(), Some("1706.03762v7"));
+        assert_eq!(paper.categories, ["cs.CL", "cs.LG"]);
+        assert_eq!(
+            paper.abstract_text.as_deref(),
+            Some("The dominant sequence transduction models...")
+        );
+        assert_eq!(paper.provider.as_deref(), Some("arxiv"));
+    }
+
+    #[tokio::test]
+    async fn test_retries_throttled_request() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(1)
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_raw(EMPTY_FEED, "application/atom+xml"),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let provider = ArxivProvider::new()
+            .with_base_url(&server.uri())
+            .with_request_interval(Duration::from_millis(10));
+
+        let results = provider.search(&options("electron")).await.unwrap();
+        assert!(results.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_after_max_retries() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(429))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let provider = ArxivProvider::new()
+            .with_base_url(&server.uri())
+            .with_request_interval(Duration::from_millis(10))
+            .with_max_retries(1);
+
+        let error = provider.search(&options("electron")).await.unwrap_err();
+        assert!(matches!(
+            error,
+            SearchError::HttpError {
+                status_code: Some(429),
+                ..
+            }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_paces_consecutive_requests() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_raw(EMPTY_FEED, "application/atom+xml"),
+            )
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let provider = ArxivProvider::new()
+            .with_base_url(&server.uri())
+            .with_request_interval(Duration::from_millis(200));
+
+        let started = Instant::now();
+        provider.search(&options("electron")).await.unwrap();
+        provider.search(&options("proton")).await.unwrap();
+        assert!(started.elapsed() >= Duration::from_millis(200));
+    }
+
+    #[test]
+    fn test_query_builder() {
+        let query = ArxivQuery::new()
+            .title("attention")
+            .and_not(ArxivField::Abstract, "vision")
+            .category("cs.CL");
+        assert_eq!(
+            query.build().unwrap(),
+            "(ti:attention ANDNOT abs:vision) AND cat:cs.CL"
+        );
+
+        let query = ArxivQuery::new()
+            .author("Hinton")
+            .or(ArxivField::Title, "deep belief nets")
+            .category("cs.LG")
+            .category("stat.ML");
+        assert_eq!(
+            query.build().unwrap(),
+            "(au:Hinton OR ti:\"deep belief nets\") AND (cat:cs.LG OR cat:stat.ML)"
+        );
+
+        assert_eq!(
+            ArxivQuery::new().category("hep-th").build().unwrap(),
+            "cat:hep-th"
+        );
+        assert_eq!(
+            ArxivQuery::new().all("electron").build().unwrap(),
+            "all:electron"
+        );
+    }
+
+    #[test]
+    fn test_query_builder_rejects_leading_and_not() {
+        let query = ArxivQuery::new()
+            .and_not(ArxivField::Abstract, "vision")
+            .title("attention");
+        assert!(matches!(query.build(), Err(SearchError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn test_query_builder_rejects_leading_or() {
+        let query = ArxivQuery::new()
+            .or(ArxivField::Title, "attention")
+            .category("cs.CL");
+        assert!(matches!(
+            String::try_from(query),
+            Err(SearchError::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn test_to_search_query() {
+        assert_eq!(
+            to_search_query(" quantum computing "),
+            "all:quantum computing"
+        );
+        assert_eq!(
+            to_search_query("ti:transformer AND cat:cs.LG"),
+            "ti:transformer AND cat:cs.LG"
+        );
+        assert_eq!(
+            to_search_query("(au:Bengio OR au:LeCun)"),
+            "(au:Bengio OR au:LeCun)"
+        );
+        // A bare prefix with no value is not a fielded query
+        assert_eq!(to_search_query("ti: tips"), "all:ti: tips");
+    }
+}
@@ -1,29 +1,533 @@
 //! Brave Search API provider
 
 use crate::{
+    domains,
     error::{SearchError, SearchResult},
-    types::{SearchOptions, SearchProvider, SearchResult as SearchResultType},
+    highlight,
+    language::primary_subtag,
+    locale::Locale,
+    providers::{collect_results, forward_http_headers, parse_clock, parse_count},
+    types::{
+        Freshness, GeoLocation, PlaceResult, ProviderConfig, ResultKind, SafeSearch, SearchMode,
+        SearchOptions, SearchProvider, SearchResponse, SearchResult as SearchResultType,
+        VideoResult,
+    },
+    utils::{
+        debug,
+        http::{build_url, extract_domain, normalize_text, HttpClient},
+        intern::{intern, interned},
+    },
 };
+use chrono::NaiveDate;
+use scraper::Html;
+use serde::Deserialize;
 use std::collections::HashMap;
+use std::fmt;
 
-/// Brave Search provider (stub implementation)
-#[derive(Debug)]
-pub struct BraveProvider {
-    api_key: String,
+/// Brave returns at most 20 results per request
+const MAX_COUNT: u32 = 20;
+
+/// The video endpoint returns up to 50
+const MAX_VIDEO_COUNT: u32 = 50;
+
+/// Brave accepts offsets (in pages) up to 9
+const MAX_OFFSET: u32 = 9;
+
+/// Restrict results by when they were discovered
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BraveFreshness {
+    /// Last 24 hours
+    Day,
+    /// Last 7 days
+    Week,
+    /// Last 31 days
+    Month,
+    /// Last 365 days
+    Year,
+    /// A custom date range (inclusive)
+    Range { from: NaiveDate, to: NaiveDate },
 }
 
-impl BraveProvider {
-    pub fn new(api_key: &str) -> SearchResult<Self> {
-        if api_key.is_empty() {
+impl fmt::Display for BraveFreshness {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BraveFreshness::Day => write!(f, "pd"),
+            BraveFreshness::Week => write!(f, "pw"),
+            BraveFreshness::Month => write!(f, "pm"),
+            BraveFreshness::Year => write!(f, "py"),
+            BraveFreshness::Range { from, to } => {
+                write!(f, "{}to{}", from.format("%Y-%m-%d"), to.format("%Y-%m-%d"))
+            }
+        }
+    }
+}
+
+impl From<Freshness> for BraveFreshness {
+    fn from(freshness: Freshness) -> Self {
+        match freshness {
+            Freshness::Day => BraveFreshness::Day,
+            Freshness::Week => BraveFreshness::Week,
+            Freshness::Month => BraveFreshness::Month,
+            Freshness::Year => BraveFreshness::Year,
+        }
+    }
+}
+
+/// Result types that can be requested with `result_filter`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BraveResultType {
+    Web,
+    News,
+    Videos,
+    Discussions,
+}
+
+impl fmt::Display for BraveResultType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BraveResultType::Web => write!(f, "web"),
+            BraveResultType::News => write!(f, "news"),
+            BraveResultType::Videos => write!(f, "videos"),
+            BraveResultType::Discussions => write!(f, "discussions"),
+        }
+    }
+}
+
+/// Brave API result (shared by web, news, video, discussion and location results)
+#[derive(Debug, Deserialize)]
+struct BraveResult {
+    title: String,
+    url: String,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    age: Option<String>,
+    #[serde(default)]
+    page_age: Option<String>,
+    #[serde(default)]
+    extra_snippets: Option<Vec<String>>,
+    #[serde(default)]
+    meta_url: Option<BraveMetaUrl>,
+    #[serde(default)]
+    profile: Option<BraveProfile>,
+    #[serde(default)]
+    thumbnail: Option<BraveThumbnail>,
+    /// Language of the page, e.g. "en"
+    #[serde(default)]
+    language: Option<String>,
+    /// Set on video results
+    #[serde(default)]
+    video: Option<BraveVideo>,
+    /// Set on location results, as `[latitude, longitude]`
+    #[serde(default)]
+    coordinates: Option<Vec<f64>>,
+    #[serde(default)]
+    postal_address: Option<BravePostalAddress>,
+    #[serde(default)]
+    rating: Option<BraveRating>,
+    #[serde(default)]
+    opening_hours: Option<BraveOpeningHours>,
+    #[serde(default)]
+    contact: Option<BraveContact>,
+    #[serde(default)]
+    categories: Option<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BraveMetaUrl {
+    #[serde(default)]
+    hostname: Option<String>,
+    #[serde(default)]
+    favicon: Option<String>,
+}
+
+/// The site that published a result
+#[derive(Debug, Deserialize)]
+struct BraveProfile {
+    #[serde(default)]
+    img: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BraveVideo {
+    /// Length like `"03:25"`
+    #[serde(default)]
+    duration: Option<String>,
+    /// A number or a string
+    #[serde(default)]
+    views: Option<serde_json::Value>,
+    #[serde(default)]
+    creator: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BravePostalAddress {
+    #[serde(default, rename = "displayAddress")]
+    display_address: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BraveRating {
+    #[serde(default, rename = "ratingValue")]
+    rating_value: Option<f64>,
+    #[serde(default, rename = "reviewCount")]
+    review_count: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BraveOpeningHours {
+    /// Opening periods today
+    #[serde(default)]
+    current_day: Vec<BraveHours>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BraveHours {
+    opens: String,
+    closes: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct BraveContact {
+    #[serde(default)]
+    telephone: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BraveThumbnail {
+    #[serde(default)]
+    src: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct BraveResultSet {
+    /// Parsed one by one, as [`BraveResult`]s
+    #[serde(default)]
+    results: Vec<serde_json::Value>,
+}
+
+/// Brave API response
+#[derive(Debug, Deserialize)]
+struct BraveResponse {
+    #[serde(default)]
+    web: Option<BraveResultSet>,
+    #[serde(default)]
+    news: Option<BraveResultSet>,
+    #[serde(default)]
+    videos: Option<BraveResultSet>,
+    #[serde(default)]
+    discussions: Option<BraveResultSet>,
+    #[serde(default)]
+    locations: Option<BraveResultSet>,
+}
+
+/// Brave Search configuration
+#[derive(Debug, Clone)]
+pub struct BraveConfig {
+    /// Brave Search API subscription token
+    pub api_key: String,
+    /// Base URL for the web search endpoint; video searches go to `videos/search` beside it
+    pub base_url: String,
+    /// Goggle used to re-rank results
+    pub goggles_id: Option<String>,
+    /// Only return results discovered within this period
+    pub freshness: Option<BraveFreshness>,
+    /// Result types to include (empty returns Brave's defaults)
+    pub result_filter: Vec<BraveResultType>,
+    /// Request up to five additional excerpts per result
+    pub extra_snippets: bool,
+}
+
+impl Default for BraveConfig {
+    fn default() -> Self {
+        Self {
+            api_key: String::new(),
+            base_url: "https://api.search.brave.com/res/v1/web/search".to_string(),
+            goggles_id: None,
+            freshness: None,
+            result_filter: Vec::new(),
+            extra_snippets: false,
+        }
+    }
+}
+
+impl ProviderConfig for BraveConfig {
+    fn validate(&self) -> Result<(), SearchError> {
+        if self.api_key.is_empty() {
             return Err(SearchError::ConfigError(
                 "Brave API key is required".to_string(),
             ));
         }
+        Ok(())
+    }
 
-        Ok(Self {
+    fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    fn api_key(&self) -> Option<&str> {
+        Some(&self.api_key)
+    }
+}
+
+/// Brave Search provider
+#[derive(Debug)]
+pub struct BraveProvider {
+    config: BraveConfig,
+    http_client: HttpClient,
+}
+
+impl BraveProvider {
+    /// Create a new Brave provider with the given API key
+    pub fn new(api_key: &str) -> SearchResult<Self> {
+        Self::with_config(BraveConfig {
             api_key: api_key.to_string(),
+            ..Default::default()
+        })
+    }
+
+    /// Create a new Brave provider with custom configuration
+    pub fn with_config(config: BraveConfig) -> SearchResult<Self> {
+        config.validate()?;
+
+        Ok(Self {
+            config,
+            http_client: HttpClient::new().with_accept("application/json"),
         })
     }
+
+    /// Re-rank results with a Goggle (URL or inline definition)
+    pub fn with_goggles_id(mut self, goggles_id: impl Into<String>) -> Self {
+        self.config.goggles_id = Some(goggles_id.into());
+        self
+    }
+
+    /// Only return results discovered within the given period
+    pub fn with_freshness(mut self, freshness: BraveFreshness) -> Self {
+        self.config.freshness = Some(freshness);
+        self
+    }
+
+    /// Only include the given result types
+    pub fn with_result_filter(mut self, result_types: Vec<BraveResultType>) -> Self {
+        self.config.result_filter = result_types;
+        self
+    }
+
+    /// Request additional excerpts per result, appended to the snippet
+    pub fn with_extra_snippets(mut self, extra_snippets: bool) -> Self {
+        self.config.extra_snippets = extra_snippets;
+        self
+    }
+
+    forward_http_headers!();
+
+    /// The video search endpoint next to the configured web search one
+    fn videos_url(&self) -> SearchResult<String> {
+        let url = url::Url::parse(&self.config.base_url)?.join("../videos/search")?;
+        Ok(url.into())
+    }
+
+    /// Build the search URL with parameters
+    fn build_search_url(&self, options: &SearchOptions) -> SearchResult<String> {
+        let video = options.mode == SearchMode::Video;
+        let mut params = HashMap::new();
+        params.insert(
+            "q".to_string(),
+            domains::with_site_operators(&options.query_with_file_type(), options),
+        );
+
+        let max_count = if video { MAX_VIDEO_COUNT } else { MAX_COUNT };
+        let count = options.max_results.unwrap_or(10).clamp(1, max_count);
+        params.insert("count".to_string(), count.to_string());
+
+        if let Some(page) = options.page {
+            let offset = page.saturating_sub(1).min(MAX_OFFSET);
+            params.insert("offset".to_string(), offset.to_string());
+        }
+
+        if let Some(locale) =
+            Locale::from_options(options.language.as_deref(), options.region.as_deref())?
+        {
+            if let Some(search_lang) = locale.brave_search_lang()? {
+                params.insert("search_lang".to_string(), search_lang);
+            }
+            if let Some(country) = locale.brave_country() {
+                params.insert("country".to_string(), country);
+            }
+        }
+
+        if let Some(safe_search) = &options.safe_search {
+            let level = match safe_search {
+                SafeSearch::Off => "off",
+                SafeSearch::Moderate => "moderate",
+                SafeSearch::Strict => "strict",
+            };
+            params.insert("safesearch".to_string(), level.to_string());
+        }
+
+        // The configured period wins over the per-search option
+        let freshness = self
+            .config
+            .freshness
+            .clone()
+            .or_else(|| options.freshness.map(BraveFreshness::from));
+        if let Some(freshness) = freshness {
+            params.insert("freshness".to_string(), freshness.to_string());
+        }
+
+        // Goggles, result types and extra snippets only apply to web search
+        match options.mode {
+            SearchMode::Web => {}
+            SearchMode::Video => return build_url(&self.videos_url()?, params),
+            SearchMode::Places => {
+                params.insert("result_filter".to_string(), "locations".to_string());
+                return build_url(&self.config.base_url, params);
+            }
+        }
+
+        if let Some(goggles_id) = &self.config.goggles_id {
+            params.insert("goggles_id".to_string(), goggles_id.clone());
+        }
+
+        if !self.config.result_filter.is_empty() {
+            let filter: Vec<String> = self
+                .config
+                .result_filter
+                .iter()
+                .map(ToString::to_string)
+                .collect();
+            params.insert("result_filter".to_string(), filter.join(","));
+        }
+
+        if self.config.extra_snippets {
+            params.insert("extra_snippets".to_string(), "true".to_string());
+        }
+
+        build_url(&self.config.base_url, params)
+    }
+}
+
+/// Brave's `X-Loc-*` headers for `location`
+///
+/// A name is read as `City,Region,Country`; the country is only sent when it is a
+/// two-letter code.
+fn location_headers(location: &GeoLocation) -> Vec<(String, String)> {
+    let header = |name: &str, value: String| (format!("X-Loc-{name}"), value);
+    match location {
+        GeoLocation::Coordinates {
+            latitude,
+            longitude,
+        } => vec![
+            header("Lat", latitude.to_string()),
+            header("Long", longitude.to_string()),
+        ],
+        GeoLocation::Name(name) => {
+            let parts: Vec<&str> = name.split(',').map(str::trim).collect();
+            let mut headers = vec![header("City", parts[0].to_string())];
+            if parts.len() >= 3 {
+                headers.push(header("State-Name", parts[1].to_string()));
+            }
+            if let Some(country) = parts[1..].last().filter(|country| country.len() == 2) {
+                headers.push(header("Country", country.to_uppercase()));
+            }
+            headers
+        }
+    }
+}
+
+/// Brave highlights query terms with `<strong>` tags; keep only the text
+fn strip_tags(text: &str) -> String {
+    normalize_text(
+        &Html::parse_fragment(text)
+            .root_element()
+            .text()
+            .collect::<String>(),
+    )
+}
+
+fn convert_result(result: BraveResult, raw: serde_json::Value) -> SearchResultType {
+    let mut snippets: Vec<String> = result
+        .description
+        .iter()
+        .chain(result.extra_snippets.iter().flatten())
+        .map(|snippet| strip_tags(snippet))
+        .filter(|snippet| !snippet.is_empty())
+        .collect();
+    snippets.dedup();
+    let snippet = (!snippets.is_empty()).then(|| snippets.join(" … "));
+    let terms: Vec<String> = result
+        .description
+        .iter()
+        .chain(result.extra_snippets.iter().flatten())
+        .flat_map(|snippet| highlight::html_terms(snippet))
+        .collect();
+    let highlights = snippet
+        .as_deref()
+        .map(|snippet| highlight::find_terms(snippet, &terms))
+        .unwrap_or_default();
+
+    let domain = result
+        .meta_url
+        .as_ref()
+        .and_then(|meta| meta.hostname.as_deref())
+        .map(intern)
+        .or_else(|| extract_domain(&result.url));
+    let favicon_url = result
+        .profile
+        .and_then(|profile| profile.img)
+        .or_else(|| result.meta_url.and_then(|meta| meta.favicon));
+    let thumbnail_url = result.thumbnail.and_then(|thumbnail| thumbnail.src);
+    let video = result.video.map(|video| VideoResult {
+        duration: video.duration.as_deref().and_then(parse_clock),
+        channel: video.creator,
+        views: video.views.as_ref().and_then(parse_count),
+        thumbnail_url: thumbnail_url.clone(),
+    });
+    let place = (result.coordinates.is_some() || result.postal_address.is_some()).then(|| {
+        let coordinates = result.coordinates.unwrap_or_default();
+        let hours: Vec<String> = result
+            .opening_hours
+            .into_iter()
+            .flat_map(|hours| hours.current_day)
+            .map(|period| format!("{}–{}", period.opens, period.closes))
+            .collect();
+        PlaceResult {
+            address: result
+                .postal_address
+                .and_then(|address| address.display_address),
+            latitude: coordinates.first().copied(),
+            longitude: coordinates.get(1).copied(),
+            rating: result
+                .rating
+                .as_ref()
+                .and_then(|rating| rating.rating_value),
+            reviews: result.rating.and_then(|rating| rating.review_count),
+            hours: (!hours.is_empty()).then(|| hours.join(", ")),
+            phone: result.contact.and_then(|contact| contact.telephone),
+            category: result
+                .categories
+                .and_then(|categories| categories.into_iter().next()),
+        }
+    });
+
+    SearchResultType {
+        title: strip_tags(&result.title),
+        url: result.url,
+        snippet,
+        highlights,
+        domain,
+        published_date: result.age.or(result.page_age),
+        language: result.language.as_deref().map(primary_subtag),
+        thumbnail_url,
+        favicon_url,
+        video,
+        place,
+        provider: Some(interned!("brave")),
+        // The original result, including fields this SDK doesn't map
+        raw: Some(raw),
+        ..Default::default()
+    }
 }
 
 #[async_trait::async_trait]
@@ -32,15 +536,385 @@ impl SearchProvider for BraveProvider {
         "brave"
     }
 
-    async fn search(&self, _options: &SearchOptions) -> SearchResult<Vec<SearchResultType>> {
-        Err(SearchError::ProviderError(
-            "Brave provider implementation coming soon".to_string(),
-        ))
+    fn supports_pagination(&self) -> bool {
+        true
+    }
+
+    async fn search(&self, options: &SearchOptions) -> SearchResult<Vec<SearchResultType>> {
+        Ok(self.search_response(options).await?.results)
+    }
+
+    async fn search_response(&self, options: &SearchOptions) -> SearchResult<SearchResponse> {
+        debug::log_request(
+            &options.debug,
+            "Brave Search request",
+            &format!("query: {}", options.query),
+        );
+
+        let url = self.build_search_url(options)?;
+
+        let mut headers = HashMap::new();
+        headers.insert(
+            "X-Subscription-Token".to_string(),
+            self.config.api_key.clone(),
+        );
+        if let Some(location) = &options.location {
+            headers.extend(location_headers(location));
+        }
+
+        // Warnings count results across the web, news, video and discussion sets in turn
+        let sets = match options.mode {
+            // Location results come back with web search when asked for
+            SearchMode::Places => {
+                let response: BraveResponse = self
+                    .http_client
+                    .get_json_with_headers(&url, headers)
+                    .await?;
+                vec![(ResultKind::Place, response.locations)]
+            }
+            SearchMode::Web => {
+                let response: BraveResponse = self
+                    .http_client
+                    .get_json_with_headers(&url, headers)
+                    .await?;
+                vec![
+                    (ResultKind::Organic, response.web),
+                    (ResultKind::News, response.news),
+                    (ResultKind::Video, response.videos),
+                    (ResultKind::Forum, response.discussions),
+                ]
+            }
+            // The video endpoint lists its results at the top level
+            SearchMode::Video => {
+                let response: BraveResultSet = self
+                    .http_client
+                    .get_json_with_headers(&url, headers)
+                    .await?;
+                vec![(ResultKind::Video, Some(response))]
+            }
+        };
+        let items = sets
+            .into_iter()
+            .flat_map(|(kind, set)| {
+                set.into_iter()
+                    .flat_map(|set| set.results)
+                    .map(move |raw| (kind, raw))
+            })
+            .map(|(kind, raw)| {
+                let result = BraveResult::deserialize(&raw).map_err(|e| e.to_string())?;
+                Ok(SearchResultType {
+                    kind,
+                    ..convert_result(result, raw)
+                })
+            });
+        let (mut results, warnings) = collect_results("brave", items);
+
+        if let Some(max_results) = options.max_results {
+            results.truncate(max_results as usize);
+        }
+
+        debug::log_response(
+            &options.debug,
+            &format!("Brave Search returned {} results", results.len()),
+        );
+
+        Ok(SearchResponse {
+            results,
+            warnings,
+            ..Default::default()
+        })
+    }
+
+    fn supports_safe_search(&self) -> bool {
+        true
+    }
+
+    fn supports_domain_filter(&self) -> bool {
+        true
+    }
+
+    fn supports_freshness(&self) -> bool {
+        true
+    }
+
+    fn supports_mode(&self, _mode: SearchMode) -> bool {
+        true
     }
 
     fn config(&self) -> HashMap<String, String> {
         let mut config = HashMap::new();
         config.insert("api_key".to_string(), "***".to_string());
+        config.insert("base_url".to_string(), self.config.base_url.clone());
+        if let Some(goggles_id) = &self.config.goggles_id {
+            config.insert("goggles_id".to_string(), goggles_id.clone());
+        }
+        if let Some(freshness) = &self.config.freshness {
+            config.insert("freshness".to_string(), freshness.to_string());
+        }
+        config.insert(
+            "extra_snippets".to_string(),
+            self.config.extra_snippets.to_string(),
+        );
+        if !self.config.result_filter.is_empty() {
+            let types: Vec<String> = self
+                .config
+                .result_filter
+                .iter()
+                .map(ToString::to_string)
+                .collect();
+            config.insert("result_filter".to_string(), types.join(","));
+        }
         config
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::FileType;
+    use wiremock::{
+        matchers::{header, method, path, query_param},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    fn provider_for(server: &MockServer) -> BraveProvider {
+        BraveProvider::with_config(BraveConfig {
+            api_key: "test-key".to_string(),
+            base_url: format!("{}/res/v1/web/search", server.uri()),
+            ..Default::default()
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_brave_requires_api_key() {
+        assert!(matches!(
+            BraveProvider::new(""),
+            Err(SearchError::ConfigError(_))
+        ));
+    }
+
+    #[test]
+    fn test_freshness_format() {
+        assert_eq!(BraveFreshness::Week.to_string(), "pw");
+        let range = BraveFreshness::Range {
+            from: NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+            to: NaiveDate::from_ymd_opt(2024, 3, 31).unwrap(),
+        };
+        assert_eq!(range.to_string(), "2024-01-01to2024-03-31");
+    }
+
+    #[test]
+    fn test_filters_become_query_operators() {
+        let provider = BraveProvider::new("test-key").unwrap();
+        let url = provider
+            .build_search_url(&SearchOptions {
+                query: "rust".to_string(),
+                include_domains: Some(vec!["docs.rs".to_string()]),
+                exclude_domains: Some(vec!["reddit.com".to_string()]),
+                file_type: Some(FileType::Pdf),
+                ..Default::default()
+            })
+            .unwrap();
+        let url = url::Url::parse(&url).unwrap();
+        let (_, q) = url.query_pairs().find(|(key, _)| key == "q").unwrap();
+        assert_eq!(q, "rust filetype:pdf site:docs.rs -site:reddit.com");
+    }
+
+    #[test]
+    fn test_freshness_option_unless_configured() {
+        let options = SearchOptions {
+            query: "rust".to_string(),
+            freshness: Some(Freshness::Day),
+            ..Default::default()
+        };
+        let provider = BraveProvider::new("test-key").unwrap();
+        assert!(provider
+            .build_search_url(&options)
+            .unwrap()
+            .contains("freshness=pd"));
+        let provider = provider.with_freshness(BraveFreshness::Year);
+        assert!(provider
+            .build_search_url(&options)
+            .unwrap()
+            .contains("freshness=py"));
+    }
+
+    #[tokio::test]
+    async fn test_search_sends_options_and_maps_results() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(header("X-Subscription-Token", "test-key"))
+            .and(query_param("q", "rust"))
+            .and(query_param("goggles_id", "https://example.com/tech.goggle"))
+            .and(query_param("freshness", "pm"))
+            .and(query_param("result_filter", "web,news"))
+            .and(query_param("extra_snippets", "true"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "web": { "results": [{
+                    "title": "The <strong>Rust</strong> Language",
+                    "url": "https://www.rust-lang.org/",
+                    "description": "A language empowering <strong>everyone</strong>.",
+                    "age": "2 days ago",
+                    "extra_snippets": ["Fast and reliable.", "Memory safe."],
+                    "language": "en",
+                    "meta_url": { "hostname": "www.rust-lang.org" }
+                }]},
+                "news": { "results": [{
+                    "title": "Rust 2.0 released",
+                    "url": "https://news.example.com/rust",
+                    "page_age": "2024-05-01T10:00:00"
+                }]}
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let provider = provider_for(&server)
+            .with_goggles_id("https://example.com/tech.goggle")
+            .with_freshness(BraveFreshness::Month)
+            .with_result_filter(vec![BraveResultType::Web, BraveResultType::News])
+            .with_extra_snippets(true);
+
+        let results = provider
+            .search(&SearchOptions {
+                query: "rust".to_string(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].title, "The Rust Language");
+        assert_eq!(
+            results[0].snippet.as_deref(),
+            Some("A language empowering everyone. … Fast and reliable. … Memory safe.")
+        );
+        assert_eq!(results[0].published_date.as_deref(), Some("2 days ago"));
+        assert_eq!(results[0].domain.as_deref(), Some("www.rust-lang.org"));
+        assert_eq!(results[0].language.as_deref(), Some("en"));
+        assert_eq!(results[1].domain.as_deref(), Some("news.example.com"));
+        assert_eq!(
+            results[1].published_date.as_deref(),
+            Some("2024-05-01T10:00:00")
+        );
+        assert!(results[1].snippet.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_video_search_uses_video_endpoint() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/res/v1/videos/search"))
+            .and(query_param("q", "rust"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "type": "videos",
+                "results": [{
+                    "title": "Rust in 100 Seconds",
+                    "url": "https://www.youtube.com/watch?v=5C_HPTJg5ek",
+                    "description": "Rust is a memory-safe systems language.",
+                    "thumbnail": { "src": "https://imgs.search.brave.com/rust.jpg" },
+                    "video": { "duration": "02:29", "views": "2,100,000", "creator": "Fireship" }
+                }]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let results = provider_for(&server)
+            .with_goggles_id("https://example.com/tech.goggle")
+            .search(&SearchOptions {
+                query: "rust".to_string(),
+                mode: SearchMode::Video,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(results[0].kind, ResultKind::Video);
+        let video = results[0].video.as_ref().unwrap();
+        assert_eq!(video.duration, Some(std::time::Duration::from_secs(149)));
+        assert_eq!(video.views, Some(2_100_000));
+        assert_eq!(video.channel.as_deref(), Some("Fireship"));
+        assert_eq!(
+            video.thumbnail_url.as_deref(),
+            Some("https://imgs.search.brave.com/rust.jpg")
+        );
+    }
+
+    #[test]
+    fn test_location_headers() {
+        let headers = location_headers(&GeoLocation::Name("Austin, Texas, US".to_string()));
+        assert_eq!(
+            headers,
+            [
+                ("X-Loc-City".to_string(), "Austin".to_string()),
+                ("X-Loc-State-Name".to_string(), "Texas".to_string()),
+                ("X-Loc-Country".to_string(), "US".to_string()),
+            ]
+        );
+        assert_eq!(
+            location_headers(&GeoLocation::Name("Berlin".to_string())).len(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_places_search_maps_location_results() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/res/v1/web/search"))
+            .and(query_param("q", "coffee near Berlin"))
+            .and(query_param("result_filter", "locations"))
+            .and(header("X-Loc-Lat", "52.52"))
+            .and(header("X-Loc-Long", "13.405"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "locations": { "results": [{
+                    "type": "location_result",
+                    "title": "The Barn",
+                    "url": "https://thebarn.de/",
+                    "coordinates": [52.5298, 13.4013],
+                    "postal_address": { "displayAddress": "Auguststraße 58, 10119 Berlin" },
+                    "rating": { "ratingValue": 4.6, "bestRating": 5, "reviewCount": 1520 },
+                    "opening_hours": { "current_day": [
+                        { "abbr_name": "Mon", "opens": "08:00", "closes": "18:00" }
+                    ]},
+                    "contact": { "telephone": "+49 30 12345" },
+                    "categories": ["Coffee shop", "Cafe"]
+                }]}
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let results = provider_for(&server)
+            .search(&SearchOptions {
+                query: "coffee near Berlin".to_string(),
+                mode: SearchMode::Places,
+                location: Some(GeoLocation::Coordinates {
+                    latitude: 52.52,
+                    longitude: 13.405,
+                }),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].kind, ResultKind::Place);
+        assert_eq!(results[0].title, "The Barn");
+        let place = results[0].place.as_ref().unwrap();
+        assert_eq!(
+            place.address.as_deref(),
+            Some("Auguststraße 58, 10119 Berlin")
+        );
+        assert_eq!(
+            (place.latitude, place.longitude),
+            (Some(52.5298), Some(13.4013))
+        );
+        assert_eq!((place.rating, place.reviews), (Some(4.6), Some(1520)));
+        assert_eq!(place.hours.as_deref(), Some("08:00–18:00"));
+        assert_eq!(place.phone.as_deref(), Some("+49 30 12345"));
+        assert_eq!(place.category.as_deref(), Some("Coffee shop"));
+    }
+}
@@ -1,12 +1,85 @@
 //! DuckDuckGo search provider (uses HTML scraping)
 
 use crate::{
+    domains,
     error::{SearchError, SearchResult},
-    types::{ProviderConfig, SearchOptions, SearchProvider, SearchResult as SearchResultType},
-    utils::{debug, http::HttpClient},
+    highlight, locale,
+    providers::{collect_results, forward_http_headers},
+    types::{
+        ProviderConfig, SearchOptions, SearchProvider, SearchResponse,
+        SearchResult as SearchResultType,
+    },
+    utils::{
+        debug,
+        extract::{element_text, HighlightMarkers},
+        http::HttpClient,
+        intern::interned,
+        robots::RobotsChecker,
+        user_agent::UserAgentPolicy,
+    },
 };
 use scraper::{Html, Selector};
 use std::collections::HashMap;
+use std::fmt;
+use std::sync::atomic::AtomicUsize;
+
+/// Markup that only appears on DuckDuckGo's anti-bot challenge pages
+const CHALLENGE_MARKERS: &[&str] = &[
+    "anomaly-modal",
+    "anomaly.js",
+    "challenge-form",
+    "g-recaptcha",
+    "bots use DuckDuckGo too",
+];
+
+/// Where a result page puts its links and snippets
+struct Layout {
+    /// Logged when the layout is used
+    name: &'static str,
+    /// Each result's title link
+    link: &'static str,
+    /// Each result's snippet; the n-th snippet belongs to the n-th link
+    snippet: &'static str,
+}
+
+/// Result markup DuckDuckGo has served, tried in order until one finds links
+const LAYOUTS: &[Layout] = &[
+    Layout {
+        name: "html",
+        link: "h2.result__title a",
+        snippet: ".result__snippet",
+    },
+    Layout {
+        name: "html without title headings",
+        link: "a.result__a",
+        snippet: ".result__snippet",
+    },
+    Layout {
+        name: "lite",
+        link: "a.result-link",
+        snippet: "td.result-snippet",
+    },
+    Layout {
+        name: "react",
+        link: "a[data-testid='result-title-a']",
+        snippet: "[data-result='snippet']",
+    },
+];
+
+/// Elements that hold the results on every layout; a page with one of these but no
+/// recognizable links has changed its markup
+const RESULTS_CONTAINER: &str = "#links, .results, .serp__results, #react-layout, table.results";
+
+/// Markup of DuckDuckGo's "No results." page
+const NO_RESULTS: &str = ".no-results, .result--no-result";
+
+/// Characters of an unrecognized results container quoted in the parse error
+const LAYOUT_EXCERPT_CHARS: usize = 300;
+
+fn selector(css: &str) -> SearchResult<Selector> {
+    Selector::parse(css)
+        .map_err(|e| SearchError::ParseError(format!("Invalid CSS selector {css:?}: {e}")))
+}
 
 /// DuckDuckGo search types
 #[derive(Debug, Clone)]
@@ -16,12 +89,12 @@ pub enum SearchType {
     News,
 }
 
-impl ToString for SearchType {
-    fn to_string(&self) -> String {
+impl fmt::Display for SearchType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            SearchType::Text => "text".to_string(),
-            SearchType::Images => "images".to_string(),
-            SearchType::News => "news".to_string(),
+            SearchType::Text => write!(f, "text"),
+            SearchType::Images => write!(f, "images"),
+            SearchType::News => write!(f, "news"),
         }
     }
 }
@@ -35,8 +108,18 @@ pub struct DuckDuckGoConfig {
     pub search_type: SearchType,
     /// Whether to use lite version
     pub use_lite: bool,
-    /// User agent for requests
-    pub user_agent: String,
+    /// How the `User-Agent` header is chosen for each request
+    pub user_agent: UserAgentPolicy,
+    /// Value of the `Accept-Language` header
+    pub accept_language: Option<String>,
+    /// Extra headers sent with every request, overriding the defaults
+    pub headers: HashMap<String, String>,
+    /// Check robots.txt before scraping (on by default)
+    pub respect_robots_txt: bool,
+    /// Wrap the terms DuckDuckGo highlights in snippets in these markers (dropped by
+    /// default); the results' `highlights` are then left empty, so the terms aren't
+    /// marked twice
+    pub highlight_markers: Option<HighlightMarkers>,
 }
 
 impl Default for DuckDuckGoConfig {
@@ -45,7 +128,11 @@ impl Default for DuckDuckGoConfig {
             base_url: "https://html.duckduckgo.com/html".to_string(),
             search_type: SearchType::Text,
             use_lite: false,
-            user_agent: "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/91.0.4472.124 Safari/537.36".to_string(),
+            user_agent: UserAgentPolicy::default(),
+            accept_language: Some("en-US,en;q=0.9".to_string()),
+            headers: HashMap::new(),
+            respect_robots_txt: true,
+            highlight_markers: None,
         }
     }
 }
@@ -56,6 +143,15 @@ impl ProviderConfig for DuckDuckGoConfig {
         if self.base_url.is_empty() {
             return Err(SearchError::ConfigError("Base URL is required".to_string()));
         }
+        if let UserAgentPolicy::Rotating(agents) | UserAgentPolicy::Random(agents) =
+            &self.user_agent
+        {
+            if agents.is_empty() {
+                return Err(SearchError::ConfigError(
+                    "User agent list must not be empty".to_string(),
+                ));
+            }
+        }
         Ok(())
     }
 
@@ -69,6 +165,7 @@ impl ProviderConfig for DuckDuckGoConfig {
 pub struct DuckDuckGoProvider {
     config: DuckDuckGoConfig,
     http_client: HttpClient,
+    user_agent_counter: AtomicUsize,
 }
 
 impl DuckDuckGoProvider {
@@ -82,44 +179,105 @@ impl DuckDuckGoProvider {
         Self {
             config,
             http_client: HttpClient::new(),
+            user_agent_counter: AtomicUsize::new(0),
         }
     }
 
     /// Create a DuckDuckGo provider for image search
     pub fn for_images() -> Self {
-        let mut config = DuckDuckGoConfig::default();
-        config.search_type = SearchType::Images;
-        config.base_url = "https://duckduckgo.com/i.js".to_string();
-        Self::with_config(config)
+        Self::with_config(DuckDuckGoConfig {
+            base_url: "https://duckduckgo.com/i.js".to_string(),
+            search_type: SearchType::Images,
+            ..Default::default()
+        })
     }
 
     /// Create a DuckDuckGo provider for news search
     pub fn for_news() -> Self {
-        let mut config = DuckDuckGoConfig::default();
-        config.search_type = SearchType::News;
-        config.base_url = "https://duckduckgo.com/news.js".to_string();
-        Self::with_config(config)
+        Self::with_config(DuckDuckGoConfig {
+            base_url: "https://duckduckgo.com/news.js".to_string(),
+            search_type: SearchType::News,
+            ..Default::default()
+        })
     }
 
-    /// Perform text search using HTML scraping
-    async fn search_text(&self, options: &SearchOptions) -> SearchResult<Vec<SearchResultType>> {
+    /// Enable or disable robots.txt checks
+    ///
+    /// Only disable this if you have permission to scrape DuckDuckGo regardless of its
+    /// crawl policy.
+    pub fn respect_robots_txt(mut self, respect: bool) -> Self {
+        self.config.respect_robots_txt = respect;
+        self
+    }
+
+    /// Keep the terms DuckDuckGo highlights in snippets, wrapped in `markers`, instead of
+    /// listing them in each result's `highlights`
+    pub fn with_highlight_markers(mut self, markers: HighlightMarkers) -> Self {
+        self.config.highlight_markers = Some(markers);
+        self
+    }
+
+    /// Always send the given user agent
+    pub fn with_user_agent(self, user_agent: impl Into<String>) -> Self {
+        self.with_user_agent_policy(UserAgentPolicy::fixed(user_agent))
+    }
+
+    /// Set how the user agent is chosen for each request
+    pub fn with_user_agent_policy(mut self, policy: UserAgentPolicy) -> Self {
+        self.config.user_agent = policy;
+        self
+    }
+
+    /// Set the `Accept-Language` header (e.g. `"de-DE,de;q=0.9"`)
+    pub fn with_accept_language(mut self, accept_language: impl Into<String>) -> Self {
+        self.config.accept_language = Some(accept_language.into());
+        self
+    }
+
+    /// Send an extra header with every request, overriding the default of the same name
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.config.headers.insert(name.into(), value.into());
+        self
+    }
+
+    forward_http_headers!();
+
+    /// Build the headers for the next request
+    fn request_headers(&self) -> HashMap<String, String> {
         let mut headers = HashMap::new();
-        headers.insert("User-Agent".to_string(), self.config.user_agent.clone());
+        if let Some(user_agent) = self.config.user_agent.select(&self.user_agent_counter) {
+            headers.insert("User-Agent".to_string(), user_agent.to_string());
+        }
+        if let Some(accept_language) = &self.config.accept_language {
+            headers.insert("Accept-Language".to_string(), accept_language.clone());
+        }
         headers.insert(
             "Referer".to_string(),
             "https://html.duckduckgo.com/".to_string(),
         );
+        headers.extend(self.config.headers.clone());
+        headers
+    }
+
+    /// Perform text search using HTML scraping
+    async fn search_text(&self, options: &SearchOptions) -> SearchResult<SearchResponse> {
+        if self.config.respect_robots_txt {
+            RobotsChecker::shared().check(&self.config.base_url).await?;
+        }
+
+        let headers = self.request_headers();
 
         let mut form_data = HashMap::new();
-        form_data.insert("q".to_string(), options.query.clone());
+        form_data.insert(
+            "q".to_string(),
+            domains::with_site_operators(&options.query_with_file_type(), options),
+        );
         form_data.insert("b".to_string(), "".to_string());
 
-        // Add region if provided
-        if let Some(region) = &options.region {
-            form_data.insert("kl".to_string(), region.clone());
-        } else {
-            form_data.insert("kl".to_string(), "wt-wt".to_string()); // Default to worldwide
-        }
+        // Region code; ready-made `kl` values such as "us-en" are passed through
+        let kl = locale::duckduckgo_kl(options.language.as_deref(), options.region.as_deref())?
+            .unwrap_or_else(|| "wt-wt".to_string()); // Default to worldwide
+        form_data.insert("kl".to_string(), kl);
 
         debug::log_request(
             &options.debug,
@@ -142,60 +300,112 @@ impl DuckDuckGoProvider {
     }
 
     /// Parse HTML search results from DuckDuckGo
-    fn parse_text_results(
-        &self,
-        html: &str,
-        max_results: u32,
-    ) -> SearchResult<Vec<SearchResultType>> {
-        let document = Html::parse_document(html);
-        let mut results = Vec::new();
-
-        // Selector for search result links
-        let result_selector = Selector::parse("h2.result__title a")
-            .map_err(|_| SearchError::ParseError("Invalid CSS selector for results".to_string()))?;
-
-        // Selector for result snippets
-        let snippet_selector = Selector::parse(".result__snippet").map_err(|_| {
-            SearchError::ParseError("Invalid CSS selector for snippets".to_string())
-        })?;
+    fn parse_text_results(&self, html: &str, max_results: u32) -> SearchResult<SearchResponse> {
+        if let Some(marker) = CHALLENGE_MARKERS
+            .iter()
+            .find(|marker| html.contains(*marker))
+        {
+            return Err(SearchError::BotDetected {
+                provider: "duckduckgo".to_string(),
+                hint: format!(
+                    "challenge page returned (matched \"{marker}\"); slow down or change the user agent"
+                ),
+            });
+        }
 
-        let result_links: Vec<_> = document.select(&result_selector).collect();
-        let result_snippets: Vec<_> = document.select(&snippet_selector).collect();
+        let document = Html::parse_document(html);
 
-        for (i, link_element) in result_links.iter().enumerate() {
-            if results.len() >= max_results as usize {
+        let mut layout = None;
+        for candidate in LAYOUTS {
+            let links: Vec<_> = document.select(&selector(candidate.link)?).collect();
+            if !links.is_empty() {
+                let snippets: Vec<_> = document.select(&selector(candidate.snippet)?).collect();
+                layout = Some((candidate, links, snippets));
                 break;
             }
-
-            if let Some(href) = link_element.value().attr("href") {
-                // Skip DuckDuckGo internal links
-                if href.contains("duckduckgo.com") || href.contains("google.com/search") {
-                    continue;
-                }
+        }
+        let Some((layout, result_links, result_snippets)) = layout else {
+            return Self::check_empty_page(&document);
+        };
+        tracing::debug!(layout = layout.name, "parsing DuckDuckGo results");
+
+        let items = result_links
+            .iter()
+            .enumerate()
+            // Skip DuckDuckGo internal links (ads and redirects)
+            .filter(|(_, link_element)| {
+                link_element.value().attr("href").is_none_or(|href| {
+                    !href.contains("duckduckgo.com") && !href.contains("google.com/search")
+                })
+            })
+            .map(|(i, link_element)| {
+                let href = link_element
+                    .value()
+                    .attr("href")
+                    .ok_or_else(|| "result link has no href".to_string())?;
 
                 let url = crate::utils::http::normalize_url(href);
-                let title = crate::utils::http::normalize_text(&link_element.inner_html());
+                let title = element_text(*link_element, None);
 
                 // Get corresponding snippet
-                let snippet = result_snippets.get(i).map(|snippet_elem| {
-                    crate::utils::http::normalize_text(&snippet_elem.inner_html())
+                let snippet_elem = result_snippets.get(i);
+                let snippet = snippet_elem.map(|snippet_elem| {
+                    element_text(*snippet_elem, self.config.highlight_markers.as_ref())
                 });
+                // Marked-up snippets already show the terms
+                let highlights = snippet
+                    .as_deref()
+                    .zip(snippet_elem)
+                    .filter(|_| self.config.highlight_markers.is_none())
+                    .map(|(snippet, snippet_elem)| {
+                        let terms = highlight::html_terms(&snippet_elem.inner_html());
+                        highlight::find_terms(snippet, &terms)
+                    })
+                    .unwrap_or_default();
 
                 let domain = crate::utils::http::extract_domain(&url);
 
-                results.push(SearchResultType {
+                Ok(SearchResultType {
                     url,
                     title,
                     snippet,
+                    highlights,
                     domain,
                     published_date: None,
-                    provider: Some("duckduckgo".to_string()),
+                    provider: Some(interned!("duckduckgo")),
                     raw: None,
-                });
+                    ..Default::default()
+                })
+            });
+
+        let (mut results, warnings) = collect_results("duckduckgo", items);
+        results.truncate(max_results as usize);
+        Ok(SearchResponse {
+            results,
+            warnings,
+            ..Default::default()
+        })
+    }
+}
+
+impl DuckDuckGoProvider {
+    /// Accept a page without result links only if it really has no results
+    ///
+    /// A results container without any links DuckDuckGo is known to use means the markup
+    /// changed, which is reported instead of silently returning nothing.
+    fn check_empty_page(document: &Html) -> SearchResult<SearchResponse> {
+        let container = document.select(&selector(RESULTS_CONTAINER)?).next();
+        let no_results = document.select(&selector(NO_RESULTS)?).next().is_some();
+        match container {
+            Some(container) if !no_results => {
+                let markup = crate::utils::http::normalize_text(&container.html());
+                let excerpt: String = markup.chars().take(LAYOUT_EXCERPT_CHARS).collect();
+                Err(SearchError::ParseError(format!(
+                    "Unrecognized DuckDuckGo result layout: {excerpt}"
+                )))
             }
+            _ => Ok(SearchResponse::default()),
         }
-
-        Ok(results)
     }
 }
 
@@ -211,7 +421,15 @@ impl SearchProvider for DuckDuckGoProvider {
         "duckduckgo"
     }
 
+    fn supports_domain_filter(&self) -> bool {
+        true
+    }
+
     async fn search(&self, options: &SearchOptions) -> SearchResult<Vec<SearchResultType>> {
+        Ok(self.search_response(options).await?.results)
+    }
+
+    async fn search_response(&self, options: &SearchOptions) -> SearchResult<SearchResponse> {
         match self.config.search_type {
             SearchType::Text => self.search_text(options).await,
             SearchType::Images => {
@@ -227,6 +445,11 @@ impl SearchProvider for DuckDuckGoProvider {
         }
     }
 
+    /// DuckDuckGo needs no credentials
+    async fn validate_credentials(&self) -> SearchResult<()> {
+        Ok(())
+    }
+
     fn config(&self) -> HashMap<String, String> {
         let mut config = HashMap::new();
         config.insert("base_url".to_string(), self.config.base_url.clone());
@@ -235,6 +458,134 @@ impl SearchProvider for DuckDuckGoProvider {
             self.config.search_type.to_string(),
         );
         config.insert("use_lite".to_string(), self.config.use_lite.to_string());
+        config.insert(
+            "respect_robots_txt".to_string(),
+            self.config.respect_robots_txt.to_string(),
+        );
         config
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_headers_apply_policy_and_overrides() {
+        let provider = DuckDuckGoProvider::new()
+            .with_user_agent_policy(UserAgentPolicy::rotating(["first", "second"]))
+            .with_accept_language("de-DE,de;q=0.9")
+            .with_header("Referer", "https://example.com/")
+            .with_header("DNT", "1");
+
+        let headers = provider.request_headers();
+        assert_eq!(headers["User-Agent"], "first");
+        assert_eq!(headers["Accept-Language"], "de-DE,de;q=0.9");
+        assert_eq!(headers["Referer"], "https://example.com/");
+        assert_eq!(headers["DNT"], "1");
+
+        assert_eq!(provider.request_headers()["User-Agent"], "second");
+    }
+
+    #[test]
+    fn test_challenge_page_is_reported() {
+        let html = r#"<html><body><div class="anomaly-modal__modal">
+            <div class="anomaly-modal__title">Unfortunately, bots use DuckDuckGo too.</div>
+            <form id="challenge-form" action="/anomaly.js"></form>
+        </div></body></html>"#;
+
+        let error = DuckDuckGoProvider::new()
+            .parse_text_results(html, 10)
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            SearchError::BotDetected { ref provider, .. } if provider == "duckduckgo"
+        ));
+    }
+
+    #[test]
+    fn test_page_without_results_is_empty() {
+        let html = r#"<html><body><div class="no-results">No results.</div></body></html>"#;
+
+        let response = DuckDuckGoProvider::new()
+            .parse_text_results(html, 10)
+            .unwrap();
+        assert!(response.results.is_empty());
+    }
+
+    #[test]
+    fn test_lite_layout_is_parsed() {
+        let html = r#"<html><body><table class="results">
+            <tr><td>1.</td><td><a class="result-link" href="https://www.rust-lang.org/">Rust</a></td></tr>
+            <tr><td></td><td class="result-snippet">A language empowering everyone.</td></tr>
+        </table></body></html>"#;
+
+        let response = DuckDuckGoProvider::new()
+            .parse_text_results(html, 10)
+            .unwrap();
+        assert_eq!(response.results.len(), 1);
+        assert_eq!(response.results[0].url, "https://www.rust-lang.org/");
+        assert_eq!(
+            response.results[0].snippet.as_deref(),
+            Some("A language empowering everyone.")
+        );
+    }
+
+    #[test]
+    fn test_marked_up_snippets_have_no_highlights() {
+        let html = r#"<html><body><table class="results">
+            <tr><td>1.</td><td><a class="result-link" href="https://www.rust-lang.org/">Rust</a></td></tr>
+            <tr><td></td><td class="result-snippet"><b>Rust</b> empowers everyone.</td></tr>
+        </table></body></html>"#;
+
+        let plain = DuckDuckGoProvider::new()
+            .parse_text_results(html, 10)
+            .unwrap();
+        assert_eq!(plain.results[0].highlights, vec![0..4]);
+
+        let marked = DuckDuckGoProvider::new()
+            .with_highlight_markers(HighlightMarkers::markdown())
+            .parse_text_results(html, 10)
+            .unwrap();
+        assert_eq!(
+            marked.results[0].snippet.as_deref(),
+            Some("**Rust** empowers everyone.")
+        );
+        assert!(marked.results[0].highlights.is_empty());
+    }
+
+    #[test]
+    fn test_unrecognized_layout_is_an_error() {
+        let html = r#"<html><body><div id="links" class="results">
+            <section class="new-result"><a href="https://www.rust-lang.org/">Rust</a></section>
+        </div></body></html>"#;
+
+        let error = DuckDuckGoProvider::new()
+            .parse_text_results(html, 10)
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            SearchError::ParseError(ref message) if message.contains("new-result")
+        ));
+
+        let html = r#"<html><body><div id="links" class="results">
+            <div class="result result--no-result"><div class="no-results">No results.</div></div>
+        </div></body></html>"#;
+        let response = DuckDuckGoProvider::new()
+            .parse_text_results(html, 10)
+            .unwrap();
+        assert!(response.results.is_empty());
+    }
+
+    #[test]
+    fn test_empty_user_agent_list_is_invalid() {
+        let config = DuckDuckGoConfig {
+            user_agent: UserAgentPolicy::Random(Vec::new()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            config.validate(),
+            Err(SearchError::ConfigError(_))
+        ));
+    }
+}
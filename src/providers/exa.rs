@@ -1,54 +1,185 @@
 //! Exa Search API provider
 
 use crate::{
+    cassette,
     error::{SearchError, SearchResult},
-    types::{SearchOptions, SearchProvider, SearchResult as SearchResultType},
+    providers::map_results,
+    types::{SearchOptions, SearchProvider, SearchResponse, SearchResult as SearchResultType},
+    utils::{
+        http::default_http_settings,
+        intern::{intern, interned},
+    },
 };
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fmt;
 use url::Url;
 
 const DEFAULT_BASE_URL: &str = "https://api.exa.ai/search";
 
+/// One Exa result; only `url` is required, the full object is kept as `raw`
 #[derive(Debug, Deserialize)]
 struct ExaSearchResult {
-    id: String,
-    title: String,
     url: String,
+    title: Option<String>,
     text: Option<String>, // Only present when include_contents is true
     #[serde(rename = "publishedDate")]
     published_date: Option<String>,
-    author: Option<String>,
-    score: Option<f64>, // Relevance score
+    image: Option<String>,
+    favicon: Option<String>,
+    score: Option<f64>,
 }
 
 #[derive(Debug, Deserialize)]
+#[allow(dead_code)]
 struct ExaSearchResponse {
     #[serde(rename = "requestId")]
-    request_id: String,
+    request_id: Option<String>,
     #[serde(rename = "autopromptString")]
-    autoPrompt_string: String,
-    results: Vec<ExaSearchResult>,
+    auto_prompt_string: Option<String>,
+    /// Parsed one by one with [`map_results`]
+    #[serde(default)]
+    results: Vec<serde_json::Value>,
     #[serde(rename = "searchTime")]
     search_time: Option<f64>,
 }
 
 #[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
 struct ExaSearchRequest {
     query: String,
-    #[serde(rename = "max_results")]
-    max_results: Option<usize>,
-    model: String,
-    #[serde(rename = "include_contents")]
-    include_contents: bool,
+    #[serde(rename = "type")]
+    search_type: ExaSearchType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    category: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    num_results: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    include_domains: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    exclude_domains: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    start_published_date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    end_published_date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    contents: Option<ExaContentsRequest>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ExaFindSimilarRequest {
+    url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    category: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    num_results: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    include_domains: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    exclude_domains: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    start_published_date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    end_published_date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    contents: Option<ExaContentsRequest>,
+}
+
+#[derive(Debug, Serialize)]
+struct ExaContentsEndpointRequest {
+    ids: Vec<String>,
+    text: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct ExaContentsResponse {
+    #[serde(default)]
+    results: Vec<serde_json::Value>,
+}
+
+#[derive(Debug, Serialize)]
+struct ExaContentsRequest {
+    text: bool,
+}
+
+/// How Exa matches the query against its index
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExaSearchType {
+    /// Embeddings-based semantic search
+    Neural,
+    /// Traditional keyword search
+    Keyword,
+    /// Let Exa pick neural or keyword per query
+    Auto,
+}
+
+impl ExaSearchType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Neural => "neural",
+            Self::Keyword => "keyword",
+            Self::Auto => "auto",
+        }
+    }
+}
+
+impl fmt::Display for ExaSearchType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Content category to focus an Exa search on
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExaCategory {
+    Company,
+    ResearchPaper,
+    News,
+    Pdf,
+    Github,
+    Tweet,
+    PersonalSite,
+    LinkedinProfile,
+    FinancialReport,
+    /// Any category name Exa accepts that isn't listed above
+    Other(String),
+}
+
+impl ExaCategory {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Company => "company",
+            Self::ResearchPaper => "research paper",
+            Self::News => "news",
+            Self::Pdf => "pdf",
+            Self::Github => "github",
+            Self::Tweet => "tweet",
+            Self::PersonalSite => "personal site",
+            Self::LinkedinProfile => "linkedin profile",
+            Self::FinancialReport => "financial report",
+            Self::Other(category) => category,
+        }
+    }
+}
+
+impl fmt::Display for ExaCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
 }
 
 #[derive(Debug)]
 pub struct ExaProvider {
     api_key: String,
     base_url: String,
-    model: String,
+    search_type: ExaSearchType,
+    category: Option<ExaCategory>,
+    include_domains: Vec<String>,
+    exclude_domains: Vec<String>,
     include_contents: bool,
+    client: reqwest::Client,
 }
 
 impl ExaProvider {
@@ -62,8 +193,17 @@ impl ExaProvider {
         Ok(Self {
             api_key: api_key.to_string(),
             base_url: DEFAULT_BASE_URL.to_string(),
-            model: "keyword".to_string(),
+            search_type: ExaSearchType::Keyword,
+            category: None,
+            include_domains: Vec::new(),
+            exclude_domains: Vec::new(),
             include_contents: false,
+            client: default_http_settings()
+                .client_builder()
+                .build()
+                .map_err(|e| {
+                    SearchError::ConfigError(format!("Failed to create HTTP client: {e}"))
+                })?,
         })
     }
 
@@ -73,16 +213,55 @@ impl ExaProvider {
         Ok(provider)
     }
 
+    /// Select the search type by name: "keyword", "neural" (or "embeddings") or "auto"
     pub fn with_model(mut self, model: &str) -> SearchResult<Self> {
-        if model != "keyword" && model != "embeddings" {
-            return Err(SearchError::ConfigError(
-                "Model must be 'keyword' or 'embeddings'".to_string(),
-            ));
-        }
-        self.model = model.to_string();
+        self.search_type = match model {
+            "keyword" => ExaSearchType::Keyword,
+            "neural" | "embeddings" => ExaSearchType::Neural,
+            "auto" => ExaSearchType::Auto,
+            _ => {
+                return Err(SearchError::ConfigError(
+                    "Model must be 'keyword', 'neural', 'embeddings' or 'auto'".to_string(),
+                ))
+            }
+        };
         Ok(self)
     }
 
+    pub fn with_search_type(mut self, search_type: ExaSearchType) -> Self {
+        self.search_type = search_type;
+        self
+    }
+
+    pub fn with_category(mut self, category: ExaCategory) -> Self {
+        self.category = Some(category);
+        self
+    }
+
+    /// Only return results from these domains
+    ///
+    /// Combined with `SearchOptions::include_domains` when both are set.
+    pub fn with_include_domains<I, S>(mut self, domains: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.include_domains = domains.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Never return results from these domains
+    ///
+    /// Combined with `SearchOptions::exclude_domains` when both are set.
+    pub fn with_exclude_domains<I, S>(mut self, domains: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.exclude_domains = domains.into_iter().map(Into::into).collect();
+        self
+    }
+
     pub fn with_contents(mut self, include_contents: bool) -> Self {
         self.include_contents = include_contents;
         self
@@ -92,54 +271,122 @@ impl ExaProvider {
         self.base_url = base_url.to_string();
         self
     }
-}
 
-#[async_trait::async_trait]
-impl SearchProvider for ExaProvider {
-    fn name(&self) -> &str {
-        "exa"
+    fn build_request(&self, options: &SearchOptions) -> ExaSearchRequest {
+        ExaSearchRequest {
+            query: options.query.clone(),
+            search_type: self.search_type,
+            category: self.category.as_ref().map(|c| c.as_str().to_string()),
+            num_results: options.max_results,
+            include_domains: merge_domains(&self.include_domains, &options.include_domains),
+            exclude_domains: merge_domains(&self.exclude_domains, &options.exclude_domains),
+            start_published_date: options
+                .published_after
+                .map(|date| format!("{}T00:00:00.000Z", date.format("%Y-%m-%d"))),
+            end_published_date: options
+                .published_before
+                .map(|date| format!("{}T23:59:59.999Z", date.format("%Y-%m-%d"))),
+            contents: self
+                .include_contents
+                .then_some(ExaContentsRequest { text: true }),
+        }
     }
 
-    async fn search(&self, options: &SearchOptions) -> SearchResult<Vec<SearchResultType>> {
-        if options.query.trim().is_empty() {
-            return Err(SearchError::InvalidInput(
-                "Query cannot be empty".to_string(),
-            ));
+    /// Find pages similar to `url`
+    ///
+    /// Uses Exa's `findSimilar` endpoint. `options.query` is ignored; `max_results`, the
+    /// domain filters and the published date range apply as they do for [`search`](SearchProvider::search).
+    pub async fn find_similar(
+        &self,
+        url: &str,
+        options: &SearchOptions,
+    ) -> SearchResult<Vec<SearchResultType>> {
+        if url.trim().is_empty() {
+            return Err(SearchError::InvalidInput("URL cannot be empty".to_string()));
         }
 
-        let client = reqwest::Client::new();
-
-        let request_body = ExaSearchRequest {
-            query: options.query.clone(),
-            max_results: options.max_results.map(|n| n as usize),
-            model: self.model.clone(),
-            include_contents: self.include_contents,
+        let request = self.build_request(options);
+        let request_body = ExaFindSimilarRequest {
+            url: url.to_string(),
+            category: request.category,
+            num_results: request.num_results,
+            include_domains: request.include_domains,
+            exclude_domains: request.exclude_domains,
+            start_published_date: request.start_published_date,
+            end_published_date: request.end_published_date,
+            contents: request.contents,
         };
 
+        let endpoint = self.endpoint_url("findSimilar");
         if let Some(debug) = &options.debug {
             if debug.enabled && debug.log_requests {
+                log::info!("Exa API request: {endpoint} for similar pages to: {url}");
+            }
+        }
+
+        let exa_response: ExaSearchResponse = self.post(&endpoint, &request_body).await?;
+
+        if let Some(debug) = &options.debug {
+            if debug.enabled && debug.log_responses {
                 log::info!(
-                    "Exa API request: {} with query: {}",
-                    self.base_url,
-                    options.query
+                    "Exa API response: {} similar pages to: {url}",
+                    exa_response.results.len()
                 );
             }
         }
 
-        let response = client
-            .post(&self.base_url)
-            .header("Content-Type", "application/json")
-            .header("Authorization", format!("Bearer {}", self.api_key))
-            .json(&request_body)
-            .send()
-            .await
-            .map_err(|e| {
-                if e.is_timeout() {
-                    SearchError::Timeout { timeout_ms: 15000 }
-                } else {
-                    SearchError::ProviderError(format!("Exa API request failed: {e}"))
-                }
-            })?;
+        Ok(convert_results(exa_response.results).results)
+    }
+
+    /// Fetch the cleaned page text for Exa result ids or URLs
+    ///
+    /// Wraps Exa's `contents` endpoint. Each returned result carries the text in both
+    /// `content` and `snippet`; ids Exa cannot resolve are left out.
+    pub async fn get_contents<I, S>(&self, ids_or_urls: I) -> SearchResult<Vec<SearchResultType>>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let ids: Vec<String> = ids_or_urls.into_iter().map(Into::into).collect();
+        if ids.is_empty() {
+            return Err(SearchError::InvalidInput(
+                "At least one id or URL is required".to_string(),
+            ));
+        }
+
+        let request_body = ExaContentsEndpointRequest { ids, text: true };
+        let exa_response: ExaContentsResponse = self
+            .post(&self.endpoint_url("contents"), &request_body)
+            .await?;
+
+        Ok(convert_results(exa_response.results).results)
+    }
+
+    /// URL of another Exa endpoint, next to the configured search endpoint
+    fn endpoint_url(&self, endpoint: &str) -> String {
+        let base = self.base_url.trim_end_matches('/');
+        let base = base.strip_suffix("/search").unwrap_or(base);
+        format!("{base}/{endpoint}")
+    }
+
+    /// POST a JSON body to an Exa endpoint and decode the JSON response
+    async fn post<B, R>(&self, url: &str, body: &B) -> SearchResult<R>
+    where
+        B: Serialize + ?Sized,
+        R: DeserializeOwned,
+    {
+        let response = cassette::send(
+            self.client
+                .post(url)
+                .header("Content-Type", "application/json")
+                .header("Authorization", format!("Bearer {}", self.api_key))
+                .json(body),
+        )
+        .await
+        .map_err(|e| match e {
+            SearchError::Timeout { .. } => e,
+            _ => SearchError::ProviderError(format!("Exa API request failed: {e}")),
+        })?;
 
         let status = response.status();
         if !status.is_success() {
@@ -162,78 +409,340 @@ impl SearchProvider for ExaProvider {
             )));
         }
 
-        let exa_response: ExaSearchResponse = response.json().await.map_err(|e| {
-            SearchError::ProviderError(format!("Failed to parse Exa response: {e}"))
-        })?;
+        response
+            .json()
+            .await
+            .map_err(|e| SearchError::ProviderError(format!("Failed to parse Exa response: {e}")))
+    }
+}
+
+/// Convert Exa's results, skipping malformed ones with a warning
+fn convert_results(results: Vec<serde_json::Value>) -> SearchResponse {
+    let (results, warnings) = map_results("exa", results, |result: ExaSearchResult, raw| {
+        let domain = Url::parse(&result.url)
+            .ok()
+            .and_then(|url| url.host_str().map(intern));
+
+        Ok(SearchResultType {
+            url: result.url,
+            title: result.title.unwrap_or_default(),
+            // Full page text, only present when contents were requested; filling `content`
+            // lets `enrich::fetch_content` skip these results
+            content: result.text.clone(),
+            snippet: result.text,
+            domain,
+            published_date: result.published_date,
+            thumbnail_url: result.image,
+            favicon_url: result.favicon,
+            score: result.score,
+            provider: Some(interned!("exa")),
+            raw: Some(raw),
+            ..Default::default()
+        })
+    });
+
+    SearchResponse {
+        results,
+        warnings,
+        ..Default::default()
+    }
+}
+
+/// Combine provider-level and per-search domain lists, `None` when both are empty
+fn merge_domains(configured: &[String], requested: &Option<Vec<String>>) -> Option<Vec<String>> {
+    let mut domains = configured.to_vec();
+    for domain in requested.iter().flatten() {
+        if !domains.contains(domain) {
+            domains.push(domain.clone());
+        }
+    }
+    (!domains.is_empty()).then_some(domains)
+}
+
+#[async_trait::async_trait]
+impl SearchProvider for ExaProvider {
+    fn name(&self) -> &str {
+        "exa"
+    }
+
+    fn supports_domain_filter(&self) -> bool {
+        true
+    }
+
+    async fn search(&self, options: &SearchOptions) -> SearchResult<Vec<SearchResultType>> {
+        Ok(self.search_response(options).await?.results)
+    }
+
+    async fn search_response(&self, options: &SearchOptions) -> SearchResult<SearchResponse> {
+        if options.query.trim().is_empty() {
+            return Err(SearchError::InvalidInput(
+                "Query cannot be empty".to_string(),
+            ));
+        }
+
+        let request_body = self.build_request(options);
 
         if let Some(debug) = &options.debug {
-            if debug.enabled && debug.log_responses {
+            if debug.enabled && debug.log_requests {
                 log::info!(
-                    "Exa API response: {} results for query: {}",
-                    exa_response.results.len(),
-                    exa_response.autoPrompt_string
+                    "Exa API request: {} with query: {}",
+                    self.base_url,
+                    options.query
                 );
             }
         }
 
-        let results = exa_response
-            .results
-            .into_iter()
-            .map(|result| {
-                let domain = Url::parse(&result.url)
-                    .ok()
-                    .and_then(|url| url.host_str().map(|s| s.to_string()));
+        let exa_response: ExaSearchResponse = self.post(&self.base_url, &request_body).await?;
 
-                let mut raw_data = HashMap::new();
-                raw_data.insert(
-                    "id".to_string(),
-                    serde_json::Value::String(result.id.clone()),
+        if let Some(debug) = &options.debug {
+            if debug.enabled && debug.log_responses {
+                log::info!(
+                    "Exa API response: {} results for query: {}",
+                    exa_response.results.len(),
+                    exa_response
+                        .auto_prompt_string
+                        .as_deref()
+                        .unwrap_or(&options.query)
                 );
+            }
+        }
 
-                if let Some(score) = result.score {
-                    raw_data.insert(
-                        "score".to_string(),
-                        serde_json::Value::Number(
-                            serde_json::Number::from_f64(score)
-                                .unwrap_or_else(|| serde_json::Number::from(0)),
-                        ),
-                    );
-                }
-                if let Some(author) = &result.author {
-                    raw_data.insert(
-                        "author".to_string(),
-                        serde_json::Value::String(author.clone()),
-                    );
-                }
-
-                SearchResultType {
-                    url: result.url,
-                    title: result.title,
-                    snippet: result.text, // This might be None if content isn't included
-                    domain,
-                    published_date: result.published_date,
-                    provider: Some("exa".to_string()),
-                    raw: if raw_data.is_empty() {
-                        None
-                    } else {
-                        Some(serde_json::to_value(raw_data).unwrap_or_default())
-                    },
-                }
-            })
-            .collect();
-
-        Ok(results)
+        Ok(convert_results(exa_response.results))
     }
 
     fn config(&self) -> HashMap<String, String> {
         let mut config = HashMap::new();
         config.insert("api_key".to_string(), "***".to_string());
         config.insert("base_url".to_string(), self.base_url.clone());
-        config.insert("model".to_string(), self.model.clone());
+        config.insert("type".to_string(), self.search_type.to_string());
+        if let Some(category) = &self.category {
+            config.insert("category".to_string(), category.to_string());
+        }
         config.insert(
             "include_contents".to_string(),
             self.include_contents.to_string(),
         );
+        if !self.include_domains.is_empty() {
+            config.insert(
+                "include_domains".to_string(),
+                self.include_domains.join(","),
+            );
+        }
+        if !self.exclude_domains.is_empty() {
+            config.insert(
+                "exclude_domains".to_string(),
+                self.exclude_domains.join(","),
+            );
+        }
         config
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use wiremock::{
+        matchers::{body_json, header, method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    #[tokio::test]
+    async fn test_search_sends_filters() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(header("Authorization", "Bearer test-key"))
+            .and(body_json(serde_json::json!({
+                "query": "rust async runtimes",
+                "type": "neural",
+                "category": "research paper",
+                "numResults": 5,
+                "includeDomains": ["arxiv.org", "acm.org"],
+                "excludeDomains": ["medium.com"],
+                "startPublishedDate": "2023-01-01T00:00:00.000Z",
+                "endPublishedDate": "2023-12-31T23:59:59.999Z",
+                "contents": { "text": true }
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "requestId": "req-1",
+                "results": [{
+                    "id": "https://arxiv.org/abs/2301.00001",
+                    "title": "Async Rust",
+                    "url": "https://arxiv.org/abs/2301.00001",
+                    "text": "A study of async runtimes",
+                    "publishedDate": "2023-01-02T00:00:00.000Z",
+                    "score": 0.9
+                }]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let provider = ExaProvider::new("test-key")
+            .unwrap()
+            .with_base_url(&server.uri())
+            .with_search_type(ExaSearchType::Neural)
+            .with_category(ExaCategory::ResearchPaper)
+            .with_include_domains(["arxiv.org"])
+            .with_contents(true);
+
+        let results = provider
+            .search(&SearchOptions {
+                query: "rust async runtimes".to_string(),
+                max_results: Some(5),
+                include_domains: Some(vec!["acm.org".to_string(), "arxiv.org".to_string()]),
+                exclude_domains: Some(vec!["medium.com".to_string()]),
+                published_after: NaiveDate::from_ymd_opt(2023, 1, 1),
+                published_before: NaiveDate::from_ymd_opt(2023, 12, 31),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].domain.as_deref(), Some("arxiv.org"));
+        assert_eq!(
+            results[0].snippet.as_deref(),
+            Some("A study of async runtimes")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_find_similar() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/findSimilar"))
+            .and(body_json(serde_json::json!({
+                "url": "https://tokio.rs",
+                "numResults": 3,
+                "excludeDomains": ["tokio.rs"]
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "requestId": "req-2",
+                "results": [{
+                    "id": "https://async.rs",
+                    "title": "async-std",
+                    "url": "https://async.rs",
+                    "score": 0.8
+                }]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let provider = ExaProvider::new("test-key")
+            .unwrap()
+            .with_base_url(&format!("{}/search", server.uri()))
+            .with_exclude_domains(["tokio.rs"]);
+
+        let results = provider
+            .find_similar(
+                "https://tokio.rs",
+                &SearchOptions {
+                    max_results: Some(3),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].url, "https://async.rs");
+        assert_eq!(results[0].provider.as_deref(), Some("exa"));
+    }
+
+    #[tokio::test]
+    async fn test_get_contents() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/contents"))
+            .and(body_json(serde_json::json!({
+                "ids": ["https://tokio.rs/blog"],
+                "text": true
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "results": [{
+                    "id": "https://tokio.rs/blog",
+                    "title": "Tokio blog",
+                    "url": "https://tokio.rs/blog",
+                    "text": "Announcing Tokio 1.0"
+                }]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let provider = ExaProvider::new("test-key")
+            .unwrap()
+            .with_base_url(&server.uri());
+
+        let results = provider
+            .get_contents(["https://tokio.rs/blog"])
+            .await
+            .unwrap();
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].content.as_deref(), Some("Announcing Tokio 1.0"));
+        assert!(provider.get_contents(Vec::<String>::new()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_lenient_parsing() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "results": [
+                    {
+                        "url": "https://tokio.rs",
+                        "highlights": ["An asynchronous runtime"]
+                    },
+                    { "title": "No URL" },
+                    {
+                        "id": "https://docs.rs",
+                        "title": "Docs.rs",
+                        "url": "https://docs.rs"
+                    }
+                ]
+            })))
+            .mount(&server)
+            .await;
+
+        let provider = ExaProvider::new("test-key")
+            .unwrap()
+            .with_base_url(&server.uri());
+        let response = provider
+            .search_response(&SearchOptions {
+                query: "rust".to_string(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(response.results.len(), 2);
+        assert_eq!(response.results[0].title, "");
+        // Fields the SDK doesn't know about are kept
+        let raw = response.results[0].raw.as_ref().unwrap();
+        assert_eq!(raw["highlights"][0], "An asynchronous runtime");
+        assert_eq!(response.warnings.len(), 1);
+        assert_eq!(response.warnings[0].index, 1);
+        assert!(response.warnings[0].message.contains("url"));
+    }
+
+    #[test]
+    fn test_with_model_names() {
+        let provider = ExaProvider::new("key").unwrap();
+        assert_eq!(
+            provider.with_model("embeddings").unwrap().search_type,
+            ExaSearchType::Neural
+        );
+        let provider = ExaProvider::new("key").unwrap();
+        assert_eq!(
+            provider.with_model("auto").unwrap().search_type,
+            ExaSearchType::Auto
+        );
+        assert!(ExaProvider::new("key")
+            .unwrap()
+            .with_model("semantic")
+            .is_err());
+    }
+}
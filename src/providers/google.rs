@@ -2,39 +2,96 @@
 
 use crate::{
     error::{SearchError, SearchResult},
-    types::{ProviderConfig, SearchOptions, SearchProvider, SearchResult as SearchResultType},
-    utils::{debug, http::HttpClient},
+    highlight,
+    language::language_code,
+    locale::Locale,
+    providers::{forward_http_headers, map_results},
+    types::{
+        Freshness, ProviderConfig, ResultKind, SearchOptions, SearchProvider, SearchResponse,
+        SearchResult as SearchResultType,
+    },
+    utils::{
+        debug,
+        http::HttpClient,
+        intern::{intern, interned},
+    },
 };
-use serde::{Deserialize, Serialize};
+use chrono::Utc;
+use serde::Deserialize;
 use std::collections::HashMap;
+use std::fmt;
 
 /// Google Custom Search API response types
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize)]
 struct GoogleSearchItem {
     title: String,
     link: String,
     #[serde(rename = "displayLink")]
     display_link: String,
     snippet: String,
+    /// The snippet with matching terms in `<b>`
+    #[serde(rename = "htmlSnippet", default)]
+    html_snippet: Option<String>,
+    /// Set for files such as PDFs, e.g. `application/pdf`
+    #[serde(default)]
+    mime: Option<String>,
     #[serde(default)]
     pagemap: Option<GooglePageMap>,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize)]
 struct GooglePageMap {
     #[serde(default)]
     metatags: Option<Vec<HashMap<String, String>>>,
+    #[serde(default)]
+    cse_thumbnail: Option<Vec<GoogleThumbnail>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GoogleThumbnail {
+    src: String,
+}
+
+impl GoogleSearchItem {
+    /// First metatag value found under any of `keys`
+    fn metatag(&self, keys: &[&str]) -> Option<String> {
+        let meta = self.pagemap.as_ref()?.metatags.as_ref()?.first()?;
+        keys.iter()
+            .find_map(|key| meta.get(*key))
+            .filter(|value| !value.trim().is_empty())
+            .cloned()
+    }
+
+    /// The first language meta tag holding a language code; pages also write names such
+    /// as "English" there
+    fn language(&self) -> Option<String> {
+        ["og:locale", "content-language", "dc.language", "language"]
+            .iter()
+            .find_map(|key| language_code(&self.metatag(&[key])?))
+    }
+
+    fn thumbnail_url(&self) -> Option<String> {
+        self.pagemap
+            .as_ref()
+            .and_then(|pm| pm.cse_thumbnail.as_ref())
+            .and_then(|thumbnails| thumbnails.first())
+            .map(|thumbnail| thumbnail.src.clone())
+            .or_else(|| self.metatag(&["og:image", "twitter:image"]))
+    }
 }
 
 #[derive(Debug, Deserialize)]
+#[allow(dead_code)]
 struct GoogleSearchResponse {
+    /// Parsed one by one with [`map_results`]
     #[serde(default)]
-    items: Option<Vec<GoogleSearchItem>>,
+    items: Option<Vec<serde_json::Value>>,
     #[serde(rename = "searchInformation")]
     search_information: Option<GoogleSearchInfo>,
 }
 
 #[derive(Debug, Deserialize)]
+#[allow(dead_code)]
 struct GoogleSearchInfo {
     #[serde(rename = "totalResults")]
     total_results: String,
@@ -42,6 +99,77 @@ struct GoogleSearchInfo {
     search_time: f64,
 }
 
+/// Whether `siteSearch` limits results to the site or removes it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GoogleSiteSearchFilter {
+    Include,
+    Exclude,
+}
+
+impl GoogleSiteSearchFilter {
+    fn as_param(&self) -> &'static str {
+        match self {
+            Self::Include => "i",
+            Self::Exclude => "e",
+        }
+    }
+}
+
+/// Relative date window for `dateRestrict`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GoogleDateRestrict {
+    Days(u32),
+    Weeks(u32),
+    Months(u32),
+    Years(u32),
+}
+
+impl fmt::Display for GoogleDateRestrict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Days(n) => write!(f, "d{n}"),
+            Self::Weeks(n) => write!(f, "w{n}"),
+            Self::Months(n) => write!(f, "m{n}"),
+            Self::Years(n) => write!(f, "y{n}"),
+        }
+    }
+}
+
+impl From<Freshness> for GoogleDateRestrict {
+    fn from(freshness: Freshness) -> Self {
+        match freshness {
+            Freshness::Day => Self::Days(1),
+            Freshness::Week => Self::Weeks(1),
+            Freshness::Month => Self::Months(1),
+            Freshness::Year => Self::Years(1),
+        }
+    }
+}
+
+/// Image type filter (`imgType`) for image search
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GoogleImageType {
+    Clipart,
+    Face,
+    Lineart,
+    Stock,
+    Photo,
+    Animated,
+}
+
+impl GoogleImageType {
+    fn as_param(&self) -> &'static str {
+        match self {
+            Self::Clipart => "clipart",
+            Self::Face => "face",
+            Self::Lineart => "lineart",
+            Self::Stock => "stock",
+            Self::Photo => "photo",
+            Self::Animated => "animated",
+        }
+    }
+}
+
 /// Google Custom Search configuration
 #[derive(Debug, Clone)]
 pub struct GoogleConfig {
@@ -51,6 +179,22 @@ pub struct GoogleConfig {
     pub cx: String,
     /// Base URL for the API
     pub base_url: String,
+    /// Site to restrict results to or exclude (`siteSearch`/`siteSearchFilter`)
+    pub site_search: Option<(String, GoogleSiteSearchFilter)>,
+    /// Only return files of this extension, e.g. "pdf" (`fileType`)
+    pub file_type: Option<String>,
+    /// Only return documents from this recent window (`dateRestrict`)
+    pub date_restrict: Option<GoogleDateRestrict>,
+    /// Phrase every result must contain (`exactTerms`)
+    pub exact_terms: Option<String>,
+    /// Word or phrase no result may contain (`excludeTerms`)
+    pub exclude_terms: Option<String>,
+    /// Licensing filter such as "cc_publicdomain" or "cc_attribute" (`rights`)
+    pub rights: Option<String>,
+    /// Search images instead of web pages (`searchType=image`)
+    pub image_search: bool,
+    /// Image type filter, only used for image search (`imgType`)
+    pub img_type: Option<GoogleImageType>,
 }
 
 impl Default for GoogleConfig {
@@ -59,6 +203,14 @@ impl Default for GoogleConfig {
             api_key: String::new(),
             cx: String::new(),
             base_url: "https://www.googleapis.com/customsearch/v1".to_string(),
+            site_search: None,
+            file_type: None,
+            date_restrict: None,
+            exact_terms: None,
+            exclude_terms: None,
+            rights: None,
+            image_search: false,
+            img_type: None,
         }
     }
 }
@@ -121,13 +273,128 @@ impl GoogleProvider {
         })
     }
 
+    /// Restrict results to `site`, or drop results from it
+    pub fn with_site_search(mut self, site: &str, filter: GoogleSiteSearchFilter) -> Self {
+        self.config.site_search = Some((site.to_string(), filter));
+        self
+    }
+
+    /// Only return files with this extension, e.g. "pdf"
+    pub fn with_file_type(mut self, file_type: &str) -> Self {
+        self.config.file_type = Some(file_type.to_string());
+        self
+    }
+
+    /// Only return documents from a recent window, e.g. `GoogleDateRestrict::Weeks(2)`
+    pub fn with_date_restrict(mut self, date_restrict: GoogleDateRestrict) -> Self {
+        self.config.date_restrict = Some(date_restrict);
+        self
+    }
+
+    /// Require this phrase in every result
+    pub fn with_exact_terms(mut self, terms: &str) -> Self {
+        self.config.exact_terms = Some(terms.to_string());
+        self
+    }
+
+    /// Drop results containing this word or phrase
+    pub fn with_exclude_terms(mut self, terms: &str) -> Self {
+        self.config.exclude_terms = Some(terms.to_string());
+        self
+    }
+
+    /// Filter by license, e.g. "cc_publicdomain" or "cc_attribute"
+    pub fn with_rights(mut self, rights: &str) -> Self {
+        self.config.rights = Some(rights.to_string());
+        self
+    }
+
+    /// Search images (the engine must have image search enabled), optionally of one type
+    pub fn with_image_search(mut self, img_type: Option<GoogleImageType>) -> Self {
+        self.config.image_search = true;
+        self.config.img_type = img_type;
+        self
+    }
+
+    forward_http_headers!();
+
     /// Build the search URL with parameters
     fn build_search_url(&self, options: &SearchOptions) -> SearchResult<String> {
         let mut params = HashMap::new();
 
         params.insert("key".to_string(), self.config.api_key.clone());
         params.insert("cx".to_string(), self.config.cx.clone());
-        params.insert("q".to_string(), options.query.clone());
+
+        // siteSearch takes a single site; further domains become query operators
+        let mut query = options.query.clone();
+        let mut site_search = self.config.site_search.clone();
+        let include_domains = options.include_domains.as_deref().unwrap_or_default();
+        match include_domains {
+            [] => {}
+            [domain] if site_search.is_none() => {
+                site_search = Some((domain.clone(), GoogleSiteSearchFilter::Include));
+            }
+            domains => {
+                let sites: Vec<String> = domains.iter().map(|d| format!("site:{d}")).collect();
+                query = format!("{query} ({})", sites.join(" OR "));
+            }
+        }
+        for domain in options.exclude_domains.iter().flatten() {
+            if site_search.is_none() {
+                site_search = Some((domain.clone(), GoogleSiteSearchFilter::Exclude));
+            } else {
+                query = format!("{query} -site:{domain}");
+            }
+        }
+        params.insert("q".to_string(), query);
+
+        if let Some((site, filter)) = site_search {
+            params.insert("siteSearch".to_string(), site);
+            params.insert(
+                "siteSearchFilter".to_string(),
+                filter.as_param().to_string(),
+            );
+        }
+
+        // An explicit window wins; otherwise use freshness, else derive one from
+        // published_after
+        let date_restrict = self
+            .config
+            .date_restrict
+            .or_else(|| options.freshness.map(GoogleDateRestrict::from))
+            .or_else(|| {
+                let after = options.published_after?;
+                let days = (Utc::now().date_naive() - after).num_days().max(1);
+                Some(GoogleDateRestrict::Days(days as u32))
+            });
+        if let Some(date_restrict) = date_restrict {
+            params.insert("dateRestrict".to_string(), date_restrict.to_string());
+        }
+
+        // The configured type wins over the per-search option
+        let file_type = self
+            .config
+            .file_type
+            .clone()
+            .or_else(|| options.file_type.map(|t| t.extension().to_string()));
+        if let Some(file_type) = file_type {
+            params.insert("fileType".to_string(), file_type);
+        }
+        if let Some(terms) = &self.config.exact_terms {
+            params.insert("exactTerms".to_string(), terms.clone());
+        }
+        if let Some(terms) = &self.config.exclude_terms {
+            params.insert("excludeTerms".to_string(), terms.clone());
+        }
+        if let Some(rights) = &self.config.rights {
+            params.insert("rights".to_string(), rights.clone());
+        }
+        if self.config.image_search {
+            params.insert("searchType".to_string(), "image".to_string());
+            if let Some(img_type) = &self.config.img_type {
+                params.insert("imgType".to_string(), img_type.as_param().to_string());
+            }
+        }
 
         // Add max results (Google limits to 10 per request)
         if let Some(max_results) = options.max_results {
@@ -142,14 +409,13 @@ impl GoogleProvider {
             params.insert("start".to_string(), start.to_string());
         }
 
-        // Add language
-        if let Some(language) = &options.language {
-            params.insert("lr".to_string(), format!("lang_{language}"));
+        // Add language and region
+        let locale = Locale::from_options(options.language.as_deref(), options.region.as_deref())?;
+        if let Some(lr) = locale.as_ref().and_then(Locale::google_lr) {
+            params.insert("lr".to_string(), lr);
         }
-
-        // Add region
-        if let Some(region) = &options.region {
-            params.insert("gl".to_string(), region.clone());
+        if let Some(gl) = locale.as_ref().and_then(Locale::google_gl) {
+            params.insert("gl".to_string(), gl);
         }
 
         // Add safe search
@@ -171,7 +437,15 @@ impl SearchProvider for GoogleProvider {
         "google"
     }
 
+    fn supports_pagination(&self) -> bool {
+        true
+    }
+
     async fn search(&self, options: &SearchOptions) -> SearchResult<Vec<SearchResultType>> {
+        Ok(self.search_response(options).await?.results)
+    }
+
+    async fn search_response(&self, options: &SearchOptions) -> SearchResult<SearchResponse> {
         // Log request if debugging is enabled
         debug::log_request(
             &options.debug,
@@ -197,40 +471,58 @@ impl SearchProvider for GoogleProvider {
             ),
         );
 
-        // Convert Google results to standard format
-        let results = if let Some(items) = response.items {
-            items
-                .into_iter()
-                .map(|item| {
-                    // Extract published date from metadata if available
-                    let published_date = item
-                        .pagemap
-                        .as_ref()
-                        .and_then(|pm| pm.metatags.as_ref())
-                        .and_then(|tags| tags.first())
-                        .and_then(|meta| {
-                            meta.get("article:published_time")
-                                .or_else(|| meta.get("date"))
-                                .or_else(|| meta.get("og:updated_time"))
-                        })
-                        .cloned();
-
-                    SearchResultType {
-                        url: item.link.clone(),
-                        title: item.title.clone(),
-                        snippet: Some(item.snippet.clone()),
-                        domain: Some(item.display_link.clone()),
-                        published_date,
-                        provider: Some("google".to_string()),
-                        raw: serde_json::to_value(&item).ok(),
-                    }
-                })
-                .collect()
-        } else {
-            Vec::new()
-        };
+        // Convert Google results to standard format, skipping malformed ones
+        let items = response.items.unwrap_or_default();
+        let (results, warnings) = map_results("google", items, |item: GoogleSearchItem, raw| {
+            // Extract published date from metadata if available
+            let published_date =
+                item.metatag(&["article:published_time", "date", "og:updated_time"]);
+            let highlights = item
+                .html_snippet
+                .as_deref()
+                .map(|html| highlight::find_terms(&item.snippet, &highlight::html_terms(html)))
+                .unwrap_or_default();
+            let kind = match item.mime.as_deref() {
+                Some(mime) if mime != "text/html" => ResultKind::Document,
+                _ => ResultKind::Organic,
+            };
+
+            Ok(SearchResultType {
+                published_date,
+                provider: Some(interned!("google")),
+                thumbnail_url: item.thumbnail_url(),
+                description: item.metatag(&["og:description", "twitter:description"]),
+                site_name: item.metatag(&["og:site_name"]),
+                language: item.language(),
+                url: item.link,
+                title: item.title,
+                snippet: Some(item.snippet),
+                kind,
+                highlights,
+                domain: Some(intern(&item.display_link)),
+                // The original item, including fields this SDK doesn't map
+                raw: Some(raw),
+                ..Default::default()
+            })
+        });
+
+        Ok(SearchResponse {
+            results,
+            warnings,
+            ..Default::default()
+        })
+    }
 
-        Ok(results)
+    fn supports_safe_search(&self) -> bool {
+        true
+    }
+
+    fn supports_domain_filter(&self) -> bool {
+        true
+    }
+
+    fn supports_freshness(&self) -> bool {
+        true
     }
 
     fn config(&self) -> HashMap<String, String> {
@@ -238,6 +530,221 @@ impl SearchProvider for GoogleProvider {
         config.insert("api_key".to_string(), "***".to_string()); // Hide API key
         config.insert("cx".to_string(), self.config.cx.clone());
         config.insert("base_url".to_string(), self.config.base_url.clone());
+        if let Some((site, filter)) = &self.config.site_search {
+            config.insert(
+                "site_search".to_string(),
+                format!("{site} ({})", filter.as_param()),
+            );
+        }
+        let optional = [
+            ("file_type", self.config.file_type.clone()),
+            (
+                "date_restrict",
+                self.config
+                    .date_restrict
+                    .map(|restrict| restrict.to_string()),
+            ),
+            ("exact_terms", self.config.exact_terms.clone()),
+            ("exclude_terms", self.config.exclude_terms.clone()),
+            ("rights", self.config.rights.clone()),
+            (
+                "img_type",
+                self.config
+                    .img_type
+                    .map(|img_type| img_type.as_param().to_string()),
+            ),
+        ];
+        for (name, value) in optional {
+            if let Some(value) = value {
+                config.insert(name.to_string(), value);
+            }
+        }
+        config.insert(
+            "image_search".to_string(),
+            self.config.image_search.to_string(),
+        );
         config
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::FileType;
+    use chrono::Duration;
+
+    fn query_params(url: &str) -> HashMap<String, String> {
+        url::Url::parse(url)
+            .unwrap()
+            .query_pairs()
+            .into_owned()
+            .collect()
+    }
+
+    #[test]
+    fn test_advanced_params() {
+        let provider = GoogleProvider::new("key", "cx")
+            .unwrap()
+            .with_site_search("rust-lang.org", GoogleSiteSearchFilter::Include)
+            .with_file_type("pdf")
+            .with_date_restrict(GoogleDateRestrict::Weeks(2))
+            .with_exact_terms("borrow checker")
+            .with_exclude_terms("gc")
+            .with_rights("cc_attribute")
+            .with_image_search(Some(GoogleImageType::Photo));
+
+        let params = query_params(
+            &provider
+                .build_search_url(&SearchOptions {
+                    query: "ownership".to_string(),
+                    ..Default::default()
+                })
+                .unwrap(),
+        );
+
+        assert_eq!(params["q"], "ownership");
+        assert_eq!(params["siteSearch"], "rust-lang.org");
+        assert_eq!(params["siteSearchFilter"], "i");
+        assert_eq!(params["fileType"], "pdf");
+        assert_eq!(params["dateRestrict"], "w2");
+        assert_eq!(params["exactTerms"], "borrow checker");
+        assert_eq!(params["excludeTerms"], "gc");
+        assert_eq!(params["rights"], "cc_attribute");
+        assert_eq!(params["searchType"], "image");
+        assert_eq!(params["imgType"], "photo");
+    }
+
+    #[test]
+    fn test_search_options_filters() {
+        let provider = GoogleProvider::new("key", "cx").unwrap();
+
+        let params = query_params(
+            &provider
+                .build_search_url(&SearchOptions {
+                    query: "async".to_string(),
+                    include_domains: Some(vec!["docs.rs".to_string()]),
+                    exclude_domains: Some(vec!["reddit.com".to_string()]),
+                    published_after: Some(Utc::now().date_naive() - Duration::days(30)),
+                    file_type: Some(FileType::Ppt),
+                    ..Default::default()
+                })
+                .unwrap(),
+        );
+        assert_eq!(params["q"], "async -site:reddit.com");
+        assert_eq!(params["siteSearch"], "docs.rs");
+        assert_eq!(params["siteSearchFilter"], "i");
+        assert_eq!(params["dateRestrict"], "d30");
+        assert_eq!(params["fileType"], "ppt");
+
+        let params = query_params(
+            &provider
+                .build_search_url(&SearchOptions {
+                    query: "async".to_string(),
+                    include_domains: Some(vec!["docs.rs".to_string(), "tokio.rs".to_string()]),
+                    ..Default::default()
+                })
+                .unwrap(),
+        );
+        assert_eq!(params["q"], "async (site:docs.rs OR site:tokio.rs)");
+        assert!(!params.contains_key("siteSearch"));
+
+        let params = query_params(
+            &provider
+                .build_search_url(&SearchOptions {
+                    query: "async".to_string(),
+                    freshness: Some(Freshness::Month),
+                    published_after: Some(Utc::now().date_naive() - Duration::days(90)),
+                    ..Default::default()
+                })
+                .unwrap(),
+        );
+        assert_eq!(params["dateRestrict"], "m1");
+    }
+
+    #[tokio::test]
+    async fn test_pagemap_extraction() {
+        use wiremock::{matchers::method, Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "items": [{
+                    "title": "The Rust Programming Language",
+                    "link": "https://doc.rust-lang.org/book/",
+                    "displayLink": "doc.rust-lang.org",
+                    "snippet": "An introductory book about Rust.",
+                    "pagemap": {
+                        "cse_thumbnail": [{ "src": "https://encrypted-tbn0.gstatic.com/images?q=1", "width": "225", "height": "225" }],
+                        "metatags": [{
+                            "og:image": "https://doc.rust-lang.org/book/og.png",
+                            "og:description": "The official book on Rust",
+                            "og:site_name": "Rust Documentation",
+                            "og:locale": "en_US",
+                            "article:published_time": "2024-01-01"
+                        }]
+                    }
+                }, {
+                    "title": "Rust by Example",
+                    "link": "https://doc.rust-lang.org/rust-by-example/",
+                    "displayLink": "doc.rust-lang.org",
+                    "snippet": "A collection of runnable examples.",
+                    "pagemap": { "metatags": [{
+                        "og:image": "https://doc.rust-lang.org/rbe.png",
+                        "language": "English"
+                    }] }
+                }, {
+                    "title": "Rust Reference (PDF)",
+                    "link": "https://example.com/rust-reference.pdf",
+                    "displayLink": "example.com",
+                    "snippet": "The primary reference for the Rust language.",
+                    "mime": "application/pdf",
+                    "pagemap": { "metatags": [{
+                        "content-language": "French",
+                        "dc.language": "fr-CA"
+                    }] }
+                }]
+            })))
+            .mount(&server)
+            .await;
+
+        let provider = GoogleProvider::with_config(GoogleConfig {
+            api_key: "key".to_string(),
+            cx: "cx".to_string(),
+            base_url: server.uri(),
+            ..Default::default()
+        })
+        .unwrap();
+
+        let results = provider
+            .search(&SearchOptions {
+                query: "rust book".to_string(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(
+            results[0].thumbnail_url.as_deref(),
+            Some("https://encrypted-tbn0.gstatic.com/images?q=1")
+        );
+        assert_eq!(
+            results[0].description.as_deref(),
+            Some("The official book on Rust")
+        );
+        assert_eq!(results[0].site_name.as_deref(), Some("Rust Documentation"));
+        assert_eq!(results[0].published_date.as_deref(), Some("2024-01-01"));
+        assert_eq!(results[0].language.as_deref(), Some("en"));
+
+        // Falls back to og:image without a CSE thumbnail
+        assert_eq!(
+            results[1].thumbnail_url.as_deref(),
+            Some("https://doc.rust-lang.org/rbe.png")
+        );
+        assert_eq!(results[1].site_name, None);
+        assert_eq!(results[1].language, None);
+
+        assert_eq!(results[1].kind, ResultKind::Organic);
+        assert_eq!(results[2].kind, ResultKind::Document);
+        assert_eq!(results[2].language.as_deref(), Some("fr"));
+    }
+}
@@ -5,9 +5,11 @@ pub mod brave;
 pub mod duckduckgo;
 pub mod exa;
 pub mod google;
+pub mod scholar;
 pub mod searxng;
 pub mod serpapi;
 pub mod tavily;
+pub mod youtube;
 
 // Re-export providers for convenience
 pub use arxiv::ArxivProvider;
@@ -15,6 +17,169 @@ pub use brave::BraveProvider;
 pub use duckduckgo::DuckDuckGoProvider;
 pub use exa::ExaProvider;
 pub use google::GoogleProvider;
+pub use scholar::GoogleScholarProvider;
 pub use searxng::SearxNGProvider;
 pub use serpapi::SerpApiProvider;
 pub use tavily::TavilyProvider;
+pub use youtube::YouTubeProvider;
+
+use crate::types::{ParseWarning, SearchResult};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::time::Duration;
+use url::Url;
+
+/// Keep the results that mapped successfully and have a usable URL, turning each failure
+/// into a [`ParseWarning`] rather than failing the whole search
+///
+/// `items` are a provider's results in response order, already mapped; a failed item
+/// carries the reason it couldn't be mapped.
+pub(crate) fn collect_results<I>(provider: &str, items: I) -> (Vec<SearchResult>, Vec<ParseWarning>)
+where
+    I: IntoIterator<Item = Result<SearchResult, String>>,
+{
+    let mut results = Vec::new();
+    let mut warnings = Vec::new();
+    for (index, item) in items.into_iter().enumerate() {
+        match item.and_then(|result| check_url(&result.url).map(|()| result)) {
+            Ok(result) => results.push(result),
+            Err(message) => {
+                let warning = ParseWarning {
+                    provider: provider.to_string(),
+                    index,
+                    message,
+                };
+                log::warn!("{warning}");
+                warnings.push(warning);
+            }
+        }
+    }
+    (results, warnings)
+}
+
+/// Deserialize each of a provider's JSON results on its own and map it with `map`, like
+/// [`collect_results`]
+///
+/// `map` also gets the result's original JSON, unknown fields included, to keep as
+/// [`SearchResult::raw`].
+pub(crate) fn map_results<T, F>(
+    provider: &str,
+    items: Vec<Value>,
+    mut map: F,
+) -> (Vec<SearchResult>, Vec<ParseWarning>)
+where
+    T: DeserializeOwned,
+    F: FnMut(T, Value) -> Result<SearchResult, String>,
+{
+    let items = items.into_iter().map(|raw| {
+        let item = T::deserialize(&raw).map_err(|e| e.to_string())?;
+        map(item, raw)
+    });
+    collect_results(provider, items)
+}
+
+/// A video length written like a clock, `"3:25"` or `"1:02:03"`, as Brave and SerpAPI
+/// report them
+pub(crate) fn parse_clock(length: &str) -> Option<Duration> {
+    let parts: Vec<&str> = length.trim().split(':').collect();
+    if parts.len() > 3 {
+        return None;
+    }
+    parts
+        .into_iter()
+        .try_fold(0, |seconds: u64, part| {
+            let part: u64 = part.parse().ok()?;
+            Some(seconds * 60 + part)
+        })
+        .map(Duration::from_secs)
+}
+
+/// A count that providers send either as a number or a string such as `"1,234"`
+pub(crate) fn parse_count(value: &Value) -> Option<u64> {
+    match value {
+        Value::Number(number) => number.as_u64(),
+        Value::String(text) => text.replace(',', "").trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// A result URL must be absolute, with a host
+fn check_url(url: &str) -> Result<(), String> {
+    match Url::parse(url) {
+        Ok(parsed) if parsed.has_host() => Ok(()),
+        Ok(_) => Err(format!("URL has no host: {url}")),
+        Err(e) => Err(format!("invalid URL \"{url}\": {e}")),
+    }
+}
+
+/// `with_accept` and `with_accept_encoding` builder methods for a provider whose requests
+/// go through its `http_client`, forwarding to the
+/// [`HttpClient`](crate::utils::http::HttpClient) ones
+macro_rules! forward_http_headers {
+    () => {
+        /// Override the `Accept` header sent with every request
+        pub fn with_accept(mut self, accept: impl Into<String>) -> Self {
+            self.http_client = self.http_client.with_accept(accept);
+            self
+        }
+
+        /// Override the `Accept-Encoding` header (`gzip, br` by default)
+        pub fn with_accept_encoding(mut self, encoding: impl Into<String>) -> Self {
+            self.http_client = self.http_client.with_accept_encoding(encoding);
+            self
+        }
+    };
+}
+pub(crate) use forward_http_headers;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Deserialize)]
+    struct Item {
+        url: String,
+        title: String,
+    }
+
+    #[test]
+    fn test_map_results_skips_and_reports_bad_items() {
+        let items = serde_json::json!([
+            { "url": "https://docs.rs", "title": "Docs.rs" },
+            { "url": "https://crates.io" },
+            { "url": "/relative/link", "title": "Relative" },
+            { "url": "https://lib.rs", "title": "Lib.rs" }
+        ]);
+        let (results, warnings) = map_results(
+            "test",
+            serde_json::from_value(items).unwrap(),
+            |item: Item, _| {
+                Ok(SearchResult {
+                    url: item.url,
+                    title: item.title,
+                    ..Default::default()
+                })
+            },
+        );
+
+        let urls: Vec<_> = results.iter().map(|r| r.url.as_str()).collect();
+        assert_eq!(urls, ["https://docs.rs", "https://lib.rs"]);
+        let indexes: Vec<_> = warnings.iter().map(|w| w.index).collect();
+        assert_eq!(indexes, [1, 2]);
+        assert!(warnings[0].message.contains("title"));
+        assert!(warnings[1].message.contains("invalid URL"));
+    }
+
+    #[test]
+    fn test_parse_video_lengths_and_counts() {
+        assert_eq!(parse_clock("3:25"), Some(Duration::from_secs(205)));
+        assert_eq!(parse_clock("1:02:03"), Some(Duration::from_secs(3723)));
+        assert_eq!(parse_clock("LIVE"), None);
+        assert_eq!(parse_count(&serde_json::json!(1200)), Some(1200));
+        assert_eq!(
+            parse_count(&serde_json::json!("1,234,567")),
+            Some(1_234_567)
+        );
+    }
+}
@@ -0,0 +1,485 @@
+//! Google Scholar provider (through SerpAPI's `google_scholar` engine)
+//!
+//! Besides the standard result fields, each result's `raw` value holds a
+//! [`ScholarMetadata`] with the authors, publication year and cited-by count, alongside
+//! the fields of a [`ScholarlyResult`], so either's `from_result` reads it. As a
+//! [`ScholarlyProvider`], it returns the same metadata as `ScholarlyResult`s.
+
+use crate::{
+    error::{SearchError, SearchResult},
+    providers::{forward_http_headers, map_results, serpapi::SerpApiProvider},
+    scholarly::{ScholarlyProvider, ScholarlyResult},
+    types::{SearchOptions, SearchProvider, SearchResponse, SearchResult as SearchResultType},
+    utils::{
+        debug,
+        http::{build_url, extract_domain, HttpClient},
+        intern::interned,
+    },
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Google Scholar returns at most 20 results per request
+const MAX_NUM: u32 = 20;
+
+#[derive(Debug, Deserialize, Serialize)]
+struct ScholarResult {
+    title: String,
+    result_id: Option<String>,
+    link: Option<String>,
+    snippet: Option<String>,
+    #[serde(rename = "type")]
+    result_type: Option<String>,
+    publication_info: Option<ScholarPublicationInfo>,
+    inline_links: Option<ScholarInlineLinks>,
+    resources: Option<Vec<ScholarResource>>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct ScholarPublicationInfo {
+    summary: Option<String>,
+    authors: Option<Vec<ScholarAuthor>>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+struct ScholarAuthor {
+    name: String,
+    author_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct ScholarInlineLinks {
+    cited_by: Option<ScholarCitedBy>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct ScholarCitedBy {
+    total: Option<u64>,
+    link: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct ScholarResource {
+    link: Option<String>,
+    file_format: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ScholarResponse {
+    /// Parsed one by one with [`map_results`]
+    organic_results: Option<Vec<serde_json::Value>>,
+    error: Option<String>,
+}
+
+/// Scholarly metadata stored in [`SearchResult::raw`](crate::types::SearchResult::raw)
+/// for Google Scholar results
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ScholarMetadata {
+    /// Author names, as listed by Google Scholar (often abbreviated, e.g. "A Vaswani")
+    pub authors: Vec<String>,
+    /// Publication year
+    pub year: Option<u16>,
+    /// Number of citing documents
+    pub cited_by: Option<u64>,
+    /// Google Scholar page listing the citing documents
+    pub cited_by_link: Option<String>,
+    /// Publication summary line ("authors - venue, year - publisher")
+    pub publication: Option<String>,
+    /// Google Scholar result identifier
+    pub result_id: Option<String>,
+    /// Result type reported by Google Scholar (e.g. "Pdf", "Html", "Citation")
+    pub result_type: Option<String>,
+}
+
+impl ScholarMetadata {
+    /// Read the metadata back from a Google Scholar search result
+    pub fn from_result(result: &SearchResultType) -> Option<Self> {
+        serde_json::from_value(result.raw.clone()?).ok()
+    }
+}
+
+/// Google Scholar search provider
+#[derive(Debug)]
+pub struct GoogleScholarProvider {
+    api_key: String,
+    base_url: String,
+    year_from: Option<u16>,
+    year_to: Option<u16>,
+    include_citations: bool,
+    http_client: HttpClient,
+}
+
+impl GoogleScholarProvider {
+    /// Create a new Google Scholar provider with a SerpAPI key
+    pub fn new(api_key: &str) -> SearchResult<Self> {
+        if api_key.is_empty() {
+            return Err(SearchError::ConfigError(
+                "SerpAPI key is required".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            api_key: api_key.to_string(),
+            base_url: "https://serpapi.com/search.json".to_string(),
+            year_from: None,
+            year_to: None,
+            include_citations: true,
+            http_client: HttpClient::new(),
+        })
+    }
+
+    /// Only return papers published in or after this year
+    pub fn with_year_from(mut self, year: u16) -> Self {
+        self.year_from = Some(year);
+        self
+    }
+
+    /// Only return papers published in or before this year
+    pub fn with_year_to(mut self, year: u16) -> Self {
+        self.year_to = Some(year);
+        self
+    }
+
+    /// Include bare citations (results Google Scholar knows only from references)
+    pub fn with_citations(mut self, include_citations: bool) -> Self {
+        self.include_citations = include_citations;
+        self
+    }
+
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.to_string();
+        self
+    }
+
+    forward_http_headers!();
+
+    /// Build the search URL with parameters
+    fn build_search_url(&self, options: &SearchOptions) -> SearchResult<String> {
+        let mut params = HashMap::new();
+        params.insert("engine".to_string(), "google_scholar".to_string());
+        params.insert("api_key".to_string(), self.api_key.clone());
+        params.insert("q".to_string(), options.query.clone());
+
+        let num = options.max_results.unwrap_or(10).clamp(1, MAX_NUM);
+        params.insert("num".to_string(), num.to_string());
+
+        if let Some(page) = options.page.filter(|page| *page > 1) {
+            params.insert("start".to_string(), ((page - 1) * num).to_string());
+        }
+
+        if let Some(language) = &options.language {
+            params.insert("hl".to_string(), language.clone());
+        }
+
+        if let Some(year) = self.year_from {
+            params.insert("as_ylo".to_string(), year.to_string());
+        }
+
+        if let Some(year) = self.year_to {
+            params.insert("as_yhi".to_string(), year.to_string());
+        }
+
+        if !self.include_citations {
+            params.insert("as_vis".to_string(), "1".to_string());
+        }
+
+        build_url(&self.base_url, params)
+    }
+}
+
+/// Find the publication year in a summary such as "A Vaswani, N Shazeer - Advances in
+/// neural information processing systems, 2017 - proceedings.neurips.cc"
+fn parse_year(summary: &str) -> Option<u16> {
+    summary
+        .rsplit(|c: char| !c.is_ascii_digit())
+        .filter(|token| token.len() == 4)
+        .filter_map(|token| token.parse::<u16>().ok())
+        .find(|year| (1500..=2100).contains(year))
+}
+
+/// Find the venue in a summary such as "A Vaswani, N Shazeer - Advances in neural
+/// information processing systems, 2017 - proceedings.neurips.cc"
+fn parse_venue(summary: &str) -> Option<String> {
+    let source = summary.split(" - ").nth(1)?.trim();
+    let venue = match source.rsplit_once(',') {
+        Some((venue, year)) if year.trim().parse::<u16>().is_ok() => venue,
+        _ if source.parse::<u16>().is_ok() => "",
+        _ => source,
+    };
+    Some(venue.trim().to_string()).filter(|venue| !venue.is_empty())
+}
+
+/// `raw` for a result: the [`ScholarlyResult`] fields with the [`ScholarMetadata`] ones
+/// added, so both types read it back
+fn raw_value(paper: &ScholarlyResult, metadata: &ScholarMetadata) -> Option<serde_json::Value> {
+    let mut raw = serde_json::to_value(paper).ok()?;
+    let serde_json::Value::Object(metadata) = serde_json::to_value(metadata).ok()? else {
+        return None;
+    };
+    raw.as_object_mut()?.extend(metadata);
+    Some(raw)
+}
+
+fn convert_result(result: ScholarResult) -> Result<SearchResultType, String> {
+    let url = result
+        .link
+        .clone()
+        .or_else(|| {
+            result
+                .resources
+                .iter()
+                .flatten()
+                .find_map(|resource| resource.link.clone())
+        })
+        .ok_or_else(|| format!("\"{}\" has no link", result.title))?;
+
+    let summary = result
+        .publication_info
+        .as_ref()
+        .and_then(|info| info.summary.clone());
+    let cited_by = result
+        .inline_links
+        .as_ref()
+        .and_then(|links| links.cited_by.as_ref());
+
+    let metadata = ScholarMetadata {
+        authors: result
+            .publication_info
+            .as_ref()
+            .and_then(|info| info.authors.as_ref())
+            .map(|authors| authors.iter().map(|author| author.name.clone()).collect())
+            .unwrap_or_default(),
+        year: summary.as_deref().and_then(parse_year),
+        cited_by: cited_by.and_then(|cited_by| cited_by.total),
+        cited_by_link: cited_by.and_then(|cited_by| cited_by.link.clone()),
+        publication: summary,
+        result_id: result.result_id.clone(),
+        result_type: result.result_type.clone(),
+    };
+
+    let published_date = metadata.year.map(|year| year.to_string());
+    let paper = ScholarlyResult {
+        title: result.title.clone(),
+        url: url.clone(),
+        authors: metadata.authors.clone(),
+        abstract_text: result.snippet.clone(),
+        venue: metadata.publication.as_deref().and_then(parse_venue),
+        year: metadata.year,
+        published_date: published_date.clone(),
+        citations: metadata.cited_by,
+        ..Default::default()
+    };
+
+    Ok(SearchResultType {
+        domain: extract_domain(&url),
+        url,
+        title: result.title,
+        snippet: result.snippet,
+        published_date,
+        provider: Some(interned!("google_scholar")),
+        raw: raw_value(&paper, &metadata),
+        ..Default::default()
+    })
+}
+
+#[async_trait::async_trait]
+impl SearchProvider for GoogleScholarProvider {
+    fn name(&self) -> &str {
+        "google_scholar"
+    }
+
+    fn supports_pagination(&self) -> bool {
+        true
+    }
+
+    async fn search(&self, options: &SearchOptions) -> SearchResult<Vec<SearchResultType>> {
+        Ok(self.search_response(options).await?.results)
+    }
+
+    async fn search_response(&self, options: &SearchOptions) -> SearchResult<SearchResponse> {
+        debug::log_request(
+            &options.debug,
+            "Google Scholar request",
+            &format!("query: {}", options.query),
+        );
+
+        let url = self.build_search_url(options)?;
+        let body = self.http_client.get_bytes(&url).await?;
+        let response: ScholarResponse = serde_json::from_slice(&body)?;
+
+        if let Some(error) = response.error {
+            return Err(SearchError::ProviderError(format!(
+                "SerpAPI error: {error}"
+            )));
+        }
+
+        // Citations without a link to the paper are skipped with a warning
+        let (results, warnings) = map_results(
+            "google_scholar",
+            response.organic_results.unwrap_or_default(),
+            |result, _| convert_result(result),
+        );
+
+        debug::log_response(
+            &options.debug,
+            &format!("Google Scholar returned {} results", results.len()),
+        );
+
+        Ok(SearchResponse {
+            results,
+            warnings,
+            ..Default::default()
+        })
+    }
+
+    /// Checks the SerpAPI key with the free Account API, as [`SerpApiProvider`] does
+    async fn validate_credentials(&self) -> SearchResult<()> {
+        SerpApiProvider::new(&self.api_key)?
+            .with_base_url(&self.base_url)
+            .validate_credentials()
+            .await
+    }
+
+    fn config(&self) -> HashMap<String, String> {
+        let mut config = HashMap::new();
+        config.insert("api_key".to_string(), "***".to_string());
+        config.insert("base_url".to_string(), self.base_url.clone());
+        config.insert(
+            "include_citations".to_string(),
+            self.include_citations.to_string(),
+        );
+        if let Some(year) = self.year_from {
+            config.insert("year_from".to_string(), year.to_string());
+        }
+        if let Some(year) = self.year_to {
+            config.insert("year_to".to_string(), year.to_string());
+        }
+        config
+    }
+}
+
+/// Google Scholar reports no DOIs or abstracts; `abstract_text` holds the result snippet
+#[async_trait::async_trait]
+impl ScholarlyProvider for GoogleScholarProvider {
+    async fn search_scholarly(
+        &self,
+        options: &SearchOptions,
+    ) -> SearchResult<Vec<ScholarlyResult>> {
+        let response = self.search_response(options).await?;
+        Ok(response
+            .results
+            .iter()
+            .filter_map(ScholarlyResult::from_result)
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::{
+        matchers::{method, query_param},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    #[test]
+    fn test_parse_year() {
+        assert_eq!(
+            parse_year("A Vaswani, N Shazeer - Advances in neural information processing systems, 2017 - proceedings.neurips.cc"),
+            Some(2017)
+        );
+        assert_eq!(parse_year("J Smith - Journal of Things, 12345"), None);
+        assert_eq!(parse_year("no year here"), None);
+    }
+
+    #[test]
+    fn test_parse_venue() {
+        assert_eq!(
+            parse_venue("A Vaswani, N Shazeer - Advances in neural information processing systems, 2017 - proceedings.neurips.cc").as_deref(),
+            Some("Advances in neural information processing systems")
+        );
+        assert_eq!(parse_venue("J Smith - 2019 - Springer"), None);
+        assert_eq!(
+            parse_venue("J Smith - Nature - nature.com").as_deref(),
+            Some("Nature")
+        );
+        assert_eq!(parse_venue("J Smith"), None);
+    }
+
+    #[tokio::test]
+    async fn test_search_maps_scholarly_metadata() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(query_param("engine", "google_scholar"))
+            .and(query_param("q", "transformers"))
+            .and(query_param("as_ylo", "2015"))
+            .and(query_param("as_vis", "1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "organic_results": [{
+                    "position": 0,
+                    "title": "Attention is all you need",
+                    "result_id": "5Gohgn6QFikJ",
+                    "link": "https://proceedings.neurips.cc/paper/7181-attention",
+                    "snippet": "The dominant sequence transduction models…",
+                    "type": "Html",
+                    "publication_info": {
+                        "summary": "A Vaswani, N Shazeer - Advances in neural information processing systems, 2017 - proceedings.neurips.cc",
+                        "authors": [
+                            { "name": "A Vaswani", "author_id": "oR9sCGYAAAAJ" },
+                            { "name": "N Shazeer", "author_id": "wsGvgA8AAAAJ" }
+                        ]
+                    },
+                    "inline_links": {
+                        "cited_by": { "total": 120000, "link": "https://scholar.google.com/scholar?cites=1" }
+                    }
+                }]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let provider = GoogleScholarProvider::new("test-key")
+            .unwrap()
+            .with_base_url(&server.uri())
+            .with_year_from(2015)
+            .with_citations(false);
+
+        let results = provider
+            .search(&SearchOptions {
+                query: "transformers".to_string(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].published_date.as_deref(), Some("2017"));
+        assert_eq!(results[0].domain.as_deref(), Some("proceedings.neurips.cc"));
+
+        let metadata = ScholarMetadata::from_result(&results[0]).unwrap();
+        assert_eq!(metadata.authors, ["A Vaswani", "N Shazeer"]);
+        assert_eq!(metadata.year, Some(2017));
+        assert_eq!(metadata.cited_by, Some(120000));
+        assert_eq!(metadata.result_id.as_deref(), Some("5Gohgn6QFikJ"));
+
+        let paper = ScholarlyResult::from_result(&results[0]).unwrap();
+        assert_eq!(paper.authors, ["A Vaswani", "N Shazeer"]);
+        assert_eq!(
+            paper.venue.as_deref(),
+            Some("Advances in neural information processing systems")
+        );
+        assert_eq!(paper.citations, Some(120000));
+        assert_eq!(paper.provider.as_deref(), Some("google_scholar"));
+        assert_eq!(paper.abstract_text, results[0].snippet);
+
+        // The provider name doesn't matter, e.g. behind a differently named wrapper
+        let mut renamed = results[0].clone();
+        renamed.provider = Some(interned!("scholar-mirror"));
+        let renamed = ScholarlyResult::from_result(&renamed).unwrap();
+        assert_eq!(renamed.citations, Some(120000));
+
+        let bibtex = crate::format::to_bibtex(&results);
+        assert!(bibtex.starts_with("@article{vaswani2017attention,\n"));
+        assert!(bibtex.contains("  author = {A Vaswani and N Shazeer},\n"));
+    }
+}
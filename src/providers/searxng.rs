@@ -1,28 +1,564 @@
-//! SearXNG provider
+//! SearXNG metasearch provider
+//!
+//! Queries a SearXNG instance through its JSON API (`format=json`). Many public
+//! instances disable the JSON output format; in that case the provider falls back to
+//! parsing the regular HTML results page.
+//!
+//! Public instances are often flaky, so several instances can be configured. Searches
+//! rotate among them; an instance that errors or rate-limits is demoted for a cooldown
+//! period that grows with each consecutive failure, and the next instance is tried.
 
 use crate::{
     error::{SearchError, SearchResult},
-    types::{SearchOptions, SearchProvider, SearchResult as SearchResultType},
+    locale,
+    providers::{collect_results, forward_http_headers, map_results},
+    types::{
+        ProviderConfig, SafeSearch, SearchOptions, SearchProvider, SearchResponse,
+        SearchResult as SearchResultType,
+    },
+    utils::{
+        debug,
+        http::{build_url, extract_domain, normalize_text, HttpClient},
+        intern::interned,
+    },
 };
+use scraper::{Html, Selector};
+use serde::Deserialize;
 use std::collections::HashMap;
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 
-#[derive(Debug)]
-pub struct SearxNGProvider {
-    base_url: String,
+/// Cooldown after an instance's first consecutive failure; doubles with each further one
+const BASE_COOLDOWN: Duration = Duration::from_secs(30);
+
+/// Upper bound for the failure cooldown
+const MAX_COOLDOWN: Duration = Duration::from_secs(600);
+
+/// Cooldown after an instance rate-limits us
+const RATE_LIMIT_COOLDOWN: Duration = Duration::from_secs(300);
+
+/// SearXNG result categories
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearxNGCategory {
+    General,
+    News,
+    Images,
+    Science,
 }
 
-impl SearxNGProvider {
-    pub fn new(base_url: &str) -> SearchResult<Self> {
-        if base_url.is_empty() {
+impl fmt::Display for SearxNGCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SearxNGCategory::General => write!(f, "general"),
+            SearxNGCategory::News => write!(f, "news"),
+            SearxNGCategory::Images => write!(f, "images"),
+            SearxNGCategory::Science => write!(f, "science"),
+        }
+    }
+}
+
+/// Restrict results to a recent time window
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearxNGTimeRange {
+    Day,
+    Week,
+    Month,
+    Year,
+}
+
+impl fmt::Display for SearxNGTimeRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SearxNGTimeRange::Day => write!(f, "day"),
+            SearxNGTimeRange::Week => write!(f, "week"),
+            SearxNGTimeRange::Month => write!(f, "month"),
+            SearxNGTimeRange::Year => write!(f, "year"),
+        }
+    }
+}
+
+/// SearXNG JSON API result
+#[derive(Debug, Deserialize)]
+struct SearxNGResult {
+    url: String,
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    content: Option<String>,
+    #[serde(default, rename = "publishedDate")]
+    published_date: Option<String>,
+}
+
+/// SearXNG JSON API response
+#[derive(Debug, Deserialize)]
+struct SearxNGResponse {
+    /// Parsed one by one with [`map_results`]
+    #[serde(default)]
+    results: Vec<serde_json::Value>,
+}
+
+/// SearXNG configuration
+#[derive(Debug, Clone)]
+pub struct SearxNGConfig {
+    /// Base URLs of the instances to rotate among (e.g. `https://searx.example.org`)
+    pub instances: Vec<String>,
+    /// Engines to query (empty uses the instance defaults)
+    pub engines: Vec<String>,
+    /// Categories to search (empty uses the instance defaults)
+    pub categories: Vec<SearxNGCategory>,
+    /// Only return results from this time window
+    pub time_range: Option<SearxNGTimeRange>,
+    /// Try the JSON API before falling back to HTML
+    pub use_json: bool,
+}
+
+impl Default for SearxNGConfig {
+    fn default() -> Self {
+        Self {
+            instances: Vec::new(),
+            engines: Vec::new(),
+            categories: Vec::new(),
+            time_range: None,
+            use_json: true,
+        }
+    }
+}
+
+impl ProviderConfig for SearxNGConfig {
+    fn validate(&self) -> Result<(), SearchError> {
+        if self.instances.is_empty() || self.instances.iter().any(String::is_empty) {
             return Err(SearchError::ConfigError(
                 "SearXNG base URL is required".to_string(),
             ));
         }
+        for instance in &self.instances {
+            url::Url::parse(instance)?;
+        }
+        Ok(())
+    }
+
+    fn base_url(&self) -> &str {
+        self.instances.first().map_or("", String::as_str)
+    }
+}
+
+/// Health snapshot of a configured SearXNG instance
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearxNGInstanceHealth {
+    /// Base URL of the instance
+    pub url: String,
+    /// Number of successful searches
+    pub successes: u64,
+    /// Number of failed searches
+    pub failures: u64,
+    /// Failures since the last success
+    pub consecutive_failures: u32,
+    /// Whether the instance is currently cooling down after failures
+    pub demoted: bool,
+    /// Share of successful searches (1.0 for an instance that hasn't been used yet)
+    pub score: f64,
+}
+
+/// Per-instance bookkeeping
+#[derive(Debug)]
+struct InstanceState {
+    url: String,
+    successes: u64,
+    failures: u64,
+    consecutive_failures: u32,
+    demoted_until: Option<Instant>,
+    /// Set once the instance has rejected a JSON request, so later searches go straight to HTML
+    json_unavailable: bool,
+}
+
+impl InstanceState {
+    fn new(url: String) -> Self {
+        Self {
+            url,
+            successes: 0,
+            failures: 0,
+            consecutive_failures: 0,
+            demoted_until: None,
+            json_unavailable: false,
+        }
+    }
+
+    fn is_demoted(&self, now: Instant) -> bool {
+        self.demoted_until.is_some_and(|until| until > now)
+    }
+}
+
+/// SearXNG search provider
+#[derive(Debug)]
+pub struct SearxNGProvider {
+    config: SearxNGConfig,
+    http_client: HttpClient,
+    instances: Mutex<Vec<InstanceState>>,
+    next_instance: AtomicUsize,
+}
+
+impl SearxNGProvider {
+    /// Create a new SearXNG provider for the instance at `base_url`
+    pub fn new(base_url: &str) -> SearchResult<Self> {
+        Self::with_instances([base_url])
+    }
+
+    /// Create a provider that rotates among several instances
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use websearch::providers::SearxNGProvider;
+    ///
+    /// let provider = SearxNGProvider::with_instances([
+    ///     "https://searx.example.org",
+    ///     "https://search.example.net",
+    /// ])?;
+    /// # Ok::<(), websearch::SearchError>(())
+    /// ```
+    pub fn with_instances<I, S>(instances: I) -> SearchResult<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self::with_config(SearxNGConfig {
+            instances: instances.into_iter().map(Into::into).collect(),
+            ..Default::default()
+        })
+    }
+
+    /// Create a new SearXNG provider with custom configuration
+    pub fn with_config(config: SearxNGConfig) -> SearchResult<Self> {
+        config.validate()?;
+
+        let instances = config
+            .instances
+            .iter()
+            .cloned()
+            .map(InstanceState::new)
+            .collect();
 
         Ok(Self {
-            base_url: base_url.to_string(),
+            config,
+            http_client: HttpClient::new(),
+            instances: Mutex::new(instances),
+            next_instance: AtomicUsize::new(0),
+        })
+    }
+
+    /// Current health of every configured instance
+    pub fn instance_health(&self) -> Vec<SearxNGInstanceHealth> {
+        let now = Instant::now();
+        self.instances
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|state| {
+                let total = state.successes + state.failures;
+                SearxNGInstanceHealth {
+                    url: state.url.clone(),
+                    successes: state.successes,
+                    failures: state.failures,
+                    consecutive_failures: state.consecutive_failures,
+                    demoted: state.is_demoted(now),
+                    score: if total == 0 {
+                        1.0
+                    } else {
+                        state.successes as f64 / total as f64
+                    },
+                }
+            })
+            .collect()
+    }
+
+    /// Query only these engines (e.g. `["google", "wikipedia"]`)
+    pub fn with_engines<I, S>(mut self, engines: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.config.engines = engines.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Search these categories
+    pub fn with_categories(mut self, categories: Vec<SearxNGCategory>) -> Self {
+        self.config.categories = categories;
+        self
+    }
+
+    /// Only return results from the given time window
+    pub fn with_time_range(mut self, time_range: SearxNGTimeRange) -> Self {
+        self.config.time_range = Some(time_range);
+        self
+    }
+
+    /// Enable or disable the JSON API (HTML parsing is used when disabled)
+    pub fn with_json(mut self, use_json: bool) -> Self {
+        self.config.use_json = use_json;
+        self
+    }
+
+    forward_http_headers!();
+
+    /// Build the search URL with parameters
+    fn build_search_url(
+        &self,
+        instance: &str,
+        options: &SearchOptions,
+        json: bool,
+    ) -> SearchResult<String> {
+        let mut params = HashMap::new();
+        params.insert("q".to_string(), options.query_with_file_type());
+
+        if json {
+            params.insert("format".to_string(), "json".to_string());
+        }
+
+        if !self.config.engines.is_empty() {
+            params.insert("engines".to_string(), self.config.engines.join(","));
+        }
+
+        if !self.config.categories.is_empty() {
+            let categories: Vec<String> = self
+                .config
+                .categories
+                .iter()
+                .map(ToString::to_string)
+                .collect();
+            params.insert("categories".to_string(), categories.join(","));
+        }
+
+        if let Some(time_range) = self.config.time_range {
+            params.insert("time_range".to_string(), time_range.to_string());
+        }
+
+        if let Some(language) = locale::searxng_language(options.language.as_deref())? {
+            params.insert("language".to_string(), language);
+        }
+
+        if let Some(page) = options.page {
+            params.insert("pageno".to_string(), page.to_string());
+        }
+
+        if let Some(safe_search) = &options.safe_search {
+            let level = match safe_search {
+                SafeSearch::Off => "0",
+                SafeSearch::Moderate => "1",
+                SafeSearch::Strict => "2",
+            };
+            params.insert("safesearch".to_string(), level.to_string());
+        }
+
+        build_url(&search_endpoint(instance), params)
+    }
+
+    /// Instance indices in the order they should be tried: healthy instances in
+    /// round-robin order, then demoted ones by how soon their cooldown ends
+    fn instance_order(&self) -> Vec<usize> {
+        let instances = self.instances.lock().unwrap();
+        let count = instances.len();
+        let start = self.next_instance.fetch_add(1, Ordering::Relaxed) % count;
+        let now = Instant::now();
+
+        let (mut order, mut demoted): (Vec<usize>, Vec<usize>) = (0..count)
+            .map(|offset| (start + offset) % count)
+            .partition(|&index| !instances[index].is_demoted(now));
+        demoted.sort_by_key(|&index| instances[index].demoted_until);
+        order.extend(demoted);
+        order
+    }
+
+    fn record_success(&self, index: usize) {
+        let mut instances = self.instances.lock().unwrap();
+        let state = &mut instances[index];
+        state.successes += 1;
+        state.consecutive_failures = 0;
+        state.demoted_until = None;
+    }
+
+    fn record_failure(&self, index: usize, error: &SearchError) {
+        let mut instances = self.instances.lock().unwrap();
+        let state = &mut instances[index];
+        state.failures += 1;
+        state.consecutive_failures += 1;
+
+        let rate_limited = matches!(
+            error,
+            SearchError::RateLimit(_)
+                | SearchError::HttpError {
+                    status_code: Some(429),
+                    ..
+                }
+        );
+        let cooldown = if rate_limited {
+            RATE_LIMIT_COOLDOWN
+        } else {
+            let exponent = state.consecutive_failures.saturating_sub(1).min(10);
+            (BASE_COOLDOWN * 2u32.pow(exponent)).min(MAX_COOLDOWN)
+        };
+        state.demoted_until = Some(Instant::now() + cooldown);
+    }
+
+    /// Search a single instance, falling back from JSON to HTML if the instance rejects JSON
+    async fn search_instance(
+        &self,
+        index: usize,
+        options: &SearchOptions,
+    ) -> SearchResult<SearchResponse> {
+        let (instance, json_unavailable) = {
+            let instances = self.instances.lock().unwrap();
+            (
+                instances[index].url.clone(),
+                instances[index].json_unavailable,
+            )
+        };
+
+        if !self.config.use_json || json_unavailable {
+            return self.search_html(&instance, options).await;
+        }
+
+        match self.search_json(&instance, options).await {
+            Err(error) if json_not_supported(&error) => {
+                debug::log(
+                    &options.debug,
+                    &format!("SearXNG JSON API unavailable on {instance}, falling back to HTML"),
+                    &error.to_string(),
+                );
+                self.instances.lock().unwrap()[index].json_unavailable = true;
+                self.search_html(&instance, options).await
+            }
+            result => result,
+        }
+    }
+
+    /// Search through the JSON API
+    async fn search_json(
+        &self,
+        instance: &str,
+        options: &SearchOptions,
+    ) -> SearchResult<SearchResponse> {
+        let url = self.build_search_url(instance, options, true)?;
+        // Decoded separately so an HTML page served in place of JSON surfaces as a ParseError
+        let body = self.http_client.get_bytes(&url).await?;
+        let response: SearxNGResponse = serde_json::from_slice(&body)?;
+
+        let (results, warnings) =
+            map_results("searxng", response.results, |result: SearxNGResult, raw| {
+                Ok(SearchResultType {
+                    domain: extract_domain(&result.url),
+                    title: normalize_text(&result.title),
+                    snippet: result
+                        .content
+                        .as_deref()
+                        .map(normalize_text)
+                        .filter(|snippet| !snippet.is_empty()),
+                    url: result.url,
+                    published_date: result.published_date,
+                    provider: Some(interned!("searxng")),
+                    // The original result, including fields this SDK doesn't map
+                    raw: Some(raw),
+                    ..Default::default()
+                })
+            });
+        Ok(SearchResponse {
+            results,
+            warnings,
+            ..Default::default()
         })
     }
+
+    /// Search by scraping the HTML results page
+    async fn search_html(
+        &self,
+        instance: &str,
+        options: &SearchOptions,
+    ) -> SearchResult<SearchResponse> {
+        let url = self.build_search_url(instance, options, false)?;
+        let html = self.http_client.get_text(&url).await?;
+        parse_html_results(&html)
+    }
+}
+
+/// The `/search` endpoint of an instance
+fn search_endpoint(instance: &str) -> String {
+    let base = instance.trim_end_matches('/');
+    if base.ends_with("/search") {
+        base.to_string()
+    } else {
+        format!("{base}/search")
+    }
+}
+
+/// Whether a failed JSON request means the instance doesn't serve JSON (as opposed to
+/// a transient failure that HTML would hit too)
+fn json_not_supported(error: &SearchError) -> bool {
+    matches!(
+        error,
+        SearchError::ParseError(_)
+            | SearchError::AuthenticationError(_)
+            | SearchError::HttpError {
+                status_code: Some(400 | 403 | 404 | 406),
+                ..
+            }
+    )
+}
+
+/// Parse results from SearXNG's HTML page (`simple` theme, with the legacy `oscar`
+/// markup as a fallback)
+fn parse_html_results(html: &str) -> SearchResult<SearchResponse> {
+    let parse = |selector: &str| {
+        Selector::parse(selector)
+            .map_err(|_| SearchError::ParseError(format!("Invalid CSS selector: {selector}")))
+    };
+    let result_selector = parse("article.result, div.result")?;
+    let link_selector = parse("h3 a, h4 a")?;
+    let snippet_selector = parse("p.content, p.result-content")?;
+    let date_selector = parse("time")?;
+
+    let document = Html::parse_document(html);
+    let items = document.select(&result_selector).map(|element| {
+        let link = element
+            .select(&link_selector)
+            .next()
+            .ok_or_else(|| "result has no title link".to_string())?;
+        let url = link
+            .value()
+            .attr("href")
+            .ok_or_else(|| "result link has no href".to_string())?;
+
+        let snippet = element
+            .select(&snippet_selector)
+            .next()
+            .map(|snippet| normalize_text(&snippet.text().collect::<String>()))
+            .filter(|snippet| !snippet.is_empty());
+
+        let published_date = element.select(&date_selector).next().map(|time| {
+            time.value()
+                .attr("datetime")
+                .map(str::to_string)
+                .unwrap_or_else(|| normalize_text(&time.text().collect::<String>()))
+        });
+
+        Ok(SearchResultType {
+            url: url.to_string(),
+            title: normalize_text(&link.text().collect::<String>()),
+            snippet,
+            domain: extract_domain(url),
+            published_date,
+            provider: Some(interned!("searxng")),
+            ..Default::default()
+        })
+    });
+
+    let (results, warnings) = collect_results("searxng", items);
+    Ok(SearchResponse {
+        results,
+        warnings,
+        ..Default::default()
+    })
 }
 
 #[async_trait::async_trait]
@@ -31,15 +567,262 @@ impl SearchProvider for SearxNGProvider {
         "searxng"
     }
 
-    async fn search(&self, _options: &SearchOptions) -> SearchResult<Vec<SearchResultType>> {
-        Err(SearchError::ProviderError(
-            "SearXNG provider implementation coming soon".to_string(),
-        ))
+    fn supports_pagination(&self) -> bool {
+        true
+    }
+
+    async fn search(&self, options: &SearchOptions) -> SearchResult<Vec<SearchResultType>> {
+        Ok(self.search_response(options).await?.results)
+    }
+
+    async fn search_response(&self, options: &SearchOptions) -> SearchResult<SearchResponse> {
+        debug::log_request(
+            &options.debug,
+            "SearXNG Search request",
+            &format!("query: {}", options.query),
+        );
+
+        let mut last_error = None;
+
+        for index in self.instance_order() {
+            match self.search_instance(index, options).await {
+                Ok(mut response) => {
+                    self.record_success(index);
+
+                    if let Some(max_results) = options.max_results {
+                        response.results.truncate(max_results as usize);
+                    }
+
+                    debug::log_response(
+                        &options.debug,
+                        &format!("SearXNG returned {} results", response.results.len()),
+                    );
+
+                    return Ok(response);
+                }
+                Err(error) => {
+                    debug::log(
+                        &options.debug,
+                        "SearXNG instance failed, trying next instance",
+                        &error.to_string(),
+                    );
+                    self.record_failure(index, &error);
+                    last_error = Some(error);
+                }
+            }
+        }
+
+        Err(last_error.expect("SearXNG provider has at least one instance"))
+    }
+
+    /// SearXNG instances are public or self-hosted, without credentials
+    async fn validate_credentials(&self) -> SearchResult<()> {
+        Ok(())
     }
 
     fn config(&self) -> HashMap<String, String> {
         let mut config = HashMap::new();
-        config.insert("base_url".to_string(), self.base_url.clone());
+        config.insert("base_url".to_string(), self.config.base_url().to_string());
+        config.insert("instances".to_string(), self.config.instances.join(","));
+        config.insert("engines".to_string(), self.config.engines.join(","));
+        config.insert(
+            "categories".to_string(),
+            self.config
+                .categories
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(","),
+        );
+        if let Some(time_range) = self.config.time_range {
+            config.insert("time_range".to_string(), time_range.to_string());
+        }
+        config.insert("use_json".to_string(), self.config.use_json.to_string());
         config
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::{
+        matchers::{method, path, query_param},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    fn options(query: &str) -> SearchOptions {
+        SearchOptions {
+            query: query.to_string(),
+            max_results: Some(5),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_new_requires_valid_url() {
+        assert!(matches!(
+            SearxNGProvider::new(""),
+            Err(SearchError::ConfigError(_))
+        ));
+        assert!(SearxNGProvider::new("not a url").is_err());
+        assert!(SearxNGProvider::new("https://searx.example.org").is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_json_search_sends_filters() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/search"))
+            .and(query_param("q", "rust"))
+            .and(query_param("format", "json"))
+            .and(query_param("engines", "wikipedia,github"))
+            .and(query_param("categories", "general,science"))
+            .and(query_param("time_range", "month"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "query": "rust",
+                "results": [{
+                    "url": "https://www.rust-lang.org/",
+                    "title": "Rust Programming Language",
+                    "content": "A language empowering everyone",
+                    "engine": "wikipedia",
+                    "publishedDate": "2024-01-01T00:00:00"
+                }]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let provider = SearxNGProvider::new(&server.uri())
+            .unwrap()
+            .with_engines(["wikipedia", "github"])
+            .with_categories(vec![SearxNGCategory::General, SearxNGCategory::Science])
+            .with_time_range(SearxNGTimeRange::Month);
+
+        let results = provider.search(&options("rust")).await.unwrap();
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].title, "Rust Programming Language");
+        assert_eq!(results[0].domain.as_deref(), Some("www.rust-lang.org"));
+        assert_eq!(
+            results[0].published_date.as_deref(),
+            Some("2024-01-01T00:00:00")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_falls_back_to_html_when_json_is_disabled() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/search"))
+            .and(query_param("format", "json"))
+            .respond_with(ResponseTemplate::new(403))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/search"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(
+                r#"<html><body>
+                <article class="result result-default">
+                    <h3><a href="https://example.com/one">First  result</a></h3>
+                    <p class="content">First snippet</p>
+                </article>
+                <article class="result">
+                    <h3><a href="https://example.org/two">Second result</a></h3>
+                </article>
+                </body></html>"#,
+                "text/html",
+            ))
+            .mount(&server)
+            .await;
+
+        let provider = SearxNGProvider::new(&format!("{}/", server.uri())).unwrap();
+
+        let results = provider.search(&options("rust")).await.unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].title, "First result");
+        assert_eq!(results[0].snippet.as_deref(), Some("First snippet"));
+        assert_eq!(results[1].url, "https://example.org/two");
+        assert!(results[1].snippet.is_none());
+
+        // The JSON API is not retried once the instance has rejected it
+        provider.search(&options("rust")).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_rotates_among_healthy_instances() {
+        let first = MockServer::start().await;
+        let second = MockServer::start().await;
+        for server in [&first, &second] {
+            Mock::given(method("GET"))
+                .respond_with(
+                    ResponseTemplate::new(200).set_body_json(serde_json::json!({ "results": [] })),
+                )
+                .expect(1)
+                .mount(server)
+                .await;
+        }
+
+        let provider = SearxNGProvider::with_instances([first.uri(), second.uri()]).unwrap();
+        provider.search(&options("rust")).await.unwrap();
+        provider.search(&options("rust")).await.unwrap();
+
+        assert!(provider
+            .instance_health()
+            .iter()
+            .all(|health| health.successes == 1 && !health.demoted));
+    }
+
+    #[tokio::test]
+    async fn test_failing_instance_is_demoted() {
+        let broken = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(429))
+            .expect(1)
+            .mount(&broken)
+            .await;
+        let healthy = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "results": [{ "url": "https://example.com/", "title": "Example" }]
+            })))
+            .expect(3)
+            .mount(&healthy)
+            .await;
+
+        let provider = SearxNGProvider::with_instances([broken.uri(), healthy.uri()]).unwrap();
+
+        // The broken instance is tried first, fails over to the healthy one, and is
+        // then skipped while it cools down
+        for _ in 0..3 {
+            let results = provider.search(&options("rust")).await.unwrap();
+            assert_eq!(results[0].title, "Example");
+        }
+
+        let health = provider.instance_health();
+        assert!(health[0].demoted);
+        assert_eq!(health[0].failures, 1);
+        assert_eq!(health[0].score, 0.0);
+        assert!(!health[1].demoted);
+        assert_eq!(health[1].successes, 3);
+    }
+
+    #[tokio::test]
+    async fn test_server_errors_are_not_masked() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(500))
+            .mount(&server)
+            .await;
+
+        let provider = SearxNGProvider::new(&server.uri()).unwrap();
+        let error = provider.search(&options("rust")).await.unwrap_err();
+        assert!(matches!(
+            error,
+            SearchError::HttpError {
+                status_code: Some(500),
+                ..
+            }
+        ));
+    }
+}
@@ -1,24 +1,195 @@
 //! SerpAPI provider
+//!
+//! SerpAPI fronts many search engines behind one API. Each engine takes slightly
+//! different query parameters and returns its own result shape, so [`SerpApiEngine`]
+//! selects both the request mapping and the response parser.
 
 use crate::{
     error::{SearchError, SearchResult},
-    types::{SearchOptions, SearchProvider, SearchResult as SearchResultType},
+    freshness, highlight,
+    locale::{self, Locale},
+    providers::{collect_results, forward_http_headers, parse_clock, parse_count},
+    types::{
+        FaqItem, Freshness, GeoLocation, HealthStatus, ParseWarning, PlaceResult, ProviderHealth,
+        ResultKind, RichResult, SafeSearch, SearchMode, SearchOptions, SearchProvider,
+        SearchResponse, SearchResult as SearchResultType, VideoResult,
+    },
+    utils::{
+        http::{build_url, HttpClient},
+        intern::{intern, interned},
+    },
 };
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::sync::Arc;
 
+/// Search engines supported by SerpAPI
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SerpApiEngine {
+    Google,
+    Bing,
+    Baidu,
+    Yahoo,
+    DuckDuckGo,
+    GoogleNews,
+    GoogleScholar,
+    /// Used for [`SearchMode::Video`] searches whatever the configured engine
+    YouTube,
+    /// Used for [`SearchMode::Places`] searches whatever the configured engine
+    GoogleMaps,
+    /// Any other SerpAPI engine; requests and responses are treated like Google's
+    Other(String),
+}
+
+impl SerpApiEngine {
+    /// The engine's identifier in SerpAPI's `engine` parameter
+    pub fn as_str(&self) -> &str {
+        match self {
+            SerpApiEngine::Google => "google",
+            SerpApiEngine::Bing => "bing",
+            SerpApiEngine::Baidu => "baidu",
+            SerpApiEngine::Yahoo => "yahoo",
+            SerpApiEngine::DuckDuckGo => "duckduckgo",
+            SerpApiEngine::GoogleNews => "google_news",
+            SerpApiEngine::GoogleScholar => "google_scholar",
+            SerpApiEngine::YouTube => "youtube",
+            SerpApiEngine::GoogleMaps => "google_maps",
+            SerpApiEngine::Other(engine) => engine,
+        }
+    }
+
+    /// Whether the engine takes `tbs=qdr:` to limit results by recency
+    fn takes_freshness(&self) -> bool {
+        matches!(self, SerpApiEngine::Google | SerpApiEngine::Other(_))
+    }
+}
+
+impl fmt::Display for SerpApiEngine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl From<&str> for SerpApiEngine {
+    fn from(engine: &str) -> Self {
+        match engine {
+            "google" => SerpApiEngine::Google,
+            "bing" => SerpApiEngine::Bing,
+            "baidu" => SerpApiEngine::Baidu,
+            "yahoo" => SerpApiEngine::Yahoo,
+            "duckduckgo" => SerpApiEngine::DuckDuckGo,
+            "google_news" => SerpApiEngine::GoogleNews,
+            "google_scholar" => SerpApiEngine::GoogleScholar,
+            "youtube" => SerpApiEngine::YouTube,
+            "google_maps" => SerpApiEngine::GoogleMaps,
+            other => SerpApiEngine::Other(other.to_string()),
+        }
+    }
+}
+
+/// Organic result as returned by the web engines (Google, Bing, Baidu, Yahoo,
+/// DuckDuckGo) and Google Scholar; ads and inline videos share its shape
 #[derive(Debug, Deserialize, Serialize)]
 struct SerpApiSearchResult {
     position: Option<u32>,
     title: String,
-    link: String,
+    /// Missing for Google Scholar citations without a landing page
+    link: Option<String>,
     displayed_link: Option<String>,
+    /// Ads call it `description`
+    #[serde(alias = "description")]
     snippet: Option<String>,
     snippet_highlighted_words: Option<Vec<String>>,
     date: Option<String>,
+    favicon: Option<String>,
+    thumbnail: Option<String>,
+    /// Google Scholar only
+    publication_info: Option<SerpApiPublicationInfo>,
+    /// Google Scholar only: PDF/HTML copies of the paper
+    resources: Option<Vec<SerpApiResource>>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct SerpApiPublicationInfo {
+    summary: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct SerpApiResource {
+    link: Option<String>,
+}
+
+/// Google News result: either a single article or a cluster of related stories
+#[derive(Debug, Deserialize, Serialize)]
+struct SerpApiNewsResult {
+    title: Option<String>,
+    link: Option<String>,
+    snippet: Option<String>,
+    date: Option<String>,
+    source: Option<SerpApiNewsSource>,
+    highlight: Option<Box<SerpApiNewsResult>>,
+    stories: Option<Vec<SerpApiNewsResult>>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct SerpApiNewsSource {
+    name: Option<String>,
+}
+
+/// YouTube engine result
+#[derive(Debug, Deserialize)]
+struct SerpApiVideoResult {
+    title: String,
+    link: Option<String>,
+    description: Option<String>,
+    published_date: Option<String>,
+    /// Length like `"3:25"`
+    length: Option<String>,
+    views: Option<serde_json::Value>,
+    channel: Option<SerpApiChannel>,
+    thumbnail: Option<SerpApiVideoThumbnail>,
+}
+
+/// Google Maps engine result
+#[derive(Debug, Deserialize)]
+struct SerpApiPlaceResult {
+    title: String,
+    place_id: Option<String>,
+    website: Option<String>,
+    description: Option<String>,
+    address: Option<String>,
+    gps_coordinates: Option<SerpApiCoordinates>,
+    rating: Option<f64>,
+    reviews: Option<serde_json::Value>,
+    /// Like `"Open ⋅ Closes 6 PM"`
+    hours: Option<String>,
+    open_state: Option<String>,
+    phone: Option<String>,
+    #[serde(rename = "type")]
+    category: Option<String>,
+    thumbnail: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SerpApiCoordinates {
+    latitude: Option<f64>,
+    longitude: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SerpApiChannel {
+    name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SerpApiVideoThumbnail {
+    #[serde(rename = "static")]
+    still: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
+#[allow(dead_code)]
 struct SerpApiSearchMetadata {
     id: String,
     status: String,
@@ -28,6 +199,7 @@ struct SerpApiSearchMetadata {
 }
 
 #[derive(Debug, Deserialize)]
+#[allow(dead_code)]
 struct SerpApiSearchInformation {
     total_results: Option<u64>,
     time_taken_displayed: Option<f32>,
@@ -35,18 +207,37 @@ struct SerpApiSearchInformation {
 }
 
 #[derive(Debug, Deserialize)]
+#[allow(dead_code)]
 struct SerpApiResponse {
     search_metadata: Option<SerpApiSearchMetadata>,
     search_information: Option<SerpApiSearchInformation>,
-    organic_results: Option<Vec<SerpApiSearchResult>>,
+    /// Parsed one by one, as [`SerpApiSearchResult`]s
+    organic_results: Option<Vec<serde_json::Value>>,
+    /// Parsed one by one, as [`SerpApiNewsResult`]s
+    news_results: Option<Vec<serde_json::Value>>,
+    /// Parsed one by one, as [`SerpApiVideoResult`]s
+    video_results: Option<Vec<serde_json::Value>>,
+    /// Parsed one by one, as [`SerpApiPlaceResult`]s
+    local_results: Option<Vec<serde_json::Value>>,
+    /// Google Maps' single match, when the query names one place
+    place_results: Option<serde_json::Value>,
+    /// Parsed like `organic_results`, as [`ResultKind::Ad`]
+    ads: Option<Vec<serde_json::Value>>,
+    /// Parsed like `organic_results`, as [`ResultKind::Video`]
+    inline_videos: Option<Vec<serde_json::Value>>,
+    answer_box: Option<serde_json::Value>,
+    knowledge_graph: Option<serde_json::Value>,
+    related_questions: Option<Vec<serde_json::Value>>,
+    top_stories: Option<Vec<serde_json::Value>>,
     error: Option<String>,
 }
 
 #[derive(Debug)]
 pub struct SerpApiProvider {
     api_key: String,
-    engine: String,
+    engine: SerpApiEngine,
     base_url: String,
+    http_client: HttpClient,
 }
 
 impl SerpApiProvider {
@@ -59,13 +250,15 @@ impl SerpApiProvider {
 
         Ok(Self {
             api_key: api_key.to_string(),
-            engine: "google".to_string(),
+            engine: SerpApiEngine::Google,
             base_url: "https://serpapi.com/search.json".to_string(),
+            http_client: HttpClient::new(),
         })
     }
 
-    pub fn with_engine(mut self, engine: &str) -> Self {
-        self.engine = engine.to_string();
+    /// Select the SerpAPI engine, e.g. `SerpApiEngine::Bing` or `"google_news"`
+    pub fn with_engine(mut self, engine: impl Into<SerpApiEngine>) -> Self {
+        self.engine = engine.into();
         self
     }
 
@@ -73,130 +266,1270 @@ impl SerpApiProvider {
         self.base_url = base_url.to_string();
         self
     }
-}
 
-#[async_trait::async_trait]
-impl SearchProvider for SerpApiProvider {
-    fn name(&self) -> &str {
-        "serpapi"
-    }
+    forward_http_headers!();
 
-    async fn search(&self, options: &SearchOptions) -> SearchResult<Vec<SearchResultType>> {
-        let client = reqwest::Client::new();
+    /// The engine a search in `mode` goes to: YouTube for videos, Google Maps for places,
+    /// else the configured one
+    fn engine_for(&self, mode: SearchMode) -> &SerpApiEngine {
+        match mode {
+            SearchMode::Web => &self.engine,
+            SearchMode::Video => &SerpApiEngine::YouTube,
+            SearchMode::Places => &SerpApiEngine::GoogleMaps,
+        }
+    }
 
-        // Build query parameters with owned strings
+    /// Build query parameters using the selected engine's parameter names
+    fn build_params(&self, options: &SearchOptions) -> SearchResult<HashMap<String, String>> {
         let mut params = HashMap::new();
-        params.insert("engine".to_string(), self.engine.clone());
+        let locale = Locale::from_options(options.language.as_deref(), options.region.as_deref())?;
+        let locale = locale.as_ref();
+        let engine = self.engine_for(options.mode);
+        params.insert("engine".to_string(), engine.as_str().to_string());
         params.insert("api_key".to_string(), self.api_key.clone());
-        params.insert("q".to_string(), options.query.clone());
-        params.insert(
-            "num".to_string(),
-            options.max_results.unwrap_or(10).to_string(),
-        );
 
-        // Add pagination if page > 1
-        if let Some(page) = options.page {
-            if page > 1 {
-                let max_results = options.max_results.unwrap_or(10);
-                let start = (page - 1) * max_results + 1;
-                params.insert("start".to_string(), start.to_string());
+        let max_results = options.max_results.unwrap_or(10);
+        // Zero-based offset of the first requested result
+        let offset = options
+            .page
+            .filter(|page| *page > 1)
+            .map(|page| (page - 1) * max_results);
+
+        match engine {
+            SerpApiEngine::Bing => {
+                params.insert("q".to_string(), options.query_with_file_type());
+                params.insert("count".to_string(), max_results.to_string());
+                if let Some(offset) = offset {
+                    params.insert("first".to_string(), (offset + 1).to_string());
+                }
+                if let Some(region) = locale.and_then(|l| l.region.as_deref()) {
+                    params.insert("cc".to_string(), region.to_lowercase());
+                }
+                if let Some(safe_search) = &options.safe_search {
+                    let level = match safe_search {
+                        SafeSearch::Off => "Off",
+                        SafeSearch::Moderate => "Moderate",
+                        SafeSearch::Strict => "Strict",
+                    };
+                    params.insert("safeSearch".to_string(), level.to_string());
+                }
+                match &options.location {
+                    Some(GeoLocation::Name(name)) => {
+                        params.insert("location".to_string(), name.clone());
+                    }
+                    Some(GeoLocation::Coordinates {
+                        latitude,
+                        longitude,
+                    }) => {
+                        params.insert("lat".to_string(), latitude.to_string());
+                        params.insert("lon".to_string(), longitude.to_string());
+                    }
+                    None => {}
+                }
+            }
+            SerpApiEngine::Baidu => {
+                params.insert("q".to_string(), options.query.clone());
+                params.insert("rn".to_string(), max_results.min(50).to_string());
+                if let Some(offset) = offset {
+                    params.insert("pn".to_string(), offset.to_string());
+                }
+            }
+            SerpApiEngine::Yahoo => {
+                params.insert("p".to_string(), options.query.clone());
+                if let Some(offset) = offset {
+                    params.insert("b".to_string(), (offset + 1).to_string());
+                }
+                if let Some(language) = locale.and_then(|l| l.language.as_deref()) {
+                    params.insert("vl".to_string(), format!("lang_{language}"));
+                }
+                if let Some(region) = locale.and_then(|l| l.region.as_deref()) {
+                    params.insert("vc".to_string(), region.to_lowercase());
+                }
+            }
+            SerpApiEngine::DuckDuckGo => {
+                params.insert("q".to_string(), options.query.clone());
+                if let Some(offset) = offset {
+                    params.insert("start".to_string(), offset.to_string());
+                }
+                if let Some(kl) =
+                    locale::duckduckgo_kl(options.language.as_deref(), options.region.as_deref())?
+                {
+                    params.insert("kl".to_string(), kl);
+                }
+                if let Some(safe_search) = &options.safe_search {
+                    let level = match safe_search {
+                        SafeSearch::Off => "-2",
+                        SafeSearch::Moderate => "-1",
+                        SafeSearch::Strict => "1",
+                    };
+                    params.insert("safe".to_string(), level.to_string());
+                }
+            }
+            SerpApiEngine::GoogleNews => {
+                params.insert("q".to_string(), options.query.clone());
+                if let Some(hl) = locale.and_then(Locale::google_hl) {
+                    params.insert("hl".to_string(), hl);
+                }
+                if let Some(gl) = locale.and_then(Locale::google_gl) {
+                    params.insert("gl".to_string(), gl);
+                }
+            }
+            // Pages are requested with tokens from the previous response, so only the first
+            // is available
+            SerpApiEngine::YouTube => {
+                params.insert("search_query".to_string(), options.query.clone());
+                if let Some(hl) = locale.and_then(Locale::google_hl) {
+                    params.insert("hl".to_string(), hl);
+                }
+                if let Some(gl) = locale.and_then(Locale::google_gl) {
+                    params.insert("gl".to_string(), gl);
+                }
+            }
+            // Google Maps returns 20 places per page
+            SerpApiEngine::GoogleMaps => {
+                params.insert("q".to_string(), options.query.clone());
+                params.insert("type".to_string(), "search".to_string());
+                // Maps only takes coordinates, as `@latitude,longitude,zoom`
+                if let Some(GeoLocation::Coordinates {
+                    latitude,
+                    longitude,
+                }) = &options.location
+                {
+                    params.insert("ll".to_string(), format!("@{latitude},{longitude},14z"));
+                }
+                if let Some(page) = options.page.filter(|page| *page > 1) {
+                    params.insert("start".to_string(), ((page - 1) * 20).to_string());
+                }
+                if let Some(hl) = locale.and_then(Locale::google_hl) {
+                    params.insert("hl".to_string(), hl);
+                }
+                if let Some(gl) = locale.and_then(Locale::google_gl) {
+                    params.insert("gl".to_string(), gl);
+                }
+            }
+            SerpApiEngine::GoogleScholar => {
+                params.insert("q".to_string(), options.query.clone());
+                params.insert("num".to_string(), max_results.min(20).to_string());
+                if let Some(offset) = offset {
+                    params.insert("start".to_string(), offset.to_string());
+                }
+                if let Some(hl) = locale.and_then(Locale::google_hl) {
+                    params.insert("hl".to_string(), hl);
+                }
+            }
+            SerpApiEngine::Google | SerpApiEngine::Other(_) => {
+                params.insert("q".to_string(), options.query_with_file_type());
+                params.insert("num".to_string(), max_results.to_string());
+                if let Some(offset) = offset {
+                    params.insert("start".to_string(), (offset + 1).to_string());
+                }
+                if let Some(hl) = locale.and_then(Locale::google_hl) {
+                    params.insert("hl".to_string(), hl);
+                }
+                if let Some(gl) = locale.and_then(Locale::google_gl) {
+                    params.insert("gl".to_string(), gl);
+                }
+                if let Some(safe_search) = &options.safe_search {
+                    params.insert("safe".to_string(), safe_search.to_string());
+                }
+                if let Some(freshness) = options.freshness {
+                    let period = match freshness {
+                        Freshness::Day => "d",
+                        Freshness::Week => "w",
+                        Freshness::Month => "m",
+                        Freshness::Year => "y",
+                    };
+                    params.insert("tbs".to_string(), format!("qdr:{period}"));
+                }
+                match &options.location {
+                    Some(GeoLocation::Name(name)) => {
+                        params.insert("location".to_string(), name.clone());
+                    }
+                    Some(GeoLocation::Coordinates {
+                        latitude,
+                        longitude,
+                    }) => {
+                        params.insert(
+                            "uule".to_string(),
+                            locale::google_uule(*latitude, *longitude),
+                        );
+                    }
+                    None => {}
+                }
             }
         }
 
-        // Add language if specified
-        if let Some(ref language) = options.language {
-            params.insert("hl".to_string(), language.clone());
+        Ok(params)
+    }
+
+    /// Convert a parsed response into results plus any rich results
+    fn convert_response(&self, mut response: SerpApiResponse, mode: SearchMode) -> SearchResponse {
+        let rich_results = extract_rich_results(&mut response);
+        let (results, warnings) = self.convert_results(response, mode);
+        SearchResponse {
+            results,
+            rich_results,
+            warnings,
+            ..Default::default()
         }
+    }
 
-        // Add region if specified
-        if let Some(ref region) = options.region {
-            params.insert("gl".to_string(), region.clone());
+    /// Convert a parsed response using the selected engine's result shape, skipping
+    /// malformed results with a warning
+    ///
+    /// Google News story clusters are expanded first, so warnings count their articles.
+    fn convert_results(
+        &self,
+        response: SerpApiResponse,
+        mode: SearchMode,
+    ) -> (Vec<SearchResultType>, Vec<ParseWarning>) {
+        match self.engine_for(mode) {
+            SerpApiEngine::GoogleNews => {
+                let articles = response
+                    .news_results
+                    .unwrap_or_default()
+                    .into_iter()
+                    .flat_map(|item| match SerpApiNewsResult::deserialize(item) {
+                        Ok(result) => flatten_news_result(result)
+                            .into_iter()
+                            .map(convert_news_result)
+                            .collect(),
+                        Err(e) => vec![Err(e.to_string())],
+                    });
+                collect_results("serpapi", articles)
+            }
+            SerpApiEngine::YouTube => {
+                let videos = response
+                    .video_results
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|raw| {
+                        let result =
+                            SerpApiVideoResult::deserialize(&raw).map_err(|e| e.to_string())?;
+                        convert_video_result(result, raw)
+                    });
+                collect_results("serpapi", videos)
+            }
+            SerpApiEngine::GoogleMaps => {
+                let places = response
+                    .local_results
+                    .unwrap_or_default()
+                    .into_iter()
+                    .chain(response.place_results)
+                    .map(|raw| {
+                        let result =
+                            SerpApiPlaceResult::deserialize(&raw).map_err(|e| e.to_string())?;
+                        convert_place_result(result, raw)
+                    });
+                collect_results("serpapi", places)
+            }
+            _ => {
+                // Organic results keep their ranks; videos and ads follow rather than
+                // pushing them down
+                let tagged = |items: Option<Vec<serde_json::Value>>, kind: ResultKind| {
+                    items.unwrap_or_default().into_iter().map(move |item| {
+                        let result =
+                            SerpApiSearchResult::deserialize(item).map_err(|e| e.to_string())?;
+                        convert_organic_result(result)
+                            .map(|result| SearchResultType { kind, ..result })
+                    })
+                };
+                let items = tagged(response.organic_results, ResultKind::Organic)
+                    .chain(tagged(response.inline_videos, ResultKind::Video))
+                    .chain(tagged(response.ads, ResultKind::Ad));
+                collect_results("serpapi", items)
+            }
         }
+    }
+}
 
-        // Add safe search if specified
-        if let Some(ref safe_search) = options.safe_search {
-            params.insert("safe".to_string(), safe_search.to_string());
+/// String field of a loosely typed SerpAPI object
+fn str_field(value: &serde_json::Value, key: &str) -> Option<String> {
+    value
+        .get(key)
+        .and_then(|v| v.as_str())
+        .filter(|v| !v.trim().is_empty())
+        .map(|v| v.to_string())
+}
+
+/// Knowledge graph keys that are mapped to dedicated fields or are SerpAPI bookkeeping
+const KNOWLEDGE_GRAPH_SKIP_KEYS: &[&str] = &[
+    "title",
+    "type",
+    "description",
+    "website",
+    "image",
+    "kgmid",
+    "entity_type",
+];
+
+/// Pull answer boxes, knowledge panels, related questions and top stories out of a response
+fn extract_rich_results(response: &mut SerpApiResponse) -> Vec<RichResult> {
+    let mut rich_results = Vec::new();
+
+    if let Some(answer_box) = response.answer_box.take() {
+        rich_results.push(RichResult::AnswerBox {
+            title: str_field(&answer_box, "title"),
+            answer: str_field(&answer_box, "answer").or_else(|| str_field(&answer_box, "result")),
+            snippet: str_field(&answer_box, "snippet"),
+            url: str_field(&answer_box, "link"),
+        });
+    }
+
+    if let Some(graph) = response.knowledge_graph.take() {
+        if let Some(title) = str_field(&graph, "title") {
+            let attributes = graph
+                .as_object()
+                .into_iter()
+                .flatten()
+                .filter(|(key, _)| {
+                    !KNOWLEDGE_GRAPH_SKIP_KEYS.contains(&key.as_str()) && !key.ends_with("link")
+                })
+                .filter_map(|(key, value)| Some((key.clone(), value.as_str()?.to_string())))
+                .collect::<BTreeMap<_, _>>();
+
+            rich_results.push(RichResult::KnowledgeGraph {
+                title,
+                entity_type: str_field(&graph, "type"),
+                description: str_field(&graph, "description"),
+                url: str_field(&graph, "website")
+                    .or_else(|| graph.get("source").and_then(|s| str_field(s, "link"))),
+                image_url: str_field(&graph, "image").or_else(|| {
+                    graph
+                        .get("header_images")
+                        .and_then(|images| images.get(0))
+                        .and_then(|image| str_field(image, "image"))
+                }),
+                attributes,
+            });
         }
+    }
 
-        // Make the request
-        let request = client.get(&self.base_url).query(&params);
+    let items: Vec<FaqItem> = response
+        .related_questions
+        .take()
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|question| {
+            Some(FaqItem {
+                question: str_field(question, "question")?,
+                answer: str_field(question, "snippet").or_else(|| str_field(question, "answer")),
+                url: str_field(question, "link"),
+            })
+        })
+        .collect();
+    if !items.is_empty() {
+        rich_results.push(RichResult::Faq { items });
+    }
 
-        let response = request.send().await.map_err(|e| SearchError::HttpError {
-            message: format!("Failed to send request: {e}"),
-            status_code: None,
-            response_body: None,
-        })?;
+    let stories: Vec<SearchResultType> = response
+        .top_stories
+        .take()
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|story| {
+            let link = str_field(&story, "link")?;
+            Some(SearchResultType {
+                domain: domain_from_link(&link),
+                url: link,
+                title: str_field(&story, "title").unwrap_or_default(),
+                // `source` is a plain name here, unlike Google News results
+                site_name: str_field(&story, "source")
+                    .or_else(|| story.get("source").and_then(|s| str_field(s, "name"))),
+                published_date: str_field(&story, "date"),
+                thumbnail_url: str_field(&story, "thumbnail"),
+                provider: Some(interned!("serpapi")),
+                raw: Some(story),
+                ..Default::default()
+            })
+        })
+        .collect();
+    if !stories.is_empty() {
+        rich_results.push(RichResult::TopStories { stories });
+    }
 
-        let status = response.status();
-        let response_text = response.text().await.map_err(|e| SearchError::HttpError {
-            message: format!("Failed to read response: {e}"),
-            status_code: None,
-            response_body: None,
-        })?;
+    rich_results
+}
+
+/// Extract a domain from a full URL or a displayed link such as
+/// `https://www.example.com › docs` or `www.example.com/docs`
+fn domain_from_link(link: &str) -> Option<Arc<str>> {
+    if let Some(host) = url::Url::parse(link)
+        .ok()
+        .and_then(|url| url.host_str().map(intern))
+    {
+        return Some(host);
+    }
+    let host = link.split_once("://").map_or(link, |(_, rest)| rest);
+    let host = host.split(['/', ' ', '?', '#']).next()?;
+    (!host.is_empty()).then(|| intern(host))
+}
+
+fn convert_organic_result(result: SerpApiSearchResult) -> Result<SearchResultType, String> {
+    // Scholar citations may only link to a PDF/HTML resource
+    let link = result
+        .link
+        .clone()
+        .or_else(|| {
+            result
+                .resources
+                .iter()
+                .flatten()
+                .find_map(|resource| resource.link.clone())
+        })
+        .ok_or_else(|| format!("\"{}\" has no link", result.title))?;
+
+    let domain = domain_from_link(&link)
+        .or_else(|| result.displayed_link.as_deref().and_then(domain_from_link));
+
+    let snippet = result.snippet.clone().or_else(|| {
+        result
+            .publication_info
+            .as_ref()
+            .and_then(|info| info.summary.clone())
+    });
+
+    let highlights = snippet
+        .as_deref()
+        .zip(result.snippet_highlighted_words.as_deref())
+        .map(|(snippet, words)| highlight::find_terms(snippet, words))
+        .unwrap_or_default();
+
+    let raw_value = serde_json::to_value(&result).unwrap_or_default();
+    Ok(SearchResultType {
+        url: link,
+        title: result.title,
+        snippet,
+        highlights,
+        domain,
+        published_date: result.date,
+        thumbnail_url: result.thumbnail,
+        favicon_url: result.favicon,
+        provider: Some(interned!("serpapi")),
+        raw: Some(raw_value),
+        ..Default::default()
+    })
+}
+
+/// Expand a Google News story cluster into its individual articles
+fn flatten_news_result(mut result: SerpApiNewsResult) -> Vec<SerpApiNewsResult> {
+    if result.link.is_some() {
+        return vec![result];
+    }
+
+    let mut articles: Vec<SerpApiNewsResult> =
+        result.highlight.take().map(|h| *h).into_iter().collect();
+    articles.extend(result.stories.take().unwrap_or_default());
+    articles
+}
+
+fn convert_news_result(result: SerpApiNewsResult) -> Result<SearchResultType, String> {
+    let link = result
+        .link
+        .clone()
+        .ok_or_else(|| "news article has no link".to_string())?;
+    let raw_value = serde_json::to_value(&result).unwrap_or_default();
+
+    Ok(SearchResultType {
+        domain: domain_from_link(&link),
+        url: link,
+        title: result.title.unwrap_or_default(),
+        kind: ResultKind::News,
+        snippet: result
+            .snippet
+            .or_else(|| result.source.and_then(|source| source.name)),
+        published_date: result.date,
+        provider: Some(interned!("serpapi")),
+        raw: Some(raw_value),
+        ..Default::default()
+    })
+}
+
+fn convert_video_result(
+    result: SerpApiVideoResult,
+    raw: serde_json::Value,
+) -> Result<SearchResultType, String> {
+    let link = result
+        .link
+        .ok_or_else(|| format!("\"{}\" has no link", result.title))?;
+    let thumbnail_url = result.thumbnail.and_then(|thumbnail| thumbnail.still);
+
+    Ok(SearchResultType {
+        domain: domain_from_link(&link),
+        url: link,
+        title: result.title,
+        snippet: result.description,
+        kind: ResultKind::Video,
+        published_date: result.published_date,
+        thumbnail_url: thumbnail_url.clone(),
+        video: Some(VideoResult {
+            duration: result.length.as_deref().and_then(parse_clock),
+            channel: result.channel.and_then(|channel| channel.name),
+            views: result.views.as_ref().and_then(parse_count),
+            thumbnail_url,
+        }),
+        provider: Some(interned!("serpapi")),
+        raw: Some(raw),
+        ..Default::default()
+    })
+}
+
+/// A Google Maps place links to its website, else to its Maps page
+fn convert_place_result(
+    result: SerpApiPlaceResult,
+    raw: serde_json::Value,
+) -> Result<SearchResultType, String> {
+    let link = result
+        .website
+        .or_else(|| {
+            result
+                .place_id
+                .map(|id| format!("https://www.google.com/maps/place/?q=place_id:{id}"))
+        })
+        .ok_or_else(|| format!("\"{}\" has no link", result.title))?;
+    let coordinates = result.gps_coordinates;
+
+    Ok(SearchResultType {
+        domain: domain_from_link(&link),
+        url: link,
+        title: result.title,
+        snippet: result.description.or_else(|| result.address.clone()),
+        kind: ResultKind::Place,
+        thumbnail_url: result.thumbnail,
+        place: Some(PlaceResult {
+            address: result.address,
+            latitude: coordinates.as_ref().and_then(|c| c.latitude),
+            longitude: coordinates.as_ref().and_then(|c| c.longitude),
+            rating: result.rating,
+            reviews: result.reviews.as_ref().and_then(parse_count),
+            hours: result.hours.or(result.open_state),
+            phone: result.phone,
+            category: result.category,
+        }),
+        provider: Some(interned!("serpapi")),
+        raw: Some(raw),
+        ..Default::default()
+    })
+}
+
+/// Classify the `error` field of a SerpAPI response by what it says
+fn api_error(error: String) -> SearchError {
+    let lowercase = error.to_lowercase();
+    if lowercase.contains("run out of searches") || lowercase.contains("rate limit") {
+        SearchError::RateLimit(error)
+    } else if lowercase.contains("api key") {
+        SearchError::AuthenticationError(error)
+    } else {
+        SearchError::ProviderError(format!("SerpAPI error: {error}"))
+    }
+}
+
+/// Response of SerpAPI's Account API (`/account.json`)
+#[derive(Debug, Deserialize)]
+struct SerpApiAccount {
+    total_searches_left: Option<u64>,
+    error: Option<String>,
+}
+
+impl SerpApiProvider {
+    /// Account API URL next to the configured search endpoint
+    fn account_url(&self) -> SearchResult<String> {
+        let account = url::Url::parse(&self.base_url)?.join("account.json")?;
+        let mut params = HashMap::new();
+        params.insert("api_key".to_string(), self.api_key.clone());
+        build_url(account.as_str(), params)
+    }
+
+    async fn fetch_account(&self) -> SearchResult<SerpApiAccount> {
+        let body = HttpClient::new().get_text(&self.account_url()?).await?;
+        let account: SerpApiAccount = serde_json::from_str(&body)?;
+        match account.error {
+            Some(error) => Err(SearchError::AuthenticationError(error)),
+            None => Ok(account),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl SearchProvider for SerpApiProvider {
+    fn name(&self) -> &str {
+        "serpapi"
+    }
+
+    fn supports_pagination(&self) -> bool {
+        true
+    }
+
+    async fn search(&self, options: &SearchOptions) -> SearchResult<Vec<SearchResultType>> {
+        Ok(self.search_response(options).await?.results)
+    }
+
+    async fn search_response(&self, options: &SearchOptions) -> SearchResult<SearchResponse> {
+        let params = self.build_params(options)?;
+        let url = build_url(&self.base_url, params)?;
+
+        // Make the request; unchanged results are revalidated rather than downloaded again
+        let (status, body) = self
+            .http_client
+            .get_bytes_with_status(&url, HashMap::new())
+            .await?;
 
         if !status.is_success() {
-            return Err(SearchError::ProviderError(format!(
-                "SerpAPI request failed with status {status}: {response_text}"
-            )));
+            let body = String::from_utf8_lossy(&body).into_owned();
+            let detail = serde_json::from_str::<serde_json::Value>(&body)
+                .ok()
+                .and_then(|value| str_field(&value, "error"))
+                .unwrap_or_else(|| body.clone());
+            return Err(SearchError::HttpError {
+                message: format!("SerpAPI request failed with status {status}: {detail}"),
+                status_code: Some(status.as_u16()),
+                response_body: Some(body),
+            });
         }
 
         // Parse JSON response
-        let serp_response: SerpApiResponse = serde_json::from_str(&response_text).map_err(|e| {
+        let serp_response: SerpApiResponse = serde_json::from_slice(&body).map_err(|e| {
             SearchError::ParseError(format!("Failed to parse SerpAPI response: {e}"))
         })?;
 
         // Check for API error
         if let Some(error) = serp_response.error {
-            return Err(SearchError::ProviderError(format!(
-                "SerpAPI error: {error}"
-            )));
-        }
-
-        // Extract results
-        let organic_results = serp_response.organic_results.unwrap_or_default();
-
-        if organic_results.is_empty() {
-            return Ok(vec![]);
-        }
-
-        // Transform to standard format
-        let results = organic_results
-            .into_iter()
-            .map(|result| {
-                // Extract domain from displayed_link or link
-                let domain = result
-                    .displayed_link
-                    .as_ref()
-                    .or(Some(&result.link))
-                    .and_then(|link| {
-                        if let Ok(url) = url::Url::parse(link) {
-                            url.host_str().map(|s| s.to_string())
-                        } else {
-                            link.split('/').next().map(|s| s.to_string())
-                        }
-                    });
+            return Err(api_error(error));
+        }
 
-                let raw_value = serde_json::to_value(&result).unwrap_or_default();
-                SearchResultType {
-                    url: result.link,
-                    title: result.title,
-                    snippet: result.snippet,
-                    domain,
-                    published_date: result.date,
-                    provider: Some("serpapi".to_string()),
-                    raw: Some(raw_value),
-                }
-            })
-            .collect();
+        let mut response = self.convert_response(serp_response, options.mode);
+        // Video and place searches go to an engine without `tbs`, even when the configured
+        // one takes it, so their dates are checked here
+        if let Some(freshness) = options.freshness {
+            if !self.engine_for(options.mode).takes_freshness() {
+                freshness::retain_fresh(&mut response.results, freshness);
+            }
+        }
+        Ok(response)
+    }
+
+    /// Uses the free Account API, which also reports the remaining search credits
+    async fn health_check(&self) -> ProviderHealth {
+        let started = std::time::Instant::now();
+        let account = self.fetch_account().await;
+        let mut health = ProviderHealth::from_result(
+            self.name(),
+            started.elapsed(),
+            account.as_ref().map(|_| ()).map_err(Clone::clone),
+        );
+
+        if let Ok(account) = account {
+            health.remaining_quota = account.total_searches_left;
+            if account.total_searches_left == Some(0) {
+                health.status = HealthStatus::RateLimited;
+                health.message = Some("No searches left on this SerpAPI plan".to_string());
+            }
+        }
+        health
+    }
+
+    /// Uses the free Account API, so no search credit is spent
+    async fn validate_credentials(&self) -> SearchResult<()> {
+        self.fetch_account().await.map(|_| ())
+    }
+
+    fn supports_safe_search(&self) -> bool {
+        true
+    }
 
-        Ok(results)
+    /// Only the Google engine (and others treated like it) take `tbs=qdr:`; video and place
+    /// searches are filtered by date after the search instead
+    fn supports_freshness(&self) -> bool {
+        self.engine.takes_freshness()
+    }
+
+    fn supports_mode(&self, _mode: SearchMode) -> bool {
+        true
     }
 
     fn config(&self) -> HashMap<String, String> {
         let mut config = HashMap::new();
         config.insert("api_key".to_string(), "***".to_string());
-        config.insert("engine".to_string(), self.engine.clone());
+        config.insert("engine".to_string(), self.engine.to_string());
         config.insert("base_url".to_string(), self.base_url.clone());
         config
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn provider(engine: SerpApiEngine) -> SerpApiProvider {
+        SerpApiProvider::new("test-key")
+            .unwrap()
+            .with_engine(engine)
+    }
+
+    fn options() -> SearchOptions {
+        SearchOptions {
+            query: "rust".to_string(),
+            max_results: Some(10),
+            page: Some(2),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_health_check_reports_quota() {
+        use wiremock::{
+            matchers::{method, path, query_param},
+            Mock, MockServer, ResponseTemplate,
+        };
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/account.json"))
+            .and(query_param("api_key", "test-key"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "account_email": "dev@example.com",
+                "plan_searches_left": 80,
+                "total_searches_left": 95
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/account.json"))
+            .and(query_param("api_key", "bad-key"))
+            .respond_with(ResponseTemplate::new(401).set_body_json(serde_json::json!({
+                "error": "Invalid API key."
+            })))
+            .mount(&server)
+            .await;
+
+        let base_url = format!("{}/search.json", server.uri());
+        let health = SerpApiProvider::new("test-key")
+            .unwrap()
+            .with_base_url(&base_url)
+            .health_check()
+            .await;
+        assert_eq!(health.status, HealthStatus::Healthy);
+        assert_eq!(health.remaining_quota, Some(95));
+
+        let health = SerpApiProvider::new("bad-key")
+            .unwrap()
+            .with_base_url(&base_url)
+            .health_check()
+            .await;
+        assert_eq!(health.status, HealthStatus::AuthFailed);
+    }
+
+    #[tokio::test]
+    async fn test_validate_credentials_uses_account_api() {
+        use wiremock::{
+            matchers::{method, path, query_param},
+            Mock, MockServer, ResponseTemplate,
+        };
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/account.json"))
+            .and(query_param("api_key", "bad-key"))
+            .respond_with(ResponseTemplate::new(401).set_body_json(serde_json::json!({
+                "error": "Invalid API key."
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let provider = SerpApiProvider::new("bad-key")
+            .unwrap()
+            .with_base_url(&format!("{}/search.json", server.uri()));
+        let error = provider.validate_credentials().await.unwrap_err();
+        assert_eq!(error.class(), crate::ErrorClass::Misconfigured);
+    }
+
+    #[tokio::test]
+    async fn test_search_errors_keep_their_status() {
+        use wiremock::{
+            matchers::{method, path, query_param},
+            Mock, MockServer, ResponseTemplate,
+        };
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/search.json"))
+            .and(query_param("api_key", "bad-key"))
+            .respond_with(ResponseTemplate::new(401).set_body_json(serde_json::json!({
+                "error": "Invalid API key."
+            })))
+            .mount(&server)
+            .await;
+
+        let error = SerpApiProvider::new("bad-key")
+            .unwrap()
+            .with_base_url(&format!("{}/search.json", server.uri()))
+            .search(&options())
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            SearchError::HttpError {
+                status_code: Some(401),
+                ..
+            }
+        ));
+        assert!(error.to_string().contains("Invalid API key"));
+        assert_eq!(error.class(), crate::ErrorClass::Misconfigured);
+    }
+
+    #[test]
+    fn test_api_error_classification() {
+        assert!(matches!(
+            api_error("Your account has run out of searches.".to_string()),
+            SearchError::RateLimit(_)
+        ));
+        assert!(matches!(
+            api_error("Invalid API key.".to_string()),
+            SearchError::AuthenticationError(_)
+        ));
+        assert!(matches!(
+            api_error("Google hasn't returned any results for this query.".to_string()),
+            SearchError::ProviderError(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_video_search_is_filtered_by_freshness() {
+        use wiremock::{
+            matchers::{method, query_param},
+            Mock, MockServer, ResponseTemplate,
+        };
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(query_param("engine", "youtube"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "video_results": [{
+                    "title": "Rust in 2015",
+                    "link": "https://www.youtube.com/watch?v=old",
+                    "published_date": "2015-05-15"
+                }, {
+                    "title": "Rust today",
+                    "link": "https://www.youtube.com/watch?v=new"
+                }]
+            })))
+            .mount(&server)
+            .await;
+
+        let provider = provider(SerpApiEngine::Google).with_base_url(&server.uri());
+        assert!(provider.supports_freshness());
+        let results = provider
+            .search(&SearchOptions {
+                mode: SearchMode::Video,
+                freshness: Some(Freshness::Week),
+                ..options()
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].url, "https://www.youtube.com/watch?v=new");
+        let requests = server.received_requests().await.unwrap();
+        assert!(!requests[0].url.query().unwrap().contains("tbs="));
+    }
+
+    #[test]
+    fn test_domain_from_link() {
+        for link in [
+            "https://doc.rust-lang.org/book/",
+            "https://doc.rust-lang.org › book",
+            "doc.rust-lang.org/book",
+        ] {
+            assert_eq!(domain_from_link(link).as_deref(), Some("doc.rust-lang.org"));
+        }
+        assert_eq!(domain_from_link("https://"), None);
+    }
+
+    #[test]
+    fn test_engine_from_str() {
+        assert_eq!(SerpApiEngine::from("bing"), SerpApiEngine::Bing);
+        assert_eq!(
+            SerpApiEngine::from("google_scholar"),
+            SerpApiEngine::GoogleScholar
+        );
+        assert_eq!(
+            SerpApiEngine::from("ebay"),
+            SerpApiEngine::Other("ebay".to_string())
+        );
+        assert_eq!(SerpApiEngine::GoogleNews.to_string(), "google_news");
+    }
+
+    #[test]
+    fn test_engine_specific_params() {
+        let google = provider(SerpApiEngine::Google)
+            .build_params(&options())
+            .unwrap();
+        assert_eq!(google["engine"], "google");
+        assert_eq!(google["num"], "10");
+        assert_eq!(google["start"], "11");
+        assert!(!google.contains_key("tbs"));
+        let week = SearchOptions {
+            freshness: Some(Freshness::Week),
+            ..options()
+        };
+        let google = provider(SerpApiEngine::Google).build_params(&week).unwrap();
+        assert_eq!(google["tbs"], "qdr:w");
+
+        let bing = provider(SerpApiEngine::Bing)
+            .build_params(&options())
+            .unwrap();
+        assert_eq!(bing["count"], "10");
+        assert_eq!(bing["first"], "11");
+
+        let baidu = provider(SerpApiEngine::Baidu)
+            .build_params(&options())
+            .unwrap();
+        assert_eq!(baidu["rn"], "10");
+        assert_eq!(baidu["pn"], "10");
+
+        let yahoo = provider(SerpApiEngine::Yahoo)
+            .build_params(&options())
+            .unwrap();
+        assert_eq!(yahoo["p"], "rust");
+        assert!(!yahoo.contains_key("q"));
+        assert_eq!(yahoo["b"], "11");
+
+        let video = SearchOptions {
+            mode: SearchMode::Video,
+            ..options()
+        };
+        let youtube = provider(SerpApiEngine::Bing).build_params(&video).unwrap();
+        assert_eq!(youtube["engine"], "youtube");
+        assert_eq!(youtube["search_query"], "rust");
+
+        let places = SearchOptions {
+            mode: SearchMode::Places,
+            ..options()
+        };
+        let maps = provider(SerpApiEngine::Google)
+            .build_params(&places)
+            .unwrap();
+        assert_eq!(maps["engine"], "google_maps");
+        assert_eq!(maps["type"], "search");
+        assert_eq!(maps["start"], "20");
+    }
+
+    #[test]
+    fn test_location_params() {
+        let austin = SearchOptions {
+            location: Some(GeoLocation::Name("Austin,Texas,United States".to_string())),
+            ..options()
+        };
+        let google = provider(SerpApiEngine::Google)
+            .build_params(&austin)
+            .unwrap();
+        assert_eq!(google["location"], "Austin,Texas,United States");
+        let bing = provider(SerpApiEngine::Bing).build_params(&austin).unwrap();
+        assert_eq!(bing["location"], "Austin,Texas,United States");
+        let yahoo = provider(SerpApiEngine::Yahoo)
+            .build_params(&austin)
+            .unwrap();
+        assert!(!yahoo.contains_key("location"));
+
+        let berlin = SearchOptions {
+            location: Some(GeoLocation::Coordinates {
+                latitude: 52.52,
+                longitude: 13.405,
+            }),
+            ..options()
+        };
+        let google = provider(SerpApiEngine::Google)
+            .build_params(&berlin)
+            .unwrap();
+        assert!(google["uule"].starts_with("a "));
+        let bing = provider(SerpApiEngine::Bing).build_params(&berlin).unwrap();
+        assert_eq!(
+            (bing["lat"].as_str(), bing["lon"].as_str()),
+            ("52.52", "13.405")
+        );
+        let maps = provider(SerpApiEngine::Google)
+            .build_params(&SearchOptions {
+                mode: SearchMode::Places,
+                ..berlin
+            })
+            .unwrap();
+        assert_eq!(maps["ll"], "@52.52,13.405,14z");
+    }
+
+    #[test]
+    fn test_parse_organic_results() {
+        let response: SerpApiResponse = serde_json::from_value(serde_json::json!({
+            "organic_results": [{
+                "position": 1,
+                "title": "Rust",
+                "link": "https://www.rust-lang.org/",
+                "displayed_link": "www.rust-lang.org › learn",
+                "snippet": "A language empowering everyone"
+            }]
+        }))
+        .unwrap();
+
+        let (results, _) = provider(SerpApiEngine::Bing).convert_results(response, SearchMode::Web);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].url, "https://www.rust-lang.org/");
+        assert_eq!(results[0].domain.as_deref(), Some("www.rust-lang.org"));
+    }
+
+    #[test]
+    fn test_ads_and_inline_videos_follow_organic_results() {
+        let response: SerpApiResponse = serde_json::from_value(serde_json::json!({
+            "ads": [{
+                "title": "Learn Rust fast",
+                "link": "https://ads.example.com/rust",
+                "description": "Sponsored course"
+            }],
+            "inline_videos": [{
+                "title": "Rust in 100 seconds",
+                "link": "https://www.youtube.com/watch?v=5C_HPTJg5ek",
+                "thumbnail": "https://i.ytimg.com/vi/5C_HPTJg5ek/hq.jpg"
+            }],
+            "organic_results": [{
+                "title": "Rust",
+                "link": "https://www.rust-lang.org/"
+            }]
+        }))
+        .unwrap();
+
+        let (results, _) =
+            provider(SerpApiEngine::Google).convert_results(response, SearchMode::Web);
+        let kinds: Vec<_> = results.iter().map(|r| r.kind).collect();
+        assert_eq!(
+            kinds,
+            [ResultKind::Organic, ResultKind::Video, ResultKind::Ad]
+        );
+        assert!(results[1].thumbnail_url.is_some());
+        assert_eq!(results[2].snippet.as_deref(), Some("Sponsored course"));
+    }
+
+    #[test]
+    fn test_parse_youtube_video_results() {
+        let response: SerpApiResponse = serde_json::from_value(serde_json::json!({
+            "video_results": [{
+                "title": "Rust in 100 Seconds",
+                "link": "https://www.youtube.com/watch?v=5C_HPTJg5ek",
+                "channel": { "name": "Fireship", "verified": true },
+                "published_date": "3 years ago",
+                "views": 2_100_000,
+                "length": "2:29",
+                "description": "Rust is a memory-safe systems language.",
+                "thumbnail": { "static": "https://i.ytimg.com/vi/5C_HPTJg5ek/hq720.jpg" }
+            }, {
+                "title": "Live stream without a link"
+            }]
+        }))
+        .unwrap();
+
+        let (results, warnings) =
+            provider(SerpApiEngine::Google).convert_results(response, SearchMode::Video);
+        assert_eq!(results.len(), 1);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(results[0].kind, ResultKind::Video);
+        let video = results[0].video.as_ref().unwrap();
+        assert_eq!(video.duration, Some(std::time::Duration::from_secs(149)));
+        assert_eq!(video.channel.as_deref(), Some("Fireship"));
+        assert_eq!(video.views, Some(2_100_000));
+        assert_eq!(video.thumbnail_url, results[0].thumbnail_url);
+    }
+
+    #[test]
+    fn test_parse_google_maps_place_results() {
+        let response: SerpApiResponse = serde_json::from_value(serde_json::json!({
+            "local_results": [{
+                "position": 1,
+                "title": "The Barn",
+                "place_id": "ChIJ123",
+                "gps_coordinates": { "latitude": 52.5298, "longitude": 13.4013 },
+                "rating": 4.6,
+                "reviews": 1520,
+                "type": "Coffee shop",
+                "address": "Auguststraße 58, 10119 Berlin",
+                "open_state": "Open ⋅ Closes 6 PM",
+                "phone": "+49 30 12345"
+            }, {
+                "title": "Bonanza Coffee",
+                "website": "https://bonanzacoffee.de/",
+                "hours": "Closed ⋅ Opens 9 AM"
+            }]
+        }))
+        .unwrap();
+
+        let (results, _) =
+            provider(SerpApiEngine::Google).convert_results(response, SearchMode::Places);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].kind, ResultKind::Place);
+        assert_eq!(
+            results[0].url,
+            "https://www.google.com/maps/place/?q=place_id:ChIJ123"
+        );
+        let place = results[0].place.as_ref().unwrap();
+        assert_eq!(
+            (place.latitude, place.longitude),
+            (Some(52.5298), Some(13.4013))
+        );
+        assert_eq!(place.reviews, Some(1520));
+        assert_eq!(place.hours.as_deref(), Some("Open ⋅ Closes 6 PM"));
+        assert_eq!(place.category.as_deref(), Some("Coffee shop"));
+        assert_eq!(results[1].url, "https://bonanzacoffee.de/");
+        assert_eq!(
+            results[1].place.as_ref().unwrap().hours.as_deref(),
+            Some("Closed ⋅ Opens 9 AM")
+        );
+    }
+
+    #[test]
+    fn test_parse_news_results_flattens_story_clusters() {
+        let response: SerpApiResponse = serde_json::from_value(serde_json::json!({
+            "news_results": [
+                {
+                    "title": "Rust 1.80 released",
+                    "link": "https://blog.rust-lang.org/1.80",
+                    "source": { "name": "Rust Blog" },
+                    "date": "07/25/2024, 07:00 AM, +0000 UTC"
+                },
+                {
+                    "highlight": {
+                        "title": "Highlighted story",
+                        "link": "https://news.example.com/a",
+                        "source": { "name": "Example News" }
+                    },
+                    "stories": [{
+                        "title": "Related story",
+                        "link": "https://other.example.org/b"
+                    }]
+                }
+            ]
+        }))
+        .unwrap();
+
+        let (results, _) =
+            provider(SerpApiEngine::GoogleNews).convert_results(response, SearchMode::Web);
+        let urls: Vec<_> = results.iter().map(|r| r.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            [
+                "https://blog.rust-lang.org/1.80",
+                "https://news.example.com/a",
+                "https://other.example.org/b"
+            ]
+        );
+        assert_eq!(results[0].snippet.as_deref(), Some("Rust Blog"));
+        assert_eq!(results[2].domain.as_deref(), Some("other.example.org"));
+    }
+
+    #[test]
+    fn test_parse_scholar_results_without_link() {
+        let response: SerpApiResponse = serde_json::from_value(serde_json::json!({
+            "organic_results": [
+                {
+                    "title": "Attention is all you need",
+                    "publication_info": { "summary": "A Vaswani, N Shazeer - 2017 - proceedings.neurips.cc" },
+                    "resources": [{ "link": "https://papers.example.org/attention.pdf" }]
+                },
+                { "title": "[CITATION] No link at all" }
+            ]
+        }))
+        .unwrap();
+
+        let (results, warnings) =
+            provider(SerpApiEngine::GoogleScholar).convert_results(response, SearchMode::Web);
+        assert_eq!(results.len(), 1);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].index, 1);
+        assert_eq!(results[0].url, "https://papers.example.org/attention.pdf");
+        assert!(results[0].snippet.as_deref().unwrap().contains("Vaswani"));
+    }
+
+    #[test]
+    fn test_parse_rich_results() {
+        let response: SerpApiResponse = serde_json::from_value(serde_json::json!({
+            "answer_box": {
+                "type": "organic_result",
+                "title": "Mount Everest height",
+                "answer": "8,849 m",
+                "link": "https://en.wikipedia.org/wiki/Mount_Everest"
+            },
+            "knowledge_graph": {
+                "title": "Mount Everest",
+                "type": "Mountain",
+                "description": "Earth's highest mountain above sea level.",
+                "kgmid": "/m/0blbxp",
+                "elevation": "8,849 m",
+                "first_ascent": "29 May 1953",
+                "source": { "name": "Wikipedia", "link": "https://en.wikipedia.org/wiki/Mount_Everest" },
+                "knowledge_graph_search_link": "https://www.google.com/search?kgmid=/m/0blbxp",
+                "header_images": [{ "image": "https://example.com/everest.jpg" }]
+            },
+            "related_questions": [
+                { "question": "Who climbed Everest first?", "snippet": "Tenzing Norgay and Edmund Hillary", "link": "https://example.com/first" },
+                { "snippet": "missing question is skipped" }
+            ],
+            "top_stories": [
+                { "title": "Everest season opens", "link": "https://news.example.com/everest", "source": "Example News", "date": "2 hours ago", "thumbnail": "https://news.example.com/t.jpg" }
+            ],
+            "organic_results": [{
+                "title": "Mount Everest - Wikipedia",
+                "link": "https://en.wikipedia.org/wiki/Mount_Everest"
+            }]
+        }))
+        .unwrap();
+
+        let response = provider(SerpApiEngine::Google).convert_response(response, SearchMode::Web);
+        assert_eq!(response.results.len(), 1);
+        assert_eq!(response.rich_results.len(), 4);
+
+        match &response.rich_results[0] {
+            RichResult::AnswerBox { answer, url, .. } => {
+                assert_eq!(answer.as_deref(), Some("8,849 m"));
+                assert!(url.is_some());
+            }
+            other => panic!("expected answer box, got {other:?}"),
+        }
+        match &response.rich_results[1] {
+            RichResult::KnowledgeGraph {
+                title,
+                entity_type,
+                url,
+                image_url,
+                attributes,
+                ..
+            } => {
+                assert_eq!(title, "Mount Everest");
+                assert_eq!(entity_type.as_deref(), Some("Mountain"));
+                assert_eq!(
+                    url.as_deref(),
+                    Some("https://en.wikipedia.org/wiki/Mount_Everest")
+                );
+                assert_eq!(
+                    image_url.as_deref(),
+                    Some("https://example.com/everest.jpg")
+                );
+                assert_eq!(attributes.len(), 2);
+                assert_eq!(attributes["first_ascent"], "29 May 1953");
+            }
+            other => panic!("expected knowledge graph, got {other:?}"),
+        }
+        match &response.rich_results[2] {
+            RichResult::Faq { items } => {
+                assert_eq!(items.len(), 1);
+                assert_eq!(items[0].question, "Who climbed Everest first?");
+            }
+            other => panic!("expected FAQ, got {other:?}"),
+        }
+        match &response.rich_results[3] {
+            RichResult::TopStories { stories } => {
+                assert_eq!(stories[0].site_name.as_deref(), Some("Example News"));
+                assert_eq!(
+                    stories[0].thumbnail_url.as_deref(),
+                    Some("https://news.example.com/t.jpg")
+                );
+            }
+            other => panic!("expected top stories, got {other:?}"),
+        }
+    }
+}
@@ -4,31 +4,41 @@
 //! It provides comprehensive, real-time search results with high relevance.
 
 use crate::{
+    cassette,
     error::{SearchError, SearchResult},
-    types::{SearchOptions, SearchProvider, SearchResult as SearchResultType},
+    providers::map_results,
+    types::{
+        Freshness, SearchOptions, SearchProvider, SearchResponse, SearchResult as SearchResultType,
+    },
+    utils::{
+        http::default_http_settings,
+        intern::{intern, interned},
+    },
 };
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::Arc;
 
-/// Tavily search result structure
-#[derive(Debug, Deserialize, Serialize)]
+/// Tavily search result structure; only `url` is required, the full object is kept as `raw`
+#[derive(Debug, Deserialize)]
 struct TavilySearchResult {
-    title: String,
     url: String,
-    content: String,
-    score: Option<f64>,
+    title: Option<String>,
+    content: Option<String>,
     published_date: Option<String>,
+    score: Option<f64>,
 }
 
 /// Tavily API response structure
 #[derive(Debug, Deserialize)]
+#[allow(dead_code)]
 struct TavilyResponse {
     answer: Option<String>,
-    query: String,
+    query: Option<String>,
     response_time: Option<f64>,
-    images: Option<Vec<String>>,
-    results: Vec<TavilySearchResult>,
-    follow_up_questions: Option<Vec<String>>,
+    /// Parsed one by one with [`map_results`]
+    #[serde(default)]
+    results: Vec<serde_json::Value>,
 }
 
 /// Tavily search request structure
@@ -43,6 +53,12 @@ struct TavilyRequest {
     max_results: u32,
     include_domains: Option<Vec<String>>,
     exclude_domains: Option<Vec<String>>,
+    /// Only read for news searches
+    #[serde(skip_serializing_if = "Option::is_none")]
+    days: Option<u32>,
+    /// `day`, `week`, `month` or `year`, for every topic
+    #[serde(skip_serializing_if = "Option::is_none")]
+    time_range: Option<Freshness>,
 }
 
 /// Tavily Search API provider
@@ -127,15 +143,22 @@ impl SearchProvider for TavilyProvider {
     }
 
     async fn search(&self, options: &SearchOptions) -> SearchResult<Vec<SearchResultType>> {
+        Ok(self.search_response(options).await?.results)
+    }
+
+    async fn search_response(&self, options: &SearchOptions) -> SearchResult<SearchResponse> {
         if options.query.is_empty() {
             return Err(SearchError::InvalidInput(
                 "Query cannot be empty".to_string(),
             ));
         }
 
-        let timeout_duration = std::time::Duration::from_millis(options.timeout.unwrap_or(15000));
-        let client = reqwest::Client::builder()
-            .timeout(timeout_duration)
+        let settings = match options.effective_timeout() {
+            Some(timeout) => default_http_settings().with_timeout(timeout),
+            None => default_http_settings().without_timeout(),
+        };
+        let client = settings
+            .client_builder()
             .build()
             .map_err(|e| {
                 SearchError::ConfigError(format!("Failed to create HTTP client: {e}"))
@@ -153,22 +176,25 @@ impl SearchProvider for TavilyProvider {
             max_results,
             include_domains: None, // Could be added as future enhancement
             exclude_domains: None, // Could be added as future enhancement
+            days: options.freshness.map(|freshness| freshness.days()),
+            time_range: options.freshness,
         };
 
-        let response = client
-            .post(&self.base_url)
-            .header("Content-Type", "application/json")
-            .json(&request_body)
-            .send()
-            .await
-            .map_err(|e| SearchError::HttpError {
-                message: format!("Failed to send request to Tavily: {e}"),
-                status_code: None,
-                response_body: None,
-            })?;
+        let response = cassette::send(
+            client
+                .post(&self.base_url)
+                .header("Content-Type", "application/json")
+                .json(&request_body),
+        )
+        .await
+        .map_err(|e| SearchError::HttpError {
+            message: format!("Failed to send request to Tavily: {e}"),
+            status_code: None,
+            response_body: None,
+        })?;
 
         let status = response.status();
-        let response_text = response.text().await.map_err(|e| SearchError::HttpError {
+        let body = response.bytes().await.map_err(|e| SearchError::HttpError {
             message: format!("Failed to read Tavily response: {e}"),
             status_code: Some(status.as_u16()),
             response_body: None,
@@ -188,38 +214,46 @@ impl SearchProvider for TavilyProvider {
             return Err(SearchError::HttpError {
                 message: format!("Tavily API error ({status}): {error_msg}"),
                 status_code: Some(status.as_u16()),
-                response_body: Some(response_text),
+                response_body: Some(String::from_utf8_lossy(&body).into_owned()),
             });
         }
 
-        let tavily_response: TavilyResponse =
-            serde_json::from_str(&response_text).map_err(|e| {
-                SearchError::ParseError(format!(
-                    "Failed to parse Tavily response: {e}. Response: {response_text}"
-                ))
-            })?;
-
-        // Convert Tavily results to our standard format
-        let results: Vec<SearchResultType> = tavily_response
-            .results
-            .into_iter()
-            .map(|result| {
-                // Store the original result as raw data
-                let raw_value = serde_json::to_value(&result).unwrap_or_default();
+        let tavily_response: TavilyResponse = serde_json::from_slice(&body).map_err(|e| {
+            SearchError::ParseError(format!(
+                "Failed to parse Tavily response: {e}. Response: {}",
+                String::from_utf8_lossy(&body)
+            ))
+        })?;
 
-                SearchResultType {
+        // Convert Tavily results to our standard format, skipping malformed ones
+        let (results, warnings) = map_results(
+            "tavily",
+            tavily_response.results,
+            |result: TavilySearchResult, raw_value| {
+                Ok(SearchResultType {
+                    domain: extract_domain(&result.url),
                     url: result.url,
-                    title: result.title,
-                    snippet: Some(result.content),
-                    domain: extract_domain(raw_value["url"].as_str().unwrap_or("")),
+                    title: result.title.unwrap_or_default(),
+                    snippet: result.content,
                     published_date: result.published_date,
-                    provider: Some("tavily".to_string()),
+                    score: result.score,
+                    provider: Some(interned!("tavily")),
+                    // The original result, including fields this SDK doesn't map
                     raw: Some(raw_value),
-                }
-            })
-            .collect();
+                    ..Default::default()
+                })
+            },
+        );
+
+        Ok(SearchResponse {
+            results,
+            warnings,
+            ..Default::default()
+        })
+    }
 
-        Ok(results)
+    fn supports_freshness(&self) -> bool {
+        true
     }
 
     fn config(&self) -> HashMap<String, String> {
@@ -245,9 +279,9 @@ impl SearchProvider for TavilyProvider {
 }
 
 /// Extract domain from URL
-fn extract_domain(url: &str) -> Option<String> {
+fn extract_domain(url: &str) -> Option<Arc<str>> {
     if let Ok(parsed_url) = url::Url::parse(url) {
-        parsed_url.host_str().map(|host| host.to_string())
+        parsed_url.host_str().map(intern)
     } else {
         None
     }
@@ -303,8 +337,6 @@ mod tests {
 
     #[test]
     fn test_tavily_search_depth_validation() {
-        let provider = TavilyProvider::new("tvly-test-valid-api-key-format").unwrap();
-
         // Valid search depths - create new providers for each test
         let provider1 = TavilyProvider::new("tvly-test-valid-api-key-format").unwrap();
         let provider2 = TavilyProvider::new("tvly-test-valid-api-key-format").unwrap();
@@ -355,11 +387,11 @@ mod tests {
     fn test_extract_domain() {
         assert_eq!(
             extract_domain("https://example.com/path"),
-            Some("example.com".to_string())
+            Some("example.com".into())
         );
         assert_eq!(
             extract_domain("http://subdomain.example.org"),
-            Some("subdomain.example.org".to_string())
+            Some("subdomain.example.org".into())
         );
         assert_eq!(extract_domain("invalid-url"), None);
         assert_eq!(extract_domain(""), None);
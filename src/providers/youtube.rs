@@ -0,0 +1,537 @@
+//! YouTube Data API provider
+//!
+//! Searches YouTube videos with the Data API's `search` endpoint, then looks up their
+//! lengths and view counts with one `videos` request, which the search listing doesn't
+//! include. Every result is a video, so the provider answers both [`SearchMode`]s.
+//!
+//! The API pages with opaque tokens rather than page numbers, so only the first page can be
+//! requested; a later [`SearchOptions::page`] fails with [`SearchError::Unsupported`].
+
+use crate::{
+    error::{SearchError, SearchResult},
+    locale::Locale,
+    providers::{map_results, parse_count},
+    types::{
+        ProviderHealth, ResultKind, SafeSearch, SearchMode, SearchOptions, SearchProvider,
+        SearchResponse, SearchResult as SearchResultType, VideoResult,
+    },
+    utils::{
+        debug,
+        extract::fragment_text,
+        http::{build_url, HttpClient},
+        intern::interned,
+    },
+};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// The search endpoint returns at most 50 results per request
+const MAX_RESULTS: u32 = 50;
+
+/// Result URLs are this followed by the video ID
+const WATCH_URL: &str = "https://www.youtube.com/watch?v=";
+
+/// A long-lived public video, looked up by health checks
+const HEALTH_CHECK_VIDEO_ID: &str = "jNQXAC9IVRw";
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct YouTubeSearchItem {
+    id: YouTubeVideoId,
+    snippet: YouTubeSnippet,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct YouTubeVideoId {
+    video_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct YouTubeSnippet {
+    /// HTML-escaped, like `Rust &amp; WebAssembly`
+    title: String,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    published_at: Option<String>,
+    #[serde(default)]
+    channel_title: Option<String>,
+    #[serde(default)]
+    thumbnails: HashMap<String, YouTubeThumbnail>,
+}
+
+impl YouTubeSnippet {
+    /// The largest thumbnail the listing offers
+    fn thumbnail_url(&self) -> Option<String> {
+        ["high", "medium", "default"]
+            .iter()
+            .find_map(|size| self.thumbnails.get(*size))
+            .map(|thumbnail| thumbnail.url.clone())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct YouTubeThumbnail {
+    url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct YouTubeSearchResponse {
+    /// Parsed one by one with [`map_results`]
+    #[serde(default)]
+    items: Vec<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct YouTubeVideosResponse {
+    #[serde(default)]
+    items: Vec<YouTubeVideo>,
+}
+
+/// Details of one video from the `videos` endpoint
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct YouTubeVideo {
+    id: String,
+    #[serde(default)]
+    content_details: Option<YouTubeContentDetails>,
+    #[serde(default)]
+    statistics: Option<YouTubeStatistics>,
+}
+
+#[derive(Debug, Deserialize)]
+struct YouTubeContentDetails {
+    /// ISO 8601, like `PT2M29S`
+    #[serde(default)]
+    duration: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct YouTubeStatistics {
+    /// A number in a string
+    #[serde(default)]
+    view_count: Option<serde_json::Value>,
+}
+
+/// YouTube Data API v3 provider
+#[derive(Debug)]
+pub struct YouTubeProvider {
+    api_key: String,
+    base_url: String,
+    http_client: HttpClient,
+}
+
+impl YouTubeProvider {
+    /// Create a YouTube provider with a Google Cloud API key that has the YouTube Data API
+    /// enabled
+    pub fn new(api_key: &str) -> SearchResult<Self> {
+        if api_key.is_empty() {
+            return Err(SearchError::ConfigError(
+                "YouTube API key is required".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            api_key: api_key.to_string(),
+            base_url: "https://www.googleapis.com/youtube/v3".to_string(),
+            http_client: HttpClient::new().with_accept("application/json"),
+        })
+    }
+
+    /// Set a custom base URL, under which the `search` and `videos` endpoints are found
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    fn build_search_url(&self, options: &SearchOptions) -> SearchResult<String> {
+        if options.page.is_some_and(|page| page > 1) {
+            return Err(SearchError::Unsupported(
+                "YouTube only returns the first page of results".to_string(),
+            ));
+        }
+
+        let mut params = HashMap::new();
+        params.insert("part".to_string(), "snippet".to_string());
+        params.insert("type".to_string(), "video".to_string());
+        params.insert("q".to_string(), options.query.clone());
+        params.insert("key".to_string(), self.api_key.clone());
+
+        let max_results = options.max_results.unwrap_or(10).clamp(1, MAX_RESULTS);
+        params.insert("maxResults".to_string(), max_results.to_string());
+
+        let locale = Locale::from_options(options.language.as_deref(), options.region.as_deref())?;
+        if let Some(language) = locale.as_ref().and_then(|l| l.language.clone()) {
+            params.insert("relevanceLanguage".to_string(), language);
+        }
+        if let Some(region) = locale.as_ref().and_then(|l| l.region.clone()) {
+            params.insert("regionCode".to_string(), region);
+        }
+
+        if let Some(safe_search) = &options.safe_search {
+            let level = match safe_search {
+                SafeSearch::Off => "none",
+                SafeSearch::Moderate => "moderate",
+                SafeSearch::Strict => "strict",
+            };
+            params.insert("safeSearch".to_string(), level.to_string());
+        }
+
+        if let Some(after) = options.published_after {
+            params.insert(
+                "publishedAfter".to_string(),
+                format!("{}T00:00:00Z", after.format("%Y-%m-%d")),
+            );
+        }
+        if let Some(before) = options.published_before {
+            params.insert(
+                "publishedBefore".to_string(),
+                format!("{}T23:59:59Z", before.format("%Y-%m-%d")),
+            );
+        }
+
+        build_url(&format!("{}/search", self.base_url), params)
+    }
+
+    /// Lengths and view counts of `ids`, by video ID
+    async fn fetch_details(&self, ids: &[&str]) -> SearchResult<HashMap<String, YouTubeVideo>> {
+        let response = self
+            .fetch_videos("contentDetails,statistics", &ids.join(","))
+            .await?;
+        Ok(response
+            .items
+            .into_iter()
+            .map(|video| (video.id.clone(), video))
+            .collect())
+    }
+
+    /// The `part`s of the comma-separated video `ids` from the `videos` endpoint
+    async fn fetch_videos(&self, part: &str, ids: &str) -> SearchResult<YouTubeVideosResponse> {
+        let mut params = HashMap::new();
+        params.insert("part".to_string(), part.to_string());
+        params.insert("id".to_string(), ids.to_string());
+        params.insert("key".to_string(), self.api_key.clone());
+        let url = build_url(&format!("{}/videos", self.base_url), params)?;
+
+        self.http_client.get_json(&url).await
+    }
+}
+
+/// An ISO 8601 duration as YouTube reports video lengths, like `PT1H2M3S` or `P1DT2H`
+fn parse_iso_duration(duration: &str) -> Option<Duration> {
+    let rest = duration.strip_prefix('P')?;
+    let (days, time) = match rest.split_once('T') {
+        Some((days, time)) => (days, time),
+        None => (rest, ""),
+    };
+
+    let mut seconds = 0;
+    for (part, units) in [
+        (days, &[('D', 86_400)][..]),
+        (time, &[('H', 3600), ('M', 60), ('S', 1)][..]),
+    ] {
+        let mut number = String::new();
+        for c in part.chars() {
+            if c.is_ascii_digit() {
+                number.push(c);
+                continue;
+            }
+            let (_, unit) = units.iter().find(|(designator, _)| *designator == c)?;
+            seconds += number.parse::<u64>().ok()? * unit;
+            number.clear();
+        }
+        if !number.is_empty() {
+            return None;
+        }
+    }
+    Some(Duration::from_secs(seconds))
+}
+
+fn convert_item(item: YouTubeSearchItem, raw: serde_json::Value) -> SearchResultType {
+    let thumbnail_url = item.snippet.thumbnail_url();
+    SearchResultType {
+        url: format!("{WATCH_URL}{}", item.id.video_id),
+        title: fragment_text(&item.snippet.title, None),
+        snippet: item
+            .snippet
+            .description
+            .map(|description| fragment_text(&description, None))
+            .filter(|description| !description.is_empty()),
+        kind: ResultKind::Video,
+        domain: Some(interned!("www.youtube.com")),
+        published_date: item.snippet.published_at,
+        provider: Some(interned!("youtube")),
+        thumbnail_url: thumbnail_url.clone(),
+        video: Some(VideoResult {
+            channel: item.snippet.channel_title,
+            thumbnail_url,
+            ..Default::default()
+        }),
+        // The original item, including fields this SDK doesn't map
+        raw: Some(raw),
+        ..Default::default()
+    }
+}
+
+#[async_trait::async_trait]
+impl SearchProvider for YouTubeProvider {
+    fn name(&self) -> &str {
+        "youtube"
+    }
+
+    async fn search(&self, options: &SearchOptions) -> SearchResult<Vec<SearchResultType>> {
+        Ok(self.search_response(options).await?.results)
+    }
+
+    async fn search_response(&self, options: &SearchOptions) -> SearchResult<SearchResponse> {
+        debug::log_request(
+            &options.debug,
+            "YouTube search request",
+            &format!("query: {}", options.query),
+        );
+
+        let url = self.build_search_url(options)?;
+        let response: YouTubeSearchResponse = self.http_client.get_json(&url).await?;
+        let (mut results, warnings) =
+            map_results("youtube", response.items, |item: YouTubeSearchItem, raw| {
+                Ok(convert_item(item, raw))
+            });
+
+        // The listing has no lengths or view counts. A failed lookup fails the search, as
+        // it's rejected for the same reasons (key, quota) the next search would be.
+        let ids: Vec<&str> = results
+            .iter()
+            .filter_map(|result| result.url.strip_prefix(WATCH_URL))
+            .collect();
+        let details = if ids.is_empty() {
+            HashMap::new()
+        } else {
+            self.fetch_details(&ids).await?
+        };
+        for result in &mut results {
+            let Some(details) = result
+                .url
+                .strip_prefix(WATCH_URL)
+                .and_then(|id| details.get(id))
+            else {
+                continue;
+            };
+            if let Some(video) = &mut result.video {
+                video.duration = details
+                    .content_details
+                    .as_ref()
+                    .and_then(|content| content.duration.as_deref())
+                    .and_then(parse_iso_duration);
+                video.views = details
+                    .statistics
+                    .as_ref()
+                    .and_then(|statistics| statistics.view_count.as_ref())
+                    .and_then(parse_count);
+            }
+        }
+
+        debug::log_response(
+            &options.debug,
+            &format!("YouTube returned {} results", results.len()),
+        );
+
+        Ok(SearchResponse {
+            results,
+            warnings,
+            ..Default::default()
+        })
+    }
+
+    /// Looks up one video's ID, which costs one quota unit instead of a search's hundred
+    async fn health_check(&self) -> ProviderHealth {
+        let started = std::time::Instant::now();
+        let result = self
+            .fetch_videos("id", HEALTH_CHECK_VIDEO_ID)
+            .await
+            .map(|_| ());
+        ProviderHealth::from_result(self.name(), started.elapsed(), result)
+    }
+
+    fn supports_safe_search(&self) -> bool {
+        true
+    }
+
+    fn supports_mode(&self, mode: SearchMode) -> bool {
+        matches!(mode, SearchMode::Web | SearchMode::Video)
+    }
+
+    fn config(&self) -> HashMap<String, String> {
+        let mut config = HashMap::new();
+        config.insert("api_key".to_string(), "***".to_string());
+        config.insert("base_url".to_string(), self.base_url.clone());
+        config
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::{
+        matchers::{method, path, query_param},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    #[test]
+    fn test_parse_iso_duration() {
+        assert_eq!(
+            parse_iso_duration("PT2M29S"),
+            Some(Duration::from_secs(149))
+        );
+        assert_eq!(parse_iso_duration("PT1H"), Some(Duration::from_secs(3600)));
+        assert_eq!(
+            parse_iso_duration("P1DT2H"),
+            Some(Duration::from_secs(93_600))
+        );
+        assert_eq!(parse_iso_duration("PT0S"), Some(Duration::ZERO));
+        assert_eq!(parse_iso_duration("PT5X"), None);
+        assert_eq!(parse_iso_duration("2:29"), None);
+    }
+
+    #[tokio::test]
+    async fn test_search_fills_in_video_details() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/search"))
+            .and(query_param("q", "rust"))
+            .and(query_param("type", "video"))
+            .and(query_param("key", "test-key"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "items": [{
+                    "id": { "kind": "youtube#video", "videoId": "5C_HPTJg5ek" },
+                    "snippet": {
+                        "publishedAt": "2021-09-20T16:00:10Z",
+                        "channelTitle": "Fireship",
+                        "title": "Rust in 100 Seconds",
+                        "description": "Learn the basics of Rust &amp; why it&#39;s loved.",
+                        "thumbnails": {
+                            "default": { "url": "https://i.ytimg.com/vi/5C_HPTJg5ek/default.jpg" },
+                            "high": { "url": "https://i.ytimg.com/vi/5C_HPTJg5ek/hqdefault.jpg" }
+                        }
+                    }
+                }, {
+                    "id": { "kind": "youtube#channel", "channelId": "UCsBjURrPoezykLs9EqgamOA" },
+                    "snippet": { "title": "Fireship" }
+                }]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/videos"))
+            .and(query_param("id", "5C_HPTJg5ek"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "items": [{
+                    "id": "5C_HPTJg5ek",
+                    "contentDetails": { "duration": "PT2M29S" },
+                    "statistics": { "viewCount": "2100000" }
+                }]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let provider = YouTubeProvider::new("test-key")
+            .unwrap()
+            .with_base_url(&server.uri());
+        let response = provider
+            .search_response(&SearchOptions {
+                query: "rust".to_string(),
+                mode: SearchMode::Video,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(response.results.len(), 1);
+        assert_eq!(response.warnings.len(), 1);
+        let result = &response.results[0];
+        assert_eq!(result.url, "https://www.youtube.com/watch?v=5C_HPTJg5ek");
+        assert_eq!(
+            result.snippet.as_deref(),
+            Some("Learn the basics of Rust & why it's loved.")
+        );
+        assert_eq!(
+            result.video,
+            Some(VideoResult {
+                duration: Some(Duration::from_secs(149)),
+                channel: Some("Fireship".to_string()),
+                views: Some(2_100_000),
+                thumbnail_url: Some("https://i.ytimg.com/vi/5C_HPTJg5ek/hqdefault.jpg".to_string()),
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_later_pages_are_unsupported() {
+        let provider = YouTubeProvider::new("test-key").unwrap();
+        let result = provider
+            .search(&SearchOptions {
+                query: "rust".to_string(),
+                page: Some(2),
+                ..Default::default()
+            })
+            .await;
+        assert!(matches!(result, Err(SearchError::Unsupported(_))));
+    }
+
+    #[tokio::test]
+    async fn test_failed_detail_lookup_fails_the_search() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/search"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "items": [{
+                    "id": { "kind": "youtube#video", "videoId": "5C_HPTJg5ek" },
+                    "snippet": { "title": "Rust in 100 Seconds" }
+                }]
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/videos"))
+            .respond_with(ResponseTemplate::new(403))
+            .mount(&server)
+            .await;
+
+        let provider = YouTubeProvider::new("test-key")
+            .unwrap()
+            .with_base_url(&server.uri());
+        let result = provider
+            .search(&SearchOptions {
+                query: "rust".to_string(),
+                ..Default::default()
+            })
+            .await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_health_check_looks_up_one_video() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/videos"))
+            .and(query_param("part", "id"))
+            .and(query_param("id", HEALTH_CHECK_VIDEO_ID))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "items": [{ "id": HEALTH_CHECK_VIDEO_ID }]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let provider = YouTubeProvider::new("test-key")
+            .unwrap()
+            .with_base_url(&server.uri());
+        let health = provider.health_check().await;
+        assert_eq!(health.status, crate::types::HealthStatus::Healthy);
+    }
+}
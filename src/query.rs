@@ -0,0 +1,561 @@
+//! Query expansion
+//!
+//! One phrasing of a question misses pages that word it differently. [`expand`] turns a
+//! query into variants: synonyms from a small built-in thesaurus, singular forms and the
+//! quoted phrase. [`MultiQuery`] searches every variant and fuses the results into one
+//! list, trading a few more requests for recall in research workloads. Plug in an LLM or
+//! any other source of rewrites by implementing [`QueryExpander`].
+//!
+//! [`web_search_all`] and [`web_search_any`] run several queries and keep the results all
+//! or any of them returned, noting which queries found each one.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use websearch::query::expand;
+//!
+//! let variants = expand("fast json parsers");
+//! assert_eq!(variants[0], "fast json parsers");
+//! assert!(variants.contains(&"quick json parsers".to_string()));
+//! assert!(variants.contains(&"fast json parser".to_string()));
+//! assert!(variants.contains(&"\"fast json parsers\"".to_string()));
+//! ```
+
+use crate::{
+    batch::{web_search_batch, BatchSearchResult},
+    error::{SearchError, SearchResult as Result},
+    multi_provider::MultiProviderSearch,
+    processors::dedup_key,
+    types::{SearchProvider, SearchQuery, SearchResult},
+};
+use std::cmp::Ordering;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+/// Groups of interchangeable words used by [`expand`]
+const THESAURUS: &[&[&str]] = &[
+    &["fast", "quick", "speedy"],
+    &["big", "large"],
+    &["small", "little", "tiny"],
+    &["cheap", "inexpensive", "affordable"],
+    &["best", "top"],
+    &["buy", "purchase"],
+    &["car", "automobile"],
+    &["movie", "film"],
+    &["job", "career"],
+    &["illness", "disease"],
+    &["doctor", "physician"],
+    &["error", "bug"],
+    &["fix", "repair", "solve"],
+    &["guide", "tutorial"],
+    &["example", "sample"],
+    &["method", "approach", "technique"],
+    &["study", "research"],
+    &["paper", "article"],
+    &["start", "begin"],
+    &["use", "utilize"],
+    &["vs", "versus"],
+];
+
+/// Plural endings removed by [`singular`] and what replaces them
+const PLURALS: &[(&str, &str)] = &[
+    ("ies", "y"),
+    ("sses", "ss"),
+    ("ches", "ch"),
+    ("shes", "sh"),
+    ("xes", "x"),
+    ("s", ""),
+];
+
+/// Rewrites of `query` that should find pages it would miss, starting with `query` itself
+///
+/// Variants are, in order: each word swapped for its synonyms in a small built-in
+/// thesaurus, every plural word in the singular ("parsers" to "parser"), and the whole
+/// query quoted as an exact phrase. Duplicates are dropped, ignoring case.
+pub fn expand(query: &str) -> Vec<String> {
+    let query = query.trim();
+    let words: Vec<&str> = query.split_whitespace().collect();
+    let mut variants = vec![query.to_string()];
+
+    for (i, word) in words.iter().enumerate() {
+        let lower = word.to_lowercase();
+        let Some(group) = THESAURUS
+            .iter()
+            .find(|group| group.contains(&lower.as_str()))
+        else {
+            continue;
+        };
+        for synonym in group.iter().filter(|synonym| **synonym != lower) {
+            let mut rewritten = words.clone();
+            rewritten[i] = synonym;
+            variants.push(rewritten.join(" "));
+        }
+    }
+
+    let singular: Vec<String> = words.iter().map(|word| singular(word)).collect();
+    variants.push(singular.join(" "));
+
+    if words.len() > 1 && !query.contains('"') {
+        variants.push(format!("\"{query}\""));
+    }
+
+    let mut seen = HashSet::new();
+    variants.retain(|variant| !variant.is_empty() && seen.insert(variant.to_lowercase()));
+    variants
+}
+
+/// `word` without a plural ending, e.g. "libraries" to "library"
+///
+/// Short words, words that don't look like plain English (`c++`, `tokio::spawn`) and
+/// endings such as "-ss", "-us" and "-is" are left as they are.
+fn singular(word: &str) -> String {
+    let lower = word.to_lowercase();
+    if lower.chars().count() <= 4
+        || !lower.chars().all(|c| c.is_alphabetic())
+        || ["ss", "us", "is"]
+            .iter()
+            .any(|ending| lower.ends_with(ending))
+    {
+        return word.to_string();
+    }
+    PLURALS
+        .iter()
+        .find_map(|(suffix, replacement)| {
+            let stem = lower.strip_suffix(suffix)?;
+            (stem.chars().count() >= 3).then(|| format!("{stem}{replacement}"))
+        })
+        .unwrap_or_else(|| word.to_string())
+}
+
+/// Source of query variants for [`MultiQuery`], e.g. an LLM asked for rewrites
+#[async_trait::async_trait]
+pub trait QueryExpander: Send + Sync + std::fmt::Debug {
+    /// Variants of `query` worth searching; `query` itself is searched either way
+    async fn expand(&self, query: &str) -> Result<Vec<String>>;
+}
+
+/// The built-in expansion, [`expand`]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ThesaurusExpander;
+
+#[async_trait::async_trait]
+impl QueryExpander for ThesaurusExpander {
+    async fn expand(&self, query: &str) -> Result<Vec<String>> {
+        Ok(expand(query))
+    }
+}
+
+/// Reciprocal rank fusion constant: how much a top rank counts over a lower one
+const RRF_K: f64 = 60.0;
+
+/// Search every variant of a query and fuse the results
+///
+/// Variants run concurrently, like a batch. Results are merged by URL (as by
+/// [`Dedup`](crate::processors::Dedup)) and ordered by reciprocal rank fusion, so pages
+/// several variants find near the top come first. The search fails only when every
+/// variant does.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use websearch::{providers::DuckDuckGoProvider, query::MultiQuery, SearchQuery};
+///
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let results = MultiQuery::new()
+///     .with_max_variants(4)
+///     .search(&DuckDuckGoProvider::new(), &SearchQuery::new("fast json parsers"))
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct MultiQuery {
+    expander: Arc<dyn QueryExpander>,
+    max_variants: usize,
+    concurrency: usize,
+}
+
+impl Default for MultiQuery {
+    fn default() -> Self {
+        Self {
+            expander: Arc::new(ThesaurusExpander),
+            max_variants: 5,
+            concurrency: 4,
+        }
+    }
+}
+
+impl MultiQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get variants from `expander` instead of the built-in thesaurus
+    pub fn with_expander(mut self, expander: impl QueryExpander + 'static) -> Self {
+        self.expander = Arc::new(expander);
+        self
+    }
+
+    /// Search at most this many variants, including the original query (5 by default)
+    pub fn with_max_variants(mut self, max_variants: usize) -> Self {
+        self.max_variants = max_variants.max(1);
+        self
+    }
+
+    /// Run at most this many variants at once (4 by default)
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency;
+        self
+    }
+
+    /// Search `provider` for every variant of `query`
+    pub async fn search(
+        &self,
+        provider: &dyn SearchProvider,
+        query: &SearchQuery,
+    ) -> Result<Vec<SearchResult>> {
+        let variants = self.variants(query).await?;
+        let batch = web_search_batch(provider, variants, self.concurrency).await;
+        fuse(batch, query.max_results)
+    }
+
+    /// Search every variant of `query` through a multi-provider search's strategy
+    pub async fn search_multi(
+        &self,
+        search: &MultiProviderSearch,
+        query: &SearchQuery,
+    ) -> Result<Vec<SearchResult>> {
+        let variants = self.variants(query).await?;
+        let batch = search.search_batch(variants, self.concurrency).await;
+        fuse(batch, query.max_results)
+    }
+
+    /// `query` followed by its expansions, up to `max_variants`
+    async fn variants(&self, query: &SearchQuery) -> Result<Vec<SearchQuery>> {
+        let mut texts = vec![query.query.clone()];
+        texts.extend(
+            self.expander
+                .expand(&query.query)
+                .await?
+                .into_iter()
+                .filter(|variant| !variant.eq_ignore_ascii_case(&query.query)),
+        );
+        texts.truncate(self.max_variants);
+
+        Ok(texts
+            .into_iter()
+            .map(|text| SearchQuery {
+                query: text,
+                ..query.clone()
+            })
+            .collect())
+    }
+}
+
+/// Merge the variants' results by reciprocal rank fusion, failing only if every variant did
+fn fuse(batch: Vec<BatchSearchResult>, max_results: Option<u32>) -> Result<Vec<SearchResult>> {
+    let mut error = None;
+    let mut searched = false;
+    let mut fused: Vec<(f64, SearchResult)> = Vec::new();
+    let mut positions: HashMap<String, usize> = HashMap::new();
+
+    for item in batch {
+        let results = match item.result {
+            Ok(results) => results,
+            Err(e) => {
+                error.get_or_insert(e);
+                continue;
+            }
+        };
+        searched = true;
+        for (rank, result) in results.into_iter().enumerate() {
+            let score = 1.0 / (RRF_K + rank as f64 + 1.0);
+            match positions.entry(dedup_key(&result.url)) {
+                Entry::Occupied(entry) => fused[*entry.get()].0 += score,
+                Entry::Vacant(entry) => {
+                    entry.insert(fused.len());
+                    fused.push((score, result));
+                }
+            }
+        }
+    }
+    if !searched {
+        return Err(error.unwrap_or_else(|| {
+            SearchError::InvalidInput("No query variants to search".to_string())
+        }));
+    }
+
+    fused.sort_by(|(a, _), (b, _)| b.partial_cmp(a).unwrap_or(Ordering::Equal));
+    let mut results: Vec<SearchResult> = fused.into_iter().map(|(_, result)| result).collect();
+    if let Some(max_results) = max_results {
+        results.truncate(max_results as usize);
+    }
+    Ok(results)
+}
+
+/// A result of [`web_search_all`] or [`web_search_any`] and the queries that found it
+#[derive(Debug, Clone)]
+pub struct QueryMatch {
+    pub result: SearchResult,
+    /// Position of each query that returned this result in the queries given, in order
+    pub queries: Vec<usize>,
+}
+
+/// Results every one of `queries` returned, e.g. for pages that must mention A and B
+///
+/// Queries run concurrently against `provider`, and results are matched on their URL (as
+/// by [`Dedup`](crate::processors::Dedup)). They come in the first query's order. Any
+/// failing query fails the search, since the intersection would be incomplete.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use websearch::{providers::DuckDuckGoProvider, web_search_all, SearchQuery};
+///
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let queries = vec![
+///     SearchQuery::new("rust borrow checker"),
+///     SearchQuery::new("rust lifetimes"),
+/// ];
+/// for found in web_search_all(&DuckDuckGoProvider::new(), queries).await? {
+///     println!("{} (queries {:?})", found.result.url, found.queries);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub async fn web_search_all(
+    provider: &dyn SearchProvider,
+    queries: Vec<SearchQuery>,
+) -> Result<Vec<QueryMatch>> {
+    combine(search_each(provider, queries).await?, true)
+}
+
+/// Results any of `queries` returned, each listing the queries that found it
+///
+/// Queries run concurrently against `provider`, and results are merged on their URL (as
+/// by [`Dedup`](crate::processors::Dedup)), in the order first found going through the
+/// queries in turn. The search fails only when every query does.
+pub async fn web_search_any(
+    provider: &dyn SearchProvider,
+    queries: Vec<SearchQuery>,
+) -> Result<Vec<QueryMatch>> {
+    combine(search_each(provider, queries).await?, false)
+}
+
+async fn search_each(
+    provider: &dyn SearchProvider,
+    queries: Vec<SearchQuery>,
+) -> Result<Vec<BatchSearchResult>> {
+    if queries.is_empty() {
+        return Err(SearchError::InvalidInput(
+            "At least one query is required".to_string(),
+        ));
+    }
+    let concurrency = queries.len();
+    Ok(web_search_batch(provider, queries, concurrency).await)
+}
+
+/// Merge each query's results by URL, keeping only those every query found if `require_all`
+fn combine(batch: Vec<BatchSearchResult>, require_all: bool) -> Result<Vec<QueryMatch>> {
+    // Queries are told apart by position, as the same text may be searched with other options
+    let query_count = batch.len();
+    let mut error = None;
+    let mut searched = false;
+    let mut matches: Vec<QueryMatch> = Vec::new();
+    let mut positions: HashMap<String, usize> = HashMap::new();
+
+    for (index, item) in batch.into_iter().enumerate() {
+        let results = match item.result {
+            Ok(results) => results,
+            Err(e) if require_all => return Err(e),
+            Err(e) => {
+                error.get_or_insert(e);
+                continue;
+            }
+        };
+        searched = true;
+        for result in results {
+            match positions.entry(dedup_key(&result.url)) {
+                Entry::Occupied(entry) => {
+                    let found = &mut matches[*entry.get()];
+                    if found.queries.last() != Some(&index) {
+                        found.queries.push(index);
+                    }
+                }
+                Entry::Vacant(entry) => {
+                    entry.insert(matches.len());
+                    matches.push(QueryMatch {
+                        result,
+                        queries: vec![index],
+                    });
+                }
+            }
+        }
+    }
+
+    if let (false, Some(error)) = (searched, error) {
+        return Err(error);
+    }
+    if require_all {
+        matches.retain(|found| found.queries.len() == query_count);
+    }
+    Ok(matches)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::SearchOptions;
+
+    #[test]
+    fn test_expand() {
+        assert_eq!(
+            expand("Best rust libraries"),
+            [
+                "Best rust libraries",
+                "top rust libraries",
+                "Best rust library",
+                "\"Best rust libraries\"",
+            ]
+        );
+        assert_eq!(expand("rust"), ["rust"]);
+        assert_eq!(
+            expand("parsing nested json"),
+            ["parsing nested json", "\"parsing nested json\""]
+        );
+        assert_eq!(
+            expand("c++ classes"),
+            ["c++ classes", "c++ class", "\"c++ classes\""]
+        );
+    }
+
+    /// Returns the query's words as result URLs, so variants overlap on shared words
+    #[derive(Debug)]
+    struct WordProvider;
+
+    #[async_trait::async_trait]
+    impl SearchProvider for WordProvider {
+        fn name(&self) -> &str {
+            "words"
+        }
+
+        async fn search(&self, options: &SearchOptions) -> Result<Vec<SearchResult>> {
+            if options.query.contains("fail") {
+                return Err(SearchError::Other("scripted failure".to_string()));
+            }
+            Ok(options
+                .query
+                .split_whitespace()
+                .map(|word| SearchResult {
+                    url: format!("https://example.com/{}", word.trim_matches('"')),
+                    ..Default::default()
+                })
+                .collect())
+        }
+    }
+
+    #[derive(Debug)]
+    struct FixedExpander(Vec<&'static str>);
+
+    #[async_trait::async_trait]
+    impl QueryExpander for FixedExpander {
+        async fn expand(&self, _query: &str) -> Result<Vec<String>> {
+            Ok(self.0.iter().map(|variant| variant.to_string()).collect())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_multi_query_fuses_variants() {
+        let results = MultiQuery::new()
+            .with_expander(FixedExpander(vec!["beta gamma", "fail", "gamma delta"]))
+            .search(&WordProvider, &SearchQuery::new("alpha gamma"))
+            .await
+            .unwrap();
+
+        let urls: Vec<&str> = results.iter().map(|r| r.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            [
+                "https://example.com/gamma",
+                "https://example.com/alpha",
+                "https://example.com/beta",
+                "https://example.com/delta",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_multi_query_fails_when_every_variant_fails() {
+        let result = MultiQuery::new()
+            .with_expander(FixedExpander(vec!["fail again"]))
+            .search(&WordProvider, &SearchQuery::new("fail"))
+            .await;
+        assert!(matches!(result, Err(SearchError::Other(_))));
+    }
+
+    fn urls(matches: &[QueryMatch]) -> Vec<(&str, usize)> {
+        matches
+            .iter()
+            .map(|found| (found.result.url.as_str(), found.queries.len()))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_web_search_all_and_any() {
+        let queries = || {
+            vec![
+                SearchQuery::new("rust async"),
+                SearchQuery::new("async tokio"),
+            ]
+        };
+
+        let all = web_search_all(&WordProvider, queries()).await.unwrap();
+        assert_eq!(urls(&all), [("https://example.com/async", 2)]);
+        assert_eq!(all[0].queries, [0, 1]);
+
+        let any = web_search_any(&WordProvider, queries()).await.unwrap();
+        assert_eq!(
+            urls(&any),
+            [
+                ("https://example.com/rust", 1),
+                ("https://example.com/async", 2),
+                ("https://example.com/tokio", 1),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_queries_with_the_same_text_count_separately() {
+        let queries = vec![
+            SearchQuery::new("rust async"),
+            SearchQuery {
+                include_domains: Some(vec!["example.com".to_string()]),
+                ..SearchQuery::new("rust async")
+            },
+            SearchQuery::new("async tokio"),
+        ];
+
+        let all = web_search_all(&WordProvider, queries).await.unwrap();
+        assert_eq!(urls(&all), [("https://example.com/async", 3)]);
+        assert_eq!(all[0].queries, [0, 1, 2]);
+    }
+
+    #[tokio::test]
+    async fn test_web_search_all_fails_with_any_query() {
+        let queries = vec![SearchQuery::new("rust"), SearchQuery::new("fail")];
+        assert!(web_search_all(&WordProvider, queries.clone())
+            .await
+            .is_err());
+        assert_eq!(
+            web_search_any(&WordProvider, queries).await.unwrap().len(),
+            1
+        );
+        assert!(matches!(
+            web_search_any(&WordProvider, vec![]).await,
+            Err(SearchError::InvalidInput(_))
+        ));
+    }
+}
@@ -0,0 +1,275 @@
+//! Per-provider request queues
+//!
+//! A queue spaces out searches sent to one provider so that bursts, such as a large
+//! [`web_search_batch`](crate::web_search_batch), go out at the provider's rate limit
+//! instead of failing with 429s. Searches wait their turn in FIFO order, or by
+//! [`SearchOptions::priority`](crate::SearchOptions::priority) with [`QueueOrder::Priority`].
+//! When a queue is at its maximum depth, further searches fail immediately with
+//! [`SearchError::QueueFull`] so callers can shed load.
+//!
+//! Queues apply to searches made through [`crate::web_search`], [`crate::web_search_batch`]
+//! and [`crate::multi_provider::MultiProviderSearch`], and are keyed by provider name.
+//! There are none by default. A multi-provider search stops waiting when its
+//! [deadline](crate::multi_provider::MultiProviderConfig::deadline) passes.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use websearch::queue::{self, ProviderQueue, QueueOrder};
+//!
+//! // At most 2 Brave searches per second, with up to 100 waiting
+//! queue::set_provider_queue(
+//!     "brave",
+//!     Some(ProviderQueue::new(2.0).with_max_depth(100).with_order(QueueOrder::Priority)),
+//! );
+//! assert_eq!(queue::queue_depth("brave"), 0);
+//! ```
+
+use crate::error::{SearchError, SearchResult as Result};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+use tokio::sync::Notify;
+use tokio::time::Instant;
+
+/// Order in which waiting searches are sent
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum QueueOrder {
+    /// First come, first served
+    #[default]
+    Fifo,
+    /// Highest [`SearchOptions::priority`](crate::SearchOptions::priority) first, then
+    /// first come, first served
+    Priority,
+}
+
+/// Rate and depth of a provider's request queue
+#[derive(Debug, Clone)]
+pub struct ProviderQueue {
+    interval: Duration,
+    max_depth: Option<usize>,
+    order: QueueOrder,
+}
+
+impl ProviderQueue {
+    /// Send at most `requests_per_second` searches to the provider
+    pub fn new(requests_per_second: f64) -> Self {
+        Self {
+            interval: Duration::from_secs_f64(1.0 / requests_per_second.max(0.001)),
+            max_depth: None,
+            order: QueueOrder::Fifo,
+        }
+    }
+
+    /// Fail searches with [`SearchError::QueueFull`] once `max_depth` are waiting
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = Some(max_depth);
+        self
+    }
+
+    pub fn with_order(mut self, order: QueueOrder) -> Self {
+        self.order = order;
+        self
+    }
+}
+
+struct Waiter {
+    seq: u64,
+    priority: i32,
+}
+
+struct QueueState {
+    waiting: Vec<Waiter>,
+    next_seq: u64,
+    /// Earliest time the next search may start
+    next_start: Instant,
+}
+
+impl QueueState {
+    /// The waiter whose turn is next
+    fn head(&self, order: QueueOrder) -> Option<u64> {
+        let head = match order {
+            QueueOrder::Fifo => self.waiting.iter().min_by_key(|w| w.seq),
+            QueueOrder::Priority => self
+                .waiting
+                .iter()
+                .min_by_key(|w| (std::cmp::Reverse(w.priority), w.seq)),
+        };
+        head.map(|w| w.seq)
+    }
+
+    fn remove(&mut self, seq: u64) {
+        self.waiting.retain(|w| w.seq != seq);
+    }
+}
+
+struct Queue {
+    config: ProviderQueue,
+    state: Mutex<QueueState>,
+    /// Woken whenever a waiter leaves, so the next one can check whether it is head
+    left: Notify,
+}
+
+enum Turn {
+    Go,
+    WaitUntil(Instant),
+    WaitForHead,
+}
+
+impl Queue {
+    fn turn(&self, seq: u64) -> Turn {
+        let mut state = self.state.lock().unwrap();
+        if state.head(self.config.order) != Some(seq) {
+            return Turn::WaitForHead;
+        }
+        let now = Instant::now();
+        if state.next_start > now {
+            return Turn::WaitUntil(state.next_start);
+        }
+        state.next_start = now + self.config.interval;
+        state.remove(seq);
+        Turn::Go
+    }
+}
+
+/// Takes a waiter out of the queue when its search starts or is cancelled
+struct Leave<'a> {
+    queue: &'a Queue,
+    seq: u64,
+}
+
+impl Drop for Leave<'_> {
+    fn drop(&mut self) {
+        self.queue.state.lock().unwrap().remove(self.seq);
+        self.queue.left.notify_waiters();
+    }
+}
+
+fn queues() -> &'static Mutex<HashMap<String, Arc<Queue>>> {
+    static QUEUES: OnceLock<Mutex<HashMap<String, Arc<Queue>>>> = OnceLock::new();
+    QUEUES.get_or_init(Default::default)
+}
+
+/// Queue searches sent to `provider` (`None` to remove its queue)
+///
+/// Searches already waiting in a replaced queue still go out at the old rate.
+pub fn set_provider_queue(provider: &str, queue: Option<ProviderQueue>) {
+    let mut queues = queues().lock().unwrap();
+    match queue {
+        Some(config) => {
+            let queue = Queue {
+                config,
+                state: Mutex::new(QueueState {
+                    waiting: Vec::new(),
+                    next_seq: 0,
+                    next_start: Instant::now(),
+                }),
+                left: Notify::new(),
+            };
+            queues.insert(provider.to_string(), Arc::new(queue));
+        }
+        None => {
+            queues.remove(provider);
+        }
+    }
+}
+
+/// Number of searches waiting in `provider`'s queue
+pub fn queue_depth(provider: &str) -> usize {
+    queues()
+        .lock()
+        .unwrap()
+        .get(provider)
+        .map_or(0, |queue| queue.state.lock().unwrap().waiting.len())
+}
+
+/// Wait until a search may be sent to `provider`, if it has a queue
+pub(crate) async fn wait_turn(provider: &str, priority: Option<i32>) -> Result<()> {
+    let Some(queue) = queues().lock().unwrap().get(provider).cloned() else {
+        return Ok(());
+    };
+
+    let seq = {
+        let mut state = queue.state.lock().unwrap();
+        if let Some(max_depth) = queue.config.max_depth {
+            if state.waiting.len() >= max_depth {
+                return Err(SearchError::QueueFull {
+                    provider: provider.to_string(),
+                    max_depth,
+                });
+            }
+        }
+        let seq = state.next_seq;
+        state.next_seq += 1;
+        state.waiting.push(Waiter {
+            seq,
+            priority: priority.unwrap_or(0),
+        });
+        seq
+    };
+    let _leave = Leave { queue: &queue, seq };
+
+    loop {
+        // Registered before checking, so a waiter leaving in between isn't missed
+        let left = queue.left.notified();
+        tokio::pin!(left);
+        left.as_mut().enable();
+
+        match queue.turn(seq) {
+            Turn::Go => return Ok(()),
+            Turn::WaitUntil(start) => tokio::time::sleep_until(start).await,
+            Turn::WaitForHead => left.await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_queue_spaces_requests_and_rejects_when_full() {
+        let provider = "queue-test";
+        set_provider_queue(provider, Some(ProviderQueue::new(20.0).with_max_depth(1)));
+
+        let started = std::time::Instant::now();
+        let (first, second, third) = tokio::join!(
+            wait_turn(provider, None),
+            wait_turn(provider, None),
+            wait_turn(provider, None),
+        );
+        assert!(first.is_ok() && second.is_ok());
+        assert!(matches!(
+            third,
+            Err(SearchError::QueueFull { max_depth: 1, .. })
+        ));
+        // The second search waited one interval (50ms) after the first
+        assert!(started.elapsed() >= Duration::from_millis(45));
+        assert_eq!(queue_depth(provider), 0);
+        set_provider_queue(provider, None);
+    }
+
+    #[tokio::test]
+    async fn test_priority_order() {
+        let provider = "priority-queue-test";
+        set_provider_queue(
+            provider,
+            Some(ProviderQueue::new(50.0).with_order(QueueOrder::Priority)),
+        );
+        // Take the first slot, so the others have to queue
+        wait_turn(provider, None).await.unwrap();
+
+        let order = Mutex::new(Vec::new());
+        let search = |priority| {
+            let order = &order;
+            async move {
+                wait_turn(provider, Some(priority)).await.unwrap();
+                order.lock().unwrap().push(priority);
+            }
+        };
+        tokio::join!(search(1), search(5), search(3));
+
+        assert_eq!(*order.lock().unwrap(), [5, 3, 1]);
+        set_provider_queue(provider, None);
+    }
+}
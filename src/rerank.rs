@@ -0,0 +1,212 @@
+//! Semantic reranking of search results
+//!
+//! Results merged from several providers come back grouped by provider, not by how well
+//! they answer the query. A [`Reranker`] reorders them by relevance to the query; set one
+//! with [`MultiProviderConfig::with_reranker`](crate::multi_provider::MultiProviderConfig::with_reranker)
+//! and aggregate searches rerank the merged results before truncating them to
+//! `max_results`.
+//!
+//! [`HttpReranker`] calls a hosted cross-encoder through the rerank API shared by Cohere,
+//! Jina and self-hosted servers that mimic it.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use websearch::multi_provider::{MultiProviderConfig, MultiProviderStrategy};
+//! use websearch::rerank::HttpReranker;
+//!
+//! let reranker = HttpReranker::new("https://api.jina.ai/v1/rerank")
+//!     .with_api_key("jina_...")
+//!     .with_model("jina-reranker-v2-base-multilingual");
+//! let config = MultiProviderConfig::new(MultiProviderStrategy::Aggregate).with_reranker(reranker);
+//! ```
+
+use crate::{
+    cassette,
+    error::{SearchError, SearchResult as Result},
+    types::SearchResult,
+};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Orders search results by relevance to a query
+#[async_trait::async_trait]
+pub trait Reranker: Send + Sync + std::fmt::Debug {
+    /// Return `results` reordered, most relevant to `query` first
+    ///
+    /// Implementations may drop results they consider irrelevant.
+    async fn rerank(&self, query: &str, results: Vec<SearchResult>) -> Result<Vec<SearchResult>>;
+}
+
+#[derive(Debug, Serialize)]
+struct RerankRequest<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    model: Option<&'a str>,
+    query: &'a str,
+    documents: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RerankResponse {
+    results: Vec<RankedDocument>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RankedDocument {
+    index: usize,
+    #[serde(alias = "score")]
+    relevance_score: f64,
+}
+
+/// Reranker backed by a Cohere-compatible `/rerank` HTTP endpoint
+///
+/// Sends `{"model", "query", "documents"}`, where each document is a result's title and
+/// snippet, and expects `{"results": [{"index", "relevance_score"}]}` back. Results the
+/// endpoint leaves out keep their original order after the ranked ones.
+#[derive(Debug, Clone)]
+pub struct HttpReranker {
+    endpoint: String,
+    api_key: Option<String>,
+    model: Option<String>,
+    timeout: Duration,
+    client: reqwest::Client,
+}
+
+impl HttpReranker {
+    /// Rerank through the API at `endpoint`, e.g. `https://api.cohere.com/v2/rerank`
+    pub fn new(endpoint: impl Into<String>) -> Self {
+        Self {
+            endpoint: endpoint.into(),
+            api_key: None,
+            model: None,
+            timeout: Duration::from_secs(10),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Send `api_key` as a bearer token
+    pub fn with_api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
+
+    /// Ask the endpoint for a specific reranking model
+    pub fn with_model(mut self, model: impl Into<String>) -> Self {
+        self.model = Some(model.into());
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+}
+
+#[async_trait::async_trait]
+impl Reranker for HttpReranker {
+    async fn rerank(&self, query: &str, results: Vec<SearchResult>) -> Result<Vec<SearchResult>> {
+        if results.len() < 2 {
+            return Ok(results);
+        }
+
+        let body = RerankRequest {
+            model: self.model.as_deref(),
+            query,
+            documents: results.iter().map(document).collect(),
+        };
+        let mut request = self
+            .client
+            .post(&self.endpoint)
+            .timeout(self.timeout)
+            .json(&body);
+        if let Some(api_key) = &self.api_key {
+            request = request.bearer_auth(api_key);
+        }
+
+        let response = cassette::send(request).await?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(SearchError::HttpError {
+                message: format!("Rerank request failed with status: {status}"),
+                status_code: Some(status.as_u16()),
+                response_body: response.text().await.ok(),
+            });
+        }
+        let ranked: RerankResponse = response
+            .json()
+            .await
+            .map_err(|e| SearchError::ParseError(format!("Invalid rerank response: {e}")))?;
+
+        let mut ranked = ranked.results;
+        ranked.sort_by(|a, b| b.relevance_score.total_cmp(&a.relevance_score));
+
+        let mut slots: Vec<Option<SearchResult>> = results.into_iter().map(Some).collect();
+        let mut reranked: Vec<SearchResult> = ranked
+            .iter()
+            .filter_map(|doc| slots.get_mut(doc.index).and_then(Option::take))
+            .collect();
+        reranked.extend(slots.into_iter().flatten());
+        Ok(reranked)
+    }
+}
+
+/// Text sent to the reranker for a result
+fn document(result: &SearchResult) -> String {
+    match &result.snippet {
+        Some(snippet) => format!("{}\n{}", result.title, snippet),
+        None => result.title.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::sample_results;
+    use wiremock::{
+        matchers::{body_json, header, method},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    #[tokio::test]
+    async fn test_http_reranker_orders_by_score() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(header("Authorization", "Bearer test-key"))
+            .and(body_json(serde_json::json!({
+                "model": "rerank-test",
+                "query": "rust",
+                "documents": [
+                    "mock Result 1\nmock content 1",
+                    "mock Result 2\nmock content 2",
+                    "mock Result 3\nmock content 3"
+                ]
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "results": [
+                    { "index": 0, "relevance_score": 0.2 },
+                    { "index": 2, "relevance_score": 0.9 }
+                ]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let reranker = HttpReranker::new(server.uri())
+            .with_api_key("test-key")
+            .with_model("rerank-test");
+        let results = reranker
+            .rerank("rust", sample_results("mock", 3))
+            .await
+            .unwrap();
+
+        let urls: Vec<_> = results.iter().map(|r| r.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            [
+                "https://mock.com/3",
+                "https://mock.com/1",
+                "https://mock.com/2"
+            ]
+        );
+    }
+}
@@ -0,0 +1,488 @@
+//! Deciding which providers a multi-provider search queries
+//!
+//! Every [`MultiProviderSearch`](crate::multi_provider::MultiProviderSearch) search starts by
+//! asking a [`RoutingStrategy`] for a [`RoutingPlan`]: the providers to query and whether to
+//! try them in turn, merge their results or take the first answer. The built-in strategies
+//! behind [`MultiProviderStrategy`](crate::multi_provider::MultiProviderStrategy) are
+//! implemented this way, and [`MultiProviderStrategy::Custom`] plugs in your own.
+//!
+//! [`MultiProviderStrategy::Custom`]: crate::multi_provider::MultiProviderStrategy::Custom
+//!
+//! # Examples
+//!
+//! ```rust
+//! use std::collections::HashMap;
+//! use std::sync::Arc;
+//! use websearch::multi_provider::{MultiProviderConfig, MultiProviderStrategy, ProviderStats};
+//! use websearch::routing::{RoutingPlan, RoutingStrategy};
+//! use websearch::SearchQuery;
+//!
+//! /// Papers go to arXiv, everything else to Google with DuckDuckGo as fallback
+//! #[derive(Debug)]
+//! struct AcademicRouting;
+//!
+//! #[async_trait::async_trait]
+//! impl RoutingStrategy for AcademicRouting {
+//!     async fn route(
+//!         &self,
+//!         _providers: &[&str],
+//!         query: &SearchQuery,
+//!         _stats: &HashMap<String, ProviderStats>,
+//!     ) -> RoutingPlan {
+//!         if query.query.contains("paper") {
+//!             RoutingPlan::aggregate(["arxiv", "google_scholar"])
+//!         } else {
+//!             RoutingPlan::failover(["google", "duckduckgo"])
+//!         }
+//!     }
+//! }
+//!
+//! let config = MultiProviderConfig::new(MultiProviderStrategy::Custom(Arc::new(AcademicRouting)));
+//! ```
+
+use crate::{
+    cache,
+    classify::{QueryClassifier, QueryKind, RuleClassifier},
+    multi_provider::ProviderStats,
+    types::SearchQuery,
+    utils::debug,
+};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// Providers chosen for one search, by name, and how to query them
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RoutingPlan {
+    /// Try the tiers in order until a provider succeeds
+    Failover {
+        /// Provider names tried tier by tier
+        tiers: Vec<Vec<String>>,
+        /// Query all providers in a tier at once and take the first success, instead of
+        /// one after another
+        parallel: bool,
+    },
+    /// Query every provider and merge their results
+    Aggregate(Vec<String>),
+    /// Take the results of the first provider that succeeds
+    Race(Vec<String>),
+}
+
+impl RoutingPlan {
+    /// Search with `provider` alone
+    pub fn single(provider: impl Into<String>) -> Self {
+        Self::Failover {
+            tiers: vec![vec![provider.into()]],
+            parallel: false,
+        }
+    }
+
+    /// Try `providers` one at a time, in order, until one succeeds
+    pub fn failover<I, S>(providers: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self::Failover {
+            tiers: providers.into_iter().map(|p| vec![p.into()]).collect(),
+            parallel: false,
+        }
+    }
+
+    /// Query all of `providers` and merge their results
+    pub fn aggregate<I, S>(providers: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self::Aggregate(providers.into_iter().map(Into::into).collect())
+    }
+
+    /// Take the first successful answer among `providers`
+    pub fn race<I, S>(providers: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self::Race(providers.into_iter().map(Into::into).collect())
+    }
+}
+
+/// Chooses the providers for each search
+#[async_trait::async_trait]
+pub trait RoutingStrategy: Send + Sync + std::fmt::Debug {
+    /// Plan the search for `query`
+    ///
+    /// `providers` holds the configured provider names in configuration order, and
+    /// `stats` their statistics so far. Names in the plan that aren't configured are
+    /// skipped with a warning.
+    async fn route(
+        &self,
+        providers: &[&str],
+        query: &SearchQuery,
+        stats: &HashMap<String, ProviderStats>,
+    ) -> RoutingPlan;
+}
+
+/// [`MultiProviderStrategy::Failover`](crate::multi_provider::MultiProviderStrategy::Failover):
+/// providers in tiers, tried tier by tier
+#[derive(Debug, Clone, Default)]
+pub struct FailoverRouting {
+    /// Provider names per tier; providers not listed form a final tier, and with no
+    /// tiers each provider is its own tier in configuration order
+    pub tiers: Vec<Vec<String>>,
+    /// Query all providers in a tier at once
+    pub parallel: bool,
+}
+
+impl FailoverRouting {
+    /// Provider names grouped into tiers, in the order they are tried
+    fn tiers(&self, providers: &[&str]) -> Vec<Vec<String>> {
+        if self.tiers.is_empty() {
+            return providers.iter().map(|p| vec![p.to_string()]).collect();
+        }
+
+        let mut assigned = vec![false; providers.len()];
+        let mut tiers: Vec<Vec<String>> = self
+            .tiers
+            .iter()
+            .map(|names| {
+                names
+                    .iter()
+                    .filter_map(|name| {
+                        let index = providers.iter().position(|p| p == name);
+                        if index.is_none() {
+                            log::warn!("Failover tier names unknown provider '{name}'");
+                        }
+                        index
+                    })
+                    .filter(|&i| !std::mem::replace(&mut assigned[i], true))
+                    .map(|i| providers[i].to_string())
+                    .collect()
+            })
+            .collect();
+
+        let rest: Vec<String> = (0..providers.len())
+            .filter(|&i| !assigned[i])
+            .map(|i| providers[i].to_string())
+            .collect();
+        tiers.push(rest);
+        tiers.retain(|tier| !tier.is_empty());
+        tiers
+    }
+}
+
+#[async_trait::async_trait]
+impl RoutingStrategy for FailoverRouting {
+    async fn route(
+        &self,
+        providers: &[&str],
+        _query: &SearchQuery,
+        _stats: &HashMap<String, ProviderStats>,
+    ) -> RoutingPlan {
+        RoutingPlan::Failover {
+            tiers: self.tiers(providers),
+            parallel: self.parallel,
+        }
+    }
+}
+
+/// [`MultiProviderStrategy::LoadBalance`](crate::multi_provider::MultiProviderStrategy::LoadBalance):
+/// one provider per search, round-robin
+///
+/// The rotation advances once per search, whatever the outcome, so failures and searches
+/// made with other strategies don't affect which provider is next. In
+/// [sticky](Self::sticky) mode there is no rotation: each query is hashed to a provider,
+/// so repeats of a query reach the same provider and its caches.
+#[derive(Debug, Default)]
+pub struct LoadBalanceRouting {
+    next: AtomicUsize,
+    sticky: bool,
+}
+
+impl LoadBalanceRouting {
+    /// Send each query to the same provider every time
+    ///
+    /// Queries are assigned by rendezvous hashing over the provider names, so restricting
+    /// a search to fewer providers only moves the queries of the providers left out.
+    pub fn sticky() -> Self {
+        Self {
+            sticky: true,
+            ..Default::default()
+        }
+    }
+
+    /// The provider `query` has affinity with
+    fn affinity<'a>(providers: &[&'a str], query: &SearchQuery) -> &'a str {
+        let key = query.query.trim().to_lowercase();
+        // A stable hash, so queries keep their provider across restarts and upgrades
+        let weight =
+            |provider: &str| cache::stable_hash(&format!("{key}\n{:?}\n{provider}", query.id_list));
+        providers
+            .iter()
+            .copied()
+            .max_by_key(|provider| weight(provider))
+            .expect("providers is not empty")
+    }
+}
+
+#[async_trait::async_trait]
+impl RoutingStrategy for LoadBalanceRouting {
+    async fn route(
+        &self,
+        providers: &[&str],
+        query: &SearchQuery,
+        _stats: &HashMap<String, ProviderStats>,
+    ) -> RoutingPlan {
+        if providers.is_empty() {
+            return RoutingPlan::failover(Vec::<String>::new());
+        }
+
+        let provider = if self.sticky {
+            Self::affinity(providers, query)
+        } else {
+            providers[self.next.fetch_add(1, Ordering::Relaxed) % providers.len()]
+        };
+
+        debug::log(&query.debug, "Load balancing to provider", provider);
+        RoutingPlan::single(provider)
+    }
+}
+
+/// [`MultiProviderStrategy::Aggregate`](crate::multi_provider::MultiProviderStrategy::Aggregate):
+/// every provider, results merged
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AggregateRouting;
+
+#[async_trait::async_trait]
+impl RoutingStrategy for AggregateRouting {
+    async fn route(
+        &self,
+        providers: &[&str],
+        _query: &SearchQuery,
+        _stats: &HashMap<String, ProviderStats>,
+    ) -> RoutingPlan {
+        RoutingPlan::aggregate(providers.iter().copied())
+    }
+}
+
+/// [`MultiProviderStrategy::RaceFirst`](crate::multi_provider::MultiProviderStrategy::RaceFirst):
+/// the first provider to succeed
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RaceRouting;
+
+#[async_trait::async_trait]
+impl RoutingStrategy for RaceRouting {
+    async fn route(
+        &self,
+        providers: &[&str],
+        _query: &SearchQuery,
+        _stats: &HashMap<String, ProviderStats>,
+    ) -> RoutingPlan {
+        RoutingPlan::race(providers.iter().copied())
+    }
+}
+
+/// [`MultiProviderStrategy::SmartRoute`](crate::multi_provider::MultiProviderStrategy::SmartRoute):
+/// providers picked by the [kind of query](crate::classify)
+///
+/// The query's kind decides which providers are tried first; the remaining providers
+/// follow in configuration order, so a search still fails over to them. By default paper-like
+/// queries go to arXiv, Google Scholar and Exa, time-sensitive ones to Tavily, Brave,
+/// SerpAPI and Google, and error messages to Google, Brave, SearXNG and DuckDuckGo.
+#[derive(Debug, Clone)]
+pub struct SmartRouting {
+    classifier: Arc<dyn QueryClassifier>,
+    routes: HashMap<QueryKind, Vec<String>>,
+}
+
+impl Default for SmartRouting {
+    fn default() -> Self {
+        let route = |names: &[&str]| names.iter().map(|n| n.to_string()).collect();
+        Self {
+            classifier: Arc::new(RuleClassifier::default()),
+            routes: HashMap::from([
+                (
+                    QueryKind::Academic,
+                    route(&["arxiv", "google_scholar", "exa"]),
+                ),
+                (
+                    QueryKind::News,
+                    route(&["tavily", "brave", "serpapi", "google"]),
+                ),
+                (
+                    QueryKind::Code,
+                    route(&["google", "brave", "searxng", "duckduckgo"]),
+                ),
+            ]),
+        }
+    }
+}
+
+impl SmartRouting {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Classify queries with `classifier`, e.g. an ML model, instead of the keyword rules
+    pub fn with_classifier(mut self, classifier: impl QueryClassifier + 'static) -> Self {
+        self.classifier = Arc::new(classifier);
+        self
+    }
+
+    /// Try `providers` first, in order, for queries of `kind`
+    pub fn with_route<I, S>(mut self, kind: QueryKind, providers: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.routes
+            .insert(kind, providers.into_iter().map(Into::into).collect());
+        self
+    }
+}
+
+#[async_trait::async_trait]
+impl RoutingStrategy for SmartRouting {
+    async fn route(
+        &self,
+        providers: &[&str],
+        query: &SearchQuery,
+        _stats: &HashMap<String, ProviderStats>,
+    ) -> RoutingPlan {
+        let kind = self.classifier.classify(query).await;
+        let preferred: Vec<&str> = self
+            .routes
+            .get(&kind)
+            .into_iter()
+            .flatten()
+            .map(String::as_str)
+            .filter(|name| providers.contains(name))
+            .collect();
+
+        debug::log(
+            &query.debug,
+            &format!("Routing {kind:?} query"),
+            &preferred.join(", "),
+        );
+        let rest = providers.iter().filter(|name| !preferred.contains(name));
+        RoutingPlan::failover(preferred.iter().chain(rest).copied())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    const PROVIDERS: [&str; 4] = ["duckduckgo", "google", "arxiv", "brave"];
+
+    #[test]
+    fn test_failover_tiers() {
+        assert_eq!(
+            FailoverRouting::default().tiers(&PROVIDERS),
+            [["duckduckgo"], ["google"], ["arxiv"], ["brave"]]
+        );
+
+        let routing = FailoverRouting {
+            tiers: vec![
+                vec!["google".into(), "brave".into(), "missing".into()],
+                vec!["duckduckgo".into(), "google".into()],
+            ],
+            parallel: false,
+        };
+        assert_eq!(
+            routing.tiers(&PROVIDERS),
+            [vec!["google", "brave"], vec!["duckduckgo"], vec!["arxiv"]]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_load_balance_rotation_ignores_stats() {
+        let query = SearchQuery::new("rust");
+        let mut stats = HashMap::new();
+        let routing = LoadBalanceRouting::default();
+
+        let plan = routing.route(&PROVIDERS, &query, &stats).await;
+        assert_eq!(plan, RoutingPlan::single("duckduckgo"));
+
+        let mut duckduckgo = ProviderStats::default();
+        duckduckgo.total_requests = 7;
+        stats.insert("duckduckgo".to_string(), duckduckgo);
+        let plan = routing.route(&PROVIDERS, &query, &stats).await;
+        assert_eq!(plan, RoutingPlan::single("google"));
+
+        // The rotation wraps within whatever subset the search is restricted to
+        let plan = routing.route(&["arxiv", "brave"], &query, &stats).await;
+        assert_eq!(plan, RoutingPlan::single("arxiv"));
+    }
+
+    #[tokio::test]
+    async fn test_sticky_load_balance_keeps_query_affinity() {
+        let stats = HashMap::new();
+        let routing = LoadBalanceRouting::sticky();
+        let provider = |plan: RoutingPlan| match plan {
+            RoutingPlan::Failover { tiers, .. } => tiers[0][0].clone(),
+            plan => panic!("unexpected plan {plan:?}"),
+        };
+
+        let mut chosen = HashSet::new();
+        for i in 0..32 {
+            let query = SearchQuery::new(format!("query {i}"));
+            let first = provider(routing.route(&PROVIDERS, &query, &stats).await);
+            let again = provider(routing.route(&PROVIDERS, &query, &stats).await);
+            assert_eq!(first, again);
+
+            // Dropping another provider doesn't move the query
+            let others: Vec<&str> = PROVIDERS
+                .into_iter()
+                .filter(|p| *p == first || *p != PROVIDERS[(i % 4) as usize])
+                .collect();
+            assert_eq!(
+                provider(routing.route(&others, &query, &stats).await),
+                first
+            );
+            chosen.insert(first);
+        }
+        assert!(chosen.len() > 1, "every query went to {chosen:?}");
+
+        // The hash is fixed, so assignments survive restarts and new Rust versions
+        let query = SearchQuery::new("query 0");
+        assert_eq!(
+            provider(routing.route(&PROVIDERS, &query, &stats).await),
+            "brave"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_smart_routing_prefers_providers_for_the_query_kind() {
+        let stats = HashMap::new();
+        let routing = SmartRouting::new();
+
+        let plan = routing
+            .route(
+                &PROVIDERS,
+                &SearchQuery::new("diffusion models paper"),
+                &stats,
+            )
+            .await;
+        assert_eq!(
+            plan,
+            RoutingPlan::failover(["arxiv", "duckduckgo", "google", "brave"])
+        );
+
+        let plan = routing
+            .route(&PROVIDERS, &SearchQuery::new("banana bread"), &stats)
+            .await;
+        assert_eq!(plan, RoutingPlan::failover(PROVIDERS));
+
+        let routing = routing.with_route(QueryKind::General, ["brave"]);
+        let plan = routing
+            .route(&PROVIDERS, &SearchQuery::new("banana bread"), &stats)
+            .await;
+        assert_eq!(
+            plan,
+            RoutingPlan::failover(["brave", "duckduckgo", "google", "arxiv"])
+        );
+    }
+}
@@ -0,0 +1,123 @@
+//! Client-side safe-search fallback
+//!
+//! Google, Brave and SerpAPI filter adult content themselves when asked to. The other
+//! providers (DuckDuckGo scraping, SearXNG instances, Tavily, ...) don't, or not reliably,
+//! so when [`SafeSearch::Strict`] is requested their results are additionally checked
+//! against a blocklist of adult domains. This gives the option the same meaning whichever
+//! provider answers.
+//!
+//! Providers declare native support with [`SearchProvider::supports_safe_search`].
+
+use crate::types::{SafeSearch, SearchProvider, SearchResult};
+use url::Url;
+
+/// Adult-content domains dropped from strict safe-search results; subdomains match too
+pub const BLOCKED_DOMAINS: &[&str] = &[
+    "pornhub.com",
+    "xvideos.com",
+    "xnxx.com",
+    "xhamster.com",
+    "redtube.com",
+    "youporn.com",
+    "tube8.com",
+    "spankbang.com",
+    "youjizz.com",
+    "porn.com",
+    "brazzers.com",
+    "onlyfans.com",
+    "fansly.com",
+    "chaturbate.com",
+    "stripchat.com",
+    "bongacams.com",
+    "livejasmin.com",
+    "cam4.com",
+    "myfreecams.com",
+    "adultfriendfinder.com",
+    "eporner.com",
+    "motherless.com",
+    "hentaihaven.xxx",
+    "nhentai.net",
+    "rule34.xxx",
+    "e-hentai.org",
+    "literotica.com",
+];
+
+/// Top-level domains reserved for adult content
+const BLOCKED_TLDS: &[&str] = &["xxx", "adult", "porn", "sex"];
+
+/// Whether `host` is, or is a subdomain of, a blocked adult domain
+pub fn is_blocked_domain(host: &str) -> bool {
+    let host = host.trim_end_matches('.').to_lowercase();
+    let tld = host.rsplit('.').next().unwrap_or_default();
+    BLOCKED_TLDS.contains(&tld)
+        || BLOCKED_DOMAINS.iter().any(|domain| {
+            host == *domain
+                || host
+                    .strip_suffix(domain)
+                    .is_some_and(|prefix| prefix.ends_with('.'))
+        })
+}
+
+/// Drop results whose URL points to a blocked adult domain
+pub fn retain_safe(results: &mut Vec<SearchResult>) {
+    results.retain(|result| {
+        let host = Url::parse(&result.url)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_string));
+        !host.is_some_and(|host| is_blocked_domain(&host))
+    });
+}
+
+/// Apply the blocklist when strict safe search was requested from a provider that doesn't
+/// filter on its own
+pub(crate) fn enforce(
+    provider: &dyn SearchProvider,
+    safe_search: Option<&SafeSearch>,
+    results: &mut Vec<SearchResult>,
+) {
+    if safe_search == Some(&SafeSearch::Strict) && !provider.supports_safe_search() {
+        retain_safe(results);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::MockProvider;
+
+    fn result(url: &str) -> SearchResult {
+        SearchResult {
+            url: url.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_blocked_domains_and_subdomains() {
+        assert!(is_blocked_domain("pornhub.com"));
+        assert!(is_blocked_domain("www.PornHub.com."));
+        assert!(is_blocked_domain("anything.xxx"));
+        assert!(!is_blocked_domain("notpornhub.com"));
+        assert!(!is_blocked_domain("example.com"));
+    }
+
+    #[test]
+    fn test_enforce_only_for_strict_without_native_support() {
+        let all = || {
+            vec![
+                result("https://www.rust-lang.org/"),
+                result("https://www.xvideos.com/video1"),
+                result("not a url"),
+            ]
+        };
+        let provider = MockProvider::new("mock");
+
+        let mut results = all();
+        enforce(&provider, Some(&SafeSearch::Moderate), &mut results);
+        assert_eq!(results.len(), 3);
+
+        enforce(&provider, Some(&SafeSearch::Strict), &mut results);
+        let urls: Vec<_> = results.iter().map(|r| r.url.as_str()).collect();
+        assert_eq!(urls, ["https://www.rust-lang.org/", "not a url"]);
+    }
+}
@@ -0,0 +1,281 @@
+//! Scholarly metadata
+//!
+//! Academic providers know more about a paper than [`SearchResult`] holds: its authors,
+//! DOI, venue and citation count. [`ScholarlyProvider`]s return it typed, as
+//! [`ScholarlyResult`]s. Converting one into a `SearchResult` keeps the metadata in `raw`,
+//! where [`ScholarlyResult::from_result`] reads it back, so the same providers work with
+//! `web_search` and everything built on it.
+//!
+//! arXiv and Google Scholar implement [`ScholarlyProvider`].
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use websearch::{providers::ArxivProvider, scholarly::ScholarlyProvider, SearchOptions};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let papers = ArxivProvider::new()
+//!     .search_scholarly(&SearchOptions {
+//!         query: "attention is all you need".to_string(),
+//!         ..Default::default()
+//!     })
+//!     .await?;
+//! for paper in papers {
+//!     println!("{} ({}) doi:{:?}", paper.title, paper.authors.join(", "), paper.doi);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{
+    error::SearchResult as Result,
+    format::escape_bibtex,
+    types::{SearchOptions, SearchProvider, SearchResult},
+    utils::http::extract_domain,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// A paper or other scholarly work with its bibliographic metadata
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ScholarlyResult {
+    /// Title of the work
+    pub title: String,
+    /// Landing page of the work
+    pub url: String,
+    /// Author names in the order listed
+    #[serde(default)]
+    pub authors: Vec<String>,
+    /// Digital Object Identifier, e.g. "10.48550/arXiv.1706.03762"
+    #[serde(default)]
+    pub doi: Option<String>,
+    /// Abstract, or the provider's snippet when it has no abstract (Google Scholar);
+    /// serialized as `abstract`
+    #[serde(default, rename = "abstract")]
+    pub abstract_text: Option<String>,
+    /// Journal, conference or other venue it was published in
+    #[serde(default)]
+    pub venue: Option<String>,
+    /// Year it was published
+    #[serde(default)]
+    pub year: Option<u16>,
+    /// When it was published, as the provider reports it
+    #[serde(default)]
+    pub published_date: Option<String>,
+    /// Number of works citing it
+    #[serde(default)]
+    pub citations: Option<u64>,
+    /// Direct link to the full text as a PDF
+    #[serde(default)]
+    pub pdf_url: Option<String>,
+    /// arXiv identifier with version, e.g. "1706.03762v7"
+    #[serde(default)]
+    pub arxiv_id: Option<String>,
+    /// Subject categories, e.g. arXiv's "cs.CL"
+    #[serde(default)]
+    pub categories: Vec<String>,
+    /// The provider that returned it; taken from the `SearchResult` rather than stored
+    #[serde(skip)]
+    pub provider: Option<Arc<str>>,
+}
+
+impl ScholarlyResult {
+    /// Read the metadata back from a search result converted from a `ScholarlyResult`, or
+    /// from a scholarly provider that stores the same fields in `raw`
+    ///
+    /// Returns `None` for other results, and when `raw` was dropped (see
+    /// `SearchOptions::include_raw`).
+    pub fn from_result(result: &SearchResult) -> Option<Self> {
+        let scholarly: Self = serde_json::from_value(result.raw.clone()?).ok()?;
+        Some(Self {
+            provider: result.provider.clone(),
+            ..scholarly
+        })
+    }
+
+    /// Citation key in the usual "author, year, first title word" form, e.g.
+    /// "vaswani2017attention"
+    pub fn citation_key(&self) -> String {
+        let ascii_word = |word: &str| -> String {
+            word.chars()
+                .filter(char::is_ascii_alphanumeric)
+                .collect::<String>()
+                .to_lowercase()
+        };
+        let author = self
+            .authors
+            .first()
+            .and_then(|author| author.split_whitespace().next_back())
+            .map(ascii_word)
+            .unwrap_or_default();
+        let word = self
+            .title
+            .split_whitespace()
+            .map(ascii_word)
+            .find(|word| !word.is_empty() && !KEY_STOPWORDS.contains(&word.as_str()))
+            .unwrap_or_default();
+        let year = self.year.map(|year| year.to_string()).unwrap_or_default();
+
+        let key = format!("{author}{year}{word}");
+        if key.is_empty() {
+            "untitled".to_string()
+        } else {
+            key
+        }
+    }
+
+    /// Render as a BibTeX entry with the citation key `key`
+    ///
+    /// Works with a venue are `@article`s, the rest `@misc`. arXiv preprints get the
+    /// `eprint`, `archivePrefix` and `primaryClass` fields arXiv's own export uses.
+    pub fn to_bibtex(&self, key: &str) -> String {
+        let text =
+            |value: &str| escape_bibtex(&value.split_whitespace().collect::<Vec<_>>().join(" "));
+        // URLs and DOIs are written as-is; only braces would unbalance the field
+        let verbatim = |value: &str| value.replace('{', "%7B").replace('}', "%7D");
+
+        let mut fields = vec![("title", text(&self.title))];
+        if !self.authors.is_empty() {
+            let authors: Vec<String> = self.authors.iter().map(|author| text(author)).collect();
+            fields.push(("author", authors.join(" and ")));
+        }
+        if let Some(venue) = &self.venue {
+            fields.push(("journal", text(venue)));
+        }
+        if let Some(year) = self.year {
+            fields.push(("year", year.to_string()));
+        }
+        if let Some(doi) = &self.doi {
+            fields.push(("doi", verbatim(doi)));
+        }
+        if let Some(arxiv_id) = &self.arxiv_id {
+            // Cite the paper rather than one version of it
+            let unversioned = match arxiv_id.rsplit_once('v') {
+                Some((id, version)) if version.parse::<u32>().is_ok() => id,
+                _ => arxiv_id,
+            };
+            fields.push(("eprint", verbatim(unversioned)));
+            fields.push(("archivePrefix", "arXiv".to_string()));
+            if let Some(category) = self.categories.first() {
+                fields.push(("primaryClass", text(category)));
+            }
+        }
+        fields.push(("url", verbatim(&self.url)));
+
+        let entry_type = if self.venue.is_some() {
+            "article"
+        } else {
+            "misc"
+        };
+        let mut entry = format!("@{entry_type}{{{key},\n");
+        for (name, value) in fields {
+            entry.push_str(&format!("  {name} = {{{value}}},\n"));
+        }
+        entry.push_str("}\n");
+        entry
+    }
+}
+
+/// Title words skipped when picking the one for a citation key
+const KEY_STOPWORDS: [&str; 5] = ["a", "an", "the", "on", "of"];
+
+impl From<ScholarlyResult> for SearchResult {
+    fn from(result: ScholarlyResult) -> Self {
+        let raw = serde_json::to_value(&result).ok();
+        SearchResult {
+            domain: extract_domain(&result.url),
+            url: result.url,
+            title: result.title,
+            snippet: result.abstract_text,
+            published_date: result
+                .published_date
+                .or_else(|| result.year.map(|year| year.to_string())),
+            provider: result.provider,
+            raw,
+            ..Default::default()
+        }
+    }
+}
+
+/// A search provider for academic works that can return their full metadata
+#[async_trait::async_trait]
+pub trait ScholarlyProvider: SearchProvider {
+    /// Search for works matching `options`, with their bibliographic metadata
+    async fn search_scholarly(&self, options: &SearchOptions) -> Result<Vec<ScholarlyResult>>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trips_through_search_result() {
+        let paper = ScholarlyResult {
+            title: "Attention Is All You Need".to_string(),
+            url: "https://arxiv.org/abs/1706.03762v7".to_string(),
+            authors: vec!["Ashish Vaswani".to_string(), "Noam Shazeer".to_string()],
+            doi: Some("10.48550/arXiv.1706.03762".to_string()),
+            abstract_text: Some("The dominant sequence transduction models".to_string()),
+            year: Some(2017),
+            provider: Some("arxiv".into()),
+            ..Default::default()
+        };
+
+        let result = SearchResult::from(paper.clone());
+        assert_eq!(result.snippet, paper.abstract_text);
+        assert_eq!(result.domain.as_deref(), Some("arxiv.org"));
+        assert_eq!(result.published_date.as_deref(), Some("2017"));
+        assert_eq!(
+            result.raw.as_ref().unwrap()["abstract"],
+            "The dominant sequence transduction models"
+        );
+        assert_eq!(ScholarlyResult::from_result(&result).unwrap(), paper);
+
+        let plain = SearchResult::default();
+        assert_eq!(ScholarlyResult::from_result(&plain), None);
+    }
+
+    #[test]
+    fn test_to_bibtex() {
+        let paper = ScholarlyResult {
+            title: "Attention Is All You Need:\n  Transformers & {Friends}".to_string(),
+            url: "https://arxiv.org/abs/1706.03762v7".to_string(),
+            authors: vec!["Ashish Vaswani".to_string(), "Noam Shazeer".to_string()],
+            doi: Some("10.48550/arXiv.1706.03762".to_string()),
+            year: Some(2017),
+            arxiv_id: Some("1706.03762v7".to_string()),
+            categories: vec!["cs.CL".to_string(), "cs.LG".to_string()],
+            ..Default::default()
+        };
+
+        assert_eq!(paper.citation_key(), "vaswani2017attention");
+        assert_eq!(
+            paper.to_bibtex("vaswani2017attention"),
+            "@misc{vaswani2017attention,\n\
+             \x20 title = {Attention Is All You Need: Transformers \\& \\{Friends\\}},\n\
+             \x20 author = {Ashish Vaswani and Noam Shazeer},\n\
+             \x20 year = {2017},\n\
+             \x20 doi = {10.48550/arXiv.1706.03762},\n\
+             \x20 eprint = {1706.03762},\n\
+             \x20 archivePrefix = {arXiv},\n\
+             \x20 primaryClass = {cs.CL},\n\
+             \x20 url = {https://arxiv.org/abs/1706.03762v7},\n\
+             }\n"
+        );
+
+        let article = ScholarlyResult {
+            title: "The Nature of Things".to_string(),
+            venue: Some("Nature".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(article.citation_key(), "nature");
+        assert!(article
+            .to_bibtex("nature")
+            .starts_with("@article{nature,\n"));
+        assert!(article
+            .to_bibtex("nature")
+            .contains("  journal = {Nature},\n"));
+        assert_eq!(ScholarlyResult::default().citation_key(), "untitled");
+    }
+}
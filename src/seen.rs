@@ -0,0 +1,206 @@
+//! Excluding results already seen
+//!
+//! Iterative research loops ask for "more results I haven't seen" by passing the URLs they
+//! already have as `SearchOptions::exclude_urls`. No provider takes such a list, so matching
+//! results are dropped after the search. Providers that
+//! [paginate](crate::SearchProvider::supports_pagination) are then asked for deeper pages
+//! (up to [`MAX_BACKFILL_PAGES`] more, within the search's timeout) until `max_results` new
+//! results are found or a page brings nothing new. Multi-provider and batch searches only
+//! drop them.
+//!
+//! With the `store` feature, `ResultStore::seen_urls` lists every URL an archive has
+//! recorded, so a research session can exclude everything it has already been shown.
+//!
+//! URLs are compared as by [`Dedup`](crate::processors::Dedup), so
+//! `http://www.example.com/a/` excludes `https://example.com/a`.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use std::collections::HashSet;
+//! use websearch::{seen, testing::sample_results};
+//!
+//! let mut results = sample_results("mock", 3);
+//! let seen_urls: HashSet<String> = HashSet::from([results[0].url.clone()]);
+//!
+//! seen::retain_unseen(&mut results, &seen_urls);
+//! assert_eq!(results.len(), 2);
+//! ```
+
+use crate::{processors::dedup_key, types::SearchResult};
+use std::collections::HashSet;
+
+/// How many pages past the requested one a search fetches to replace excluded results
+pub const MAX_BACKFILL_PAGES: u32 = 3;
+
+/// Drop results whose URL is in `seen`
+pub fn retain_unseen(results: &mut Vec<SearchResult>, seen: &HashSet<String>) {
+    if seen.is_empty() {
+        return;
+    }
+    let seen: HashSet<String> = seen.iter().map(|url| dedup_key(url)).collect();
+    results.retain(|result| !seen.contains(&dedup_key(&result.url)));
+}
+
+/// Apply `exclude_urls` to a provider's results
+pub(crate) fn enforce(exclude_urls: Option<&HashSet<String>>, results: &mut Vec<SearchResult>) {
+    if let Some(exclude_urls) = exclude_urls {
+        retain_unseen(results, exclude_urls);
+    }
+}
+
+/// Add the results of a deeper page that aren't in `results` yet
+pub(crate) fn extend_unseen(results: &mut Vec<SearchResult>, more: Vec<SearchResult>) {
+    let mut have: HashSet<String> = results.iter().map(|r| dedup_key(&r.url)).collect();
+    results.extend(
+        more.into_iter()
+            .filter(|result| have.insert(dedup_key(&result.url))),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        testing::MockProvider,
+        types::{SearchOptions, SearchQuery},
+        web_search, web_search_batch,
+    };
+    use std::time::Duration;
+
+    fn result(url: &str) -> SearchResult {
+        SearchResult {
+            url: url.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_excluded_results_are_backfilled_from_deeper_pages() {
+        let provider = MockProvider::new("mock")
+            .then_return(Ok(vec![
+                result("https://a.com/"),
+                result("https://b.com/"),
+                result("https://c.com/"),
+            ]))
+            .then_return(Ok(vec![
+                result("https://www.b.com"),
+                result("https://c.com"),
+                result("https://d.com/"),
+            ]))
+            .then_return(Ok(vec![result("https://e.com/")]))
+            .with_results(vec![]);
+
+        let results = web_search(SearchOptions {
+            query: "rust".to_string(),
+            max_results: Some(3),
+            exclude_urls: Some(HashSet::from([
+                "http://a.com".to_string(),
+                "https://b.com/".to_string(),
+            ])),
+            provider: Box::new(provider.clone()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+        let urls: Vec<&str> = results.iter().map(|r| r.url.as_str()).collect();
+        assert_eq!(urls, ["https://c.com/", "https://d.com/", "https://e.com/"]);
+        assert_eq!(provider.call_count(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_no_backfill_from_providers_that_ignore_page() {
+        let provider = MockProvider::new("mock")
+            .with_pagination(false)
+            .with_results(vec![result("https://a.com/"), result("https://b.com/")]);
+
+        let results = web_search(SearchOptions {
+            query: "rust".to_string(),
+            exclude_urls: Some(HashSet::from(["https://a.com/".to_string()])),
+            provider: Box::new(provider.clone()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(provider.call_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_backfill_stops_when_a_page_repeats() {
+        // Claims to paginate but answers every page with the same results
+        let provider = MockProvider::new("mock")
+            .with_results(vec![result("https://a.com/"), result("https://b.com/")]);
+
+        let results = web_search(SearchOptions {
+            query: "rust".to_string(),
+            exclude_urls: Some(HashSet::from(["https://a.com/".to_string()])),
+            provider: Box::new(provider.clone()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(provider.call_count(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_backfill_shares_the_search_timeout() {
+        let provider = MockProvider::new("mock")
+            .then_return(Ok(vec![result("https://a.com/"), result("https://b.com/")]))
+            .with_results(vec![result("https://c.com/")])
+            .with_delay(Duration::from_millis(60));
+
+        let results = web_search(SearchOptions {
+            query: "rust".to_string(),
+            exclude_urls: Some(HashSet::from(["https://a.com/".to_string()])),
+            request_timeout: Some(Duration::from_millis(100)),
+            provider: Box::new(provider.clone()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+        // The second page would finish at 120ms, past the search's 100ms timeout
+        assert_eq!(results.len(), 1);
+        assert_eq!(provider.call_count(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_backfill_stops_when_the_provider_runs_out() {
+        let provider = MockProvider::new("mock")
+            .then_return(Ok(vec![result("https://a.com/"), result("https://b.com/")]))
+            .with_results(vec![]);
+
+        let results = web_search(SearchOptions {
+            query: "rust".to_string(),
+            exclude_urls: Some(HashSet::from(["https://a.com/".to_string()])),
+            provider: Box::new(provider.clone()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(provider.call_count(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_batch_searches_drop_excluded_urls() {
+        let provider = MockProvider::new("mock")
+            .with_results(vec![result("https://a.com/"), result("https://b.com/")]);
+        let query = SearchQuery {
+            exclude_urls: Some(HashSet::from(["https://a.com".to_string()])),
+            ..SearchQuery::new("rust")
+        };
+
+        let batch = web_search_batch(&provider, vec![query], 1).await;
+
+        let results = batch[0].result.as_ref().unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].url, "https://b.com/");
+    }
+}
@@ -0,0 +1,320 @@
+//! Self-hosted meta-search REST API
+//!
+//! [`router`] exposes a [`MultiProviderSearch`] over HTTP:
+//!
+//! - `GET /search?q=...` runs the configured strategy; add `provider=<name>` to query a
+//!   single provider. `max_results`, `page`, `language` and `region` are passed through.
+//! - `POST /search` takes a JSON [`SearchQuery`](crate::types::SearchQuery) body instead
+//!   (`provider` still goes in the query string)
+//! - `GET /providers` lists the configured providers with their statistics
+//! - `GET /healthz` returns `{"status": "ok"}`
+//!
+//! Errors are returned as `{"error": "..."}` with a matching status code.
+
+use crate::{
+    error::SearchError,
+    multi_provider::{MultiProviderSearch, MultiSearchResponse, ProviderStats, SearchOptionsMulti},
+    types::SearchResult,
+};
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use std::{net::SocketAddr, sync::Arc};
+
+#[derive(Debug, Deserialize)]
+struct SearchParams {
+    q: Option<String>,
+    provider: Option<String>,
+    /// Comma-separated providers the strategy may use
+    providers: Option<String>,
+    max_results: Option<u32>,
+    page: Option<u32>,
+    language: Option<String>,
+    region: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct SearchBody {
+    query: String,
+    provider: Option<String>,
+    results: Vec<SearchResult>,
+    /// Set when the search deadline cut the search short
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    partial: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct ProviderBody {
+    name: String,
+    stats: ProviderStats,
+}
+
+struct ApiError(SearchError);
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let status = match self.0.root_cause() {
+            SearchError::InvalidInput(_) | SearchError::Unsupported(_) => StatusCode::BAD_REQUEST,
+            SearchError::RateLimit(_)
+            | SearchError::BudgetExceeded { .. }
+            | SearchError::QueueFull { .. } => StatusCode::TOO_MANY_REQUESTS,
+            SearchError::Timeout { .. } => StatusCode::GATEWAY_TIMEOUT,
+            _ => StatusCode::BAD_GATEWAY,
+        };
+
+        (
+            status,
+            Json(serde_json::json!({ "error": self.0.report() })),
+        )
+            .into_response()
+    }
+}
+
+/// Build the REST API router for `search`
+pub fn router(search: MultiProviderSearch) -> Router {
+    Router::new()
+        .route("/search", get(search_handler).post(search_json_handler))
+        .route("/providers", get(providers_handler))
+        .route("/healthz", get(healthz_handler))
+        .with_state(Arc::new(search))
+}
+
+/// Serve the REST API on `addr` until the process is stopped
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use websearch::{
+///     multi_provider::{MultiProviderConfig, MultiProviderSearch, MultiProviderStrategy},
+///     providers::DuckDuckGoProvider,
+///     server::serve,
+/// };
+///
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let config = MultiProviderConfig::new(MultiProviderStrategy::Failover)
+///     .add_provider(Box::new(DuckDuckGoProvider::new()));
+/// serve("127.0.0.1:8080".parse()?, MultiProviderSearch::new(config)).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn serve(addr: SocketAddr, search: MultiProviderSearch) -> std::io::Result<()> {
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    axum::serve(listener, router(search)).await
+}
+
+async fn search_handler(
+    State(search): State<Arc<MultiProviderSearch>>,
+    Query(params): Query<SearchParams>,
+) -> Result<Json<SearchBody>, ApiError> {
+    let query = params.q.filter(|q| !q.trim().is_empty()).ok_or_else(|| {
+        ApiError(SearchError::InvalidInput(
+            "Missing query parameter q".to_string(),
+        ))
+    })?;
+
+    let options = SearchOptionsMulti {
+        query: query.clone(),
+        max_results: params.max_results,
+        page: params.page,
+        language: params.language,
+        region: params.region,
+        providers: params.providers.map(|names| {
+            names
+                .split(',')
+                .map(|name| name.trim().to_string())
+                .collect()
+        }),
+        ..Default::default()
+    };
+
+    let response = match &params.provider {
+        Some(provider) => search
+            .search_provider(provider, &options)
+            .await
+            .map(MultiSearchResponse::from),
+        None => search.search_response(&options).await,
+    }
+    .map_err(ApiError)?;
+
+    Ok(Json(SearchBody {
+        query,
+        provider: params.provider,
+        results: response.results,
+        partial: response.partial,
+    }))
+}
+
+#[derive(Debug, Deserialize)]
+struct ProviderParam {
+    provider: Option<String>,
+}
+
+async fn search_json_handler(
+    State(search): State<Arc<MultiProviderSearch>>,
+    Query(params): Query<ProviderParam>,
+    Json(options): Json<SearchOptionsMulti>,
+) -> Result<Json<SearchBody>, ApiError> {
+    if options.query.trim().is_empty() && options.id_list.is_none() {
+        return Err(ApiError(SearchError::InvalidInput(
+            "A query or id_list is required".to_string(),
+        )));
+    }
+
+    let response = match &params.provider {
+        Some(provider) => search
+            .search_provider(provider, &options)
+            .await
+            .map(MultiSearchResponse::from),
+        None => search.search_response(&options).await,
+    }
+    .map_err(ApiError)?;
+
+    Ok(Json(SearchBody {
+        query: options.query,
+        provider: params.provider,
+        results: response.results,
+        partial: response.partial,
+    }))
+}
+
+async fn providers_handler(
+    State(search): State<Arc<MultiProviderSearch>>,
+) -> Json<Vec<ProviderBody>> {
+    let mut stats = search.get_stats();
+    Json(
+        search
+            .provider_names()
+            .into_iter()
+            .map(|name| ProviderBody {
+                name: name.to_string(),
+                stats: stats.remove(name).unwrap_or_default(),
+            })
+            .collect(),
+    )
+}
+
+async fn healthz_handler() -> Json<serde_json::Value> {
+    Json(serde_json::json!({ "status": "ok" }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        error::SearchResult as Result,
+        multi_provider::{MultiProviderConfig, MultiProviderStrategy},
+        types::{SearchOptions, SearchProvider},
+    };
+
+    #[derive(Debug)]
+    struct StaticProvider(&'static str);
+
+    #[async_trait::async_trait]
+    impl SearchProvider for StaticProvider {
+        fn name(&self) -> &str {
+            self.0
+        }
+
+        async fn search(&self, options: &SearchOptions) -> Result<Vec<SearchResult>> {
+            Ok(vec![SearchResult {
+                title: format!("{} result for {}", self.0, options.query),
+                url: format!("https://{}.example/1", self.0),
+                provider: Some(self.0.into()),
+                ..Default::default()
+            }])
+        }
+    }
+
+    async fn spawn_server() -> String {
+        let config = MultiProviderConfig::new(MultiProviderStrategy::Failover)
+            .add_provider(Box::new(StaticProvider("first")))
+            .add_provider(Box::new(StaticProvider("second")));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app = router(MultiProviderSearch::new(config));
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        format!("http://{addr}")
+    }
+
+    #[tokio::test]
+    async fn test_search_endpoints() {
+        let base = spawn_server().await;
+        let client = reqwest::Client::new();
+
+        let body: serde_json::Value = client
+            .get(format!("{base}/search?q=rust"))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(body["results"][0]["title"], "first result for rust");
+
+        let body: serde_json::Value = client
+            .get(format!("{base}/search?q=rust&provider=second"))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(body["provider"], "second");
+        assert_eq!(body["results"][0]["title"], "second result for rust");
+
+        let response = client
+            .get(format!("{base}/search?q=rust&provider=missing"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 400);
+
+        let response = client.get(format!("{base}/search")).send().await.unwrap();
+        assert_eq!(response.status(), 400);
+
+        let providers: serde_json::Value = client
+            .get(format!("{base}/providers"))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(providers[0]["name"], "first");
+        assert_eq!(providers[0]["stats"]["total_requests"], 1);
+        assert_eq!(providers[1]["stats"]["total_requests"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_search_with_json_body() {
+        let base = spawn_server().await;
+        let body: serde_json::Value = reqwest::Client::new()
+            .post(format!("{base}/search?provider=second"))
+            .json(&serde_json::json!({ "query": "tokio", "max_results": 3 }))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(body["results"][0]["title"], "second result for tokio");
+    }
+
+    #[tokio::test]
+    async fn test_healthz() {
+        let base = spawn_server().await;
+        let body: serde_json::Value = reqwest::get(format!("{base}/healthz"))
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(body["status"], "ok");
+    }
+}
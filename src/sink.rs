@@ -0,0 +1,339 @@
+//! Notifications for new search results
+//!
+//! A [`ResultSink`] receives results as they appear: [`WebhookSink`] POSTs them as JSON to
+//! any URL, [`SlackSink`] posts a message to a Slack (or Mattermost, Discord `/slack`)
+//! incoming webhook. A [`ResultNotifier`] remembers which URLs it has already announced
+//! and forwards only new ones to its sinks, so repeated searches for the same query only
+//! report what changed. [`web_search_batch_notify`] runs a batch through a notifier.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use websearch::{providers::DuckDuckGoProvider, types::SearchQuery};
+//! use websearch::sink::{web_search_batch_notify, ResultNotifier, SlackSink, WebhookSink};
+//!
+//! # #[tokio::main]
+//! # async fn main() {
+//! let notifier = ResultNotifier::new()
+//!     .with_sink(WebhookSink::new("https://example.com/hooks/search"))
+//!     .with_sink(SlackSink::new("https://hooks.slack.com/services/T000/B000/XXXX"));
+//!
+//! let queries = vec![SearchQuery::new("rust 2027 edition")];
+//! web_search_batch_notify(&DuckDuckGoProvider::new(), queries, 4, &notifier).await;
+//! # }
+//! ```
+
+use crate::{
+    batch::{web_search_batch, BatchSearchResult},
+    cassette,
+    error::{SearchError, SearchResult as Result},
+    processors::dedup_key,
+    types::{SearchProvider, SearchQuery, SearchResult},
+};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Destination for newly found results
+#[async_trait::async_trait]
+pub trait ResultSink: Send + Sync + std::fmt::Debug {
+    /// Deliver `results`, all found by searching for `query`
+    async fn send(&self, query: &str, results: &[SearchResult]) -> Result<()>;
+}
+
+/// JSON body POSTed by [`WebhookSink`]
+#[derive(Debug, Serialize)]
+struct WebhookPayload<'a> {
+    query: &'a str,
+    count: usize,
+    results: &'a [SearchResult],
+}
+
+/// POSTs `{"query", "count", "results"}` as JSON to a URL
+#[derive(Debug, Clone)]
+pub struct WebhookSink {
+    url: String,
+    headers: Vec<(String, String)>,
+    timeout: Duration,
+    client: reqwest::Client,
+}
+
+impl WebhookSink {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            headers: Vec::new(),
+            timeout: Duration::from_secs(10),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Send an extra header with every request, e.g. for authentication
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+}
+
+#[async_trait::async_trait]
+impl ResultSink for WebhookSink {
+    async fn send(&self, query: &str, results: &[SearchResult]) -> Result<()> {
+        let mut request = self.client.post(&self.url).timeout(self.timeout);
+        for (name, value) in &self.headers {
+            request = request.header(name, value);
+        }
+        post(request.json(&WebhookPayload {
+            query,
+            count: results.len(),
+            results,
+        }))
+        .await
+    }
+}
+
+/// Posts a message listing the results to a Slack-compatible incoming webhook
+#[derive(Debug, Clone)]
+pub struct SlackSink {
+    webhook_url: String,
+    timeout: Duration,
+    client: reqwest::Client,
+}
+
+impl SlackSink {
+    pub fn new(webhook_url: impl Into<String>) -> Self {
+        Self {
+            webhook_url: webhook_url.into(),
+            timeout: Duration::from_secs(10),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+}
+
+#[async_trait::async_trait]
+impl ResultSink for SlackSink {
+    async fn send(&self, query: &str, results: &[SearchResult]) -> Result<()> {
+        let text = slack_message(query, results);
+        post(
+            self.client
+                .post(&self.webhook_url)
+                .timeout(self.timeout)
+                .json(&serde_json::json!({ "text": text })),
+        )
+        .await
+    }
+}
+
+/// Slack `mrkdwn` text: a heading followed by one linked title per result
+fn slack_message(query: &str, results: &[SearchResult]) -> String {
+    let noun = if results.len() == 1 {
+        "result"
+    } else {
+        "results"
+    };
+    let mut text = format!(
+        "*{} new {noun} for \"{}\"*",
+        results.len(),
+        escape_slack(query)
+    );
+    for result in results {
+        let title = result.title.replace(['|', '\n'], " ");
+        text.push_str(&format!(
+            "\n• <{}|{}>",
+            escape_slack(&result.url),
+            escape_slack(&title)
+        ));
+    }
+    text
+}
+
+/// Escape the characters Slack treats as control sequences
+fn escape_slack(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+async fn post(request: reqwest::RequestBuilder) -> Result<()> {
+    let response = cassette::send(request).await?;
+    let status = response.status();
+    if status.is_success() {
+        Ok(())
+    } else {
+        Err(SearchError::HttpError {
+            message: format!("Sink request failed with status: {status}"),
+            status_code: Some(status.as_u16()),
+            response_body: response.text().await.ok(),
+        })
+    }
+}
+
+/// Forwards results to sinks, skipping URLs it has already forwarded
+///
+/// URLs are compared the same way as aggregate deduplication (ignoring scheme, `www.` and
+/// trailing slashes). Sink failures are logged and don't stop delivery to other sinks.
+#[derive(Debug, Default)]
+pub struct ResultNotifier {
+    sinks: Vec<Arc<dyn ResultSink>>,
+    seen: Mutex<HashSet<String>>,
+}
+
+impl ResultNotifier {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_sink(mut self, sink: impl ResultSink + 'static) -> Self {
+        self.sinks.push(Arc::new(sink));
+        self
+    }
+
+    /// Treat `urls` as already announced, e.g. results from before a restart
+    pub fn with_seen<I, S>(self, urls: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.seen
+            .lock()
+            .unwrap()
+            .extend(urls.into_iter().map(|url| dedup_key(url.as_ref())));
+        self
+    }
+
+    /// Send the results not announced before to every sink, and return them
+    ///
+    /// They count as announced once at least one sink accepts them; if every sink fails,
+    /// nothing is returned and the next call sends them again.
+    pub async fn notify(&self, query: &str, results: &[SearchResult]) -> Vec<SearchResult> {
+        let mut keys = HashSet::new();
+        let new: Vec<SearchResult> = {
+            let seen = self.seen.lock().unwrap();
+            results
+                .iter()
+                .filter(|result| {
+                    let key = dedup_key(&result.url);
+                    !seen.contains(&key) && keys.insert(key)
+                })
+                .cloned()
+                .collect()
+        };
+        if new.is_empty() {
+            return new;
+        }
+
+        let deliveries = self.sinks.iter().map(|sink| sink.send(query, &new));
+        let mut delivered = self.sinks.is_empty();
+        for (sink, outcome) in self
+            .sinks
+            .iter()
+            .zip(futures::future::join_all(deliveries).await)
+        {
+            match outcome {
+                Ok(()) => delivered = true,
+                Err(error) => log::warn!("Result sink {sink:?} failed: {error}"),
+            }
+        }
+        if !delivered {
+            return Vec::new();
+        }
+        self.seen.lock().unwrap().extend(keys);
+        new
+    }
+}
+
+/// Run a batch like [`web_search_batch`], passing each query's results to `notifier`
+///
+/// Notifications are sent once the whole batch has finished, in query order.
+pub async fn web_search_batch_notify(
+    provider: &dyn SearchProvider,
+    queries: Vec<SearchQuery>,
+    concurrency: usize,
+    notifier: &ResultNotifier,
+) -> Vec<BatchSearchResult> {
+    let batch = web_search_batch(provider, queries, concurrency).await;
+    for item in &batch {
+        if let Ok(results) = &item.result {
+            notifier.notify(&item.query.query, results).await;
+        }
+    }
+    batch
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{sample_results, MockProvider};
+    use wiremock::{
+        matchers::{body_partial_json, header, method},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    #[tokio::test]
+    async fn test_notifier_sends_only_new_results() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(header("X-Token", "secret"))
+            .and(body_partial_json(serde_json::json!({
+                "query": "rust",
+                "count": 2
+            })))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let notifier = ResultNotifier::new()
+            .with_sink(WebhookSink::new(server.uri()).with_header("X-Token", "secret"));
+        let queries = vec![SearchQuery::new("rust"), SearchQuery::new("rust")];
+        let batch =
+            web_search_batch_notify(&MockProvider::new("mock"), queries, 1, &notifier).await;
+
+        assert!(batch.iter().all(|item| item.is_ok()));
+        assert!(notifier
+            .notify("rust", &sample_results("mock", 2))
+            .await
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_results_are_resent_when_every_sink_fails() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&server)
+            .await;
+
+        let notifier = ResultNotifier::new().with_sink(WebhookSink::new(server.uri()));
+        let results = sample_results("mock", 2);
+
+        assert!(notifier.notify("rust", &results).await.is_empty());
+        assert_eq!(notifier.notify("rust", &results).await.len(), 2);
+        assert!(notifier.notify("rust", &results).await.is_empty());
+    }
+
+    #[test]
+    fn test_slack_message_escapes_links() {
+        let mut results = sample_results("mock", 1);
+        results[0].title = "A <b>|B</b>".to_string();
+        assert_eq!(
+            slack_message("rust & go", &results),
+            "*1 new result for \"rust &amp; go\"*\n• <https://mock.com/1|A &lt;b&gt; B&lt;/b&gt;>"
+        );
+    }
+}
@@ -0,0 +1,418 @@
+//! SQLite archive of past search results
+//!
+//! [`ResultStore::record`] saves every search (query, provider, time) together with its
+//! results. Results are deduplicated on URL: a page returned by many searches is stored
+//! once, with the time it was first and last seen, and linked to each search that returned
+//! it. The archive can then be listed and searched without touching any provider, and
+//! `record` reports which results are new, which is what change monitoring needs.
+//!
+//! Requires the `store` feature.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use websearch::{store::ResultStore, testing::sample_results};
+//!
+//! let store = ResultStore::open_in_memory().unwrap();
+//! let new = store.record("rust", "mock", &sample_results("mock", 2)).unwrap();
+//! assert_eq!(new.len(), 2);
+//!
+//! // The same results again are not new
+//! let new = store.record("rust", "mock", &sample_results("mock", 2)).unwrap();
+//! assert!(new.is_empty());
+//! assert_eq!(store.find("content", 10).unwrap().len(), 2);
+//! ```
+
+use crate::{
+    error::{SearchError, SearchResult as Result},
+    types::SearchResult,
+};
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS searches (
+    id INTEGER PRIMARY KEY,
+    query TEXT NOT NULL,
+    provider TEXT NOT NULL,
+    searched_at TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS results (
+    id INTEGER PRIMARY KEY,
+    url TEXT NOT NULL UNIQUE,
+    title TEXT NOT NULL,
+    snippet TEXT,
+    domain TEXT,
+    published_date TEXT,
+    provider TEXT,
+    raw TEXT,
+    first_seen TEXT NOT NULL,
+    last_seen TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS search_results (
+    search_id INTEGER NOT NULL REFERENCES searches(id) ON DELETE CASCADE,
+    result_id INTEGER NOT NULL REFERENCES results(id) ON DELETE CASCADE,
+    rank INTEGER NOT NULL,
+    PRIMARY KEY (search_id, result_id)
+);
+CREATE INDEX IF NOT EXISTS searches_query ON searches(query);
+";
+
+const RESULT_COLUMNS: &str =
+    "r.url, r.title, r.snippet, r.domain, r.published_date, r.provider, r.raw, r.first_seen, r.last_seen";
+
+/// A search saved by [`ResultStore::record`]
+#[derive(Debug, Clone)]
+pub struct StoredSearch {
+    pub id: i64,
+    pub query: String,
+    pub provider: String,
+    pub searched_at: DateTime<Utc>,
+    /// Number of results the search returned
+    pub result_count: usize,
+}
+
+/// An archived result and when it was seen
+#[derive(Debug, Clone)]
+pub struct StoredResult {
+    pub result: SearchResult,
+    /// When a search first returned this URL
+    pub first_seen: DateTime<Utc>,
+    /// When a search most recently returned this URL
+    pub last_seen: DateTime<Utc>,
+}
+
+/// Search history and results persisted in a SQLite database
+#[derive(Debug)]
+pub struct ResultStore {
+    conn: Mutex<Connection>,
+}
+
+impl ResultStore {
+    /// Open (or create) the archive at `path`
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)?;
+        }
+        Self::init(Connection::open(path).map_err(store_error)?)
+    }
+
+    /// A throwaway archive held in memory
+    pub fn open_in_memory() -> Result<Self> {
+        Self::init(Connection::open_in_memory().map_err(store_error)?)
+    }
+
+    /// Open the archive at [`default_path`](Self::default_path), if one is known
+    pub fn open_default() -> Result<Self> {
+        let path = Self::default_path().ok_or_else(|| {
+            SearchError::ConfigError("No data directory for the result store".to_string())
+        })?;
+        Self::open(path)
+    }
+
+    /// `$WEBSEARCH_DATA_DIR/history.db`, else `$XDG_DATA_HOME/websearch/history.db`, else
+    /// `~/.local/share/websearch/history.db`
+    pub fn default_path() -> Option<PathBuf> {
+        let non_empty = |name: &str| std::env::var_os(name).filter(|value| !value.is_empty());
+
+        non_empty("WEBSEARCH_DATA_DIR")
+            .map(PathBuf::from)
+            .or_else(|| non_empty("XDG_DATA_HOME").map(|dir| PathBuf::from(dir).join("websearch")))
+            .or_else(|| {
+                non_empty("HOME").map(|home| PathBuf::from(home).join(".local/share/websearch"))
+            })
+            .map(|dir| dir.join("history.db"))
+    }
+
+    fn init(conn: Connection) -> Result<Self> {
+        conn.execute_batch("PRAGMA foreign_keys = ON;")
+            .and_then(|_| conn.execute_batch(SCHEMA))
+            .map_err(store_error)?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    /// Save a search and its results, returning the results whose URL was not archived yet
+    ///
+    /// Results already in the archive have their fields refreshed and `last_seen` updated.
+    pub fn record(
+        &self,
+        query: &str,
+        provider: &str,
+        results: &[SearchResult],
+    ) -> Result<Vec<SearchResult>> {
+        let now = Utc::now().to_rfc3339();
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction().map_err(store_error)?;
+
+        tx.execute(
+            "INSERT INTO searches (query, provider, searched_at) VALUES (?1, ?2, ?3)",
+            params![query, provider, now],
+        )
+        .map_err(store_error)?;
+        let search_id = tx.last_insert_rowid();
+
+        let mut new_results = Vec::new();
+        for (rank, result) in results.iter().enumerate() {
+            let existing: Option<i64> = tx
+                .query_row(
+                    "SELECT id FROM results WHERE url = ?1",
+                    params![result.url],
+                    |row| row.get(0),
+                )
+                .optional()
+                .map_err(store_error)?;
+            let raw = result.raw.as_ref().map(|raw| raw.to_string());
+
+            let result_id = match existing {
+                Some(id) => {
+                    tx.execute(
+                        "UPDATE results SET title = ?2, snippet = ?3, domain = ?4,
+                             published_date = ?5, provider = ?6, raw = ?7, last_seen = ?8
+                         WHERE id = ?1",
+                        params![
+                            id,
+                            result.title,
+                            result.snippet,
+                            result.domain,
+                            result.published_date,
+                            result.provider.as_deref().unwrap_or(provider),
+                            raw,
+                            now
+                        ],
+                    )
+                    .map_err(store_error)?;
+                    id
+                }
+                None => {
+                    tx.execute(
+                        "INSERT INTO results (url, title, snippet, domain, published_date,
+                             provider, raw, first_seen, last_seen)
+                         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?8)",
+                        params![
+                            result.url,
+                            result.title,
+                            result.snippet,
+                            result.domain,
+                            result.published_date,
+                            result.provider.as_deref().unwrap_or(provider),
+                            raw,
+                            now
+                        ],
+                    )
+                    .map_err(store_error)?;
+                    new_results.push(result.clone());
+                    tx.last_insert_rowid()
+                }
+            };
+
+            tx.execute(
+                "INSERT OR IGNORE INTO search_results (search_id, result_id, rank)
+                 VALUES (?1, ?2, ?3)",
+                params![search_id, result_id, rank as i64],
+            )
+            .map_err(store_error)?;
+        }
+
+        tx.commit().map_err(store_error)?;
+        Ok(new_results)
+    }
+
+    /// The most recent searches, newest first
+    pub fn searches(&self, limit: usize) -> Result<Vec<StoredSearch>> {
+        let conn = self.conn.lock().unwrap();
+        let mut statement = conn
+            .prepare(
+                "SELECT s.id, s.query, s.provider, s.searched_at, COUNT(sr.result_id)
+                 FROM searches s LEFT JOIN search_results sr ON sr.search_id = s.id
+                 GROUP BY s.id ORDER BY s.id DESC LIMIT ?1",
+            )
+            .map_err(store_error)?;
+        let searches = statement
+            .query_map(params![limit as i64], |row| {
+                Ok(StoredSearch {
+                    id: row.get(0)?,
+                    query: row.get(1)?,
+                    provider: row.get(2)?,
+                    searched_at: parse_time(row.get(3)?),
+                    result_count: row.get::<_, i64>(4)? as usize,
+                })
+            })
+            .map_err(store_error)?
+            .collect::<rusqlite::Result<_>>()
+            .map_err(store_error)?;
+        Ok(searches)
+    }
+
+    /// Results of the latest search for exactly `query`, in their original order
+    pub fn latest_results(&self, query: &str) -> Result<Vec<StoredResult>> {
+        let conn = self.conn.lock().unwrap();
+        let sql = format!(
+            "SELECT {RESULT_COLUMNS} FROM search_results sr
+             JOIN results r ON r.id = sr.result_id
+             WHERE sr.search_id = (SELECT MAX(id) FROM searches WHERE query = ?1)
+             ORDER BY sr.rank"
+        );
+        let mut statement = conn.prepare(&sql).map_err(store_error)?;
+        let results = statement
+            .query_map(params![query], stored_result)
+            .map_err(store_error)?
+            .collect::<rusqlite::Result<_>>()
+            .map_err(store_error)?;
+        Ok(results)
+    }
+
+    /// Archived results whose title, snippet or URL contains `text`, most recently seen first
+    pub fn find(&self, text: &str, limit: usize) -> Result<Vec<StoredResult>> {
+        let pattern = format!(
+            "%{}%",
+            text.replace('\\', "\\\\")
+                .replace('%', "\\%")
+                .replace('_', "\\_")
+        );
+        let conn = self.conn.lock().unwrap();
+        let sql = format!(
+            "SELECT {RESULT_COLUMNS} FROM results r
+             WHERE r.title LIKE ?1 ESCAPE '\\' OR r.snippet LIKE ?1 ESCAPE '\\'
+                OR r.url LIKE ?1 ESCAPE '\\'
+             ORDER BY r.last_seen DESC, r.id DESC LIMIT ?2"
+        );
+        let mut statement = conn.prepare(&sql).map_err(store_error)?;
+        let results = statement
+            .query_map(params![pattern, limit as i64], stored_result)
+            .map_err(store_error)?
+            .collect::<rusqlite::Result<_>>()
+            .map_err(store_error)?;
+        Ok(results)
+    }
+
+    /// Whether a search has already returned `url`
+    pub fn contains(&self, url: &str) -> Result<bool> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM results WHERE url = ?1)",
+            params![url],
+            |row| row.get(0),
+        )
+        .map_err(store_error)
+    }
+
+    /// Every URL a recorded search has returned, e.g. for
+    /// [`SearchOptions::exclude_urls`](crate::SearchOptions::exclude_urls) to ask only for
+    /// results not seen before
+    pub fn seen_urls(&self) -> Result<HashSet<String>> {
+        let conn = self.conn.lock().unwrap();
+        let mut statement = conn
+            .prepare("SELECT url FROM results")
+            .map_err(store_error)?;
+        let urls = statement
+            .query_map([], |row| row.get(0))
+            .map_err(store_error)?
+            .collect::<rusqlite::Result<_>>()
+            .map_err(store_error)?;
+        Ok(urls)
+    }
+
+    /// Delete searches made before `cutoff`, and results no remaining search returned
+    ///
+    /// Returns the number of searches deleted.
+    pub fn prune_before(&self, cutoff: DateTime<Utc>) -> Result<usize> {
+        let conn = self.conn.lock().unwrap();
+        let deleted = conn
+            .execute(
+                "DELETE FROM searches WHERE searched_at < ?1",
+                params![cutoff.to_rfc3339()],
+            )
+            .map_err(store_error)?;
+        conn.execute(
+            "DELETE FROM results WHERE id NOT IN (SELECT result_id FROM search_results)",
+            [],
+        )
+        .map_err(store_error)?;
+        Ok(deleted)
+    }
+}
+
+fn stored_result(row: &Row) -> rusqlite::Result<StoredResult> {
+    let raw: Option<String> = row.get(6)?;
+    Ok(StoredResult {
+        result: SearchResult {
+            url: row.get(0)?,
+            title: row.get(1)?,
+            snippet: row.get(2)?,
+            domain: row.get(3)?,
+            published_date: row.get(4)?,
+            provider: row.get(5)?,
+            raw: raw.and_then(|raw| serde_json::from_str(&raw).ok()),
+            ..Default::default()
+        },
+        first_seen: parse_time(row.get(7)?),
+        last_seen: parse_time(row.get(8)?),
+    })
+}
+
+fn parse_time(time: String) -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339(&time)
+        .map(|time| time.with_timezone(&Utc))
+        .unwrap_or_default()
+}
+
+fn store_error(error: rusqlite::Error) -> SearchError {
+    SearchError::Other(format!("Result store error: {error}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::sample_results;
+
+    #[test]
+    fn test_record_dedups_on_url() {
+        let store = ResultStore::open_in_memory().unwrap();
+
+        let first = store
+            .record("rust", "mock", &sample_results("mock", 2))
+            .unwrap();
+        assert_eq!(first.len(), 2);
+
+        let mut again = sample_results("mock", 3);
+        again[0].title = "Updated title".to_string();
+        let new = store.record("rust", "mock", &again).unwrap();
+        assert_eq!(new.len(), 1);
+        assert_eq!(new[0].url, "https://mock.com/3");
+
+        let latest = store.latest_results("rust").unwrap();
+        assert_eq!(latest.len(), 3);
+        assert_eq!(latest[0].result.title, "Updated title");
+        assert!(latest[0].first_seen <= latest[0].last_seen);
+
+        let searches = store.searches(10).unwrap();
+        assert_eq!(searches.len(), 2);
+        assert_eq!(searches[0].result_count, 3);
+        assert!(store.contains("https://mock.com/1").unwrap());
+        assert_eq!(store.seen_urls().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_find_and_prune() {
+        let store = ResultStore::open_in_memory().unwrap();
+        let mut results = sample_results("mock", 2);
+        results[1].snippet = Some("100% safe_rust".to_string());
+        results[1].raw = Some(serde_json::json!({ "rank": 2 }));
+        store.record("rust", "mock", &results).unwrap();
+
+        let found = store.find("100% safe_", 10).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].result.raw, Some(serde_json::json!({ "rank": 2 })));
+        assert!(store.find("100%_safe", 10).unwrap().is_empty());
+
+        assert_eq!(store.prune_before(Utc::now()).unwrap(), 1);
+        assert!(store.searches(10).unwrap().is_empty());
+        assert!(!store.contains("https://mock.com/1").unwrap());
+    }
+}
@@ -0,0 +1,332 @@
+//! Query autocompletion
+//!
+//! [`SuggestProvider`] implementations wrap the public suggestion endpoints of
+//! Google, DuckDuckGo and Brave. Use [`suggest`] to validate the query and call one.
+
+use crate::{
+    error::{SearchError, SearchResult},
+    utils::http::{build_url, HttpClient},
+};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// Trait for providers that complete partial queries
+#[async_trait::async_trait]
+pub trait SuggestProvider: Send + Sync + std::fmt::Debug {
+    /// Name of the suggestion provider
+    fn name(&self) -> &str;
+
+    /// Return completions for `query`, best first
+    async fn suggest(&self, query: &str) -> SearchResult<Vec<String>>;
+}
+
+/// Get query completions from `provider`
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use websearch::suggest::{suggest, DuckDuckGoSuggestProvider};
+///
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let suggestions = suggest("rust asy", &DuckDuckGoSuggestProvider::new()).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn suggest(query: &str, provider: &dyn SuggestProvider) -> SearchResult<Vec<String>> {
+    if query.trim().is_empty() {
+        return Err(SearchError::InvalidInput(
+            "A query is required for suggestions".to_string(),
+        ));
+    }
+
+    provider.suggest(query).await
+}
+
+/// OpenSearch suggestion format: `["query", ["completion", ...], ...]`
+fn parse_opensearch(body: &str) -> SearchResult<Vec<String>> {
+    let value: serde_json::Value = serde_json::from_str(body)?;
+    let completions = value
+        .get(1)
+        .and_then(|v| v.as_array())
+        .ok_or_else(|| SearchError::ParseError("Unexpected suggestion format".to_string()))?;
+
+    Ok(completions
+        .iter()
+        .filter_map(|v| v.as_str())
+        .map(|s| s.to_string())
+        .collect())
+}
+
+/// Google Suggest (no API key required)
+#[derive(Debug)]
+pub struct GoogleSuggestProvider {
+    base_url: String,
+    language: Option<String>,
+    region: Option<String>,
+    http_client: HttpClient,
+}
+
+impl GoogleSuggestProvider {
+    pub fn new() -> Self {
+        Self {
+            base_url: "https://suggestqueries.google.com/complete/search".to_string(),
+            language: None,
+            region: None,
+            http_client: HttpClient::new(),
+        }
+    }
+
+    /// Interface language, e.g. "en"
+    pub fn with_language(mut self, language: &str) -> Self {
+        self.language = Some(language.to_string());
+        self
+    }
+
+    /// Country code, e.g. "US"
+    pub fn with_region(mut self, region: &str) -> Self {
+        self.region = Some(region.to_string());
+        self
+    }
+
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.to_string();
+        self
+    }
+}
+
+impl Default for GoogleSuggestProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait::async_trait]
+impl SuggestProvider for GoogleSuggestProvider {
+    fn name(&self) -> &str {
+        "google"
+    }
+
+    async fn suggest(&self, query: &str) -> SearchResult<Vec<String>> {
+        let mut params = HashMap::new();
+        params.insert("client".to_string(), "firefox".to_string());
+        params.insert("q".to_string(), query.to_string());
+        if let Some(language) = &self.language {
+            params.insert("hl".to_string(), language.clone());
+        }
+        if let Some(region) = &self.region {
+            params.insert("gl".to_string(), region.clone());
+        }
+
+        let url = build_url(&self.base_url, params)?;
+        parse_opensearch(&self.http_client.get_text(&url).await?)
+    }
+}
+
+/// DuckDuckGo autocomplete (no API key required)
+#[derive(Debug)]
+pub struct DuckDuckGoSuggestProvider {
+    base_url: String,
+    region: Option<String>,
+    http_client: HttpClient,
+}
+
+impl DuckDuckGoSuggestProvider {
+    pub fn new() -> Self {
+        Self {
+            base_url: "https://duckduckgo.com/ac/".to_string(),
+            region: None,
+            http_client: HttpClient::new(),
+        }
+    }
+
+    /// DuckDuckGo region code, e.g. "us-en"
+    pub fn with_region(mut self, region: &str) -> Self {
+        self.region = Some(region.to_string());
+        self
+    }
+
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.to_string();
+        self
+    }
+}
+
+impl Default for DuckDuckGoSuggestProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait::async_trait]
+impl SuggestProvider for DuckDuckGoSuggestProvider {
+    fn name(&self) -> &str {
+        "duckduckgo"
+    }
+
+    async fn suggest(&self, query: &str) -> SearchResult<Vec<String>> {
+        let mut params = HashMap::new();
+        params.insert("q".to_string(), query.to_string());
+        params.insert("type".to_string(), "list".to_string());
+        if let Some(region) = &self.region {
+            params.insert("kl".to_string(), region.clone());
+        }
+
+        let url = build_url(&self.base_url, params)?;
+        parse_opensearch(&self.http_client.get_text(&url).await?)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct BraveSuggestResponse {
+    #[serde(default)]
+    results: Vec<BraveSuggestResult>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BraveSuggestResult {
+    query: String,
+}
+
+/// Brave Suggest API (requires a Brave Search API key with the Autosuggest plan)
+#[derive(Debug)]
+pub struct BraveSuggestProvider {
+    api_key: String,
+    base_url: String,
+    region: Option<String>,
+    max_results: Option<u32>,
+    http_client: HttpClient,
+}
+
+impl BraveSuggestProvider {
+    pub fn new(api_key: &str) -> SearchResult<Self> {
+        if api_key.is_empty() {
+            return Err(SearchError::ConfigError(
+                "Brave API key is required".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            api_key: api_key.to_string(),
+            base_url: "https://api.search.brave.com/res/v1/suggest/search".to_string(),
+            region: None,
+            max_results: None,
+            http_client: HttpClient::new(),
+        })
+    }
+
+    /// Country code, e.g. "US"
+    pub fn with_region(mut self, region: &str) -> Self {
+        self.region = Some(region.to_string());
+        self
+    }
+
+    /// Number of suggestions to request (Brave allows up to 20)
+    pub fn with_max_results(mut self, max_results: u32) -> Self {
+        self.max_results = Some(max_results.min(20));
+        self
+    }
+
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.to_string();
+        self
+    }
+}
+
+#[async_trait::async_trait]
+impl SuggestProvider for BraveSuggestProvider {
+    fn name(&self) -> &str {
+        "brave"
+    }
+
+    async fn suggest(&self, query: &str) -> SearchResult<Vec<String>> {
+        let mut params = HashMap::new();
+        params.insert("q".to_string(), query.to_string());
+        if let Some(region) = &self.region {
+            params.insert("country".to_string(), region.clone());
+        }
+        if let Some(max_results) = self.max_results {
+            params.insert("count".to_string(), max_results.to_string());
+        }
+
+        let mut headers = HashMap::new();
+        headers.insert("Accept".to_string(), "application/json".to_string());
+        headers.insert("X-Subscription-Token".to_string(), self.api_key.clone());
+
+        let url = build_url(&self.base_url, params)?;
+        let body = self
+            .http_client
+            .get_text_with_headers(&url, headers)
+            .await?;
+        let response: BraveSuggestResponse = serde_json::from_str(&body)?;
+
+        Ok(response
+            .results
+            .into_iter()
+            .map(|result| result.query)
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::{
+        matchers::{header, method, query_param},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    #[tokio::test]
+    async fn test_duckduckgo_suggestions() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(query_param("q", "rust asy"))
+            .and(query_param("type", "list"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(
+                r#"["rust asy",["rust async","rust async trait"]]"#,
+                "application/x-suggestions+json",
+            ))
+            .mount(&server)
+            .await;
+
+        let provider = DuckDuckGoSuggestProvider::new().with_base_url(&server.uri());
+        let suggestions = suggest("rust asy", &provider).await.unwrap();
+        assert_eq!(suggestions, ["rust async", "rust async trait"]);
+    }
+
+    #[tokio::test]
+    async fn test_brave_suggestions() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(header("X-Subscription-Token", "test-key"))
+            .and(query_param("count", "5"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "type": "suggest",
+                "query": { "original": "tok" },
+                "results": [{ "query": "tokio" }, { "query": "tokio rs" }]
+            })))
+            .mount(&server)
+            .await;
+
+        let provider = BraveSuggestProvider::new("test-key")
+            .unwrap()
+            .with_max_results(5)
+            .with_base_url(&server.uri());
+        let suggestions = suggest("tok", &provider).await.unwrap();
+        assert_eq!(suggestions, ["tokio", "tokio rs"]);
+    }
+
+    #[tokio::test]
+    async fn test_empty_query_is_rejected() {
+        let provider = GoogleSuggestProvider::new();
+        assert!(matches!(
+            suggest("  ", &provider).await,
+            Err(SearchError::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_opensearch_rejects_other_shapes() {
+        assert!(parse_opensearch(r#"{"results": []}"#).is_err());
+    }
+}
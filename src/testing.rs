@@ -0,0 +1,320 @@
+//! Test doubles for code that uses search providers
+//!
+//! [`MockProvider`] returns canned results (or scripted errors, optionally after a delay)
+//! without any network access, and [`RecordingProvider`] wraps any provider to capture the
+//! queries it receives. Both are cheap to clone; clones share their call counters and
+//! recordings, so keep a clone around to inspect after handing one to a search.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use websearch::{testing::{MockProvider, RecordingProvider}, web_search, SearchOptions};
+//!
+//! # #[tokio::main]
+//! # async fn main() {
+//! let provider = RecordingProvider::new(MockProvider::new("mock"));
+//! let results = web_search(SearchOptions {
+//!     query: "rust".to_string(),
+//!     provider: Box::new(provider.clone()),
+//!     ..Default::default()
+//! })
+//! .await
+//! .unwrap();
+//!
+//! assert_eq!(results[0].title, "mock Result 1");
+//! assert_eq!(provider.calls()[0].query.query, "rust");
+//! # }
+//! ```
+
+use crate::{
+    error::{SearchError, SearchResult as Result},
+    types::{ProviderHealth, SearchMode, SearchOptions, SearchProvider, SearchQuery, SearchResult},
+    utils::intern::intern,
+};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// `count` placeholder results attributed to `provider`
+///
+/// Result `i` (starting at 1) is titled `"{provider} Result {i}"` and links to
+/// `https://{provider}.com/{i}`.
+pub fn sample_results(provider: &str, count: usize) -> Vec<SearchResult> {
+    (1..=count)
+        .map(|i| SearchResult {
+            title: format!("{provider} Result {i}"),
+            url: format!("https://{provider}.com/{i}"),
+            snippet: Some(format!("{provider} content {i}")),
+            provider: Some(intern(provider)),
+            ..Default::default()
+        })
+        .collect()
+}
+
+/// A provider that answers from canned results without touching the network
+///
+/// By default every search returns two [`sample_results`]. Scripted responses queued with
+/// [`then_return`](Self::then_return) or [`fail_times`](Self::fail_times) are used first,
+/// one per search, before falling back to the default.
+#[derive(Debug, Clone)]
+pub struct MockProvider {
+    name: String,
+    outcome: std::result::Result<Vec<SearchResult>, SearchError>,
+    script: Arc<Mutex<VecDeque<Result<Vec<SearchResult>>>>>,
+    delay: Duration,
+    modes: Vec<SearchMode>,
+    paginates: bool,
+    calls: Arc<AtomicUsize>,
+}
+
+impl MockProvider {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            outcome: Ok(sample_results(name, 2)),
+            script: Arc::default(),
+            delay: Duration::ZERO,
+            modes: vec![SearchMode::Web],
+            paginates: true,
+            calls: Arc::default(),
+        }
+    }
+
+    /// Return `results` from every (unscripted) search
+    pub fn with_results(mut self, results: Vec<SearchResult>) -> Self {
+        self.outcome = Ok(results);
+        self
+    }
+
+    /// Fail every (unscripted) search with `error`
+    pub fn with_error(mut self, error: SearchError) -> Self {
+        self.outcome = Err(error);
+        self
+    }
+
+    /// Wait this long before answering each search
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Report support for these search modes instead of only [`SearchMode::Web`]
+    pub fn with_modes(mut self, modes: impl Into<Vec<SearchMode>>) -> Self {
+        self.modes = modes.into();
+        self
+    }
+
+    /// Report whether `page` is honored (it is by default); scripted responses are
+    /// returned in order either way
+    pub fn with_pagination(mut self, paginates: bool) -> Self {
+        self.paginates = paginates;
+        self
+    }
+
+    /// Queue a one-off response for the next unanswered search
+    pub fn then_return(self, response: Result<Vec<SearchResult>>) -> Self {
+        self.script.lock().unwrap().push_back(response);
+        self
+    }
+
+    /// Fail the next `times` searches with `error`, e.g. to exercise retries and failover
+    pub fn fail_times(self, times: usize, error: SearchError) -> Self {
+        self.script
+            .lock()
+            .unwrap()
+            .extend(std::iter::repeat_n(Err(error), times));
+        self
+    }
+
+    /// Number of searches made so far, across all clones
+    pub fn call_count(&self) -> usize {
+        self.calls.load(Ordering::SeqCst)
+    }
+}
+
+#[async_trait::async_trait]
+impl SearchProvider for MockProvider {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn search(&self, _options: &SearchOptions) -> Result<Vec<SearchResult>> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        if !self.delay.is_zero() {
+            tokio::time::sleep(self.delay).await;
+        }
+
+        let scripted = self.script.lock().unwrap().pop_front();
+        scripted.unwrap_or_else(|| self.outcome.clone())
+    }
+
+    fn supports_mode(&self, mode: SearchMode) -> bool {
+        self.modes.contains(&mode)
+    }
+
+    fn supports_pagination(&self) -> bool {
+        self.paginates
+    }
+}
+
+/// A search seen by a [`RecordingProvider`]
+#[derive(Debug, Clone)]
+pub struct RecordedCall {
+    /// The options the search was made with
+    pub query: SearchQuery,
+    /// What the wrapped provider returned
+    pub result: Result<Vec<SearchResult>>,
+}
+
+/// Wraps a provider and records every search made through it
+///
+/// Health and credential checks go straight to the wrapped provider and aren't recorded.
+#[derive(Debug, Clone)]
+pub struct RecordingProvider {
+    inner: Arc<dyn SearchProvider>,
+    calls: Arc<Mutex<Vec<RecordedCall>>>,
+}
+
+impl RecordingProvider {
+    pub fn new(inner: impl SearchProvider + 'static) -> Self {
+        Self {
+            inner: Arc::new(inner),
+            calls: Arc::default(),
+        }
+    }
+
+    /// Searches made so far, oldest first, across all clones
+    pub fn calls(&self) -> Vec<RecordedCall> {
+        self.calls.lock().unwrap().clone()
+    }
+
+    /// The query text of each search made so far
+    pub fn queries(&self) -> Vec<String> {
+        self.calls
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|call| call.query.query.clone())
+            .collect()
+    }
+}
+
+#[async_trait::async_trait]
+impl SearchProvider for RecordingProvider {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn supports_safe_search(&self) -> bool {
+        self.inner.supports_safe_search()
+    }
+
+    fn supports_domain_filter(&self) -> bool {
+        self.inner.supports_domain_filter()
+    }
+
+    fn supports_freshness(&self) -> bool {
+        self.inner.supports_freshness()
+    }
+
+    fn supports_pagination(&self) -> bool {
+        self.inner.supports_pagination()
+    }
+
+    fn supports_mode(&self, mode: SearchMode) -> bool {
+        self.inner.supports_mode(mode)
+    }
+
+    async fn search(&self, options: &SearchOptions) -> Result<Vec<SearchResult>> {
+        let result = self.inner.search(options).await;
+        self.calls.lock().unwrap().push(RecordedCall {
+            query: SearchQuery::from(options),
+            result: result.clone(),
+        });
+        result
+    }
+
+    async fn health_check(&self) -> ProviderHealth {
+        self.inner.health_check().await
+    }
+
+    async fn validate_credentials(&self) -> Result<()> {
+        self.inner.validate_credentials().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(query: &str) -> SearchOptions {
+        SearchOptions {
+            query: query.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_scripted_responses_come_first() {
+        let provider = MockProvider::new("mock")
+            .fail_times(2, SearchError::RateLimit("slow down".to_string()))
+            .then_return(Ok(Vec::new()));
+
+        for _ in 0..2 {
+            assert!(matches!(
+                provider.search(&options("rust")).await,
+                Err(SearchError::RateLimit(_))
+            ));
+        }
+        assert!(provider.search(&options("rust")).await.unwrap().is_empty());
+        assert_eq!(provider.search(&options("rust")).await.unwrap().len(), 2);
+        assert_eq!(provider.clone().call_count(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_recording_provider_captures_queries() {
+        let mock = MockProvider::new("mock").with_error(SearchError::Other("down".to_string()));
+        let provider = RecordingProvider::new(mock.clone());
+
+        let mut paged = options("tokio");
+        paged.page = Some(2);
+        provider.search(&options("rust")).await.unwrap_err();
+        provider.clone().search(&paged).await.unwrap_err();
+
+        let calls = provider.calls();
+        assert_eq!(provider.queries(), ["rust", "tokio"]);
+        assert_eq!(calls[1].query.page, Some(2));
+        assert!(calls[1].result.is_err());
+        assert_eq!(mock.call_count(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_recording_provider_forwards_credential_checks() {
+        /// Searches fine but rejects its key when asked
+        #[derive(Debug)]
+        struct RevokedKey;
+
+        #[async_trait::async_trait]
+        impl SearchProvider for RevokedKey {
+            fn name(&self) -> &str {
+                "revoked"
+            }
+
+            async fn search(&self, _options: &SearchOptions) -> Result<Vec<SearchResult>> {
+                Ok(Vec::new())
+            }
+
+            async fn validate_credentials(&self) -> Result<()> {
+                Err(SearchError::AuthenticationError("revoked".to_string()))
+            }
+        }
+
+        let provider = RecordingProvider::new(RevokedKey);
+        assert!(matches!(
+            provider.validate_credentials().await,
+            Err(SearchError::AuthenticationError(_))
+        ));
+        assert!(provider.calls().is_empty());
+    }
+}
@@ -0,0 +1,222 @@
+//! Trending searches
+//!
+//! [`TrendsProvider`] implementations report what people are currently searching for in a
+//! region. Use [`trending`] to validate the region and call one.
+
+use crate::{
+    error::{SearchError, SearchResult},
+    utils::http::{build_url, HttpClient},
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// A query that is currently trending
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrendingQuery {
+    /// The trending search query
+    pub query: String,
+    /// Approximate number of searches, when the provider reports it
+    pub search_volume: Option<u64>,
+    /// Growth in search interest, in percent
+    pub increase_percentage: Option<u64>,
+    /// Topic categories such as "Sports" or "Technology"
+    pub categories: Vec<String>,
+    /// When the query started trending
+    pub started_at: Option<DateTime<Utc>>,
+}
+
+/// Trait for providers that report trending searches
+#[async_trait::async_trait]
+pub trait TrendsProvider: Send + Sync + std::fmt::Debug {
+    /// Name of the trends provider
+    fn name(&self) -> &str;
+
+    /// Return the queries trending in `region` (a country code such as "US"), most popular first
+    async fn trending(&self, region: &str) -> SearchResult<Vec<TrendingQuery>>;
+}
+
+/// Get trending searches for `region` from `provider`
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use websearch::trends::{trending, SerpApiTrendsProvider};
+///
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let provider = SerpApiTrendsProvider::new("YOUR_SERPAPI_KEY")?;
+/// for trend in trending("US", &provider).await? {
+///     println!("{}", trend.query);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub async fn trending(
+    region: &str,
+    provider: &dyn TrendsProvider,
+) -> SearchResult<Vec<TrendingQuery>> {
+    let region = region.trim();
+    if region.is_empty() {
+        return Err(SearchError::InvalidInput(
+            "A region is required for trending searches".to_string(),
+        ));
+    }
+
+    provider.trending(&region.to_uppercase()).await
+}
+
+#[derive(Debug, Deserialize)]
+struct SerpApiTrendsResponse {
+    #[serde(default)]
+    trending_searches: Vec<SerpApiTrendingSearch>,
+    error: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SerpApiTrendingSearch {
+    query: String,
+    search_volume: Option<u64>,
+    increase_percentage: Option<u64>,
+    start_timestamp: Option<i64>,
+    #[serde(default)]
+    categories: Vec<SerpApiTrendCategory>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SerpApiTrendCategory {
+    name: String,
+}
+
+/// Google Trends "Trending now" through SerpAPI
+#[derive(Debug)]
+pub struct SerpApiTrendsProvider {
+    api_key: String,
+    base_url: String,
+    hours: Option<u32>,
+    http_client: HttpClient,
+}
+
+impl SerpApiTrendsProvider {
+    pub fn new(api_key: &str) -> SearchResult<Self> {
+        if api_key.is_empty() {
+            return Err(SearchError::ConfigError(
+                "SerpAPI key is required".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            api_key: api_key.to_string(),
+            base_url: "https://serpapi.com/search.json".to_string(),
+            hours: None,
+            http_client: HttpClient::new(),
+        })
+    }
+
+    /// Only include queries that started trending in the past `hours` (4, 24, 48 or 168)
+    pub fn with_hours(mut self, hours: u32) -> Self {
+        self.hours = Some(hours);
+        self
+    }
+
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.to_string();
+        self
+    }
+}
+
+#[async_trait::async_trait]
+impl TrendsProvider for SerpApiTrendsProvider {
+    fn name(&self) -> &str {
+        "serpapi"
+    }
+
+    async fn trending(&self, region: &str) -> SearchResult<Vec<TrendingQuery>> {
+        let mut params = HashMap::new();
+        params.insert(
+            "engine".to_string(),
+            "google_trends_trending_now".to_string(),
+        );
+        params.insert("api_key".to_string(), self.api_key.clone());
+        params.insert("geo".to_string(), region.to_string());
+        if let Some(hours) = self.hours {
+            params.insert("hours".to_string(), hours.to_string());
+        }
+
+        let url = build_url(&self.base_url, params)?;
+        let body = self.http_client.get_text(&url).await?;
+        let response: SerpApiTrendsResponse = serde_json::from_str(&body)?;
+
+        if let Some(error) = response.error {
+            return Err(SearchError::ProviderError(format!(
+                "SerpAPI error: {error}"
+            )));
+        }
+
+        Ok(response
+            .trending_searches
+            .into_iter()
+            .map(|search| TrendingQuery {
+                query: search.query,
+                search_volume: search.search_volume,
+                increase_percentage: search.increase_percentage,
+                categories: search.categories.into_iter().map(|c| c.name).collect(),
+                started_at: search
+                    .start_timestamp
+                    .and_then(|ts| DateTime::from_timestamp(ts, 0)),
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::{
+        matchers::{method, query_param},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    #[tokio::test]
+    async fn test_serpapi_trending() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(query_param("engine", "google_trends_trending_now"))
+            .and(query_param("geo", "US"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "trending_searches": [{
+                    "query": "world series",
+                    "start_timestamp": 1729000000,
+                    "active": true,
+                    "search_volume": 500000,
+                    "increase_percentage": 1000,
+                    "categories": [{ "id": 17, "name": "Sports" }]
+                }, {
+                    "query": "eclipse"
+                }]
+            })))
+            .mount(&server)
+            .await;
+
+        let provider = SerpApiTrendsProvider::new("test-key")
+            .unwrap()
+            .with_base_url(&server.uri());
+        let trends = trending("us", &provider).await.unwrap();
+
+        assert_eq!(trends.len(), 2);
+        assert_eq!(trends[0].query, "world series");
+        assert_eq!(trends[0].search_volume, Some(500000));
+        assert_eq!(trends[0].categories, ["Sports"]);
+        assert!(trends[0].started_at.is_some());
+        assert_eq!(trends[1].search_volume, None);
+    }
+
+    #[tokio::test]
+    async fn test_empty_region_is_rejected() {
+        let provider = SerpApiTrendsProvider::new("test-key").unwrap();
+        assert!(matches!(
+            trending(" ", &provider).await,
+            Err(SearchError::InvalidInput(_))
+        ));
+    }
+}
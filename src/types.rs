@@ -1,12 +1,21 @@
 //! Core types and traits for the search SDK
 
+use crate::dry_run::RequestPlan;
 use crate::error::SearchError;
+use crate::processors::ResultProcessor;
+use crate::utils::http::{extract_domain, favicon_url};
+use crate::utils::intern::intern;
+use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fmt;
+use std::ops::Range;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
 
 /// Represents a web search result returned by any search provider
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SearchResult {
     /// URL of the search result
     pub url: String,
@@ -14,19 +23,524 @@ pub struct SearchResult {
     pub title: String,
     /// Snippet/description of the web page
     pub snippet: Option<String>,
-    /// The source website domain
-    pub domain: Option<String>,
+    /// What kind of result this is, for providers that tell them apart
+    #[serde(default, skip_serializing_if = "ResultKind::is_organic")]
+    pub kind: ResultKind,
+    /// Byte ranges of `snippet` the provider highlighted as matching the query (see
+    /// [`crate::highlight`])
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub highlights: Vec<Range<usize>>,
+    /// The source website domain, shared between results from the same site (see
+    /// [`crate::utils::intern`])
+    pub domain: Option<Arc<str>>,
     /// When the result was published or last updated
     pub published_date: Option<String>,
-    /// The search provider that returned this result
-    pub provider: Option<String>,
+    /// The search provider that returned this result, shared between its results
+    pub provider: Option<Arc<str>>,
     /// Raw response data from the provider
     #[serde(skip_serializing_if = "Option::is_none")]
     pub raw: Option<serde_json::Value>,
+    /// Readable text of the result page, populated by [`crate::enrich::fetch_content`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    /// Preview image for the result (e.g. Google's `cse_thumbnail` or the page's `og:image`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thumbnail_url: Option<String>,
+    /// Icon of the result's site, when the provider exposes one (see
+    /// [`favicon_or_fallback`](Self::favicon_or_fallback))
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub favicon_url: Option<String>,
+    /// Video details, for results of [`SearchMode::Video`] searches
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub video: Option<VideoResult>,
+    /// Place details, for results of [`SearchMode::Places`] searches
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub place: Option<PlaceResult>,
+    /// Description the page declares about itself (`og:description`), when the provider exposes it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Human-readable site name (`og:site_name`), when the provider exposes it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub site_name: Option<String>,
+    /// ISO 639-1 code of the result's language, when known (see `websearch::langdetect`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// Relevance score the provider reported, on its own scale (Exa, Tavily)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub score: Option<f64>,
+    /// Relevance from 0 to 1, comparable across providers, when set by
+    /// [`NormalizeScores`](crate::processors::NormalizeScores)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub normalized_score: Option<f64>,
+    /// Embedding of the title and snippet, when computed (see `websearch::embeddings`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub embedding: Option<Vec<f32>>,
+    /// Every provider that returned this URL in an aggregate search, with its rank there
+    ///
+    /// Filled in by the aggregate strategy and merged by deduplication, so a result several
+    /// engines agree on lists each of them. Empty for single-provider searches.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sources: Vec<ProviderHit>,
+}
+
+impl SearchResult {
+    /// The provider's favicon, else the conventional `/favicon.ico` of the result's domain
+    ///
+    /// The fallback isn't checked, so it may not exist; `None` when the result has neither
+    /// a favicon nor a domain.
+    ///
+    /// ```rust
+    /// use websearch::SearchResult;
+    ///
+    /// let result = SearchResult {
+    ///     url: "https://www.rust-lang.org/learn".to_string(),
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(
+    ///     result.favicon_or_fallback().as_deref(),
+    ///     Some("https://www.rust-lang.org/favicon.ico")
+    /// );
+    /// ```
+    pub fn favicon_or_fallback(&self) -> Option<String> {
+        if let Some(favicon) = &self.favicon_url {
+            return Some(favicon.clone());
+        }
+        let domain = self.domain.clone().or_else(|| extract_domain(&self.url))?;
+        Some(favicon_url(&domain))
+    }
+}
+
+/// Details of a video result (see [`SearchMode::Video`])
+///
+/// Each field is set when the provider reports it.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VideoResult {
+    /// Running time, written like `"3m 25s"` when serialized
+    #[serde(
+        default,
+        with = "humantime_serde",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub duration: Option<Duration>,
+    /// Channel or account that published the video
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channel: Option<String>,
+    /// View count
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub views: Option<u64>,
+    /// Still image of the video, also set as the result's
+    /// [`thumbnail_url`](SearchResult::thumbnail_url)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thumbnail_url: Option<String>,
+}
+
+/// Details of a place result (see [`SearchMode::Places`])
+///
+/// The place's name is the result's [`title`](SearchResult::title). Each field is set when
+/// the provider reports it.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PlaceResult {
+    /// Postal address, formatted for display
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address: Option<String>,
+    /// Latitude in degrees
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latitude: Option<f64>,
+    /// Longitude in degrees
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub longitude: Option<f64>,
+    /// Average rating, usually out of 5
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rating: Option<f64>,
+    /// Number of reviews behind `rating`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reviews: Option<u64>,
+    /// Opening hours as the provider words them, e.g. `"Open ⋅ Closes 6 PM"`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hours: Option<String>,
+    /// Phone number
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub phone: Option<String>,
+    /// Kind of place, e.g. `"Coffee shop"`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+}
+
+/// What a search looks for, see [`SearchOptions::mode`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+#[serde(rename_all = "snake_case")]
+pub enum SearchMode {
+    /// Web pages, supported by every provider
+    #[default]
+    Web,
+    /// Videos, with [`SearchResult::video`] set (Brave, SerpAPI's YouTube engine and
+    /// YouTube)
+    Video,
+    /// Local businesses and other places, with [`SearchResult::place`] set (Brave and
+    /// SerpAPI's Google Maps engine)
+    Places,
+}
+
+impl fmt::Display for SearchMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SearchMode::Web => "web",
+            SearchMode::Video => "video",
+            SearchMode::Places => "places",
+        })
+    }
+}
+
+impl FromStr for SearchMode {
+    type Err = SearchError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        parse_variant(
+            "search mode",
+            value,
+            &[
+                ("web", SearchMode::Web),
+                ("video", SearchMode::Video),
+                ("places", SearchMode::Places),
+            ],
+        )
+    }
+}
+
+/// Where results should be relevant to, see [`SearchOptions::location`]
+///
+/// Serialized as `{"latitude": 52.52, "longitude": 13.405}` or a plain name string.
+///
+/// ```rust
+/// use websearch::GeoLocation;
+///
+/// let point: GeoLocation = "52.52, 13.405".parse().unwrap();
+/// assert_eq!(point, GeoLocation::Coordinates { latitude: 52.52, longitude: 13.405 });
+/// let city: GeoLocation = "Austin,Texas,United States".parse().unwrap();
+/// assert_eq!(city, GeoLocation::Name("Austin,Texas,United States".to_string()));
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum GeoLocation {
+    /// A point, in degrees
+    Coordinates { latitude: f64, longitude: f64 },
+    /// A location name, ideally in the canonical `City,Region,Country` form of Google's
+    /// geotargets (as listed by SerpAPI's Locations API), e.g. `"Austin,Texas,United States"`
+    Name(String),
+}
+
+impl GeoLocation {
+    /// Check that coordinates are in range and names aren't empty
+    pub fn validate(&self) -> Result<(), SearchError> {
+        match self {
+            GeoLocation::Coordinates {
+                latitude,
+                longitude,
+            } => {
+                if !(-90.0..=90.0).contains(latitude) || !(-180.0..=180.0).contains(longitude) {
+                    return Err(SearchError::InvalidInput(format!(
+                        "Coordinates out of range: {latitude},{longitude}"
+                    )));
+                }
+            }
+            GeoLocation::Name(name) => {
+                if name.trim().is_empty() {
+                    return Err(SearchError::InvalidInput(
+                        "Location name is empty".to_string(),
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for GeoLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GeoLocation::Coordinates {
+                latitude,
+                longitude,
+            } => write!(f, "{latitude},{longitude}"),
+            GeoLocation::Name(name) => f.write_str(name),
+        }
+    }
+}
+
+impl FromStr for GeoLocation {
+    type Err = SearchError;
+
+    /// `"latitude,longitude"` gives coordinates, anything else a name
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim();
+        let coordinates = value.split_once(',').and_then(|(latitude, longitude)| {
+            Some((
+                latitude.trim().parse().ok()?,
+                longitude.trim().parse().ok()?,
+            ))
+        });
+        let location = match coordinates {
+            Some((latitude, longitude)) => GeoLocation::Coordinates {
+                latitude,
+                longitude,
+            },
+            _ => GeoLocation::Name(value.to_string()),
+        };
+        location.validate()?;
+        Ok(location)
+    }
+}
+
+/// Kinds of results providers mix into their listings
+///
+/// Set by providers that label their results: SerpAPI ads and inline videos, Brave's news,
+/// video, discussion and location results and Google's documents. Everything else is
+/// [`Organic`](ResultKind::Organic).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+#[serde(rename_all = "snake_case")]
+pub enum ResultKind {
+    /// A regular web result
+    #[default]
+    Organic,
+    /// A sponsored result
+    Ad,
+    /// A news article
+    News,
+    /// A video
+    Video,
+    /// A forum thread or other discussion
+    Forum,
+    /// A document file such as a PDF
+    Document,
+    /// A business or other place
+    Place,
+}
+
+impl ResultKind {
+    /// Whether this is the default kind, [`Organic`](ResultKind::Organic)
+    pub fn is_organic(&self) -> bool {
+        *self == ResultKind::Organic
+    }
+}
+
+impl fmt::Display for ResultKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ResultKind::Organic => "organic",
+            ResultKind::Ad => "ad",
+            ResultKind::News => "news",
+            ResultKind::Video => "video",
+            ResultKind::Forum => "forum",
+            ResultKind::Document => "document",
+            ResultKind::Place => "place",
+        })
+    }
+}
+
+impl FromStr for ResultKind {
+    type Err = SearchError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        parse_variant(
+            "result kind",
+            value,
+            &[
+                ("organic", ResultKind::Organic),
+                ("ad", ResultKind::Ad),
+                ("news", ResultKind::News),
+                ("video", ResultKind::Video),
+                ("forum", ResultKind::Forum),
+                ("document", ResultKind::Document),
+                ("place", ResultKind::Place),
+            ],
+        )
+    }
+}
+
+/// One provider's vote for a result
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProviderHit {
+    /// Name of the provider
+    pub provider: Arc<str>,
+    /// Position in that provider's results, starting at 1
+    pub rank: usize,
+    /// Relevance score the provider reported, for providers that report one (Exa, Tavily)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub score: Option<f64>,
+}
+
+impl ProviderHit {
+    /// The hit for `result`, returned by `provider` at `rank`
+    pub(crate) fn new(provider: &str, rank: usize, result: &SearchResult) -> Self {
+        Self {
+            provider: intern(provider),
+            rank,
+            score: result.score.or_else(|| {
+                result
+                    .raw
+                    .as_ref()
+                    .and_then(|raw| raw.get("score"))
+                    .and_then(|score| score.as_f64())
+            }),
+        }
+    }
+}
+
+/// Structured answer a provider shows next to its regular results
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RichResult {
+    /// Direct answer or featured snippet
+    AnswerBox {
+        title: Option<String>,
+        answer: Option<String>,
+        snippet: Option<String>,
+        url: Option<String>,
+    },
+    /// Entity panel describing a person, place, organisation, ...
+    KnowledgeGraph {
+        title: String,
+        entity_type: Option<String>,
+        description: Option<String>,
+        url: Option<String>,
+        image_url: Option<String>,
+        /// Remaining facts shown in the panel, e.g. "founded" => "2010"
+        attributes: BTreeMap<String, String>,
+    },
+    /// Related questions with short answers ("People also ask")
+    Faq { items: Vec<FaqItem> },
+    /// Headline carousel for news-worthy queries
+    TopStories { stories: Vec<SearchResult> },
+}
+
+/// One question of a [`RichResult::Faq`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FaqItem {
+    pub question: String,
+    pub answer: Option<String>,
+    pub url: Option<String>,
+}
+
+/// A result the provider returned that couldn't be parsed, and was left out of the response
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParseWarning {
+    pub provider: String,
+    /// Position of the result in the provider's response
+    pub index: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} result {} skipped: {}",
+            self.provider, self.index, self.message
+        )
+    }
+}
+
+/// Full response of a search: the results plus any rich results the provider returned
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SearchResponse {
+    /// Regular search results
+    pub results: Vec<SearchResult>,
+    /// Answer boxes, knowledge panels and similar, for providers that expose them
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rich_results: Vec<RichResult>,
+    /// The requests a [dry run](SearchOptions::dry_run) would have sent
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub planned_requests: Vec<RequestPlan>,
+    /// Results left out because they were malformed, for providers that parse leniently
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<ParseWarning>,
+}
+
+impl From<Vec<SearchResult>> for SearchResponse {
+    fn from(results: Vec<SearchResult>) -> Self {
+        Self {
+            results,
+            ..Default::default()
+        }
+    }
+}
+
+/// Overall result of a provider health check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthStatus {
+    /// The provider answered normally
+    Healthy,
+    /// The API key or other credentials were rejected
+    AuthFailed,
+    /// The provider is throttling requests, blocking them as automated, or the quota is used up
+    RateLimited,
+    /// Any other failure (network errors, server errors, bad configuration)
+    Unavailable,
+}
+
+impl HealthStatus {
+    /// Classify the error a provider returned
+    pub fn from_error(error: &SearchError) -> Self {
+        match error.root_cause() {
+            SearchError::AuthenticationError(_) => HealthStatus::AuthFailed,
+            SearchError::HttpError {
+                status_code: Some(401 | 403),
+                ..
+            } => HealthStatus::AuthFailed,
+            SearchError::RateLimit(_) | SearchError::BotDetected { .. } => {
+                HealthStatus::RateLimited
+            }
+            SearchError::HttpError {
+                status_code: Some(402 | 429),
+                ..
+            } => HealthStatus::RateLimited,
+            _ => HealthStatus::Unavailable,
+        }
+    }
+}
+
+/// Report from [`SearchProvider::health_check`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderHealth {
+    /// Name of the checked provider
+    pub provider: String,
+    pub status: HealthStatus,
+    /// Time taken by the check request
+    pub latency_ms: u64,
+    /// Error or informational message
+    pub message: Option<String>,
+    /// Remaining API quota, for providers that report it
+    pub remaining_quota: Option<u64>,
+}
+
+impl ProviderHealth {
+    /// Build a report from the outcome of a check request that took `latency`
+    pub fn from_result(
+        provider: &str,
+        latency: std::time::Duration,
+        result: Result<(), SearchError>,
+    ) -> Self {
+        let (status, message) = match result {
+            Ok(()) => (HealthStatus::Healthy, None),
+            Err(error) => (HealthStatus::from_error(&error), Some(error.to_string())),
+        };
+
+        Self {
+            provider: provider.to_string(),
+            status,
+            latency_ms: latency.as_millis() as u64,
+            message,
+            remaining_quota: None,
+        }
+    }
 }
 
 /// Debug options for the search SDK
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct DebugOptions {
     /// Enable verbose logging
     pub enabled: bool,
@@ -37,7 +551,9 @@ pub struct DebugOptions {
 }
 
 /// Safe search setting levels
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+#[serde(rename_all = "snake_case")]
 pub enum SafeSearch {
     Off,
     Moderate,
@@ -54,8 +570,127 @@ impl fmt::Display for SafeSearch {
     }
 }
 
+impl FromStr for SafeSearch {
+    type Err = SearchError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        parse_variant(
+            "safe search level",
+            value,
+            &[
+                ("off", SafeSearch::Off),
+                ("moderate", SafeSearch::Moderate),
+                ("strict", SafeSearch::Strict),
+            ],
+        )
+    }
+}
+
+/// Document types for [`SearchOptions::file_type`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+#[serde(rename_all = "snake_case")]
+pub enum FileType {
+    Pdf,
+    Doc,
+    Ppt,
+    Xls,
+}
+
+impl FileType {
+    /// File extension, as used by `filetype:` operators and Google's `fileType`
+    pub fn extension(self) -> &'static str {
+        match self {
+            FileType::Pdf => "pdf",
+            FileType::Doc => "doc",
+            FileType::Ppt => "ppt",
+            FileType::Xls => "xls",
+        }
+    }
+}
+
+impl fmt::Display for FileType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.extension())
+    }
+}
+
+impl FromStr for FileType {
+    type Err = SearchError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        parse_variant(
+            "file type",
+            value.trim_start_matches('.'),
+            &[
+                ("pdf", FileType::Pdf),
+                ("doc", FileType::Doc),
+                ("ppt", FileType::Ppt),
+                ("xls", FileType::Xls),
+            ],
+        )
+    }
+}
+
+/// How recent results must be, see [`SearchOptions::freshness`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+#[serde(rename_all = "snake_case")]
+pub enum Freshness {
+    /// The past 24 hours
+    Day,
+    /// The past 7 days
+    Week,
+    /// The past month
+    Month,
+    /// The past year
+    Year,
+}
+
+impl Freshness {
+    /// Length of the window in days (a month counts 31)
+    pub fn days(&self) -> u32 {
+        match self {
+            Freshness::Day => 1,
+            Freshness::Week => 7,
+            Freshness::Month => 31,
+            Freshness::Year => 365,
+        }
+    }
+}
+
+impl fmt::Display for Freshness {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Freshness::Day => "day",
+            Freshness::Week => "week",
+            Freshness::Month => "month",
+            Freshness::Year => "year",
+        })
+    }
+}
+
+impl FromStr for Freshness {
+    type Err = SearchError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        parse_variant(
+            "freshness",
+            value,
+            &[
+                ("day", Freshness::Day),
+                ("week", Freshness::Week),
+                ("month", Freshness::Month),
+                ("year", Freshness::Year),
+            ],
+        )
+    }
+}
+
 /// Sort options for search results (primarily for Arxiv)
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+#[serde(rename_all = "snake_case")]
 pub enum SortBy {
     Relevance,
     LastUpdatedDate,
@@ -72,8 +707,26 @@ impl fmt::Display for SortBy {
     }
 }
 
+impl FromStr for SortBy {
+    type Err = SearchError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        parse_variant(
+            "sort field",
+            value,
+            &[
+                ("relevance", SortBy::Relevance),
+                ("lastupdateddate", SortBy::LastUpdatedDate),
+                ("submitteddate", SortBy::SubmittedDate),
+            ],
+        )
+    }
+}
+
 /// Sort order for search results
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+#[serde(rename_all = "snake_case")]
 pub enum SortOrder {
     Ascending,
     Descending,
@@ -88,6 +741,47 @@ impl fmt::Display for SortOrder {
     }
 }
 
+impl FromStr for SortOrder {
+    type Err = SearchError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        parse_variant(
+            "sort order",
+            value,
+            &[
+                ("ascending", SortOrder::Ascending),
+                ("asc", SortOrder::Ascending),
+                ("descending", SortOrder::Descending),
+                ("desc", SortOrder::Descending),
+            ],
+        )
+    }
+}
+
+/// Match `value` against `variants` ignoring case, `-` and `_`, so `last-updated-date`,
+/// `last_updated_date` and `lastUpdatedDate` are the same; variant names are given in
+/// that normalized form
+pub(crate) fn parse_variant<T: Clone>(
+    kind: &str,
+    value: &str,
+    variants: &[(&str, T)],
+) -> Result<T, SearchError> {
+    let normalized: String = value
+        .trim()
+        .chars()
+        .filter(|c| !matches!(c, '-' | '_'))
+        .flat_map(char::to_lowercase)
+        .collect();
+    variants
+        .iter()
+        .find(|(name, _)| *name == normalized)
+        .map(|(_, variant)| variant.clone())
+        .ok_or_else(|| SearchError::InvalidInput(format!("Unknown {kind} '{value}'")))
+}
+
+/// Default [`SearchOptions::request_timeout`]
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(15);
+
 /// Common options for web search across all providers
 #[derive(Debug)]
 pub struct SearchOptions {
@@ -99,6 +793,9 @@ pub struct SearchOptions {
     pub max_results: Option<u32>,
     /// Language/locale for results
     pub language: Option<String>,
+    /// Drop results the provider reports in a language other than `language`, since many
+    /// providers treat it as a hint (see [`crate::language`])
+    pub require_language: bool,
     /// Country/region for results
     pub region: Option<String>,
     /// Safe search setting
@@ -111,14 +808,63 @@ pub struct SearchOptions {
     pub sort_by: Option<SortBy>,
     /// (Arxiv specific) Sort direction
     pub sort_order: Option<SortOrder>,
+    /// Only return results from these domains (for providers that support it)
+    pub include_domains: Option<Vec<String>>,
+    /// Never return results from these domains (for providers that support it)
+    pub exclude_domains: Option<Vec<String>>,
+    /// Drop results with these URLs, e.g. ones an earlier search already returned, and
+    /// fetch deeper pages to make up the count (see [`crate::seen`])
+    pub exclude_urls: Option<HashSet<String>>,
+    /// Drop results of these kinds, e.g. sponsored results (only ads by default; empty to
+    /// keep everything)
+    pub exclude_kinds: Vec<ResultKind>,
+    /// Only return results published on or after this date (for providers that support it)
+    pub published_after: Option<NaiveDate>,
+    /// Only return results published on or before this date (for providers that support it)
+    pub published_before: Option<NaiveDate>,
+    /// Only return documents of this type (Google, Brave, DuckDuckGo, SearXNG and SerpAPI's
+    /// Google and Bing engines)
+    pub file_type: Option<FileType>,
+    /// Only return results published within this window; providers without a recency
+    /// parameter drop older dated results client-side (see [`crate::freshness`])
+    pub freshness: Option<Freshness>,
+    /// Search for web pages, videos or places; providers that can't search in this mode
+    /// fail the search (see [`SearchProvider::supports_mode`])
+    pub mode: SearchMode,
+    /// Prefer results relevant to this location (Brave and SerpAPI's Google and Bing
+    /// engines, plus coordinates on its Google Maps engine; other providers ignore it)
+    pub location: Option<GeoLocation>,
+    /// Position in the provider's request queue when it orders by priority (higher goes
+    /// first; 0 when unset, see [`crate::queue`])
+    pub priority: Option<i32>,
+    /// ID tying this search's logs, tracing spans and `X-Request-Id` headers together;
+    /// generated when unset (see [`crate::correlation`])
+    pub correlation_id: Option<String>,
     /// Custom timeout in milliseconds
+    #[deprecated(since = "0.1.2", note = "use `request_timeout`")]
     pub timeout: Option<u64>,
+    /// How long the provider may take to answer (15 seconds by default; `None` for no limit)
+    pub request_timeout: Option<Duration>,
+    /// Fail the search if a provider response body is larger than this many bytes
+    pub max_response_bytes: Option<usize>,
+    /// Truncate result snippets to this many characters
+    pub max_snippet_chars: Option<usize>,
+    /// Keep each provider's original item in [`SearchResult::raw`] (true by default; see
+    /// [`without_raw`](Self::without_raw)). Providers still build it; it's dropped from the
+    /// results before they're returned.
+    pub include_raw: bool,
     /// Debug options
     pub debug: Option<DebugOptions>,
+    /// Build the provider's requests and return them in
+    /// [`SearchResponse::planned_requests`] instead of sending them (see [`crate::dry_run`])
+    pub dry_run: bool,
+    /// Applied in order to the results before they are returned (see [`crate::processors`])
+    pub processors: Vec<Arc<dyn ResultProcessor>>,
     /// The search provider to use
     pub provider: Box<dyn SearchProvider>,
 }
 
+#[allow(deprecated)]
 impl Default for SearchOptions {
     fn default() -> Self {
         Self {
@@ -126,19 +872,311 @@ impl Default for SearchOptions {
             id_list: None,
             max_results: Some(10),
             language: None,
+            require_language: false,
             region: None,
             safe_search: None,
             page: Some(1),
             start: None,
             sort_by: None,
             sort_order: None,
-            timeout: Some(15000), // 15 seconds
+            include_domains: None,
+            exclude_domains: None,
+            exclude_urls: None,
+            exclude_kinds: vec![ResultKind::Ad],
+            published_after: None,
+            published_before: None,
+            file_type: None,
+            freshness: None,
+            mode: SearchMode::Web,
+            location: None,
+            priority: None,
+            correlation_id: None,
+            timeout: None,
+            request_timeout: Some(DEFAULT_TIMEOUT),
+            max_response_bytes: None,
+            max_snippet_chars: None,
+            include_raw: true,
             debug: None,
+            dry_run: false,
+            processors: Vec::new(),
             provider: Box::new(DummyProvider), // Will be replaced
         }
     }
 }
 
+impl SearchOptions {
+    /// Drop [`SearchResult::raw`] from the results before they're returned, so pipelines
+    /// that only read the standard fields don't keep a copy of every provider item
+    ///
+    /// Providers parse and build the payloads either way, so this saves memory held after
+    /// the search, not the work during it.
+    pub fn without_raw(self) -> Self {
+        Self {
+            include_raw: false,
+            ..self
+        }
+    }
+
+    /// The timeout to apply: the deprecated millisecond `timeout` when a caller still sets
+    /// it, else [`request_timeout`](Self::request_timeout)
+    #[allow(deprecated)]
+    pub fn effective_timeout(&self) -> Option<Duration> {
+        self.timeout
+            .map(Duration::from_millis)
+            .or(self.request_timeout)
+    }
+
+    /// The query with a `filetype:` operator appended when [`file_type`](Self::file_type)
+    /// is set, for providers that take filters as query operators
+    pub(crate) fn query_with_file_type(&self) -> String {
+        match self.file_type {
+            Some(file_type) => format!("{} filetype:{}", self.query, file_type.extension()),
+            None => self.query.clone(),
+        }
+    }
+}
+
+/// Provider-independent search parameters
+///
+/// Carries the same fields as [`SearchOptions`] minus the provider, so a query can be
+/// reused across providers, queued in batches, or fanned out by `MultiProviderSearch`.
+///
+/// Unlike [`SearchOptions`] it implements serde, so queries can be read from JSON
+/// config or HTTP requests and persisted; missing fields take their default values.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SearchQuery {
+    /// The search query text
+    pub query: String,
+    /// (Arxiv specific) A comma-delimited list of Arxiv IDs to fetch
+    pub id_list: Option<String>,
+    /// Maximum number of results to return
+    pub max_results: Option<u32>,
+    /// Language/locale for results
+    pub language: Option<String>,
+    /// Drop results the provider reports in a language other than `language`, since many
+    /// providers treat it as a hint (see [`crate::language`])
+    pub require_language: bool,
+    /// Country/region for results
+    pub region: Option<String>,
+    /// Safe search setting
+    pub safe_search: Option<SafeSearch>,
+    /// Result page number (for pagination)
+    pub page: Option<u32>,
+    /// (Arxiv specific) The starting index for results (pagination offset)
+    pub start: Option<u32>,
+    /// (Arxiv specific) Sort order for results
+    pub sort_by: Option<SortBy>,
+    /// (Arxiv specific) Sort direction
+    pub sort_order: Option<SortOrder>,
+    /// Only return results from these domains (for providers that support it)
+    pub include_domains: Option<Vec<String>>,
+    /// Never return results from these domains (for providers that support it)
+    pub exclude_domains: Option<Vec<String>>,
+    /// Drop results with these URLs, e.g. ones an earlier search already returned, and
+    /// fetch deeper pages to make up the count (see [`crate::seen`])
+    pub exclude_urls: Option<HashSet<String>>,
+    /// Drop results of these kinds, e.g. sponsored results (only ads by default; empty to
+    /// keep everything)
+    pub exclude_kinds: Vec<ResultKind>,
+    /// Only return results published on or after this date (for providers that support it)
+    pub published_after: Option<NaiveDate>,
+    /// Only return results published on or before this date (for providers that support it)
+    pub published_before: Option<NaiveDate>,
+    /// Only return documents of this type (Google, Brave, DuckDuckGo, SearXNG and SerpAPI's
+    /// Google and Bing engines)
+    pub file_type: Option<FileType>,
+    /// Only return results published within this window; providers without a recency
+    /// parameter drop older dated results client-side (see [`crate::freshness`])
+    pub freshness: Option<Freshness>,
+    /// Search for web pages, videos or places; providers that can't search in this mode
+    /// fail the search (see [`SearchProvider::supports_mode`])
+    pub mode: SearchMode,
+    /// Prefer results relevant to this location (Brave and SerpAPI's Google and Bing
+    /// engines, plus coordinates on its Google Maps engine; other providers ignore it)
+    pub location: Option<GeoLocation>,
+    /// Position in the provider's request queue when it orders by priority (higher goes
+    /// first; 0 when unset, see [`crate::queue`])
+    pub priority: Option<i32>,
+    /// ID tying this search's logs, tracing spans and `X-Request-Id` headers together;
+    /// generated when unset (see [`crate::correlation`])
+    pub correlation_id: Option<String>,
+    /// (`MultiProviderSearch` only) Restrict the search to these providers, by name
+    pub providers: Option<Vec<String>>,
+    /// Custom timeout in milliseconds
+    #[deprecated(since = "0.1.2", note = "use `request_timeout`")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<u64>,
+    /// How long the provider may take to answer, written like `"10s"` or `"1m 30s"`
+    #[serde(with = "humantime_serde")]
+    pub request_timeout: Option<Duration>,
+    /// Fail the search if a provider response body is larger than this many bytes
+    pub max_response_bytes: Option<usize>,
+    /// Truncate result snippets to this many characters
+    pub max_snippet_chars: Option<usize>,
+    /// Keep each provider's original item in [`SearchResult::raw`] (true by default;
+    /// dropped after the provider builds it)
+    pub include_raw: bool,
+    /// Debug options
+    pub debug: Option<DebugOptions>,
+}
+
+impl SearchQuery {
+    /// Create a query for the given text with default options
+    pub fn new(query: impl Into<String>) -> Self {
+        Self {
+            query: query.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Drop [`SearchResult::raw`] from the results, as [`SearchOptions::without_raw`]
+    pub fn without_raw(self) -> Self {
+        Self {
+            include_raw: false,
+            ..self
+        }
+    }
+
+    /// Build [`SearchOptions`] for this query bound to the given provider
+    pub fn with_provider(&self, provider: Box<dyn SearchProvider>) -> SearchOptions {
+        SearchOptions {
+            provider,
+            ..self.to_search_options()
+        }
+    }
+
+    /// Build [`SearchOptions`] for this query without a provider attached
+    ///
+    /// Used when the provider is invoked directly rather than through `options.provider`.
+    #[allow(deprecated)]
+    pub(crate) fn to_search_options(&self) -> SearchOptions {
+        SearchOptions {
+            query: self.query.clone(),
+            id_list: self.id_list.clone(),
+            max_results: self.max_results,
+            language: self.language.clone(),
+            require_language: self.require_language,
+            region: self.region.clone(),
+            safe_search: self.safe_search.clone(),
+            page: self.page,
+            start: self.start,
+            sort_by: self.sort_by.clone(),
+            sort_order: self.sort_order.clone(),
+            include_domains: self.include_domains.clone(),
+            exclude_domains: self.exclude_domains.clone(),
+            exclude_urls: self.exclude_urls.clone(),
+            exclude_kinds: self.exclude_kinds.clone(),
+            published_after: self.published_after,
+            published_before: self.published_before,
+            file_type: self.file_type,
+            freshness: self.freshness,
+            mode: self.mode,
+            location: self.location.clone(),
+            priority: self.priority,
+            correlation_id: self.correlation_id.clone(),
+            timeout: self.timeout,
+            request_timeout: self.request_timeout,
+            max_response_bytes: self.max_response_bytes,
+            max_snippet_chars: self.max_snippet_chars,
+            include_raw: self.include_raw,
+            debug: self.debug.clone(),
+            dry_run: false,
+            processors: Vec::new(),
+            provider: Box::new(DummyProvider),
+        }
+    }
+}
+
+#[allow(deprecated)]
+impl Default for SearchQuery {
+    fn default() -> Self {
+        Self {
+            query: String::new(),
+            id_list: None,
+            max_results: Some(10),
+            language: None,
+            require_language: false,
+            region: None,
+            safe_search: None,
+            page: Some(1),
+            start: None,
+            sort_by: None,
+            sort_order: None,
+            include_domains: None,
+            exclude_domains: None,
+            exclude_urls: None,
+            exclude_kinds: vec![ResultKind::Ad],
+            published_after: None,
+            published_before: None,
+            file_type: None,
+            freshness: None,
+            mode: SearchMode::Web,
+            location: None,
+            priority: None,
+            correlation_id: None,
+            providers: None,
+            timeout: None,
+            request_timeout: Some(DEFAULT_TIMEOUT),
+            max_response_bytes: None,
+            max_snippet_chars: None,
+            include_raw: true,
+            debug: None,
+        }
+    }
+}
+
+impl From<&SearchOptions> for SearchQuery {
+    /// Copy everything except the provider, e.g. to serialize the options
+    #[allow(deprecated)]
+    fn from(options: &SearchOptions) -> Self {
+        Self {
+            query: options.query.clone(),
+            id_list: options.id_list.clone(),
+            max_results: options.max_results,
+            language: options.language.clone(),
+            require_language: options.require_language,
+            region: options.region.clone(),
+            safe_search: options.safe_search.clone(),
+            page: options.page,
+            start: options.start,
+            sort_by: options.sort_by.clone(),
+            sort_order: options.sort_order.clone(),
+            include_domains: options.include_domains.clone(),
+            exclude_domains: options.exclude_domains.clone(),
+            exclude_urls: options.exclude_urls.clone(),
+            exclude_kinds: options.exclude_kinds.clone(),
+            published_after: options.published_after,
+            published_before: options.published_before,
+            file_type: options.file_type,
+            freshness: options.freshness,
+            mode: options.mode,
+            location: options.location.clone(),
+            priority: options.priority,
+            correlation_id: options.correlation_id.clone(),
+            providers: None,
+            timeout: options.timeout,
+            request_timeout: options.request_timeout,
+            max_response_bytes: options.max_response_bytes,
+            max_snippet_chars: options.max_snippet_chars,
+            include_raw: options.include_raw,
+            debug: options.debug.clone(),
+        }
+    }
+}
+
+impl From<&str> for SearchQuery {
+    fn from(query: &str) -> Self {
+        Self::new(query)
+    }
+}
+
+impl From<String> for SearchQuery {
+    fn from(query: String) -> Self {
+        Self::new(query)
+    }
+}
+
 /// Trait that all search provider implementations must satisfy
 #[async_trait::async_trait]
 pub trait SearchProvider: Send + Sync + std::fmt::Debug {
@@ -148,10 +1186,138 @@ pub trait SearchProvider: Send + Sync + std::fmt::Debug {
     /// Search method implementation
     async fn search(&self, options: &SearchOptions) -> Result<Vec<SearchResult>, SearchError>;
 
+    /// Search and keep provider extras such as answer boxes
+    ///
+    /// Providers without rich results can rely on the default, which wraps [`search`](Self::search).
+    async fn search_response(
+        &self,
+        options: &SearchOptions,
+    ) -> Result<SearchResponse, SearchError> {
+        Ok(self.search(options).await?.into())
+    }
+
     /// Get provider configuration (for debugging/logging)
     fn config(&self) -> HashMap<String, String> {
         HashMap::new()
     }
+
+    /// Whether the provider itself filters adult content for [`SafeSearch::Strict`]
+    ///
+    /// When it doesn't, strict searches fall back to the domain blocklist in
+    /// [`crate::safe_search`].
+    fn supports_safe_search(&self) -> bool {
+        false
+    }
+
+    /// Whether the provider itself applies `include_domains` and `exclude_domains`
+    ///
+    /// When it doesn't, results are filtered client-side by [`crate::domains`].
+    fn supports_domain_filter(&self) -> bool {
+        false
+    }
+
+    /// Whether the provider itself applies [`SearchOptions::freshness`]
+    ///
+    /// When it doesn't, results are filtered client-side by [`crate::freshness`].
+    fn supports_freshness(&self) -> bool {
+        false
+    }
+
+    /// Whether the provider returns later pages for [`SearchOptions::page`]
+    ///
+    /// Deeper pages, e.g. to replace results dropped by `exclude_urls`, are only requested
+    /// from providers that paginate; the rest would answer with the first page again.
+    fn supports_pagination(&self) -> bool {
+        false
+    }
+
+    /// Whether the provider can search in `mode`; every provider supports
+    /// [`SearchMode::Web`]
+    fn supports_mode(&self, mode: SearchMode) -> bool {
+        mode == SearchMode::Web
+    }
+
+    /// Check that the provider is reachable and accepts the configured credentials
+    ///
+    /// The default runs a one-result search; providers with a cheaper status or account
+    /// endpoint override it.
+    async fn health_check(&self) -> ProviderHealth {
+        let options = SearchOptions {
+            query: "health check".to_string(),
+            max_results: Some(1),
+            ..Default::default()
+        };
+
+        let started = std::time::Instant::now();
+        let result = self.search(&options).await.map(|_| ());
+        ProviderHealth::from_result(self.name(), started.elapsed(), result)
+    }
+
+    /// Check that the configured credentials are accepted, e.g. to catch a bad key at startup
+    ///
+    /// The default runs a one-result search. Providers with a free account endpoint use it
+    /// instead, and providers without credentials succeed without sending a request.
+    async fn validate_credentials(&self) -> Result<(), SearchError> {
+        let options = SearchOptions {
+            query: "credential check".to_string(),
+            max_results: Some(1),
+            ..Default::default()
+        };
+        self.search(&options).await.map(|_| ())
+    }
+}
+
+/// Shared providers: one configured provider can back many searches and tasks at once
+///
+/// `Box::new(provider.clone())` fits anywhere a `Box<dyn SearchProvider>` is expected.
+#[async_trait::async_trait]
+impl<T: SearchProvider + ?Sized> SearchProvider for Arc<T> {
+    fn name(&self) -> &str {
+        (**self).name()
+    }
+
+    async fn search(&self, options: &SearchOptions) -> Result<Vec<SearchResult>, SearchError> {
+        (**self).search(options).await
+    }
+
+    async fn search_response(
+        &self,
+        options: &SearchOptions,
+    ) -> Result<SearchResponse, SearchError> {
+        (**self).search_response(options).await
+    }
+
+    fn config(&self) -> HashMap<String, String> {
+        (**self).config()
+    }
+
+    fn supports_safe_search(&self) -> bool {
+        (**self).supports_safe_search()
+    }
+
+    fn supports_domain_filter(&self) -> bool {
+        (**self).supports_domain_filter()
+    }
+
+    fn supports_freshness(&self) -> bool {
+        (**self).supports_freshness()
+    }
+
+    fn supports_pagination(&self) -> bool {
+        (**self).supports_pagination()
+    }
+
+    fn supports_mode(&self, mode: SearchMode) -> bool {
+        (**self).supports_mode(mode)
+    }
+
+    async fn health_check(&self) -> ProviderHealth {
+        (**self).health_check().await
+    }
+
+    async fn validate_credentials(&self) -> Result<(), SearchError> {
+        (**self).validate_credentials().await
+    }
 }
 
 /// Dummy provider for default implementation (should not be used)
@@ -1,12 +1,20 @@
 //! Debug utilities for the search SDK
 
-use crate::types::DebugOptions;
+use crate::{correlation, types::DebugOptions};
+
+/// Prefix for debug lines, including the running search's correlation ID
+fn prefix() -> String {
+    match correlation::current_correlation_id() {
+        Some(id) => format!("[search-sdk] [{id}]"),
+        None => "[search-sdk]".to_string(),
+    }
+}
 
 /// Log a message if debugging is enabled
 pub fn log(options: &Option<DebugOptions>, message: &str, data: &str) {
     if let Some(debug_opts) = options {
         if debug_opts.enabled {
-            eprintln!("[search-sdk] {message}: {data}");
+            eprintln!("{} {message}: {data}", prefix());
         }
     }
 }
@@ -15,7 +23,7 @@ pub fn log(options: &Option<DebugOptions>, message: &str, data: &str) {
 pub fn log_request(options: &Option<DebugOptions>, message: &str, data: &str) {
     if let Some(debug_opts) = options {
         if debug_opts.enabled && debug_opts.log_requests {
-            eprintln!("[search-sdk] REQUEST: {message}: {data}");
+            eprintln!("{} REQUEST: {message}: {data}", prefix());
         }
     }
 }
@@ -24,7 +32,7 @@ pub fn log_request(options: &Option<DebugOptions>, message: &str, data: &str) {
 pub fn log_response(options: &Option<DebugOptions>, message: &str) {
     if let Some(debug_opts) = options {
         if debug_opts.enabled && debug_opts.log_responses {
-            eprintln!("[search-sdk] RESPONSE: {message}");
+            eprintln!("{} RESPONSE: {message}", prefix());
         }
     }
 }
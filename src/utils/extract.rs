@@ -0,0 +1,411 @@
+//! Readability-style extraction of the main content of an HTML page
+
+use crate::utils::http::normalize_text;
+use scraper::{ElementRef, Html, Selector};
+
+/// Elements that never contain readable content
+const SKIPPED_TAGS: &[&str] = &[
+    "script", "style", "noscript", "template", "svg", "head", "nav", "header", "footer", "aside",
+    "form", "iframe", "button", "select", "dialog",
+];
+
+/// ARIA roles used for page chrome rather than content
+const SKIPPED_ROLES: &[&str] = &[
+    "navigation",
+    "banner",
+    "contentinfo",
+    "complementary",
+    "search",
+    "dialog",
+];
+
+/// `class`/`id` tokens that mark ads and other boilerplate
+const BOILERPLATE_TOKENS: &[&str] = &[
+    "ad",
+    "ads",
+    "advert",
+    "advertisement",
+    "banner",
+    "breadcrumb",
+    "breadcrumbs",
+    "comments",
+    "cookie",
+    "cookies",
+    "footer",
+    "menu",
+    "nav",
+    "navbar",
+    "newsletter",
+    "popup",
+    "promo",
+    "related",
+    "share",
+    "sidebar",
+    "social",
+    "sponsored",
+    "subscribe",
+];
+
+/// Elements whose text forms its own paragraph
+const BLOCK_TAGS: &[&str] = &[
+    "p",
+    "div",
+    "section",
+    "article",
+    "main",
+    "body",
+    "li",
+    "dt",
+    "dd",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "pre",
+    "blockquote",
+    "figcaption",
+    "td",
+    "th",
+    "tr",
+];
+
+/// Content extracted from an HTML page
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExtractedPage {
+    /// Page title (`og:title`, `<title>` or the first `<h1>`)
+    pub title: Option<String>,
+    /// Main text with boilerplate removed, one paragraph per block separated by blank lines
+    pub text: String,
+    /// Canonical URL (`<link rel="canonical">` or `og:url`)
+    pub canonical_url: Option<String>,
+    /// Meta description (`description` or `og:description`)
+    pub description: Option<String>,
+}
+
+/// Extract the title, main text, canonical URL and description from an HTML document
+///
+/// Navigation, headers, footers, forms, scripts and elements whose class or id marks
+/// them as ads or sidebars are dropped. When the page has an `<article>`, `<main>` or
+/// `role="main"` element, only the largest of them is used as the content root.
+///
+/// # Examples
+///
+/// ```rust
+/// use websearch::utils::extract::extract_page;
+///
+/// let page = extract_page(
+///     "<html><head><title>Hi</title></head><body><nav>Home</nav><p>Hello</p></body></html>",
+/// );
+/// assert_eq!(page.title.as_deref(), Some("Hi"));
+/// assert_eq!(page.text, "Hello");
+/// ```
+pub fn extract_page(html: &str) -> ExtractedPage {
+    let document = Html::parse_document(html);
+
+    ExtractedPage {
+        title: extract_title(&document),
+        text: extract_main_text(&document),
+        canonical_url: first_attr(&document, r#"link[rel="canonical"]"#, "href")
+            .or_else(|| first_attr(&document, r#"meta[property="og:url"]"#, "content")),
+        description: first_attr(&document, r#"meta[name="description"]"#, "content")
+            .or_else(|| first_attr(&document, r#"meta[property="og:description"]"#, "content")),
+    }
+}
+
+/// Extract only the main text of an HTML document
+pub fn extract_text(html: &str) -> String {
+    extract_main_text(&Html::parse_document(html))
+}
+
+/// Text wrapped around highlighted terms when a snippet is converted to text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HighlightMarkers {
+    /// Inserted before each highlighted term
+    pub open: String,
+    /// Inserted after each highlighted term
+    pub close: String,
+}
+
+impl HighlightMarkers {
+    pub fn new(open: impl Into<String>, close: impl Into<String>) -> Self {
+        Self {
+            open: open.into(),
+            close: close.into(),
+        }
+    }
+
+    /// Markdown bold: `**term**`
+    pub fn markdown() -> Self {
+        Self::new("**", "**")
+    }
+}
+
+/// Plain text of an HTML fragment such as a result title or snippet
+///
+/// Entities are decoded, tags removed and whitespace collapsed. With `markers`, terms the
+/// provider highlighted with `<b>` or `<strong>` are wrapped in them instead of being lost.
+///
+/// ```rust
+/// use websearch::utils::extract::{fragment_text, HighlightMarkers};
+///
+/// let snippet = "<b>Rust</b> is fast &amp; safe";
+/// assert_eq!(fragment_text(snippet, None), "Rust is fast & safe");
+/// assert_eq!(
+///     fragment_text(snippet, Some(&HighlightMarkers::markdown())),
+///     "**Rust** is fast & safe"
+/// );
+/// ```
+pub fn fragment_text(html: &str, markers: Option<&HighlightMarkers>) -> String {
+    element_text(Html::parse_fragment(html).root_element(), markers)
+}
+
+/// Plain text of an element's contents, as [`fragment_text`]
+pub fn element_text(element: ElementRef, markers: Option<&HighlightMarkers>) -> String {
+    let mut text = String::new();
+    push_text(element, markers, &mut text);
+    normalize_text(&text)
+}
+
+/// Append the text under `element`, wrapping the outermost highlights in `markers`
+fn push_text(element: ElementRef, markers: Option<&HighlightMarkers>, text: &mut String) {
+    for child in element.children() {
+        if let Some(fragment) = child.value().as_text() {
+            text.push_str(fragment);
+        } else if let Some(child) = ElementRef::wrap(child) {
+            match markers.filter(|_| matches!(child.value().name(), "b" | "strong")) {
+                Some(marker) => {
+                    text.push_str(&marker.open);
+                    // Nested highlights are already inside the markers
+                    push_text(child, None, text);
+                    text.push_str(&marker.close);
+                }
+                None => push_text(child, markers, text),
+            }
+        }
+    }
+}
+
+fn extract_title(document: &Html) -> Option<String> {
+    first_attr(document, r#"meta[property="og:title"]"#, "content")
+        .or_else(|| first_text(document, "title"))
+        .or_else(|| first_text(document, "h1"))
+}
+
+fn extract_main_text(document: &Html) -> String {
+    let selector = Selector::parse(r#"article, main, [role="main"]"#).unwrap();
+
+    let paragraphs = document
+        .select(&selector)
+        .map(collect_paragraphs)
+        .filter(|paragraphs| !paragraphs.is_empty())
+        .max_by_key(|paragraphs| paragraphs.iter().map(String::len).sum::<usize>())
+        .unwrap_or_else(|| collect_paragraphs(document.root_element()));
+
+    paragraphs.join("\n\n")
+}
+
+/// Walk the text nodes under `root`, grouping them by their nearest block ancestor
+fn collect_paragraphs(root: ElementRef) -> Vec<String> {
+    let mut paragraphs = Vec::new();
+    let mut current = String::new();
+    let mut current_block = None;
+
+    for node in root.descendants() {
+        let Some(text) = node.value().as_text() else {
+            continue;
+        };
+        if text.trim().is_empty() {
+            continue;
+        }
+
+        let mut block = None;
+        let mut hidden = false;
+        for ancestor in node.ancestors() {
+            let Some(element) = ElementRef::wrap(ancestor) else {
+                continue;
+            };
+            if is_boilerplate(element) {
+                hidden = true;
+                break;
+            }
+            if block.is_none() && BLOCK_TAGS.contains(&element.value().name()) {
+                block = Some(ancestor.id());
+            }
+            if ancestor.id() == root.id() {
+                break;
+            }
+        }
+        if hidden {
+            continue;
+        }
+
+        if block != current_block && !current.trim().is_empty() {
+            paragraphs.push(normalize_text(&current));
+            current.clear();
+        }
+        current_block = block;
+        current.push_str(text);
+        current.push(' ');
+    }
+
+    if !current.trim().is_empty() {
+        paragraphs.push(normalize_text(&current));
+    }
+
+    paragraphs
+}
+
+fn is_boilerplate(element: ElementRef) -> bool {
+    let value = element.value();
+
+    if SKIPPED_TAGS.contains(&value.name())
+        || value.attr("hidden").is_some()
+        || value.attr("aria-hidden") == Some("true")
+    {
+        return true;
+    }
+
+    if let Some(role) = value.attr("role") {
+        if SKIPPED_ROLES.contains(&role.trim().to_ascii_lowercase().as_str()) {
+            return true;
+        }
+    }
+
+    [value.attr("class"), value.attr("id")]
+        .into_iter()
+        .flatten()
+        .flat_map(|attr| attr.split(|c: char| c.is_whitespace() || c == '-' || c == '_'))
+        .any(|token| BOILERPLATE_TOKENS.contains(&token.to_ascii_lowercase().as_str()))
+}
+
+fn first_attr(document: &Html, selector: &str, attr: &str) -> Option<String> {
+    let selector = Selector::parse(selector).ok()?;
+    document
+        .select(&selector)
+        .filter_map(|element| element.value().attr(attr))
+        .map(normalize_text)
+        .find(|value| !value.is_empty())
+}
+
+fn first_text(document: &Html, selector: &str) -> Option<String> {
+    let selector = Selector::parse(selector).ok()?;
+    document
+        .select(&selector)
+        .map(|element| normalize_text(&element.text().collect::<String>()))
+        .find(|value| !value.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fragment_text_decodes_and_marks_highlights() {
+        let html = "Rust&#x27;s   <b>borrow <i>checker</i></b> &lt;3 <strong>safety</strong>";
+
+        assert_eq!(fragment_text(html, None), "Rust's borrow checker <3 safety");
+        assert_eq!(
+            fragment_text(html, Some(&HighlightMarkers::new("[", "]"))),
+            "Rust's [borrow checker] <3 [safety]"
+        );
+    }
+
+    #[test]
+    fn test_extracts_metadata() {
+        let html = r#"<html><head>
+            <title>Fallback title</title>
+            <meta property="og:title" content="Open Graph title">
+            <meta name="description" content="  A short   summary ">
+            <link rel="canonical" href="https://example.com/post">
+        </head><body><p>Body</p></body></html>"#;
+
+        let page = extract_page(html);
+
+        assert_eq!(page.title.as_deref(), Some("Open Graph title"));
+        assert_eq!(page.description.as_deref(), Some("A short summary"));
+        assert_eq!(
+            page.canonical_url.as_deref(),
+            Some("https://example.com/post")
+        );
+    }
+
+    #[test]
+    fn test_metadata_fallbacks() {
+        let html = r#"<html><head>
+            <meta property="og:url" content="https://example.com/og">
+            <meta property="og:description" content="OG description">
+        </head><body><h1>Heading title</h1></body></html>"#;
+
+        let page = extract_page(html);
+
+        assert_eq!(page.title.as_deref(), Some("Heading title"));
+        assert_eq!(page.description.as_deref(), Some("OG description"));
+        assert_eq!(
+            page.canonical_url.as_deref(),
+            Some("https://example.com/og")
+        );
+    }
+
+    #[test]
+    fn test_strips_page_chrome_and_scripts() {
+        let html = r#"<html><body>
+            <header><a href="/">Logo</a></header>
+            <nav><ul><li>Home</li><li>About</li></ul></nav>
+            <div class="content">
+                <h1>Title</h1>
+                <script>track();</script>
+                <p>First <b>paragraph</b> text.</p>
+                <div class="ad-banner">Buy now!</div>
+                <p>Second paragraph.</p>
+            </div>
+            <div id="sidebar">Popular posts</div>
+            <footer>Copyright</footer>
+        </body></html>"#;
+
+        assert_eq!(
+            extract_text(html),
+            "Title\n\nFirst paragraph text.\n\nSecond paragraph."
+        );
+    }
+
+    #[test]
+    fn test_prefers_article_content() {
+        let html = r#"<html><body>
+            <div>Teaser text outside the article</div>
+            <article>
+                <h2>Story</h2>
+                <p>The story body is here.</p>
+                <div class="share-buttons">Share on social</div>
+            </article>
+            <div role="complementary">Related links</div>
+        </body></html>"#;
+
+        assert_eq!(extract_text(html), "Story\n\nThe story body is here.");
+    }
+
+    #[test]
+    fn test_picks_largest_main_candidate() {
+        let html = r#"<html><body>
+            <article><p>Short</p></article>
+            <main><p>A much longer main section of the page.</p></main>
+        </body></html>"#;
+
+        assert_eq!(
+            extract_text(html),
+            "A much longer main section of the page."
+        );
+    }
+
+    #[test]
+    fn test_does_not_match_partial_tokens() {
+        let html = r#"<html><body>
+            <div class="shadow-box"><p>Kept because shadow is not an ad.</p></div>
+            <div hidden>Hidden</div>
+            <div aria-hidden="true">Also hidden</div>
+        </body></html>"#;
+
+        assert_eq!(extract_text(html), "Kept because shadow is not an ad.");
+    }
+}
@@ -1,41 +1,273 @@
 //! HTTP utilities for making requests to search APIs
 
-use crate::error::{SearchError, SearchResult};
-use reqwest::{Client, Response};
+use crate::{
+    cassette,
+    error::{SearchError, SearchResult},
+};
+use bytes::Bytes;
+use reqwest::{header, Client, RequestBuilder, Response, StatusCode};
 use serde::de::DeserializeOwned;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
 use std::time::Duration;
 use url::Url;
 
+/// Number of GET responses each client keeps for conditional revalidation
+const MAX_VALIDATED_RESPONSES: usize = 64;
+
+/// User agent sent by clients built from [`HttpSettings`]
+const USER_AGENT: &str = "search-sdk-rust/0.0.1";
+
+/// Connection settings shared by every provider's HTTP client
+///
+/// Providers build their clients from the process-wide defaults when they are created, so
+/// call [`set_default_http_settings`] before creating providers. [`HttpClient::builder`]
+/// starts from the same defaults for a one-off client.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::time::Duration;
+/// use websearch::{utils::http::set_default_http_settings, HttpSettings};
+///
+/// set_default_http_settings(
+///     HttpSettings::default()
+///         .with_connect_timeout(Duration::from_secs(2))
+///         .with_pool_idle_timeout(Duration::from_secs(30))
+///         .with_tcp_keepalive(Duration::from_secs(60)),
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct HttpSettings {
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    pool_idle_timeout: Option<Duration>,
+    pool_max_idle_per_host: Option<usize>,
+    tcp_keepalive: Option<Duration>,
+    dns_cache: bool,
+}
+
+impl Default for HttpSettings {
+    fn default() -> Self {
+        Self {
+            timeout: Some(Duration::from_millis(15000)),
+            connect_timeout: None,
+            pool_idle_timeout: None,
+            pool_max_idle_per_host: None,
+            tcp_keepalive: None,
+            dns_cache: false,
+        }
+    }
+}
+
+impl HttpSettings {
+    /// Limit on a whole request, from connecting to reading the last byte
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Let requests take as long as the server does
+    pub fn without_timeout(mut self) -> Self {
+        self.timeout = None;
+        self
+    }
+
+    /// Limit on establishing the connection, so unreachable hosts fail fast while slow
+    /// responses still get the full request timeout
+    pub fn with_connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Close pooled connections idle for longer than `timeout` (90s by default)
+    pub fn with_pool_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.pool_idle_timeout = Some(timeout);
+        self
+    }
+
+    /// Keep at most `max` idle connections per host (unlimited by default)
+    pub fn with_pool_max_idle_per_host(mut self, max: usize) -> Self {
+        self.pool_max_idle_per_host = Some(max);
+        self
+    }
+
+    /// Send TCP keep-alive probes on idle connections every `interval`
+    pub fn with_tcp_keepalive(mut self, interval: Duration) -> Self {
+        self.tcp_keepalive = Some(interval);
+        self
+    }
+
+    /// Resolve hosts with the caching Hickory DNS resolver instead of the system
+    /// resolver (needs the `dns-cache` feature; ignored without it)
+    pub fn with_dns_cache(mut self, dns_cache: bool) -> Self {
+        self.dns_cache = dns_cache;
+        self
+    }
+
+    /// A `reqwest` client builder with these settings, for providers that don't use
+    /// [`HttpClient`]
+    pub fn client_builder(&self) -> reqwest::ClientBuilder {
+        let mut builder = Client::builder().user_agent(USER_AGENT);
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+        if let Some(timeout) = self.connect_timeout {
+            builder = builder.connect_timeout(timeout);
+        }
+        if let Some(timeout) = self.pool_idle_timeout {
+            builder = builder.pool_idle_timeout(timeout);
+        }
+        if let Some(max) = self.pool_max_idle_per_host {
+            builder = builder.pool_max_idle_per_host(max);
+        }
+        if let Some(interval) = self.tcp_keepalive {
+            builder = builder.tcp_keepalive(interval);
+        }
+        #[cfg(feature = "dns-cache")]
+        {
+            builder = builder.hickory_dns(self.dns_cache);
+        }
+        builder
+    }
+
+    /// Build an [`HttpClient`] with these settings
+    pub fn build(self) -> HttpClient {
+        HttpClient {
+            client: self
+                .client_builder()
+                .build()
+                .expect("Failed to create HTTP client"),
+            default_headers: Vec::new(),
+            validated: Default::default(),
+        }
+    }
+}
+
+fn default_settings() -> &'static RwLock<HttpSettings> {
+    static SETTINGS: OnceLock<RwLock<HttpSettings>> = OnceLock::new();
+    SETTINGS.get_or_init(Default::default)
+}
+
+/// Use `settings` for HTTP clients created from now on
+pub fn set_default_http_settings(settings: HttpSettings) {
+    *default_settings().write().unwrap() = settings;
+}
+
+/// The settings new HTTP clients are created with
+pub fn default_http_settings() -> HttpSettings {
+    default_settings().read().unwrap().clone()
+}
+
 /// HTTP client wrapper with search-specific functionality
+///
+/// GET responses that carry an `ETag` or `Last-Modified` validator are remembered, and
+/// repeating the request sends `If-None-Match`/`If-Modified-Since`; when the server
+/// answers `304 Not Modified` the remembered body is returned without downloading it
+/// again. Clones share the remembered responses.
+///
+/// Responses are requested compressed (`Accept-Encoding: gzip, br`) and decompressed
+/// transparently; [`with_accept_encoding`](Self::with_accept_encoding) and
+/// [`with_accept`](Self::with_accept) override the negotiated headers for servers that
+/// need something else.
 #[derive(Debug, Clone)]
 pub struct HttpClient {
     client: Client,
-    default_timeout: Duration,
+    default_headers: Vec<(String, String)>,
+    validated: Arc<Mutex<ValidatedResponses>>,
+}
+
+/// A response body with the validators needed to revalidate it
+#[derive(Debug)]
+struct Validated {
+    etag: Option<String>,
+    last_modified: Option<String>,
+    content_type: Option<String>,
+    body: Bytes,
+}
+
+/// Bounded map of validated responses, oldest evicted first
+#[derive(Debug, Default)]
+struct ValidatedResponses {
+    entries: HashMap<String, Validated>,
+    order: VecDeque<String>,
+}
+
+impl ValidatedResponses {
+    fn insert(&mut self, key: String, validated: Validated) {
+        if self.entries.insert(key.clone(), validated).is_none() {
+            self.order.push_back(key);
+            if self.order.len() > MAX_VALIDATED_RESPONSES {
+                if let Some(oldest) = self.order.pop_front() {
+                    self.entries.remove(&oldest);
+                }
+            }
+        }
+    }
 }
 
 impl HttpClient {
-    /// Create a new HTTP client with default settings
+    /// Create a new HTTP client with the [default settings](default_http_settings)
     pub fn new() -> Self {
-        Self {
-            client: Client::builder()
-                .user_agent("search-sdk-rust/0.0.1")
-                .build()
-                .expect("Failed to create HTTP client"),
-            default_timeout: Duration::from_millis(15000),
-        }
+        Self::builder().build()
     }
 
     /// Create a new HTTP client with custom timeout
+    pub fn with_request_timeout(timeout: Duration) -> Self {
+        Self::builder().with_timeout(timeout).build()
+    }
+
+    /// Create a new HTTP client with a timeout in milliseconds
+    #[deprecated(since = "0.1.2", note = "use `with_request_timeout`")]
     pub fn with_timeout(timeout_ms: u64) -> Self {
-        Self {
-            client: Client::builder()
-                .user_agent("search-sdk-rust/0.0.1")
-                .timeout(Duration::from_millis(timeout_ms))
-                .build()
-                .expect("Failed to create HTTP client"),
-            default_timeout: Duration::from_millis(timeout_ms),
+        Self::with_request_timeout(Duration::from_millis(timeout_ms))
+    }
+
+    /// Settings for a new client, starting from the [defaults](default_http_settings)
+    pub fn builder() -> HttpSettings {
+        default_http_settings()
+    }
+
+    /// Send `name: value` with every request, unless the request sets that header itself
+    pub fn with_default_header(
+        mut self,
+        name: impl Into<String>,
+        value: impl Into<String>,
+    ) -> Self {
+        let name = name.into();
+        self.default_headers
+            .retain(|(existing, _)| !existing.eq_ignore_ascii_case(&name));
+        self.default_headers.push((name, value.into()));
+        self
+    }
+
+    /// Send `Accept: accept` with every request
+    pub fn with_accept(self, accept: impl Into<String>) -> Self {
+        self.with_default_header(header::ACCEPT.as_str(), accept)
+    }
+
+    /// Send `Accept-Encoding: encoding` instead of the default `gzip, br`
+    ///
+    /// Use `identity` to ask for uncompressed responses.
+    pub fn with_accept_encoding(self, encoding: impl Into<String>) -> Self {
+        self.with_default_header(header::ACCEPT_ENCODING.as_str(), encoding)
+    }
+
+    /// Add the default headers and then `headers` (which take precedence) to `request`
+    fn with_headers(
+        &self,
+        mut request: RequestBuilder,
+        headers: HashMap<String, String>,
+    ) -> RequestBuilder {
+        for (name, value) in &self.default_headers {
+            if !headers.keys().any(|key| key.eq_ignore_ascii_case(name)) {
+                request = request.header(name, value);
+            }
+        }
+        for (name, value) in headers {
+            request = request.header(name, value);
         }
+        request
     }
 
     /// Make a GET request and deserialize the JSON response
@@ -43,14 +275,7 @@ impl HttpClient {
     where
         T: DeserializeOwned,
     {
-        let response = self
-            .client
-            .get(url)
-            .timeout(self.default_timeout)
-            .send()
-            .await?;
-
-        self.handle_response_json(response).await
+        self.get_json_with_headers(url, HashMap::new()).await
     }
 
     /// Make a GET request with headers and deserialize the JSON response
@@ -62,42 +287,138 @@ impl HttpClient {
     where
         T: DeserializeOwned,
     {
-        let mut request = self.client.get(url).timeout(self.default_timeout);
-
-        for (key, value) in headers {
-            request = request.header(key, value);
-        }
-
-        let response = request.send().await?;
-        self.handle_response_json(response).await
+        let body = self.get_bytes_with_headers(url, headers).await?;
+        Ok(serde_json::from_slice(&body)?)
     }
 
     /// Make a GET request and return the response as text
     pub async fn get_text(&self, url: &str) -> SearchResult<String> {
-        let response = self
-            .client
-            .get(url)
-            .timeout(self.default_timeout)
-            .send()
-            .await?;
-
-        self.handle_response_text(response).await
+        self.get_text_with_headers(url, HashMap::new()).await
     }
 
     /// Make a GET request with headers and return the response as text
+    ///
+    /// The body is decoded with the charset named in the `Content-Type` header, or as
+    /// UTF-8 when there is none.
     pub async fn get_text_with_headers(
         &self,
         url: &str,
         headers: HashMap<String, String>,
     ) -> SearchResult<String> {
-        let mut request = self.client.get(url).timeout(self.default_timeout);
+        let (status, content_type, body) = self.fetch(url, headers).await?;
+        let text = decode_text(&body, content_type.as_deref());
+        if status.is_success() {
+            Ok(text)
+        } else {
+            Err(status_error(status, text))
+        }
+    }
+
+    /// Make a GET request and return the undecoded body, e.g. for `serde_json::from_slice`
+    pub async fn get_bytes(&self, url: &str) -> SearchResult<Bytes> {
+        self.get_bytes_with_headers(url, HashMap::new()).await
+    }
 
-        for (key, value) in headers {
-            request = request.header(key, value);
+    /// Make a GET request with headers and return the undecoded body
+    pub async fn get_bytes_with_headers(
+        &self,
+        url: &str,
+        headers: HashMap<String, String>,
+    ) -> SearchResult<Bytes> {
+        let (status, content_type, body) = self.fetch(url, headers).await?;
+        if status.is_success() {
+            Ok(body)
+        } else {
+            Err(status_error(
+                status,
+                decode_text(&body, content_type.as_deref()),
+            ))
         }
+    }
 
-        let response = request.send().await?;
-        self.handle_response_text(response).await
+    /// Make a GET request with headers and return the status and body, whatever the status
+    ///
+    /// A `304 Not Modified` answer to a revalidated request is returned as `200 OK` with
+    /// the remembered body. The body is decoded as by
+    /// [`get_text_with_headers`](Self::get_text_with_headers).
+    pub async fn get_with_status(
+        &self,
+        url: &str,
+        headers: HashMap<String, String>,
+    ) -> SearchResult<(StatusCode, String)> {
+        let (status, content_type, body) = self.fetch(url, headers).await?;
+        Ok((status, decode_text(&body, content_type.as_deref())))
+    }
+
+    /// Like [`get_with_status`](Self::get_with_status), but without decoding the body
+    ///
+    /// The body is shared rather than copied, including with the revalidation cache.
+    pub async fn get_bytes_with_status(
+        &self,
+        url: &str,
+        headers: HashMap<String, String>,
+    ) -> SearchResult<(StatusCode, Bytes)> {
+        let (status, _, body) = self.fetch(url, headers).await?;
+        Ok((status, body))
+    }
+
+    /// Send a GET request, revalidating a remembered response, and return the status,
+    /// `Content-Type` and body
+    async fn fetch(
+        &self,
+        url: &str,
+        headers: HashMap<String, String>,
+    ) -> SearchResult<(StatusCode, Option<String>, Bytes)> {
+        let mut sorted: Vec<_> = headers.iter().collect();
+        sorted.sort();
+        let key = format!("{url} {sorted:?}");
+
+        let mut request = self.with_headers(self.client.get(url), headers);
+        if let Some(validated) = self.validated.lock().unwrap().entries.get(&key) {
+            if let Some(etag) = &validated.etag {
+                request = request.header(header::IF_NONE_MATCH, etag);
+            }
+            if let Some(last_modified) = &validated.last_modified {
+                request = request.header(header::IF_MODIFIED_SINCE, last_modified);
+            }
+        }
+
+        let response = cassette::send(request).await?;
+        let status = response.status();
+        if status == StatusCode::NOT_MODIFIED {
+            if let Some(validated) = self.validated.lock().unwrap().entries.get(&key) {
+                return Ok((
+                    StatusCode::OK,
+                    validated.content_type.clone(),
+                    validated.body.clone(),
+                ));
+            }
+        }
+
+        let validator = |name| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+        };
+        let etag = validator(header::ETAG);
+        let last_modified = validator(header::LAST_MODIFIED);
+        let content_type = validator(header::CONTENT_TYPE);
+        let body = response.bytes().await?;
+
+        if status.is_success() && (etag.is_some() || last_modified.is_some()) {
+            self.validated.lock().unwrap().insert(
+                key,
+                Validated {
+                    etag,
+                    last_modified,
+                    content_type: content_type.clone(),
+                    body: body.clone(),
+                },
+            );
+        }
+        Ok((status, content_type, body))
     }
 
     /// Make a POST request with form data and deserialize the JSON response
@@ -109,13 +430,10 @@ impl HttpClient {
     where
         T: DeserializeOwned,
     {
-        let response = self
-            .client
-            .post(url)
-            .timeout(self.default_timeout)
-            .form(&form_data)
-            .send()
-            .await?;
+        let response = cassette::send(
+            self.with_headers(self.client.post(url).form(&form_data), HashMap::new()),
+        )
+        .await?;
 
         self.handle_response_json(response).await
     }
@@ -126,13 +444,10 @@ impl HttpClient {
         url: &str,
         form_data: HashMap<String, String>,
     ) -> SearchResult<String> {
-        let response = self
-            .client
-            .post(url)
-            .timeout(self.default_timeout)
-            .form(&form_data)
-            .send()
-            .await?;
+        let response = cassette::send(
+            self.with_headers(self.client.post(url).form(&form_data), HashMap::new()),
+        )
+        .await?;
 
         self.handle_response_text(response).await
     }
@@ -144,18 +459,58 @@ impl HttpClient {
         form_data: HashMap<String, String>,
         headers: HashMap<String, String>,
     ) -> SearchResult<String> {
-        let mut request = self
-            .client
-            .post(url)
-            .timeout(self.default_timeout)
-            .form(&form_data);
+        let request = self.with_headers(self.client.post(url).form(&form_data), headers);
+
+        let response = cassette::send(request).await?;
+        self.handle_response_text(response).await
+    }
+
+    /// Make a GET request with headers and return the body as text, reading at most `max_bytes`
+    ///
+    /// Bodies larger than the limit are truncated rather than rejected, which is what
+    /// page fetching wants: the start of a document is still useful.
+    pub async fn get_text_limited(
+        &self,
+        url: &str,
+        headers: HashMap<String, String>,
+        max_bytes: usize,
+    ) -> SearchResult<TextResponse> {
+        let request = self.with_headers(self.client.get(url), headers);
+
+        let mut response = cassette::send(request).await?;
+        let status = response.status();
 
-        for (key, value) in headers {
-            request = request.header(key, value);
+        if !status.is_success() {
+            return Err(SearchError::HttpError {
+                message: format!("Request failed with status: {status}"),
+                status_code: Some(status.as_u16()),
+                response_body: None,
+            });
         }
 
-        let response = request.send().await?;
-        self.handle_response_text(response).await
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.to_string());
+
+        let mut body = Vec::new();
+        let mut truncated = false;
+        while let Some(chunk) = response.chunk().await? {
+            let remaining = max_bytes.saturating_sub(body.len());
+            if chunk.len() > remaining {
+                body.extend_from_slice(&chunk[..remaining]);
+                truncated = true;
+                break;
+            }
+            body.extend_from_slice(&chunk);
+        }
+
+        Ok(TextResponse {
+            body: decode_text(&body, content_type.as_deref()),
+            content_type,
+            truncated,
+        })
     }
 
     /// Handle HTTP response and deserialize as JSON
@@ -200,6 +555,17 @@ impl HttpClient {
     }
 }
 
+/// Text body returned by [`HttpClient::get_text_limited`]
+#[derive(Debug, Clone)]
+pub struct TextResponse {
+    /// Response body, lossily decoded as UTF-8
+    pub body: String,
+    /// Value of the `Content-Type` header, if present
+    pub content_type: Option<String>,
+    /// Whether the body was cut off at the size limit
+    pub truncated: bool,
+}
+
 impl Default for HttpClient {
     fn default() -> Self {
         Self::new()
@@ -217,11 +583,16 @@ pub fn build_url(base_url: &str, params: HashMap<String, String>) -> SearchResul
     Ok(url.to_string())
 }
 
-/// Extract domain from a URL
-pub fn extract_domain(url: &str) -> Option<String> {
+/// Extract domain from a URL, as a shared copy (see [`intern`](super::intern::intern))
+pub fn extract_domain(url: &str) -> Option<Arc<str>> {
     Url::parse(url)
         .ok()
-        .and_then(|parsed| parsed.host_str().map(|host| host.to_string()))
+        .and_then(|parsed| parsed.host_str().map(super::intern::intern))
+}
+
+/// The conventional favicon location of a site, `https://{domain}/favicon.ico`
+pub fn favicon_url(domain: &str) -> String {
+    format!("https://{domain}/favicon.ico")
 }
 
 /// Normalize text by removing excess whitespace
@@ -239,3 +610,174 @@ pub fn normalize_url(url: &str) -> String {
         url.to_string()
     }
 }
+
+/// Decode `body` with the charset named in `content_type`, falling back to UTF-8
+///
+/// A byte order mark takes precedence, as in browsers; undecodable bytes become U+FFFD.
+fn decode_text(body: &[u8], content_type: Option<&str>) -> String {
+    let encoding = content_type
+        .and_then(|content_type| {
+            content_type.split(';').skip(1).find_map(|param| {
+                let (name, value) = param.split_once('=')?;
+                name.trim()
+                    .eq_ignore_ascii_case("charset")
+                    .then(|| value.trim().trim_matches('"'))
+            })
+        })
+        .and_then(|label| encoding_rs::Encoding::for_label(label.as_bytes()))
+        .unwrap_or(encoding_rs::UTF_8);
+    encoding.decode(body).0.into_owned()
+}
+
+/// The error for an unsuccessful `status`, keeping the decoded body for diagnosis
+fn status_error(status: StatusCode, body: String) -> SearchError {
+    SearchError::HttpError {
+        message: format!("Request failed with status: {status}"),
+        status_code: Some(status.as_u16()),
+        response_body: Some(body),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::{
+        matchers::{header, headers, method},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    #[tokio::test]
+    async fn test_unchanged_responses_are_revalidated() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(header("If-None-Match", "\"v1\""))
+            .respond_with(ResponseTemplate::new(304))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("ETag", "\"v1\"")
+                    .set_body_string("{\"answer\": 42}"),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = HttpClient::new();
+        let url = format!("{}/search?q=rust", server.uri());
+        for _ in 0..2 {
+            let body: serde_json::Value = client.get_json(&url).await.unwrap();
+            assert_eq!(body["answer"], 42);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_text_is_decoded_with_the_content_type_charset() {
+        let server = MockServer::start().await;
+        // "café crème" in ISO-8859-1
+        let latin1 = b"caf\xe9 cr\xe8me".to_vec();
+        Mock::given(method("GET"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_raw(latin1.clone(), "text/plain; charset=ISO-8859-1"),
+            )
+            .mount(&server)
+            .await;
+
+        let client = HttpClient::new();
+        let url = format!("{}/latin1", server.uri());
+        assert_eq!(client.get_text(&url).await.unwrap(), "café crème");
+        let (status, text) = client.get_with_status(&url, HashMap::new()).await.unwrap();
+        assert_eq!((status, text.as_str()), (StatusCode::OK, "café crème"));
+        assert_eq!(client.get_bytes(&url).await.unwrap(), latin1);
+
+        assert_eq!(decode_text("naïve".as_bytes(), None), "naïve");
+        assert_eq!(
+            decode_text(b"na\xefve", Some("text/html; charset=\"windows-1252\"")),
+            "naïve"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_connect_timeout_is_separate_from_request_timeout() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string("slow")
+                    .set_delay(Duration::from_millis(300)),
+            )
+            .mount(&server)
+            .await;
+
+        let client = HttpClient::builder()
+            .with_connect_timeout(Duration::from_millis(100))
+            .with_timeout(Duration::from_secs(5))
+            .build();
+        let url = format!("{}/slow", server.uri());
+        assert_eq!(client.get_text(&url).await.unwrap(), "slow");
+
+        // Nothing answers on this non-routable address, so only the connect timeout can
+        // end the request before the 5 second limit
+        let started = std::time::Instant::now();
+        assert!(client.get_text("http://10.255.255.1/").await.is_err());
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+
+    #[tokio::test]
+    #[allow(deprecated)]
+    async fn test_request_timeouts() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string("slow")
+                    .set_delay(Duration::from_millis(300)),
+            )
+            .mount(&server)
+            .await;
+
+        let url = format!("{}/slow", server.uri());
+        let short = Duration::from_millis(100);
+        assert!(HttpClient::with_request_timeout(short)
+            .get_text(&url)
+            .await
+            .is_err());
+        assert!(HttpClient::with_timeout(100).get_text(&url).await.is_err());
+
+        let client = HttpClient::builder()
+            .with_timeout(short)
+            .without_timeout()
+            .build();
+        assert_eq!(client.get_text(&url).await.unwrap(), "slow");
+    }
+
+    #[tokio::test]
+    async fn test_compression_and_accept_overrides() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(headers("Accept-Encoding", vec!["gzip", "br"]))
+            .respond_with(ResponseTemplate::new(200).set_body_string("compressed"))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(header("Accept-Encoding", "identity"))
+            .and(header("Accept", "application/json"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("overridden"))
+            .mount(&server)
+            .await;
+
+        let url = format!("{}/accept", server.uri());
+        assert_eq!(
+            HttpClient::new().get_text(&url).await.unwrap(),
+            "compressed"
+        );
+
+        let client = HttpClient::new()
+            .with_accept_encoding("identity")
+            .with_accept("application/json");
+        assert_eq!(client.get_text(&url).await.unwrap(), "overridden");
+    }
+}
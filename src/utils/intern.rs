@@ -0,0 +1,69 @@
+//! Shared copies of strings that repeat across many results
+//!
+//! A thousand results from one provider would otherwise hold a thousand copies of
+//! `"google"`, and most of their domains repeat too. [`intern`] hands out one [`Arc<str>`]
+//! per distinct string, so cloning a result or its provider name is a reference count
+//! bump rather than an allocation.
+//!
+//! Provider names are string literals, so providers use the `interned!` macro, which looks each one
+//! up once and keeps it in a static, leaving the table's lock to domains.
+
+use std::collections::HashSet;
+use std::sync::{Arc, OnceLock, RwLock};
+
+/// Distinct strings kept before the table is emptied, bounding its memory in long-running
+/// processes that see many different domains
+const MAX_ENTRIES: usize = 16_384;
+
+/// The shared copy of `s`, allocated on first use
+pub fn intern(s: &str) -> Arc<str> {
+    static TABLE: OnceLock<RwLock<HashSet<Arc<str>>>> = OnceLock::new();
+    let table = TABLE.get_or_init(Default::default);
+    if let Some(existing) = table.read().unwrap().get(s) {
+        return existing.clone();
+    }
+
+    let mut table = table.write().unwrap();
+    // Another thread may have added it between the locks
+    if let Some(existing) = table.get(s) {
+        return existing.clone();
+    }
+    // Strings already handed out stay valid; later calls just get new copies
+    if table.len() >= MAX_ENTRIES {
+        table.clear();
+    }
+    let interned: Arc<str> = Arc::from(s);
+    table.insert(interned.clone());
+    interned
+}
+
+/// The shared copy of a string literal, looked up on the first call from each call site
+macro_rules! interned {
+    ($s:literal) => {{
+        static SHARED: ::std::sync::OnceLock<::std::sync::Arc<str>> = ::std::sync::OnceLock::new();
+        SHARED
+            .get_or_init(|| $crate::utils::intern::intern($s))
+            .clone()
+    }};
+}
+pub(crate) use interned;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_intern_shares_one_copy() {
+        let a = intern("example.com");
+        let b = intern(&String::from("example.com"));
+        assert!(Arc::ptr_eq(&a, &b));
+        assert!(!Arc::ptr_eq(&a, &intern("example.org")));
+    }
+
+    #[test]
+    fn test_interned_literals_share_the_table_copy() {
+        let name = || interned!("example.net");
+        assert!(Arc::ptr_eq(&name(), &name()));
+        assert!(Arc::ptr_eq(&name(), &intern("example.net")));
+    }
+}
@@ -1,4 +1,8 @@
 //! Utility modules for the search SDK
 
 pub mod debug;
+pub mod extract;
 pub mod http;
+pub mod intern;
+pub mod robots;
+pub mod user_agent;
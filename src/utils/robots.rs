@@ -0,0 +1,317 @@
+//! robots.txt compliance for scraping providers and content enrichment
+//!
+//! [`RobotsChecker`] downloads `/robots.txt` once per origin, caches the parsed rules
+//! and answers whether a URL may be fetched. Rules are matched as described in
+//! RFC 9309: the most specific user-agent group applies, and within it the longest
+//! matching `Allow`/`Disallow` pattern wins.
+
+use crate::{
+    error::{SearchError, SearchResult},
+    utils::http::HttpClient,
+};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use url::Url;
+
+/// Product token used to select robots.txt groups
+pub const DEFAULT_ROBOTS_AGENT: &str = "websearch";
+
+/// How long fetched rules are reused before robots.txt is requested again
+const CACHE_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// robots.txt files larger than this are truncated (RFC 9309 requires at least 500 KiB)
+const MAX_ROBOTS_BYTES: usize = 512 * 1024;
+
+/// Parsed rules from a robots.txt file for a single user agent
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RobotsRules {
+    rules: Vec<RobotsRule>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct RobotsRule {
+    pattern: String,
+    allow: bool,
+}
+
+impl RobotsRules {
+    /// Rules that allow every path
+    pub fn allow_all() -> Self {
+        Self::default()
+    }
+
+    /// Parse a robots.txt body, keeping only the groups that apply to `agent`
+    ///
+    /// Groups naming `agent` take precedence; otherwise the `*` groups are used.
+    pub fn parse(body: &str, agent: &str) -> Self {
+        let agent = agent.to_ascii_lowercase();
+        let mut specific = Vec::new();
+        let mut wildcard = Vec::new();
+
+        let mut group_agents: Vec<String> = Vec::new();
+        let mut in_rules = false;
+
+        for line in body.lines() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let key = key.trim().to_ascii_lowercase();
+            let value = value.trim();
+
+            match key.as_str() {
+                "user-agent" => {
+                    if in_rules {
+                        group_agents.clear();
+                        in_rules = false;
+                    }
+                    group_agents.push(value.to_ascii_lowercase());
+                }
+                "allow" | "disallow" => {
+                    in_rules = true;
+                    // An empty Disallow means "allow everything" and adds no rule
+                    if value.is_empty() {
+                        continue;
+                    }
+                    let rule = RobotsRule {
+                        pattern: value.to_string(),
+                        allow: key == "allow",
+                    };
+                    if group_agents
+                        .iter()
+                        .any(|name| name != "*" && agent.contains(name.as_str()))
+                    {
+                        specific.push(rule);
+                    } else if group_agents.iter().any(|name| name == "*") {
+                        wildcard.push(rule);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Self {
+            rules: if specific.is_empty() {
+                wildcard
+            } else {
+                specific
+            },
+        }
+    }
+
+    /// Whether `path` (including any query string) may be fetched
+    pub fn is_allowed(&self, path: &str) -> bool {
+        self.rules
+            .iter()
+            .filter(|rule| pattern_matches(&rule.pattern, path))
+            // Longest pattern wins; on a tie, Allow beats Disallow
+            .max_by_key(|rule| (rule.pattern.len(), rule.allow))
+            .is_none_or(|rule| rule.allow)
+    }
+}
+
+/// Match a robots.txt path pattern supporting `*` wildcards and a trailing `$` anchor
+fn pattern_matches(pattern: &str, path: &str) -> bool {
+    let (pattern, anchored) = match pattern.strip_suffix('$') {
+        Some(pattern) => (pattern, true),
+        None => (pattern, false),
+    };
+
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = path.strip_prefix(first) else {
+        return false;
+    };
+
+    let parts: Vec<&str> = parts.collect();
+    for (index, part) in parts.iter().enumerate() {
+        let is_last = index == parts.len() - 1;
+        if is_last && anchored {
+            return rest.ends_with(part);
+        }
+        match rest.find(part) {
+            Some(position) => rest = &rest[position + part.len()..],
+            None => return false,
+        }
+    }
+
+    !anchored || rest.is_empty()
+}
+
+/// Fetches and caches robots.txt rules per origin
+#[derive(Debug)]
+pub struct RobotsChecker {
+    agent: String,
+    http_client: HttpClient,
+    cache: Mutex<HashMap<String, (Instant, Arc<RobotsRules>)>>,
+}
+
+impl RobotsChecker {
+    /// Create a checker identifying as [`DEFAULT_ROBOTS_AGENT`]
+    pub fn new() -> Self {
+        Self::with_agent(DEFAULT_ROBOTS_AGENT)
+    }
+
+    /// Create a checker that selects robots.txt groups for `agent`
+    pub fn with_agent(agent: &str) -> Self {
+        Self {
+            agent: agent.to_string(),
+            http_client: HttpClient::with_request_timeout(Duration::from_secs(5)),
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Process-wide checker shared by the built-in scrapers and the enrichment fetcher
+    pub fn shared() -> &'static RobotsChecker {
+        static SHARED: OnceLock<RobotsChecker> = OnceLock::new();
+        SHARED.get_or_init(RobotsChecker::new)
+    }
+
+    /// Whether robots.txt allows fetching `url`
+    ///
+    /// A missing robots.txt (4xx) allows everything. If robots.txt cannot be retrieved
+    /// at all, the URL is allowed and nothing is cached, so the next request retries.
+    pub async fn is_allowed(&self, url: &str) -> SearchResult<bool> {
+        let parsed = Url::parse(url)?;
+        let origin = parsed.origin().ascii_serialization();
+
+        let mut path = parsed.path().to_string();
+        if let Some(query) = parsed.query() {
+            path.push('?');
+            path.push_str(query);
+        }
+
+        Ok(self.rules_for(&origin).await.is_allowed(&path))
+    }
+
+    /// Return [`SearchError::RobotsDisallowed`] if robots.txt forbids fetching `url`
+    pub async fn check(&self, url: &str) -> SearchResult<()> {
+        if self.is_allowed(url).await? {
+            Ok(())
+        } else {
+            Err(SearchError::RobotsDisallowed {
+                url: url.to_string(),
+            })
+        }
+    }
+
+    async fn rules_for(&self, origin: &str) -> Arc<RobotsRules> {
+        if let Some((fetched_at, rules)) = self.cache.lock().unwrap().get(origin) {
+            if fetched_at.elapsed() < CACHE_TTL {
+                return Arc::clone(rules);
+            }
+        }
+
+        let mut headers = HashMap::new();
+        headers.insert(
+            "User-Agent".to_string(),
+            format!("{}/{}", self.agent, env!("CARGO_PKG_VERSION")),
+        );
+
+        let rules = match self
+            .http_client
+            .get_text_limited(&format!("{origin}/robots.txt"), headers, MAX_ROBOTS_BYTES)
+            .await
+        {
+            Ok(response) => RobotsRules::parse(&response.body, &self.agent),
+            Err(SearchError::HttpError {
+                status_code: Some(400..=499),
+                ..
+            }) => RobotsRules::allow_all(),
+            Err(_) => return Arc::new(RobotsRules::allow_all()),
+        };
+
+        let rules = Arc::new(rules);
+        self.cache
+            .lock()
+            .unwrap()
+            .insert(origin.to_string(), (Instant::now(), Arc::clone(&rules)));
+        rules
+    }
+}
+
+impl Default for RobotsChecker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::{
+        matchers::{method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    const ROBOTS: &str = "
+        # Example robots.txt
+        User-agent: *
+        Disallow: /private/
+        Allow: /private/public
+        Disallow: /*.pdf$
+
+        User-agent: otherbot
+        User-agent: websearch
+        Disallow: /html
+    ";
+
+    #[test]
+    fn test_specific_group_takes_precedence() {
+        let rules = RobotsRules::parse(ROBOTS, "websearch");
+        assert!(!rules.is_allowed("/html/?q=rust"));
+        assert!(rules.is_allowed("/private/page"));
+
+        let generic = RobotsRules::parse(ROBOTS, "somebot");
+        assert!(generic.is_allowed("/html/"));
+        assert!(!generic.is_allowed("/private/page"));
+    }
+
+    #[test]
+    fn test_longest_match_and_wildcards() {
+        let rules = RobotsRules::parse(ROBOTS, "somebot");
+        assert!(rules.is_allowed("/private/public/page"));
+        assert!(!rules.is_allowed("/docs/file.pdf"));
+        assert!(rules.is_allowed("/docs/file.pdf?download=1"));
+        assert!(rules.is_allowed("/"));
+    }
+
+    #[test]
+    fn test_empty_disallow_allows_everything() {
+        let rules = RobotsRules::parse("User-agent: *\nDisallow:\n", "websearch");
+        assert!(rules.is_allowed("/anything"));
+    }
+
+    #[tokio::test]
+    async fn test_checker_fetches_once_per_origin() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/robots.txt"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(ROBOTS))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let checker = RobotsChecker::new();
+        assert!(checker
+            .is_allowed(&format!("{}/docs", server.uri()))
+            .await
+            .unwrap());
+        assert!(matches!(
+            checker.check(&format!("{}/html/", server.uri())).await,
+            Err(SearchError::RobotsDisallowed { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_missing_robots_allows_everything() {
+        let server = MockServer::start().await;
+
+        let checker = RobotsChecker::new();
+        assert!(checker
+            .is_allowed(&format!("{}/html/", server.uri()))
+            .await
+            .unwrap());
+    }
+}
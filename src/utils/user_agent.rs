@@ -0,0 +1,119 @@
+//! User-agent selection for scraping providers
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Current desktop browser user agents used by [`UserAgentPolicy::default`]
+pub const DEFAULT_BROWSER_USER_AGENTS: &[&str] = &[
+    "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/129.0.0.0 Safari/537.36",
+    "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/129.0.0.0 Safari/537.36",
+    "Mozilla/5.0 (Windows NT 10.0; Win64; x64; rv:131.0) Gecko/20100101 Firefox/131.0",
+    "Mozilla/5.0 (Macintosh; Intel Mac OS X 14.7; rv:131.0) Gecko/20100101 Firefox/131.0",
+    "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/18.0 Safari/605.1.15",
+    "Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/129.0.0.0 Safari/537.36",
+];
+
+/// How a scraping provider picks the `User-Agent` header for each request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UserAgentPolicy {
+    /// Always send the same user agent
+    Fixed(String),
+    /// Cycle through the list in order, one entry per request
+    Rotating(Vec<String>),
+    /// Pick a random entry from the list for every request
+    Random(Vec<String>),
+}
+
+impl UserAgentPolicy {
+    /// Always send `user_agent`
+    pub fn fixed(user_agent: impl Into<String>) -> Self {
+        Self::Fixed(user_agent.into())
+    }
+
+    /// Cycle through `user_agents` in order
+    pub fn rotating<I, S>(user_agents: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self::Rotating(user_agents.into_iter().map(Into::into).collect())
+    }
+
+    /// Pick randomly from `user_agents` on every request
+    pub fn random<I, S>(user_agents: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self::Random(user_agents.into_iter().map(Into::into).collect())
+    }
+
+    /// Select the user agent for the next request
+    ///
+    /// `counter` holds the rotation position and is advanced by [`UserAgentPolicy::Rotating`].
+    /// Returns `None` when the policy has an empty list.
+    pub fn select(&self, counter: &AtomicUsize) -> Option<&str> {
+        match self {
+            Self::Fixed(user_agent) => Some(user_agent),
+            Self::Rotating(user_agents) if !user_agents.is_empty() => {
+                let index = counter.fetch_add(1, Ordering::Relaxed) % user_agents.len();
+                Some(&user_agents[index])
+            }
+            Self::Random(user_agents) if !user_agents.is_empty() => {
+                let index = random_u64() as usize % user_agents.len();
+                Some(&user_agents[index])
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Default for UserAgentPolicy {
+    fn default() -> Self {
+        Self::random(DEFAULT_BROWSER_USER_AGENTS.iter().copied())
+    }
+}
+
+/// Cheap non-cryptographic randomness from the standard library's randomly seeded hasher
+fn random_u64() -> u64 {
+    RandomState::new().build_hasher().finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixed_policy() {
+        let policy = UserAgentPolicy::fixed("agent/1.0");
+        let counter = AtomicUsize::new(0);
+        assert_eq!(policy.select(&counter), Some("agent/1.0"));
+        assert_eq!(policy.select(&counter), Some("agent/1.0"));
+    }
+
+    #[test]
+    fn test_rotating_policy_cycles() {
+        let policy = UserAgentPolicy::rotating(["a", "b", "c"]);
+        let counter = AtomicUsize::new(0);
+        let picked: Vec<_> = (0..4).map(|_| policy.select(&counter).unwrap()).collect();
+        assert_eq!(picked, ["a", "b", "c", "a"]);
+    }
+
+    #[test]
+    fn test_random_policy_picks_from_list() {
+        let policy = UserAgentPolicy::default();
+        let counter = AtomicUsize::new(0);
+        for _ in 0..20 {
+            let agent = policy.select(&counter).unwrap();
+            assert!(DEFAULT_BROWSER_USER_AGENTS.contains(&agent));
+        }
+    }
+
+    #[test]
+    fn test_empty_list_selects_nothing() {
+        let counter = AtomicUsize::new(0);
+        assert_eq!(UserAgentPolicy::Rotating(Vec::new()).select(&counter), None);
+        assert_eq!(UserAgentPolicy::Random(Vec::new()).select(&counter), None);
+    }
+}
@@ -3,14 +3,13 @@
 //! These tests ensure the CLI binary works correctly with all flags and options.
 
 use std::process::Command;
-use std::path::Path;
 
 const CLI_BINARY: &str = "websearch";
 
 /// Helper function to run CLI commands and capture output
 fn run_cli_command(args: &[&str]) -> (String, String, bool) {
     let output = Command::new("cargo")
-        .args(&["run", "--bin", CLI_BINARY, "--"])
+        .args(["run", "--bin", CLI_BINARY, "--"])
         .args(args)
         .output()
         .expect("Failed to execute CLI command");
@@ -26,7 +25,7 @@ fn run_cli_command(args: &[&str]) -> (String, String, bool) {
 fn cli_binary_exists() -> bool {
     // Try to build the binary first
     let build_output = Command::new("cargo")
-        .args(&["build", "--bin", CLI_BINARY])
+        .args(["build", "--bin", CLI_BINARY])
         .output()
         .expect("Failed to build CLI binary");
 
@@ -94,6 +93,153 @@ fn test_multi_search_help() {
     assert!(stdout.contains("--providers"));
 }
 
+#[test]
+fn test_suggest_help() {
+    let (stdout, _stderr, success) = run_cli_command(&["suggest", "--help"]);
+
+    assert!(success, "Suggest help should succeed");
+    assert!(stdout.contains("autocomplete suggestions"));
+    assert!(stdout.contains("duckduckgo"));
+    assert!(stdout.contains("brave"));
+}
+
+#[test]
+fn test_trends_help() {
+    let (stdout, _stderr, success) = run_cli_command(&["trends", "--help"]);
+
+    assert!(success, "Trends help should succeed");
+    assert!(stdout.contains("trending searches"));
+    assert!(stdout.contains("--region"));
+}
+
+#[test]
+fn test_products_help() {
+    let (stdout, _stderr, success) = run_cli_command(&["products", "--help"]);
+
+    assert!(success, "Products help should succeed");
+    assert!(stdout.contains("product listings"));
+    assert!(stdout.contains("--country"));
+}
+
+#[test]
+fn test_output_file_flags() {
+    for args in [&["--help"][..], &["multi", "--help"][..]] {
+        let (stdout, _stderr, success) = run_cli_command(args);
+
+        assert!(success, "Help should succeed");
+        assert!(stdout.contains("--output"));
+        assert!(stdout.contains("--append"));
+        assert!(stdout.contains("--quiet"));
+        assert!(stdout.contains("--export"));
+        assert!(stdout.contains("--highlight"));
+        assert!(stdout.contains("--mode"));
+        assert!(stdout.contains("--near"));
+        assert!(stdout.contains("--location"));
+        assert!(stdout.contains("--freshness"));
+    }
+
+    let (stdout, _stderr, _success) = run_cli_command(&["--help"]);
+    assert!(stdout.contains("--require-language"));
+
+    let (_stdout, stderr, success) = run_cli_command(&["test", "--append"]);
+    assert!(!success, "--append without --output should be rejected");
+    assert!(stderr.contains("--output"));
+}
+
+#[test]
+fn test_search_filter_flags() {
+    let (stdout, _stderr, success) = run_cli_command(&["--help"]);
+
+    assert!(success, "Help should succeed");
+    for flag in ["--page", "--timeout-ms", "--site", "--exclude-site", "--after", "--before"] {
+        assert!(stdout.contains(flag), "Help should list {flag}");
+    }
+
+    let (_stdout, stderr, success) = run_cli_command(&["test", "--after", "last week"]);
+    assert!(!success, "Invalid dates should be rejected");
+    assert!(stderr.contains("--after"));
+}
+
+#[test]
+fn test_dry_run_flag() {
+    let (stdout, stderr, success) = run_cli_command(&[
+        "rust",
+        "--provider",
+        "duckduckgo",
+        "--site",
+        "docs.rs",
+        "--dry-run",
+        "--format",
+        "json",
+    ]);
+
+    assert!(success, "Dry run should succeed without network: {stderr}");
+    let requests: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    let search = requests
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|request| request["method"] == "POST")
+        .expect("Should plan the search request");
+    assert!(search["body"].as_str().unwrap().contains("site%3Adocs.rs"));
+}
+
+#[test]
+fn test_multi_limit_flags() {
+    let (stdout, _stderr, success) = run_cli_command(&["multi", "--help"]);
+
+    assert!(success, "Multi help should succeed");
+    for flag in [
+        "--timeout-ms",
+        "--max-concurrent",
+        "--dedup",
+        "--per-provider-results",
+        "--budget",
+        "--deadline",
+        "--tier",
+        "--parallel-tiers",
+    ] {
+        assert!(stdout.contains(flag), "Multi help should list {flag}");
+    }
+}
+
+#[test]
+fn test_cache_subcommand() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("0000000000000001.json"), "{}").unwrap();
+
+    let run = |action: &str| {
+        let output = Command::new("cargo")
+            .args(["run", "--bin", CLI_BINARY, "--", "cache", action])
+            .env("WEBSEARCH_CACHE_DIR", dir.path())
+            .output()
+            .expect("Failed to execute CLI command");
+        assert!(output.status.success(), "cache {action} should succeed");
+        String::from_utf8_lossy(&output.stdout).to_string()
+    };
+
+    assert!(run("stats").contains("Entries: 1 (1 expired)"));
+    assert!(run("clear").contains("Removed 1 cached searches"));
+    assert!(run("stats").contains("Entries: 0"));
+}
+
+#[test]
+fn test_providers_verify_flag() {
+    let (stdout, _stderr, success) = run_cli_command(&["providers", "--help"]);
+
+    assert!(success, "Providers help should succeed");
+    assert!(stdout.contains("--verify"));
+}
+
+#[test]
+fn test_serve_help() {
+    let (stdout, _stderr, success) = run_cli_command(&["serve", "--help"]);
+
+    assert!(success, "Serve help should succeed");
+    assert!(stdout.contains("REST API"));
+    assert!(stdout.contains("--port"));
+}
+
 #[test]
 fn test_arxiv_search_flags() {
     let (stdout, _stderr, success) = run_cli_command(&["--help"]);
@@ -155,7 +301,7 @@ fn test_duckduckgo_search_dry_run() {
     ]);
 
     if success {
-        assert!(stdout.len() > 0, "Should return some results");
+        assert!(!stdout.is_empty(), "Should return some results");
         assert!(stdout.contains("1."), "Should have numbered results");
     } else {
         // If it fails, it should be due to network/parsing, not configuration
@@ -165,7 +311,7 @@ fn test_duckduckgo_search_dry_run() {
 
 #[test]
 fn test_output_formats() {
-    let formats = ["simple", "table", "json"];
+    let formats = ["simple", "table", "json", "rss", "atom", "bibtex"];
 
     for format in &formats {
         // The format should be mentioned in help
@@ -179,7 +325,7 @@ fn test_multi_search_strategies() {
     let strategies = ["aggregate", "failover", "load-balance"];
 
     for strategy in &strategies {
-        let (stdout, _stderr, success) = run_cli_command(&[
+        let (stdout, _stderr, _success) = run_cli_command(&[
             "multi",
             "--help"
         ]);
@@ -251,7 +397,7 @@ fn test_max_results_parameter() {
 #[test]
 fn test_json_output_format() {
     // Test that JSON format is properly structured when it works
-    let (stdout, stderr, success) = run_cli_command(&[
+    let (stdout, _stderr, success) = run_cli_command(&[
         "providers" // This should always work and return structured data
     ]);
 
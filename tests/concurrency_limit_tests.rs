@@ -0,0 +1,50 @@
+//! The process-wide request limit is global state, so it is exercised in its own test
+//! binary rather than alongside tests that expect unlimited concurrency.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+use websearch::{
+    limits, testing::sample_results, types::SearchQuery, web_search_batch, SearchOptions,
+    SearchProvider, SearchResult,
+};
+
+/// Records the highest number of searches it has seen running at once
+#[derive(Debug, Default)]
+struct ConcurrencyProbe {
+    in_flight: AtomicUsize,
+    peak: AtomicUsize,
+}
+
+#[async_trait::async_trait]
+impl SearchProvider for ConcurrencyProbe {
+    fn name(&self) -> &str {
+        "probe"
+    }
+
+    async fn search(&self, _options: &SearchOptions) -> websearch::Result<Vec<SearchResult>> {
+        let now = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+        self.peak.fetch_max(now, Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        self.in_flight.fetch_sub(1, Ordering::SeqCst);
+        Ok(sample_results("probe", 1))
+    }
+}
+
+#[tokio::test]
+async fn test_global_limit_caps_in_flight_requests() {
+    let queries: Vec<SearchQuery> = (0..8)
+        .map(|i| SearchQuery::new(format!("query {i}")))
+        .collect();
+
+    let probe = ConcurrencyProbe::default();
+    limits::set_max_concurrent_requests(Some(2));
+    let results = web_search_batch(&probe, queries.clone(), 8).await;
+    assert!(results.iter().all(|item| item.is_ok()));
+    assert_eq!(probe.peak.load(Ordering::SeqCst), 2);
+
+    let probe = ConcurrencyProbe::default();
+    limits::set_max_concurrent_requests(None);
+    assert_eq!(limits::max_concurrent_requests(), None);
+    web_search_batch(&probe, queries, 8).await;
+    assert_eq!(probe.peak.load(Ordering::SeqCst), 8);
+}
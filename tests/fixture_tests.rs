@@ -0,0 +1,181 @@
+//! Parser regression tests against recorded provider responses
+//!
+//! Each file under `tests/fixtures/<provider>/` is a sanitized real response. The tests
+//! serve it from a mock server, run the provider's search against it and compare the mapped
+//! `SearchResponse` with the `.expected.json` golden file next to it, so changes in a
+//! provider's response schema, or in our mapping of it, show up without live API access.
+//!
+//! After an intentional mapping change, regenerate the golden files and review the diff:
+//!
+//! ```sh
+//! UPDATE_FIXTURES=1 cargo test --test fixture_tests
+//! ```
+
+use std::path::PathBuf;
+use std::time::Duration;
+use websearch::{
+    providers::{
+        arxiv::ArxivProvider,
+        brave::{BraveConfig, BraveProvider},
+        duckduckgo::{DuckDuckGoConfig, DuckDuckGoProvider},
+        exa::ExaProvider,
+        google::{GoogleConfig, GoogleProvider},
+        scholar::GoogleScholarProvider,
+        searxng::SearxNGProvider,
+        serpapi::SerpApiProvider,
+        tavily::TavilyProvider,
+    },
+    SearchOptions, SearchProvider,
+};
+use wiremock::{matchers::any, Mock, MockServer, ResponseTemplate};
+
+fn fixture_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(name)
+}
+
+/// Serve the fixture `name` for every request to a new mock server
+async fn serve_fixture(name: &str, content_type: &str) -> MockServer {
+    let body = std::fs::read(fixture_path(name))
+        .unwrap_or_else(|e| panic!("failed to read fixture {name}: {e}"));
+    let server = MockServer::start().await;
+    Mock::given(any())
+        .respond_with(ResponseTemplate::new(200).set_body_raw(body, content_type))
+        .mount(&server)
+        .await;
+    server
+}
+
+/// Search `provider` for `query` and compare the response with the fixture's golden file
+async fn assert_matches_golden(name: &str, provider: &dyn SearchProvider, query: &str) {
+    let response = provider
+        .search_response(&SearchOptions {
+            query: query.to_string(),
+            ..Default::default()
+        })
+        .await
+        .unwrap_or_else(|e| panic!("{name}: search failed: {e}"));
+    let actual = serde_json::to_string_pretty(&response).unwrap() + "\n";
+
+    let stem = name.rsplit_once('.').map_or(name, |(stem, _)| stem);
+    let golden = fixture_path(&format!("{stem}.expected.json"));
+    if std::env::var_os("UPDATE_FIXTURES").is_some() {
+        std::fs::write(&golden, &actual).unwrap();
+        return;
+    }
+    let expected = std::fs::read_to_string(&golden).unwrap_or_else(|e| {
+        panic!(
+            "failed to read {}: {e} (run with UPDATE_FIXTURES=1 to create it)",
+            golden.display()
+        )
+    });
+    assert_eq!(
+        actual,
+        expected,
+        "{name} no longer maps to {}; if the change is intended, rerun with UPDATE_FIXTURES=1",
+        golden.display()
+    );
+}
+
+#[tokio::test]
+async fn test_google_fixture() {
+    let server = serve_fixture("google/rust.json", "application/json").await;
+    let provider = GoogleProvider::with_config(GoogleConfig {
+        api_key: "key".to_string(),
+        cx: "cx".to_string(),
+        base_url: server.uri(),
+        ..Default::default()
+    })
+    .unwrap();
+    assert_matches_golden("google/rust.json", &provider, "rust").await;
+}
+
+#[tokio::test]
+async fn test_brave_fixture() {
+    let server = serve_fixture("brave/rust.json", "application/json").await;
+    let provider = BraveProvider::with_config(BraveConfig {
+        api_key: "key".to_string(),
+        base_url: server.uri(),
+        ..Default::default()
+    })
+    .unwrap();
+    assert_matches_golden("brave/rust.json", &provider, "rust").await;
+}
+
+#[tokio::test]
+async fn test_exa_fixture() {
+    let server = serve_fixture("exa/rust.json", "application/json").await;
+    let provider = ExaProvider::new("key")
+        .unwrap()
+        .with_base_url(&server.uri());
+    assert_matches_golden("exa/rust.json", &provider, "rust").await;
+}
+
+#[tokio::test]
+async fn test_tavily_fixture() {
+    let server = serve_fixture("tavily/rust.json", "application/json").await;
+    let provider = TavilyProvider::new("tvly-key")
+        .unwrap()
+        .with_base_url(&server.uri());
+    assert_matches_golden("tavily/rust.json", &provider, "rust").await;
+}
+
+#[tokio::test]
+async fn test_serpapi_fixture() {
+    let server = serve_fixture("serpapi/rust.json", "application/json").await;
+    let provider = SerpApiProvider::new("key")
+        .unwrap()
+        .with_base_url(&server.uri());
+    assert_matches_golden("serpapi/rust.json", &provider, "rust").await;
+}
+
+#[tokio::test]
+async fn test_scholar_fixture() {
+    let server = serve_fixture("scholar/attention.json", "application/json").await;
+    let provider = GoogleScholarProvider::new("key")
+        .unwrap()
+        .with_base_url(&server.uri());
+    assert_matches_golden(
+        "scholar/attention.json",
+        &provider,
+        "attention is all you need",
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_duckduckgo_fixture() {
+    let server = serve_fixture("duckduckgo/rust.html", "text/html; charset=utf-8").await;
+    let provider = DuckDuckGoProvider::with_config(DuckDuckGoConfig {
+        base_url: server.uri(),
+        respect_robots_txt: false,
+        ..Default::default()
+    });
+    assert_matches_golden("duckduckgo/rust.html", &provider, "rust").await;
+}
+
+#[tokio::test]
+async fn test_searxng_json_fixture() {
+    let server = serve_fixture("searxng/rust.json", "application/json").await;
+    let provider = SearxNGProvider::new(&server.uri()).unwrap();
+    assert_matches_golden("searxng/rust.json", &provider, "rust").await;
+}
+
+#[tokio::test]
+async fn test_searxng_html_fixture() {
+    let server = serve_fixture("searxng/rust-simple-theme.html", "text/html; charset=utf-8").await;
+    let provider = SearxNGProvider::new(&server.uri())
+        .unwrap()
+        .with_json(false);
+    assert_matches_golden("searxng/rust-simple-theme.html", &provider, "rust").await;
+}
+
+#[tokio::test]
+async fn test_arxiv_fixture() {
+    let server = serve_fixture("arxiv/transformers.xml", "application/atom+xml").await;
+    let provider = ArxivProvider::new()
+        .with_base_url(&server.uri())
+        .with_request_interval(Duration::ZERO);
+    assert_matches_golden("arxiv/transformers.xml", &provider, "transformers").await;
+}
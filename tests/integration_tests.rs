@@ -2,142 +2,19 @@
 //!
 //! These tests cover edge cases, error handling, and integration between components.
 
-use async_trait::async_trait;
-use std::sync::{Arc, Mutex};
 use tokio::time::Duration;
-use websearch::{error::SearchError, multi_provider::*, types::*, web_search};
-
-// Mock provider that can be configured for various test scenarios
-#[derive(Debug, Clone)]
-struct TestProvider {
-    name: String,
-    behavior: TestProviderBehavior,
-    call_count: Arc<Mutex<usize>>,
-}
-
-#[derive(Debug, Clone)]
-enum TestProviderBehavior {
-    Success(Vec<SearchResult>),
-    Error(SearchError),
-    Slow {
-        delay_ms: u64,
-        then: Box<TestProviderBehavior>,
-    },
-    Conditional {
-        calls_before_success: usize,
-        error: SearchError,
-        success: Vec<SearchResult>,
-    },
-}
-
-impl TestProvider {
-    fn new(name: &str, behavior: TestProviderBehavior) -> Self {
-        Self {
-            name: name.to_string(),
-            behavior,
-            call_count: Arc::new(Mutex::new(0)),
-        }
-    }
-
-    fn success(name: &str, results: Vec<SearchResult>) -> Self {
-        Self::new(name, TestProviderBehavior::Success(results))
-    }
-
-    fn error(name: &str, error: SearchError) -> Self {
-        Self::new(name, TestProviderBehavior::Error(error))
-    }
-
-    fn slow(name: &str, delay_ms: u64, then: TestProviderBehavior) -> Self {
-        Self::new(
-            name,
-            TestProviderBehavior::Slow {
-                delay_ms,
-                then: Box::new(then),
-            },
-        )
-    }
-
-    fn conditional(
-        name: &str,
-        calls_before_success: usize,
-        error: SearchError,
-        success: Vec<SearchResult>,
-    ) -> Self {
-        Self::new(
-            name,
-            TestProviderBehavior::Conditional {
-                calls_before_success,
-                error,
-                success,
-            },
-        )
-    }
-
-    fn call_count(&self) -> usize {
-        *self.call_count.lock().unwrap()
-    }
-}
-
-#[async_trait]
-impl SearchProvider for TestProvider {
-    fn name(&self) -> &str {
-        &self.name
-    }
-
-    async fn search(&self, _options: &SearchOptions) -> websearch::Result<Vec<SearchResult>> {
-        let current_count = {
-            let mut count = self.call_count.lock().unwrap();
-            *count += 1;
-            *count
-        };
-
-        match &self.behavior {
-            TestProviderBehavior::Success(results) => Ok(results.clone()),
-            TestProviderBehavior::Error(error) => Err(error.clone()),
-            TestProviderBehavior::Slow { delay_ms, then } => {
-                tokio::time::sleep(Duration::from_millis(*delay_ms)).await;
-                match then.as_ref() {
-                    TestProviderBehavior::Success(results) => Ok(results.clone()),
-                    TestProviderBehavior::Error(error) => Err(error.clone()),
-                    _ => Err(SearchError::Other(
-                        "Nested slow behavior not supported".to_string(),
-                    )),
-                }
-            }
-            TestProviderBehavior::Conditional {
-                calls_before_success,
-                error,
-                success,
-            } => {
-                if current_count <= *calls_before_success {
-                    Err(error.clone())
-                } else {
-                    Ok(success.clone())
-                }
-            }
-        }
-    }
-}
-
-// Helper function to create test search results
-fn create_test_results(provider: &str, count: usize) -> Vec<SearchResult> {
-    (1..=count)
-        .map(|i| SearchResult {
-            title: format!("{provider} Result {i}"),
-            url: format!("https://{provider}.com/result/{i}"),
-            snippet: Some(format!("{provider} content {i}")),
-            domain: None,
-            published_date: None,
-            provider: Some(provider.to_string()),
-            raw: None,
-        })
-        .collect()
-}
+use websearch::{
+    error::SearchError,
+    multi_provider::*,
+    testing::{sample_results, MockProvider},
+    types::*,
+    web_search,
+};
 
 #[tokio::test]
 async fn test_search_with_unicode_query() {
-    let results = create_test_results("unicode", 2);
-    let provider = TestProvider::success("unicode", results);
+    let results = sample_results("unicode", 2);
+    let provider = MockProvider::new("unicode").with_results(results);
 
     let options = SearchOptions {
         query: "🔍 search emoji 中文 العربية русский".to_string(),
@@ -147,14 +24,14 @@ async fn test_search_with_unicode_query() {
 
     let search_results = web_search(options).await.unwrap();
     assert_eq!(search_results.len(), 2);
-    assert_eq!(search_results[0].provider, Some("unicode".to_string()));
+    assert_eq!(search_results[0].provider.as_deref(), Some("unicode"));
 }
 
 #[tokio::test]
 async fn test_search_with_very_long_query() {
     let long_query = "a".repeat(10000); // 10KB query
-    let results = create_test_results("long", 1);
-    let provider = TestProvider::success("long", results);
+    let results = sample_results("long", 1);
+    let provider = MockProvider::new("long").with_results(results);
 
     let options = SearchOptions {
         query: long_query,
@@ -168,8 +45,8 @@ async fn test_search_with_very_long_query() {
 
 #[tokio::test]
 async fn test_search_with_special_characters() {
-    let results = create_test_results("special", 1);
-    let provider = TestProvider::success("special", results);
+    let results = sample_results("special", 1);
+    let provider = MockProvider::new("special").with_results(results);
 
     let options = SearchOptions {
         query: r#"query with "quotes" & <tags> and [brackets] {braces} \backslashes/ & &amp; %20"#
@@ -237,7 +114,7 @@ async fn test_error_types_comprehensive() {
     ];
 
     for (name, error) in error_cases {
-        let provider = TestProvider::error(name, error.clone());
+        let provider = MockProvider::new(name).with_error(error.clone());
         let options = SearchOptions {
             query: "test".to_string(),
             provider: Box::new(provider),
@@ -248,13 +125,18 @@ async fn test_error_types_comprehensive() {
         assert!(result.is_err(), "Expected error for case: {name}");
 
         match result.unwrap_err() {
-            SearchError::ProviderError(msg) => {
+            wrapped @ SearchError::Provider { .. } => {
                 assert!(
-                    msg.contains("failed"),
+                    wrapped.to_string().contains("failed"),
                     "Error message should mention failure for case: {name}"
                 );
+                assert_eq!(
+                    wrapped.root_cause().to_string(),
+                    error.to_string(),
+                    "Provider error should keep the original error for case: {name}"
+                );
             }
-            _ => panic!("Expected ProviderError wrapper for case: {name}"),
+            _ => panic!("Expected Provider wrapper for case: {name}"),
         }
     }
 }
@@ -262,24 +144,25 @@ async fn test_error_types_comprehensive() {
 #[tokio::test]
 async fn test_multi_provider_resilience() {
     // Test scenario: First provider intermittently fails, second is reliable
-    let unreliable_provider = TestProvider::conditional(
-        "unreliable",
-        2, // Fail for first 2 calls
-        SearchError::HttpError {
-            status_code: Some(503),
-            message: "Service Unavailable".to_string(),
-            response_body: None,
-        },
-        create_test_results("unreliable", 1),
-    );
+    let unreliable_provider = MockProvider::new("unreliable")
+        .with_results(sample_results("unreliable", 1))
+        .fail_times(
+            2, // Fail for first 2 calls
+            SearchError::HttpError {
+                status_code: Some(503),
+                message: "Service Unavailable".to_string(),
+                response_body: None,
+            },
+        );
 
-    let reliable_provider = TestProvider::success("reliable", create_test_results("reliable", 2));
+    let reliable_provider =
+        MockProvider::new("reliable").with_results(sample_results("reliable", 2));
 
     let config = MultiProviderConfig::new(MultiProviderStrategy::Failover)
         .add_provider(Box::new(unreliable_provider.clone()))
         .add_provider(Box::new(reliable_provider));
 
-    let mut multi_search = MultiProviderSearch::new(config);
+    let multi_search = MultiProviderSearch::new(config);
     let options = SearchOptionsMulti {
         query: "test".to_string(),
         ..Default::default()
@@ -287,15 +170,15 @@ async fn test_multi_provider_resilience() {
 
     // First call: unreliable fails, reliable succeeds
     let results1 = multi_search.search(&options).await.unwrap();
-    assert_eq!(results1[0].provider, Some("reliable".to_string()));
+    assert_eq!(results1[0].provider.as_deref(), Some("reliable"));
 
     // Second call: unreliable still fails, reliable succeeds
     let results2 = multi_search.search(&options).await.unwrap();
-    assert_eq!(results2[0].provider, Some("reliable".to_string()));
+    assert_eq!(results2[0].provider.as_deref(), Some("reliable"));
 
     // Third call: unreliable now succeeds
     let results3 = multi_search.search(&options).await.unwrap();
-    assert_eq!(results3[0].provider, Some("unreliable".to_string()));
+    assert_eq!(results3[0].provider.as_deref(), Some("unreliable"));
 
     // Verify call counts
     assert_eq!(unreliable_provider.call_count(), 3);
@@ -303,14 +186,14 @@ async fn test_multi_provider_resilience() {
 
 #[tokio::test]
 async fn test_sequential_multi_provider_access() {
-    let provider1 = TestProvider::success("provider1", create_test_results("provider1", 1));
-    let provider2 = TestProvider::success("provider2", create_test_results("provider2", 1));
+    let provider1 = MockProvider::new("provider1").with_results(sample_results("provider1", 1));
+    let provider2 = MockProvider::new("provider2").with_results(sample_results("provider2", 1));
 
     let config = MultiProviderConfig::new(MultiProviderStrategy::LoadBalance)
         .add_provider(Box::new(provider1))
         .add_provider(Box::new(provider2));
 
-    let mut multi_search = MultiProviderSearch::new(config);
+    let multi_search = MultiProviderSearch::new(config);
     let options = SearchOptionsMulti {
         query: "sequential test".to_string(),
         ..Default::default()
@@ -333,7 +216,7 @@ async fn test_sequential_multi_provider_access() {
 
 #[tokio::test]
 async fn test_edge_case_empty_results() {
-    let provider = TestProvider::success("empty", vec![]);
+    let provider = MockProvider::new("empty").with_results(vec![]);
 
     let options = SearchOptions {
         query: "test".to_string(),
@@ -354,8 +237,9 @@ async fn test_edge_case_malformed_urls_in_results() {
             snippet: Some("Valid content".to_string()),
             domain: None,
             published_date: None,
-            provider: Some("test".to_string()),
+            provider: Some("test".into()),
             raw: None,
+            ..Default::default()
         },
         SearchResult {
             title: "Invalid URL Result".to_string(),
@@ -363,8 +247,9 @@ async fn test_edge_case_malformed_urls_in_results() {
             snippet: Some("Invalid URL content".to_string()),
             domain: None,
             published_date: None,
-            provider: Some("test".to_string()),
+            provider: Some("test".into()),
             raw: None,
+            ..Default::default()
         },
         SearchResult {
             title: "Empty URL Result".to_string(),
@@ -372,12 +257,13 @@ async fn test_edge_case_malformed_urls_in_results() {
             snippet: Some("Empty URL content".to_string()),
             domain: None,
             published_date: None,
-            provider: Some("test".to_string()),
+            provider: Some("test".into()),
             raw: None,
+            ..Default::default()
         },
     ];
 
-    let provider = TestProvider::success("malformed", malformed_results);
+    let provider = MockProvider::new("malformed").with_results(malformed_results);
 
     let options = SearchOptions {
         query: "test".to_string(),
@@ -394,8 +280,8 @@ async fn test_edge_case_malformed_urls_in_results() {
 
 #[tokio::test]
 async fn test_large_number_of_results() {
-    let large_results = create_test_results("large", 1000);
-    let provider = TestProvider::success("large", large_results);
+    let large_results = sample_results("large", 1000);
+    let provider = MockProvider::new("large").with_results(large_results);
 
     let options = SearchOptions {
         query: "test".to_string(),
@@ -421,11 +307,12 @@ async fn test_memory_usage_with_large_content() {
         snippet: Some("x".repeat(1_000_000)), // 1MB of content
         domain: None,
         published_date: None,
-        provider: Some("large".to_string()),
+        provider: Some("large".into()),
         raw: None,
+        ..Default::default()
     }];
 
-    let provider = TestProvider::success("large", large_content_results);
+    let provider = MockProvider::new("large").with_results(large_content_results);
 
     let options = SearchOptions {
         query: "test".to_string(),
@@ -438,24 +325,142 @@ async fn test_memory_usage_with_large_content() {
     assert_eq!(results[0].snippet.as_ref().unwrap().len(), 1_000_000);
 }
 
+#[tokio::test]
+async fn test_strict_safe_search_filters_blocked_domains() {
+    let provider = MockProvider::new("mock").with_results(vec![
+        SearchResult {
+            url: "https://www.rust-lang.org/".to_string(),
+            ..Default::default()
+        },
+        SearchResult {
+            url: "https://www.pornhub.com/view".to_string(),
+            ..Default::default()
+        },
+    ]);
+
+    let results = web_search(SearchOptions {
+        query: "test".to_string(),
+        safe_search: Some(SafeSearch::Strict),
+        provider: Box::new(provider),
+        ..Default::default()
+    })
+    .await
+    .unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].url, "https://www.rust-lang.org/");
+}
+
+#[tokio::test]
+async fn test_max_snippet_chars_truncates_large_content() {
+    let provider = MockProvider::new("large").with_results(vec![SearchResult {
+        title: "Large Content Result".to_string(),
+        url: "https://example.com/large".to_string(),
+        snippet: Some("x".repeat(1_000_000)),
+        ..Default::default()
+    }]);
+
+    let results = web_search(SearchOptions {
+        query: "test".to_string(),
+        max_snippet_chars: Some(200),
+        provider: Box::new(provider),
+        ..Default::default()
+    })
+    .await
+    .unwrap();
+    assert_eq!(results[0].snippet.as_ref().unwrap().len(), 200);
+}
+
+#[tokio::test]
+async fn test_without_raw_drops_provider_payloads() {
+    let results = || {
+        vec![SearchResult {
+            title: "Scored".to_string(),
+            url: "https://example.com/scored".to_string(),
+            raw: Some(serde_json::json!({ "score": 0.9 })),
+            ..Default::default()
+        }]
+    };
+
+    let kept = web_search(SearchOptions {
+        query: "test".to_string(),
+        provider: Box::new(MockProvider::new("mock").with_results(results())),
+        ..Default::default()
+    })
+    .await
+    .unwrap();
+    assert!(kept[0].raw.is_some());
+
+    let dropped = web_search(
+        SearchOptions {
+            query: "test".to_string(),
+            provider: Box::new(MockProvider::new("mock").with_results(results())),
+            ..Default::default()
+        }
+        .without_raw(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(dropped[0].raw, None);
+
+    // Aggregated results still carry the provider's score in their sources
+    let config = MultiProviderConfig::new(MultiProviderStrategy::Aggregate)
+        .add_provider(Box::new(MockProvider::new("mock").with_results(results())));
+    let aggregated = MultiProviderSearch::new(config)
+        .search(&SearchOptionsMulti::new("test").without_raw())
+        .await
+        .unwrap();
+    assert_eq!(aggregated[0].raw, None);
+    assert_eq!(aggregated[0].sources[0].score, Some(0.9));
+}
+
+#[tokio::test]
+async fn test_video_mode_needs_a_video_provider() {
+    let video = |provider: MockProvider| SearchOptions {
+        query: "test".to_string(),
+        mode: SearchMode::Video,
+        provider: Box::new(provider),
+        ..Default::default()
+    };
+
+    let error = web_search(video(MockProvider::new("web_only")))
+        .await
+        .unwrap_err();
+    assert!(matches!(error, SearchError::InvalidInput(ref message) if message.contains("video")));
+
+    let provider = MockProvider::new("videos").with_modes([SearchMode::Video]);
+    assert_eq!(web_search(video(provider)).await.unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_out_of_range_location_is_rejected() {
+    let error = web_search(SearchOptions {
+        query: "test".to_string(),
+        location: Some(GeoLocation::Coordinates {
+            latitude: 120.0,
+            longitude: 0.0,
+        }),
+        provider: Box::new(MockProvider::new("test")),
+        ..Default::default()
+    })
+    .await
+    .unwrap_err();
+    assert!(matches!(error, SearchError::InvalidInput(_)));
+}
+
 #[tokio::test]
 async fn test_provider_statistics_accuracy() {
-    let fast_provider = TestProvider::slow(
-        "fast",
-        10, // 10ms delay
-        TestProviderBehavior::Success(create_test_results("fast", 1)),
-    );
-    let slow_provider = TestProvider::slow(
-        "slow",
-        100, // 100ms delay
-        TestProviderBehavior::Success(create_test_results("slow", 1)),
-    );
+    let fast_provider = MockProvider::new("fast")
+        .with_results(sample_results("fast", 1))
+        .with_delay(Duration::from_millis(10));
+    let slow_provider = MockProvider::new("slow")
+        .with_results(sample_results("slow", 1))
+        .with_delay(Duration::from_millis(100));
 
     let config = MultiProviderConfig::new(MultiProviderStrategy::LoadBalance)
         .add_provider(Box::new(fast_provider))
         .add_provider(Box::new(slow_provider));
 
-    let mut multi_search = MultiProviderSearch::new(config);
+    let multi_search = MultiProviderSearch::new(config);
     let options = SearchOptionsMulti {
         query: "test".to_string(),
         ..Default::default()
@@ -482,7 +487,7 @@ async fn test_provider_statistics_accuracy() {
 
 #[tokio::test]
 async fn test_search_options_validation() {
-    let provider = TestProvider::success("test", create_test_results("test", 1));
+    let provider = MockProvider::new("test").with_results(sample_results("test", 1));
 
     // Test with both empty query and no id_list (should fail)
     let invalid_options = SearchOptions {
@@ -515,7 +520,7 @@ async fn test_search_options_validation() {
 
 #[tokio::test]
 async fn test_debug_logging_does_not_crash() {
-    let provider = TestProvider::success("debug", create_test_results("debug", 1));
+    let provider = MockProvider::new("debug").with_results(sample_results("debug", 1));
 
     let options = SearchOptions {
         query: "debug test".to_string(),
@@ -532,3 +537,145 @@ async fn test_debug_logging_does_not_crash() {
     let results = web_search(options).await.unwrap();
     assert_eq!(results.len(), 1);
 }
+
+#[test]
+fn test_search_query_serde_round_trip() {
+    let query: SearchQuery = serde_json::from_str(
+        r#"{
+            "query": "rust async",
+            "max_results": 5,
+            "safe_search": "moderate",
+            "sort_by": "submitted_date",
+            "include_domains": ["docs.rs"],
+            "published_after": "2024-01-01",
+            "request_timeout": "2m 30s",
+            "location": { "latitude": 52.52, "longitude": 13.405 },
+            "debug": { "enabled": true }
+        }"#,
+    )
+    .unwrap();
+
+    assert_eq!(query.query, "rust async");
+    assert_eq!(query.max_results, Some(5));
+    assert_eq!(query.safe_search, Some(SafeSearch::Moderate));
+    assert_eq!(query.sort_by, Some(SortBy::SubmittedDate));
+    assert_eq!(query.page, SearchQuery::default().page);
+    assert_eq!(
+        query.request_timeout,
+        Some(std::time::Duration::from_secs(150))
+    );
+    assert_eq!(
+        query.location,
+        Some(GeoLocation::Coordinates {
+            latitude: 52.52,
+            longitude: 13.405
+        })
+    );
+    assert!(query.debug.as_ref().unwrap().enabled);
+
+    let options = query.with_provider(Box::new(MockProvider::new("test").with_results(Vec::new())));
+    let json = serde_json::to_value(SearchQuery::from(&options)).unwrap();
+    assert_eq!(json["include_domains"][0], "docs.rs");
+    assert_eq!(json["published_after"], "2024-01-01");
+    assert_eq!(json["safe_search"], "moderate");
+    assert_eq!(json["request_timeout"], "2m 30s");
+    assert!(json.get("timeout").is_none());
+    assert_eq!(json["location"]["longitude"], 13.405);
+
+    let named: SearchQuery = serde_json::from_str(r#"{"location": "Austin,Texas,United States"}"#)
+        .unwrap();
+    assert_eq!(
+        named.location,
+        Some(GeoLocation::Name("Austin,Texas,United States".to_string()))
+    );
+}
+
+#[test]
+fn test_option_enums_parse_from_strings() {
+    assert_eq!(
+        "Moderate".parse::<SafeSearch>().unwrap(),
+        SafeSearch::Moderate
+    );
+    assert_eq!(".PDF".parse::<FileType>().unwrap(), FileType::Pdf);
+    assert_eq!("desc".parse::<SortOrder>().unwrap(), SortOrder::Descending);
+    for name in ["last-updated-date", "last_updated_date", "lastUpdatedDate"] {
+        assert_eq!(name.parse::<SortBy>().unwrap(), SortBy::LastUpdatedDate);
+    }
+    assert!(matches!(
+        "race".parse::<MultiProviderStrategy>(),
+        Ok(MultiProviderStrategy::RaceFirst)
+    ));
+
+    // Display output parses back to the same value
+    let strategy: MultiProviderStrategy = MultiProviderStrategy::LoadBalance
+        .to_string()
+        .parse()
+        .unwrap();
+    assert!(matches!(strategy, MultiProviderStrategy::LoadBalance));
+    assert_eq!(
+        SortBy::SubmittedDate.to_string().parse::<SortBy>().unwrap(),
+        SortBy::SubmittedDate
+    );
+
+    assert!(matches!(
+        "sometimes".parse::<SafeSearch>(),
+        Err(SearchError::InvalidInput(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_replay_recorded_provider_traffic() {
+    use websearch::{cassette::Cassette, providers::SerpApiProvider};
+
+    let cassette = Cassette::replay(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/cassettes/serpapi_rust.json"
+    ))
+    .unwrap();
+
+    // The recorded key is redacted, so any key replays the same response
+    let results = cassette
+        .scope(web_search(SearchOptions {
+            query: "rust".to_string(),
+            max_results: Some(2),
+            provider: Box::new(SerpApiProvider::new("any-key").unwrap()),
+            ..Default::default()
+        }))
+        .await
+        .unwrap();
+
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].url, "https://www.rust-lang.org/");
+    assert_eq!(
+        results[1].title,
+        "The Rust Programming Language - The Rust Book"
+    );
+}
+
+#[tokio::test]
+async fn test_max_response_bytes_rejects_large_responses() {
+    use websearch::{cassette::Cassette, providers::SerpApiProvider};
+
+    let cassette = Cassette::replay(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/cassettes/serpapi_rust.json"
+    ))
+    .unwrap();
+
+    let error = cassette
+        .scope(web_search(SearchOptions {
+            query: "rust".to_string(),
+            max_results: Some(2),
+            max_response_bytes: Some(64),
+            provider: Box::new(SerpApiProvider::new("any-key").unwrap()),
+            ..Default::default()
+        }))
+        .await
+        .unwrap_err();
+
+    assert!(
+        error.report().contains("64-byte size limit"),
+        "{}",
+        error.report()
+    );
+}
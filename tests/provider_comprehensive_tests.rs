@@ -16,6 +16,7 @@ use wiremock::{
 };
 
 /// Test configuration for each provider
+#[allow(dead_code)]
 struct ProviderTestConfig {
     name: &'static str,
     requires_api_key: bool,
@@ -202,7 +203,7 @@ async fn test_duckduckgo_real_search() {
             for (i, result) in results.iter().enumerate() {
                 assert!(!result.title.is_empty(), "Result {} should have title", i);
                 assert!(!result.url.is_empty(), "Result {} should have URL", i);
-                assert_eq!(result.provider, Some("duckduckgo".to_string()));
+                assert_eq!(result.provider.as_deref(), Some("duckduckgo"));
                 println!("✅ Result {}: {}", i + 1, result.title);
             }
         }
@@ -241,8 +242,8 @@ async fn test_arxiv_real_search() {
             assert!(!result.title.is_empty(), "ArXiv result should have title");
             assert!(!result.url.is_empty(), "ArXiv result should have URL");
             assert!(result.url.contains("arxiv.org"), "ArXiv URL should contain arxiv.org");
-            assert_eq!(result.provider, Some("arxiv".to_string()));
-            assert_eq!(result.domain, Some("arxiv.org".to_string()));
+            assert_eq!(result.provider.as_deref(), Some("arxiv"));
+            assert_eq!(result.domain.as_deref(), Some("arxiv.org"));
 
             println!("✅ ArXiv paper: {}", result.title);
         }
@@ -272,7 +273,7 @@ async fn test_arxiv_query_search() {
             for result in &results {
                 assert!(!result.title.is_empty());
                 assert!(result.url.contains("arxiv.org"));
-                assert_eq!(result.provider, Some("arxiv".to_string()));
+                assert_eq!(result.provider.as_deref(), Some("arxiv"));
             }
         }
         Err(e) => {
@@ -327,7 +328,7 @@ async fn test_provider_timeout_handling() {
 
     let options = SearchOptions {
         query: "test timeout".to_string(),
-        timeout: Some(1), // Very short timeout (1ms)
+        request_timeout: Some(std::time::Duration::from_millis(1)), // Very short timeout
         max_results: Some(1),
         provider: Box::new(duckduckgo),
         ..Default::default()
@@ -515,7 +516,7 @@ async fn test_real_api_integration() {
         let options = SearchOptions {
             query: "rust programming".to_string(),
             max_results: Some(1),
-            timeout: Some(10000), // 10 second timeout
+            request_timeout: Some(std::time::Duration::from_secs(10)),
             provider,
             ..Default::default()
         };
@@ -8,8 +8,8 @@ use std::time::Duration;
 use websearch::{
     error::SearchError,
     providers::tavily::TavilyProvider,
-    types::{DebugOptions, SearchOptions, SearchProvider},
-    web_search,
+    types::{DebugOptions, Freshness, SearchOptions, SearchProvider},
+    web_search, web_search_response,
 };
 use wiremock::{
     matchers::{body_partial_json, header, method, path},
@@ -93,9 +93,9 @@ async fn test_tavily_successful_search() {
     assert_eq!(results.len(), 3);
     assert_eq!(results[0].title, "The Rust Programming Language");
     assert_eq!(results[0].url, "https://www.rust-lang.org/");
-    assert_eq!(results[0].provider, Some("tavily".to_string()));
+    assert_eq!(results[0].provider.as_deref(), Some("tavily"));
     assert!(results[0].snippet.is_some());
-    assert_eq!(results[0].domain, Some("www.rust-lang.org".to_string()));
+    assert_eq!(results[0].domain.as_deref(), Some("www.rust-lang.org"));
     assert_eq!(results[0].published_date, Some("2024-01-15".to_string()));
 
     // Check that raw data is preserved
@@ -104,6 +104,34 @@ async fn test_tavily_successful_search() {
     assert_eq!(raw["score"], 0.95);
 }
 
+#[tokio::test]
+async fn test_tavily_freshness_is_sent_not_post_filtered() {
+    let mock_server = setup_mock_server().await;
+
+    Mock::given(method("POST"))
+        .and(path("/"))
+        .and(body_partial_json(
+            json!({ "days": 7, "time_range": "week" }),
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_json(create_successful_tavily_response()))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let provider = TavilyProvider::new("tvly-test-valid-api-key-format")
+        .unwrap()
+        .with_base_url(&mock_server.uri());
+
+    let options = SearchOptions {
+        freshness: Some(Freshness::Week),
+        ..create_test_options_with_provider(provider, "rust news")
+    };
+    let results = web_search(options).await.unwrap();
+
+    // Tavily applies the window itself, so its dated results aren't dropped again
+    assert_eq!(results.len(), 3);
+}
+
 #[tokio::test]
 async fn test_tavily_search_with_advanced_depth() {
     let mock_server = setup_mock_server().await;
@@ -185,11 +213,18 @@ async fn test_tavily_api_key_validation_errors() {
 
     assert!(result.is_err());
     match result.unwrap_err() {
-        SearchError::ProviderError(msg) => {
-            assert!(msg.contains("401"));
-            assert!(msg.contains("Unauthorized"));
-        }
-        _ => panic!("Expected ProviderError with 401 status"),
+        SearchError::Provider { source, .. } => match *source {
+            SearchError::HttpError {
+                status_code,
+                message,
+                ..
+            } => {
+                assert_eq!(status_code, Some(401));
+                assert!(message.contains("Unauthorized"));
+            }
+            other => panic!("Expected HttpError, got {other:?}"),
+        },
+        _ => panic!("Expected Provider error with 401 status"),
     }
 }
 
@@ -216,11 +251,18 @@ async fn test_tavily_rate_limit_handling() {
 
     assert!(result.is_err());
     match result.unwrap_err() {
-        SearchError::ProviderError(msg) => {
-            assert!(msg.contains("429"));
-            assert!(msg.contains("Rate limit exceeded"));
-        }
-        _ => panic!("Expected ProviderError with 429 status"),
+        SearchError::Provider { source, .. } => match *source {
+            SearchError::HttpError {
+                status_code,
+                message,
+                ..
+            } => {
+                assert_eq!(status_code, Some(429));
+                assert!(message.contains("Rate limit exceeded"));
+            }
+            other => panic!("Expected HttpError, got {other:?}"),
+        },
+        _ => panic!("Expected Provider error with 429 status"),
     }
 }
 
@@ -247,11 +289,18 @@ async fn test_tavily_payment_required_error() {
 
     assert!(result.is_err());
     match result.unwrap_err() {
-        SearchError::ProviderError(msg) => {
-            assert!(msg.contains("402"));
-            assert!(msg.contains("Payment required"));
-        }
-        _ => panic!("Expected ProviderError with 402 status"),
+        SearchError::Provider { source, .. } => match *source {
+            SearchError::HttpError {
+                status_code,
+                message,
+                ..
+            } => {
+                assert_eq!(status_code, Some(402));
+                assert!(message.contains("Payment required"));
+            }
+            other => panic!("Expected HttpError, got {other:?}"),
+        },
+        _ => panic!("Expected Provider error with 402 status"),
     }
 }
 
@@ -278,11 +327,18 @@ async fn test_tavily_server_error_handling() {
 
     assert!(result.is_err());
     match result.unwrap_err() {
-        SearchError::ProviderError(msg) => {
-            assert!(msg.contains("500"));
-            assert!(msg.contains("server error"));
-        }
-        _ => panic!("Expected ProviderError with 500 status"),
+        SearchError::Provider { source, .. } => match *source {
+            SearchError::HttpError {
+                status_code,
+                message,
+                ..
+            } => {
+                assert_eq!(status_code, Some(500));
+                assert!(message.contains("server error"));
+            }
+            other => panic!("Expected HttpError, got {other:?}"),
+        },
+        _ => panic!("Expected Provider error with 500 status"),
     }
 }
 
@@ -306,13 +362,58 @@ async fn test_tavily_malformed_response() {
 
     assert!(result.is_err());
     match result.unwrap_err() {
-        SearchError::ProviderError(msg) => {
-            assert!(msg.contains("Parsing error"));
+        SearchError::Provider { source, .. } => {
+            assert!(matches!(*source, SearchError::ParseError(_)));
         }
-        _ => panic!("Expected ProviderError wrapping ParseError"),
+        _ => panic!("Expected Provider error wrapping ParseError"),
     }
 }
 
+#[tokio::test]
+async fn test_tavily_partially_malformed_results() {
+    let mock_server = setup_mock_server().await;
+
+    // No `query`, a result without a URL and fields this SDK doesn't know about
+    Mock::given(method("POST"))
+        .and(path("/"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "results": [
+                { "title": "Missing URL", "content": "No link" },
+                {
+                    "title": "The Rust Programming Language",
+                    "url": "https://www.rust-lang.org/",
+                    "content": "A language empowering everyone",
+                    "score": 0.95,
+                    "favicon": "https://www.rust-lang.org/favicon.ico"
+                },
+                { "url": "https://doc.rust-lang.org/book/", "score": 0.8 }
+            ],
+            "images": [{ "url": "https://www.rust-lang.org/logo.png", "description": "Logo" }],
+            "request_id": "123e4567-e89b-12d3-a456-426614174000"
+        })))
+        .mount(&mock_server)
+        .await;
+
+    let provider = TavilyProvider::new("tvly-test-valid-api-key-format")
+        .unwrap()
+        .with_base_url(&mock_server.uri());
+
+    let options = create_test_options_with_provider(provider, "rust");
+    let response = web_search_response(options).await.unwrap();
+
+    assert_eq!(response.results.len(), 2);
+    assert_eq!(
+        response.results[0].raw.as_ref().unwrap()["favicon"],
+        "https://www.rust-lang.org/favicon.ico"
+    );
+    assert_eq!(response.results[1].title, "");
+    assert_eq!(response.results[1].snippet, None);
+
+    assert_eq!(response.warnings.len(), 1);
+    assert_eq!(response.warnings[0].provider, "tavily");
+    assert_eq!(response.warnings[0].index, 0);
+}
+
 #[tokio::test]
 async fn test_tavily_empty_results() {
     let mock_server = setup_mock_server().await;
@@ -423,7 +524,7 @@ async fn test_tavily_request_timeout() {
         .with_base_url(&mock_server.uri());
 
     let mut options = create_test_options_with_provider(provider, "test query");
-    options.timeout = Some(1000); // 1 second timeout
+    options.request_timeout = Some(std::time::Duration::from_secs(1));
 
     let start_time = std::time::Instant::now();
     let result = web_search(options).await;
@@ -446,10 +547,10 @@ async fn test_tavily_network_error() {
 
     assert!(result.is_err());
     match result.unwrap_err() {
-        SearchError::ProviderError(msg) => {
-            assert!(msg.contains("HTTP request failed"));
+        SearchError::Provider { source, .. } => {
+            assert!(matches!(*source, SearchError::HttpError { .. }));
         }
-        _ => panic!("Expected ProviderError wrapping HttpError"),
+        _ => panic!("Expected Provider error wrapping HttpError"),
     }
 }
 